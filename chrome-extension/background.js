@@ -1,13 +1,27 @@
 // WebSocket client for My Launcher Chrome Extension
+
+// ランチャーが「Disconnect」で閉じたときのクローズコード（websocket_server.rs の CLOSE_DISCONNECTED_BY_USER）
+const DISCONNECTED_BY_USER = 4001;
+const USER_DISCONNECT_DELAY = 5 * 60 * 1000;
+
+// WebSocket が止められている環境向けの Native Messaging ホスト（my-launcher register-native-host で登録）
+const NATIVE_HOST_NAME = 'com.my_launcher.tabs';
+// WebSocket に続けてこの回数つながらなければ Native Messaging を試す
+const WS_FAILURES_BEFORE_NATIVE = 3;
 class WebSocketClient {
     constructor() {
         this.ws = null;
         this.reconnectDelay = 1000;
         this.maxReconnectDelay = 30000;
         this.keepAliveInterval = null;
+        this.fullSyncInterval = null;
         this.requestId = 0;
         this.pendingRequests = new Map();
         this.isConnected = false;
+        this.native = null;
+        this.transport = null;
+        this.wsFailures = 0;
+        this.disconnectedByUser = false;
     }
     
     connect() {
@@ -15,16 +29,14 @@ class WebSocketClient {
         
         try {
             this.ws = new WebSocket('ws://localhost:9999');
+            let opened = false;
             
             this.ws.onopen = () => {
                 console.log('=== WEBSOCKET CONNECTED ===');
-                this.isConnected = true;
+                opened = true;
+                this.wsFailures = 0;
                 this.reconnectDelay = 1000; // Reset reconnect delay
-                this.startKeepAlive();
-                this.sendTabUpdate();
-                
-                // Store connection state
-                chrome.storage.local.set({ wsConnected: true });
+                this.onConnected('websocket');
             };
             
             this.ws.onmessage = (event) => {
@@ -39,10 +51,14 @@ class WebSocketClient {
             
             this.ws.onclose = (event) => {
                 console.log('=== WEBSOCKET DISCONNECTED ===', event.code, event.reason);
-                this.isConnected = false;
-                this.stopKeepAlive();
-                this.clearPendingRequests();
-                chrome.storage.local.set({ wsConnected: false });
+                this.onDisconnected();
+                if (event.code === DISCONNECTED_BY_USER) {
+                    // ランチャーの接続一覧から切断されたら、しばらく接続し直さない
+                    this.reconnectDelay = USER_DISCONNECT_DELAY;
+                } else if (!opened && ++this.wsFailures >= WS_FAILURES_BEFORE_NATIVE) {
+                    this.connectNative();
+                    return;
+                }
                 this.scheduleReconnect();
             };
             
@@ -55,12 +71,91 @@ class WebSocketClient {
         }
     }
     
+    // WebSocket の代わりに Native Messaging ホストを起動してつなぐ（切れたらまた WebSocket から試す）
+    connectNative() {
+        console.log(`WebSocket failed ${this.wsFailures} times, trying native messaging host ${NATIVE_HOST_NAME}`);
+        this.wsFailures = 0;
+        try {
+            this.native = chrome.runtime.connectNative(NATIVE_HOST_NAME);
+        } catch (e) {
+            console.error('Failed to start native messaging host:', e);
+            this.native = null;
+            this.scheduleReconnect();
+            return;
+        }
+        
+        this.native.onMessage.addListener((message) => {
+            console.log('Native message received:', message);
+            // ホストが動いてランチャーにつながっている
+            this.reconnectDelay = 1000;
+            this.handleMessage(message);
+        });
+        this.native.onDisconnect.addListener(() => {
+            const error = chrome.runtime.lastError;
+            console.log('=== NATIVE HOST DISCONNECTED ===', error ? error.message : '');
+            this.native = null;
+            this.onDisconnected();
+            if (this.disconnectedByUser) {
+                this.disconnectedByUser = false;
+                this.reconnectDelay = USER_DISCONNECT_DELAY;
+            }
+            this.scheduleReconnect();
+        });
+        console.log('=== NATIVE HOST CONNECTED ===');
+        this.onConnected('nativeMessaging');
+    }
+    
+    onConnected(transport) {
+        this.isConnected = true;
+        this.transport = transport;
+        this.startKeepAlive();
+        this.startFullSync();
+        this.sendHello();
+        this.sendTabUpdate();
+        this.sendRecentlyClosed();
+        this.sendReadingList();
+        
+        // Store connection state
+        chrome.storage.local.set({ wsConnected: true, transport });
+    }
+    
+    onDisconnected() {
+        this.isConnected = false;
+        this.transport = null;
+        this.stopKeepAlive();
+        this.stopFullSync();
+        this.clearPendingRequests();
+        chrome.storage.local.set({ wsConnected: false, transport: null });
+    }
+    
+    // 接続中の経路で送る
+    transmit(message) {
+        if (this.native) {
+            this.native.postMessage(message);
+        } else {
+            this.ws.send(JSON.stringify(message));
+        }
+    }
+    
     scheduleReconnect() {
         console.log(`Scheduling reconnect in ${this.reconnectDelay}ms`);
         setTimeout(() => this.connect(), this.reconnectDelay);
         this.reconnectDelay = Math.min(this.reconnectDelay * 2, this.maxReconnectDelay);
     }
     
+    // ランチャーの接続一覧に表示するブラウザとプロファイルを名乗る
+    async sendHello() {
+        const brands = (navigator.userAgentData && navigator.userAgentData.brands) || [];
+        const brand = brands.map(b => b.brand).find(name => !/Not.A.Brand|Chromium/i.test(name))
+            || (brands.length > 0 ? brands[brands.length - 1].brand : null);
+        const { profileName } = await chrome.storage.local.get('profileName');
+        try {
+            await this.sendRequest('hello', { browser: brand, profile: profileName || null });
+        } catch (e) {
+            console.error('Failed to send hello:', e);
+        }
+    }
+    
     startKeepAlive() {
         this.stopKeepAlive();
         this.keepAliveInterval = setInterval(() => {
@@ -77,6 +172,23 @@ class WebSocketClient {
         }
     }
     
+    // 差分の取りこぼしを直すため、定期的に全タブを送り直す
+    startFullSync() {
+        this.stopFullSync();
+        this.fullSyncInterval = setInterval(() => {
+            if (this.isConnected) {
+                this.sendTabUpdate();
+            }
+        }, 300000); // Full sync every 5 minutes
+    }
+    
+    stopFullSync() {
+        if (this.fullSyncInterval) {
+            clearInterval(this.fullSyncInterval);
+            this.fullSyncInterval = null;
+        }
+    }
+    
     clearPendingRequests() {
         for (const [id, request] of this.pendingRequests) {
             if (request.reject) {
@@ -108,7 +220,7 @@ class WebSocketClient {
             this.pendingRequests.set(id, { resolve, reject });
             
             try {
-                this.ws.send(JSON.stringify(message));
+                this.transmit(message);
                 console.log('Sent request:', message);
             } catch (e) {
                 this.pendingRequests.delete(id);
@@ -124,7 +236,7 @@ class WebSocketClient {
         }
         
         try {
-            this.ws.send(JSON.stringify(message));
+            this.transmit(message);
         } catch (e) {
             console.error('Failed to send message:', e);
         }
@@ -155,6 +267,57 @@ class WebSocketClient {
                     this.executeSwitchToTab(message.data.tab_id, message.data.window_id);
                     break;
                     
+                case 'tabCloseRequested':
+                    console.log('Tab close requested:', message.data.tab_id);
+                    this.executeCloseTab(message.data.tab_id);
+                    break;
+                    
+                case 'bookmarkCreateRequested':
+                    console.log('Bookmark create requested:', message.data.url);
+                    this.executeCreateBookmark(message.data.url, message.data.title, message.data.folder);
+                    break;
+                    
+                case 'bookmarkDeleteRequested':
+                    console.log('Bookmark delete requested:', message.data.url);
+                    this.executeDeleteBookmark(message.data.id);
+                    break;
+                    
+                case 'bookmarkRenameRequested':
+                    console.log('Bookmark rename requested:', message.data.url);
+                    this.executeRenameBookmark(message.data.url, message.data.title);
+                    break;
+                    
+                case 'tabsCloseRequested':
+                    console.log('Tabs close requested:', message.data.tab_ids);
+                    this.executeCloseTabs(message.data.tab_ids);
+                    break;
+                    
+                case 'tabsMoveRequested':
+                    console.log('Tabs move requested:', message.data.tab_ids);
+                    this.executeMoveTabsToNewWindow(message.data.tab_ids);
+                    break;
+                    
+                case 'recentlyClosedRequested':
+                    console.log('Recently closed tabs requested');
+                    this.sendRecentlyClosed();
+                    break;
+                    
+                case 'closedTabRestoreRequested':
+                    console.log('Closed tab restore requested:', message.data.session_id);
+                    this.executeRestoreClosedTab(message.data.session_id);
+                    break;
+                    
+                case 'tabsRefreshRequested':
+                    console.log('Tabs refresh requested');
+                    this.sendTabUpdate();
+                    break;
+                    
+                case 'disconnectRequested':
+                    // Native Messaging では Close のコードを送れないので、イベントで知らせてから切られる
+                    console.log('Disconnect requested by the launcher');
+                    this.disconnectedByUser = true;
+                    break;
+                    
                 case 'tabsUpdated':
                     console.log('Tabs updated event received');
                     // Optionally handle tabs update from server
@@ -171,15 +334,7 @@ class WebSocketClient {
         
         try {
             const tabs = await chrome.tabs.query({});
-            const tabData = tabs.map(tab => ({
-                id: tab.id,
-                window_id: tab.windowId,
-                title: tab.title || tab.url || 'Untitled',
-                url: tab.url || '',
-                fav_icon_url: tab.favIconUrl || '',
-                active: tab.active,
-                index: tab.index
-            }));
+            const tabData = tabs.map(toTabData);
             
             console.log(`Sending ${tabData.length} tabs to server`);
             await this.sendRequest('updateTabs', { tabs: tabData });
@@ -188,6 +343,177 @@ class WebSocketClient {
         }
     }
     
+    // 最近閉じたタブ（閉じたウィンドウは含めない）を送る
+    async sendRecentlyClosed() {
+        if (!this.isConnected) {
+            return;
+        }
+        try {
+            const sessions = await chrome.sessions.getRecentlyClosed({ maxResults: chrome.sessions.MAX_SESSION_RESULTS });
+            const tabs = sessions
+                .filter(session => session.tab && session.tab.sessionId && session.tab.url)
+                .map(session => ({
+                    session_id: session.tab.sessionId,
+                    title: session.tab.title || session.tab.url,
+                    url: session.tab.url,
+                    closed_at: session.lastModified
+                }));
+            await this.sendRequest('updateRecentlyClosed', { tabs });
+        } catch (e) {
+            console.error('Failed to send recently closed tabs:', e);
+        }
+    }
+    
+    // リーディングリストを送る（chrome.readingList は Chrome 120 以降）
+    async sendReadingList() {
+        if (!this.isConnected || !chrome.readingList) {
+            return;
+        }
+        try {
+            const items = await chrome.readingList.query({});
+            const entries = items.map(item => ({
+                title: item.title || item.url,
+                url: item.url,
+                has_been_read: item.hasBeenRead,
+                created_at: Math.floor(item.creationTime / 1000)
+            }));
+            await this.sendRequest('updateReadingList', { entries });
+        } catch (e) {
+            console.error('Failed to send reading list:', e);
+        }
+    }
+    
+    async executeRestoreClosedTab(sessionId) {
+        if (!sessionId) {
+            console.error('Invalid session ID:', sessionId);
+            return;
+        }
+        
+        try {
+            const restored = await chrome.sessions.restore(sessionId);
+            if (restored.tab) {
+                await chrome.windows.update(restored.tab.windowId, { focused: true });
+            }
+            console.log('Restored closed tab', sessionId);
+        } catch (e) {
+            console.error('Failed to restore closed tab:', e);
+        }
+    }
+    
+    // タブ一覧の差分を送る（未接続なら次の全体の同期で反映される）
+    sendTabDelta(method, params) {
+        if (!this.isConnected) {
+            return;
+        }
+        this.sendRequest(method, params).catch(e => {
+            console.error(`Failed to send ${method}:`, e);
+        });
+    }
+    
+    // ブックマークバーからのパス（例: "Work/Reading"）のフォルダを探し、なければ作成する
+    async resolveBookmarkFolder(folderPath) {
+        const BOOKMARKS_BAR_ID = '1';
+        let parentId = BOOKMARKS_BAR_ID;
+        
+        for (const name of folderPath.split('/').filter(part => part.length > 0)) {
+            const children = await chrome.bookmarks.getChildren(parentId);
+            const existing = children.find(child => !child.url && child.title === name);
+            if (existing) {
+                parentId = existing.id;
+            } else {
+                const created = await chrome.bookmarks.create({ parentId, title: name });
+                parentId = created.id;
+            }
+        }
+        
+        return parentId;
+    }
+    
+    async executeCreateBookmark(url, title, folder) {
+        try {
+            const bookmark = { url, title };
+            if (folder) {
+                bookmark.parentId = await this.resolveBookmarkFolder(folder);
+            }
+            
+            const created = await chrome.bookmarks.create(bookmark);
+            console.log('Bookmark created:', created.id, created.title);
+        } catch (e) {
+            console.error('Failed to create bookmark:', e);
+        }
+    }
+    
+    // 同じ URL の別のブックマークは残すため、ランチャーが選んだ 1 件だけを ID で削除する
+    async executeDeleteBookmark(id) {
+        try {
+            await chrome.bookmarks.remove(id);
+            console.log('Deleted bookmark', id);
+        } catch (e) {
+            console.error('Failed to delete bookmark:', e);
+        }
+    }
+    
+    async executeRenameBookmark(url, title) {
+        try {
+            const bookmarks = await chrome.bookmarks.search({ url });
+            for (const bookmark of bookmarks) {
+                await chrome.bookmarks.update(bookmark.id, { title });
+            }
+            console.log(`Renamed ${bookmarks.length} bookmark(s) for`, url);
+        } catch (e) {
+            console.error('Failed to rename bookmark:', e);
+        }
+    }
+    
+    executeCloseTab(tabId) {
+        if (!tabId) {
+            console.error('Invalid tab ID:', tabId);
+            return;
+        }
+        
+        // タブ一覧の更新は onRemoved リスナーが送信する
+        chrome.tabs.remove(tabId, () => {
+            if (chrome.runtime.lastError) {
+                console.error('Failed to close tab:', chrome.runtime.lastError);
+            } else {
+                console.log('Closed tab:', tabId);
+            }
+        });
+    }
+    
+    async executeCloseTabs(tabIds) {
+        if (!Array.isArray(tabIds) || tabIds.length === 0) {
+            console.error('Invalid tab IDs:', tabIds);
+            return;
+        }
+        
+        try {
+            await chrome.tabs.remove(tabIds);
+            console.log(`Closed ${tabIds.length} tabs`);
+        } catch (e) {
+            console.error('Failed to close tabs:', e);
+        }
+    }
+    
+    // 最初のタブで新しいウィンドウを作り、残りのタブをその後ろに移す
+    async executeMoveTabsToNewWindow(tabIds) {
+        if (!Array.isArray(tabIds) || tabIds.length === 0) {
+            console.error('Invalid tab IDs:', tabIds);
+            return;
+        }
+        
+        try {
+            const [first, ...rest] = tabIds;
+            const window = await chrome.windows.create({ tabId: first, focused: true });
+            if (rest.length > 0) {
+                await chrome.tabs.move(rest, { windowId: window.id, index: -1 });
+            }
+            console.log(`Moved ${tabIds.length} tabs to window`, window.id);
+        } catch (e) {
+            console.error('Failed to move tabs:', e);
+        }
+    }
+    
     executeSwitchToTab(tabId, windowId) {
         console.log('=== EXECUTING TAB SWITCH ===');
         console.log(`Tab ID: ${tabId}, Window ID: ${windowId}`);
@@ -237,39 +563,73 @@ class WebSocketClient {
     }
 }
 
+// サーバーへ送るタブの形式
+function toTabData(tab) {
+    return {
+        id: tab.id,
+        window_id: tab.windowId,
+        title: tab.title || tab.url || 'Untitled',
+        url: tab.url || '',
+        fav_icon_url: tab.favIconUrl || '',
+        active: tab.active,
+        index: tab.index,
+        pinned: tab.pinned
+    };
+}
+
 // Create global WebSocket client instance
 let wsClient = new WebSocketClient();
 
-// Native Messaging support has been removed in favor of WebSocket
-
-// Tab change listeners
+// Tab change listeners (send deltas; a full list is sent on connect and every few minutes)
 chrome.tabs.onCreated.addListener((tab) => {
     console.log('Tab created:', tab.id);
-    wsClient.sendTabUpdate();
+    wsClient.sendTabDelta('tabCreated', { tab: toTabData(tab) });
 });
 
 chrome.tabs.onRemoved.addListener((tabId) => {
     console.log('Tab removed:', tabId);
-    wsClient.sendTabUpdate();
+    wsClient.sendTabDelta('tabRemoved', { tab_id: tabId });
 });
 
 chrome.tabs.onUpdated.addListener((tabId, changeInfo, tab) => {
-    if (changeInfo.status === 'complete' || changeInfo.title || changeInfo.url) {
+    if (changeInfo.status === 'complete' || changeInfo.title || changeInfo.url || changeInfo.favIconUrl || changeInfo.pinned !== undefined) {
         console.log('Tab updated:', tabId);
-        wsClient.sendTabUpdate();
+        wsClient.sendTabDelta('tabUpdated', { tab: toTabData(tab) });
     }
 });
 
 chrome.tabs.onActivated.addListener((activeInfo) => {
     console.log('Tab activated:', activeInfo.tabId);
-    wsClient.sendTabUpdate();
+    // サーバー側で同じウィンドウの他のタブは非アクティブになる
+    chrome.tabs.get(activeInfo.tabId, (tab) => {
+        if (!chrome.runtime.lastError) {
+            wsClient.sendTabDelta('tabUpdated', { tab: toTabData(tab) });
+        }
+    });
 });
 
 chrome.tabs.onMoved.addListener((tabId, moveInfo) => {
     console.log('Tab moved:', tabId);
-    wsClient.sendTabUpdate();
+    wsClient.sendTabDelta('tabMoved', { tab_id: tabId, window_id: moveInfo.windowId, index: moveInfo.toIndex });
+});
+
+chrome.tabs.onAttached.addListener((tabId, attachInfo) => {
+    console.log('Tab attached:', tabId);
+    wsClient.sendTabDelta('tabMoved', { tab_id: tabId, window_id: attachInfo.newWindowId, index: attachInfo.newPosition });
 });
 
+// 閉じたタブ・復元したタブが変わったら最近閉じたタブの一覧を送り直す
+chrome.sessions.onChanged.addListener(() => {
+    wsClient.sendRecentlyClosed();
+});
+
+// リーディングリストが変わったら送り直す
+if (chrome.readingList) {
+    chrome.readingList.onEntryAdded.addListener(() => wsClient.sendReadingList());
+    chrome.readingList.onEntryRemoved.addListener(() => wsClient.sendReadingList());
+    chrome.readingList.onEntryUpdated.addListener(() => wsClient.sendReadingList());
+}
+
 // Initialize on extension load
 chrome.runtime.onInstalled.addListener(() => {
     console.log('Extension installed/updated');
@@ -0,0 +1,215 @@
+//! 色の変換（`#ff8800` / `rgb(10, 20, 30)` / `hsl(30, 100%, 50%)`）
+//!
+//! クエリ全体が色の表記なら、見本付きで各形式に変換した結果を表示する。Enter でその表記をコピーする。
+
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use std::error::Error;
+
+/// 色の表記を解析して RGB を返す
+pub fn parse_color(text: &str) -> Option<[u8; 3]> {
+    let text = text.trim().to_lowercase();
+
+    if let Some(hex) = text.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+    if let Some(args) = function_args(&text, "rgb") {
+        let values = parse_components(&args)?;
+        let channel = |v: f64| (0.0..=255.0).contains(&v).then_some(v.round() as u8);
+        return Some([channel(values[0])?, channel(values[1])?, channel(values[2])?]);
+    }
+    if let Some(args) = function_args(&text, "hsl") {
+        let values = parse_components(&args)?;
+        if !(0.0..=100.0).contains(&values[1]) || !(0.0..=100.0).contains(&values[2]) {
+            return None;
+        }
+        return Some(hsl_to_rgb(values[0], values[1], values[2]));
+    }
+    None
+}
+
+fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let expanded: String = match hex.len() {
+        // #f80 → #ff8800
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// `rgb(…)` の括弧の中身
+fn function_args(text: &str, name: &str) -> Option<String> {
+    let inner = text.strip_prefix(name)?.trim_start().strip_prefix('(')?.strip_suffix(')')?;
+    Some(inner.to_string())
+}
+
+/// カンマまたは空白区切りの3つの数値（`%` と `deg` は無視）
+fn parse_components(args: &str) -> Option<[f64; 3]> {
+    let values: Vec<f64> = args
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.trim_end_matches('%').trim_end_matches("deg").parse::<f64>())
+        .collect::<Result<_, _>>()
+        .ok()?;
+    match values.as_slice() {
+        [a, b, c] => Some([*a, *b, *c]),
+        _ => None,
+    }
+}
+
+/// HSL（色相は度、彩度・明度は%）を RGB に変換
+pub fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> [u8; 3] {
+    let h = hue.rem_euclid(360.0) / 360.0;
+    let s = saturation / 100.0;
+    let l = lightness / 100.0;
+
+    if s == 0.0 {
+        let v = (l * 255.0).round() as u8;
+        return [v, v, v];
+    }
+
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let channel = |t: f64| {
+        let t = t.rem_euclid(1.0);
+        let v = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        (v * 255.0).round() as u8
+    };
+    [channel(h + 1.0 / 3.0), channel(h), channel(h - 1.0 / 3.0)]
+}
+
+/// RGB を HSL（色相は度、彩度・明度は%、いずれも整数に丸める）に変換
+pub fn rgb_to_hsl(rgb: [u8; 3]) -> (u32, u32, u32) {
+    let [r, g, b] = rgb.map(|c| c as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+
+    if max == min {
+        return (0, 0, (l * 100.0).round() as u32);
+    }
+
+    let d = max - min;
+    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+    let h = if max == r {
+        (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+
+    ((h * 60.0).round() as u32 % 360, (s * 100.0).round() as u32, (l * 100.0).round() as u32)
+}
+
+/// 各形式の表記
+pub fn color_formats(rgb: [u8; 3]) -> Vec<(String, &'static str)> {
+    let [r, g, b] = rgb;
+    let (h, s, l) = rgb_to_hsl(rgb);
+    vec![
+        (format!("#{:02x}{:02x}{:02x}", r, g, b), "Hex"),
+        (format!("rgb({}, {}, {})", r, g, b), "RGB"),
+        (format!("hsl({}, {}%, {}%)", h, s, l), "HSL"),
+    ]
+}
+
+/// 色の表記を見本付きの結果にするプロバイダー
+pub struct ColorProvider;
+
+impl KeywordProvider for ColorProvider {
+    fn name(&self) -> &'static str {
+        "color"
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &[]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("#ff8800 / rgb(255, 136, 0) / hsl(32, 100%, 50%)", "Convert a color to other notations"),
+        ];
+        HELP
+    }
+
+    fn search(&self, _keyword: &str, _args: &str) -> Vec<SearchResult> {
+        Vec::new()
+    }
+
+    fn search_query(&self, query: &str) -> Option<Vec<SearchResult>> {
+        let rgb = parse_color(query)?;
+        Some(
+            color_formats(rgb)
+                .into_iter()
+                .map(|(text, format)| SearchResult {
+                    title: text.clone(),
+                    description: format!("{} · Enter to copy", format),
+                    action: Action::CopyToClipboard(text),
+                    window_info: None,
+                    result_type: ResultType::Color { rgb },
+                    group: None,
+                    metadata: None,
+                })
+                .collect(),
+        )
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        Err(format!("unknown color command: {}", command).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color_formats() {
+        assert_eq!(parse_color("#ff8800"), Some([255, 136, 0]));
+        assert_eq!(parse_color("#F80"), Some([255, 136, 0]));
+        assert_eq!(parse_color("rgb(10,20,30)"), Some([10, 20, 30]));
+        assert_eq!(parse_color("rgb(10 20 30)"), Some([10, 20, 30]));
+        assert_eq!(parse_color("hsl(32, 100%, 50%)"), Some([255, 136, 0]));
+        assert_eq!(parse_color("hsl(0deg 0% 100%)"), Some([255, 255, 255]));
+    }
+
+    #[test]
+    fn test_parse_color_rejects_invalid() {
+        assert_eq!(parse_color("#ff88"), None);
+        assert_eq!(parse_color("#gg0000"), None);
+        assert_eq!(parse_color("rgb(300, 0, 0)"), None);
+        assert_eq!(parse_color("rgb(1, 2)"), None);
+        assert_eq!(parse_color("hsl(0, 120%, 50%)"), None);
+        assert_eq!(parse_color("github"), None);
+    }
+
+    #[test]
+    fn test_rgb_to_hsl() {
+        assert_eq!(rgb_to_hsl([255, 0, 0]), (0, 100, 50));
+        assert_eq!(rgb_to_hsl([0, 0, 255]), (240, 100, 50));
+        assert_eq!(rgb_to_hsl([128, 128, 128]), (0, 0, 50));
+    }
+
+    #[test]
+    fn test_search_query_lists_conversions() {
+        let results = ColorProvider.search_query("#ff8800").unwrap();
+        let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["#ff8800", "rgb(255, 136, 0)", "hsl(32, 100%, 50%)"]);
+        assert_eq!(results[0].result_type, ResultType::Color { rgb: [255, 136, 0] });
+        assert_eq!(results[1].action, Action::CopyToClipboard("rgb(255, 136, 0)".to_string()));
+        assert!(ColorProvider.search_query("hello").is_none());
+    }
+}
@@ -0,0 +1,917 @@
+use crate::core::empty_state::EmptySection;
+use crate::core::result_composer::{GoogleRowPlacement, ResultCategory};
+use crate::core::SearchMode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// ランチャー全体の設定（config.json）
+///
+/// 項目が欠けていても既定値で補われるため、必要な項目だけを書けばよい。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LauncherConfig {
+    pub update: UpdateConfig,
+    pub alt_tab: AltTabConfig,
+    pub notes: NotesConfig,
+    pub layouts: LayoutsConfig,
+    pub new_tab: NewTabConfig,
+    pub lookup: LookupConfig,
+    pub ranking: RankingConfig,
+    pub sources: SourcesConfig,
+    pub providers: ProvidersConfig,
+    pub debug: DebugConfig,
+    pub logging: LoggingConfig,
+    pub motion: MotionConfig,
+    pub appearance: AppearanceConfig,
+    pub auto_hide: AutoHideConfig,
+    pub tabs: TabsConfig,
+    pub grid: GridConfig,
+    /// ランチャーを開かずに操作を実行するホットキー（デーモンが登録する）
+    pub hotkeys: Vec<HotkeyBinding>,
+    /// 一連の操作をまとめて実行するマクロ（`macro` キーワード）
+    pub macros: Vec<MacroConfig>,
+    pub api: ApiConfig,
+    pub scripts: ScriptsConfig,
+    pub empty_state: EmptyStateConfig,
+    pub previews: PreviewsConfig,
+    pub privacy: PrivacyConfig,
+    pub docker: DockerConfig,
+    /// ブラウザではなくアプリで開く URL（`core/action_rewriter.rs`）
+    pub open_in_app: Vec<AppHandlerConfig>,
+}
+
+/// 自動更新の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// 起動時に新しいリリースを確認するか（既定では無効）
+    pub check_on_startup: bool,
+    /// GitHub Releases API の最新リリースURL
+    pub releases_url: String,
+    /// ダウンロードするアセット名
+    pub asset_name: String,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            check_on_startup: false,
+            releases_url: "https://api.github.com/repos/takeru911/my-launcher/releases/latest".to_string(),
+            asset_name: "my-launcher.exe".to_string(),
+        }
+    }
+}
+
+/// Alt+Tabオーバーレイ（my-launcher-alt-tab）の設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AltTabConfig {
+    pub placement: OverlayPlacement,
+}
+
+/// オーバーレイを表示するモニター
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum OverlayPlacement {
+    /// プライマリモニターの中央
+    #[default]
+    Primary,
+    /// マウスカーソルがあるモニター
+    CursorMonitor,
+    /// フォーカス中のウィンドウがあるモニター
+    FocusedWindowMonitor,
+    /// 列挙順で指定したモニター
+    MonitorIndex { index: usize },
+}
+
+/// クイックメモ（`note` キーワード）の設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotesConfig {
+    /// 追記先の Markdown ファイル（省略時はデータディレクトリの notes.md）
+    pub path: Option<PathBuf>,
+}
+
+impl NotesConfig {
+    pub fn path(&self) -> PathBuf {
+        self.path.clone().unwrap_or_else(crate::paths::notes_path)
+    }
+}
+
+/// ウィンドウ配置（`layout` キーワード）の設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutsConfig {
+    /// 復元時に見つからないアプリを起動し直すか
+    pub relaunch_missing: bool,
+}
+
+/// プロファイルを指定して新しいタブで検索する（`nt` キーワード）の設定
+///
+/// プロファイルは `Chrome - Profile 1` の形式（ブックマークの `[...]` と同じ表記）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewTabConfig {
+    /// サブキーワードがないときに開くプロファイル（省略時は検出したプロファイルを並べて選ばせる）
+    pub default_profile: Option<String>,
+    /// サブキーワードとプロファイル（例: `"work": "Chrome - Profile 1"` なら `nt work <query>`）
+    pub profiles: HashMap<String, String>,
+    /// 検索の URL（`{query}` は検索語）
+    pub search_url: String,
+}
+
+impl Default for NewTabConfig {
+    fn default() -> Self {
+        Self {
+            default_profile: None,
+            profiles: HashMap::new(),
+            search_url: "https://www.google.com/search?q={query}".to_string(),
+        }
+    }
+}
+
+/// 辞書・翻訳（`def` / `tr` キーワード）の設定
+///
+/// URL の `{query}` は検索語、`{source}` と `{target}` は翻訳元・翻訳先の言語に置き換えられる。
+/// 応答の JSON から表示する文字列の位置は JSON Pointer で指定する。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LookupConfig {
+    pub dictionary_url: String,
+    pub dictionary_pointer: String,
+    pub translate_url: String,
+    pub translate_pointer: String,
+    pub source_lang: String,
+    pub target_lang: String,
+}
+
+impl Default for LookupConfig {
+    fn default() -> Self {
+        Self {
+            dictionary_url: "https://api.dictionaryapi.dev/api/v2/entries/en/{query}".to_string(),
+            dictionary_pointer: "/0/meanings/0/definitions/0/definition".to_string(),
+            translate_url: "https://api.mymemory.translated.net/get?q={query}&langpair={source}|{target}".to_string(),
+            translate_pointer: "/responseData/translatedText".to_string(),
+            source_lang: "en".to_string(),
+            target_lang: "ja".to_string(),
+        }
+    }
+}
+
+/// Browserモードの検索結果の並び順と重み
+///
+/// `order` は種類の並び順（書かなかった種類は後ろに並ぶ）。
+/// `weights` を設定すると、種類ごとにまとめず重みと順位から算出したスコアの順に混ぜて並べる。
+/// `google_row` で「Google: ...」の行だけを最後に回したり、ローカルの結果がないときだけ表示したりできる。
+/// `group_by_profile` を有効にすると、ブックマークと履歴をプロファイルごとの見出しの下にまとめる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    pub order: Vec<ResultCategory>,
+    pub weights: HashMap<ResultCategory, f32>,
+    pub google_row: GoogleRowPlacement,
+    pub group_by_profile: bool,
+    /// 空のクエリで、今の時間帯によく開くウィンドウやページを提案する（usage.json の記録を使う）
+    pub smart_suggestions: bool,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            order: ResultCategory::DEFAULT_ORDER.to_vec(),
+            weights: HashMap::new(),
+            google_row: GoogleRowPlacement::default(),
+            group_by_profile: false,
+            smart_suggestions: false,
+        }
+    }
+}
+
+/// 検索元（ブックマーク・履歴・タブ）の待ち時間の上限
+///
+/// 上限を過ぎた検索元は「timed out」の行を表示し、結果が届いたら検索し直して反映する。
+/// `timeouts_ms` で検索元ごとに上書きできる（例: `{ "history": 1000 }`）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourcesConfig {
+    pub timeout_ms: u64,
+    pub timeouts_ms: HashMap<ResultCategory, u64>,
+    pub watchdog: WatchdogConfig,
+    /// 入力してすぐ表示するために覚えておく最近の検索結果の数（0 で無効、`core/result_cache.rs`）
+    pub warm_results: usize,
+}
+
+impl Default for SourcesConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 300,
+            timeouts_ms: HashMap::new(),
+            watchdog: WatchdogConfig::default(),
+            warm_results: 20,
+        }
+    }
+}
+
+/// 上限を続けて超える検索元を抑える設定（`core/source_watchdog.rs`）
+///
+/// `limit_after` 回続けて上限を超えると結果の数を減らして入力の待ち時間を延ばし、
+/// `pause_after` 回続けると `pause_secs` 秒のあいだ検索しない。上限内に終われば元に戻る。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    pub limit_after: u32,
+    pub pause_after: u32,
+    pub pause_secs: u64,
+    /// 抑えている間の結果の数の上限
+    pub limited_results: usize,
+    /// 抑えている間に入力の待ち時間へ足す時間
+    pub extra_debounce_ms: u64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            limit_after: 3,
+            pause_after: 6,
+            pause_secs: 60,
+            limited_results: 20,
+            extra_debounce_ms: 400,
+        }
+    }
+}
+
+impl SourcesConfig {
+    pub fn timeout(&self, category: ResultCategory) -> Duration {
+        Duration::from_millis(self.timeouts_ms.get(&category).copied().unwrap_or(self.timeout_ms))
+    }
+}
+
+/// 検索結果の提供元の設定（設定パネルで切り替えると保存される）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProvidersConfig {
+    /// 無効にした提供元の名前（例: `["history", "wifi"]`）
+    pub disabled: Vec<String>,
+}
+
+/// アニメーションなどの動きの設定
+///
+/// 省略した項目は Windows の「アニメーション効果」とバッテリー節約機能の状態に従う（`system_prefs.rs`）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MotionConfig {
+    pub animations: Option<bool>,
+    pub thumbnails: Option<bool>,
+    pub continuous_repaint: Option<bool>,
+}
+
+/// 配色のプリセット
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    #[default]
+    Dark,
+    /// 黒地に白の文字（WCAG のコントラスト比を満たす）
+    HighContrast,
+}
+
+/// 見た目の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppearanceConfig {
+    pub theme: Theme,
+    /// 画面下部のショートカットと結果の種類の凡例（慣れたら消せる）
+    pub hint_bar: bool,
+    /// 一覧（Browser/Tabs モード）でタイトルの右に並べる列（空ならタイトルだけ）
+    pub list_columns: Vec<ColumnConfig>,
+    /// 文字・グリッド・余白をまとめて拡大する倍率（指定しなければ Windows の「テキストのサイズ」に合わせる）
+    pub ui_scale: Option<f32>,
+    /// ランチャーのウィンドウの出し方
+    pub presentation: Presentation,
+    /// `presentation` が `drop_down` のときの大きさと動き
+    pub drop_down: DropDownConfig,
+}
+
+impl Default for AppearanceConfig {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            hint_bar: true,
+            list_columns: vec![
+                ColumnConfig { column: ListColumn::Url, width: 320.0 },
+                ColumnConfig { column: ListColumn::VisitCount, width: 50.0 },
+                ColumnConfig { column: ListColumn::LastVisit, width: 70.0 },
+            ],
+            ui_scale: None,
+            presentation: Presentation::default(),
+            drop_down: DropDownConfig::default(),
+        }
+    }
+}
+
+/// ランチャーのウィンドウの出し方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Presentation {
+    /// 枠付きのウィンドウを画面中央に出す
+    #[default]
+    Centered,
+    /// アクティブなモニターの上端から滑り降りてくる（Quake 風のターミナルと同じ）
+    DropDown,
+}
+
+/// 上端から降りてくる表示（`presentation: drop_down`）の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DropDownConfig {
+    /// 幅（モニターの作業領域より広ければ作業領域に合わせる）
+    pub width: f32,
+    pub height: f32,
+    /// 降りてくるまでのミリ秒（0 ならすぐに出す。アニメーション効果がオフのときも動かさない）
+    pub slide_ms: u64,
+}
+
+impl Default for DropDownConfig {
+    fn default() -> Self {
+        Self { width: 1200.0, height: 560.0, slide_ms: 150 }
+    }
+}
+
+/// フォーカスを失ったランチャーを閉じる設定（`core/auto_hide.rs`）
+///
+/// デーモン・ホットキーから開いたときと、上端から降りてくる表示のときだけ使う。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoHideConfig {
+    pub enabled: bool,
+    /// フォーカスを失ってから閉じるまでのミリ秒（その間に戻れば閉じない）
+    pub grace_ms: u64,
+    /// 設定パネルや確認ダイアログを開いている間は閉じない
+    pub keep_open_with_dialogs: bool,
+}
+
+impl Default for AutoHideConfig {
+    fn default() -> Self {
+        Self { enabled: true, grace_ms: 300, keep_open_with_dialogs: true }
+    }
+}
+
+/// 一覧の列の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListColumn {
+    Domain,
+    Url,
+    VisitCount,
+    LastVisit,
+}
+
+/// 一覧の列と幅（ピクセル）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnConfig {
+    pub column: ListColumn,
+    pub width: f32,
+}
+
+/// Chrome拡張機能から届くタブ一覧の設定
+///
+/// 拡張機能はタブが変わるたびに一覧を送り、30秒ごとに keepAlive を送る。
+/// どちらも `stale_after_secs` 秒届かなければタブの結果をグレーにし、`drop_after_secs` 秒で表示しなくなる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TabsConfig {
+    pub stale_after_secs: u64,
+    pub drop_after_secs: u64,
+}
+
+impl Default for TabsConfig {
+    fn default() -> Self {
+        Self {
+            stale_after_secs: crate::core::native_messaging::DEFAULT_STALE_AFTER.as_secs(),
+            drop_after_secs: crate::core::native_messaging::DEFAULT_DROP_AFTER.as_secs(),
+        }
+    }
+}
+
+impl TabsConfig {
+    /// この設定の時間で古いタブを扱う `TabManager`
+    pub fn tab_manager(&self) -> crate::core::TabManager {
+        crate::core::TabManager::new()
+            .with_timeouts(Duration::from_secs(self.stale_after_secs), Duration::from_secs(self.drop_after_secs))
+    }
+}
+
+/// ウィンドウのグリッド（ランチャーの Windows モードと Alt+Tab オーバーレイで共通）
+///
+/// 範囲外の値は `validated` で範囲内に丸める。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridConfig {
+    pub columns: usize,
+    pub item_width: f32,
+    pub item_height: f32,
+    pub spacing: f32,
+    /// 表示サイズに対するサムネイルのキャプチャ解像度の倍率（高 DPI 向けに大きく撮って縮小する）
+    pub thumbnail_scale: f32,
+    /// タイルのタイトルを省略するまでの文字数
+    pub title_length: usize,
+    /// ブラウザのウィンドウのサムネイルに、アクティブなタブのファビコンを重ねる
+    pub favicons: bool,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            columns: 5,
+            item_width: 200.0,
+            item_height: 150.0,
+            spacing: 10.0,
+            thumbnail_scale: 2.0,
+            title_length: 25,
+            favicons: true,
+        }
+    }
+}
+
+impl GridConfig {
+    /// 各項目を使える範囲に丸めた設定（丸めた項目は警告をログに出す）
+    pub fn validated(&self) -> Self {
+        fn clamp<T: PartialOrd + Copy + std::fmt::Display>(name: &str, value: T, min: T, max: T) -> T {
+            let clamped = if value < min {
+                min
+            } else if value > max {
+                max
+            } else {
+                value
+            };
+            if clamped != value {
+                log::warn!("grid.{} = {} is out of range ({}..={}), using {}", name, value, min, max, clamped);
+            }
+            clamped
+        }
+
+        Self {
+            columns: clamp("columns", self.columns, 1, 12),
+            item_width: clamp("item_width", self.item_width, 80.0, 600.0),
+            item_height: clamp("item_height", self.item_height, 80.0, 600.0),
+            spacing: clamp("spacing", self.spacing, 0.0, 50.0),
+            thumbnail_scale: clamp("thumbnail_scale", self.thumbnail_scale, 0.5, 4.0),
+            title_length: clamp("title_length", self.title_length, 8, 100),
+            favicons: self.favicons,
+        }
+    }
+}
+
+/// ランチャーを開かずに操作を実行するホットキー
+///
+/// 例: `{ "keys": "Win+1", "action": { "type": "switch_window", "process": "WindowsTerminal.exe" } }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    /// `Ctrl+Alt+B` の形式のキーの組み合わせ（`hotkeys::parse_hotkey`）
+    pub keys: String,
+    pub action: HotkeyAction,
+}
+
+/// ホットキーで実行する操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// プロセス名（と `title` を含むタイトル）が一致する最初のウィンドウに切り替える
+    SwitchWindow {
+        process: String,
+        #[serde(default)]
+        title: Option<String>,
+    },
+    OpenUrl { url: String },
+    /// 最近使ったウィンドウの一覧を開く（`my-launcher --recent` を別のウィンドウで起動する）
+    RecentWindows,
+    /// 検索して、Web検索の行を除いた先頭の結果を実行する（例: ブックマークの名前で検索して開く）
+    Search {
+        query: String,
+        #[serde(default = "default_hotkey_mode")]
+        mode: SearchMode,
+    },
+}
+
+fn default_hotkey_mode() -> SearchMode {
+    SearchMode::Browser
+}
+
+/// 1つの結果から順に実行する操作（`macros::parse_step` の書式）
+///
+/// 例: `{ "name": "Standup", "steps": ["switch slack.exe", "paste おはようございます", "key enter"] }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroConfig {
+    pub name: String,
+    pub steps: Vec<String>,
+    /// 各ステップの間に待つ時間
+    #[serde(default = "default_macro_delay_ms")]
+    pub delay_ms: u64,
+}
+
+fn default_macro_delay_ms() -> u64 {
+    150
+}
+
+/// URL をブラウザではなくアプリで開く規則
+///
+/// 例: `{ "pattern": "open.spotify.com/track/*", "target": "spotify:track:{1}" }`
+/// `app` を省略すると `target` を既定のアプリ（URL スキームの登録先）で開く。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppHandlerConfig {
+    pub pattern: String,
+    pub target: String,
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+/// デーモンのローカル HTTP API（`http_api.rs`）の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// 有効にすると 127.0.0.1 で待ち受ける（トークンはデータディレクトリの api_token）
+    pub enabled: bool,
+    pub port: u16,
+    /// ブラウザから呼べるページのオリジン（空ならブラウザからは呼べない）
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self { enabled: false, port: 9998, allowed_origins: Vec::new() }
+    }
+}
+
+/// 結果を表示する前に変換する Rhai スクリプト（`result_script.rs`）のモードごとのパス
+///
+/// 相対パスはデータディレクトリから。`scripting` フィーチャーでビルドしたときだけ使われる。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptsConfig {
+    pub browser: Option<PathBuf>,
+    pub windows: Option<PathBuf>,
+    pub tabs: Option<PathBuf>,
+}
+
+impl ScriptsConfig {
+    pub fn path(&self, mode: SearchMode) -> Option<PathBuf> {
+        let path = match mode {
+            SearchMode::Browser => self.browser.as_ref(),
+            SearchMode::Windows => self.windows.as_ref(),
+            SearchMode::Tabs => self.tabs.as_ref(),
+            SearchMode::General => None,
+        }?;
+        Some(if path.is_relative() { crate::paths::data_dir().join(path) } else { path.clone() })
+    }
+}
+
+/// 検索語が空のときに表示するセクション（`core/empty_state.rs`）のモードごとの並び
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmptyStateConfig {
+    pub browser: Vec<EmptySection>,
+    pub windows: Vec<EmptySection>,
+    pub tabs: Vec<EmptySection>,
+    pub general: Vec<EmptySection>,
+    /// `pinned` セクションに常に表示する項目
+    pub pinned: Vec<PinnedItem>,
+    /// セクションごとの最大件数
+    pub max_items: usize,
+}
+
+impl Default for EmptyStateConfig {
+    fn default() -> Self {
+        use EmptySection::*;
+        Self {
+            browser: vec![Pinned, RecentQueries, TopSites, Shortcuts],
+            windows: vec![Shortcuts],
+            tabs: vec![Shortcuts],
+            general: vec![Pinned, RecentQueries, Shortcuts],
+            pinned: Vec::new(),
+            max_items: 5,
+        }
+    }
+}
+
+impl EmptyStateConfig {
+    pub fn sections(&self, mode: SearchMode) -> &[EmptySection] {
+        match mode {
+            SearchMode::Browser => &self.browser,
+            SearchMode::Windows => &self.windows,
+            SearchMode::Tabs => &self.tabs,
+            SearchMode::General => &self.general,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinnedItem {
+    pub title: String,
+    pub url: String,
+}
+
+/// よく開くブックマークのプレビュー画像（`page_preview.rs`、`previews` フィーチャーが必要）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewsConfig {
+    /// ページを取得するため既定では無効
+    pub enabled: bool,
+    /// この回数以上開いたブックマークだけ取得する
+    pub min_opens: usize,
+    /// キャッシュの合計サイズの上限
+    pub max_cache_mb: u64,
+}
+
+impl Default for PreviewsConfig {
+    fn default() -> Self {
+        Self { enabled: false, min_opens: 3, max_cache_mb: 20 }
+    }
+}
+
+/// プライバシーモード（`core/privacy.rs`）
+///
+/// 有効な間は履歴を検索せず、開いたものや検索語を記録しない。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    /// 起動時からプライバシーモードにする
+    pub enabled: bool,
+    /// 自動でプライバシーモードにする時間帯
+    pub schedule: Vec<PrivacySchedule>,
+    /// ブックマーク・履歴を読み込まないプロファイル（`Chrome - Profile 2` の形式）
+    pub private_profiles: Vec<String>,
+}
+
+/// プライバシーモードにする時間帯（`start` が `end` より後なら日をまたぐ）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrivacySchedule {
+    /// `HH:MM`
+    pub start: String,
+    pub end: String,
+    /// `mon` / `tue` ... `sun`（省略すると毎日）
+    #[serde(default)]
+    pub days: Vec<String>,
+}
+
+/// `docker` キーワード（`system/docker.rs`）の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DockerConfig {
+    /// 有効にするとキーワードを登録する（Docker を使わない環境では無効のまま）
+    pub enabled: bool,
+    /// Docker Engine API の名前付きパイプ
+    pub pipe: String,
+}
+
+impl Default for DockerConfig {
+    fn default() -> Self {
+        Self { enabled: false, pipe: r"\\.\pipe\docker_engine".to_string() }
+    }
+}
+
+/// デバッグ用の設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebugConfig {
+    /// 起動時からフレームごとの処理時間を表示する（F12 でも切り替えられる）
+    pub frame_overlay: bool,
+}
+
+/// ログに書く前に伏せる内容（`log_redaction.rs`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// URL のクエリ文字列の値・認証情報・`redact_patterns` を伏せる（調べるときは false にしてそのまま書く）
+    pub redact: bool,
+    /// 伏せる文字列（ウィンドウタイトルの一部など。`*` は空白以外の文字の並びに一致）
+    pub redact_patterns: Vec<String>,
+    /// URL の外でも値を伏せるパラメーター名（token・password などに加えて）
+    pub sensitive_params: Vec<String>,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self { redact: true, redact_patterns: Vec::new(), sensitive_params: Vec::new() }
+    }
+}
+
+impl LauncherConfig {
+    /// 既定の場所から設定を読み込む（読み込めない場合は既定値）
+    pub fn load() -> Self {
+        let path = crate::paths::config_path();
+        if !path.exists() {
+            log::debug!("Config file not found at {}, using defaults", path.display());
+            return Self::default();
+        }
+
+        match Self::load_from(&path) {
+            Ok(config) => {
+                log::info!("Loaded config from {}", path.display());
+                config
+            }
+            Err(e) => {
+                log::error!("Failed to load config from {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, r#"{ "update": { "check_on_startup": true } }"#).unwrap();
+
+        let config = LauncherConfig::load_from(&path).unwrap();
+        assert!(config.update.check_on_startup);
+        assert_eq!(config.update.asset_name, "my-launcher.exe");
+        assert_eq!(config.notes.path, None);
+        assert_eq!(config.ranking.order, ResultCategory::DEFAULT_ORDER.to_vec());
+    }
+
+    #[test]
+    fn test_ranking_format() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "ranking": { "order": ["tab", "bookmark", "google"], "weights": { "tab": 2.0 } } }"#,
+        ).unwrap();
+        assert_eq!(
+            config.ranking.order,
+            vec![ResultCategory::Tab, ResultCategory::Bookmark, ResultCategory::Google]
+        );
+        assert_eq!(config.ranking.weights.get(&ResultCategory::Tab), Some(&2.0));
+        assert_eq!(config.ranking.google_row, GoogleRowPlacement::Ranked);
+
+        let config: LauncherConfig = serde_json::from_str(r#"{ "ranking": { "google_row": "fallback" } }"#).unwrap();
+        assert_eq!(config.ranking.google_row, GoogleRowPlacement::Fallback);
+    }
+
+    #[test]
+    fn test_overlay_placement_format() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "alt_tab": { "placement": { "mode": "monitor_index", "index": 2 } } }"#,
+        ).unwrap();
+        assert_eq!(config.alt_tab.placement, OverlayPlacement::MonitorIndex { index: 2 });
+
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "alt_tab": { "placement": { "mode": "cursor_monitor" } } }"#,
+        ).unwrap();
+        assert_eq!(config.alt_tab.placement, OverlayPlacement::CursorMonitor);
+    }
+
+    #[test]
+    fn test_drop_down_presentation_format() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "appearance": { "presentation": "drop_down", "drop_down": { "width": 1600 } } }"#,
+        ).unwrap();
+        assert_eq!(config.appearance.presentation, Presentation::DropDown);
+        assert_eq!(config.appearance.drop_down.width, 1600.0);
+        assert_eq!(config.appearance.drop_down.slide_ms, 150);
+        assert_eq!(LauncherConfig::default().appearance.presentation, Presentation::Centered);
+    }
+
+    #[test]
+    fn test_auto_hide_format() {
+        let config: LauncherConfig = serde_json::from_str(r#"{ "auto_hide": { "grace_ms": 1000 } }"#).unwrap();
+        assert!(config.auto_hide.enabled);
+        assert_eq!(config.auto_hide.grace_ms, 1000);
+        assert!(config.auto_hide.keep_open_with_dialogs);
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.json");
+
+        let mut config = LauncherConfig::default();
+        config.update.releases_url = "https://example.com/latest".to_string();
+        config.save_to(&path).unwrap();
+
+        let loaded = LauncherConfig::load_from(&path).unwrap();
+        assert_eq!(loaded.update.releases_url, "https://example.com/latest");
+        assert!(!loaded.update.check_on_startup);
+    }
+
+    #[test]
+    fn test_grid_validation() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "grid": { "columns": 0, "item_width": 1000.0, "thumbnail_scale": 1.5, "title_length": 3 } }"#,
+        ).unwrap();
+        let grid = config.grid.validated();
+        assert_eq!(grid.columns, 1);
+        assert_eq!(grid.item_width, 600.0);
+        assert_eq!(grid.item_height, 150.0);
+        assert_eq!(grid.thumbnail_scale, 1.5);
+        assert_eq!(grid.title_length, 8);
+        assert_eq!(GridConfig::default().validated(), GridConfig::default());
+    }
+
+    #[test]
+    fn test_hotkeys_format() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "hotkeys": [
+                { "keys": "Win+1", "action": { "type": "switch_window", "process": "WindowsTerminal.exe" } },
+                { "keys": "Win+B", "action": { "type": "search", "query": "standup notes" } },
+                { "keys": "Alt+Q", "action": { "type": "recent_windows" } }
+            ] }"#,
+        ).unwrap();
+        assert_eq!(
+            config.hotkeys[0].action,
+            HotkeyAction::SwitchWindow { process: "WindowsTerminal.exe".to_string(), title: None }
+        );
+        assert_eq!(
+            config.hotkeys[1].action,
+            HotkeyAction::Search { query: "standup notes".to_string(), mode: SearchMode::Browser }
+        );
+        assert_eq!(config.hotkeys[2].action, HotkeyAction::RecentWindows);
+    }
+
+    #[test]
+    fn test_new_tab_format() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "new_tab": { "default_profile": "Chrome - Default", "profiles": { "work": "Chrome - Profile 1" } } }"#,
+        ).unwrap();
+        assert_eq!(config.new_tab.default_profile.as_deref(), Some("Chrome - Default"));
+        assert_eq!(config.new_tab.profiles["work"], "Chrome - Profile 1");
+        assert_eq!(config.new_tab.search_url, NewTabConfig::default().search_url);
+    }
+
+    #[test]
+    fn test_macros_format() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "macros": [ { "name": "Standup", "steps": ["switch slack.exe", "key enter"] } ] }"#,
+        ).unwrap();
+        assert_eq!(config.macros[0].name, "Standup");
+        assert_eq!(config.macros[0].steps.len(), 2);
+        assert_eq!(config.macros[0].delay_ms, 150);
+    }
+
+    #[test]
+    fn test_api_format() {
+        let config: LauncherConfig = serde_json::from_str(r#"{ "api": { "enabled": true } }"#).unwrap();
+        assert!(config.api.enabled);
+        assert_eq!(config.api.port, 9998);
+        assert!(!LauncherConfig::default().api.enabled);
+    }
+
+    #[test]
+    fn test_docker_format() {
+        let config: LauncherConfig = serde_json::from_str(r#"{ "docker": { "enabled": true } }"#).unwrap();
+        assert!(config.docker.enabled);
+        assert_eq!(config.docker.pipe, r"\\.\pipe\docker_engine");
+    }
+
+    #[test]
+    fn test_empty_state_format() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "empty_state": { "browser": ["top_sites"], "pinned": [{ "title": "Jira", "url": "https://jira.example.com" }] } }"#,
+        )
+        .unwrap();
+        assert_eq!(config.empty_state.sections(SearchMode::Browser), &[EmptySection::TopSites]);
+        assert_eq!(config.empty_state.sections(SearchMode::Windows), &[EmptySection::Shortcuts]);
+        assert_eq!(config.empty_state.pinned[0].title, "Jira");
+        assert_eq!(config.empty_state.max_items, 5);
+    }
+
+    #[test]
+    fn test_privacy_format() {
+        let config: LauncherConfig = serde_json::from_str(
+            r#"{ "privacy": { "schedule": [{ "start": "18:00", "end": "09:00", "days": ["sat", "sun"] }], "private_profiles": ["Chrome - Profile 2"] } }"#,
+        )
+        .unwrap();
+        assert!(!config.privacy.enabled);
+        assert_eq!(config.privacy.schedule[0].days, vec!["sat", "sun"]);
+        assert_eq!(config.privacy.private_profiles, vec!["Chrome - Profile 2"]);
+    }
+
+    #[test]
+    fn test_logging_format() {
+        assert!(LauncherConfig::default().logging.redact);
+        let config: LauncherConfig = serde_json::from_str(r#"{ "logging": { "redact_patterns": ["Chat with *"] } }"#).unwrap();
+        assert!(config.logging.redact);
+        assert_eq!(config.logging.redact_patterns, vec!["Chat with *"]);
+    }
+}
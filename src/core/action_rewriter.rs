@@ -0,0 +1,112 @@
+use super::Action;
+use crate::config::AppHandlerConfig;
+
+/// 実行する直前に操作を置き換える（`LauncherCore::execute_action` が最初に通す）
+pub trait ActionRewriter: Send + Sync {
+    /// 置き換える操作（置き換えないなら `None`）
+    fn rewrite(&self, action: &Action) -> Option<Action>;
+}
+
+/// URL を開く操作を、設定の `open_in_app` に一致すればアプリで開く操作にする
+///
+/// パターンはスキームと `www.` を除いた URL（`zoom.us/j/123`）に対して比べ、`*` は任意の文字列に一致する。
+/// パターンに `?` がなければクエリ文字列は比べない。`target` の `{1}`〜`{9}` は `*` に一致した部分、
+/// `{query}` はクエリ文字列、`{url}` は元の URL に置き換える。
+pub struct AppHandlerRewriter {
+    handlers: Vec<AppHandlerConfig>,
+}
+
+impl AppHandlerRewriter {
+    pub fn new(handlers: Vec<AppHandlerConfig>) -> Self {
+        Self { handlers }
+    }
+
+    /// URL に一致する最初の規則で、開く先を作る
+    pub fn target_for(&self, url: &str) -> Option<(Option<String>, String)> {
+        let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let without_fragment = without_scheme.split('#').next().unwrap_or_default();
+        let normalized = without_fragment.strip_prefix("www.").unwrap_or(without_fragment);
+        let (path, query) = normalized.split_once('?').unwrap_or((normalized, ""));
+
+        self.handlers.iter().find_map(|handler| {
+            let text = if handler.pattern.contains('?') { normalized } else { path.trim_end_matches('/') };
+            let captures = glob_captures(&handler.pattern, text)?;
+            let mut target = handler.target.replace("{url}", url).replace("{query}", query);
+            for (i, capture) in captures.iter().enumerate().take(9) {
+                target = target.replace(&format!("{{{}}}", i + 1), capture);
+            }
+            Some((handler.app.clone(), target))
+        })
+    }
+}
+
+impl ActionRewriter for AppHandlerRewriter {
+    fn rewrite(&self, action: &Action) -> Option<Action> {
+        match action {
+            Action::OpenBookmark(url) | Action::OpenHistory(url) | Action::OpenUrl(url) => {
+                let (app, target) = self.target_for(url)?;
+                log::info!("Opening {} in an app: {}", url, target);
+                Some(Action::OpenInApp { app, target })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `*` を含むパターンに一致すれば、`*` に一致した部分を返す（英字の大文字小文字は区別しない）
+fn glob_captures(pattern: &str, text: &str) -> Option<Vec<String>> {
+    fn matches(pattern: &[char], text: &[char], captures: &mut Vec<String>) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some(('*', rest)) => {
+                for end in 0..=text.len() {
+                    captures.push(text[..end].iter().collect());
+                    if matches(rest, &text[end..], captures) {
+                        return true;
+                    }
+                    captures.pop();
+                }
+                false
+            }
+            Some((c, rest)) => {
+                text.first().is_some_and(|t| t.eq_ignore_ascii_case(c)) && matches(rest, &text[1..], captures)
+            }
+        }
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let mut captures = Vec::new();
+    matches(&pattern, &text, &mut captures).then_some(captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler(pattern: &str, target: &str, app: Option<&str>) -> AppHandlerConfig {
+        AppHandlerConfig { pattern: pattern.to_string(), target: target.to_string(), app: app.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let rewriter = AppHandlerRewriter::new(vec![
+            handler("open.spotify.com/track/*", "spotify:track:{1}", None),
+            handler("*zoom.us/j/*", "zoommtg://zoom.us/join?confno={2}&{query}", Some(r"C:\Zoom\Zoom.exe")),
+        ]);
+
+        assert_eq!(
+            rewriter.rewrite(&Action::OpenHistory("https://open.spotify.com/track/4uLU6hMC?si=abc".to_string())),
+            Some(Action::OpenInApp { app: None, target: "spotify:track:4uLU6hMC".to_string() })
+        );
+        assert_eq!(
+            rewriter.rewrite(&Action::OpenBookmark("https://us02web.zoom.us/j/123456/?pwd=x".to_string())),
+            Some(Action::OpenInApp {
+                app: Some(r"C:\Zoom\Zoom.exe".to_string()),
+                target: "zoommtg://zoom.us/join?confno=123456&pwd=x".to_string(),
+            })
+        );
+        assert_eq!(rewriter.rewrite(&Action::OpenUrl("https://open.spotify.com/album/1".to_string())), None);
+        assert_eq!(rewriter.rewrite(&Action::GoogleSearch("open.spotify.com/track/1".to_string())), None);
+    }
+}
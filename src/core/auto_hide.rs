@@ -0,0 +1,86 @@
+//! フォーカスを失ったランチャーを自動で閉じる判断
+//!
+//! 一度フォーカスを得たあとでフォーカスを失い、猶予の間に戻らなければ閉じる。起動直後でまだ
+//! フォーカスを得ていないときや、設定パネル・確認ダイアログを開いているとき（設定による）は閉じない。
+
+use crate::config::AutoHideConfig;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct AutoHide {
+    grace: Duration,
+    keep_open_with_dialogs: bool,
+    had_focus: bool,
+    /// フォーカスを失った時刻（戻ったら `None`）
+    lost_at: Option<Instant>,
+}
+
+impl AutoHide {
+    pub fn new(config: &AutoHideConfig) -> Self {
+        Self {
+            grace: Duration::from_millis(config.grace_ms),
+            keep_open_with_dialogs: config.keep_open_with_dialogs,
+            had_focus: false,
+            lost_at: None,
+        }
+    }
+
+    /// 毎フレームのフォーカスの状態を渡す。閉じるべきなら true
+    pub fn observe(&mut self, focused: bool, dialog_open: bool, now: Instant) -> bool {
+        if focused || (dialog_open && self.keep_open_with_dialogs) {
+            self.had_focus |= focused;
+            self.lost_at = None;
+            return false;
+        }
+        if !self.had_focus {
+            return false;
+        }
+        let lost_at = *self.lost_at.get_or_insert(now);
+        now.duration_since(lost_at) >= self.grace
+    }
+
+    /// 猶予が過ぎるまでの時間（フォーカスを失っていなければ `None`。再描画の予約に使う）
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.lost_at.map(|lost_at| self.grace.saturating_sub(now.duration_since(lost_at)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto_hide(grace_ms: u64) -> AutoHide {
+        AutoHide::new(&AutoHideConfig { grace_ms, ..AutoHideConfig::default() })
+    }
+
+    #[test]
+    fn test_hides_after_grace_period() {
+        let start = Instant::now();
+        let mut auto_hide = auto_hide(300);
+        // まだフォーカスを得ていなければ閉じない
+        assert!(!auto_hide.observe(false, false, start));
+
+        assert!(!auto_hide.observe(true, false, start));
+        assert!(!auto_hide.observe(false, false, start + Duration::from_millis(100)));
+        assert_eq!(auto_hide.remaining(start + Duration::from_millis(200)), Some(Duration::from_millis(200)));
+        assert!(auto_hide.observe(false, false, start + Duration::from_millis(400)));
+    }
+
+    #[test]
+    fn test_refocus_and_dialogs_reset_the_grace_period() {
+        let start = Instant::now();
+        let mut auto_hide = auto_hide(300);
+        auto_hide.observe(true, false, start);
+        auto_hide.observe(false, false, start + Duration::from_millis(100));
+        auto_hide.observe(true, false, start + Duration::from_millis(200));
+        assert!(!auto_hide.observe(false, false, start + Duration::from_millis(450)));
+
+        // ダイアログを開いている間は閉じない
+        assert!(!auto_hide.observe(false, true, start + Duration::from_millis(2000)));
+        assert_eq!(auto_hide.remaining(start + Duration::from_millis(2000)), None);
+
+        let mut always = AutoHide::new(&AutoHideConfig { grace_ms: 0, keep_open_with_dialogs: false, ..AutoHideConfig::default() });
+        always.observe(true, false, start);
+        assert!(always.observe(false, true, start));
+    }
+}
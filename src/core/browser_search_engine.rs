@@ -1,57 +1,699 @@
-use super::{SearchEngine, SearchResult, SearchMode, Action, ResultType, window_manager::WindowInfo};
+use super::search_engine::{open_url_result, BookmarkSource, route_general, run_command_result, GeneralRoute};
+use super::{SearchEngine, SearchResult, SearchMode, Action, ResultType, ResultMetadata, ChromeTab, TabFreshness, window_manager::WindowInfo};
 use crate::data::{
-    browser_provider::{BrowserDataProvider, ChromeBrowserProvider, CachedBrowserProvider},
+    browser_provider::{BrowserDataProvider, ChromeBrowserProvider, CachedBrowserProvider, BookmarkEdit},
     browser_item::{BookmarkItem, HistoryItem},
+    browser_index::BrowserIndexStore,
+    tab_item::TabItem,
     tab_provider::{TabProvider, ChromeTabProvider},
+    notes::NoteStore,
+    recent_queries::RecentQueryStore,
+    usage::{UsageEntry, UsageStore, UsageTarget},
+    workspaces::{TagRule, TagTarget, WorkspaceStore, Workspaces},
 };
+use crate::filter::{GeometryFilter, TagFilter};
+use crate::timers::{self, TimerKind, TimerStore};
+use crate::layout_manager::LayoutManager;
+use crate::session::{self, Session};
+use super::keyword_provider::{KeywordHelp, KeywordProvider};
+use super::result_composer::{compose_results, group_results, place_google_row, time_of_day_score, ResultCategory};
+use super::result_filter::ResultFilter;
+use super::query::Query;
+use super::timed_source::{PendingSource, SourceResult, TimedSource};
+use super::source_watchdog::Throttle;
+use super::provider_registry::ProviderRegistry;
+use super::confirmation::ConfirmableAction;
+use super::health::{HealthRegistry, HealthState};
+use super::privacy::PrivacyMode;
+use super::title_history::TitleHistory;
+use super::recently_closed::{closed_tab_results, CLOSED_KEYWORD};
+use super::reading_list::reading_list_results;
+use super::empty_state::{EmptyState, EmptyStateSource};
+use crate::config::{EmptyStateConfig, LauncherConfig, MacroConfig, NewTabConfig, PreviewsConfig, PrivacyConfig, RankingConfig, SourcesConfig};
+use crate::disk_cache::{self, CacheUsage, DiskCache};
+use crate::time_format;
+use std::error::Error;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use std::collections::{BTreeSet, HashMap};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Browserモードでこの接頭辞を付けるとタブのみを検索する（例: `t github`）
+pub const TAB_SEARCH_PREFIX: &str = "t ";
+
+const CLOSE_DUPLICATE_TABS: &str = "close duplicate tabs";
+const OPEN_TABS_OVERVIEW: &str = "open tabs overview";
+const CLEAR_CACHES: &str = "clear caches";
+/// 時間帯の提案に出す最低のスコア（同じ時間帯に2回ほど開いていれば出る）
+const SUGGESTION_MIN_SCORE: f32 = 1.5;
+const MAX_SUGGESTIONS: usize = 5;
+/// Browserモードの検索に混ぜる最近閉じたタブの上限（`closed` キーワードでは上限なし）
+const MAX_CLOSED_TABS: usize = 5;
+/// 時間帯の提案をまとめる見出し
+const SUGGESTIONS_GROUP: &str = "Suggested for now";
+/// 前回のセッションで開いていたアプリを起動し直すコマンド（Windows モード）
+const RESTORE_SESSION: &str = "restore previous session";
+
+/// アクティブなタブをブックマークに追加するキーワード（例: `bm add Work/Reading`）
+pub const BOOKMARK_ADD_KEYWORD: &str = "bm add";
+
+/// クイックメモを追記するキーワード（例: `note 牛乳を買う`）
+pub const NOTE_KEYWORD: &str = "note";
+
+/// クイックメモを検索するキーワード（例: `notes 牛乳`）
+pub const NOTES_KEYWORD: &str = "notes";
+
+/// 相対時間のタイマーを設定するキーワード（例: `timer 25m 休憩`）
+pub const TIMER_KEYWORD: &str = "timer";
+
+/// 時刻を指定してリマインダーを設定するキーワード（例: `remind 15:30 会議`）
+pub const REMIND_KEYWORD: &str = "remind";
+
+/// ウィンドウ配置を保存・復元するキーワード（例: `layout save 自宅` / `layout 自宅`）
+pub const LAYOUT_KEYWORD: &str = "layout";
+
+/// ウィンドウ・プロファイルにワークスペースのタグを付けるキーワード（例: `tag project-A my-launcher`）
+pub const TAG_KEYWORD: &str = "tag";
+
+/// プロファイルを指定して新しいタブで検索するキーワード（例: `nt work rust lifetimes`）
+pub const NEW_TAB_KEYWORD: &str = "nt";
+
+/// 設定したマクロを一覧・実行するキーワード（例: `macro standup`）
+pub const MACRO_KEYWORD: &str = "macro";
+
+/// 組み込みの提供元の名前（設定パネルでの表示名、設定ファイルの `providers.disabled` の値）
+pub const WINDOWS_PROVIDER: &str = "windows";
+pub const TABS_PROVIDER: &str = "tabs";
+pub const BOOKMARKS_PROVIDER: &str = "bookmarks";
+pub const HISTORY_PROVIDER: &str = "history";
+pub const BOOKMARK_ADD_PROVIDER: &str = "bookmark_add";
+pub const NOTES_PROVIDER: &str = "notes";
+pub const TIMERS_PROVIDER: &str = "timers";
+pub const LAYOUTS_PROVIDER: &str = "layouts";
+pub const WORKSPACES_PROVIDER: &str = "workspaces";
+pub const NEW_TAB_PROVIDER: &str = "new_tab";
+pub const MACROS_PROVIDER: &str = "macros";
+pub const RECENTLY_CLOSED_PROVIDER: &str = "recently_closed";
+pub const READING_LIST_PROVIDER: &str = "reading_list";
+
+/// 組み込みの提供元とシステム操作のプロバイダーを登録したレジストリ
+fn default_registry() -> ProviderRegistry {
+    const KEYWORD_MODES: &[SearchMode] = &[SearchMode::Browser, SearchMode::Tabs];
+    let mut registry = ProviderRegistry::new();
+    registry.register_builtin(WINDOWS_PROVIDER, &[], &[SearchMode::Windows], &[
+        KeywordHelp::new(RESTORE_SESSION, "Relaunch the apps that were open in the previous session"),
+    ]);
+    registry.register_builtin(TABS_PROVIDER, &[], &[SearchMode::Browser, SearchMode::Tabs], &[
+        KeywordHelp::new(CLOSE_DUPLICATE_TABS, "Close tabs that have the same URL as another tab"),
+        KeywordHelp::new(OPEN_TABS_OVERVIEW, "Show every tab grouped by window"),
+    ]);
+    registry.register_builtin(BOOKMARKS_PROVIDER, &[], &[SearchMode::Browser], &[
+        KeywordHelp::new(CLEAR_CACHES, "Show and delete the cached previews and favicons"),
+    ]);
+    registry.register_builtin(HISTORY_PROVIDER, &[], &[SearchMode::Browser], &[]);
+    registry.register_builtin(READING_LIST_PROVIDER, &[], &[SearchMode::Browser], &[]);
+    registry.register_builtin(RECENTLY_CLOSED_PROVIDER, &[CLOSED_KEYWORD], KEYWORD_MODES, &[
+        KeywordHelp::new("closed [query]", "Reopen a recently closed tab"),
+    ]);
+    registry.register_builtin(BOOKMARK_ADD_PROVIDER, &[BOOKMARK_ADD_KEYWORD], KEYWORD_MODES, &[
+        KeywordHelp::new("bm add [folder]", "Bookmark the active tab, e.g. bm add Work/Reading"),
+    ]);
+    registry.register_builtin(NOTES_PROVIDER, &[NOTE_KEYWORD, NOTES_KEYWORD], KEYWORD_MODES, &[
+        KeywordHelp::new("note <text>", "Append a quick note"),
+        KeywordHelp::new("notes [query]", "Search your notes"),
+    ]);
+    registry.register_builtin(TIMERS_PROVIDER, &[TIMER_KEYWORD, REMIND_KEYWORD], KEYWORD_MODES, &[
+        KeywordHelp::new("timer <duration> [label]", "Start a timer, e.g. timer 25m break"),
+        KeywordHelp::new("remind <time> [label]", "Set a reminder, e.g. remind 15:30 meeting"),
+    ]);
+    registry.register_builtin(LAYOUTS_PROVIDER, &[LAYOUT_KEYWORD], KEYWORD_MODES, &[
+        KeywordHelp::new("layout save <name>", "Save the current window layout"),
+        KeywordHelp::new("layout <name>", "Restore a saved window layout"),
+    ]);
+    registry.register_builtin(WORKSPACES_PROVIDER, &[TAG_KEYWORD], KEYWORD_MODES, &[
+        KeywordHelp::new("tag <workspace> <window or profile>", "Add a window or browser profile to a workspace"),
+    ]);
+    registry.register_builtin(NEW_TAB_PROVIDER, &[NEW_TAB_KEYWORD], KEYWORD_MODES, &[
+        KeywordHelp::new("nt <profile> <query>", "Search in a new tab of a browser profile"),
+    ]);
+    registry.register_builtin(MACROS_PROVIDER, &[MACRO_KEYWORD], KEYWORD_MODES, &[
+        KeywordHelp::new("macro [name]", "List or run a configured macro"),
+    ]);
+    for provider in crate::system::default_providers() {
+        registry.register(provider);
+    }
+    registry
+}
+
+pub use super::query::strip_keyword;
+
+/// `bm add [folder]` を解析し、フォルダ（省略時は `None`）を返す
+pub fn parse_bookmark_add(query: &str) -> Option<Option<String>> {
+    strip_keyword(query, BOOKMARK_ADD_KEYWORD).map(bookmark_folder)
+}
+
+/// `bm add` の引数のフォルダ（省略時は `None`）
+fn bookmark_folder(args: &str) -> Option<String> {
+    let folder = args.trim_matches('/');
+    if folder.is_empty() { None } else { Some(folder.to_string()) }
+}
+
+/// 各ウィンドウのアクティブなタブをブックマークに追加する検索結果
+pub fn bookmark_add_results(tabs: &[ChromeTab], folder: Option<String>) -> Vec<SearchResult> {
+    let destination = folder.clone().unwrap_or_else(|| "Other bookmarks".to_string());
+    
+    tabs.iter()
+        .filter(|tab| tab.active && !tab.url.is_empty())
+        .map(|tab| {
+            let title = if tab.title.is_empty() { tab.url.clone() } else { tab.title.clone() };
+            SearchResult {
+                title: format!("Bookmark: {}", title),
+                description: format!("{} → {}", tab.url, destination),
+                action: Action::CreateBookmark {
+                    url: tab.url.clone(),
+                    title,
+                    folder: folder.clone(),
+                },
+                window_info: None,
+                result_type: ResultType::Command,
+                group: None,
+                metadata: None,
+            }
+        })
+        .collect()
+}
 
 pub struct BrowserSearchEngine {
     browser_provider: Arc<Mutex<CachedBrowserProvider>>,
     tab_provider: Arc<ChromeTabProvider>,
+    note_store: NoteStore,
+    timer_store: TimerStore,
+    layout_manager: LayoutManager,
+    /// 起動時に読み込んだ前回のセッション
+    previous_session: Option<Session>,
+    usage_store: UsageStore,
+    recent_queries: RecentQueryStore,
+    /// 「Clear caches」で消すディスクキャッシュ（表示名, キャッシュ）
+    disk_caches: Vec<(&'static str, DiskCache)>,
+    workspace_store: WorkspaceStore,
+    registry: ProviderRegistry,
+    ranking: RankingConfig,
+    new_tab: NewTabConfig,
+    macros: Vec<MacroConfig>,
+    /// フィルターチップで選んだ絞り込み
+    filter: Mutex<ResultFilter>,
+    /// これまでの検索結果に現れたプロファイル（フィルターチップの候補）
+    detected_profiles: Mutex<BTreeSet<String>>,
+    sources: Sources,
+    health: HealthRegistry,
+    /// ウィンドウの前のタイトル（タイトルが変わったウィンドウも前のタイトルで見つける）
+    title_history: TitleHistory,
+    empty_state: EmptyStateConfig,
+    /// 終了時にブックマーク・履歴の索引を保存する先
+    browser_index: Option<BrowserIndexStore>,
+    /// 有効な間は履歴を検索しない
+    privacy: PrivacyMode,
+}
+
+/// 時間の上限を決めて別スレッドで検索する検索元
+struct Sources {
+    bookmarks: TimedSource<Result<Vec<BookmarkItem>, String>>,
+    history: TimedSource<Result<Vec<HistoryItem>, String>>,
+    tabs: TimedSource<Vec<TabItem>>,
+    /// キーワードプロバイダー（プロバイダー名ごとに最初の検索で作る）
+    keywords: Mutex<HashMap<&'static str, TimedSource<Vec<SearchResult>>>>,
+    keyword_budget: Duration,
+}
+
+impl Sources {
+    fn new(config: &SourcesConfig) -> Self {
+        Self {
+            bookmarks: TimedSource::new("bookmarks", config.timeout(ResultCategory::Bookmark)).with_watchdog(config.watchdog.clone()),
+            history: TimedSource::new("history", config.timeout(ResultCategory::History)).with_watchdog(config.watchdog.clone()),
+            tabs: TimedSource::new("tabs", config.timeout(ResultCategory::Tab)).with_watchdog(config.watchdog.clone()),
+            keywords: Mutex::new(HashMap::new()),
+            keyword_budget: config.timeout(ResultCategory::Command),
+        }
+    }
+
+    /// キーワードプロバイダーの検索を開始する
+    fn start_keyword(&self, provider: Arc<dyn KeywordProvider>, keyword: &str, args: &str) -> PendingSource<Vec<SearchResult>> {
+        let mut keywords = self.keywords.lock().unwrap();
+        let source = keywords.entry(provider.name()).or_insert_with(|| TimedSource::new(provider.name(), self.keyword_budget));
+        let (keyword, args) = (keyword.to_string(), args.to_string());
+        source.start(&format!("{} {}", keyword, args), move || provider.search(&keyword, &args))
+    }
+    
+    fn take_updated(&self) -> bool {
+        // すべての検索元のフラグをリセットするため || で短絡させない
+        let bookmarks = self.bookmarks.take_updated();
+        let history = self.history.take_updated();
+        let tabs = self.tabs.take_updated();
+        let keywords = self.keywords.lock().unwrap().values().filter(|source| source.take_updated()).count() > 0;
+        bookmarks || history || tabs || keywords
+    }
+    
+    fn is_pending(&self) -> bool {
+        self.bookmarks.is_pending()
+            || self.history.is_pending()
+            || self.tabs.is_pending()
+            || self.keywords.lock().unwrap().values().any(TimedSource::is_pending)
+    }
+
+    /// 抑えている検索元のうち、いちばん長い入力の待ち時間の追加分
+    fn extra_debounce(&self) -> Duration {
+        [self.bookmarks.extra_debounce(), self.history.extra_debounce(), self.tabs.extra_debounce()]
+            .into_iter()
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// 時間内に終わらなかった検索元の行
+fn timed_out_result(source: &str, query: &str) -> SearchResult {
+    SearchResult {
+        title: format!("Searching {} timed out", source),
+        description: "Results will appear when ready · Enter to search the web instead".to_string(),
+        action: Action::GoogleSearch(query.to_string()),
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
 }
 
 impl BrowserSearchEngine {
     pub fn new() -> Self {
-        // Chrome用のプロバイダーを作成
-        let chrome_provider = match ChromeBrowserProvider::new() {
-            Ok(provider) => Box::new(provider) as Box<dyn BrowserDataProvider>,
-            Err(e) => {
-                log::error!("Failed to create Chrome provider: {}", e);
-                // ダミープロバイダーを返す
-                Box::new(DummyBrowserProvider)
-            }
-        };
-        
-        let cached_provider = CachedBrowserProvider::new(chrome_provider);
-        let tab_provider = Arc::new(ChromeTabProvider::new());
+        Self::with_providers(chrome_provider(), Arc::new(crate::core::TabManager::new()))
+    }
+    
+    pub fn new_with_tab_manager(tab_manager: Arc<crate::core::TabManager>) -> Self {
+        Self::with_providers(chrome_provider(), tab_manager)
+    }
+    
+    /// ブックマーク・履歴の取得元とタブの一覧を指定して作成（テストでは `InMemoryBrowserProvider` を渡す）
+    pub fn with_providers(browser_provider: Box<dyn BrowserDataProvider>, tab_manager: Arc<crate::core::TabManager>) -> Self {
+        let cached_provider = CachedBrowserProvider::new(browser_provider);
+        let tab_provider = Arc::new(ChromeTabProvider::new_with_tab_manager(tab_manager));
         
         Self {
             browser_provider: Arc::new(Mutex::new(cached_provider)),
             tab_provider,
+            note_store: NoteStore::new(crate::paths::notes_path()),
+            timer_store: TimerStore::default(),
+            layout_manager: LayoutManager::default(),
+            previous_session: None,
+            usage_store: UsageStore::default(),
+            recent_queries: RecentQueryStore::default(),
+            disk_caches: disk_cache::launcher_caches(&PreviewsConfig::default()),
+            workspace_store: WorkspaceStore::default(),
+            registry: default_registry(),
+            ranking: RankingConfig::default(),
+            new_tab: NewTabConfig::default(),
+            macros: Vec::new(),
+            filter: Mutex::new(ResultFilter::default()),
+            detected_profiles: Mutex::new(BTreeSet::new()),
+            sources: Sources::new(&SourcesConfig::default()),
+            health: HealthRegistry::new(),
+            title_history: TitleHistory::new(),
+            empty_state: EmptyStateConfig::default(),
+            browser_index: None,
+            privacy: PrivacyMode::default(),
         }
     }
     
-    pub fn new_with_tab_manager(tab_manager: Arc<crate::core::TabManager>) -> Self {
-        // Chrome用のプロバイダーを作成
-        let chrome_provider = match ChromeBrowserProvider::new() {
-            Ok(provider) => Box::new(provider) as Box<dyn BrowserDataProvider>,
-            Err(e) => {
-                log::error!("Failed to create Chrome provider: {}", e);
-                // ダミープロバイダーを返す
-                Box::new(DummyBrowserProvider)
+    /// キーワードプロバイダーを追加
+    pub fn register_provider(&mut self, provider: Box<dyn KeywordProvider>) {
+        self.registry.register(provider);
+    }
+    
+    /// 提供元の一覧（設定パネルから有効・無効を切り替える）
+    pub fn providers(&self) -> &ProviderRegistry {
+        &self.registry
+    }
+    
+    /// `Action::ProviderCommand` を担当のプロバイダーで実行
+    pub fn execute_provider_command(&self, provider: &str, command: &str) -> Result<String, Box<dyn Error>> {
+        self.registry
+            .all_keyword_providers()
+            .find(|p| p.name() == provider)
+            .ok_or_else(|| format!("unknown provider: {}", provider))?
+            .execute(command)
+    }
+    
+    /// `Action::ProviderCommand` が確認の要るコマンドなら、何が起きるか
+    pub fn preview_provider_command(&self, provider: &str, command: &str) -> Option<ConfirmableAction> {
+        self.registry.all_keyword_providers().find(|p| p.name() == provider)?.preview(command)
+    }
+    
+    /// バックグラウンドで取得していた結果（時間切れになった検索元を含む）が届いたか（届いていれば検索し直す）
+    pub fn take_provider_updates(&self) -> bool {
+        // すべてのプロバイダーのフラグをリセットするため any は使わない
+        let sources = self.sources.take_updated();
+        self.registry.all_keyword_providers().fold(sources, |updated, p| p.take_updated() || updated)
+    }
+    
+    /// バックグラウンドで取得中の結果があるか
+    pub fn has_pending_providers(&self) -> bool {
+        self.sources.is_pending() || self.registry.all_keyword_providers().any(|p| p.is_pending())
+    }
+    
+    /// キーワードの検索結果（無効な提供元のキーワードは `parse_query` で解析されない）
+    fn search_keyword(&self, keyword: &str, args: &str, mode: SearchMode, windows: &[WindowInfo]) -> Vec<SearchResult> {
+        match keyword {
+            BOOKMARK_ADD_KEYWORD => bookmark_add_results(&self.get_tab_manager().snapshot(), bookmark_folder(args)),
+            NOTES_KEYWORD => note_results(args, &self.note_store),
+            TIMER_KEYWORD => timer_results(TimerKind::Timer, args, &self.timer_store, Local::now()),
+            REMIND_KEYWORD => timer_results(TimerKind::Reminder, args, &self.timer_store, Local::now()),
+            LAYOUT_KEYWORD => layout_results(args, &self.layout_manager),
+            TAG_KEYWORD => tag_results(args, windows, &self.detected_profiles(), &self.workspaces()),
+            NEW_TAB_KEYWORD => new_tab_results(args, &self.new_tab, &self.detected_profiles()),
+            MACRO_KEYWORD => macro_results(args, &self.macros),
+            CLOSED_KEYWORD => closed_tab_results(&self.get_tab_manager().recently_closed(), args, usize::MAX),
+            // 本文がなければ最近のメモを表示
+            NOTE_KEYWORD if args.is_empty() => note_results("", &self.note_store),
+            NOTE_KEYWORD => vec![append_note_result(args, &self.note_store)],
+            // コマンドや HTTP を待つプロバイダーもあるので、組み込みの検索元と同じく時間を決めて待つ
+            _ => match self.registry.keyword_provider(mode, keyword) {
+                Some(provider) => {
+                    let name = provider.name();
+                    match self.sources.start_keyword(provider, keyword, args).wait() {
+                        SourceResult::Ready(results) => results,
+                        SourceResult::TimedOut => vec![timed_out_result(name, args)],
+                    }
+                }
+                None => Vec::new(),
+            },
+        }
+    }
+    
+    /// クイックメモの保存先を差し替える
+    pub fn with_note_store(mut self, note_store: NoteStore) -> Self {
+        self.note_store = note_store;
+        self
+    }
+    
+    pub fn note_store(&self) -> &NoteStore {
+        &self.note_store
+    }
+    
+    pub fn timer_store(&self) -> &TimerStore {
+        &self.timer_store
+    }
+    
+    /// ウィンドウ配置の保存先と設定を差し替える
+    pub fn with_layout_manager(mut self, layout_manager: LayoutManager) -> Self {
+        self.layout_manager = layout_manager;
+        self
+    }
+    
+    pub fn layout_manager(&self) -> &LayoutManager {
+        &self.layout_manager
+    }
+    
+    /// 前回のセッションを設定（`restore previous session` で起動し直す候補）
+    pub fn with_previous_session(mut self, session: Option<Session>) -> Self {
+        self.previous_session = session;
+        self
+    }
+    
+    pub fn previous_session(&self) -> Option<&Session> {
+        self.previous_session.as_ref()
+    }
+    
+    /// Browserモードの結果の絞り込みを設定（次の検索から反映）
+    pub fn set_filter(&self, filter: ResultFilter) {
+        *self.filter.lock().unwrap() = filter;
+    }
+    
+    /// これまでの検索で見つかったプロファイル（`ブラウザ - プロファイル`）
+    pub fn detected_profiles(&self) -> Vec<String> {
+        self.detected_profiles.lock().unwrap().iter().cloned().collect()
+    }
+    
+    /// 検出したプロファイルを記録し、絞り込みとワークスペースで除外されないかを返す
+    fn accept_profile(&self, filter: &ResultFilter, workspace: Option<&TagFilter>, profile: Option<&str>) -> bool {
+        if let Some(profile) = profile {
+            self.detected_profiles.lock().unwrap().insert(profile.to_string());
+        }
+        filter.allows_profile(profile) && workspace.is_none_or(|workspace| workspace.matches_profile(profile))
+    }
+    
+    /// ワークスペースのタグの保存先を差し替える
+    pub fn with_workspace_store(mut self, workspace_store: WorkspaceStore) -> Self {
+        self.workspace_store = workspace_store;
+        self
+    }
+    
+    pub fn workspace_store(&self) -> &WorkspaceStore {
+        &self.workspace_store
+    }
+    
+    /// 保存したタグのルールと選んでいるワークスペース（読み込めなければ空）
+    pub fn workspaces(&self) -> Workspaces {
+        self.workspace_store.load().unwrap_or_else(|e| {
+            log::warn!("Failed to load workspaces from {}: {}", self.workspace_store.path().display(), e);
+            Workspaces::default()
+        })
+    }
+    
+    /// 開いた結果の記録先を差し替える
+    pub fn with_usage_store(mut self, usage_store: UsageStore) -> Self {
+        self.usage_store = usage_store;
+        self
+    }
+    
+    pub fn usage_store(&self) -> &UsageStore {
+        &self.usage_store
+    }
+    
+    /// 「Clear caches」で表示・消去するキャッシュを差し替える
+    pub fn with_disk_caches(mut self, disk_caches: Vec<(&'static str, DiskCache)>) -> Self {
+        self.disk_caches = disk_caches;
+        self
+    }
+    
+    pub fn disk_cache_usage(&self) -> Vec<(&'static str, CacheUsage)> {
+        self.disk_caches.iter().map(|(name, cache)| (*name, cache.usage())).collect()
+    }
+    
+    /// すべてのディスクキャッシュを消して、消したバイト数を返す
+    pub fn clear_disk_caches(&self) -> u64 {
+        let mut freed = 0;
+        for (name, cache) in &self.disk_caches {
+            match cache.clear() {
+                Ok(bytes) => freed += bytes,
+                Err(e) => log::warn!("Failed to clear the {} cache in {}: {}", name, cache.dir().display(), e),
+            }
+        }
+        log::info!("Cleared {} of cached images", disk_cache::format_size(freed));
+        freed
+    }
+    
+    /// 検索語の記録先を差し替える
+    pub fn with_recent_queries(mut self, recent_queries: RecentQueryStore) -> Self {
+        self.recent_queries = recent_queries;
+        self
+    }
+    
+    pub fn recent_queries_store(&self) -> &RecentQueryStore {
+        &self.recent_queries
+    }
+    
+    /// 検索語が空のときに表示するセクションを差し替える
+    pub fn with_empty_state(mut self, empty_state: EmptyStateConfig) -> Self {
+        self.empty_state = empty_state;
+        self
+    }
+    
+    /// 検索語が空のときに表示する内容
+    pub fn empty_state(&self, mode: SearchMode) -> EmptyState {
+        EmptyState::build(mode, self.empty_state.sections(mode), self.empty_state.max_items, self)
+    }
+    
+    /// 時間帯の提案に使う記録（提案が無効なら空）
+    fn usage_entries(&self) -> Vec<UsageEntry> {
+        if !self.ranking.smart_suggestions {
+            return Vec::new();
+        }
+        self.usage_store.load().unwrap_or_else(|e| {
+            log::warn!("Failed to load usage from {}: {}", self.usage_store.path().display(), e);
+            Vec::new()
+        })
+    }
+    
+    /// Browserモードの結果の並び順と重みを差し替える
+    pub fn with_ranking(mut self, ranking: RankingConfig) -> Self {
+        self.ranking = ranking;
+        self
+    }
+    
+    /// `nt` キーワードのプロファイルと検索 URL を設定
+    pub fn with_new_tab(mut self, new_tab: NewTabConfig) -> Self {
+        self.new_tab = new_tab;
+        self
+    }
+    
+    /// 設定したマクロを登録
+    pub fn with_macros(mut self, macros: Vec<MacroConfig>) -> Self {
+        self.macros = macros;
+        self
+    }
+    
+    /// 名前が一致するマクロ
+    pub fn find_macro(&self, name: &str) -> Option<&MacroConfig> {
+        self.macros.iter().find(|config| config.name == name)
+    }
+    
+    /// 検索元の待ち時間の上限を設定
+    pub fn with_sources(mut self, config: &SourcesConfig) -> Self {
+        self.sources = Sources::new(config);
+        self
+    }
+
+    /// 検索元の状態を書き込む先（UI と共有する）
+    pub fn with_health(mut self, health: HealthRegistry) -> Self {
+        self.health = health;
+        self
+    }
+
+    pub fn health(&self) -> &HealthRegistry {
+        &self.health
+    }
+
+    /// 検索を待たずに分かる状態（無効にした検索元、ウィンドウとタブの一覧）を書き込む
+    fn report_health(&self, windows: &[WindowInfo]) {
+        let disabled = |name: &str| !self.registry.is_enabled(name);
+        for name in [BOOKMARKS_PROVIDER, HISTORY_PROVIDER] {
+            if disabled(name) {
+                self.health.report(name, HealthState::Disabled);
+            }
+        }
+        if self.privacy.is_active() {
+            self.health.report(HISTORY_PROVIDER, HealthState::Disabled);
+        }
+
+        self.health.report(WINDOWS_PROVIDER, if disabled(WINDOWS_PROVIDER) {
+            HealthState::Disabled
+        } else if windows.is_empty() {
+            HealthState::Degraded("No windows were enumerated".to_string())
+        } else {
+            HealthState::Healthy
+        });
+
+        let tab_manager = self.get_tab_manager();
+        self.health.report(TABS_PROVIDER, if disabled(TABS_PROVIDER) {
+            HealthState::Disabled
+        } else if !tab_manager.is_extension_connected() {
+            HealthState::Degraded("Chrome extension is not connected".to_string())
+        } else {
+            match tab_manager.freshness() {
+                TabFreshness::Fresh => HealthState::Healthy,
+                TabFreshness::Stale => HealthState::Degraded("Extension has not sent tabs recently".to_string()),
+                TabFreshness::Expired => HealthState::Degraded("Tab list expired; waiting for the extension".to_string()),
             }
+        });
+    }
+
+    /// 時間を決めて検索した結果から検索元の状態を書き込む
+    fn report_source<T, E: std::fmt::Display>(&self, name: &str, result: Option<&SourceResult<Result<T, E>>>) {
+        match result {
+            Some(SourceResult::Ready(Ok(_))) => self.health.report(name, HealthState::Healthy),
+            Some(SourceResult::Ready(Err(e))) => self.health.report(name, HealthState::Degraded(e.to_string())),
+            Some(SourceResult::TimedOut) => self.health.report(name, HealthState::Degraded("Search timed out".to_string())),
+            None => {}
+        }
+    }
+
+    /// 遅い検索が続いて抑えている検索元を書き込む（無効にした検索元と、結果を減らしている間のエラーは上書きしない）
+    fn report_throttle<T: Send + 'static>(&self, name: &str, source: &TimedSource<T>) {
+        let throttle = source.throttle();
+        let Some(reason) = throttle.describe(Instant::now()) else {
+            return;
         };
-        
-        let cached_provider = CachedBrowserProvider::new(chrome_provider);
-        let tab_provider = Arc::new(ChromeTabProvider::new_with_tab_manager(tab_manager));
-        
-        Self {
-            browser_provider: Arc::new(Mutex::new(cached_provider)),
-            tab_provider,
+        let replace = match self.health.get(name) {
+            Some(HealthState::Disabled) => false,
+            Some(HealthState::Degraded(_)) => matches!(throttle, Throttle::Paused { .. }),
+            _ => true,
+        };
+        if replace {
+            self.health.report(name, HealthState::Throttled(reason));
         }
     }
+
+    /// 遅い検索元を抑えている間、入力の待ち時間に足す時間
+    pub fn extra_debounce(&self) -> Duration {
+        self.sources.extra_debounce()
+    }
     
+    /// 設定を反映し、組み込みのキーワードプロバイダーを登録する（ランチャーとデーモンで共通）
+    pub fn with_config(self, config: &LauncherConfig) -> Self {
+        let mut engine = self
+            .with_note_store(NoteStore::new(config.notes.path()))
+            .with_layout_manager(LayoutManager::default().with_relaunch_missing(config.layouts.relaunch_missing))
+            .with_ranking(config.ranking.clone())
+            .with_new_tab(config.new_tab.clone())
+            .with_macros(config.macros.clone())
+            .with_empty_state(config.empty_state.clone())
+            .with_sources(&config.sources)
+            .with_privacy(&config.privacy)
+            .with_disk_caches(disk_cache::launcher_caches(&config.previews))
+            .with_browser_index(BrowserIndexStore::default());
+        engine.register_provider(Box::new(crate::lookup::LookupProvider::new(config.lookup.clone())));
+        engine.register_provider(Box::new(crate::color::ColorProvider));
+        engine.register_provider(Box::new(crate::utility::UtilityProvider));
+        engine.register_provider(Box::new(crate::date_calc::DateTimeProvider));
+        if config.docker.enabled {
+            engine.register_provider(Box::new(crate::system::docker::DockerProvider::new(config.docker.pipe.clone())));
+        }
+        engine.providers().set_disabled(&config.providers.disabled);
+        engine
+    }
+    
+    /// プライバシーモードの設定と、読み込まないプロファイル
+    pub fn with_privacy(mut self, config: &PrivacyConfig) -> Self {
+        if let Ok(mut provider) = self.browser_provider.lock() {
+            provider.set_private_profiles(config.private_profiles.clone());
+        }
+        self.privacy = PrivacyMode::new(config);
+        self
+    }
+
+    /// プライバシーモードの状態（UI のヘッダーで切り替える）
+    pub fn privacy(&self) -> &PrivacyMode {
+        &self.privacy
+    }
+
+    /// プライバシーモードが有効か。有効になったときは記録した検索語を消す
+    pub fn update_privacy(&self) -> bool {
+        if self.privacy.take_activated() {
+            log::info!("Privacy mode enabled; clearing recent queries");
+            if let Err(e) = self.recent_queries.clear() {
+                log::warn!("Failed to clear {}: {}", self.recent_queries.path().display(), e);
+            }
+        }
+        self.privacy.is_active()
+    }
+
+    /// 前回保存した索引を読み込み、`save_browser_index` の保存先にする
+    pub fn with_browser_index(mut self, store: BrowserIndexStore) -> Self {
+        if let Ok(provider) = self.browser_provider.lock() {
+            provider.load_index(&store);
+        }
+        self.browser_index = Some(store);
+        self
+    }
+
+    /// ブックマーク・履歴の索引を保存する（ランチャーの終了時）
+    pub fn save_browser_index(&self) {
+        let (Some(store), Ok(provider)) = (&self.browser_index, self.browser_provider.lock()) else {
+            return;
+        };
+        if let Err(e) = provider.save_index(store) {
+            log::warn!("Failed to save browser index to {}: {}", store.path().display(), e);
+        }
+    }
+
     pub fn refresh_browser_data(&self) {
         if let Ok(mut provider) = self.browser_provider.lock() {
             provider.refresh();
@@ -61,192 +703,1013 @@ impl BrowserSearchEngine {
     pub fn get_tab_manager(&self) -> Arc<crate::core::TabManager> {
         self.tab_provider.get_tab_manager()
     }
+    
+    /// 拡張機能に依頼したブックマークの変更を、Chromeのファイルに反映される前から検索結果に反映する
+    pub fn record_bookmark_edit(&self, url: &str, edit: BookmarkEdit) {
+        if let Ok(provider) = self.browser_provider.lock() {
+            provider.record_edit(url, edit);
+        }
+    }
+    
+    /// General モード: 接頭辞で振り分けて、それぞれのモードの検索を使う
+    fn search_general(&self, parsed: &Query, windows: &[WindowInfo]) -> Vec<SearchResult> {
+        match route_general(&parsed.raw) {
+            GeneralRoute::Empty => Vec::new(),
+            GeneralRoute::Windows(text) => self.search(text, SearchMode::Windows, windows),
+            GeneralRoute::Command("") => Vec::new(),
+            GeneralRoute::Command(command) => vec![run_command_result(command)],
+            GeneralRoute::Url(url) => {
+                let mut results = vec![open_url_result(&url)];
+                results.extend(self.search_parsed(parsed, SearchMode::Browser, windows));
+                results
+            }
+            GeneralRoute::Web(_) => self.search_parsed(parsed, SearchMode::Browser, windows),
+        }
+    }
+    
+    /// 開いているタブのみを検索
+    fn search_tabs_only(&self, query: &str) -> Vec<SearchResult> {
+        if !self.registry.is_enabled(TABS_PROVIDER) {
+            return Vec::new();
+        }
+        let tabs = self.tab_provider
+            .search_tabs(query.trim())
+            .into_iter()
+            .map(|item| item.tab)
+            .collect();
+        
+        let mut results = Vec::new();
+        if matches_builtin_command(query, CLOSE_DUPLICATE_TABS) {
+            let all_tabs = self.get_tab_manager().snapshot();
+            results.push(close_duplicate_tabs_result(&all_tabs));
+        }
+        if matches_builtin_command(query, OPEN_TABS_OVERVIEW) {
+            results.push(tabs_overview_result(&self.get_tab_manager().snapshot()));
+        }
+        let mut tabs = tab_results(tabs);
+        if self.get_tab_manager().freshness() == TabFreshness::Stale {
+            mark_stale(&mut tabs);
+        }
+        results.extend(tabs);
+        results
+    }
+}
+
+/// 拡張機能からしばらく更新がないタブの結果に印を付ける
+fn mark_stale(results: &mut [SearchResult]) {
+    for result in results {
+        if let Some(ResultMetadata::Tab { stale, .. }) = &mut result.metadata {
+            *stale = true;
+        }
+    }
+}
+
+/// `note <text>` の検索結果（メモを追記する）
+fn append_note_result(text: &str, note_store: &NoteStore) -> SearchResult {
+    SearchResult {
+        title: format!("Add note: {}", text),
+        description: format!("Append to {}", note_store.path().display()),
+        action: Action::AppendNote(text.to_string()),
+        window_info: None,
+        result_type: ResultType::Note,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// `notes [query]` の検索結果（保存済みのメモ）
+fn note_results(query: &str, note_store: &NoteStore) -> Vec<SearchResult> {
+    match note_store.search(query) {
+        Ok(entries) => entries
+            .into_iter()
+            .map(|entry| SearchResult {
+                title: entry.text,
+                description: if entry.timestamp.is_empty() {
+                    format!("Line {}", entry.line + 1)
+                } else {
+                    entry.timestamp
+                },
+                action: Action::OpenNote { line: entry.line },
+                window_info: None,
+                result_type: ResultType::Note,
+                group: None,
+                metadata: None,
+            })
+            .collect(),
+        Err(e) => {
+            log::error!("Failed to search notes: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// `timer` / `remind` の検索結果（新しいタイマーと、設定中のタイマーの取り消し）
+fn timer_results(kind: TimerKind, args: &str, timer_store: &TimerStore, now: DateTime<Local>) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    
+    if let Some(request) = timers::parse_timer_request(kind, args, now) {
+        results.push(SearchResult {
+            title: format!("{}: {}", kind.label(), request.label),
+            description: format!("Notifies at {}", request.due.format("%H:%M")),
+            action: Action::StartTimer(request),
+            window_info: None,
+            result_type: ResultType::Timer,
+            group: None,
+            metadata: None,
+        });
+    }
+    
+    match timer_store.load() {
+        Ok(active) => results.extend(active.into_iter().map(|timer| SearchResult {
+            title: format!("Cancel {}: {}", timer.kind.label().to_lowercase(), timer.label),
+            description: format!(
+                "{} (at {})",
+                timers::format_remaining(&timer, now),
+                timer.due_local().format("%H:%M")
+            ),
+            action: Action::CancelTimer(timer.id),
+            window_info: None,
+            result_type: ResultType::Timer,
+            group: None,
+            metadata: None,
+        })),
+        Err(e) => log::error!("Failed to load timers: {}", e),
+    }
+    
+    results
+}
+
+/// `layout` の検索結果（保存・削除、保存済みの配置の復元）
+fn layout_results(args: &str, layout_manager: &LayoutManager) -> Vec<SearchResult> {
+    if let Some(name) = strip_keyword(args, "save") {
+        if name.is_empty() {
+            return Vec::new();
+        }
+        return vec![SearchResult {
+            title: format!("Save layout: {}", name),
+            description: "Save the position of every window (overwrites a layout with the same name)".to_string(),
+            action: Action::SaveLayout(name.to_string()),
+            window_info: None,
+            result_type: ResultType::Command,
+            group: None,
+            metadata: None,
+        }];
+    }
+    
+    let (query, delete) = match strip_keyword(args, "delete") {
+        Some(name) => (name, true),
+        None => (args, false),
+    };
+    
+    let layouts = match layout_manager.load_all() {
+        Ok(layouts) => layouts,
+        Err(e) => {
+            log::error!("Failed to load layouts: {}", e);
+            return Vec::new();
+        }
+    };
+    
+    let query = query.to_lowercase();
+    layouts
+        .into_iter()
+        .filter(|layout| layout.name.to_lowercase().contains(&query))
+        .map(|layout| {
+            let saved_at = Utc
+                .timestamp_opt(layout.saved_at, 0)
+                .single()
+                .map(time_format::relative)
+                .unwrap_or_default();
+            let description = format!("{} windows, saved {}", layout.windows.len(), saved_at);
+            if delete {
+                SearchResult {
+                    title: format!("Delete layout: {}", layout.name),
+                    description,
+                    action: Action::DeleteLayout(layout.name),
+                    window_info: None,
+                    result_type: ResultType::Command,
+                    group: None,
+                    metadata: None,
+                }
+            } else {
+                SearchResult {
+                    title: format!("Restore layout: {}", layout.name),
+                    description,
+                    action: Action::RestoreLayout(layout.name),
+                    window_info: None,
+                    result_type: ResultType::Command,
+                    group: None,
+                    metadata: None,
+                }
+            }
+        })
+        .collect()
+}
+
+/// `tag` の検索結果
+///
+/// `tag <ワークスペース> <検索語>` は検索語に合うウィンドウ・プロファイルにタグを付ける候補を出す。
+/// タイトルに検索語を含むウィンドウはプロセス名とその検索語で、それ以外はプロセス名だけで対象を決める。
+/// `tag [ワークスペース]` は付けたタグを一覧し、選ぶと削除する。
+fn tag_results(args: &str, windows: &[WindowInfo], profiles: &[String], workspaces: &Workspaces) -> Vec<SearchResult> {
+    let (tag, query) = match args.split_once(char::is_whitespace) {
+        Some((tag, query)) => (tag, query.trim()),
+        None => (args, ""),
+    };
+    
+    if query.is_empty() {
+        let tag = tag.to_lowercase();
+        return workspaces
+            .rules
+            .iter()
+            .filter(|rule| rule.tag.to_lowercase().starts_with(&tag))
+            .map(|rule| SearchResult {
+                title: format!("Remove tag {}: {}", rule.tag, rule.target.label()),
+                description: "Remove this rule from the workspace".to_string(),
+                action: Action::RemoveTag(rule.clone()),
+                window_info: None,
+                result_type: ResultType::Command,
+                group: None,
+                metadata: None,
+            })
+            .collect();
+    }
+    
+    let query_lower = query.to_lowercase();
+    let mut rules: Vec<TagRule> = Vec::new();
+    for window in windows.iter().filter(|window| window.contains_text(query)) {
+        let title = window.title.to_lowercase().contains(&query_lower).then(|| query.to_string());
+        let rule = TagRule {
+            tag: tag.to_string(),
+            target: TagTarget::Window { process_name: window.process_name.clone(), title },
+        };
+        if !rules.contains(&rule) {
+            rules.push(rule);
+        }
+    }
+    for profile in profiles.iter().filter(|profile| profile.to_lowercase().contains(&query_lower)) {
+        rules.push(TagRule {
+            tag: tag.to_string(),
+            target: TagTarget::Profile { profile: profile.clone() },
+        });
+    }
+    
+    rules
+        .into_iter()
+        .map(|rule| {
+            let title = match &rule.target {
+                TagTarget::Window { process_name, title: Some(title) } => {
+                    format!("Tag {} windows containing \"{}\" as {}", process_name, title, rule.tag)
+                }
+                TagTarget::Window { process_name, title: None } => format!("Tag all {} windows as {}", process_name, rule.tag),
+                TagTarget::Profile { profile } => format!("Tag profile {} as {}", profile, rule.tag),
+            };
+            let description = if workspaces.rules.contains(&rule) {
+                "Already tagged".to_string()
+            } else {
+                format!("Select {} in Windows mode (Ctrl+1..9) to show only its windows and profiles", rule.tag)
+            };
+            SearchResult {
+                title,
+                description,
+                action: Action::AssignTag(rule),
+                window_info: None,
+                result_type: ResultType::Command,
+                group: None,
+                metadata: None,
+            }
+        })
+        .collect()
+}
+
+/// `nt [サブキーワード] <検索語>` の結果（開くプロファイルごとに1行）
+///
+/// サブキーワードが設定の `profiles` にあればそのプロファイル、なければ `default_profile`、
+/// それもなければ検出したプロファイルを並べる。検索語が URL ならそのまま開く。
+fn new_tab_results(args: &str, config: &NewTabConfig, detected_profiles: &[String]) -> Vec<SearchResult> {
+    let args = args.trim();
+    let (profiles, query) = match args.split_once(char::is_whitespace) {
+        Some((sub_keyword, rest)) => match config.profiles.iter().find(|(key, _)| key.eq_ignore_ascii_case(sub_keyword)) {
+            Some((_, profile)) => (vec![profile.clone()], rest.trim()),
+            None => (Vec::new(), args),
+        },
+        None => (Vec::new(), args),
+    };
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let profiles = match (profiles.is_empty(), &config.default_profile) {
+        (false, _) => profiles,
+        (true, Some(default)) => vec![default.clone()],
+        (true, None) => detected_profiles.to_vec(),
+    };
+
+    let url = if query.starts_with("http://") || query.starts_with("https://") {
+        query.to_string()
+    } else {
+        config.search_url.replace("{query}", &urlencoding::encode(query))
+    };
+    if profiles.is_empty() {
+        // プロファイルが分からなければ既定のブラウザで開く
+        return vec![SearchResult {
+            title: format!("New tab: {}", query),
+            description: url.clone(),
+            action: Action::OpenUrl(url),
+            window_info: None,
+            result_type: ResultType::Command,
+            group: None,
+            metadata: None,
+        }];
+    }
+    profiles
+        .into_iter()
+        .map(|profile| SearchResult {
+            title: format!("New tab in {}: {}", profile, query),
+            description: url.clone(),
+            action: Action::OpenInProfile { profile, url: url.clone() },
+            window_info: None,
+            result_type: ResultType::Command,
+            group: None,
+            metadata: None,
+        })
+        .collect()
+}
+
+/// `macro [名前]` の結果（名前の単語の先頭に一致するマクロ。書式に誤りがあれば説明に表示する）
+fn macro_results(args: &str, macros: &[MacroConfig]) -> Vec<SearchResult> {
+    macros
+        .iter()
+        .filter(|config| args.trim().is_empty() || matches_command(args, &config.name))
+        .map(|config| SearchResult {
+            title: format!("Run macro: {}", config.name),
+            description: match crate::macros::parse_macro(config) {
+                Ok(_) => config.steps.join(" → "),
+                Err(e) => format!("Invalid macro ({})", e),
+            },
+            action: Action::RunMacro(config.name.clone()),
+            window_info: None,
+            result_type: ResultType::Command,
+            group: None,
+            metadata: None,
+        })
+        .collect()
+}
+
+/// `ブラウザ - プロファイル` の表記（どちらかが不明なら `None`）
+fn profile_label(browser: Option<&str>, profile: Option<&str>) -> Option<String> {
+    Some(format!("{} - {}", browser?, profile?))
+}
+
+/// ブックマーク・履歴のタイトルとグループ
+///
+/// プロファイルごとにまとめる場合はプロファイルを見出しにし、
+/// まとめない場合はタイトルの先頭に `[ブラウザ - プロファイル]` を付ける。
+fn profile_title(title: &str, profile: Option<String>, group_by_profile: bool) -> (String, Option<String>) {
+    match profile {
+        Some(profile) if group_by_profile => (title.to_string(), Some(profile)),
+        Some(profile) => (format!("[{}] {}", profile, title), None),
+        None => (title.to_string(), None),
+    }
+}
+
+/// 組み込みコマンドの名前にクエリが一致するか
+///
+/// クエリの各単語がコマンド名のいずれかの単語の先頭に一致すればよい（例: `close dup`）。
+pub fn matches_command(query: &str, command_name: &str) -> bool {
+    let name_words: Vec<String> = command_name.split_whitespace().map(|w| w.to_lowercase()).collect();
+    let query_words: Vec<String> = query.split_whitespace().map(|w| w.to_lowercase()).collect();
+    
+    !query_words.is_empty()
+        && query_words.iter().all(|q| name_words.iter().any(|w| w.starts_with(q.as_str())))
+}
+
+/// キーワードなしの検索で組み込みコマンドを出すのに必要な入力の文字数（空白を除く）
+const MIN_COMMAND_QUERY_CHARS: usize = 3;
+
+/// キーワードなしの検索で組み込みコマンドの名前にクエリが一致するか
+///
+/// 1〜2文字の入力でいつもコマンドが並ばないよう、`MIN_COMMAND_QUERY_CHARS` 文字から一致させる。
+/// キーワードの引数での絞り込み（`wsl u` など）は短くてもよいので `matches_command` を使う。
+pub fn matches_builtin_command(query: &str, command_name: &str) -> bool {
+    query.chars().filter(|c| !c.is_whitespace()).count() >= MIN_COMMAND_QUERY_CHARS && matches_command(query, command_name)
+}
+
+/// 「重複したタブを閉じる」コマンドの検索結果
+fn close_duplicate_tabs_result(tabs: &[ChromeTab]) -> SearchResult {
+    let count = crate::core::tab_dedup::find_duplicate_tabs(tabs).len();
+    SearchResult {
+        title: "Close duplicate tabs".to_string(),
+        description: match count {
+            0 => "No duplicate tabs are open".to_string(),
+            1 => "1 duplicate tab will be closed".to_string(),
+            n => format!("{} duplicate tabs will be closed", n),
+        },
+        action: Action::CloseDuplicateTabs,
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// 「開いているタブの一覧」コマンドの検索結果
+fn tabs_overview_result(tabs: &[ChromeTab]) -> SearchResult {
+    let windows: BTreeSet<i32> = tabs.iter().map(|tab| tab.window_id).collect();
+    SearchResult {
+        title: "Open tabs overview".to_string(),
+        description: format!("{} tabs in {} windows, grouped by window or domain", tabs.len(), windows.len()),
+        action: Action::OpenTabsOverview,
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// 「キャッシュを消す」コマンドの検索結果（説明に今の使用量を出す）
+fn clear_caches_result(usage: &[(&str, CacheUsage)]) -> SearchResult {
+    let used: Vec<String> = usage
+        .iter()
+        .filter(|(_, usage)| usage.files > 0)
+        .map(|(name, usage)| format!("{} {} ({} files)", name, disk_cache::format_size(usage.bytes), usage.files))
+        .collect();
+    SearchResult {
+        title: "Clear caches".to_string(),
+        description: if used.is_empty() {
+            "Image caches are empty".to_string()
+        } else {
+            format!("Delete cached images: {}", used.join(", "))
+        },
+        action: Action::ClearCaches,
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// 今の時間帯によく開くウィンドウとページの提案（開いていないウィンドウは出さない）
+fn time_suggestions(entries: &[UsageEntry], windows: &[WindowInfo], now: DateTime<Local>) -> Vec<SearchResult> {
+    let mut suggested_windows = Vec::new();
+    let mut scored: Vec<(f32, &UsageEntry)> = entries
+        .iter()
+        .map(|entry| (time_of_day_score(&entry.opens, now), entry))
+        .filter(|(score, _)| *score >= SUGGESTION_MIN_SCORE)
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    scored
+        .into_iter()
+        .filter_map(|(_, entry)| match &entry.target {
+            UsageTarget::Window(fingerprint) => {
+                // 同じウィンドウに解決する記録が複数あっても1度だけ出す
+                let window = fingerprint.resolve(windows).filter(|window| !suggested_windows.contains(&window.hwnd))?;
+                suggested_windows.push(window.hwnd);
+                Some(SearchResult {
+                    title: window.title.clone(),
+                    description: window.process_name.clone(),
+                    action: Action::SwitchWindow(window.hwnd),
+                    window_info: Some(window.clone()),
+                    result_type: ResultType::Window,
+                    group: Some(SUGGESTIONS_GROUP.to_string()),
+                    metadata: None,
+                })
+            }
+            UsageTarget::Url { url } => Some(SearchResult {
+                title: entry.title.clone(),
+                description: url.clone(),
+                action: Action::OpenUrl(url.clone()),
+                window_info: None,
+                result_type: ResultType::History,
+                group: Some(SUGGESTIONS_GROUP.to_string()),
+                metadata: None,
+            }),
+        })
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+/// 「前回のセッションを復元」コマンドの検索結果
+fn restore_session_result(previous: &Session, windows: &[WindowInfo]) -> SearchResult {
+    let missing = session::missing_apps(previous, windows);
+    let description = if missing.is_empty() {
+        "Every app from the previous session is running".to_string()
+    } else {
+        let mut names: Vec<&str> = missing.iter().map(|app| app.process_name.trim_end_matches(".exe")).collect();
+        names.dedup();
+        format!("Relaunch {} app(s): {}", missing.len(), names.join(", "))
+    };
+    SearchResult {
+        title: "Restore previous session".to_string(),
+        description,
+        action: Action::RestoreSession,
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// タブの結果の種類（ピン留めしたタブは別の種類にして上に並べる）
+fn tab_result_type(tab: &ChromeTab) -> ResultType {
+    if tab.pinned {
+        ResultType::PinnedTab
+    } else {
+        ResultType::Tab
+    }
+}
+
+/// タブをウィンドウごとにまとめて検索結果にする
+///
+/// ウィンドウは最初に現れた順に「Window 1」「Window 2」…と番号を付け、
+/// ウィンドウ内ではタブの並び順を保つ。アクティブなタブには印を付ける。
+pub fn tab_results(mut tabs: Vec<ChromeTab>) -> Vec<SearchResult> {
+    let mut window_order: Vec<i32> = Vec::new();
+    for tab in &tabs {
+        if !window_order.contains(&tab.window_id) {
+            window_order.push(tab.window_id);
+        }
+    }
+    let window_number = |window_id: i32| window_order.iter().position(|id| *id == window_id).unwrap_or(0) + 1;
+    
+    tabs.sort_by_key(|tab| (window_number(tab.window_id), tab.index));
+    
+    tabs.into_iter()
+        .map(|tab| {
+            let title = if tab.title.is_empty() {
+                tab.url.clone()
+            } else {
+                tab.title.clone()
+            };
+            let title = if tab.active {
+                format!("● {}", title)
+            } else {
+                title
+            };
+            
+            let description = format!("Window {} · {}", window_number(tab.window_id), tab.url);
+            
+            SearchResult {
+                title,
+                description,
+                action: Action::SwitchToTab {
+                    tab_id: tab.id,
+                    window_id: tab.window_id,
+                },
+                window_info: None,
+                result_type: tab_result_type(&tab),
+                group: None,
+                metadata: Some(ResultMetadata::Tab {
+                    url: tab.url.clone(),
+                    active: tab.active,
+                    window_number: Some(window_number(tab.window_id)),
+                    stale: false,
+                }),
+            }
+        })
+        .collect()
+}
+
+impl EmptyStateSource for BrowserSearchEngine {
+    fn recent_queries(&self) -> Vec<String> {
+        self.recent_queries.load().unwrap_or_else(|e| {
+            log::warn!("Failed to load recent queries from {}: {}", self.recent_queries.path().display(), e);
+            Vec::new()
+        })
+    }
+    
+    fn history(&self) -> Vec<HistoryItem> {
+        if !self.registry.serves(HISTORY_PROVIDER, SearchMode::Browser) || self.privacy.is_active() {
+            return Vec::new();
+        }
+        let history = match self.browser_provider.lock() {
+            Ok(provider) => provider.get_history(),
+            Err(_) => return Vec::new(),
+        };
+        history.unwrap_or_else(|e| {
+            log::warn!("Failed to load history for top sites: {}", e);
+            Vec::new()
+        })
+    }
+    
+    fn pinned(&self) -> Vec<(String, String)> {
+        self.empty_state.pinned.iter().map(|item| (item.title.clone(), item.url.clone())).collect()
+    }
 }
 
 impl SearchEngine for BrowserSearchEngine {
-    fn search(&self, query: &str, mode: SearchMode, windows: &[WindowInfo]) -> Vec<SearchResult> {
+    fn parse_query(&self, query: &str) -> Query {
+        Query::parse(query, &self.registry.keywords())
+    }
+    
+    fn search_parsed(&self, parsed: &Query, mode: SearchMode, windows: &[WindowInfo]) -> Vec<SearchResult> {
+        if mode == SearchMode::General {
+            return self.search_general(parsed, windows);
+        }
+        if let Some(results) = self.search_alternatives(parsed, mode, windows) {
+            return results;
+        }
+        let query = parsed.raw.as_str();
         let mut results = Vec::new();
+        self.report_health(windows);
+        self.title_history.observe(windows);
+        
+        if mode != SearchMode::Windows {
+            if let Some(keyword) = &parsed.keyword {
+                return self.search_keyword(&keyword.name, &keyword.args, mode, windows);
+            }
+            if let Some(results) = self.registry.keyword_providers(mode).find_map(|provider| provider.search_query(query)) {
+                return results;
+            }
+        }
         
         match mode {
+            SearchMode::Browser if query.starts_with(TAB_SEARCH_PREFIX) => {
+                results = self.search_tabs_only(&query[TAB_SEARCH_PREFIX.len()..]);
+            }
+            SearchMode::Tabs => {
+                results = self.search_tabs_only(query);
+            }
             SearchMode::Browser => {
                 if !query.is_empty() {
-                    // 1. Google検索を最初に追加
+                    // 並び順は最後に ranking の設定で決める
+                    // Google検索
                     results.push(SearchResult {
                         title: format!("Google: {}", query),
                         description: "Search on Google".to_string(),
                         action: Action::GoogleSearch(query.to_string()),
                         window_info: None,
                         result_type: ResultType::GoogleSearch,
+                        group: None,
+                        metadata: None,
                     });
                     
-                    // 2. ブックマークを検索
-                    if let Ok(provider) = self.browser_provider.lock() {
-                        match provider.search_bookmarks(query) {
-                            Ok(bookmarks) => {
-                                // すべてのブックマークを追加（UI側で表示制御）
-                                for bookmark in bookmarks {
-                                    // タイトルにブラウザとプロファイル情報を含める
-                                    let title = if let (Some(browser), Some(profile)) = (&bookmark.browser_name, &bookmark.profile_name) {
-                                        format!("[{} - {}] {}", browser, profile, bookmark.title)
-                                    } else {
-                                        bookmark.title.clone()
-                                    };
-                                    
-                                    let mut description_parts = vec![bookmark.url.clone()];
-                                    if let Some(folder) = &bookmark.folder {
-                                        description_parts.push(folder.clone());
-                                    }
-                                    
-                                    let description = description_parts.join(" | ");
-                                    
-                                    results.push(SearchResult {
-                                        title,
-                                        description,
-                                        action: Action::OpenBookmark(bookmark.url),
-                                        window_info: None,
-                                        result_type: ResultType::Bookmark,
-                                    });
+                    let filter = self.filter.lock().unwrap().clone();
+                    let workspace = TagFilter::active(&self.workspaces());
+                    
+                    // 演算子と末尾の `?` を除いた検索語でローカルを検索し、演算子は結果に適用する
+                    let local_query = parsed.text();
+                    let query = local_query.as_str();
+                    
+                    // 組み込みコマンド
+                    if matches_builtin_command(query, CLOSE_DUPLICATE_TABS) {
+                        let all_tabs = self.get_tab_manager().snapshot();
+                        results.push(close_duplicate_tabs_result(&all_tabs));
+                    }
+                    if matches_builtin_command(query, OPEN_TABS_OVERVIEW) {
+                        results.push(tabs_overview_result(&self.get_tab_manager().snapshot()));
+                    }
+                    if matches_builtin_command(query, CLEAR_CACHES) {
+                        results.push(clear_caches_result(&self.disk_cache_usage()));
+                    }
+                    
+                    // 検索元は別スレッドで同時に検索し、上限までに終わらなかったものは時間切れの行にする
+                    // （遅い検索が続いて止めている検索元は検索しない）
+                    let runs = |name: &str, source_throttle: Throttle| {
+                        self.registry.serves(name, mode) && !matches!(source_throttle, Throttle::Paused { .. })
+                    };
+                    let bookmarks = runs(BOOKMARKS_PROVIDER, self.sources.bookmarks.throttle()).then(|| {
+                        let provider = Arc::clone(&self.browser_provider);
+                        let text = query.to_string();
+                        self.sources.bookmarks.start(query, move || {
+                            let provider = provider.lock().map_err(|e| e.to_string())?;
+                            provider.search_bookmarks(&text).map_err(|e| e.to_string())
+                        })
+                    });
+                    let history = (runs(HISTORY_PROVIDER, self.sources.history.throttle()) && !self.privacy.is_active()).then(|| {
+                        let provider = Arc::clone(&self.browser_provider);
+                        let text = query.to_string();
+                        self.sources.history.start(query, move || {
+                            let provider = provider.lock().map_err(|e| e.to_string())?;
+                            provider.search_history(&text).map_err(|e| e.to_string())
+                        })
+                    });
+                    let tabs = runs(TABS_PROVIDER, self.sources.tabs.throttle()).then(|| {
+                        let tab_provider = Arc::clone(&self.tab_provider);
+                        let text = query.to_string();
+                        self.sources.tabs.start(query, move || tab_provider.search_tabs(&text))
+                    });
+                    
+                    // ブックマークを検索
+                    let bookmarks = bookmarks.map(PendingSource::wait);
+                    self.report_source(BOOKMARKS_PROVIDER, bookmarks.as_ref());
+                    self.report_throttle(BOOKMARKS_PROVIDER, &self.sources.bookmarks);
+                    match bookmarks {
+                        Some(SourceResult::Ready(Ok(bookmarks))) => {
+                            // すべてのブックマークを追加（UI側で表示制御、抑えている間は上限まで）
+                            for bookmark in bookmarks.into_iter().take(self.sources.bookmarks.result_cap()) {
+                                if !parsed.matches(&bookmark) {
+                                    continue;
                                 }
-                            }
-                            Err(e) => {
-                                log::error!("Failed to get bookmarks: {}", e);
+                                let profile = profile_label(bookmark.browser_name.as_deref(), bookmark.profile_name.as_deref());
+                                if !self.accept_profile(&filter, workspace.as_ref(), profile.as_deref()) {
+                                    continue;
+                                }
+                                let (title, group) = profile_title(&bookmark.title, profile, self.ranking.group_by_profile);
+                                let source = match (bookmark.id, bookmark.browser_name, bookmark.profile_name) {
+                                    (Some(id), Some(browser), Some(profile)) => Some(BookmarkSource { id, browser, profile }),
+                                    _ => None,
+                                };
+                                
+                                // 表示用の文字列は UI が metadata から作る（description は URL だけ）
+                                results.push(SearchResult {
+                                    title,
+                                    description: bookmark.url.clone(),
+                                    action: Action::OpenBookmark(bookmark.url.clone()),
+                                    window_info: None,
+                                    result_type: ResultType::Bookmark,
+                                    group,
+                                    metadata: Some(ResultMetadata::Bookmark { url: bookmark.url, folder: bookmark.folder, title: bookmark.title, source }),
+                                });
                             }
                         }
-                        
-                        // 3. 履歴を検索
-                        match provider.search_history(query) {
-                            Ok(history_items) => {
-                                // すべての履歴を追加（UI側で表示制御）
-                                for history in history_items {
-                                    // タイトルにブラウザとプロファイル情報を含める
-                                    let title = if let (Some(browser), Some(profile)) = (&history.browser_name, &history.profile_name) {
-                                        format!("[{} - {}] {}", browser, profile, history.title)
-                                    } else {
-                                        history.title.clone()
-                                    };
-                                    
-                                    // Webkit timestamp (microseconds since 1601-01-01) を日時に変換
-                                    let last_visit_str = {
-                                        use std::time::{SystemTime, UNIX_EPOCH};
-                                        // Webkit timestamp を Unix timestamp に変換
-                                        let webkit_epoch_diff = 11644473600_u64; // 1601-01-01 と 1970-01-01 の差（秒）
-                                        let unix_timestamp_secs = (history.last_visit_time / 1_000_000) as u64;
-                                        
-                                        if unix_timestamp_secs > webkit_epoch_diff {
-                                            let unix_secs = unix_timestamp_secs - webkit_epoch_diff;
-                                            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                                            
-                                            if unix_secs <= now {
-                                                let elapsed_secs = now - unix_secs;
-                                                if elapsed_secs < 60 {
-                                                    "just now".to_string()
-                                                } else if elapsed_secs < 3600 {
-                                                    format!("{} min ago", elapsed_secs / 60)
-                                                } else if elapsed_secs < 86400 {
-                                                    format!("{} hours ago", elapsed_secs / 3600)
-                                                } else if elapsed_secs < 604800 {
-                                                    format!("{} days ago", elapsed_secs / 86400)
-                                                } else {
-                                                    format!("{} weeks ago", elapsed_secs / 604800)
-                                                }
-                                            } else {
-                                                "future".to_string()
-                                            }
-                                        } else {
-                                            "unknown".to_string()
-                                        }
-                                    };
-                                    
-                                    let description = format!("{} (visited {} times, {})", 
-                                        history.url, 
-                                        history.visit_count,
-                                        last_visit_str
-                                    );
-                                    
-                                    results.push(SearchResult {
-                                        title,
-                                        description,
-                                        action: Action::OpenHistory(history.url),
-                                        window_info: None,
-                                        result_type: ResultType::History,
-                                    });
+                        Some(SourceResult::Ready(Err(e))) => {
+                            log::error!("Failed to get bookmarks: {}", e);
+                        }
+                        Some(SourceResult::TimedOut) => results.push(timed_out_result(self.sources.bookmarks.name(), query)),
+                        None => {}
+                    }
+                    
+                    // リーディングリスト（拡張機能から届いた一覧を検索する）
+                    if self.registry.serves(READING_LIST_PROVIDER, mode) {
+                        let reading_list = self.get_tab_manager().reading_list();
+                        results.extend(reading_list_results(
+                            reading_list.iter().filter(|entry| entry.matches_words(query) && parsed.matches(*entry)),
+                        ));
+                    }
+                    
+                    // 最近閉じたタブ（履歴と同じくプライバシーモードでは出さない）
+                    if self.registry.serves(RECENTLY_CLOSED_PROVIDER, mode) && !self.privacy.is_active() {
+                        let closed = self.get_tab_manager().recently_closed();
+                        let matching: Vec<_> = closed
+                            .iter()
+                            .filter(|tab| tab.matches_words(query) && parsed.matches(*tab) && parsed.allows_time(tab.closed_time()))
+                            .cloned()
+                            .collect();
+                        results.extend(closed_tab_results(&matching, "", MAX_CLOSED_TABS));
+                    }
+                    
+                    // 履歴を検索
+                    let history = history.map(PendingSource::wait);
+                    self.report_source(HISTORY_PROVIDER, history.as_ref());
+                    self.report_throttle(HISTORY_PROVIDER, &self.sources.history);
+                    match history {
+                        Some(SourceResult::Ready(Ok(history_items))) => {
+                            // すべての履歴を追加（UI側で表示制御、抑えている間は上限まで）
+                            for history in history_items.into_iter().take(self.sources.history.result_cap()) {
+                                if !parsed.matches(&history) || !parsed.allows_time(time_format::from_webkit(history.last_visit_time)) {
+                                    continue;
                                 }
-                            }
-                            Err(e) => {
-                                log::error!("Failed to get history: {}", e);
+                                let profile = profile_label(history.browser_name.as_deref(), history.profile_name.as_deref());
+                                if !self.accept_profile(&filter, workspace.as_ref(), profile.as_deref()) {
+                                    continue;
+                                }
+                                let (title, group) = profile_title(&history.title, profile, self.ranking.group_by_profile);
+                                
+                                
+                                results.push(SearchResult {
+                                    title,
+                                    description: history.url.clone(),
+                                    action: Action::OpenHistory(history.url.clone()),
+                                    window_info: None,
+                                    result_type: ResultType::History,
+                                    group,
+                                    metadata: Some(ResultMetadata::History {
+                                        url: history.url,
+                                        visit_count: history.visit_count,
+                                        last_visit: time_format::from_webkit(history.last_visit_time),
+                                    }),
+                                });
                             }
                         }
-                        
-                        // 4. タブを検索
-                        log::info!("Searching tabs with query: '{}'", query);
-                        let tabs = self.tab_provider.search_tabs(query);
-                        log::info!("Found {} matching tabs", tabs.len());
-                        for tab_item in tabs {
-                            let tab = &tab_item.tab;
-                            let title = if tab.title.is_empty() {
-                                tab.url.clone()
-                            } else {
-                                tab.title.clone()
-                            };
-                            
-                            let active_indicator = if tab.active { " [ACTIVE]" } else { "" };
-                            let description = format!("{}{}", tab.url, active_indicator);
-                            
-                            results.push(SearchResult {
-                                title,
-                                description,
-                                action: Action::SwitchToTab { 
-                                    tab_id: tab.id, 
-                                    window_id: tab.window_id 
-                                },
-                                window_info: None,
-                                result_type: ResultType::Tab,
-                            });
+                        Some(SourceResult::Ready(Err(e))) => {
+                            log::error!("Failed to get history: {}", e);
                         }
+                        Some(SourceResult::TimedOut) => results.push(timed_out_result(self.sources.history.name(), query)),
+                        None => {}
+                    }
+                    
+                    // タブを検索
+                    log::info!("Searching tabs with query: '{}'", query);
+                    let tabs = match tabs.map(PendingSource::wait) {
+                        Some(SourceResult::Ready(tabs)) => tabs,
+                        Some(SourceResult::TimedOut) => {
+                            results.push(timed_out_result(self.sources.tabs.name(), query));
+                            Vec::new()
+                        }
+                        None => Vec::new(),
+                    };
+                    self.report_throttle(TABS_PROVIDER, &self.sources.tabs);
+                    let tabs: Vec<TabItem> = tabs.into_iter().take(self.sources.tabs.result_cap()).collect();
+                    log::info!("Found {} matching tabs", tabs.len());
+                    let stale = self.get_tab_manager().freshness() == TabFreshness::Stale;
+                    for tab_item in tabs.iter().filter(|tab_item| parsed.matches(*tab_item)) {
+                        let tab = &tab_item.tab;
+                        let title = if tab.title.is_empty() {
+                            tab.url.clone()
+                        } else {
+                            tab.title.clone()
+                        };
+                        
+                        results.push(SearchResult {
+                            title,
+                            description: tab.url.clone(),
+                            action: Action::SwitchToTab { 
+                                tab_id: tab.id, 
+                                window_id: tab.window_id 
+                            },
+                            window_info: None,
+                            result_type: tab_result_type(tab),
+                            group: None,
+                            metadata: Some(ResultMetadata::Tab { url: tab.url.clone(), active: tab.active, window_number: None, stale }),
+                        });
                     }
+                    
+                    results = group_results(compose_results(results, &self.ranking));
+                    results = place_google_row(results, self.ranking.google_row, parsed.web_requested);
+                    results.retain(|result| {
+                        let category = ResultCategory::of(&result.result_type);
+                        parsed.allows_category(category) && filter.allows_category(category)
+                    });
+                } else {
+                    // 空のクエリでは今の時間帯によく開くものを提案する
+                    results = time_suggestions(&self.usage_entries(), windows, Local::now());
                 }
             }
+            SearchMode::Windows if !self.registry.serves(WINDOWS_PROVIDER, mode) => {}
             SearchMode::Windows => {
-                // Windowsモードは通常のウィンドウ検索
+                // Windowsモードは通常のウィンドウ検索（ワークスペースを選んでいればそのタグのウィンドウだけ）
+                let workspace = TagFilter::active(&self.workspaces());
+                // `on:2` / `min:` / `left:` などの位置の条件を取り除いた残りで検索する
+                let (geometry, text) = GeometryFilter::parse(query);
+                let geometry = if geometry.needs_monitors() { geometry.with_monitors(crate::monitor::enumerate_monitors()) } else { geometry };
+                let query = if geometry.is_empty() { query } else { text.as_str() };
+                let tagged: Vec<&WindowInfo> = windows
+                    .iter()
+                    .filter(|window| workspace.as_ref().is_none_or(|workspace| workspace.matches_window_info(window)))
+                    .filter(|window| geometry.matches_window_info(window))
+                    .collect();
                 if query.is_empty() {
+                    // 今の時間帯によく使うアプリのウィンドウを先頭に出す（同点なら元の順）
+                    let entries = self.usage_entries();
+                    let now = Local::now();
+                    // 記録したウィンドウを今のウィンドウに解決して、hwnd ごとに一番高い点数を付ける
+                    let mut scores: HashMap<isize, f32> = HashMap::new();
+                    for entry in &entries {
+                        if let UsageTarget::Window(fingerprint) = &entry.target {
+                            if let Some(window) = fingerprint.resolve(windows) {
+                                let score = scores.entry(window.hwnd).or_default();
+                                *score = score.max(time_of_day_score(&entry.opens, now));
+                            }
+                        }
+                    }
+                    let score = |window: &WindowInfo| scores.get(&window.hwnd).copied().unwrap_or(0.0);
+                    let mut windows = tagged;
+                    if !entries.is_empty() {
+                        windows.sort_by(|a, b| score(b).partial_cmp(&score(a)).unwrap_or(std::cmp::Ordering::Equal));
+                    }
                     for window in windows {
                         results.push(SearchResult {
                             title: window.title.clone(),
-                            description: format!("{} - {}", window.process_name, window.class_name),
+                            description: window.process_name.clone(),
                             action: Action::SwitchWindow(window.hwnd),
                             window_info: Some(window.clone()),
                             result_type: ResultType::Window,
+                            group: None,
+                            metadata: None,
                         });
                     }
                 } else {
-                    for window in windows {
-                        if window.contains_text(query) {
-                            results.push(SearchResult {
-                                title: window.title.clone(),
-                                description: format!("{} - {}", window.process_name, window.class_name),
-                                action: Action::SwitchWindow(window.hwnd),
-                                window_info: Some(window.clone()),
-                                result_type: ResultType::Window,
-                            });
+                    // 今のタイトルで一致したものを先に、前のタイトルで一致したものを後ろに並べる
+                    let mut renamed = Vec::new();
+                    for window in tagged {
+                        let previous = if window.contains_text(query) {
+                            None
+                        } else {
+                            match self.title_history.previous_match(window.hwnd, query) {
+                                Some(previous) => Some(previous),
+                                None => continue,
+                            }
+                        };
+                        let description = match &previous {
+                            Some(previous) => format!("{} · was: {}", window.process_name, previous),
+                            None => window.process_name.clone(),
+                        };
+                        let result = SearchResult {
+                            title: window.title.clone(),
+                            description,
+                            action: Action::SwitchWindow(window.hwnd),
+                            window_info: Some(window.clone()),
+                            result_type: ResultType::Window,
+                            group: None,
+                            metadata: None,
+                        };
+                        if previous.is_some() {
+                            renamed.push(result);
+                        } else {
+                            results.push(result);
                         }
                     }
+                    results.extend(renamed);
                     
                     if results.len() > 10 {
                         results.truncate(10);
                     }
+                    
+                    if let Some(previous) = self.previous_session.as_ref().filter(|_| matches_builtin_command(query, RESTORE_SESSION)) {
+                        results.insert(0, restore_session_result(previous, windows));
+                    }
                 }
             }
+            SearchMode::General => {
+                // search_general で振り分け済み
+            }
         }
         
         results
     }
     
-    fn is_window_search(&self, _query: &str, mode: SearchMode) -> bool {
-        mode == SearchMode::Windows
+    fn is_window_search(&self, query: &str, mode: SearchMode) -> bool {
+        match mode {
+            SearchMode::Windows => true,
+            SearchMode::General => matches!(route_general(query), GeneralRoute::Windows(_)),
+            _ => false,
+        }
+    }
+
+    fn preview(&self, action: &Action, windows: &[WindowInfo]) -> Option<ConfirmableAction> {
+        match action {
+            Action::CloseDuplicateTabs => {
+                let tabs = self.get_tab_manager().snapshot();
+                let duplicates = crate::core::tab_dedup::find_duplicate_tabs(&tabs);
+                if duplicates.is_empty() {
+                    return None;
+                }
+                let effects = tabs
+                    .iter()
+                    .filter(|tab| duplicates.contains(&tab.id))
+                    .map(|tab| format!("Close: {}", tab.title))
+                    .collect();
+                Some(ConfirmableAction::new(action.clone(), format!("Close {} duplicate tab(s)", duplicates.len()), effects))
+            }
+            Action::RestoreLayout(name) => {
+                let layout = self.layout_manager.get(name).ok().flatten()?;
+                let plan = crate::layout_manager::plan_restore(&layout, windows, &crate::monitor::enumerate_monitors());
+                let title = |hwnd: isize| {
+                    windows.iter().find(|window| window.hwnd == hwnd).map(|window| window.title.as_str()).unwrap_or("?")
+                };
+                let mut effects: Vec<String> = plan.moves.iter().map(|(hwnd, _, _)| format!("Move: {}", title(*hwnd))).collect();
+                for saved in &plan.missing {
+                    effects.push(match (&saved.exe_path, self.layout_manager.relaunch_missing()) {
+                        (Some(exe_path), true) => format!("Launch: {}", exe_path),
+                        _ => format!("Missing: {} ({})", saved.process_name, saved.title),
+                    });
+                }
+                Some(ConfirmableAction::new(
+                    action.clone(),
+                    format!("Restore layout {} ({} window(s))", layout.name, layout.windows.len()),
+                    effects,
+                ))
+            }
+            Action::DeleteLayout(name) => {
+                let layout = self.layout_manager.get(name).ok().flatten()?;
+                let effects = layout.windows.iter().map(|saved| format!("Forget: {} ({})", saved.process_name, saved.title)).collect();
+                Some(ConfirmableAction::new(
+                    action.clone(),
+                    format!("Delete layout {} ({} window(s))", layout.name, layout.windows.len()),
+                    effects,
+                ))
+            }
+            Action::RestoreSession => {
+                let missing = session::missing_apps(self.previous_session.as_ref()?, windows);
+                if missing.is_empty() {
+                    return None;
+                }
+                let effects = missing.iter().map(|app| format!("Launch: {} ({})", app.process_name, app.title)).collect();
+                Some(ConfirmableAction::new(action.clone(), format!("Relaunch {} app(s)", missing.len()), effects))
+            }
+            Action::DeleteBookmark { url, title, source } => Some(ConfirmableAction::new(
+                action.clone(),
+                format!("Delete bookmark {}", title),
+                vec![format!("Delete: {} ({})", title, url), format!("From: {}", source.label())],
+            )),
+            Action::ProviderCommand { provider, command } => self.preview_provider_command(provider, command),
+            _ => None,
+        }
     }
 }
 
 
+/// Chrome のブックマーク・履歴の取得元（作成できなければ何も返さないプロバイダー）
+fn chrome_provider() -> Box<dyn BrowserDataProvider> {
+    match ChromeBrowserProvider::new() {
+        Ok(provider) => Box::new(provider),
+        Err(e) => {
+            log::error!("Failed to create Chrome provider: {}", e);
+            Box::new(DummyBrowserProvider)
+        }
+    }
+}
+
 // ダミーのブラウザプロバイダー（Chrome情報が取得できない場合用）
 struct DummyBrowserProvider;
 
@@ -266,4 +1729,434 @@ impl BrowserDataProvider for DummyBrowserProvider {
     fn search_history(&self, _query: &str) -> Result<Vec<HistoryItem>, Box<dyn std::error::Error>> {
         Ok(Vec::new())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_tab(id: i32, window_id: i32, index: i32, active: bool) -> ChromeTab {
+        ChromeTab {
+            id,
+            window_id,
+            title: format!("Tab {}", id),
+            url: format!("https://example.com/{}", id),
+            fav_icon_url: String::new(),
+            active,
+            index,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_tab_results_grouped_by_window() {
+        let tabs = vec![
+            create_tab(1, 200, 1, false),
+            create_tab(2, 100, 0, true),
+            ChromeTab { pinned: true, ..create_tab(3, 200, 0, true) },
+        ];
+
+        let results = tab_results(tabs);
+        let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["● Tab 3", "Tab 1", "● Tab 2"]);
+        assert!(results[0].description.starts_with("Window 1"));
+        assert!(results[2].description.starts_with("Window 2"));
+        assert_eq!(results[0].result_type, ResultType::PinnedTab);
+        assert_eq!(results[1].result_type, ResultType::Tab);
+    }
+
+    #[test]
+    fn test_matches_command() {
+        assert!(matches_command("dup", CLOSE_DUPLICATE_TABS));
+        assert!(matches_command("close dup", CLOSE_DUPLICATE_TABS));
+        assert!(matches_command("Close Duplicate Tabs", CLOSE_DUPLICATE_TABS));
+        assert!(!matches_command("close window", CLOSE_DUPLICATE_TABS));
+        assert!(!matches_command("", CLOSE_DUPLICATE_TABS));
+
+        assert!(matches_command("c", CLOSE_DUPLICATE_TABS));
+        assert!(!matches_builtin_command("c", CLOSE_DUPLICATE_TABS));
+        assert!(!matches_builtin_command("c d", CLOSE_DUPLICATE_TABS));
+        assert!(matches_builtin_command("dup", CLOSE_DUPLICATE_TABS));
+        assert!(matches_builtin_command("cl du", CLOSE_DUPLICATE_TABS));
+    }
+
+    #[test]
+    fn test_close_duplicate_tabs_result_reports_count() {
+        let mut duplicate = create_tab(2, 100, 1, false);
+        duplicate.url = "https://example.com/1".to_string();
+        let tabs = vec![create_tab(1, 100, 0, true), duplicate];
+
+        let result = close_duplicate_tabs_result(&tabs);
+        assert_eq!(result.action, Action::CloseDuplicateTabs);
+        assert_eq!(result.description, "1 duplicate tab will be closed");
+    }
+
+    #[test]
+    fn test_clear_caches_result_shows_usage() {
+        let usage = [("previews", CacheUsage { files: 3, bytes: 2048 }), ("favicons", CacheUsage::default())];
+        let result = clear_caches_result(&usage);
+        assert_eq!(result.action, Action::ClearCaches);
+        assert_eq!(result.description, "Delete cached images: previews 2 KB (3 files)");
+        assert_eq!(clear_caches_result(&[]).description, "Image caches are empty");
+    }
+
+    #[test]
+    fn test_preview_close_duplicate_tabs() {
+        let tab_manager = Arc::new(crate::core::TabManager::new());
+        let engine = BrowserSearchEngine::new_with_tab_manager(tab_manager.clone());
+        assert!(engine.preview(&Action::CloseDuplicateTabs, &[]).is_none());
+
+        let mut duplicate = create_tab(2, 100, 1, false);
+        duplicate.url = "https://example.com/1".to_string();
+        duplicate.title = "Docs".to_string();
+        tab_manager.update_tabs(vec![create_tab(1, 100, 0, true), duplicate]);
+
+        let preview = engine.preview(&Action::CloseDuplicateTabs, &[]).unwrap();
+        assert_eq!(preview.summary, "Close 1 duplicate tab(s)");
+        assert_eq!(preview.effects, vec!["Close: Docs"]);
+        assert!(engine.preview(&Action::OpenTabsOverview, &[]).is_none());
+    }
+
+    #[test]
+    fn test_preview_delete_bookmark() {
+        let engine = BrowserSearchEngine::new_with_tab_manager(Arc::new(crate::core::TabManager::new()));
+        let action = Action::DeleteBookmark {
+            url: "https://docs.rs/".to_string(),
+            title: "Docs.rs".to_string(),
+            source: BookmarkSource { id: "42".to_string(), browser: "Chrome".to_string(), profile: "Profile 1".to_string() },
+        };
+
+        let preview = engine.preview(&action, &[]).unwrap();
+        assert_eq!(preview.summary, "Delete bookmark Docs.rs");
+        assert_eq!(preview.effects, vec!["Delete: Docs.rs (https://docs.rs/)", "From: Chrome - Profile 1"]);
+    }
+
+    #[test]
+    fn test_preview_provider_command() {
+        let mut engine = BrowserSearchEngine::new_with_tab_manager(Arc::new(crate::core::TabManager::new()));
+        engine.register_provider(Box::new(crate::system::wsl::WslProvider::with_runner(|_, _| Ok(String::new()))));
+        let command = |command: &str| Action::ProviderCommand { provider: "wsl".to_string(), command: command.to_string() };
+
+        assert!(engine.preview(&command("shell:Ubuntu"), &[]).is_none());
+        assert_eq!(engine.preview(&command("terminate:Ubuntu"), &[]).unwrap().summary, "Terminate Ubuntu");
+    }
+
+    #[test]
+    fn test_report_health() {
+        let tab_manager = Arc::new(crate::core::TabManager::new());
+        let engine = BrowserSearchEngine::new_with_tab_manager(tab_manager.clone());
+        engine.providers().set_disabled(&[HISTORY_PROVIDER.to_string()]);
+
+        engine.search("", SearchMode::Tabs, &[]);
+        let health = engine.health();
+        assert_eq!(health.get(HISTORY_PROVIDER), Some(HealthState::Disabled));
+        assert_eq!(health.get(WINDOWS_PROVIDER), Some(HealthState::Degraded("No windows were enumerated".to_string())));
+        assert_eq!(health.get(TABS_PROVIDER), Some(HealthState::Degraded("Chrome extension is not connected".to_string())));
+
+        tab_manager.extension_connected();
+        tab_manager.update_tabs(vec![create_tab(1, 100, 0, true)]);
+        engine.search("", SearchMode::Tabs, &[]);
+        assert_eq!(health.get(TABS_PROVIDER), Some(HealthState::Healthy));
+    }
+
+    #[test]
+    fn test_slow_keyword_provider_times_out_and_streams_in() {
+        struct SlowProvider;
+
+        impl KeywordProvider for SlowProvider {
+            fn name(&self) -> &'static str {
+                "slow"
+            }
+
+            fn keywords(&self) -> &[&'static str] {
+                &["slow"]
+            }
+
+            fn search(&self, _keyword: &str, args: &str) -> Vec<SearchResult> {
+                std::thread::sleep(Duration::from_millis(100));
+                vec![SearchResult {
+                    title: format!("Slow: {}", args),
+                    description: String::new(),
+                    action: Action::GoogleSearch(args.to_string()),
+                    window_info: None,
+                    result_type: ResultType::Command,
+                    group: None,
+                    metadata: None,
+                }]
+            }
+
+            fn execute(&self, _command: &str) -> Result<String, Box<dyn Error>> {
+                Ok(String::new())
+            }
+        }
+
+        let mut engine = BrowserSearchEngine::new().with_sources(&SourcesConfig { timeout_ms: 10, ..SourcesConfig::default() });
+        engine.register_provider(Box::new(SlowProvider));
+
+        let results = engine.search("slow rust", SearchMode::Browser, &[]);
+        assert_eq!(results[0].title, "Searching slow timed out");
+        assert!(engine.has_pending_providers());
+
+        for _ in 0..100 {
+            if engine.take_provider_updates() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!engine.has_pending_providers());
+        assert_eq!(engine.search("slow rust", SearchMode::Browser, &[])[0].title, "Slow: rust");
+    }
+
+    #[test]
+    fn test_windows_match_previous_title() {
+        let engine = BrowserSearchEngine::new_with_tab_manager(Arc::new(crate::core::TabManager::new()));
+        let window = |title: &str| WindowInfo {
+            hwnd: 10,
+            title: title.to_string(),
+            class_name: "Chrome_WidgetWin_1".to_string(),
+            process_name: "chrome.exe".to_string(),
+            is_visible: true,
+            is_minimized: false,
+            rect: (0, 0, 800, 600),
+            requires_elevation: false,
+        };
+
+        engine.search("", SearchMode::Windows, &[window("PROJ-123 Fix login - Jira")]);
+        let results = engine.search("proj-123", SearchMode::Windows, &[window("Inbox - Gmail")]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Inbox - Gmail");
+        assert_eq!(results[0].description, "chrome.exe · was: PROJ-123 Fix login - Jira");
+    }
+
+    #[test]
+    fn test_windows_geometry_filter() {
+        let engine = BrowserSearchEngine::new_with_tab_manager(Arc::new(crate::core::TabManager::new()));
+        let window = |hwnd: isize, title: &str, is_minimized: bool| WindowInfo {
+            hwnd,
+            title: title.to_string(),
+            class_name: "Notepad".to_string(),
+            process_name: "notepad.exe".to_string(),
+            is_visible: true,
+            is_minimized,
+            rect: (0, 0, 800, 600),
+            requires_elevation: false,
+        };
+        let windows = vec![window(1, "todo.txt - Notepad", true), window(2, "notes.txt - Notepad", false)];
+
+        let titles = |query: &str| engine.search(query, SearchMode::Windows, &windows).into_iter().map(|result| result.title).collect::<Vec<_>>();
+        assert_eq!(titles("min:"), vec!["todo.txt - Notepad"]);
+        assert_eq!(titles("notepad min:"), vec!["todo.txt - Notepad"]);
+        assert!(titles("notes min:").is_empty());
+    }
+
+    #[test]
+    fn test_tabs_overview_result() {
+        let tabs = vec![create_tab(1, 100, 0, true), create_tab(2, 200, 0, true), create_tab(3, 200, 1, false)];
+        let result = tabs_overview_result(&tabs);
+        assert_eq!(result.action, Action::OpenTabsOverview);
+        assert_eq!(result.description, "3 tabs in 2 windows, grouped by window or domain");
+        assert!(matches_command("tabs over", OPEN_TABS_OVERVIEW));
+    }
+
+    #[test]
+    fn test_new_tab_results() {
+        let mut config = NewTabConfig::default();
+        config.profiles.insert("work".to_string(), "Chrome - Profile 1".to_string());
+        let detected = vec!["Chrome - Default".to_string(), "Chrome - Profile 1".to_string()];
+
+        let results = new_tab_results("Work rust lifetimes", &config, &detected);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].action,
+            Action::OpenInProfile {
+                profile: "Chrome - Profile 1".to_string(),
+                url: "https://www.google.com/search?q=rust%20lifetimes".to_string(),
+            }
+        );
+
+        // サブキーワードでなければ検索語の一部で、既定のプロファイルがなければ検出したものを並べる
+        let results = new_tab_results("home https://example.com", &config, &detected);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].description, "https://www.google.com/search?q=home%20https%3A%2F%2Fexample.com");
+
+        config.default_profile = Some("Chrome - Default".to_string());
+        let results = new_tab_results("https://example.com", &config, &detected);
+        assert_eq!(
+            results.iter().map(|r| &r.action).collect::<Vec<_>>(),
+            vec![&Action::OpenInProfile { profile: "Chrome - Default".to_string(), url: "https://example.com".to_string() }]
+        );
+
+        assert_eq!(new_tab_results("work", &config, &detected)[0].title, "New tab in Chrome - Default: work");
+        assert!(new_tab_results("", &config, &detected).is_empty());
+        assert!(matches!(new_tab_results("rust", &NewTabConfig::default(), &[])[0].action, Action::OpenUrl(_)));
+    }
+
+    #[test]
+    fn test_macro_results() {
+        let macros = vec![
+            MacroConfig {
+                name: "Standup message".to_string(),
+                steps: vec!["switch slack.exe".to_string(), "key enter".to_string()],
+                delay_ms: 150,
+            },
+            MacroConfig { name: "Broken".to_string(), steps: vec!["jump".to_string()], delay_ms: 150 },
+        ];
+
+        let results = macro_results("stand", &macros);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].action, Action::RunMacro("Standup message".to_string()));
+        assert_eq!(results[0].description, "switch slack.exe → key enter");
+
+        let results = macro_results("", &macros);
+        assert_eq!(results.len(), 2);
+        assert!(results[1].description.starts_with("Invalid macro"));
+    }
+
+    #[test]
+    fn test_parse_bookmark_add() {
+        assert_eq!(parse_bookmark_add("bm add"), Some(None));
+        assert_eq!(parse_bookmark_add("bm add "), Some(None));
+        assert_eq!(parse_bookmark_add("bm add Work/Reading/"), Some(Some("Work/Reading".to_string())));
+        assert_eq!(parse_bookmark_add("bm addition"), None);
+        assert_eq!(parse_bookmark_add("github"), None);
+    }
+
+    #[test]
+    fn test_profile_title() {
+        let profile = profile_label(Some("Chrome"), Some("Work"));
+        assert_eq!(profile, Some("Chrome - Work".to_string()));
+        assert_eq!(profile_label(Some("Chrome"), None), None);
+        assert_eq!(
+            profile_title("Rust", profile.clone(), false),
+            ("[Chrome - Work] Rust".to_string(), None)
+        );
+        assert_eq!(
+            profile_title("Rust", profile, true),
+            ("Rust".to_string(), Some("Chrome - Work".to_string()))
+        );
+        assert_eq!(profile_title("Rust", None, true), ("Rust".to_string(), None));
+    }
+
+    #[test]
+    fn test_strip_keyword() {
+        assert_eq!(strip_keyword("note buy milk ", NOTE_KEYWORD), Some("buy milk"));
+        assert_eq!(strip_keyword("notes", NOTES_KEYWORD), Some(""));
+        assert_eq!(strip_keyword("notes milk", NOTE_KEYWORD), None);
+        assert_eq!(strip_keyword("notepad", NOTE_KEYWORD), None);
+    }
+
+    #[test]
+    fn test_note_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = NoteStore::new(dir.path().join("notes.md"));
+        std::fs::write(store.path(), "- [2024-05-01 09:05] buy milk\n").unwrap();
+
+        let add = append_note_result("call bob", &store);
+        assert_eq!(add.action, Action::AppendNote("call bob".to_string()));
+
+        let found = note_results("milk", &store);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title, "buy milk");
+        assert_eq!(found[0].description, "2024-05-01 09:05");
+        assert_eq!(found[0].action, Action::OpenNote { line: 0 });
+    }
+
+    #[test]
+    fn test_timer_results_include_active_timers() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let store = TimerStore::new(dir.path().join("timers.json"));
+        let tea = timers::parse_timer_request(TimerKind::Timer, "10m tea", now).unwrap();
+        store.add(&tea).unwrap();
+
+        let results = timer_results(TimerKind::Reminder, "15:30 meeting", &store, now);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Reminder: meeting");
+        assert_eq!(results[1].title, "Cancel timer: tea");
+        assert_eq!(results[1].description, "10m 00s left (at 09:10)");
+        assert_eq!(results[1].action, Action::CancelTimer(1));
+
+        // 解析できない引数では設定中のタイマーだけ
+        let results = timer_results(TimerKind::Timer, "soon", &store, now);
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn test_layout_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = LayoutManager::new(dir.path().join("layouts.json"));
+        let windows = vec![crate::test_helpers::helpers::create_test_window(1, "Editor", "code.exe")];
+        manager.save("Docked", &windows, &[]).unwrap();
+        manager.save("Laptop", &windows, &[]).unwrap();
+
+        let results = layout_results("save Home", &manager);
+        assert_eq!(results[0].action, Action::SaveLayout("Home".to_string()));
+
+        let results = layout_results("dock", &manager);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].action, Action::RestoreLayout("Docked".to_string()));
+        assert!(results[0].description.starts_with("1 windows"));
+
+        let results = layout_results("delete lap", &manager);
+        assert_eq!(results[0].action, Action::DeleteLayout("Laptop".to_string()));
+        assert_eq!(layout_results("", &manager).len(), 2);
+    }
+
+    #[test]
+    fn test_tag_results() {
+        let windows = vec![
+            crate::test_helpers::helpers::create_test_window(1, "main.rs - my-launcher - Visual Studio Code", "Code.exe"),
+            crate::test_helpers::helpers::create_test_window(2, "lib.rs - my-launcher - Visual Studio Code", "Code.exe"),
+            crate::test_helpers::helpers::create_test_window(3, "Inbox", "outlook.exe"),
+        ];
+        let profiles = vec!["Chrome - Work".to_string(), "Chrome - Personal".to_string()];
+        let workspaces = Workspaces::default();
+
+        // 同じプロセスとタイトルのルールは1つにまとめる
+        let results = tag_results("project-A my-launcher", &windows, &profiles, &workspaces);
+        assert_eq!(results.len(), 1);
+        let rule = TagRule {
+            tag: "project-A".to_string(),
+            target: TagTarget::Window { process_name: "Code.exe".to_string(), title: Some("my-launcher".to_string()) },
+        };
+        assert_eq!(results[0].action, Action::AssignTag(rule.clone()));
+
+        // タイトルに含まれなければプロセス名だけ、プロファイルは表示名で
+        let results = tag_results("work OUTLOOK", &windows, &profiles, &workspaces);
+        assert_eq!(results[0].title, "Tag all outlook.exe windows as work");
+        let results = tag_results("work work", &windows, &profiles, &workspaces);
+        assert_eq!(results[0].title, "Tag profile Chrome - Work as work");
+
+        let workspaces = Workspaces { active: None, rules: vec![rule.clone()] };
+        let results = tag_results("proj", &windows, &profiles, &workspaces);
+        assert_eq!(results[0].action, Action::RemoveTag(rule));
+        assert!(tag_results("personal", &windows, &profiles, &workspaces).is_empty());
+    }
+
+    #[test]
+    fn test_bookmark_add_results_for_active_tabs() {
+        let tabs = vec![
+            create_tab(1, 100, 0, true),
+            create_tab(2, 100, 1, false),
+            create_tab(3, 200, 0, true),
+        ];
+
+        let results = bookmark_add_results(&tabs, Some("Work".to_string()));
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].action,
+            Action::CreateBookmark {
+                url: "https://example.com/1".to_string(),
+                title: "Tab 1".to_string(),
+                folder: Some("Work".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_tab_results_action() {
+        let results = tab_results(vec![create_tab(5, 300, 0, false)]);
+        assert_eq!(results[0].action, Action::SwitchToTab { tab_id: 5, window_id: 300 });
+        assert_eq!(results[0].result_type, ResultType::Tab);
+    }
+}
@@ -0,0 +1,207 @@
+use super::{ChromeTab, WindowInfo};
+use crate::data::browser_provider::BrowserConfig;
+use std::collections::HashMap;
+
+/// 拡張機能を入れられるブラウザのウィンドウ（プロセス名とタイトルの末尾）
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserWindowKind {
+    pub process_name: &'static str,
+    /// 例: `GitHub - Google Chrome`
+    pub title_suffix: &'static str,
+}
+
+const CHROME: BrowserWindowKind = BrowserWindowKind { process_name: "chrome.exe", title_suffix: " - Google Chrome" };
+const WAVEBOX: BrowserWindowKind = BrowserWindowKind { process_name: "wavebox.exe", title_suffix: " - Wavebox" };
+
+impl BrowserWindowKind {
+    /// 設定で有効にしたブラウザ（`LAUNCHER_ENABLE_CHROME` / `LAUNCHER_ENABLE_WAVEBOX`）
+    pub fn enabled(config: &BrowserConfig) -> Vec<Self> {
+        [(config.enable_chrome, CHROME), (config.enable_wavebox, WAVEBOX)]
+            .into_iter()
+            .filter_map(|(enabled, kind)| enabled.then_some(kind))
+            .collect()
+    }
+
+    fn matches(&self, window: &WindowInfo) -> bool {
+        // どちらも Chromium なのでクラス名は同じ
+        window.process_name.eq_ignore_ascii_case(self.process_name) && window.class_name.starts_with("Chrome_WidgetWin")
+    }
+}
+
+/// `GetWindowTextW` のバッファ長。これ以上長いタイトルは切り詰められている。
+const MAX_WINDOW_TITLE_LEN: usize = 255;
+
+/// Chromeのウィンドウ ID（拡張機能側）と HWND の対応表
+///
+/// Chromeのウィンドウタイトルはアクティブなタブのタイトルになるため、それを突き合わせて対応付ける。
+/// 一時的に対応が取れなくなっても（タブ読み込み中など）、ウィンドウが残っている間は前回の対応を保持する。
+/// 対象は設定で有効にしたブラウザのウィンドウ（Chrome と Wavebox）。
+#[derive(Debug)]
+pub struct ChromeWindowMap {
+    hwnds: HashMap<i32, isize>,
+    browsers: Vec<BrowserWindowKind>,
+}
+
+impl ChromeWindowMap {
+    /// Chrome と Wavebox のウィンドウを対応付ける
+    pub fn new() -> Self {
+        Self::with_browsers(vec![CHROME, WAVEBOX])
+    }
+
+    pub fn with_browsers(browsers: Vec<BrowserWindowKind>) -> Self {
+        Self { hwnds: HashMap::new(), browsers }
+    }
+
+    /// タブ一覧とウィンドウ一覧から対応表を更新
+    pub fn update(&mut self, tabs: &[ChromeTab], windows: &[WindowInfo]) {
+        let chrome_windows: Vec<(&WindowInfo, &BrowserWindowKind)> = windows
+            .iter()
+            .filter_map(|w| Some((w, self.browsers.iter().find(|browser| browser.matches(w))?)))
+            .collect();
+
+        // 閉じられたウィンドウの対応を削除
+        self.hwnds.retain(|window_id, hwnd| {
+            tabs.iter().any(|tab| tab.window_id == *window_id)
+                && chrome_windows.iter().any(|(w, _)| w.hwnd == *hwnd)
+        });
+
+        for tab in tabs.iter().filter(|tab| tab.active) {
+            if let Some((window, _)) = chrome_windows.iter().find(|(w, browser)| title_matches(&w.title, browser.title_suffix, &tab.title)) {
+                self.hwnds.insert(tab.window_id, window.hwnd);
+            }
+        }
+    }
+
+    /// Chromeのウィンドウ ID に対応する HWND
+    pub fn hwnd_for(&self, window_id: i32) -> Option<isize> {
+        self.hwnds.get(&window_id).copied()
+    }
+
+    /// HWND に対応する Chrome のウィンドウのアクティブなタブ
+    pub fn active_tab<'a>(&self, hwnd: isize, tabs: &'a [ChromeTab]) -> Option<&'a ChromeTab> {
+        let window_id = self.hwnds.iter().find(|(_, mapped)| **mapped == hwnd).map(|(window_id, _)| *window_id)?;
+        tabs.iter().find(|tab| tab.window_id == window_id && tab.active)
+    }
+}
+
+impl Default for ChromeWindowMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ウィンドウタイトルがタブのタイトルに対応するか（`title_suffix` はブラウザ名の部分）
+fn title_matches(window_title: &str, title_suffix: &str, tab_title: &str) -> bool {
+    if tab_title.is_empty() {
+        return false;
+    }
+
+    // プロファイル名が付く場合もあるので、末尾以降はまとめて取り除く
+    let page_title = match window_title.find(title_suffix) {
+        Some(pos) => &window_title[..pos],
+        None => window_title,
+    };
+
+    if page_title == tab_title {
+        return true;
+    }
+
+    // 長いタイトルは切り詰められている
+    window_title.chars().count() >= MAX_WINDOW_TITLE_LEN && tab_title.starts_with(page_title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_tab(id: i32, window_id: i32, title: &str, active: bool) -> ChromeTab {
+        ChromeTab {
+            id,
+            window_id,
+            title: title.to_string(),
+            url: format!("https://example.com/{}", id),
+            fav_icon_url: String::new(),
+            active,
+            index: 0,
+            pinned: false,
+        }
+    }
+
+    fn create_chrome_window(hwnd: isize, title: &str) -> WindowInfo {
+        let mut window = crate::test_helpers::helpers::create_test_window(hwnd, title, "chrome.exe");
+        window.class_name = "Chrome_WidgetWin_1".to_string();
+        window
+    }
+
+    #[test]
+    fn test_maps_window_by_active_tab_title() {
+        let tabs = vec![
+            create_tab(1, 10, "GitHub", true),
+            create_tab(2, 10, "Docs", false),
+            create_tab(3, 20, "Docs", true),
+        ];
+        let windows = vec![
+            create_chrome_window(1000, "Docs - Google Chrome"),
+            create_chrome_window(2000, "GitHub - Google Chrome - Work"),
+        ];
+
+        let mut map = ChromeWindowMap::new();
+        map.update(&tabs, &windows);
+
+        assert_eq!(map.hwnd_for(10), Some(2000));
+        assert_eq!(map.hwnd_for(20), Some(1000));
+        assert_eq!(map.hwnd_for(30), None);
+        assert_eq!(map.active_tab(1000, &tabs).map(|tab| tab.id), Some(3));
+        assert!(map.active_tab(3000, &tabs).is_none());
+    }
+
+    #[test]
+    fn test_ignores_other_processes() {
+        let tabs = vec![create_tab(1, 10, "Notes", true)];
+        let windows = vec![crate::test_helpers::helpers::create_test_window(1000, "Notes - Google Chrome", "notepad.exe")];
+
+        let mut map = ChromeWindowMap::new();
+        map.update(&tabs, &windows);
+        assert_eq!(map.hwnd_for(10), None);
+    }
+
+    #[test]
+    fn test_keeps_mapping_while_window_exists() {
+        let windows = vec![create_chrome_window(1000, "GitHub - Google Chrome")];
+        let mut map = ChromeWindowMap::new();
+        map.update(&[create_tab(1, 10, "GitHub", true)], &windows);
+
+        // タブ読み込み中でタイトルが一致しなくても対応は残る
+        let loading = vec![create_chrome_window(1000, "Untitled - Google Chrome")];
+        map.update(&[create_tab(1, 10, "Loading...", true)], &loading);
+        assert_eq!(map.hwnd_for(10), Some(1000));
+
+        // ウィンドウが閉じられたら削除
+        map.update(&[create_tab(1, 10, "Loading...", true)], &[]);
+        assert_eq!(map.hwnd_for(10), None);
+    }
+
+    #[test]
+    fn test_maps_enabled_browsers_only() {
+        let tabs = vec![create_tab(1, 10, "Mail", true)];
+        let mut wavebox = crate::test_helpers::helpers::create_test_window(1000, "Mail - Wavebox", "Wavebox.exe");
+        wavebox.class_name = "Chrome_WidgetWin_1".to_string();
+
+        let mut map = ChromeWindowMap::new();
+        map.update(&tabs, std::slice::from_ref(&wavebox));
+        assert_eq!(map.hwnd_for(10), Some(1000));
+
+        let chrome_only = BrowserConfig { enable_chrome: true, enable_wavebox: false };
+        let mut map = ChromeWindowMap::with_browsers(BrowserWindowKind::enabled(&chrome_only));
+        map.update(&tabs, &[wavebox]);
+        assert_eq!(map.hwnd_for(10), None);
+    }
+
+    #[test]
+    fn test_title_matches_truncated_title() {
+        let long_title = "a".repeat(300);
+        let window_title: String = long_title.chars().take(MAX_WINDOW_TITLE_LEN).collect();
+        assert!(title_matches(&window_title, CHROME.title_suffix, &long_title));
+        assert!(!title_matches("Other - Google Chrome", CHROME.title_suffix, "Another"));
+    }
+}
@@ -0,0 +1,19 @@
+use super::Action;
+
+/// 実行する前に確認する操作（重複タブを閉じる、ウィンドウ配置を復元するなど）
+///
+/// 検索エンジンが `SearchEngine::preview` で作り、確認のオーバーレイに影響を受けるものを一覧する。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmableAction {
+    pub action: Action,
+    /// 何をするか（例: `Close 3 duplicate tabs`）
+    pub summary: String,
+    /// 影響を受けるウィンドウ・タブ・アプリ（1件ずつ）
+    pub effects: Vec<String>,
+}
+
+impl ConfirmableAction {
+    pub fn new(action: Action, summary: impl Into<String>, effects: Vec<String>) -> Self {
+        Self { action, summary: summary.into(), effects }
+    }
+}
@@ -0,0 +1,207 @@
+//! 検索語が空のときに表示する内容
+//!
+//! モードごとに設定したセクション（最近の検索語・よく開くサイト・固定した項目・ショートカット）を
+//! 順に組み立てる。内容は `EmptyStateSource` から取り、表示は `ui/empty_state.rs` が行う。
+
+use super::{Action, SearchMode};
+use crate::data::browser_item::HistoryItem;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptySection {
+    /// 結果を開いたときの検索語
+    RecentQueries,
+    /// 履歴で訪問回数の多いページ
+    TopSites,
+    /// 設定の `empty_state.pinned`
+    Pinned,
+    /// そのモードで使える接頭辞とショートカット
+    Shortcuts,
+}
+
+impl EmptySection {
+    pub fn title(&self) -> &'static str {
+        match self {
+            EmptySection::RecentQueries => "Recent searches",
+            EmptySection::TopSites => "Top sites",
+            EmptySection::Pinned => "Pinned",
+            EmptySection::Shortcuts => "Shortcuts",
+        }
+    }
+}
+
+/// 選んだときにすること
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmptyTarget {
+    /// 検索語として入力する
+    Query(String),
+    Run(Action),
+    /// 説明だけの行
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmptyEntry {
+    pub label: String,
+    pub detail: String,
+    pub target: EmptyTarget,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmptyState {
+    /// 中身のあるセクションだけ（設定した順）
+    pub sections: Vec<(EmptySection, Vec<EmptyEntry>)>,
+}
+
+/// セクションの内容を出すもの（`BrowserSearchEngine` が実装する）
+pub trait EmptyStateSource {
+    /// 新しいものが先頭
+    fn recent_queries(&self) -> Vec<String>;
+    fn history(&self) -> Vec<HistoryItem>;
+    /// （タイトル, URL）
+    fn pinned(&self) -> Vec<(String, String)>;
+}
+
+impl EmptyState {
+    /// `sections` の順にセクションを組み立てる（各セクションは `limit` 件まで）
+    pub fn build(mode: SearchMode, sections: &[EmptySection], limit: usize, source: &dyn EmptyStateSource) -> Self {
+        let sections = sections
+            .iter()
+            .map(|section| {
+                let entries: Vec<EmptyEntry> = match section {
+                    EmptySection::RecentQueries => source
+                        .recent_queries()
+                        .into_iter()
+                        .map(|query| EmptyEntry {
+                            label: query.clone(),
+                            detail: String::new(),
+                            target: EmptyTarget::Query(query),
+                        })
+                        .collect(),
+                    EmptySection::TopSites => top_sites(source.history())
+                        .into_iter()
+                        .map(|item| EmptyEntry {
+                            label: if item.title.is_empty() { item.url.clone() } else { item.title },
+                            detail: format!("{} visits", item.visit_count),
+                            target: EmptyTarget::Run(Action::OpenHistory(item.url)),
+                        })
+                        .collect(),
+                    EmptySection::Pinned => source
+                        .pinned()
+                        .into_iter()
+                        .map(|(title, url)| EmptyEntry {
+                            label: title,
+                            detail: url.clone(),
+                            target: EmptyTarget::Run(Action::OpenUrl(url)),
+                        })
+                        .collect(),
+                    EmptySection::Shortcuts => shortcuts(mode)
+                        .iter()
+                        .map(|(key, description)| EmptyEntry {
+                            label: key.to_string(),
+                            detail: description.to_string(),
+                            target: EmptyTarget::None,
+                        })
+                        .collect(),
+                };
+                (*section, entries.into_iter().take(limit).collect::<Vec<_>>())
+            })
+            .filter(|(_, entries)| !entries.is_empty())
+            .collect();
+        Self { sections }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
+
+/// 訪問回数の多い順（同じ URL は 1 つ、回数が同じなら最近訪問したものを先に）
+fn top_sites(mut history: Vec<HistoryItem>) -> Vec<HistoryItem> {
+    history.sort_by(|a, b| b.visit_count.cmp(&a.visit_count).then(b.last_visit_time.cmp(&a.last_visit_time)));
+    let mut seen = std::collections::HashSet::new();
+    history.retain(|item| seen.insert(item.url.clone()));
+    history
+}
+
+/// モードごとの接頭辞とショートカット（キー, 説明）
+pub fn shortcuts(mode: SearchMode) -> &'static [(&'static str, &'static str)] {
+    match mode {
+        SearchMode::Browser => &[
+            ("t <query>", "Search open tabs"),
+            ("nt <query>", "Open in a new tab"),
+            ("Ctrl+1..9", "Filter by type or profile"),
+            ("Tab", "Next mode"),
+            ("Ctrl+P", "Settings"),
+        ],
+        SearchMode::Windows => &[
+            ("Arrow keys", "Move in the grid"),
+            ("Ctrl+1..9", "Workspace"),
+            ("Tab", "Next mode"),
+        ],
+        SearchMode::Tabs => &[("Alt+←/→", "Collapse/Expand a window"), ("Tab", "Next mode")],
+        SearchMode::General => &[
+            ("w <query>", "Search windows"),
+            (">command", "Run in a shell"),
+            ("https://…", "Open a URL"),
+            ("Enter", "Search on Google"),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Source;
+
+    impl EmptyStateSource for Source {
+        fn recent_queries(&self) -> Vec<String> {
+            vec!["rust book".to_string(), "jira".to_string(), "standup".to_string()]
+        }
+
+        fn history(&self) -> Vec<HistoryItem> {
+            let item = |title: &str, url: &str, visit_count: i32, last_visit_time: i64| HistoryItem {
+                title: title.to_string(),
+                url: url.to_string(),
+                visit_count,
+                last_visit_time,
+                browser_name: None,
+                profile_name: None,
+            };
+            vec![
+                item("Docs", "https://docs.rs/", 3, 10),
+                item("Mail", "https://mail.example.com/", 40, 5),
+                item("Mail", "https://mail.example.com/", 40, 9),
+                item("", "https://github.com/", 3, 20),
+            ]
+        }
+
+        fn pinned(&self) -> Vec<(String, String)> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_build_sections() {
+        let sections = [EmptySection::Pinned, EmptySection::TopSites, EmptySection::RecentQueries];
+        let state = EmptyState::build(SearchMode::Browser, &sections, 2, &Source);
+
+        // 中身のない Pinned は出さない
+        let kinds: Vec<EmptySection> = state.sections.iter().map(|(section, _)| *section).collect();
+        assert_eq!(kinds, vec![EmptySection::TopSites, EmptySection::RecentQueries]);
+
+        let top: Vec<&str> = state.sections[0].1.iter().map(|entry| entry.label.as_str()).collect();
+        assert_eq!(top, vec!["Mail", "https://github.com/"]);
+        assert_eq!(state.sections[1].1[0].target, EmptyTarget::Query("rust book".to_string()));
+        assert_eq!(state.sections[1].1.len(), 2);
+    }
+
+    #[test]
+    fn test_shortcuts_per_mode() {
+        let state = EmptyState::build(SearchMode::General, &[EmptySection::Shortcuts], 10, &Source);
+        assert_eq!(state.sections[0].1[0].label, "w <query>");
+        assert!(EmptyState::build(SearchMode::Tabs, &[], 10, &Source).is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+//! 結果の操作を Chrome 拡張機能へ送るコマンドに変換する
+//!
+//! `LauncherApp` と結合テストのシミュレーションが同じ変換を使い、`TabManager` のキューに積む。
+
+use super::native_messaging::{ChromeCommand, ChromeTab};
+use super::tab_dedup::find_duplicate_tabs;
+use super::Action;
+
+/// 拡張機能に任せる操作ならそのコマンド（それ以外の操作は空）
+///
+/// 重複タブを閉じる操作は、今のタブ一覧から閉じるタブを決める。
+pub fn commands_for(action: &Action, tabs: &[ChromeTab]) -> Vec<ChromeCommand> {
+    match action {
+        Action::SwitchToTab { tab_id, window_id } => vec![ChromeCommand::SwitchToTab { tab_id: *tab_id, window_id: *window_id }],
+        Action::CreateBookmark { url, title, folder } => vec![ChromeCommand::CreateBookmark {
+            url: url.clone(),
+            title: title.clone(),
+            folder: folder.clone(),
+        }],
+        Action::RestoreClosedTab { session_id, .. } => vec![ChromeCommand::RestoreClosedTab { session_id: session_id.clone() }],
+        Action::DeleteBookmark { source, .. } => vec![ChromeCommand::DeleteBookmark { id: source.id.clone() }],
+        Action::CloseDuplicateTabs => find_duplicate_tabs(tabs).into_iter().map(|tab_id| ChromeCommand::CloseTab { tab_id }).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::search_engine::BookmarkSource;
+    use crate::test_helpers::fixtures::tab;
+
+    #[test]
+    fn test_commands_for() {
+        let tabs = vec![
+            tab(1, 1, "Example", "https://example.com/"),
+            tab(2, 1, "Example", "https://example.com/"),
+            tab(3, 1, "Docs", "https://docs.rs/"),
+        ];
+        assert!(matches!(
+            commands_for(&Action::CloseDuplicateTabs, &tabs).as_slice(),
+            [ChromeCommand::CloseTab { tab_id: 2 }]
+        ));
+        assert!(matches!(
+            commands_for(&Action::SwitchToTab { tab_id: 3, window_id: 1 }, &tabs).as_slice(),
+            [ChromeCommand::SwitchToTab { tab_id: 3, window_id: 1 }]
+        ));
+        assert!(matches!(
+            commands_for(&Action::RestoreClosedTab { session_id: "42".to_string(), url: "https://docs.rs/".to_string() }, &tabs).as_slice(),
+            [ChromeCommand::RestoreClosedTab { session_id }] if session_id == "42"
+        ));
+        let delete = Action::DeleteBookmark {
+            url: "https://docs.rs/".to_string(),
+            title: "Docs.rs".to_string(),
+            source: BookmarkSource { id: "42".to_string(), browser: "Chrome".to_string(), profile: "Default".to_string() },
+        };
+        assert!(matches!(commands_for(&delete, &tabs).as_slice(), [ChromeCommand::DeleteBookmark { id }] if id == "42"));
+        assert!(commands_for(&Action::OpenUrl("https://docs.rs/".to_string()), &tabs).is_empty());
+    }
+}
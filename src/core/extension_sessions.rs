@@ -0,0 +1,242 @@
+//! 接続中の拡張機能（ブラウザ・プロファイルごと）の一覧
+//!
+//! WebSocket サーバーが接続ごとに登録し、メッセージを受け取るたびに最後の通信の時刻を更新する。
+//! 接続の一覧（Diagnostics のサービスの点をクリック）からタブ一覧の送り直しや切断を頼むと、
+//! その接続の処理が `take_controls` で受け取って実行する。
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 拡張機能との接続方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Transport {
+    WebSocket,
+    NativeMessaging,
+}
+
+impl Transport {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Transport::WebSocket => "WebSocket",
+            Transport::NativeMessaging => "Native messaging",
+        }
+    }
+}
+
+/// 接続に頼む操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionControl {
+    /// 全部のタブ一覧を送り直してもらう
+    RefreshTabs,
+    /// 接続を切る（拡張機能はしばらく接続し直さない）
+    Disconnect,
+}
+
+/// 接続の一覧に表示する内容（デーモンから UI へ送れる形）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: u64,
+    pub transport: Transport,
+    /// 拡張機能が `hello` で名乗ったブラウザとプロファイル
+    pub browser: Option<String>,
+    pub profile: Option<String>,
+    /// 接続元のアドレス
+    pub peer: Option<String>,
+    /// 接続してからの時間（ミリ秒）
+    pub connected_ms: u64,
+    /// 最後にメッセージを受け取ってからの時間（ミリ秒）
+    pub idle_ms: u64,
+}
+
+impl SessionInfo {
+    /// `Chrome - Profile 1` のような表示（名乗っていなければ `Unknown browser`）
+    pub fn label(&self) -> String {
+        match (&self.browser, &self.profile) {
+            (Some(browser), Some(profile)) => format!("{} - {}", browser, profile),
+            (Some(browser), None) => browser.clone(),
+            (None, _) => "Unknown browser".to_string(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Session {
+    info: SessionInfo,
+    connected_at: Instant,
+    last_activity: Instant,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionSessions {
+    next_id: Arc<AtomicU64>,
+    sessions: Arc<Mutex<Vec<Session>>>,
+    /// まだ接続の処理が受け取っていない操作（接続の ID, 操作）
+    controls: Arc<Mutex<Vec<(u64, SessionControl)>>>,
+}
+
+impl ExtensionSessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 接続を登録して ID を返す
+    pub fn open(&self, transport: Transport, peer: Option<String>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let now = Instant::now();
+        let info = SessionInfo { id, transport, browser: None, profile: None, peer, connected_ms: 0, idle_ms: 0 };
+        self.sessions.lock().unwrap().push(Session { info, connected_at: now, last_activity: now });
+        id
+    }
+
+    /// 拡張機能が名乗ったブラウザとプロファイルを記録する
+    pub fn identify(&self, id: u64, browser: Option<String>, profile: Option<String>) {
+        if let Some(session) = self.sessions.lock().unwrap().iter_mut().find(|session| session.info.id == id) {
+            session.info.browser = browser;
+            session.info.profile = profile;
+        }
+    }
+
+    /// メッセージを受け取った
+    pub fn touch(&self, id: u64) {
+        if let Some(session) = self.sessions.lock().unwrap().iter_mut().find(|session| session.info.id == id) {
+            session.last_activity = Instant::now();
+        }
+    }
+
+    pub fn close(&self, id: u64) {
+        self.sessions.lock().unwrap().retain(|session| session.info.id != id);
+        self.controls.lock().unwrap().retain(|(session, _)| *session != id);
+    }
+
+    pub fn list(&self) -> Vec<SessionInfo> {
+        let now = Instant::now();
+        self.sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|session| SessionInfo {
+                connected_ms: now.saturating_duration_since(session.connected_at).as_millis() as u64,
+                idle_ms: now.saturating_duration_since(session.last_activity).as_millis() as u64,
+                ..session.info.clone()
+            })
+            .collect()
+    }
+
+    /// `browser`（`Chrome`）の `profile`（`Profile 1`）で動いている拡張機能が接続しているか
+    ///
+    /// 拡張機能が `hello` でプロファイルを名乗っていなければ `Default` のものとみなす。
+    pub fn serves(&self, browser: &str, profile: &str) -> bool {
+        let browser = browser.to_lowercase();
+        self.sessions.lock().unwrap().iter().any(|session| {
+            session.info.browser.as_ref().is_some_and(|name| name.to_lowercase().contains(&browser))
+                && session.info.profile.as_deref().unwrap_or("Default") == profile
+        })
+    }
+
+    /// いま使われている接続方法（最後にメッセージが届いた接続のもの）
+    pub fn active_transport(&self) -> Option<Transport> {
+        self.sessions.lock().unwrap().iter().max_by_key(|session| session.last_activity).map(|session| session.info.transport)
+    }
+
+    /// 一覧をまとめて置き換える（シンクライアントがデーモンの一覧を写すとき）
+    pub fn replace_all(&self, infos: Vec<SessionInfo>) {
+        let now = Instant::now();
+        let before = |ms: u64| now.checked_sub(Duration::from_millis(ms)).unwrap_or(now);
+        *self.sessions.lock().unwrap() = infos
+            .into_iter()
+            .map(|info| Session { connected_at: before(info.connected_ms), last_activity: before(info.idle_ms), info })
+            .collect();
+    }
+
+    /// 接続に操作を頼む（その接続がなければ false）
+    pub fn request(&self, id: u64, control: SessionControl) -> bool {
+        if !self.sessions.lock().unwrap().iter().any(|session| session.info.id == id) {
+            return false;
+        }
+        self.controls.lock().unwrap().push((id, control));
+        true
+    }
+
+    /// 接続 `id` に頼まれた操作を受け取る
+    pub fn take_controls(&self, id: u64) -> Vec<SessionControl> {
+        let mut controls = self.controls.lock().unwrap();
+        let (taken, rest): (Vec<_>, Vec<_>) = controls.drain(..).partition(|(session, _)| *session == id);
+        *controls = rest;
+        taken.into_iter().map(|(_, control)| control).collect()
+    }
+
+    /// 頼まれたすべての操作を受け取る（シンクライアントがデーモンに転送するとき）
+    pub fn take_all_controls(&self) -> Vec<(u64, SessionControl)> {
+        std::mem::take(&mut *self.controls.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions_and_controls() {
+        let sessions = ExtensionSessions::new();
+        let chrome = sessions.open(Transport::WebSocket, Some("127.0.0.1:51000".to_string()));
+        let edge = sessions.open(Transport::WebSocket, None);
+        sessions.identify(chrome, Some("Chrome".to_string()), Some("Work".to_string()));
+
+        let list = sessions.list();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].label(), "Chrome - Work");
+        assert_eq!(list[1].label(), "Unknown browser");
+        assert_eq!(sessions.active_transport(), Some(Transport::WebSocket));
+
+        assert!(sessions.request(chrome, SessionControl::RefreshTabs));
+        assert!(sessions.request(edge, SessionControl::Disconnect));
+        assert!(!sessions.request(99, SessionControl::Disconnect));
+        assert_eq!(sessions.take_controls(chrome), vec![SessionControl::RefreshTabs]);
+        assert!(sessions.take_controls(chrome).is_empty());
+
+        sessions.close(edge);
+        assert_eq!(sessions.list().len(), 1);
+        assert!(sessions.take_all_controls().is_empty());
+    }
+
+    #[test]
+    fn test_serves_matches_browser_and_profile() {
+        let sessions = ExtensionSessions::new();
+        let work = sessions.open(Transport::WebSocket, None);
+        let personal = sessions.open(Transport::NativeMessaging, None);
+        sessions.identify(work, Some("Google Chrome".to_string()), Some("Profile 1".to_string()));
+        sessions.identify(personal, Some("Google Chrome".to_string()), None);
+
+        assert!(sessions.serves("Chrome", "Profile 1"));
+        assert!(sessions.serves("Chrome", "Default"));
+        assert!(!sessions.serves("Chrome", "Profile 2"));
+        assert!(!sessions.serves("Wavebox", "Default"));
+
+        sessions.close(work);
+        assert!(!sessions.serves("Chrome", "Profile 1"));
+    }
+
+    #[test]
+    fn test_replace_all_keeps_ages() {
+        let sessions = ExtensionSessions::new();
+        let info = SessionInfo {
+            id: 3,
+            transport: Transport::NativeMessaging,
+            browser: Some("Chrome".to_string()),
+            profile: None,
+            peer: None,
+            connected_ms: 60_000,
+            idle_ms: 5_000,
+        };
+        sessions.replace_all(vec![info]);
+        assert_eq!(sessions.active_transport(), Some(Transport::NativeMessaging));
+
+        let list = sessions.list();
+        assert!(list[0].connected_ms >= 60_000);
+        assert!((5_000..10_000).contains(&list[0].idle_ms));
+        assert!(sessions.request(3, SessionControl::RefreshTabs));
+        assert_eq!(sessions.take_all_controls(), vec![(3, SessionControl::RefreshTabs)]);
+    }
+}
@@ -0,0 +1,73 @@
+//! 検索元（ウィンドウ・タブ・ブックマーク・履歴）の状態
+//!
+//! 検索元は検索のたびに `HealthRegistry` へ状態を書き込み、UI のヘッダーの点と診断画面で表示する。
+
+use std::sync::{Arc, Mutex};
+
+/// ヘッダーに表示する検索元（この順に並べる）
+pub const SOURCES: [&str; 4] = ["windows", "tabs", "bookmarks", "history"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthState {
+    Healthy,
+    /// 使えるが結果が欠けているかもしれない（履歴のロック、拡張機能の更新が止まっているなど）
+    Degraded(String),
+    /// 遅い検索が続いたため結果を減らしているか、しばらく止めている（`source_watchdog.rs`）
+    Throttled(String),
+    /// 設定で無効にしている
+    Disabled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceHealth {
+    pub name: String,
+    pub state: HealthState,
+}
+
+/// 検索元の状態の一覧（スレッド間で共有する）
+#[derive(Debug, Clone, Default)]
+pub struct HealthRegistry(Arc<Mutex<Vec<SourceHealth>>>);
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 同じ名前の状態を置き換える（なければ追加）
+    pub fn report(&self, name: &str, state: HealthState) {
+        let mut sources = self.0.lock().unwrap();
+        match sources.iter_mut().find(|source| source.name == name) {
+            Some(existing) => existing.state = state,
+            None => sources.push(SourceHealth { name: name.to_string(), state }),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<HealthState> {
+        self.0.lock().unwrap().iter().find(|source| source.name == name).map(|source| source.state.clone())
+    }
+
+    /// 報告済みの状態を `SOURCES` の順に返す（それ以外の検索元は後ろに報告順で並べる）
+    pub fn all(&self) -> Vec<SourceHealth> {
+        let mut sources = self.0.lock().unwrap().clone();
+        sources.sort_by_key(|source| SOURCES.iter().position(|name| *name == source.name).unwrap_or(SOURCES.len()));
+        sources
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_replaces_and_orders() {
+        let registry = HealthRegistry::new();
+        registry.report("history", HealthState::Degraded("database is locked".to_string()));
+        registry.report("windows", HealthState::Healthy);
+        registry.report("history", HealthState::Healthy);
+
+        let names: Vec<_> = registry.all().into_iter().map(|source| source.name).collect();
+        assert_eq!(names, vec!["windows", "history"]);
+        assert_eq!(registry.get("history"), Some(HealthState::Healthy));
+        assert_eq!(registry.get("tabs"), None);
+    }
+}
@@ -0,0 +1,180 @@
+//! クエリの書き方の早見表（`?` キーワード / F1）
+//!
+//! キーワードとコマンドは提供元のレジストリの `help` から作るので、提供元を追加すると自動で載る。
+//! 検索演算子・接頭辞・ショートカットはここで一覧にする。
+
+use super::browser_search_engine::TAB_SEARCH_PREFIX;
+use super::keyword_provider::KeywordHelp;
+use super::provider_registry::ProviderRegistry;
+use super::query::{strip_keyword, SCOPE_FIELDS};
+use super::search_engine::{COMMAND_PREFIX, WINDOW_PREFIX};
+
+/// 早見表を開くキーワード（`? timer` なら `timer` で絞り込んで開く）
+pub const HELP_KEYWORD: &str = "?";
+
+/// 検索演算子（`title:` などのフィールドは `SCOPE_FIELDS` から作る）
+const OPERATORS: &[KeywordHelp] = &[
+    KeywordHelp::new("\"exact phrase\"", "Match the words next to each other"),
+    KeywordHelp::new("-word", "Exclude results containing the word"),
+    KeywordHelp::new("type:bookmark|history|tab|pinned|reading", "Only one kind of result"),
+    KeywordHelp::new("after:<date> / before:<date>", "History last visited in a period, e.g. after:2024-01-01"),
+    KeywordHelp::new("within:<n>h|d|w", "History last visited recently, e.g. within:7d"),
+    KeywordHelp::new("<query>?", "Put the Google search first"),
+    KeywordHelp::new("<query>; <query>", "Search each part and merge the results"),
+    KeywordHelp::new("on:<n> / on:primary", "Windows on a monitor (Windows mode)"),
+    KeywordHelp::new("min:", "Minimized windows only (Windows mode)"),
+    KeywordHelp::new("left: / right: / top: / bottom:", "Windows in that half of their monitor (Windows mode)"),
+];
+
+/// ランチャーのショートカット
+const SHORTCUTS: &[KeywordHelp] = &[
+    KeywordHelp::new("F1", "Open this cheat sheet"),
+    KeywordHelp::new("Tab", "Cycle modes (Windows / Browser / Tabs)"),
+    KeywordHelp::new("↑ / ↓, Home / End", "Move the selection"),
+    KeywordHelp::new("Enter", "Open the selected result"),
+    KeywordHelp::new("Shift+Enter", "Paste the selected URL into the previous window"),
+    KeywordHelp::new("Shift+Delete", "Delete the selected bookmark"),
+    KeywordHelp::new("F2", "Rename the selected bookmark"),
+    KeywordHelp::new("Ctrl+1..9", "Toggle filters (Browser) / select a workspace (Windows)"),
+    KeywordHelp::new("Alt+1..5", "Only tabs / bookmarks / history / search / windows (Browser)"),
+    KeywordHelp::new("Ctrl+S", "Cycle the sort order (Browser)"),
+    KeywordHelp::new("Ctrl+I", "Show details of the selected result"),
+    KeywordHelp::new("Ctrl+Shift+E", "Export the results"),
+    KeywordHelp::new("Alt+← / Alt+→", "Collapse / expand groups"),
+    KeywordHelp::new("Ctrl+P", "Settings"),
+    KeywordHelp::new("F12", "Frame time overlay"),
+    KeywordHelp::new("Esc", "Close"),
+];
+
+/// 早見表の見出し
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpSection {
+    Keywords,
+    Operators,
+    Prefixes,
+    Shortcuts,
+}
+
+impl HelpSection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HelpSection::Keywords => "Keywords and commands",
+            HelpSection::Operators => "Search operators",
+            HelpSection::Prefixes => "Prefixes",
+            HelpSection::Shortcuts => "Keyboard shortcuts",
+        }
+    }
+}
+
+/// 早見表の 1 行
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpEntry {
+    pub section: HelpSection,
+    pub syntax: String,
+    pub description: String,
+    /// 提供元の名前（キーワードとコマンドのみ）
+    pub provider: Option<&'static str>,
+    /// 提供元が無効にされていれば false
+    pub enabled: bool,
+}
+
+impl HelpEntry {
+    fn new(section: HelpSection, syntax: impl Into<String>, description: impl Into<String>) -> Self {
+        Self { section, syntax: syntax.into(), description: description.into(), provider: None, enabled: true }
+    }
+
+    /// 空白で区切ったすべての語を含むか（大文字小文字は区別しない）
+    pub fn matches(&self, filter: &str) -> bool {
+        let text = format!("{} {} {}", self.syntax, self.description, self.provider.unwrap_or("")).to_lowercase();
+        filter.split_whitespace().all(|term| text.contains(&term.to_lowercase()))
+    }
+}
+
+/// `?` / `? <絞り込み>` なら絞り込みの語を返す
+pub fn parse_help(query: &str) -> Option<&str> {
+    strip_keyword(query, HELP_KEYWORD)
+}
+
+/// 早見表のすべての行（見出しの順）
+pub fn cheat_sheet(registry: &ProviderRegistry) -> Vec<HelpEntry> {
+    let mut entries = Vec::new();
+
+    for info in registry.providers() {
+        let enabled = registry.is_enabled(info.name);
+        let keyword_entry = |syntax: &str, description: &str| HelpEntry {
+            provider: Some(info.name),
+            enabled,
+            ..HelpEntry::new(HelpSection::Keywords, syntax, description)
+        };
+        if info.help.is_empty() {
+            // 使い方を書いていない提供元もキーワードだけは載せる
+            entries.extend(info.keywords.iter().map(|keyword| keyword_entry(keyword, "")));
+        } else {
+            entries.extend(info.help.iter().map(|help| keyword_entry(help.syntax, help.description)));
+        }
+    }
+
+    entries.extend(SCOPE_FIELDS.iter().map(|field| {
+        HelpEntry::new(HelpSection::Operators, format!("{}:<text>", field), format!("Search only the {} field", field))
+    }));
+    entries.extend(OPERATORS.iter().map(|help| HelpEntry::new(HelpSection::Operators, help.syntax, help.description)));
+
+    entries.push(HelpEntry::new(
+        HelpSection::Prefixes,
+        format!("{}<query>", TAB_SEARCH_PREFIX),
+        "Search only open tabs (Browser mode)",
+    ));
+    entries.push(HelpEntry::new(HelpSection::Prefixes, format!("{}<query>", WINDOW_PREFIX), "Search windows (General mode)"));
+    entries.push(HelpEntry::new(HelpSection::Prefixes, format!("{}<command>", COMMAND_PREFIX), "Run in a shell (General mode)"));
+    entries.push(HelpEntry::new(HelpSection::Prefixes, format!("{} [filter]", HELP_KEYWORD), "Open this cheat sheet"));
+
+    entries.extend(SHORTCUTS.iter().map(|help| HelpEntry::new(HelpSection::Shortcuts, help.syntax, help.description)));
+    entries
+}
+
+/// 絞り込んだ早見表
+pub fn search(registry: &ProviderRegistry, filter: &str) -> Vec<HelpEntry> {
+    cheat_sheet(registry).into_iter().filter(|entry| entry.matches(filter)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SearchMode;
+
+    fn registry() -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register_builtin("timers", &["timer"], &[SearchMode::Browser], &[KeywordHelp::new("timer <duration>", "Start a timer")]);
+        registry.register_builtin("echo", &["echo", "say"], &[SearchMode::Browser], &[]);
+        registry
+    }
+
+    #[test]
+    fn test_cheat_sheet_from_registry() {
+        let registry = registry();
+        registry.set_enabled("echo", false);
+        let keywords: Vec<_> = cheat_sheet(&registry).into_iter().filter(|entry| entry.section == HelpSection::Keywords).collect();
+
+        assert_eq!(keywords.iter().map(|entry| entry.syntax.as_str()).collect::<Vec<_>>(), vec!["timer <duration>", "echo", "say"]);
+        assert_eq!(keywords[0].provider, Some("timers"));
+        assert!(keywords[0].enabled);
+        assert!(!keywords[1].enabled);
+    }
+
+    #[test]
+    fn test_search_filters_every_section() {
+        let registry = registry();
+        assert_eq!(search(&registry, "TIMER start").len(), 1);
+        assert!(search(&registry, "title:").iter().any(|entry| entry.section == HelpSection::Operators));
+        assert!(search(&registry, "ctrl+s").iter().all(|entry| entry.section == HelpSection::Shortcuts));
+        assert_eq!(search(&registry, "").len(), cheat_sheet(&registry).len());
+    }
+
+    #[test]
+    fn test_parse_help() {
+        assert_eq!(parse_help("?"), Some(""));
+        assert_eq!(parse_help("? timer"), Some("timer"));
+        assert_eq!(parse_help("rust lifetimes?"), None);
+        assert_eq!(parse_help("?foo"), None);
+    }
+}
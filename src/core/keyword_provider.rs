@@ -0,0 +1,65 @@
+use super::confirmation::ConfirmableAction;
+use super::{SearchMode, SearchResult};
+use std::error::Error;
+
+/// キーワードの使い方（`?` / F1 の早見表に表示する）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeywordHelp {
+    /// 入力の形（例: `timer <duration> [label]`）
+    pub syntax: &'static str,
+    pub description: &'static str,
+}
+
+impl KeywordHelp {
+    pub const fn new(syntax: &'static str, description: &'static str) -> Self {
+        Self { syntax, description }
+    }
+}
+
+/// キーワードで呼び出す検索結果の提供元（例: `display`）
+///
+/// 検索結果のアクションには `Action::ProviderCommand` を使い、
+/// 実行時は `name` が一致するプロバイダーの `execute` にコマンドが渡される。
+pub trait KeywordProvider: Send + Sync {
+    /// プロバイダーの識別名
+    fn name(&self) -> &'static str;
+
+    /// 呼び出しキーワード
+    fn keywords(&self) -> &[&'static str];
+
+    /// 結果を返すモード
+    fn modes(&self) -> &[SearchMode] {
+        &[SearchMode::Browser, SearchMode::Tabs]
+    }
+
+    /// 早見表に表示する使い方（キーワードなしで解釈するクエリの形も含める）
+    fn help(&self) -> &[KeywordHelp] {
+        &[]
+    }
+
+    /// キーワードに続く引数に対する検索結果
+    fn search(&self, keyword: &str, args: &str) -> Vec<SearchResult>;
+
+    /// キーワードなしでクエリ全体を解釈できる場合の検索結果（例: `#ff8800`）
+    fn search_query(&self, _query: &str) -> Option<Vec<SearchResult>> {
+        None
+    }
+
+    /// コマンドを実行し、ステータスバーに表示するメッセージを返す
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>>;
+
+    /// 取り消せないコマンドなら、何が起きるかをまとめて返す（確認してから `execute` する）
+    fn preview(&self, _command: &str) -> Option<ConfirmableAction> {
+        None
+    }
+
+    /// バックグラウンドの処理が終わり、検索し直すと結果が変わるか（呼び出すとリセットされる）
+    fn take_updated(&self) -> bool {
+        false
+    }
+
+    /// バックグラウンドで処理中のものがあるか
+    fn is_pending(&self) -> bool {
+        false
+    }
+}
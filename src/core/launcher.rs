@@ -1,18 +1,34 @@
-use super::{SearchEngine, SearchResult, SearchMode, Action, WindowManager, WindowInfo};
+use super::{SearchEngine, SearchResult, SearchMode, Action, WindowManager, WindowInfo, Query};
+use super::action_rewriter::ActionRewriter;
+use super::confirmation::ConfirmableAction;
+use super::window_mru::WindowMru;
+use crate::config::HotkeyAction;
 use std::sync::Arc;
 
-pub struct LauncherCore<S: SearchEngine, W: WindowManager> {
+pub struct LauncherCore<S: SearchEngine, W: WindowManager + ?Sized> {
     search_engine: S,
     window_manager: Arc<W>,
     cached_windows: Vec<WindowInfo>,
+    /// 最近使ったウィンドウの順（列挙のたびに更新し、切り替えたら先頭にする）
+    mru: WindowMru,
+    /// 確認のオーバーレイに表示中の操作
+    pending_confirmation: Option<ConfirmableAction>,
+    /// 確認済みで、次に実行するときは確認しない操作
+    confirmed: Option<Action>,
+    /// 実行する前に操作を置き換える処理（アプリで開く URL など）
+    rewriters: Vec<Box<dyn ActionRewriter>>,
 }
 
-impl<S: SearchEngine, W: WindowManager> LauncherCore<S, W> {
+impl<S: SearchEngine, W: WindowManager + ?Sized> LauncherCore<S, W> {
     pub fn new(search_engine: S, window_manager: Arc<W>) -> Self {
         let mut core = Self {
             search_engine,
             window_manager,
             cached_windows: Vec::new(),
+            mru: WindowMru::new(),
+            pending_confirmation: None,
+            confirmed: None,
+            rewriters: Vec::new(),
         };
         core.refresh_windows();
         core
@@ -20,25 +36,94 @@ impl<S: SearchEngine, W: WindowManager> LauncherCore<S, W> {
 
     pub fn refresh_windows(&mut self) {
         self.cached_windows = self.window_manager.enumerate_windows();
+        self.mru.observe(&self.cached_windows);
+    }
+
+    /// 最近使ったウィンドウ `count` 個（検索はしない）
+    pub fn recent_windows(&self, count: usize) -> Vec<WindowInfo> {
+        self.mru.recent(&self.cached_windows, count).into_iter().cloned().collect()
     }
 
     pub fn search(&self, query: &str, mode: SearchMode) -> Vec<SearchResult> {
         self.search_engine.search(query, mode, &self.cached_windows)
     }
 
+    pub fn search_parsed(&self, query: &Query, mode: SearchMode) -> Vec<SearchResult> {
+        self.search_engine.search_parsed(query, mode, &self.cached_windows)
+    }
+
+    /// 実行する前に操作を置き換える処理を追加する
+    pub fn with_rewriter(mut self, rewriter: Box<dyn ActionRewriter>) -> Self {
+        self.rewriters.push(rewriter);
+        self
+    }
+
+    /// 最初に一致した置き換え処理で操作を置き換える
+    pub fn rewrite(&self, action: &Action) -> Option<Action> {
+        self.rewriters.iter().find_map(|rewriter| rewriter.rewrite(action))
+    }
+
     pub fn execute_action(&self, action: &Action) {
+        let rewritten = self.rewrite(action);
+        let action = rewritten.as_ref().unwrap_or(action);
         match action {
             Action::SwitchWindow(hwnd) => {
                 self.window_manager.switch_to_window(*hwnd);
+                self.mru.touch(*hwnd);
             }
             Action::GoogleSearch(query) => {
                 let encoded_query = urlencoding::encode(query);
                 let url = format!("https://www.google.com/search?q={}", encoded_query);
                 let _ = open::that(&url);
             }
-            Action::OpenBookmark(url) | Action::OpenHistory(url) => {
+            Action::OpenBookmark(url) | Action::OpenHistory(url) | Action::OpenUrl(url) => {
                 let _ = open::that(url);
             }
+            Action::RestoreClosedTab { url, .. } => {
+                // 拡張機能で開き直すのは LauncherApp 側。ここでは URL を新しく開くだけにする
+                let _ = open::that(url);
+            }
+            Action::OpenInProfile { profile, url } => {
+                if let Err(e) = crate::data::browser_provider::open_in_profile(profile, url) {
+                    log::warn!("Failed to open {} in {} ({}), using the default browser", url, profile, e);
+                    let _ = open::that(url);
+                }
+            }
+            Action::OpenInApp { app, target } => {
+                let opened = match app {
+                    Some(app) => std::process::Command::new(app).arg(target).spawn().map(|_| ()),
+                    None => open::that(target),
+                };
+                if let Err(e) = opened {
+                    log::warn!("Failed to open {} in {}: {}", target, app.as_deref().unwrap_or("its app"), e);
+                }
+            }
+            Action::RunCommand(command) => {
+                if let Err(e) = shell_command(command).spawn() {
+                    log::warn!("Failed to run {}: {}", command, e);
+                }
+            }
+            Action::CloseDuplicateTabs
+            | Action::OpenTabsOverview
+            | Action::ClearCaches
+            | Action::CreateBookmark { .. }
+            | Action::AppendNote(_)
+            | Action::OpenNote { .. }
+            | Action::StartTimer(_)
+            | Action::CancelTimer(_)
+            | Action::SaveLayout(_)
+            | Action::RestoreLayout(_)
+            | Action::DeleteLayout(_)
+            | Action::RestoreSession
+            | Action::AssignTag(_)
+            | Action::RemoveTag(_)
+            | Action::ProviderCommand { .. }
+            | Action::CopyToClipboard(_)
+            | Action::RunMacro(_)
+            | Action::DeleteBookmark { .. } => {
+                // TabManager を持つ LauncherApp 側で処理する
+                log::info!("{:?} is handled by LauncherApp", action);
+            }
             Action::SwitchToTab { tab_id, window_id } => {
                 // Queue the command to be picked up by the native host
                 log::info!("Queueing tab switch: tab_id={}, window_id={}", tab_id, window_id);
@@ -59,11 +144,90 @@ impl<S: SearchEngine, W: WindowManager> LauncherCore<S, W> {
         }
     }
 
+    /// 確認が必要な操作なら確認待ちにして `true` を返す（確認済みの操作と、確認の要らない操作は `false`）
+    pub fn request_confirmation(&mut self, action: &Action) -> bool {
+        if self.confirmed.as_ref() == Some(action) {
+            self.confirmed = None;
+            return false;
+        }
+        self.confirmed = None;
+        self.pending_confirmation = self.search_engine.preview(action, &self.cached_windows);
+        self.pending_confirmation.is_some()
+    }
+
+    pub fn pending_confirmation(&self) -> Option<&ConfirmableAction> {
+        self.pending_confirmation.as_ref()
+    }
+
+    /// 確認待ちの操作を確認済みにする（続けて `request_confirmation` を通して実行する）
+    pub fn confirm(&mut self) {
+        self.confirmed = self.pending_confirmation.take().map(|confirmable| confirmable.action);
+    }
+
+    pub fn cancel_confirmation(&mut self) {
+        self.pending_confirmation = None;
+    }
+
+    /// ホットキーに割り当てた操作を、ランチャーのウィンドウを開かずに実行する（実行した内容を返す）
+    ///
+    /// タブの切り替えなど `LauncherApp` でしか実行できない結果は実行しない。
+    pub fn execute_hotkey(&mut self, hotkey: &HotkeyAction) -> Result<String, String> {
+        self.refresh_windows();
+        let (action, done) = match hotkey {
+            HotkeyAction::SwitchWindow { process, title } => {
+                let window = self
+                    .cached_windows
+                    .iter()
+                    .find(|window| {
+                        window.process_name.eq_ignore_ascii_case(process)
+                            && title.as_ref().is_none_or(|title| window.title.to_lowercase().contains(&title.to_lowercase()))
+                    })
+                    .ok_or_else(|| format!("No window of {} is open", process))?;
+                (Action::SwitchWindow(window.hwnd), format!("Switched to {}", window.title))
+            }
+            HotkeyAction::OpenUrl { url } => (Action::OpenUrl(url.clone()), format!("Opened {}", url)),
+            HotkeyAction::RecentWindows => return Err("The recent windows list opens in its own window".to_string()),
+            HotkeyAction::Search { query, mode } => {
+                let result = self
+                    .search(query, *mode)
+                    .into_iter()
+                    .find(|result| !matches!(result.action, Action::GoogleSearch(_)))
+                    .ok_or_else(|| format!("No results for '{}'", query))?;
+                (result.action, format!("Opened {}", result.title))
+            }
+        };
+        
+        if !action.is_headless() {
+            return Err(format!("{:?} needs the launcher window", action));
+        }
+        self.execute_action(&action);
+        Ok(done)
+    }
+
+    pub fn search_engine(&self) -> &S {
+        &self.search_engine
+    }
+
     pub fn get_cached_windows(&self) -> &[WindowInfo] {
         &self.cached_windows
     }
 }
 
+/// 入力したコマンドをシェルで実行する（Windows では出力が読めるようコンソールを開いたままにする）
+#[cfg(windows)]
+fn shell_command(command: &str) -> std::process::Command {
+    let mut shell = std::process::Command::new("cmd");
+    shell.arg("/K").arg(command);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> std::process::Command {
+    let mut shell = std::process::Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,6 +244,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 800, 600),
+                requires_elevation: false,
             },
             WindowInfo {
                 hwnd: 2,
@@ -89,6 +254,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: true,
                 rect: (100, 100, 1024, 768),
+                requires_elevation: false,
             },
         ];
 
@@ -132,6 +298,16 @@ mod tests {
         assert_eq!(window_manager.get_switched_window(), Some(42));
     }
 
+    #[test]
+    fn test_launcher_recent_windows() {
+        let (launcher, _) = create_test_launcher();
+        let hwnds = |windows: Vec<WindowInfo>| windows.iter().map(|window| window.hwnd).collect::<Vec<_>>();
+        assert_eq!(hwnds(launcher.recent_windows(5)), vec![1, 2]);
+
+        launcher.execute_action(&Action::SwitchWindow(2));
+        assert_eq!(hwnds(launcher.recent_windows(1)), vec![2]);
+    }
+
     #[test]
     fn test_launcher_refresh_windows() {
         let (mut launcher, window_manager) = create_test_launcher();
@@ -149,6 +325,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 640, 480),
+                requires_elevation: false,
             },
         ];
         window_manager.set_windows(new_windows);
@@ -159,4 +336,60 @@ mod tests {
         assert_eq!(launcher.get_cached_windows()[0].title, "New Window");
     }
 
-}
\ No newline at end of file
+
+    #[test]
+    fn test_execute_hotkey() {
+        let (mut launcher, window_manager) = create_test_launcher();
+        
+        let hotkey = HotkeyAction::SwitchWindow { process: "BROWSER.exe".to_string(), title: None };
+        assert_eq!(launcher.execute_hotkey(&hotkey), Ok("Switched to Browser".to_string()));
+        assert_eq!(window_manager.get_switched_window(), Some(2));
+        
+        let hotkey = HotkeyAction::SwitchWindow { process: "editor.exe".to_string(), title: Some("other".to_string()) };
+        assert!(launcher.execute_hotkey(&hotkey).is_err());
+        
+        // 検索の先頭の結果を実行する（Web検索の行は飛ばす）
+        let hotkey = HotkeyAction::Search { query: "editor".to_string(), mode: SearchMode::Windows };
+        assert_eq!(launcher.execute_hotkey(&hotkey), Ok("Opened Test Editor".to_string()));
+        assert_eq!(window_manager.get_switched_window(), Some(1));
+        let hotkey = HotkeyAction::Search { query: "editor".to_string(), mode: SearchMode::Browser };
+        assert!(launcher.execute_hotkey(&hotkey).is_err());
+    }
+
+    /// 重複タブを閉じる操作だけ確認する検索エンジン
+    struct ConfirmingEngine;
+
+    impl SearchEngine for ConfirmingEngine {
+        fn search_parsed(&self, _query: &Query, _mode: SearchMode, _windows: &[WindowInfo]) -> Vec<SearchResult> {
+            Vec::new()
+        }
+
+        fn is_window_search(&self, _query: &str, _mode: SearchMode) -> bool {
+            false
+        }
+
+        fn preview(&self, action: &Action, _windows: &[WindowInfo]) -> Option<ConfirmableAction> {
+            (*action == Action::CloseDuplicateTabs)
+                .then(|| ConfirmableAction::new(action.clone(), "Close 1 duplicate tab", vec!["Docs".to_string()]))
+        }
+    }
+
+    #[test]
+    fn test_confirmation() {
+        let mut launcher = LauncherCore::new(ConfirmingEngine, Arc::new(MockWindowManager::new(Vec::new())));
+
+        assert!(!launcher.request_confirmation(&Action::OpenUrl("https://example.com".to_string())));
+        assert!(launcher.request_confirmation(&Action::CloseDuplicateTabs));
+        assert_eq!(launcher.pending_confirmation().unwrap().effects, vec!["Docs"]);
+
+        launcher.cancel_confirmation();
+        assert!(launcher.pending_confirmation().is_none());
+
+        assert!(launcher.request_confirmation(&Action::CloseDuplicateTabs));
+        launcher.confirm();
+        assert!(launcher.pending_confirmation().is_none());
+        // 確認したあとの1回だけはそのまま実行する
+        assert!(!launcher.request_confirmation(&Action::CloseDuplicateTabs));
+        assert!(launcher.request_confirmation(&Action::CloseDuplicateTabs));
+    }
+}
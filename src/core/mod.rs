@@ -1,11 +1,40 @@
 pub mod search_engine;
 pub mod window_manager;
+pub mod window_enumerator;
 pub mod launcher;
 pub mod browser_search_engine;
 pub mod native_messaging;
+pub mod tab_dedup;
+pub mod tab_overview;
+pub mod chrome_window_map;
+pub mod keyword_provider;
+pub mod result_composer;
+pub mod result_filter;
+pub mod query;
+pub mod timed_source;
+pub mod provider_registry;
+pub mod result_export;
+pub mod confirmation;
+pub mod health;
+pub mod action_rewriter;
+pub mod title_history;
+pub mod window_fingerprint;
+pub mod window_mru;
+pub mod auto_hide;
+pub mod extension_commands;
+pub mod recently_closed;
+pub mod reading_list;
+pub mod empty_state;
+pub mod source_watchdog;
+pub mod result_cache;
+pub mod result_sort;
+pub mod privacy;
+pub mod extension_sessions;
+pub mod help;
 
-pub use search_engine::{SearchEngine, SearchResult, SearchMode, Action, ResultType};
+pub use search_engine::{SearchEngine, SearchResult, SearchMode, Action, ResultType, ResultMetadata};
 pub use window_manager::{WindowManager, WindowInfo};
 pub use launcher::LauncherCore;
 pub use browser_search_engine::BrowserSearchEngine;
-pub use native_messaging::{TabManager, ChromeTab};
\ No newline at end of file
+pub use query::Query;
+pub use native_messaging::{TabManager, ChromeTab, TabFreshness};
\ No newline at end of file
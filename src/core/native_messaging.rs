@@ -1,8 +1,20 @@
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use crate::core::extension_sessions::ExtensionSessions;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 既定でタブ一覧を古いとみなすまでの時間（拡張機能は30秒ごとに keepAlive を送る）
+pub const DEFAULT_STALE_AFTER: Duration = Duration::from_secs(90);
+/// 既定でタブ一覧を捨てるまでの時間
+pub const DEFAULT_DROP_AFTER: Duration = Duration::from_secs(600);
+
+/// 拡張機能へ送るのを待つコマンドの上限（超えたら古いものから捨てる）
+pub const MAX_QUEUED_COMMANDS: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChromeTab {
     pub id: i32,
     pub window_id: i32,
@@ -11,40 +23,355 @@ pub struct ChromeTab {
     pub fav_icon_url: String,
     pub active: bool,
     pub index: i32,
+    /// ピン留めしたタブ（古い拡張機能は送ってこない）
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// 最近閉じたタブ（拡張機能の `chrome.sessions.getRecentlyClosed`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClosedTab {
+    /// 復元に使う ID（`chrome.sessions.restore`）
+    pub session_id: String,
+    pub title: String,
+    pub url: String,
+    /// 閉じた時刻（UNIX 秒）
+    #[serde(default)]
+    pub closed_at: Option<i64>,
+}
+
+/// リーディングリストの項目（拡張機能の `chrome.readingList.query`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadingListEntry {
+    pub title: String,
+    pub url: String,
+    #[serde(default)]
+    pub has_been_read: bool,
+    /// 追加した時刻（UNIX 秒）
+    #[serde(default)]
+    pub created_at: Option<i64>,
+}
+
+/// タブ一覧が変わったことを受け取る側（UI など）
+struct ChangeListener {
+    sender: Sender<u64>,
+    /// 送ったあとに呼ぶ（UI の再描画など）
+    wake: Box<dyn Fn() + Send + Sync>,
+}
+
+#[derive(Default)]
+struct ChangeListeners(Mutex<Vec<ChangeListener>>);
+
+impl ChangeListeners {
+    /// 新しい revision を送る（受け取る側がいなくなっていれば外す）
+    fn notify(&self, revision: u64) {
+        self.0.lock().unwrap().retain(|listener| {
+            let alive = listener.sender.send(revision).is_ok();
+            if alive {
+                (listener.wake)();
+            }
+            alive
+        });
+    }
+}
+
+impl std::fmt::Debug for ChangeListeners {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangeListeners").field("count", &self.0.lock().map(|listeners| listeners.len()).unwrap_or(0)).finish()
+    }
 }
 
 #[derive(Debug)]
 pub struct TabManager {
-    tabs: Arc<Mutex<Vec<ChromeTab>>>,
+    /// 読む側は `Arc` を受け取るだけで、書く側は新しい一覧に置き換える（読んでいる一覧は変わらない）
+    tabs: Arc<RwLock<Arc<Vec<ChromeTab>>>>,
     command_queue: Arc<Mutex<VecDeque<ChromeCommand>>>,
+    /// 接続中の拡張機能の数
+    extension_connections: Arc<AtomicUsize>,
+    /// 最後にタブ一覧の更新か keepAlive を受け取った時刻
+    last_update: Arc<Mutex<Option<Instant>>>,
+    stale_after: Duration,
+    drop_after: Duration,
+    /// 一覧が前回保存したスナップショットのまま（拡張機能からまだ全体の一覧が届いていない）
+    restored: Arc<AtomicBool>,
+    /// 一覧が変わるたびに増える番号（スナップショットを保存するかの判断に使う）
+    revision: Arc<AtomicU64>,
+    /// 接続中の拡張機能の一覧
+    sessions: ExtensionSessions,
+    /// 一覧が変わったことを知らせる先
+    listeners: Arc<ChangeListeners>,
+    /// 最近閉じたタブ（新しいものから）
+    recently_closed: Arc<RwLock<Arc<Vec<ClosedTab>>>>,
+    /// リーディングリスト（新しく追加したものから）
+    reading_list: Arc<RwLock<Arc<Vec<ReadingListEntry>>>>,
+}
+
+/// 拡張機能から届いたタブ一覧の新しさ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabFreshness {
+    Fresh,
+    /// しばらく更新がない（切り替えに失敗するかもしれない）
+    Stale,
+    /// 長く更新がないので一覧を使わない
+    Expired,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ChromeCommand {
     SwitchToTab { tab_id: i32, window_id: i32 },
+    CloseTab { tab_id: i32 },
+    /// ブックマークを作成（folder はブックマークバーからのパス、省略時は「その他のブックマーク」）
+    CreateBookmark { url: String, title: String, folder: Option<String> },
+    /// ID が一致するブックマークを 1 件削除
+    DeleteBookmark { id: String },
+    /// URLが一致するブックマークの名前を変更
+    RenameBookmark { url: String, title: String },
+    /// まとめてタブを閉じる
+    CloseTabs { tab_ids: Vec<i32> },
+    /// タブを新しいウィンドウにまとめて移す
+    MoveTabsToNewWindow { tab_ids: Vec<i32> },
+    /// 最近閉じたタブの一覧を送ってもらう（`updateRecentlyClosed` で返ってくる）
+    GetRecentlyClosed,
+    /// 閉じたタブを開き直す
+    RestoreClosedTab { session_id: String },
 }
 
 impl TabManager {
     pub fn new() -> Self {
         Self {
-            tabs: Arc::new(Mutex::new(Vec::new())),
+            tabs: Arc::new(RwLock::new(Arc::new(Vec::new()))),
             command_queue: Arc::new(Mutex::new(VecDeque::new())),
+            extension_connections: Arc::new(AtomicUsize::new(0)),
+            last_update: Arc::new(Mutex::new(None)),
+            stale_after: DEFAULT_STALE_AFTER,
+            drop_after: DEFAULT_DROP_AFTER,
+            restored: Arc::new(AtomicBool::new(false)),
+            revision: Arc::new(AtomicU64::new(0)),
+            sessions: ExtensionSessions::new(),
+            listeners: Arc::new(ChangeListeners::default()),
+            recently_closed: Arc::new(RwLock::new(Arc::new(Vec::new()))),
+            reading_list: Arc::new(RwLock::new(Arc::new(Vec::new()))),
         }
     }
+
+    /// タブ一覧を古いとみなす時間と、捨てる時間を設定
+    pub fn with_timeouts(mut self, stale_after: Duration, drop_after: Duration) -> Self {
+        self.stale_after = stale_after;
+        self.drop_after = drop_after.max(stale_after);
+        self
+    }
+    
+    /// Chrome拡張機能が接続されているか（タブ切り替えコマンドが届くか）
+    pub fn is_extension_connected(&self) -> bool {
+        self.extension_connections.load(Ordering::SeqCst) > 0
+    }
+    
+    pub fn extension_connected(&self) {
+        self.extension_connections.fetch_add(1, Ordering::SeqCst);
+    }
+    
+    pub fn extension_disconnected(&self) {
+        let _ = self.extension_connections.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+    }
+    
+    /// 接続中の拡張機能（ブラウザ・プロファイル・接続方法・最後の通信）
+    pub fn sessions(&self) -> &ExtensionSessions {
+        &self.sessions
+    }
+    
+    /// 接続状態を直接設定（デーモンから同期する場合）
+    pub fn set_extension_connected(&self, connected: bool) {
+        self.extension_connections.store(connected as usize, Ordering::SeqCst);
+    }
     
     pub fn update_tabs(&self, tabs: Vec<ChromeTab>) {
-        let mut tab_list = self.tabs.lock().unwrap();
-        *tab_list = tabs;
+        self.update_tabs_with_age(tabs, Some(Duration::ZERO));
+    }
+    
+    /// 受け取ってから `age` だけ経ったタブ一覧を設定（デーモンから同期する場合。`None` は未受信）
+    ///
+    /// 同じ一覧が届いたとき（定期的な全体の同期、シンクライアントの同期）は変更を知らせない。
+    pub fn update_tabs_with_age(&self, tabs: Vec<ChromeTab>, age: Option<Duration>) {
+        self.replace_tabs(tabs, age, false);
+    }
+
+    fn replace_tabs(&self, tabs: Vec<ChromeTab>, age: Option<Duration>, restored: bool) {
+        let mut tab_list = self.tabs.write().unwrap();
+        let replaced = **tab_list != tabs;
+        if replaced {
+            *tab_list = Arc::new(tabs);
+        }
+        *self.last_update.lock().unwrap() = age.map(|age| Instant::now().checked_sub(age).unwrap_or_else(Instant::now));
+        drop(tab_list);
+        let was_restored = self.restored.swap(restored, Ordering::SeqCst);
+        if replaced || was_restored != restored {
+            self.changed();
+        }
+    }
+    
+    /// 保存しておいた一覧を設定（全体の一覧が届くまで古いものとして扱う）
+    pub fn restore_tabs(&self, tabs: Vec<ChromeTab>, age: Option<Duration>) {
+        self.replace_tabs(tabs, age, true);
+    }
+    
+    pub fn is_restored(&self) -> bool {
+        self.restored.load(Ordering::SeqCst)
+    }
+    
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+    
+    /// 一覧が変わるたびに新しい revision を受け取る（`wake` は送ったあとに呼ばれる）
+    ///
+    /// 受け取る側を落とせば、次の変更で登録が外れる。
+    pub fn subscribe(&self, wake: impl Fn() + Send + Sync + 'static) -> Receiver<u64> {
+        let (sender, receiver) = mpsc::channel();
+        self.listeners.0.lock().unwrap().push(ChangeListener { sender, wake: Box::new(wake) });
+        receiver
+    }
+    
+    fn changed(&self) {
+        let revision = self.revision.fetch_add(1, Ordering::SeqCst) + 1;
+        self.listeners.notify(revision);
+    }
+    
+    /// 作成・変更されたタブを一覧に反映（アクティブになったタブがあれば同じウィンドウの他のタブは非アクティブにする）
+    pub fn upsert_tab(&self, tab: ChromeTab) {
+        self.edit_tabs(|tabs| {
+            if tab.active {
+                for other in tabs.iter_mut().filter(|other| other.window_id == tab.window_id) {
+                    other.active = false;
+                }
+            }
+            match tabs.iter_mut().find(|existing| existing.id == tab.id) {
+                Some(existing) => *existing = tab,
+                None => {
+                    // 途中に開いたタブでは後ろのタブの移動イベントが来ないので、ここでずらす
+                    let window_id = tab.window_id;
+                    for other in tabs.iter_mut().filter(|other| other.window_id == window_id && other.index >= tab.index) {
+                        other.index += 1;
+                    }
+                    tabs.push(tab);
+                    reindex_window(tabs, window_id);
+                }
+            }
+        });
+    }
+    
+    /// 閉じたタブを一覧から除き、同じウィンドウのタブの並び順を詰める
+    pub fn remove_tab(&self, tab_id: i32) {
+        self.edit_tabs(|tabs| {
+            if let Some(position) = tabs.iter().position(|tab| tab.id == tab_id) {
+                let removed = tabs.remove(position);
+                reindex_window(tabs, removed.window_id);
+            }
+        });
+    }
+    
+    /// タブを別の位置（別のウィンドウを含む）へ移動し、前後のウィンドウの並び順を振り直す
+    pub fn move_tab(&self, tab_id: i32, window_id: i32, index: i32) {
+        self.edit_tabs(|tabs| {
+            if let Some(position) = tabs.iter().position(|tab| tab.id == tab_id) {
+                let mut moved = tabs.remove(position);
+                reindex_window(tabs, moved.window_id);
+                // 移動先の index 以降のタブを後ろにずらしてから入れる
+                for tab in tabs.iter_mut().filter(|tab| tab.window_id == window_id && tab.index >= index) {
+                    tab.index += 1;
+                }
+                moved.window_id = window_id;
+                moved.index = index;
+                tabs.push(moved);
+                reindex_window(tabs, window_id);
+            }
+        });
+    }
+    
+    /// 一覧の写しを `edit` で変え、実際に変わったときだけ差し替えて知らせる（知らないタブのイベントや同じ内容の更新では知らせない）
+    fn edit_tabs(&self, edit: impl FnOnce(&mut Vec<ChromeTab>)) {
+        let mut guard = self.tabs.write().unwrap();
+        let mut tabs = guard.to_vec();
+        edit(&mut tabs);
+        let changed = tabs != **guard;
+        if changed {
+            *guard = Arc::new(tabs);
+        }
+        drop(guard);
+        if changed {
+            self.changed();
+        }
+        self.heartbeat();
+    }
+    
+    /// 拡張機能が生きていることを記録（タブが変わらなければ一覧は送られてこないため）
+    pub fn heartbeat(&self) {
+        *self.last_update.lock().unwrap() = Some(Instant::now());
     }
     
+    /// 最後にタブ一覧の更新か keepAlive を受け取ってからの時間
+    pub fn tabs_age(&self) -> Option<Duration> {
+        self.last_update.lock().unwrap().map(|at| at.elapsed())
+    }
+    
+    pub fn freshness(&self) -> TabFreshness {
+        match self.tabs_age() {
+            Some(age) if age >= self.drop_after => TabFreshness::Expired,
+            Some(age) if age >= self.stale_after => TabFreshness::Stale,
+            Some(_) if self.is_restored() => TabFreshness::Stale,
+            _ => TabFreshness::Fresh,
+        }
+    }
+    
+    /// 今のタブ一覧（長く更新がなければ空）。コピーせずに共有し、あとで一覧が変わってもこの内容は変わらない
+    pub fn snapshot(&self) -> Arc<Vec<ChromeTab>> {
+        if self.freshness() == TabFreshness::Expired {
+            return Arc::default();
+        }
+        Arc::clone(&self.tabs.read().unwrap())
+    }
+    
+    /// タブ一覧のコピー（長く更新がなければ空）。読むだけなら `snapshot` を使う
     pub fn get_tabs(&self) -> Vec<ChromeTab> {
-        self.tabs.lock().unwrap().clone()
+        self.snapshot().as_ref().clone()
+    }
+    
+    /// 最近閉じたタブ（新しいものから）
+    pub fn recently_closed(&self) -> Arc<Vec<ClosedTab>> {
+        Arc::clone(&self.recently_closed.read().unwrap())
+    }
+    
+    /// 最近閉じたタブの一覧を置き換える（変わったときだけ知らせる）
+    pub fn update_recently_closed(&self, tabs: Vec<ClosedTab>) {
+        let mut closed = self.recently_closed.write().unwrap();
+        if **closed == tabs {
+            return;
+        }
+        *closed = Arc::new(tabs);
+        drop(closed);
+        self.changed();
+    }
+    
+    /// リーディングリスト（新しく追加したものから）
+    pub fn reading_list(&self) -> Arc<Vec<ReadingListEntry>> {
+        Arc::clone(&self.reading_list.read().unwrap())
+    }
+    
+    /// リーディングリストを置き換える（変わったときだけ知らせる）
+    pub fn update_reading_list(&self, mut entries: Vec<ReadingListEntry>) {
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+        let mut reading_list = self.reading_list.write().unwrap();
+        if **reading_list == entries {
+            return;
+        }
+        *reading_list = Arc::new(entries);
+        drop(reading_list);
+        self.changed();
     }
     
     pub fn search_tabs(&self, query: &str) -> Vec<ChromeTab> {
-        let tabs = self.tabs.lock().unwrap();
+        let tabs = self.snapshot();
         if query.is_empty() {
-            return tabs.clone();
+            return tabs.as_ref().clone();
         }
         
         let query_lower = query.to_lowercase();
@@ -59,6 +386,12 @@ impl TabManager {
     
     pub fn queue_command(&self, command: ChromeCommand) {
         let mut queue = self.command_queue.lock().unwrap();
+        // 拡張機能が受け取らない間にたまり続けないようにする
+        if queue.len() >= MAX_QUEUED_COMMANDS {
+            if let Some(dropped) = queue.pop_front() {
+                log::warn!("Command queue is full, dropping {:?}", dropped);
+            }
+        }
         queue.push_back(command);
     }
     
@@ -68,6 +401,16 @@ impl TabManager {
     }
 }
 
+/// ウィンドウ内のタブの index を 0 から振り直す（Vec 上の順番はそのまま）
+fn reindex_window(tabs: &mut [ChromeTab], window_id: i32) {
+    let mut window_tabs: Vec<&mut ChromeTab> = tabs.iter_mut().filter(|tab| tab.window_id == window_id).collect();
+    window_tabs.sort_by_key(|tab| tab.index);
+    for (index, tab) in window_tabs.into_iter().enumerate() {
+        tab.index = index as i32;
+    }
+}
+
+#[cfg(feature = "native-host")]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum NativeMessage {
@@ -82,6 +425,7 @@ pub enum NativeMessage {
     },
 }
 
+#[cfg(feature = "native-host")]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "command")]
 pub enum NativeCommand {
@@ -94,4 +438,150 @@ pub enum NativeCommand {
         #[serde(rename = "windowId")]
         window_id: i32 
     },
-}
\ No newline at end of file
+    #[serde(rename = "closeTab")]
+    CloseTab {
+        #[serde(rename = "tabId")]
+        tab_id: i32,
+    },
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(id: i32) -> ChromeTab {
+        ChromeTab {
+            id,
+            window_id: 1,
+            title: format!("Tab {}", id),
+            url: format!("https://example.com/{}", id),
+            fav_icon_url: String::new(),
+            active: false,
+            index: id - 1,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_tab_freshness() {
+        let manager = TabManager::new().with_timeouts(Duration::from_secs(60), Duration::from_secs(300));
+        assert_eq!(manager.freshness(), TabFreshness::Fresh);
+        assert_eq!(manager.tabs_age(), None);
+
+        manager.update_tabs(vec![tab(1)]);
+        assert_eq!(manager.freshness(), TabFreshness::Fresh);
+
+        manager.update_tabs_with_age(vec![tab(1)], Some(Duration::from_secs(120)));
+        assert_eq!(manager.freshness(), TabFreshness::Stale);
+        assert_eq!(manager.get_tabs().len(), 1);
+
+        manager.update_tabs_with_age(vec![tab(1)], Some(Duration::from_secs(600)));
+        assert_eq!(manager.freshness(), TabFreshness::Expired);
+        assert!(manager.get_tabs().is_empty());
+        assert!(manager.search_tabs("").is_empty());
+
+        // keepAlive で一覧は新しいものとして扱われる
+        manager.heartbeat();
+        assert_eq!(manager.freshness(), TabFreshness::Fresh);
+        assert_eq!(manager.search_tabs("tab 1").len(), 1);
+    }
+
+    #[test]
+    fn test_tab_deltas() {
+        let manager = TabManager::new();
+        manager.update_tabs(vec![tab(1), tab(2), tab(3)]);
+        let order = |manager: &TabManager| {
+            let mut tabs = manager.get_tabs();
+            tabs.sort_by_key(|tab| (tab.window_id, tab.index));
+            tabs.iter().map(|tab| (tab.id, tab.window_id, tab.index)).collect::<Vec<_>>()
+        };
+        assert_eq!(order(&manager), vec![(1, 1, 0), (2, 1, 1), (3, 1, 2)]);
+
+        manager.upsert_tab(ChromeTab { active: true, ..tab(4) });
+        manager.upsert_tab(ChromeTab { title: "Renamed".to_string(), active: true, ..tab(2) });
+        let tabs = manager.get_tabs();
+        assert_eq!(tabs.len(), 4);
+        assert!(tabs.iter().find(|tab| tab.id == 2).is_some_and(|tab| tab.title == "Renamed" && tab.active));
+        assert!(!tabs.iter().find(|tab| tab.id == 4).unwrap().active);
+
+        // 先頭に開いたタブは後ろのタブをずらす
+        manager.upsert_tab(ChromeTab { index: 0, ..tab(5) });
+        assert_eq!(order(&manager), vec![(5, 1, 0), (1, 1, 1), (2, 1, 2), (3, 1, 3), (4, 1, 4)]);
+        manager.remove_tab(5);
+
+        manager.remove_tab(4);
+        manager.move_tab(3, 1, 0);
+        assert_eq!(order(&manager), vec![(3, 1, 0), (1, 1, 1), (2, 1, 2)]);
+
+        manager.move_tab(1, 7, 0);
+        assert_eq!(order(&manager), vec![(3, 1, 0), (2, 1, 1), (1, 7, 0)]);
+    }
+
+    #[test]
+    fn test_snapshots_and_change_notifications() {
+        let manager = TabManager::new();
+        let wakes = Arc::new(AtomicUsize::new(0));
+        let changes = {
+            let wakes = Arc::clone(&wakes);
+            manager.subscribe(move || {
+                wakes.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+
+        manager.update_tabs(vec![tab(1), tab(2)]);
+        let before = manager.snapshot();
+        manager.remove_tab(1);
+        manager.heartbeat();
+
+        // 前に受け取った一覧は変わらない
+        assert_eq!(before.iter().map(|tab| tab.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(manager.snapshot().len(), 1);
+        assert_eq!(changes.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(wakes.load(Ordering::SeqCst), 2);
+
+        // 同じ一覧が届いても知らせない（閉じたタブのあとは詰めた並び順になっている）
+        manager.update_tabs(vec![ChromeTab { index: 0, ..tab(2) }]);
+        assert!(changes.try_recv().is_err());
+
+        // 知らないタブのイベントや、変わらない移動・更新も知らせない
+        manager.remove_tab(99);
+        manager.move_tab(99, 1, 0);
+        manager.move_tab(2, 1, 0);
+        manager.upsert_tab(ChromeTab { index: 0, ..tab(2) });
+        assert!(changes.try_recv().is_err());
+        assert_eq!(wakes.load(Ordering::SeqCst), 2);
+
+        // 最近閉じたタブも変わったときだけ知らせる
+        let closed = ClosedTab { session_id: "7".to_string(), title: "Tab 1".to_string(), url: "https://example.com/1".to_string(), closed_at: Some(1_700_000_000) };
+        manager.update_recently_closed(vec![closed.clone()]);
+        manager.update_recently_closed(vec![closed.clone()]);
+        assert_eq!(changes.try_iter().count(), 1);
+        assert_eq!(*manager.recently_closed(), vec![closed]);
+
+        // リーディングリストは新しく追加したものから並べる
+        let entry = |title: &str, created_at: i64| ReadingListEntry {
+            title: title.to_string(),
+            url: format!("https://example.com/{}", title),
+            has_been_read: false,
+            created_at: Some(created_at),
+        };
+        manager.update_reading_list(vec![entry("old", 1), entry("new", 2)]);
+        manager.update_reading_list(vec![entry("new", 2), entry("old", 1)]);
+        assert_eq!(changes.try_iter().count(), 1);
+        assert_eq!(manager.reading_list().iter().map(|entry| entry.title.as_str()).collect::<Vec<_>>(), vec!["new", "old"]);
+
+        // 受け取る側を落としたら登録が外れる
+        drop(changes);
+        manager.upsert_tab(tab(3));
+        assert_eq!(format!("{:?}", manager.listeners), "ChangeListeners { count: 0 }");
+    }
+
+    #[test]
+    fn test_command_queue_is_bounded() {
+        let manager = TabManager::new();
+        for tab_id in 0..MAX_QUEUED_COMMANDS as i32 + 2 {
+            manager.queue_command(ChromeCommand::CloseTab { tab_id });
+        }
+        assert!(matches!(manager.pop_command(), Some(ChromeCommand::CloseTab { tab_id: 2 })));
+        assert_eq!(std::iter::from_fn(|| manager.pop_command()).count(), MAX_QUEUED_COMMANDS - 1);
+    }
+}
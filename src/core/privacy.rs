@@ -0,0 +1,121 @@
+//! プライバシーモード
+//!
+//! 有効な間は履歴を検索せず、開いたもの（usage.json）と検索語（recent_queries.json）を記録しない。
+//! ヘッダーの切り替え（または設定の `privacy.enabled`）と、設定した時間帯で有効になる。
+
+use crate::config::{PrivacyConfig, PrivacySchedule};
+use chrono::{DateTime, Datelike, Local, NaiveTime, Weekday};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// プライバシーモードの状態（ランチャーの UI と検索エンジンで共有する）
+#[derive(Debug, Clone, Default)]
+pub struct PrivacyMode {
+    /// ヘッダーで切り替えた状態
+    manual: Arc<AtomicBool>,
+    schedule: Vec<PrivacySchedule>,
+    /// 前回確認したときに有効だったか（有効になった時点を知るため）
+    was_active: Arc<AtomicBool>,
+}
+
+impl PrivacyMode {
+    pub fn new(config: &PrivacyConfig) -> Self {
+        Self {
+            manual: Arc::new(AtomicBool::new(config.enabled)),
+            schedule: config.schedule.clone(),
+            was_active: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn set(&self, enabled: bool) {
+        self.manual.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn is_manual(&self) -> bool {
+        self.manual.load(Ordering::SeqCst)
+    }
+
+    /// 設定した時間帯に入っているか
+    pub fn is_scheduled_at(&self, now: DateTime<Local>) -> bool {
+        self.schedule.iter().any(|schedule| in_schedule(schedule, now))
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_manual() || self.is_scheduled_at(Local::now())
+    }
+
+    /// 前回の確認から有効になっていれば一度だけ true を返す
+    pub fn take_activated(&self) -> bool {
+        let active = self.is_active();
+        let was_active = self.was_active.swap(active, Ordering::SeqCst);
+        active && !was_active
+    }
+}
+
+/// 時間帯に入っているか（読めない時刻の時間帯は無視する）
+pub fn in_schedule(schedule: &PrivacySchedule, now: DateTime<Local>) -> bool {
+    let parse = |time: &str| NaiveTime::parse_from_str(time, "%H:%M");
+    let (Ok(start), Ok(end)) = (parse(&schedule.start), parse(&schedule.end)) else {
+        log::warn!("Ignoring privacy schedule {}-{}: expected HH:MM", schedule.start, schedule.end);
+        return false;
+    };
+    let time = now.time();
+    // 日をまたぐ時間帯は、始まった日の曜日で判断する
+    let (within, day) = if start <= end {
+        (start <= time && time < end, now.weekday())
+    } else if time >= start {
+        (true, now.weekday())
+    } else {
+        (time < end, now.weekday().pred())
+    };
+    within && (schedule.days.is_empty() || schedule.days.iter().any(|name| name.parse::<Weekday>().ok() == Some(day)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn schedule(start: &str, end: &str, days: &[&str]) -> PrivacySchedule {
+        PrivacySchedule {
+            start: start.to_string(),
+            end: end.to_string(),
+            days: days.iter().map(|day| day.to_string()).collect(),
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // 2024-06-01 は土曜日
+        Local.with_ymd_and_hms(2024, 6, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_in_schedule() {
+        let evening = schedule("18:00", "23:30", &[]);
+        assert!(in_schedule(&evening, at(3, 18, 0)));
+        assert!(!in_schedule(&evening, at(3, 23, 30)));
+
+        // 土曜の夜から日曜の朝まで
+        let overnight = schedule("22:00", "07:00", &["sat"]);
+        assert!(in_schedule(&overnight, at(1, 23, 0)));
+        assert!(in_schedule(&overnight, at(2, 6, 59)));
+        assert!(!in_schedule(&overnight, at(2, 23, 0)));
+        assert!(!in_schedule(&overnight, at(3, 6, 0)));
+
+        assert!(!in_schedule(&schedule("late", "07:00", &[]), at(1, 23, 0)));
+    }
+
+    #[test]
+    fn test_take_activated_once() {
+        let privacy = PrivacyMode::new(&PrivacyConfig::default());
+        assert!(!privacy.take_activated());
+        privacy.set(true);
+        assert!(privacy.is_active());
+        assert!(privacy.take_activated());
+        assert!(!privacy.take_activated());
+        privacy.set(false);
+        assert!(!privacy.take_activated());
+        privacy.set(true);
+        assert!(privacy.take_activated());
+    }
+}
@@ -0,0 +1,184 @@
+use super::keyword_provider::{KeywordHelp, KeywordProvider};
+use super::SearchMode;
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+/// 検索結果の提供元の情報（設定パネルに表示する）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderInfo {
+    pub name: &'static str,
+    /// 呼び出しキーワード（キーワードなしで検索する提供元は空）
+    pub keywords: Vec<&'static str>,
+    /// 結果を返すモード
+    pub modes: Vec<SearchMode>,
+    /// 早見表に表示する使い方
+    pub help: Vec<KeywordHelp>,
+}
+
+/// 検索結果の提供元の一覧と、それぞれの有効・無効
+///
+/// ウィンドウ・タブ・ブックマーク・履歴などの組み込みの提供元は情報だけを登録し、検索エンジンが
+/// `serves` で確認してから検索する。キーワードプロバイダーは本体ごと登録する。
+/// 有効・無効は実行中に切り替えられ、次の検索から反映される。
+#[derive(Default)]
+pub struct ProviderRegistry {
+    builtins: Vec<ProviderInfo>,
+    keyword_providers: Vec<Arc<dyn KeywordProvider>>,
+    disabled: Mutex<BTreeSet<String>>,
+}
+
+impl ProviderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 組み込みの提供元を登録
+    pub fn register_builtin(&mut self, name: &'static str, keywords: &[&'static str], modes: &[SearchMode], help: &[KeywordHelp]) {
+        self.builtins.push(ProviderInfo {
+            name,
+            keywords: keywords.to_vec(),
+            modes: modes.to_vec(),
+            help: help.to_vec(),
+        });
+    }
+
+    /// キーワードプロバイダーを登録
+    pub fn register(&mut self, provider: Box<dyn KeywordProvider>) {
+        self.keyword_providers.push(Arc::from(provider));
+    }
+
+    /// 登録済みのすべての提供元（組み込み、続いてキーワードプロバイダー）
+    pub fn providers(&self) -> Vec<ProviderInfo> {
+        self.builtins
+            .iter()
+            .cloned()
+            .chain(self.keyword_providers.iter().map(|provider| ProviderInfo {
+                name: provider.name(),
+                keywords: provider.keywords().to_vec(),
+                modes: provider.modes().to_vec(),
+                help: provider.help().to_vec(),
+            }))
+            .collect()
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.lock().unwrap().contains(name)
+    }
+
+    pub fn set_enabled(&self, name: &str, enabled: bool) {
+        let mut disabled = self.disabled.lock().unwrap();
+        if enabled {
+            disabled.remove(name);
+        } else {
+            disabled.insert(name.to_string());
+        }
+    }
+
+    /// 無効にした提供元の名前（設定ファイルに保存する）
+    pub fn disabled(&self) -> Vec<String> {
+        self.disabled.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn set_disabled(&self, names: &[String]) {
+        *self.disabled.lock().unwrap() = names.iter().cloned().collect();
+    }
+
+    /// 有効で、かつそのモードで結果を返す提供元か
+    pub fn serves(&self, name: &str, mode: SearchMode) -> bool {
+        self.is_enabled(name)
+            && self.providers().iter().any(|info| info.name == name && info.modes.contains(&mode))
+    }
+
+    /// 有効な提供元のキーワード
+    pub fn keywords(&self) -> Vec<&'static str> {
+        self.providers()
+            .into_iter()
+            .filter(|info| self.is_enabled(info.name))
+            .flat_map(|info| info.keywords)
+            .collect()
+    }
+
+    /// そのモードで有効なキーワードプロバイダー
+    pub fn keyword_providers(&self, mode: SearchMode) -> impl Iterator<Item = &dyn KeywordProvider> {
+        self.keyword_providers
+            .iter()
+            .map(|provider| provider.as_ref())
+            .filter(move |provider| provider.modes().contains(&mode) && self.is_enabled(provider.name()))
+    }
+
+    /// キーワードを受け持つ有効なプロバイダー（別スレッドで検索できるよう共有して返す）
+    pub fn keyword_provider(&self, mode: SearchMode, keyword: &str) -> Option<Arc<dyn KeywordProvider>> {
+        self.keyword_providers
+            .iter()
+            .find(|provider| provider.modes().contains(&mode) && self.is_enabled(provider.name()) && provider.keywords().contains(&keyword))
+            .cloned()
+    }
+
+    /// すべてのキーワードプロバイダー（無効なものを含む。バックグラウンドの処理の確認用）
+    pub fn all_keyword_providers(&self) -> impl Iterator<Item = &dyn KeywordProvider> {
+        self.keyword_providers.iter().map(|provider| provider.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SearchResult;
+    use std::error::Error;
+
+    struct EchoProvider;
+
+    impl KeywordProvider for EchoProvider {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn keywords(&self) -> &[&'static str] {
+            &["echo"]
+        }
+
+        fn search(&self, _keyword: &str, _args: &str) -> Vec<SearchResult> {
+            Vec::new()
+        }
+
+        fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+            Ok(command.to_string())
+        }
+    }
+
+    fn registry() -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register_builtin("windows", &[], &[SearchMode::Windows], &[]);
+        registry.register_builtin("notes", &["note", "notes"], &[SearchMode::Browser, SearchMode::Tabs], &[KeywordHelp::new("note <text>", "Append a note")]);
+        registry.register(Box::new(EchoProvider));
+        registry
+    }
+
+    #[test]
+    fn test_providers_metadata() {
+        let registry = registry();
+        let names: Vec<&str> = registry.providers().iter().map(|info| info.name).collect();
+        assert_eq!(names, vec!["windows", "notes", "echo"]);
+        assert_eq!(registry.keywords(), vec!["note", "notes", "echo"]);
+        assert!(registry.serves("windows", SearchMode::Windows));
+        assert!(!registry.serves("windows", SearchMode::Browser));
+        assert_eq!(registry.providers()[1].help, vec![KeywordHelp::new("note <text>", "Append a note")]);
+    }
+
+    #[test]
+    fn test_disable_at_runtime() {
+        let registry = registry();
+        registry.set_enabled("echo", false);
+        registry.set_enabled("notes", false);
+        assert_eq!(registry.keyword_providers(SearchMode::Browser).count(), 0);
+        assert!(registry.keywords().is_empty());
+        assert_eq!(registry.disabled(), vec!["echo", "notes"]);
+
+        registry.set_enabled("echo", true);
+        assert_eq!(registry.keywords(), vec!["echo"]);
+
+        registry.set_disabled(&["windows".to_string()]);
+        assert!(!registry.serves("windows", SearchMode::Windows));
+        assert!(registry.is_enabled("notes"));
+    }
+}
@@ -0,0 +1,402 @@
+use super::result_composer::ResultCategory;
+use crate::filter::Searchable;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use std::collections::BTreeSet;
+
+/// `title:rust` のように範囲を限定できるフィールド（`Searchable::search_fields` の名前）
+pub const SCOPE_FIELDS: &[&str] = &["title", "url", "browser", "profile"];
+
+/// クエリがキーワードで始まっていれば、残りの引数を返す
+///
+/// キーワードの直後は空白か終端でなければならない（`notepad` は `note` に一致しない）。
+pub fn strip_keyword<'a>(query: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = query.trim_start().strip_prefix(keyword)?;
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+/// クエリの先頭のキーワード（例: `timer 25m` の `timer` と `25m`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveKeyword {
+    pub name: String,
+    pub args: String,
+}
+
+/// `after:` / `before:` / `within:` で指定した期間
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeRange {
+    pub after: Option<DateTime<Utc>>,
+    pub before: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.after.is_none_or(|after| time >= after) && self.before.is_none_or(|before| time < before)
+    }
+}
+
+/// 解析済みの検索クエリ
+///
+/// 構文は次のとおり（キーワードで始まる場合は引数を解析しない）。
+/// - `"foo bar"`: 空白を含む語
+/// - `-foo`: 含まないもの
+/// - `title:foo` / `url:foo`: フィールドを限定
+/// - `type:bookmark` / `type:history` / `type:tab`: 結果の種類を限定
+/// - `after:2024-01-01` / `before:2024-02-01` / `within:7d`: 期間を限定（履歴の最終訪問日時）
+/// - 末尾の `?`: Web 検索の明示
+/// - `foo; bar`: どちらかに一致するもの（各クエリで検索した結果を合わせる）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub raw: String,
+    /// フィールドを限定しない検索語
+    pub terms: Vec<String>,
+    /// フィールドを限定した検索語（フィールド名, 語）
+    pub scopes: Vec<(String, String)>,
+    pub negations: Vec<String>,
+    pub types: BTreeSet<ResultCategory>,
+    pub time_range: Option<TimeRange>,
+    pub keyword: Option<ActiveKeyword>,
+    pub web_requested: bool,
+    /// `;` で区切った各クエリ（2つ以上あるときだけ。この場合は他の項目を使わない）
+    pub alternatives: Vec<Query>,
+}
+
+impl Query {
+    /// `keywords` のいずれかで始まっていればキーワードとして解析する
+    pub fn parse(raw: &str, keywords: &[&str]) -> Self {
+        Self::parse_at(raw, keywords, Utc::now())
+    }
+
+    /// `within:` の基準時刻を指定して解析（テスト用）
+    pub fn parse_at(raw: &str, keywords: &[&str], now: DateTime<Utc>) -> Self {
+        let mut query = Query {
+            raw: raw.to_string(),
+            ..Default::default()
+        };
+
+        // 複数のキーワードに一致する場合は長いほう（`bm add` など）
+        let keyword = keywords
+            .iter()
+            .filter_map(|keyword| strip_keyword(raw, keyword).map(|args| (*keyword, args)))
+            .max_by_key(|(keyword, _)| keyword.len());
+        if let Some((name, args)) = keyword {
+            query.keyword = Some(ActiveKeyword {
+                name: name.to_string(),
+                args: args.to_string(),
+            });
+            return query;
+        }
+
+        // `;` で区切ったクエリはそれぞれ解析する（区切った後ではキーワードを解析しない）
+        if let Some(mut parts) = split_alternatives(raw) {
+            if parts.len() > 1 {
+                query.alternatives = parts.iter().map(|part| Query::parse_at(part, &[], now)).collect();
+                return query;
+            }
+            // 末尾の `;` だけなら、入力途中として区切る前のクエリで検索する
+            return Query {
+                raw: raw.to_string(),
+                ..Query::parse_at(&parts.pop().unwrap_or_default(), &[], now)
+            };
+        }
+
+        // 末尾の `?` は Web 検索の明示（それだけのクエリは通常の語として扱う）
+        let mut text = raw.trim();
+        let without_question = text.trim_end_matches('?').trim_end();
+        if !without_question.is_empty() && without_question.len() < text.len() {
+            query.web_requested = true;
+            text = without_question;
+        }
+
+        for token in tokenize(text) {
+            if !query.apply_operator(&token, now) {
+                query.terms.push(token);
+            }
+        }
+        query
+    }
+
+    /// 演算子なら解釈して true を返す（解釈できなければ通常の語として扱う）
+    fn apply_operator(&mut self, token: &str, now: DateTime<Utc>) -> bool {
+        if let Some(negated) = token.strip_prefix('-') {
+            if negated.is_empty() {
+                return false;
+            }
+            self.negations.push(negated.to_lowercase());
+            return true;
+        }
+
+        let Some((field, value)) = token.split_once(':') else {
+            return false;
+        };
+        if value.is_empty() {
+            return false;
+        }
+
+        let field = field.to_lowercase();
+        match field.as_str() {
+            "type" | "is" => match parse_category(value) {
+                Some(category) => {
+                    self.types.insert(category);
+                    true
+                }
+                None => false,
+            },
+            "after" | "before" | "within" => {
+                let mut range = self.time_range.unwrap_or_default();
+                let parsed = match field.as_str() {
+                    "after" => parse_day(value).map(|day| range.after = Some(day)),
+                    "before" => parse_day(value).map(|day| range.before = Some(day)),
+                    _ => parse_period(value).map(|period| range.after = Some(now - period)),
+                };
+                if parsed.is_some() {
+                    self.time_range = Some(range);
+                }
+                parsed.is_some()
+            }
+            name if SCOPE_FIELDS.contains(&name) => {
+                self.scopes.push((field.clone(), value.to_lowercase()));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.raw.trim().is_empty()
+    }
+
+    /// データソースに渡す検索語（フィールド限定の語しかなければその語）
+    pub fn text(&self) -> String {
+        if self.terms.is_empty() {
+            self.scopes.iter().map(|(_, value)| value.as_str()).collect::<Vec<_>>().join(" ")
+        } else {
+            self.terms.join(" ")
+        }
+    }
+
+    /// フィールドの限定と除外語を満たすか
+    pub fn matches<T: Searchable>(&self, item: &T) -> bool {
+        let fields: Vec<(&str, String)> = item
+            .search_fields()
+            .into_iter()
+            .map(|(name, value)| (name, value.to_lowercase()))
+            .collect();
+
+        let scoped = self
+            .scopes
+            .iter()
+            .all(|(field, value)| fields.iter().any(|(name, text)| *name == field.as_str() && text.contains(value.as_str())));
+        let negated = self
+            .negations
+            .iter()
+            .any(|negation| fields.iter().any(|(_, text)| text.contains(negation.as_str())));
+        scoped && !negated
+    }
+
+    pub fn allows_category(&self, category: ResultCategory) -> bool {
+        self.types.is_empty() || self.types.contains(&category) || self.types.contains(&category.base())
+    }
+
+    /// 期間の限定を満たすか（日時を持たない結果は対象外）
+    pub fn allows_time(&self, time: Option<DateTime<Utc>>) -> bool {
+        match (self.time_range, time) {
+            (Some(range), Some(time)) => range.contains(time),
+            _ => true,
+        }
+    }
+}
+
+/// `;` で区切る（`"..."` で囲んだ部分は区切らない）。`;` がなければ `None`、空のクエリは除く
+fn split_alternatives(text: &str) -> Option<Vec<String>> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut split = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ';' if !quoted => {
+                split = true;
+                parts.push(std::mem::take(&mut current));
+            }
+            c => current.push(c),
+        }
+    }
+    parts.push(current);
+    split.then(|| parts.into_iter().map(|part| part.trim().to_string()).filter(|part| !part.is_empty()).collect())
+}
+
+/// 空白で区切る（`"..."` で囲んだ部分は区切らない）
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn parse_category(value: &str) -> Option<ResultCategory> {
+    match value.to_lowercase().as_str() {
+        "bookmark" | "bookmarks" | "bm" => Some(ResultCategory::Bookmark),
+        "history" | "h" => Some(ResultCategory::History),
+        "tab" | "tabs" => Some(ResultCategory::Tab),
+        "pinned" | "pin" => Some(ResultCategory::PinnedTab),
+        "reading" | "readinglist" | "rl" => Some(ResultCategory::ReadingList),
+        "google" | "web" => Some(ResultCategory::Google),
+        "command" | "commands" | "cmd" => Some(ResultCategory::Command),
+        _ => None,
+    }
+}
+
+/// `2024-01-01` をローカル時刻のその日の0時として解釈
+fn parse_day(value: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let midnight = date.and_hms_opt(0, 0, 0)?.and_local_timezone(Local).earliest()?;
+    Some(midnight.with_timezone(&Utc))
+}
+
+/// `12h` / `7d` / `2w` の期間
+fn parse_period(value: &str) -> Option<Duration> {
+    let unit = value.chars().last()?;
+    let number: i64 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'h' => Some(Duration::hours(number)),
+        'd' => Some(Duration::days(number)),
+        'w' => Some(Duration::weeks(number)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    struct Page {
+        title: String,
+        url: String,
+    }
+
+    impl Searchable for Page {
+        fn search_fields(&self) -> Vec<(&str, &str)> {
+            vec![("title", self.title.as_str()), ("url", self.url.as_str())]
+        }
+    }
+
+    fn page(title: &str, url: &str) -> Page {
+        Page {
+            title: title.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_keyword() {
+        let query = Query::parse("bm add Work", &["bm", "bm add", "note"]);
+        assert_eq!(
+            query.keyword,
+            Some(ActiveKeyword {
+                name: "bm add".to_string(),
+                args: "Work".to_string(),
+            })
+        );
+        assert!(query.terms.is_empty());
+        assert_eq!(Query::parse("notepad", &["note"]).keyword, None);
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        let query = Query::parse(r#"rust "error handling" -reddit title:book type:history url:"doc.rust""#, &[]);
+        assert_eq!(query.terms, vec!["rust", "error handling"]);
+        assert_eq!(query.negations, vec!["reddit"]);
+        assert_eq!(
+            query.scopes,
+            vec![("title".to_string(), "book".to_string()), ("url".to_string(), "doc.rust".to_string())]
+        );
+        assert!(query.allows_category(ResultCategory::History));
+        assert!(!query.allows_category(ResultCategory::Bookmark));
+        assert_eq!(query.text(), "rust error handling");
+    }
+
+    #[test]
+    fn test_unknown_operators_are_terms() {
+        let query = Query::parse("https://github.com type:unknown -", &[]);
+        assert_eq!(query.terms, vec!["https://github.com", "type:unknown", "-"]);
+        assert!(query.types.is_empty());
+    }
+
+    #[test]
+    fn test_type_tab_includes_pinned_tabs() {
+        let query = Query::parse("rust type:tab", &[]);
+        assert!(query.allows_category(ResultCategory::PinnedTab));
+        assert!(!query.allows_category(ResultCategory::ReadingList));
+
+        let query = Query::parse("rust is:pinned", &[]);
+        assert!(query.allows_category(ResultCategory::PinnedTab));
+        assert!(!query.allows_category(ResultCategory::Tab));
+    }
+
+    #[test]
+    fn test_parse_time_range_and_web_request() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 10, 12, 0, 0).unwrap();
+        let query = Query::parse_at("release notes within:7d?", &[], now);
+        assert!(query.web_requested);
+        assert_eq!(query.terms, vec!["release", "notes"]);
+        assert!(query.allows_time(Some(now - Duration::days(2))));
+        assert!(!query.allows_time(Some(now - Duration::days(8))));
+        assert!(query.allows_time(None));
+
+        assert!(!Query::parse("?", &[]).web_requested);
+    }
+
+    #[test]
+    fn test_parse_alternatives() {
+        let query = Query::parse(r#"rust -reddit; "go; lang" ;  ; title:zig"#, &["note"]);
+        assert!(query.terms.is_empty());
+        let alternatives: Vec<(Vec<String>, String)> = query.alternatives.iter().map(|alt| (alt.terms.clone(), alt.text())).collect();
+        assert_eq!(
+            alternatives,
+            vec![
+                (vec!["rust".to_string()], "rust".to_string()),
+                (vec!["go; lang".to_string()], "go; lang".to_string()),
+                (Vec::new(), "zig".to_string()),
+            ]
+        );
+        assert_eq!(query.alternatives[0].negations, vec!["reddit"]);
+
+        // 入力途中の `;` と、キーワードの引数は区切らない
+        let typing = Query::parse("rust;", &[]);
+        assert!(typing.alternatives.is_empty());
+        assert_eq!(typing.terms, vec!["rust"]);
+        assert_eq!(typing.raw, "rust;");
+        assert!(Query::parse("note a; b", &["note"]).alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_matches_scopes_and_negations() {
+        let query = Query::parse("title:rust -reddit", &[]);
+        assert!(query.matches(&page("The Rust Book", "https://doc.rust-lang.org/book")));
+        assert!(!query.matches(&page("Rust on Reddit", "https://reddit.com/r/rust")));
+        assert!(!query.matches(&page("Go", "https://rust.example.com")));
+        assert_eq!(query.text(), "rust");
+    }
+}
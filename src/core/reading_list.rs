@@ -0,0 +1,68 @@
+//! リーディングリストの検索結果
+//!
+//! プロファイルのリーディングリストは LevelDB に保存されていて直接は読めないため、拡張機能が
+//! `chrome.readingList` から送ってくる一覧を使う。未読のものを先に、追加の新しいものから並べる。
+
+use super::native_messaging::ReadingListEntry;
+use super::{Action, ResultMetadata, ResultType, SearchResult};
+use crate::filter::search_filter::Searchable;
+
+impl Searchable for ReadingListEntry {
+    fn search_fields(&self) -> Vec<(&str, &str)> {
+        vec![("title", self.title.as_str()), ("url", self.url.as_str())]
+    }
+}
+
+impl ReadingListEntry {
+    /// 空白で区切ったすべての語をタイトルか URL に含むか（大文字小文字は区別しない）
+    pub fn matches_words(&self, query: &str) -> bool {
+        let text = format!("{} {}", self.title, self.url).to_lowercase();
+        query.split_whitespace().all(|word| text.contains(&word.to_lowercase()))
+    }
+}
+
+/// リーディングリストの項目の結果（既読なら説明に印を付ける）
+pub fn reading_list_result(entry: &ReadingListEntry) -> SearchResult {
+    SearchResult {
+        title: entry.title.clone(),
+        description: if entry.has_been_read { format!("Read · {}", entry.url) } else { entry.url.clone() },
+        action: Action::OpenUrl(entry.url.clone()),
+        window_info: None,
+        result_type: ResultType::ReadingList,
+        group: None,
+        metadata: Some(ResultMetadata::Bookmark { url: entry.url.clone(), folder: None, title: entry.title.clone(), source: None }),
+    }
+}
+
+/// 一致した項目の結果（未読のものが先。同じなら渡した順＝追加の新しいものから）
+pub fn reading_list_results<'a>(entries: impl IntoIterator<Item = &'a ReadingListEntry>) -> Vec<SearchResult> {
+    let mut entries: Vec<&ReadingListEntry> = entries.into_iter().collect();
+    entries.sort_by_key(|entry| entry.has_been_read);
+    entries.into_iter().map(reading_list_result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, has_been_read: bool) -> ReadingListEntry {
+        ReadingListEntry {
+            title: title.to_string(),
+            url: format!("https://example.com/{}", title.to_lowercase().replace(' ', "-")),
+            has_been_read,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_unread_entries_first() {
+        let entries = [entry("Rust async book", true), entry("Rust blog", false), entry("Go tour", false)];
+        let matching = entries.iter().filter(|entry| entry.matches_words("RUST"));
+        let results = reading_list_results(matching);
+
+        assert_eq!(results.iter().map(|result| result.title.as_str()).collect::<Vec<_>>(), vec!["Rust blog", "Rust async book"]);
+        assert_eq!(results[0].result_type, ResultType::ReadingList);
+        assert_eq!(results[0].action, Action::OpenUrl("https://example.com/rust-blog".to_string()));
+        assert_eq!(results[1].description, "Read · https://example.com/rust-async-book");
+    }
+}
@@ -0,0 +1,94 @@
+//! 最近閉じたタブの検索結果
+//!
+//! 一覧は拡張機能が `chrome.sessions` から送ってくる（新しいものから）。選ぶと拡張機能で
+//! 開き直し、拡張機能が接続されていなければ URL を新しく開く。
+
+use super::native_messaging::ClosedTab;
+use super::{Action, ResultMetadata, ResultType, SearchResult};
+use crate::filter::search_filter::Searchable;
+use chrono::{DateTime, TimeZone, Utc};
+
+/// 最近閉じたタブだけを出すキーワード（例: `closed github`）
+pub const CLOSED_KEYWORD: &str = "closed";
+
+/// 最近閉じたタブをまとめる見出し
+pub const RECENTLY_CLOSED_GROUP: &str = "Recently closed";
+
+impl Searchable for ClosedTab {
+    fn search_fields(&self) -> Vec<(&str, &str)> {
+        vec![("title", self.title.as_str()), ("url", self.url.as_str())]
+    }
+}
+
+impl ClosedTab {
+    /// 閉じた日時（拡張機能が送ってくるのは UNIX 秒）
+    pub fn closed_time(&self) -> Option<DateTime<Utc>> {
+        self.closed_at.and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+    }
+
+    /// 空白で区切ったすべての語をタイトルか URL に含むか（大文字小文字は区別しない）
+    pub fn matches_words(&self, query: &str) -> bool {
+        let text = format!("{} {}", self.title, self.url).to_lowercase();
+        query.split_whitespace().all(|word| text.contains(&word.to_lowercase()))
+    }
+}
+
+/// 閉じたタブの結果（履歴と同じ列で表示し、閉じた日時を最終訪問の列に出す）
+pub fn closed_tab_result(tab: &ClosedTab) -> SearchResult {
+    SearchResult {
+        title: tab.title.clone(),
+        description: tab.url.clone(),
+        action: Action::RestoreClosedTab { session_id: tab.session_id.clone(), url: tab.url.clone() },
+        window_info: None,
+        result_type: ResultType::History,
+        group: Some(RECENTLY_CLOSED_GROUP.to_string()),
+        metadata: Some(ResultMetadata::History { url: tab.url.clone(), visit_count: 0, last_visit: tab.closed_time() }),
+    }
+}
+
+/// `query` のすべての語を含む閉じたタブ（新しいものから `limit` 件）
+pub fn closed_tab_results(tabs: &[ClosedTab], query: &str, limit: usize) -> Vec<SearchResult> {
+    tabs.iter().filter(|tab| tab.matches_words(query)).take(limit).map(closed_tab_result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closed(session_id: &str, title: &str, url: &str) -> ClosedTab {
+        ClosedTab {
+            session_id: session_id.to_string(),
+            title: title.to_string(),
+            url: url.to_string(),
+            closed_at: Some(1_700_000_000),
+        }
+    }
+
+    #[test]
+    fn test_closed_tab_results() {
+        let tabs = vec![
+            closed("3", "The Rust Book", "https://doc.rust-lang.org/book/"),
+            closed("2", "GitHub", "https://github.com/"),
+            closed("1", "Rust Playground", "https://play.rust-lang.org/"),
+        ];
+
+        let results = closed_tab_results(&tabs, "RUST lang", 5);
+        assert_eq!(results.iter().map(|result| result.title.as_str()).collect::<Vec<_>>(), vec!["The Rust Book", "Rust Playground"]);
+        assert_eq!(
+            results[0].action,
+            Action::RestoreClosedTab { session_id: "3".to_string(), url: "https://doc.rust-lang.org/book/".to_string() }
+        );
+        assert_eq!(results[0].group.as_deref(), Some(RECENTLY_CLOSED_GROUP));
+        assert_eq!(
+            results[0].metadata,
+            Some(ResultMetadata::History {
+                url: "https://doc.rust-lang.org/book/".to_string(),
+                visit_count: 0,
+                last_visit: Utc.timestamp_opt(1_700_000_000, 0).single(),
+            })
+        );
+
+        // 空の検索語ならすべて（上限まで）
+        assert_eq!(closed_tab_results(&tabs, "", 2).len(), 2);
+    }
+}
@@ -0,0 +1,131 @@
+//! 最近の検索結果（モードと検索語ごと、メモリ上だけ）
+//!
+//! 入力が変わったらデバウンスを待たずにここから結果を表示し、そのあと実行した検索の結果で置き換える。
+//! 同じ検索語がなければ、いちばん長い接頭辞の結果を今の検索語で絞り込んで使う。
+
+use super::{SearchMode, SearchResult};
+use std::collections::VecDeque;
+
+pub struct ResultCache {
+    capacity: usize,
+    /// 新しいものが先頭（モード, 小文字にした検索語, 結果）
+    entries: VecDeque<(SearchMode, String, Vec<SearchResult>)>,
+}
+
+impl ResultCache {
+    /// `capacity` 件まで覚える（0 なら何も覚えない）
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, entries: VecDeque::new() }
+    }
+
+    pub fn insert(&mut self, mode: SearchMode, query: &str, results: &[SearchResult]) {
+        let key = normalize(query);
+        if self.capacity == 0 || key.is_empty() {
+            return;
+        }
+        self.entries.retain(|(entry_mode, entry_key, _)| !(*entry_mode == mode && *entry_key == key));
+        self.entries.push_front((mode, key, results.to_vec()));
+        self.entries.truncate(self.capacity);
+    }
+
+    /// 覚えた結果をすべて忘れる（プライバシーモードに入ったとき）
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 検索語の結果（なければ接頭辞の結果を絞り込んだもの）
+    pub fn lookup(&self, mode: SearchMode, query: &str) -> Option<Vec<SearchResult>> {
+        let key = normalize(query);
+        if key.is_empty() {
+            return None;
+        }
+        let same_mode = || self.entries.iter().filter(|(entry_mode, _, _)| *entry_mode == mode);
+        if let Some((_, _, results)) = same_mode().find(|(_, entry_key, _)| *entry_key == key) {
+            return Some(results.clone());
+        }
+
+        let (_, _, results) = same_mode()
+            .filter(|(_, entry_key, _)| key.starts_with(entry_key.as_str()))
+            .max_by_key(|(_, entry_key, _)| entry_key.len())?;
+        let words: Vec<&str> = key.split_whitespace().collect();
+        let narrowed: Vec<SearchResult> = results
+            .iter()
+            .filter(|result| {
+                let text = format!("{} {}", result.title, result.description).to_lowercase();
+                words.iter().all(|word| text.contains(word))
+            })
+            .cloned()
+            .collect();
+        (!narrowed.is_empty()).then_some(narrowed)
+    }
+}
+
+fn normalize(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+/// 表示していた結果で選んでいた行が、新しい結果の何番目にあるか
+pub fn reconcile_selection(previous: &[SearchResult], selected: usize, fresh: &[SearchResult]) -> Option<usize> {
+    let id = previous.get(selected)?.action.id();
+    fresh.iter().position(|result| result.action.id() == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Action, ResultType};
+
+    fn result(title: &str, url: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            description: url.to_string(),
+            action: Action::OpenUrl(url.to_string()),
+            window_info: None,
+            result_type: ResultType::Bookmark,
+            group: None,
+            metadata: None,
+        }
+    }
+
+    fn titles(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|result| result.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lookup_exact_and_prefix() {
+        let mut cache = ResultCache::new(2);
+        let rust = [result("Rust Book", "https://doc.rust-lang.org/book/"), result("Rustup", "https://rustup.rs/")];
+        cache.insert(SearchMode::Browser, "Rust", &rust);
+
+        assert_eq!(titles(&cache.lookup(SearchMode::Browser, " rust ").unwrap()), vec!["Rust Book", "Rustup"]);
+        assert!(cache.lookup(SearchMode::General, "rust").is_none());
+        // 接頭辞の結果を今の検索語で絞り込む
+        assert_eq!(titles(&cache.lookup(SearchMode::Browser, "rust book").unwrap()), vec!["Rust Book"]);
+        assert!(cache.lookup(SearchMode::Browser, "rusty").is_none());
+        assert!(cache.lookup(SearchMode::Browser, "").is_none());
+
+        // 古いものから忘れる
+        cache.insert(SearchMode::Browser, "go", &[]);
+        cache.insert(SearchMode::Browser, "zig", &[]);
+        assert!(cache.lookup(SearchMode::Browser, "rust").is_none());
+        assert!(ResultCache::new(0).lookup(SearchMode::Browser, "rust").is_none());
+    }
+
+    #[test]
+    fn test_clear_forgets_all_modes() {
+        let mut cache = ResultCache::new(4);
+        cache.insert(SearchMode::Browser, "rust", &[result("Rust Book", "https://doc.rust-lang.org/book/")]);
+        cache.insert(SearchMode::General, "rust", &[result("Rustup", "https://rustup.rs/")]);
+        cache.clear();
+        assert!(cache.lookup(SearchMode::Browser, "rust").is_none());
+        assert!(cache.lookup(SearchMode::General, "rust").is_none());
+    }
+
+    #[test]
+    fn test_reconcile_selection() {
+        let cached = [result("Rust Book", "https://doc.rust-lang.org/book/"), result("Rustup", "https://rustup.rs/")];
+        let fresh = [result("Rustup", "https://rustup.rs/"), result("Rust Book", "https://doc.rust-lang.org/book/")];
+        assert_eq!(reconcile_selection(&cached, 1, &fresh), Some(0));
+        assert_eq!(reconcile_selection(&cached, 5, &fresh), None);
+    }
+}
@@ -0,0 +1,345 @@
+use super::{ResultType, SearchResult};
+use crate::config::RankingConfig;
+use chrono::{DateTime, Datelike, Local, TimeZone, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// 並び順と重みを設定する結果の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultCategory {
+    /// 「Google: ...」の行
+    Google,
+    /// 組み込みコマンド（重複タブを閉じるなど）
+    Command,
+    Bookmark,
+    History,
+    Tab,
+    /// ピン留めしたタブ（`type:tab` やタブの絞り込みにも含める）
+    PinnedTab,
+    ReadingList,
+}
+
+impl ResultCategory {
+    /// 既定の並び順（自分で残したピン留めのタブとリーディングリストは履歴より上）
+    pub const DEFAULT_ORDER: [ResultCategory; 7] = [
+        ResultCategory::Google,
+        ResultCategory::Command,
+        ResultCategory::PinnedTab,
+        ResultCategory::ReadingList,
+        ResultCategory::Bookmark,
+        ResultCategory::History,
+        ResultCategory::Tab,
+    ];
+
+    pub fn of(result_type: &ResultType) -> Self {
+        match result_type {
+            ResultType::GoogleSearch => ResultCategory::Google,
+            ResultType::Bookmark => ResultCategory::Bookmark,
+            ResultType::History => ResultCategory::History,
+            ResultType::Tab => ResultCategory::Tab,
+            ResultType::PinnedTab => ResultCategory::PinnedTab,
+            ResultType::ReadingList => ResultCategory::ReadingList,
+            _ => ResultCategory::Command,
+        }
+    }
+
+    /// 絞り込みで同じ種類として扱う種類（ピン留めしたタブはタブ）
+    pub fn base(&self) -> Self {
+        match self {
+            ResultCategory::PinnedTab => ResultCategory::Tab,
+            other => *other,
+        }
+    }
+}
+
+/// 「Google: ...」の行の表示位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoogleRowPlacement {
+    /// 他の種類と同じく `order` / `weights` に従う（既定では先頭）
+    #[default]
+    Ranked,
+    /// 常に最後
+    Bottom,
+    /// ローカルの検索結果がないときだけ表示
+    Fallback,
+}
+
+/// 並べ替え済みの結果の中で Google の行を設定した位置に移す
+///
+/// `requested`（クエリの末尾が `?`）なら、設定にかかわらず先頭に表示する。
+pub fn place_google_row(results: Vec<SearchResult>, placement: GoogleRowPlacement, requested: bool) -> Vec<SearchResult> {
+    if !requested && placement == GoogleRowPlacement::Ranked {
+        return results;
+    }
+
+    let (google, mut others): (Vec<SearchResult>, Vec<SearchResult>) = results
+        .into_iter()
+        .partition(|result| result.result_type == ResultType::GoogleSearch);
+
+    if requested {
+        google.into_iter().chain(others).collect()
+    } else if placement == GoogleRowPlacement::Fallback && !others.is_empty() {
+        others
+    } else {
+        // Bottom、またはローカルの結果がない Fallback
+        others.extend(google);
+        others
+    }
+}
+
+/// 同じグループの結果を、そのグループが最初に現れた位置にまとめる（グループのない結果の位置は変えない）
+pub fn group_results(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut slots: Vec<(Option<String>, Vec<SearchResult>)> = Vec::new();
+    for result in results {
+        match &result.group {
+            Some(group) => match slots.iter_mut().find(|(g, _)| g.as_ref() == Some(group)) {
+                Some((_, items)) => items.push(result),
+                None => slots.push((Some(group.clone()), vec![result])),
+            },
+            None => slots.push((None, vec![result])),
+        }
+    }
+    slots.into_iter().flat_map(|(_, items)| items).collect()
+}
+
+/// `foo; bar` の各クエリの結果を、順位ごとに交互に並べて合わせる
+///
+/// 同じ操作の結果は順位のよいほう（同じ順位なら先に書いたクエリ）だけを残す。
+/// 並べたあとで同じグループの結果をまとめる。
+pub fn merge_alternatives(lists: Vec<Vec<SearchResult>>) -> Vec<SearchResult> {
+    let mut seen = std::collections::HashSet::new();
+    let mut lists: Vec<_> = lists.into_iter().map(Vec::into_iter).collect();
+    let mut merged = Vec::new();
+    loop {
+        let mut remaining = false;
+        for list in lists.iter_mut() {
+            if let Some(result) = list.next() {
+                remaining = true;
+                if seen.insert(result.action.id()) {
+                    merged.push(result);
+                }
+            }
+        }
+        if !remaining {
+            break;
+        }
+    }
+    group_results(merged)
+}
+
+/// 種類ごとに集めた検索結果を設定に従って並べ替える
+///
+/// 重みが設定されていなければ `order` の順に種類ごとにまとめて並べる。
+/// 重みが1つでも設定されていれば、各結果を「種類の重み ÷ 種類内の順位」で採点して混ぜて並べる
+/// （重みを設定していない種類は 1.0、同点なら `order` の順）。
+pub fn compose_results(results: Vec<SearchResult>, config: &RankingConfig) -> Vec<SearchResult> {
+    // 設定にない種類は既定の順で後ろに並べる
+    let position = |category: ResultCategory| {
+        config.order.iter().position(|c| *c == category).unwrap_or_else(|| {
+            config.order.len() + ResultCategory::DEFAULT_ORDER.iter().position(|c| *c == category).unwrap_or(0)
+        })
+    };
+
+    let mut counts = std::collections::HashMap::new();
+    let mut ranked: Vec<(usize, usize, f32, SearchResult)> = results
+        .into_iter()
+        .map(|result| {
+            let category = ResultCategory::of(&result.result_type);
+            let rank = counts.entry(category).or_insert(0usize);
+            let entry_rank = *rank;
+            *rank += 1;
+            let weight = config.weights.get(&category).copied().unwrap_or(1.0);
+            let score = weight / (entry_rank + 1) as f32;
+            (position(category), entry_rank, score, result)
+        })
+        .collect();
+
+    if config.weights.is_empty() {
+        ranked.sort_by_key(|(position, rank, _, _)| (*position, *rank));
+    } else {
+        ranked.sort_by(|a, b| {
+            b.2.partial_cmp(&a.2)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+                .then(a.1.cmp(&b.1))
+        });
+    }
+
+    ranked.into_iter().map(|(_, _, _, result)| result).collect()
+}
+
+/// 時間帯の区切り（分）
+const TIME_BUCKET_MINUTES: i64 = 30;
+
+/// 時間帯のスコアに数える日数（古い記録ほど軽くする）
+const TIME_SCORE_DAYS: f32 = 30.0;
+
+/// 開いた時刻の記録から、今の時間帯にどれだけ開きそうかを採点する
+///
+/// 1日を30分ごとの区間に分け、今と同じ区間に開いた記録は 1、隣の区間は 0.5 を数える。
+/// 平日と週末が今と違う日の記録は半分、古い記録は日数に応じて軽くする（30日で 0）。
+pub fn time_of_day_score(opens: &[i64], now: DateTime<Local>) -> f32 {
+    const BUCKETS: i64 = 24 * 60 / TIME_BUCKET_MINUTES;
+    let bucket = |time: &DateTime<Local>| (time.hour() as i64 * 60 + time.minute() as i64) / TIME_BUCKET_MINUTES;
+    let is_weekend = |time: &DateTime<Local>| matches!(time.weekday(), Weekday::Sat | Weekday::Sun);
+    let now_bucket = bucket(&now);
+
+    opens
+        .iter()
+        .filter_map(|opened| Local.timestamp_opt(*opened, 0).single())
+        .map(|opened| {
+            let age_days = (now - opened).num_seconds() as f32 / 86_400.0;
+            if !(0.0..TIME_SCORE_DAYS).contains(&age_days) {
+                return 0.0;
+            }
+            let distance = (bucket(&opened) - now_bucket).rem_euclid(BUCKETS);
+            let closeness = match distance.min(BUCKETS - distance) {
+                0 => 1.0,
+                1 => 0.5,
+                _ => return 0.0,
+            };
+            let day_kind = if is_weekend(&opened) == is_weekend(&now) { 1.0 } else { 0.5 };
+            closeness * day_kind * (1.0 - age_days / TIME_SCORE_DAYS)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Action;
+
+    fn result(title: &str, result_type: ResultType) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            description: String::new(),
+            action: Action::GoogleSearch(title.to_string()),
+            window_info: None,
+            result_type,
+            group: None,
+            metadata: None,
+        }
+    }
+
+    fn titles(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.title.as_str()).collect()
+    }
+
+    fn sample() -> Vec<SearchResult> {
+        vec![
+            result("google", ResultType::GoogleSearch),
+            result("bookmark 1", ResultType::Bookmark),
+            result("bookmark 2", ResultType::Bookmark),
+            result("history", ResultType::History),
+            result("tab 1", ResultType::Tab),
+            result("tab 2", ResultType::Tab),
+        ]
+    }
+
+    #[test]
+    fn test_default_config_keeps_current_order() {
+        let composed = compose_results(sample(), &RankingConfig::default());
+        assert_eq!(titles(&composed), vec!["google", "bookmark 1", "bookmark 2", "history", "tab 1", "tab 2"]);
+    }
+
+    #[test]
+    fn test_pinned_tabs_and_reading_list_outrank_history() {
+        let mut results = sample();
+        results.push(result("pinned", ResultType::PinnedTab));
+        results.push(result("reading", ResultType::ReadingList));
+        let composed = compose_results(results, &RankingConfig::default());
+        assert_eq!(
+            titles(&composed),
+            vec!["google", "pinned", "reading", "bookmark 1", "bookmark 2", "history", "tab 1", "tab 2"]
+        );
+        assert_eq!(ResultCategory::PinnedTab.base(), ResultCategory::Tab);
+        assert_eq!(ResultCategory::ReadingList.base(), ResultCategory::ReadingList);
+    }
+
+    #[test]
+    fn test_custom_order_puts_unlisted_categories_last() {
+        let config = RankingConfig {
+            order: vec![ResultCategory::Tab, ResultCategory::Bookmark],
+            ..Default::default()
+        };
+        let composed = compose_results(sample(), &config);
+        assert_eq!(titles(&composed), vec!["tab 1", "tab 2", "bookmark 1", "bookmark 2", "google", "history"]);
+    }
+
+    #[test]
+    fn test_weights_interleave_categories() {
+        let mut config = RankingConfig::default();
+        config.weights.insert(ResultCategory::Tab, 2.0);
+        config.weights.insert(ResultCategory::Google, 0.1);
+        let composed = compose_results(sample(), &config);
+        // tab 1 = 2.0, tab 2 = 1.0, bookmark 1 = 1.0, history = 1.0, bookmark 2 = 0.5, google = 0.1
+        assert_eq!(titles(&composed), vec!["tab 1", "bookmark 1", "history", "tab 2", "bookmark 2", "google"]);
+    }
+
+    #[test]
+    fn test_group_results_keeps_groups_together() {
+        let grouped = |title: &str, result_type: ResultType, group: &str| SearchResult {
+            group: Some(group.to_string()),
+            ..result(title, result_type)
+        };
+        let results = vec![
+            result("google", ResultType::GoogleSearch),
+            grouped("work bookmark", ResultType::Bookmark, "Chrome - Work"),
+            grouped("personal bookmark", ResultType::Bookmark, "Chrome - Personal"),
+            grouped("work history", ResultType::History, "Chrome - Work"),
+            result("tab", ResultType::Tab),
+        ];
+        let grouped = group_results(results);
+        assert_eq!(
+            titles(&grouped),
+            vec!["google", "work bookmark", "work history", "personal bookmark", "tab"]
+        );
+    }
+
+    #[test]
+    fn test_merge_alternatives_interleaves_and_dedups() {
+        let rust = vec![result("rust", ResultType::GoogleSearch), result("rust book", ResultType::Bookmark), result("shared", ResultType::Tab)];
+        let go = vec![result("go", ResultType::GoogleSearch), result("shared", ResultType::History), result("go tour", ResultType::Bookmark)];
+        let merged = merge_alternatives(vec![rust, go]);
+        assert_eq!(titles(&merged), vec!["rust", "go", "rust book", "shared", "go tour"]);
+        // 順位のよいほう（go の 2 番目）を残す
+        assert_eq!(merged[3].result_type, ResultType::History);
+    }
+
+    #[test]
+    fn test_place_google_row() {
+        let bottom = place_google_row(sample(), GoogleRowPlacement::Bottom, false);
+        assert_eq!(titles(&bottom).last(), Some(&"google"));
+
+        let fallback = place_google_row(sample(), GoogleRowPlacement::Fallback, false);
+        assert!(!titles(&fallback).contains(&"google"));
+        let only_google = vec![result("google", ResultType::GoogleSearch)];
+        assert_eq!(place_google_row(only_google, GoogleRowPlacement::Fallback, false).len(), 1);
+
+        // 末尾の `?` で明示した場合は先頭
+        let mut results = sample();
+        results.rotate_left(1);
+        let requested = place_google_row(results, GoogleRowPlacement::Fallback, true);
+        assert_eq!(titles(&requested)[0], "google");
+    }
+
+    #[test]
+    fn test_time_of_day_score() {
+        // 2024-05-01 は水曜日
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 9, 45, 0).unwrap();
+        let at = |day: u32, hour: u32, minute: u32| Local.with_ymd_and_hms(2024, 4, day, hour, minute, 0).unwrap().timestamp();
+
+        let standup = time_of_day_score(&[at(29, 9, 40), at(30, 9, 50)], now);
+        let nearby = time_of_day_score(&[at(30, 9, 20)], now);
+        let afternoon = time_of_day_score(&[at(30, 15, 0)], now);
+        let weekend = time_of_day_score(&[at(28, 9, 40)], now);
+        let old = time_of_day_score(&[Local.with_ymd_and_hms(2024, 3, 1, 9, 45, 0).unwrap().timestamp()], now);
+
+        assert!(standup > 1.8);
+        assert!(nearby > 0.4 && nearby < 0.5);
+        assert_eq!(afternoon, 0.0);
+        assert!(weekend < 0.5);
+        assert_eq!(old, 0.0);
+    }
+}
@@ -0,0 +1,209 @@
+use super::search_engine::{ResultMetadata, ResultType, SearchResult};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// 検索結果の書き出し形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Markdown, ExportFormat::Csv, ExportFormat::Json];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+        }
+    }
+
+    /// ファイルに保存するときの拡張子
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// 書き出す1件分（metadata やウィンドウの情報から取り出した値）
+struct Row<'a> {
+    title: &'a str,
+    kind: &'static str,
+    url: Option<&'a str>,
+    /// フォルダーやプロセス名など、URL 以外の補足
+    detail: Option<String>,
+    visit_count: Option<i32>,
+    last_visit: Option<String>,
+}
+
+impl<'a> Row<'a> {
+    fn from(result: &'a SearchResult) -> Self {
+        let metadata = result.metadata.as_ref();
+        let detail = match (metadata, &result.window_info) {
+            (Some(ResultMetadata::Bookmark { folder, .. }), _) => folder.clone(),
+            (Some(_), _) => None,
+            (None, Some(window)) => Some(format!("{} ({})", window.process_name, window.class_name)),
+            (None, None) if result.description.is_empty() => None,
+            (None, None) => Some(result.description.clone()),
+        };
+        Self {
+            title: &result.title,
+            kind: kind(&result.result_type),
+            url: metadata.map(ResultMetadata::url),
+            detail,
+            visit_count: metadata.and_then(ResultMetadata::visit_count),
+            last_visit: metadata.and_then(ResultMetadata::last_visit).map(|time| time.to_rfc3339()),
+        }
+    }
+}
+
+/// 書き出しで使う結果の種類の名前
+fn kind(result_type: &ResultType) -> &'static str {
+    match result_type {
+        ResultType::GoogleSearch => "web_search",
+        ResultType::Bookmark => "bookmark",
+        ResultType::History => "history",
+        ResultType::Window => "window",
+        ResultType::Tab => "tab",
+        ResultType::PinnedTab => "pinned_tab",
+        ResultType::ReadingList => "reading_list",
+        ResultType::Update => "update",
+        ResultType::Command => "command",
+        ResultType::Note => "note",
+        ResultType::Timer => "timer",
+        ResultType::Color { .. } => "color",
+    }
+}
+
+/// 検索結果を指定した形式の文字列にする
+pub fn export(results: &[SearchResult], format: ExportFormat) -> String {
+    let rows: Vec<Row> = results.iter().map(Row::from).collect();
+    match format {
+        ExportFormat::Markdown => to_markdown(&rows),
+        ExportFormat::Csv => to_csv(&rows),
+        ExportFormat::Json => to_json(&rows),
+    }
+}
+
+/// URL のある結果はリンクの箇条書き、それ以外はタイトルと補足
+fn to_markdown(rows: &[Row]) -> String {
+    let mut out = String::new();
+    for row in rows {
+        let title = row.title.replace('[', "\\[").replace(']', "\\]");
+        match (row.url, &row.detail) {
+            (Some(url), Some(detail)) => out.push_str(&format!("- [{}]({}) — {}\n", title, url, detail)),
+            (Some(url), None) => out.push_str(&format!("- [{}]({})\n", title, url)),
+            (None, Some(detail)) => out.push_str(&format!("- {} — {}\n", title, detail)),
+            (None, None) => out.push_str(&format!("- {}\n", title)),
+        }
+    }
+    out
+}
+
+fn to_csv(rows: &[Row]) -> String {
+    let mut out = String::from("title,type,url,detail,visit_count,last_visit\n");
+    for row in rows {
+        let fields = [
+            row.title.to_string(),
+            row.kind.to_string(),
+            row.url.unwrap_or_default().to_string(),
+            row.detail.clone().unwrap_or_default(),
+            row.visit_count.map(|count| count.to_string()).unwrap_or_default(),
+            row.last_visit.clone().unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// カンマ・引用符・改行を含む値は引用符で囲む
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_json(rows: &[Row]) -> String {
+    let items: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            json!({
+                "title": row.title,
+                "type": row.kind,
+                "url": row.url,
+                "detail": row.detail,
+                "visit_count": row.visit_count,
+                "last_visit": row.last_visit,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&items).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::search_engine::Action;
+
+    fn results() -> Vec<SearchResult> {
+        vec![
+            SearchResult {
+                title: "Rust [book]".to_string(),
+                description: "https://doc.rust-lang.org/book/".to_string(),
+                action: Action::OpenBookmark("https://doc.rust-lang.org/book/".to_string()),
+                window_info: None,
+                result_type: ResultType::Bookmark,
+                group: None,
+                metadata: Some(ResultMetadata::Bookmark {
+                    url: "https://doc.rust-lang.org/book/".to_string(),
+                    folder: Some("Dev, Rust".to_string()),
+                    title: "Rust".to_string(),
+                    source: None,
+                }),
+            },
+            SearchResult {
+                title: "Close duplicate tabs".to_string(),
+                description: String::new(),
+                action: Action::CloseDuplicateTabs,
+                window_info: None,
+                result_type: ResultType::Command,
+                group: None,
+                metadata: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_export_markdown_and_csv() {
+        assert_eq!(
+            export(&results(), ExportFormat::Markdown),
+            "- [Rust \\[book\\]](https://doc.rust-lang.org/book/) — Dev, Rust\n- Close duplicate tabs\n"
+        );
+        assert_eq!(
+            export(&results(), ExportFormat::Csv),
+            "title,type,url,detail,visit_count,last_visit\n\
+             Rust [book],bookmark,https://doc.rust-lang.org/book/,\"Dev, Rust\",,\n\
+             Close duplicate tabs,command,,,,\n"
+        );
+    }
+
+    #[test]
+    fn test_export_json() {
+        let value: serde_json::Value = serde_json::from_str(&export(&results(), ExportFormat::Json)).unwrap();
+        assert_eq!(value[0]["type"], "bookmark");
+        assert_eq!(value[0]["url"], "https://doc.rust-lang.org/book/");
+        assert!(value[1]["url"].is_null());
+    }
+}
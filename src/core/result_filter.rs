@@ -0,0 +1,218 @@
+use super::result_composer::ResultCategory;
+use super::search_engine::{ResultType, SearchResult};
+use std::collections::BTreeSet;
+
+/// Browserモードの結果の絞り込み（フィルターチップの状態）
+///
+/// どちらの集合も空なら絞り込まない。プロファイルの絞り込みはプロファイルを持つ結果
+/// （ブックマーク・履歴）にだけ適用する。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResultFilter {
+    pub categories: BTreeSet<ResultCategory>,
+    /// `ブラウザ - プロファイル`（例: `Chrome - Personal`）
+    pub profiles: BTreeSet<String>,
+}
+
+impl ResultFilter {
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty() && self.profiles.is_empty()
+    }
+
+    pub fn allows_category(&self, category: ResultCategory) -> bool {
+        self.categories.is_empty() || self.categories.contains(&category) || self.categories.contains(&category.base())
+    }
+
+    pub fn allows_profile(&self, profile: Option<&str>) -> bool {
+        match profile {
+            Some(profile) => self.profiles.is_empty() || self.profiles.contains(profile),
+            None => true,
+        }
+    }
+}
+
+/// 絞り込みを切り替えるチップ
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterChip {
+    Category(ResultCategory),
+    Profile(String),
+}
+
+impl FilterChip {
+    pub fn label(&self) -> String {
+        match self {
+            FilterChip::Category(category) => match category {
+                ResultCategory::Google => "Google".to_string(),
+                ResultCategory::Command => "Commands".to_string(),
+                ResultCategory::Bookmark => "Bookmarks".to_string(),
+                ResultCategory::History => "History".to_string(),
+                ResultCategory::Tab => "Tabs".to_string(),
+                ResultCategory::PinnedTab => "Pinned tabs".to_string(),
+                ResultCategory::ReadingList => "Reading list".to_string(),
+            },
+            FilterChip::Profile(profile) => profile.clone(),
+        }
+    }
+
+    pub fn is_active(&self, filter: &ResultFilter) -> bool {
+        match self {
+            FilterChip::Category(category) => filter.categories.contains(category),
+            FilterChip::Profile(profile) => filter.profiles.contains(profile),
+        }
+    }
+
+    pub fn toggle(&self, filter: &mut ResultFilter) {
+        match self {
+            FilterChip::Category(category) => {
+                if !filter.categories.remove(category) {
+                    filter.categories.insert(*category);
+                }
+            }
+            FilterChip::Profile(profile) => {
+                if !filter.profiles.remove(profile) {
+                    filter.profiles.insert(profile.clone());
+                }
+            }
+        }
+    }
+}
+
+/// 表示するチップ（結果の種類、続いて検出したプロファイル）
+pub fn filter_chips(profiles: &[String]) -> Vec<FilterChip> {
+    [ResultCategory::Bookmark, ResultCategory::History, ResultCategory::Tab]
+        .into_iter()
+        .map(FilterChip::Category)
+        .chain(profiles.iter().cloned().map(FilterChip::Profile))
+        .collect()
+}
+
+/// Alt+1..5 で結果を 1 種類に絞る一時的な絞り込み
+///
+/// 検索元には渡さず、直前の検索結果から選ぶだけなので検索し直さない。設定にも残さない。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickFilter {
+    Tabs,
+    Bookmarks,
+    History,
+    Search,
+    Windows,
+}
+
+impl QuickFilter {
+    /// Alt+1..5 の順
+    pub const ALL: [QuickFilter; 5] =
+        [QuickFilter::Tabs, QuickFilter::Bookmarks, QuickFilter::History, QuickFilter::Search, QuickFilter::Windows];
+
+    pub fn result_type(&self) -> ResultType {
+        match self {
+            QuickFilter::Tabs => ResultType::Tab,
+            QuickFilter::Bookmarks => ResultType::Bookmark,
+            QuickFilter::History => ResultType::History,
+            QuickFilter::Search => ResultType::GoogleSearch,
+            QuickFilter::Windows => ResultType::Window,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            QuickFilter::Tabs => "Tabs",
+            QuickFilter::Bookmarks => "Bookmarks",
+            QuickFilter::History => "History",
+            QuickFilter::Search => "Search",
+            QuickFilter::Windows => "Windows",
+        }
+    }
+
+    /// 押した絞り込みに切り替える（同じものをもう一度押したら外す）
+    pub fn toggle(current: Option<Self>, pressed: Self) -> Option<Self> {
+        if current == Some(pressed) {
+            None
+        } else {
+            Some(pressed)
+        }
+    }
+
+    /// 絞り込んだ結果（`None` ならすべて）
+    pub fn apply(filter: Option<Self>, results: &[SearchResult]) -> Vec<SearchResult> {
+        match filter {
+            Some(filter) => {
+                let result_type = filter.result_type();
+                // タブの絞り込みにはピン留めしたタブも含める
+                results
+                    .iter()
+                    .filter(|result| result.result_type == result_type || (result_type.is_tab() && result.result_type.is_tab()))
+                    .cloned()
+                    .collect()
+            }
+            None => results.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::search_engine::Action;
+
+    fn result(title: &str, result_type: ResultType) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            description: String::new(),
+            action: Action::OpenUrl(format!("https://example.com/{}", title)),
+            window_info: None,
+            result_type,
+            group: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_empty_filter_allows_everything() {
+        let filter = ResultFilter::default();
+        assert!(filter.allows_category(ResultCategory::Google));
+        assert!(filter.allows_profile(Some("Chrome - Work")));
+        assert!(filter.allows_profile(None));
+    }
+
+    #[test]
+    fn test_toggle_chips() {
+        let chips = filter_chips(&["Chrome - Work".to_string(), "Wavebox - Personal".to_string()]);
+        assert_eq!(chips.len(), 5);
+        assert_eq!(chips[3].label(), "Chrome - Work");
+
+        let mut filter = ResultFilter::default();
+        chips[0].toggle(&mut filter);
+        chips[3].toggle(&mut filter);
+        assert!(chips[0].is_active(&filter));
+        assert!(filter.allows_category(ResultCategory::Bookmark));
+        assert!(!filter.allows_category(ResultCategory::Tab));
+        assert!(filter.allows_profile(Some("Chrome - Work")));
+        assert!(!filter.allows_profile(Some("Wavebox - Personal")));
+        // プロファイルを持たない結果は対象外
+        assert!(filter.allows_profile(None));
+
+        chips[0].toggle(&mut filter);
+        chips[3].toggle(&mut filter);
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn test_quick_filter() {
+        let results = vec![
+            result("tab", ResultType::Tab),
+            result("google", ResultType::GoogleSearch),
+            result("pinned", ResultType::PinnedTab),
+            result("bookmark", ResultType::Bookmark),
+        ];
+        let titles = |filter| QuickFilter::apply(filter, &results).into_iter().map(|r| r.title).collect::<Vec<_>>();
+        assert_eq!(titles(Some(QuickFilter::Tabs)), vec!["tab", "pinned"]);
+        assert_eq!(titles(Some(QuickFilter::Search)), vec!["google"]);
+        assert!(titles(Some(QuickFilter::Windows)).is_empty());
+        assert_eq!(titles(None).len(), 4);
+
+        // 同じキーをもう一度押すと外し、別のキーなら切り替える
+        let filter = QuickFilter::toggle(None, QuickFilter::History);
+        assert_eq!(filter, Some(QuickFilter::History));
+        assert_eq!(QuickFilter::toggle(filter, QuickFilter::Tabs), Some(QuickFilter::Tabs));
+        assert_eq!(QuickFilter::toggle(filter, QuickFilter::History), None);
+    }
+}
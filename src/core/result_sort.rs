@@ -0,0 +1,134 @@
+use super::result_composer::group_results;
+use super::SearchResult;
+use std::cmp::Ordering;
+
+/// Browserモードの結果の並べ替え（Ctrl+S で切り替え）
+///
+/// 説明文ではなく `ResultMetadata` の値で並べる。並べ替えるのはページ（metadata のある結果）だけで、
+/// Google の行やコマンドは元の順のまま先頭に残す。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// 検索したときの並び（ranking の設定どおり）
+    #[default]
+    Relevance,
+    /// 最終訪問の新しい順
+    Recent,
+    /// 訪問回数の多い順
+    Visits,
+    /// タイトルの順（大文字小文字を区別しない）
+    Alphabetical,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 4] = [SortOrder::Relevance, SortOrder::Recent, SortOrder::Visits, SortOrder::Alphabetical];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortOrder::Relevance => "Relevance",
+            SortOrder::Recent => "Last visit",
+            SortOrder::Visits => "Visit count",
+            SortOrder::Alphabetical => "A–Z",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|order| order == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// 結果を並べ替える（値を持たない結果は後ろに元の順で並べる）
+    pub fn apply(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        if *self == SortOrder::Relevance {
+            return results;
+        }
+
+        let (mut pages, others): (Vec<SearchResult>, Vec<SearchResult>) =
+            results.into_iter().partition(|result| result.metadata.is_some());
+        // 安定ソートなので、同じ値の結果は元の順のまま
+        pages.sort_by(|a, b| self.compare(a, b));
+        group_results(others.into_iter().chain(pages).collect())
+    }
+
+    fn compare(&self, a: &SearchResult, b: &SearchResult) -> Ordering {
+        // 値のあるほうを先に、大きいほうを先に
+        fn descending<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) => b.cmp(&a),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+        match self {
+            SortOrder::Relevance => Ordering::Equal,
+            SortOrder::Recent => descending(
+                a.metadata.as_ref().and_then(|metadata| metadata.last_visit()),
+                b.metadata.as_ref().and_then(|metadata| metadata.last_visit()),
+            ),
+            SortOrder::Visits => descending(
+                a.metadata.as_ref().and_then(|metadata| metadata.visit_count()),
+                b.metadata.as_ref().and_then(|metadata| metadata.visit_count()),
+            ),
+            SortOrder::Alphabetical => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Action, ResultMetadata, ResultType};
+    use chrono::{TimeZone, Utc};
+
+    fn history(title: &str, visit_count: i32, day: Option<u32>) -> SearchResult {
+        let url = format!("https://example.com/{}", title);
+        SearchResult {
+            title: title.to_string(),
+            description: url.clone(),
+            action: Action::OpenHistory(url.clone()),
+            window_info: None,
+            result_type: ResultType::History,
+            group: None,
+            metadata: Some(ResultMetadata::History {
+                url,
+                visit_count,
+                last_visit: day.map(|day| Utc.with_ymd_and_hms(2024, 5, day, 0, 0, 0).unwrap()),
+            }),
+        }
+    }
+
+    fn sample() -> Vec<SearchResult> {
+        let google = SearchResult {
+            title: "Google: rust".to_string(),
+            description: String::new(),
+            action: Action::GoogleSearch("rust".to_string()),
+            window_info: None,
+            result_type: ResultType::GoogleSearch,
+            group: None,
+            metadata: None,
+        };
+        vec![google, history("book", 3, Some(2)), history("Async", 10, None), history("cargo", 1, Some(9))]
+    }
+
+    fn titles(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|result| result.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        assert_eq!(titles(&SortOrder::Relevance.apply(sample())), vec!["Google: rust", "book", "Async", "cargo"]);
+        assert_eq!(titles(&SortOrder::Recent.apply(sample())), vec!["Google: rust", "cargo", "book", "Async"]);
+        assert_eq!(titles(&SortOrder::Visits.apply(sample())), vec!["Google: rust", "Async", "book", "cargo"]);
+        assert_eq!(titles(&SortOrder::Alphabetical.apply(sample())), vec!["Google: rust", "Async", "book", "cargo"]);
+    }
+
+    #[test]
+    fn test_next_cycles() {
+        let mut order = SortOrder::default();
+        for _ in 0..SortOrder::ALL.len() {
+            order = order.next();
+        }
+        assert_eq!(order, SortOrder::Relevance);
+        assert_eq!(SortOrder::Relevance.next(), SortOrder::Recent);
+    }
+}
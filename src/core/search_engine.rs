@@ -1,9 +1,105 @@
 use super::window_manager::WindowInfo;
+use super::query::Query;
+use super::result_composer::merge_alternatives;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchMode {
     Browser,
     Windows,
+    /// Chromeで開いているタブのみを検索
+    Tabs,
+    /// 接頭辞で振り分ける（`w ` ウィンドウ、`>` コマンド、URL はそのまま開き、それ以外は Google 検索）
+    General,
+}
+
+/// General モードでウィンドウを検索する接頭辞
+pub const WINDOW_PREFIX: &str = "w ";
+
+/// General モードでコマンドを実行する接頭辞
+pub const COMMAND_PREFIX: &str = ">";
+
+/// General モードのクエリの振り分け先
+#[derive(Debug, PartialEq)]
+pub enum GeneralRoute<'a> {
+    Empty,
+    /// 接頭辞を除いたウィンドウの検索語
+    Windows(&'a str),
+    /// 接頭辞を除いたコマンド
+    Command(&'a str),
+    /// 開く URL（`www.` で始まるものには `https://` を付ける）
+    Url(String),
+    /// Google で検索する
+    Web(&'a str),
+}
+
+/// General モードのクエリを接頭辞と形で振り分ける
+pub fn route_general(query: &str) -> GeneralRoute<'_> {
+    let query = query.trim_start();
+    if let Some(rest) = query.strip_prefix(WINDOW_PREFIX) {
+        return GeneralRoute::Windows(rest.trim());
+    }
+    if let Some(rest) = query.strip_prefix(COMMAND_PREFIX) {
+        return GeneralRoute::Command(rest.trim());
+    }
+    let query = query.trim_end();
+    if query.is_empty() {
+        GeneralRoute::Empty
+    } else if query.contains(char::is_whitespace) {
+        GeneralRoute::Web(query)
+    } else if query.contains("://") {
+        GeneralRoute::Url(query.to_string())
+    } else if query.starts_with("www.") {
+        GeneralRoute::Url(format!("https://{}", query))
+    } else {
+        GeneralRoute::Web(query)
+    }
+}
+
+/// Google 検索の URL（フォームと同じく空白は `+` にする）
+pub fn google_search_url(query: &str) -> String {
+    format!("https://www.google.com/search?q={}", url::form_urlencoded::byte_serialize(query.as_bytes()).collect::<String>())
+}
+
+/// `>` で入力したコマンドを実行する行
+pub fn run_command_result(command: &str) -> SearchResult {
+    SearchResult {
+        title: format!("Run: {}", command),
+        description: "Run in a shell".to_string(),
+        action: Action::RunCommand(command.to_string()),
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// 入力した URL を開く行
+pub fn open_url_result(url: &str) -> SearchResult {
+    SearchResult {
+        title: url.to_string(),
+        description: "Open URL".to_string(),
+        action: Action::OpenUrl(url.to_string()),
+        window_info: None,
+        result_type: ResultType::GoogleSearch,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// General モードで Google 検索を開く行
+pub fn google_url_result(query: &str) -> SearchResult {
+    SearchResult {
+        title: format!("Google: {}", query),
+        description: "Search on Google".to_string(),
+        action: Action::OpenUrl(google_search_url(query)),
+        window_info: None,
+        result_type: ResultType::GoogleSearch,
+        group: None,
+        metadata: None,
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -13,6 +109,125 @@ pub enum Action {
     OpenBookmark(String), // URL
     OpenHistory(String),  // URL
     SwitchToTab { tab_id: i32, window_id: i32 },  // Chrome tab
+    OpenUrl(String),
+    /// ブラウザのプロファイルを指定して URL を開く（`profile` は `Chrome - Profile 1` の形式）
+    OpenInProfile { profile: String, url: String },
+    /// URL の代わりにアプリで開く（`app` がなければ `target` を既定のアプリで開く）
+    OpenInApp { app: Option<String>, target: String },
+    /// 組み込みコマンド: 重複したタブを閉じる
+    CloseDuplicateTabs,
+    /// 組み込みコマンド: 開いているタブをウィンドウ・ドメインごとに一覧する
+    OpenTabsOverview,
+    /// 組み込みコマンド: プレビュー画像・ファビコンのキャッシュを消す
+    ClearCaches,
+    /// タブをブックマークに追加
+    CreateBookmark { url: String, title: String, folder: Option<String> },
+    /// クイックメモを追記
+    AppendNote(String),
+    /// メモファイルを開く（該当する行番号）
+    OpenNote { line: usize },
+    /// タイマー・リマインダーを設定
+    StartTimer(crate::timers::TimerRequest),
+    /// 設定中のタイマーを取り消す
+    CancelTimer(u64),
+    /// 現在のウィンドウ配置を名前を付けて保存
+    SaveLayout(String),
+    /// 保存したウィンドウ配置を復元
+    RestoreLayout(String),
+    /// 保存したウィンドウ配置を削除
+    DeleteLayout(String),
+    /// 前回のセッションで開いていて、今は動いていないアプリを起動し直す
+    RestoreSession,
+    /// ウィンドウ・プロファイルにワークスペースのタグを付ける
+    AssignTag(crate::data::workspaces::TagRule),
+    /// ワークスペースのタグのルールを削除
+    RemoveTag(crate::data::workspaces::TagRule),
+    /// キーワードプロバイダーのコマンドを実行
+    ProviderCommand { provider: String, command: String },
+    /// テキストをクリップボードにコピー
+    CopyToClipboard(String),
+    /// 設定したマクロを実行（名前）
+    RunMacro(String),
+    /// シェルでコマンドを実行（General モードの `>`）
+    RunCommand(String),
+    /// 最近閉じたタブを拡張機能で開き直す（拡張機能がなければ `url` を開く）
+    RestoreClosedTab { session_id: String, url: String },
+    /// 拡張機能でブックマークを 1 件削除する（Shift+Delete、確認してから実行）
+    DeleteBookmark { url: String, title: String, source: BookmarkSource },
+}
+
+impl SearchMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchMode::Browser => "browser",
+            SearchMode::Windows => "windows",
+            SearchMode::Tabs => "tabs",
+            SearchMode::General => "general",
+        }
+    }
+
+    /// `browser` / `windows` / `tabs` / `general`（外部から指定するときの名前）
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "browser" => Some(SearchMode::Browser),
+            "windows" => Some(SearchMode::Windows),
+            "tabs" => Some(SearchMode::Tabs),
+            "general" => Some(SearchMode::General),
+            _ => None,
+        }
+    }
+}
+
+impl Action {
+    /// 結果を識別する ID（選択の保持や、IPC で外部から実行するときに使う）
+    pub fn id(&self) -> String {
+        match self {
+            Action::SwitchWindow(hwnd) => hwnd.to_string(),
+            Action::GoogleSearch(query) => format!("google:{}", query),
+            Action::OpenBookmark(url) => format!("bookmark:{}", url),
+            Action::OpenHistory(url) => format!("history:{}", url),
+            Action::SwitchToTab { tab_id, window_id } => format!("tab:{}:{}", tab_id, window_id),
+            Action::OpenUrl(url) => format!("url:{}", url),
+            Action::OpenInProfile { profile, url } => format!("url:{}:{}", profile, url),
+            Action::OpenInApp { target, .. } => format!("app:{}", target),
+            Action::CloseDuplicateTabs => "command:close_duplicate_tabs".to_string(),
+            Action::OpenTabsOverview => "command:tabs_overview".to_string(),
+            Action::ClearCaches => "command:clear_caches".to_string(),
+            Action::CreateBookmark { url, .. } => format!("bookmark_add:{}", url),
+            Action::AppendNote(text) => format!("note_add:{}", text),
+            Action::OpenNote { line } => format!("note:{}", line),
+            Action::StartTimer(request) => format!("timer_add:{}:{}", request.due.timestamp(), request.label),
+            Action::CancelTimer(id) => format!("timer:{}", id),
+            Action::SaveLayout(name) => format!("layout_save:{}", name),
+            Action::RestoreLayout(name) => format!("layout:{}", name),
+            Action::DeleteLayout(name) => format!("layout_delete:{}", name),
+            Action::RestoreSession => "command:restore_session".to_string(),
+            Action::AssignTag(rule) => format!("tag_add:{}:{}", rule.tag, rule.target.label()),
+            Action::RemoveTag(rule) => format!("tag_remove:{}:{}", rule.tag, rule.target.label()),
+            Action::ProviderCommand { provider, command } => format!("{}:{}", provider, command),
+            Action::CopyToClipboard(text) => format!("copy:{}", text),
+            Action::RunMacro(name) => format!("macro:{}", name),
+            Action::RunCommand(command) => format!("run:{}", command),
+            Action::RestoreClosedTab { session_id, .. } => format!("closed_tab:{}", session_id),
+            Action::DeleteBookmark { source, .. } => format!("bookmark_delete:{}", source.id),
+        }
+    }
+
+    /// ランチャーのウィンドウなしで `LauncherCore::execute_action` だけで実行できるか
+    ///
+    /// シェルのコマンドは、IPC や HTTP から任意のコマンドを実行できないよう含めない。
+    pub fn is_headless(&self) -> bool {
+        matches!(
+            self,
+            Action::SwitchWindow(_)
+                | Action::GoogleSearch(_)
+                | Action::OpenBookmark(_)
+                | Action::OpenHistory(_)
+                | Action::OpenUrl(_)
+                | Action::OpenInProfile { .. }
+                | Action::OpenInApp { .. }
+        )
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -22,6 +237,23 @@ pub enum ResultType {
     History,
     Window,
     Tab,
+    /// ピン留めしたタブ（ふつうのタブより上に並べる）
+    PinnedTab,
+    /// Chrome のリーディングリストの項目
+    ReadingList,
+    Update,
+    Command,
+    Note,
+    Timer,
+    /// 色の見本付きで表示する
+    Color { rgb: [u8; 3] },
+}
+
+impl ResultType {
+    /// 開いているタブ（ピン留めを含む）
+    pub fn is_tab(&self) -> bool {
+        matches!(self, ResultType::Tab | ResultType::PinnedTab)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -31,11 +263,121 @@ pub struct SearchResult {
     pub action: Action,
     pub window_info: Option<WindowInfo>,
     pub result_type: ResultType,
+    /// 見出しの下にまとめて表示するグループ（例: `Chrome - Personal`）
+    pub group: Option<String>,
+    /// 一覧の列に表示する情報（表示の形式は UI で決める）
+    pub metadata: Option<ResultMetadata>,
+}
+
+impl SearchResult {
+    /// 別のウィンドウに貼り付けるテキスト（URL を持つ結果は URL、コピーする結果はその文字列）
+    pub fn paste_text(&self) -> Option<String> {
+        if let Some(metadata) = &self.metadata {
+            return Some(metadata.url().to_string());
+        }
+        match &self.action {
+            Action::OpenBookmark(url)
+            | Action::OpenHistory(url)
+            | Action::OpenUrl(url)
+            | Action::OpenInProfile { url, .. }
+            | Action::RestoreClosedTab { url, .. } => Some(url.clone()),
+            Action::CopyToClipboard(text) => Some(text.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// ブックマークを読み込んだブラウザ・プロファイルと、その中での ID
+#[derive(Clone, Debug, PartialEq)]
+pub struct BookmarkSource {
+    /// Bookmarks ファイルのノードの ID（拡張機能の `chrome.bookmarks` と同じ値）
+    pub id: String,
+    pub browser: String,
+    pub profile: String,
+}
+
+impl BookmarkSource {
+    /// `Chrome - Profile 1` の形式
+    pub fn label(&self) -> String {
+        format!("{} - {}", self.browser, self.profile)
+    }
+}
+
+/// 結果の種類ごとの構造化した情報
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResultMetadata {
+    /// `title` は `[ブラウザ - プロファイル]` を付ける前のブックマークの名前
+    Bookmark { url: String, folder: Option<String>, title: String, source: Option<BookmarkSource> },
+    History { url: String, visit_count: i32, last_visit: Option<DateTime<Utc>> },
+    /// `window_number` はタブ一覧で付ける、開いているウィンドウの番号（1から）
+    /// `stale` は拡張機能からしばらく更新がなく、切り替えに失敗するかもしれないタブ
+    Tab { url: String, active: bool, window_number: Option<usize>, stale: bool },
+}
+
+impl ResultMetadata {
+    pub fn url(&self) -> &str {
+        match self {
+            ResultMetadata::Bookmark { url, .. }
+            | ResultMetadata::History { url, .. }
+            | ResultMetadata::Tab { url, .. } => url,
+        }
+    }
+
+    /// URL のホスト名（`www.` を除く）
+    pub fn domain(&self) -> &str {
+        let url = self.url();
+        let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+        host.strip_prefix("www.").unwrap_or(host)
+    }
+
+    pub fn visit_count(&self) -> Option<i32> {
+        match self {
+            ResultMetadata::History { visit_count, .. } => Some(*visit_count),
+            _ => None,
+        }
+    }
+
+    pub fn is_stale(&self) -> bool {
+        matches!(self, ResultMetadata::Tab { stale: true, .. })
+    }
+
+    pub fn last_visit(&self) -> Option<DateTime<Utc>> {
+        match self {
+            ResultMetadata::History { last_visit, .. } => *last_visit,
+            _ => None,
+        }
+    }
 }
 
 pub trait SearchEngine {
-    fn search(&self, query: &str, mode: SearchMode, windows: &[WindowInfo]) -> Vec<SearchResult>;
+    /// 解析済みのクエリで検索
+    fn search_parsed(&self, query: &Query, mode: SearchMode, windows: &[WindowInfo]) -> Vec<SearchResult>;
     fn is_window_search(&self, query: &str, mode: SearchMode) -> bool;
+
+    /// 文字列をクエリとして解析（キーワードを持つ検索エンジンはキーワードを渡すよう上書きする）
+    fn parse_query(&self, query: &str) -> Query {
+        Query::parse(query, &[])
+    }
+
+    /// `foo; bar` なら各クエリで検索して結果を合わせる（区切っていなければ `None`）
+    fn search_alternatives(&self, query: &Query, mode: SearchMode, windows: &[WindowInfo]) -> Option<Vec<SearchResult>> {
+        if query.alternatives.is_empty() {
+            return None;
+        }
+        let lists = query.alternatives.iter().map(|alternative| self.search_parsed(alternative, mode, windows)).collect();
+        Some(merge_alternatives(lists))
+    }
+
+    /// 文字列のクエリで検索（以前のシグネチャとの互換用）
+    fn search(&self, query: &str, mode: SearchMode, windows: &[WindowInfo]) -> Vec<SearchResult> {
+        self.search_parsed(&self.parse_query(query), mode, windows)
+    }
+
+    /// 取り消せない操作なら、何が起きるかをまとめて返す（`None` ならそのまま実行する）
+    fn preview(&self, _action: &Action, _windows: &[WindowInfo]) -> Option<super::confirmation::ConfirmableAction> {
+        None
+    }
 }
 
 pub struct DefaultSearchEngine;
@@ -47,7 +389,14 @@ impl DefaultSearchEngine {
 }
 
 impl SearchEngine for DefaultSearchEngine {
-    fn search(&self, query: &str, mode: SearchMode, windows: &[WindowInfo]) -> Vec<SearchResult> {
+    fn search_parsed(&self, parsed: &Query, mode: SearchMode, windows: &[WindowInfo]) -> Vec<SearchResult> {
+        // General モードは `>` のコマンドに `;` を含められるよう、振り分けてから区切る
+        if mode != SearchMode::General {
+            if let Some(results) = self.search_alternatives(parsed, mode, windows) {
+                return results;
+            }
+        }
+        let query = parsed.raw.as_str();
         let mut results = Vec::new();
 
         match mode {
@@ -60,6 +409,8 @@ impl SearchEngine for DefaultSearchEngine {
                         action: Action::GoogleSearch(query.to_string()),
                         window_info: None,
                         result_type: ResultType::GoogleSearch,
+                        group: None,
+                        metadata: None,
                     });
                     
                     // TODO: ブックマークと履歴の検索結果を追加
@@ -67,44 +418,57 @@ impl SearchEngine for DefaultSearchEngine {
                 }
             }
             SearchMode::Windows => {
-                if query.is_empty() {
-                    for window in windows {
-                        results.push(SearchResult {
-                            title: window.title.clone(),
-                            description: format!("{} - {}", window.process_name, window.class_name),
-                            action: Action::SwitchWindow(window.hwnd),
-                            window_info: Some(window.clone()),
-                            result_type: ResultType::Window,
-                        });
-                    }
-                } else {
-                    for window in windows {
-                        if window.contains_text(query) {
-                            results.push(SearchResult {
-                                title: window.title.clone(),
-                                description: format!("{} - {}", window.process_name, window.class_name),
-                                action: Action::SwitchWindow(window.hwnd),
-                                window_info: Some(window.clone()),
-                                result_type: ResultType::Window,
-                            });
-                        }
-                    }
-
-                    if results.len() > 10 {
-                        results.truncate(10);
+                results = window_results(query, windows);
+            }
+            SearchMode::Tabs => {
+                // タブの情報源がないため結果なし
+            }
+            SearchMode::General => match route_general(query) {
+                GeneralRoute::Empty => {}
+                GeneralRoute::Windows(text) => results = window_results(text, windows),
+                GeneralRoute::Command(command) => {
+                    if !command.is_empty() {
+                        results.push(run_command_result(command));
                     }
                 }
-            }
+                GeneralRoute::Url(url) => {
+                    results.push(open_url_result(&url));
+                    results.push(google_url_result(query.trim()));
+                }
+                GeneralRoute::Web(text) => results.push(google_url_result(text)),
+            },
         }
 
         results
     }
 
-    fn is_window_search(&self, _query: &str, mode: SearchMode) -> bool {
-        mode == SearchMode::Windows
+    fn is_window_search(&self, query: &str, mode: SearchMode) -> bool {
+        match mode {
+            SearchMode::Windows => true,
+            SearchMode::General => matches!(route_general(query), GeneralRoute::Windows(_)),
+            _ => false,
+        }
     }
 }
 
+/// タイトルかプロセス名にクエリを含むウィンドウ（空のクエリならすべて、検索したときは 10 件まで）
+fn window_results(query: &str, windows: &[WindowInfo]) -> Vec<SearchResult> {
+    let matching = windows.iter().filter(|window| query.is_empty() || window.contains_text(query));
+    let limit = if query.is_empty() { usize::MAX } else { 10 };
+    matching
+        .take(limit)
+        .map(|window| SearchResult {
+            title: window.title.clone(),
+            description: window.process_name.clone(),
+            action: Action::SwitchWindow(window.hwnd),
+            window_info: Some(window.clone()),
+            result_type: ResultType::Window,
+            group: None,
+            metadata: None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,6 +483,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 1920, 1080),
+                requires_elevation: false,
             },
             WindowInfo {
                 hwnd: 2,
@@ -128,6 +493,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 1920, 1080),
+                requires_elevation: false,
             },
             WindowInfo {
                 hwnd: 3,
@@ -137,6 +503,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: true,
                 rect: (100, 100, 800, 600),
+                requires_elevation: false,
             },
         ]
     }
@@ -204,6 +571,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 100, 100),
+                requires_elevation: false,
             });
         }
         
@@ -236,4 +604,95 @@ mod tests {
         assert_eq!(results[0].action, Action::GoogleSearch("日本語検索".to_string()));
         assert_eq!(results[0].result_type, ResultType::GoogleSearch);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_metadata_domain() {
+        let bookmark = ResultMetadata::Bookmark {
+            url: "https://www.rust-lang.org/learn?x=1".to_string(),
+            folder: None,
+            title: "Learn Rust".to_string(),
+            source: None,
+        };
+        assert_eq!(bookmark.domain(), "rust-lang.org");
+        let tab = ResultMetadata::Tab { url: "chrome://extensions".to_string(), active: true, window_number: None, stale: false };
+        assert_eq!(tab.domain(), "extensions");
+        assert_eq!(tab.visit_count(), None);
+    }
+
+    #[test]
+    fn test_paste_text() {
+        let mut result = SearchResult {
+            title: "Rust".to_string(),
+            description: String::new(),
+            action: Action::SwitchToTab { tab_id: 1, window_id: 2 },
+            window_info: None,
+            result_type: ResultType::Tab,
+            group: None,
+            metadata: Some(ResultMetadata::Tab { url: "https://www.rust-lang.org/".to_string(), active: false, window_number: None, stale: false }),
+        };
+        assert_eq!(result.paste_text().as_deref(), Some("https://www.rust-lang.org/"));
+
+        result.metadata = None;
+        assert_eq!(result.paste_text(), None);
+        result.action = Action::CopyToClipboard("42".to_string());
+        assert_eq!(result.paste_text().as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_action_id_and_mode_name() {
+        assert_eq!(Action::OpenUrl("https://example.com".to_string()).id(), "url:https://example.com");
+        assert_eq!(Action::RunMacro("standup".to_string()).id(), "macro:standup");
+        assert!(Action::SwitchWindow(1).is_headless());
+        assert!(!Action::SwitchToTab { tab_id: 1, window_id: 2 }.is_headless());
+
+        assert_eq!(SearchMode::from_name("Tabs"), Some(SearchMode::Tabs));
+        assert_eq!(SearchMode::from_name(SearchMode::Browser.name()), Some(SearchMode::Browser));
+        assert_eq!(SearchMode::from_name("grid"), None);
+        assert_eq!(SearchMode::from_name("general"), Some(SearchMode::General));
+        assert_eq!(Action::RunCommand("dir".to_string()).id(), "run:dir");
+        assert!(!Action::RunCommand("dir".to_string()).is_headless());
+    }
+
+    #[test]
+    fn test_route_general() {
+        assert_eq!(route_general("  "), GeneralRoute::Empty);
+        assert_eq!(route_general("w code"), GeneralRoute::Windows("code"));
+        assert_eq!(route_general(">dir /b"), GeneralRoute::Command("dir /b"));
+        assert_eq!(route_general("https://github.com"), GeneralRoute::Url("https://github.com".to_string()));
+        assert_eq!(route_general("www.rust-lang.org"), GeneralRoute::Url("https://www.rust-lang.org".to_string()));
+        assert_eq!(route_general("wiki rust"), GeneralRoute::Web("wiki rust"));
+        assert_eq!(google_search_url("c++ tips"), "https://www.google.com/search?q=c%2B%2B+tips");
+    }
+
+    #[test]
+    fn test_general_mode() {
+        let engine = DefaultSearchEngine::new();
+        let windows = create_test_windows();
+
+        let results = engine.search("w notepad", SearchMode::General, &windows);
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r.result_type == ResultType::Window));
+        assert!(engine.is_window_search("w notepad", SearchMode::General));
+
+        let results = engine.search(">", SearchMode::General, &windows);
+        assert!(results.is_empty());
+
+        let results = engine.search("www.rust-lang.org", SearchMode::General, &windows);
+        assert_eq!(results[0].action, Action::OpenUrl("https://www.rust-lang.org".to_string()));
+        assert_eq!(results[1].action, Action::OpenUrl("https://www.google.com/search?q=www.rust-lang.org".to_string()));
+
+        // `>` のコマンドは `;` で区切らない
+        let results = engine.search(">cd src; dir", SearchMode::General, &windows);
+        assert_eq!(results[0].action, Action::RunCommand("cd src; dir".to_string()));
+    }
+
+    #[test]
+    fn test_semicolon_searches_each_query() {
+        let engine = DefaultSearchEngine::new();
+        let windows = create_test_windows();
+
+        let results = engine.search("notepad; code; note", SearchMode::Windows, &windows);
+        let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["Notepad", "Visual Studio Code"]);
+    }
+}
@@ -0,0 +1,148 @@
+//! 上限を続けて超える検索元を抑える
+//!
+//! `TimedSource` が検索ごとにかかった時間を記録し、遅い検索が続いた検索元は
+//! 結果の数を減らして入力の待ち時間を延ばし（`Limited`）、さらに続けばしばらく検索しない（`Paused`）。
+//! 遅いディスク上の履歴などひとつの検索元のせいで、ランチャー全体が重くならないようにする。
+
+use crate::config::WatchdogConfig;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Throttle {
+    None,
+    /// 結果の数を減らし、入力の待ち時間を延ばす
+    Limited,
+    /// `until` まで検索しない
+    Paused { until: Instant },
+}
+
+impl Throttle {
+    /// 健康状態の表示に使う説明（抑えていなければ `None`）
+    pub fn describe(&self, now: Instant) -> Option<String> {
+        match self {
+            Throttle::None => None,
+            Throttle::Limited => Some("Slow searches; showing fewer results".to_string()),
+            Throttle::Paused { until } => Some(format!(
+                "Paused for {}s after repeated slow searches",
+                until.saturating_duration_since(now).as_secs().max(1)
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    config: WatchdogConfig,
+    /// 続けて上限を超えた回数
+    strikes: u32,
+    paused_until: Option<Instant>,
+}
+
+impl Watchdog {
+    pub fn new(config: WatchdogConfig) -> Self {
+        Self { config, strikes: 0, paused_until: None }
+    }
+
+    /// 検索にかかった時間を記録する
+    pub fn record(&mut self, name: &str, elapsed: Duration, budget: Duration, now: Instant) {
+        if !self.config.enabled {
+            return;
+        }
+        if elapsed <= budget {
+            if self.strikes >= self.config.limit_after {
+                log::info!("{} is fast again ({:?}); no longer throttled", name, elapsed);
+            }
+            self.strikes = 0;
+            self.paused_until = None;
+            return;
+        }
+
+        self.strikes += 1;
+        if self.strikes >= self.config.pause_after {
+            // 再開後も遅ければ、1 回でまた止める
+            log::warn!("{} exceeded its budget {} times in a row ({:?}); pausing for {}s", name, self.strikes, elapsed, self.config.pause_secs);
+            self.paused_until = Some(now + Duration::from_secs(self.config.pause_secs));
+        } else if self.strikes == self.config.limit_after {
+            log::warn!("{} exceeded its budget {} times in a row ({:?}); limiting results", name, self.strikes, elapsed);
+        }
+    }
+
+    pub fn throttle(&self, now: Instant) -> Throttle {
+        match self.paused_until {
+            Some(until) if now < until => Throttle::Paused { until },
+            _ if self.config.enabled && self.strikes >= self.config.limit_after => Throttle::Limited,
+            _ => Throttle::None,
+        }
+    }
+
+    /// 結果の数の上限（抑えていなければ制限しない）
+    pub fn result_cap(&self, now: Instant) -> usize {
+        match self.throttle(now) {
+            Throttle::None => usize::MAX,
+            _ => self.config.limited_results,
+        }
+    }
+
+    /// 入力の待ち時間に足す時間
+    pub fn extra_debounce(&self, now: Instant) -> Duration {
+        match self.throttle(now) {
+            Throttle::None => Duration::ZERO,
+            _ => Duration::from_millis(self.config.extra_debounce_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: Duration = Duration::from_millis(300);
+    const SLOW: Duration = Duration::from_millis(900);
+    const FAST: Duration = Duration::from_millis(20);
+
+    #[test]
+    fn test_slow_searches_limit_then_pause() {
+        let mut watchdog = Watchdog::new(WatchdogConfig::default());
+        let now = Instant::now();
+        for _ in 0..2 {
+            watchdog.record("history", SLOW, BUDGET, now);
+        }
+        assert_eq!(watchdog.throttle(now), Throttle::None);
+        assert_eq!(watchdog.result_cap(now), usize::MAX);
+
+        watchdog.record("history", SLOW, BUDGET, now);
+        assert_eq!(watchdog.throttle(now), Throttle::Limited);
+        assert_eq!(watchdog.result_cap(now), 20);
+        assert_eq!(watchdog.extra_debounce(now), Duration::from_millis(400));
+
+        for _ in 0..3 {
+            watchdog.record("history", SLOW, BUDGET, now);
+        }
+        let until = now + Duration::from_secs(60);
+        assert_eq!(watchdog.throttle(now), Throttle::Paused { until });
+        assert_eq!(watchdog.throttle(now).describe(now).unwrap(), "Paused for 60s after repeated slow searches");
+
+        // 止めている時間が過ぎたら結果を減らして再開し、1 回遅ければまた止める
+        let later = until + Duration::from_secs(1);
+        assert_eq!(watchdog.throttle(later), Throttle::Limited);
+        watchdog.record("history", SLOW, BUDGET, later);
+        assert!(matches!(watchdog.throttle(later), Throttle::Paused { .. }));
+    }
+
+    #[test]
+    fn test_fast_search_recovers() {
+        let mut watchdog = Watchdog::new(WatchdogConfig::default());
+        let now = Instant::now();
+        for _ in 0..6 {
+            watchdog.record("history", SLOW, BUDGET, now);
+        }
+        watchdog.record("history", FAST, BUDGET, now);
+        assert_eq!(watchdog.throttle(now), Throttle::None);
+
+        let mut disabled = Watchdog::new(WatchdogConfig { enabled: false, ..WatchdogConfig::default() });
+        for _ in 0..6 {
+            disabled.record("history", SLOW, BUDGET, now);
+        }
+        assert_eq!(disabled.throttle(now), Throttle::None);
+    }
+}
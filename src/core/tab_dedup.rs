@@ -0,0 +1,110 @@
+use super::ChromeTab;
+use std::collections::HashMap;
+
+/// 重複判定用にURLを正規化する
+///
+/// スキームとホストの大文字小文字、フラグメント（`#...`）、末尾の `/` の違いは同じURLとみなす。
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let without_fragment = url.split('#').next().unwrap_or(url);
+
+    let normalized = match url::Url::parse(without_fragment) {
+        Ok(parsed) => parsed.to_string(),
+        Err(_) => without_fragment.to_string(),
+    };
+
+    normalized.trim_end_matches('/').to_string()
+}
+
+/// 閉じるべき重複タブのIDを返す
+///
+/// 同じURLのタブが複数ある場合は1つだけ残す。アクティブなタブがあればそれを、
+/// なければ最初に見つかったタブを残す。
+pub fn find_duplicate_tabs(tabs: &[ChromeTab]) -> Vec<i32> {
+    let mut groups: HashMap<String, Vec<&ChromeTab>> = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+
+    for tab in tabs {
+        if tab.url.is_empty() {
+            continue;
+        }
+        let key = normalize_url(&tab.url);
+        if !groups.contains_key(&key) {
+            order.push(key.clone());
+        }
+        groups.entry(key).or_default().push(tab);
+    }
+
+    let mut duplicates = Vec::new();
+    for key in order {
+        let group = &groups[&key];
+        if group.len() < 2 {
+            continue;
+        }
+
+        let keep = group.iter().find(|tab| tab.active).unwrap_or(&group[0]).id;
+        duplicates.extend(group.iter().map(|tab| tab.id).filter(|id| *id != keep));
+    }
+
+    duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_tab(id: i32, url: &str, active: bool) -> ChromeTab {
+        ChromeTab {
+            id,
+            window_id: 1,
+            title: format!("Tab {}", id),
+            url: url.to_string(),
+            fav_icon_url: String::new(),
+            active,
+            index: id,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(normalize_url("https://GitHub.com/rust-lang/"), "https://github.com/rust-lang");
+        assert_eq!(normalize_url("https://example.com/page#section"), "https://example.com/page");
+        assert_eq!(normalize_url("https://example.com/?q=1"), "https://example.com/?q=1");
+        assert_eq!(normalize_url("chrome://newtab/"), "chrome://newtab");
+    }
+
+    #[test]
+    fn test_find_duplicate_tabs_keeps_first() {
+        let tabs = vec![
+            create_tab(1, "https://example.com/", false),
+            create_tab(2, "https://example.com", false),
+            create_tab(3, "https://other.com", false),
+            create_tab(4, "https://example.com/#top", false),
+        ];
+
+        assert_eq!(find_duplicate_tabs(&tabs), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_find_duplicate_tabs_keeps_active() {
+        let tabs = vec![
+            create_tab(1, "https://example.com", false),
+            create_tab(2, "https://example.com", true),
+        ];
+
+        assert_eq!(find_duplicate_tabs(&tabs), vec![1]);
+    }
+
+    #[test]
+    fn test_find_duplicate_tabs_ignores_query_differences_and_empty_urls() {
+        let tabs = vec![
+            create_tab(1, "https://example.com/?page=1", false),
+            create_tab(2, "https://example.com/?page=2", false),
+            create_tab(3, "", false),
+            create_tab(4, "", false),
+        ];
+
+        assert!(find_duplicate_tabs(&tabs).is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+use super::ChromeTab;
+use std::collections::HashMap;
+
+/// 開いているタブの一覧（「Open tabs overview」）のまとめ方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TabGrouping {
+    /// Chromeのウィンドウごと（ウィンドウ内の並び順）
+    #[default]
+    Window,
+    /// ドメインごと（タブの多いドメインから）
+    Domain,
+}
+
+impl TabGrouping {
+    pub const ALL: [TabGrouping; 2] = [TabGrouping::Window, TabGrouping::Domain];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TabGrouping::Window => "Window",
+            TabGrouping::Domain => "Domain",
+        }
+    }
+}
+
+/// まとめたタブ（ツリーの1つの枝）
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabGroup {
+    /// 見出し（例: `Window 2` / `github.com`）
+    pub label: String,
+    pub tabs: Vec<ChromeTab>,
+}
+
+impl TabGroup {
+    pub fn tab_ids(&self) -> Vec<i32> {
+        self.tabs.iter().map(|tab| tab.id).collect()
+    }
+}
+
+/// タブのドメイン（`www.` は除く。URL として読めなければスキームまたは URL そのもの）
+pub fn tab_domain(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => match parsed.host_str() {
+            Some(host) => host.trim_start_matches("www.").to_lowercase(),
+            None => format!("{}:", parsed.scheme()),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+/// タイトル・URL に `filter` を含むタブをまとめる（大文字小文字は区別しない、空なら全て）
+pub fn group_tabs(tabs: &[ChromeTab], grouping: TabGrouping, filter: &str) -> Vec<TabGroup> {
+    let filter = filter.trim().to_lowercase();
+    let matching = tabs.iter().filter(|tab| {
+        filter.is_empty() || tab.title.to_lowercase().contains(&filter) || tab.url.to_lowercase().contains(&filter)
+    });
+
+    let mut order: Vec<String> = Vec::new();
+    let mut groups: HashMap<String, Vec<ChromeTab>> = HashMap::new();
+    let mut window_ids: HashMap<String, i32> = HashMap::new();
+    for tab in matching {
+        let key = match grouping {
+            TabGrouping::Window => tab.window_id.to_string(),
+            TabGrouping::Domain => tab_domain(&tab.url),
+        };
+        if !groups.contains_key(&key) {
+            order.push(key.clone());
+            window_ids.insert(key.clone(), tab.window_id);
+        }
+        groups.entry(key).or_default().push(tab.clone());
+    }
+
+    let mut result: Vec<TabGroup> = match grouping {
+        TabGrouping::Window => {
+            // ウィンドウは拡張機能から届いた順に番号を振る
+            order
+                .into_iter()
+                .enumerate()
+                .map(|(index, key)| {
+                    let mut tabs = groups.remove(&key).unwrap_or_default();
+                    tabs.sort_by_key(|tab| tab.index);
+                    TabGroup { label: format!("Window {}", index + 1), tabs }
+                })
+                .collect()
+        }
+        TabGrouping::Domain => order
+            .into_iter()
+            .map(|key| {
+                let tabs = groups.remove(&key).unwrap_or_default();
+                TabGroup { label: key, tabs }
+            })
+            .collect(),
+    };
+    if grouping == TabGrouping::Domain {
+        result.sort_by(|a, b| b.tabs.len().cmp(&a.tabs.len()).then_with(|| a.label.cmp(&b.label)));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(id: i32, window_id: i32, index: i32, title: &str, url: &str) -> ChromeTab {
+        ChromeTab {
+            id,
+            window_id,
+            title: title.to_string(),
+            url: url.to_string(),
+            fav_icon_url: String::new(),
+            active: false,
+            index,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_group_tabs() {
+        let tabs = vec![
+            tab(1, 10, 1, "Issues", "https://github.com/issues"),
+            tab(2, 10, 0, "Docs", "https://docs.rs/egui"),
+            tab(3, 20, 0, "Pull requests", "https://www.github.com/pulls"),
+            tab(4, 20, 1, "Extensions", "chrome://extensions"),
+        ];
+
+        let by_window = group_tabs(&tabs, TabGrouping::Window, "");
+        assert_eq!(by_window.len(), 2);
+        assert_eq!(by_window[0].label, "Window 1");
+        assert_eq!(by_window[0].tab_ids(), vec![2, 1]);
+
+        let by_domain = group_tabs(&tabs, TabGrouping::Domain, "");
+        assert_eq!(
+            by_domain.iter().map(|g| (g.label.as_str(), g.tabs.len())).collect::<Vec<_>>(),
+            vec![("github.com", 2), ("docs.rs", 1), ("extensions", 1)]
+        );
+
+        let filtered = group_tabs(&tabs, TabGrouping::Domain, "PULL");
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].tab_ids(), vec![3]);
+    }
+}
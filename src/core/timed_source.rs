@@ -0,0 +1,248 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use super::source_watchdog::{Throttle, Watchdog};
+use crate::config::WatchdogConfig;
+
+/// 検索元の結果（時間内に終わらなければ `TimedOut`）
+#[derive(Debug, PartialEq)]
+pub enum SourceResult<T> {
+    Ready(T),
+    TimedOut,
+}
+
+struct SourceState<T> {
+    /// 実行中の検索のクエリ
+    running: Option<String>,
+    /// 時間切れのあとに届いた結果（クエリ, 結果）
+    late: Option<(String, T)>,
+}
+
+/// 時間の上限を決めて検索元を別スレッドで実行する
+///
+/// 上限を過ぎた検索はそのまま続け、終わったら結果を取っておいて `take_updated` で知らせる。
+/// 同じクエリで検索し直すと取っておいた結果を返す。実行中の検索が終わるまで、
+/// 新しい検索は始めずに時間切れとして扱う（ロックされた SQLite などでスレッドが増え続けないように）。
+/// 上限を続けて超えると `Watchdog` が検索元を抑える（止めている間は `start` せずに `throttle` を見る）。
+pub struct TimedSource<T> {
+    name: &'static str,
+    budget: Duration,
+    state: Arc<Mutex<SourceState<T>>>,
+    updated: Arc<AtomicBool>,
+    watchdog: Arc<Mutex<Watchdog>>,
+}
+
+impl<T: Send + 'static> TimedSource<T> {
+    pub fn new(name: &'static str, budget: Duration) -> Self {
+        Self {
+            name,
+            budget,
+            state: Arc::new(Mutex::new(SourceState { running: None, late: None })),
+            updated: Arc::new(AtomicBool::new(false)),
+            watchdog: Arc::new(Mutex::new(Watchdog::new(WatchdogConfig::default()))),
+        }
+    }
+
+    pub fn with_watchdog(mut self, config: WatchdogConfig) -> Self {
+        self.watchdog = Arc::new(Mutex::new(Watchdog::new(config)));
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// 検索を開始する（結果は `PendingSource::wait` で受け取る）
+    pub fn start<F>(&self, query: &str, search: F) -> PendingSource<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let mut state = self.state.lock().unwrap();
+        match state.late.take() {
+            Some((late_query, result)) if late_query == query => return PendingSource::ready(result),
+            _ => {}
+        }
+        if let Some(running) = &state.running {
+            log::debug!("{} is still searching '{}', skipping '{}'", self.name, running, query);
+            return PendingSource::busy();
+        }
+        state.running = Some(query.to_string());
+        drop(state);
+
+        let (sender, receiver) = mpsc::channel();
+        let thread_state = Arc::clone(&self.state);
+        let updated = Arc::clone(&self.updated);
+        let watchdog = Arc::clone(&self.watchdog);
+        let budget = self.budget;
+        let name = self.name;
+        let query = query.to_string();
+        let spawned = std::thread::Builder::new().name(format!("search-{}", name)).spawn(move || {
+            let started = Instant::now();
+            let result = panic::catch_unwind(AssertUnwindSafe(search));
+            // 結果を渡す前に記録し、待っている側が受け取った時点の `throttle` に反映させる
+            watchdog.lock().unwrap().record(name, started.elapsed(), budget, Instant::now());
+            let mut state = thread_state.lock().unwrap();
+            state.running = None;
+            match result {
+                Ok(result) => {
+                    // 待っている側が時間切れで受信をやめていれば、次の検索のために取っておく
+                    if let Err(mpsc::SendError(result)) = sender.send(result) {
+                        log::info!("{} finished '{}' after the time budget", name, query);
+                        state.late = Some((query, result));
+                        updated.store(true, Ordering::SeqCst);
+                    }
+                }
+                Err(_) => log::error!("{} panicked while searching '{}'", name, query),
+            }
+        });
+        if let Err(e) = spawned {
+            log::error!("Failed to spawn {} search thread: {}", self.name, e);
+            self.state.lock().unwrap().running = None;
+            return PendingSource::busy();
+        }
+
+        PendingSource {
+            ready: None,
+            receiver: Some(receiver),
+            state: Some(Arc::clone(&self.state)),
+            deadline: Instant::now() + self.budget,
+        }
+    }
+
+    /// 時間切れのあとに結果が届いたか（呼び出すとリセットされる）
+    pub fn take_updated(&self) -> bool {
+        self.updated.swap(false, Ordering::SeqCst)
+    }
+
+    /// 時間切れのまま実行中の検索があるか
+    pub fn is_pending(&self) -> bool {
+        self.state.lock().unwrap().running.is_some()
+    }
+
+    /// 遅い検索が続いて抑えているか
+    pub fn throttle(&self) -> Throttle {
+        self.watchdog.lock().unwrap().throttle(Instant::now())
+    }
+
+    /// 抑えている間の結果の数の上限
+    pub fn result_cap(&self) -> usize {
+        self.watchdog.lock().unwrap().result_cap(Instant::now())
+    }
+
+    /// 抑えている間に入力の待ち時間へ足す時間
+    pub fn extra_debounce(&self) -> Duration {
+        self.watchdog.lock().unwrap().extra_debounce(Instant::now())
+    }
+}
+
+/// 開始した検索の結果の受け取り口
+pub struct PendingSource<T> {
+    ready: Option<T>,
+    receiver: Option<Receiver<T>>,
+    state: Option<Arc<Mutex<SourceState<T>>>>,
+    deadline: Instant,
+}
+
+impl<T> PendingSource<T> {
+    fn ready(result: T) -> Self {
+        Self {
+            ready: Some(result),
+            receiver: None,
+            state: None,
+            deadline: Instant::now(),
+        }
+    }
+
+    fn busy() -> Self {
+        Self {
+            ready: None,
+            receiver: None,
+            state: None,
+            deadline: Instant::now(),
+        }
+    }
+
+    /// 開始時に決めた上限まで結果を待つ
+    pub fn wait(self) -> SourceResult<T> {
+        if let Some(result) = self.ready {
+            return SourceResult::Ready(result);
+        }
+        let (Some(receiver), Some(state)) = (self.receiver, self.state) else {
+            return SourceResult::TimedOut;
+        };
+
+        match receiver.recv_timeout(self.deadline.saturating_duration_since(Instant::now())) {
+            Ok(result) => SourceResult::Ready(result),
+            Err(RecvTimeoutError::Disconnected) => SourceResult::TimedOut,
+            Err(RecvTimeoutError::Timeout) => {
+                // 検索側は状態をロックしたまま送信するので、ロック中に受信をやめれば結果を取りこぼさない
+                let _state = state.lock().unwrap();
+                match receiver.try_recv() {
+                    Ok(result) => SourceResult::Ready(result),
+                    Err(_) => SourceResult::TimedOut,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_search_is_ready() {
+        let source = TimedSource::new("fast", Duration::from_secs(5));
+        assert_eq!(source.start("rust", || 42).wait(), SourceResult::Ready(42));
+        assert!(!source.is_pending());
+        assert!(!source.take_updated());
+    }
+
+    #[test]
+    fn test_slow_search_times_out_and_is_kept() {
+        let source = TimedSource::new("slow", Duration::from_millis(20));
+        let (release, blocked) = mpsc::channel::<()>();
+        let pending = source.start("rust", move || {
+            blocked.recv().unwrap();
+            7
+        });
+        assert_eq!(pending.wait(), SourceResult::TimedOut);
+        assert!(source.is_pending());
+
+        // 実行中は新しい検索を始めない
+        assert_eq!(source.start("go", || 1).wait(), SourceResult::TimedOut);
+
+        release.send(()).unwrap();
+        let started = Instant::now();
+        while !source.take_updated() {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(!source.is_pending());
+        assert_eq!(source.start("rust", || 0).wait(), SourceResult::Ready(7));
+    }
+
+    #[test]
+    fn test_slow_search_is_throttled() {
+        let config = WatchdogConfig { limit_after: 1, ..WatchdogConfig::default() };
+        let source = TimedSource::new("slow", Duration::from_millis(10)).with_watchdog(config);
+        let slow = source.start("rust", || {
+            std::thread::sleep(Duration::from_millis(50));
+            7
+        });
+        assert_eq!(slow.wait(), SourceResult::TimedOut);
+        let started = Instant::now();
+        while !source.take_updated() {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(source.throttle(), Throttle::Limited);
+        assert_eq!(source.result_cap(), 20);
+
+        // 上限内に終われば元に戻る
+        assert_eq!(source.start("go", || 1).wait(), SourceResult::Ready(1));
+        assert_eq!(source.throttle(), Throttle::None);
+    }
+}
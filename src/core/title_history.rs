@@ -0,0 +1,103 @@
+//! ウィンドウごとのタイトルの履歴（セッション中だけ覚える）
+//!
+//! ブラウザーやエディターはタイトルがよく変わるため、検索のたびにウィンドウ一覧を取り込んで
+//! 前のタイトルを残しておき、少し前のタイトルでも同じウィンドウを見つけられるようにする。
+
+use super::window_manager::WindowInfo;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// ウィンドウごとに覚える前のタイトルの数
+const MAX_PREVIOUS_TITLES: usize = 5;
+
+/// 前のタイトルを覚えておく時間（タイトルが変わってから）
+const KEEP_FOR: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Default)]
+struct TitleEntry {
+    current: String,
+    /// 前のタイトルと、そのタイトルでなくなった時刻（新しいものが先頭）
+    previous: VecDeque<(String, Instant)>,
+}
+
+#[derive(Debug, Default)]
+pub struct TitleHistory {
+    entries: Mutex<HashMap<isize, TitleEntry>>,
+}
+
+impl TitleHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 今のウィンドウ一覧を取り込む（変わったタイトルは前のタイトルに回し、閉じたウィンドウは忘れる）
+    pub fn observe(&self, windows: &[WindowInfo]) {
+        self.observe_at(windows, Instant::now());
+    }
+
+    fn observe_at(&self, windows: &[WindowInfo], now: Instant) {
+        // 列挙に失敗したときの空の一覧で履歴を消さない
+        if windows.is_empty() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|hwnd, _| windows.iter().any(|window| window.hwnd == *hwnd));
+
+        for window in windows {
+            let entry = entries.entry(window.hwnd).or_default();
+            if entry.current == window.title {
+                continue;
+            }
+            let old = std::mem::replace(&mut entry.current, window.title.clone());
+            entry.previous.retain(|(title, _)| *title != window.title && *title != old);
+            if !old.is_empty() {
+                entry.previous.push_front((old, now));
+            }
+            entry.previous.truncate(MAX_PREVIOUS_TITLES);
+        }
+        for entry in entries.values_mut() {
+            entry.previous.retain(|(_, changed_at)| now.duration_since(*changed_at) < KEEP_FOR);
+        }
+    }
+
+    /// クエリを含む前のタイトルのうち一番新しいもの（英字の大文字小文字は区別しない）
+    pub fn previous_match(&self, hwnd: isize, query: &str) -> Option<String> {
+        let query = query.to_lowercase();
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&hwnd)?
+            .previous
+            .iter()
+            .find(|(title, _)| title.to_lowercase().contains(&query))
+            .map(|(title, _)| title.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::helpers::create_test_window;
+
+    fn window(hwnd: isize, title: &str) -> WindowInfo {
+        create_test_window(hwnd, title, "chrome.exe")
+    }
+
+    #[test]
+    fn test_previous_titles() {
+        let history = TitleHistory::new();
+        let start = Instant::now();
+        history.observe_at(&[window(1, "PROJ-123 Fix login - Jira"), window(2, "Inbox")], start);
+        history.observe_at(&[window(1, "Pull request #42 - GitHub"), window(2, "Inbox")], start + Duration::from_secs(60));
+
+        assert_eq!(history.previous_match(1, "proj-123"), Some("PROJ-123 Fix login - Jira".to_string()));
+        assert_eq!(history.previous_match(1, "github"), None);
+        assert_eq!(history.previous_match(2, "inbox"), None);
+
+        // 時間が経った前のタイトルと、閉じたウィンドウは忘れる
+        history.observe_at(&[window(1, "Pull request #42 - GitHub")], start + Duration::from_secs(60) + KEEP_FOR);
+        assert_eq!(history.previous_match(1, "jira"), None);
+        history.observe_at(&[window(2, "Inbox")], start + KEEP_FOR * 2);
+        assert!(history.entries.lock().unwrap().get(&1).is_none());
+    }
+}
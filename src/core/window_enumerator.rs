@@ -0,0 +1,163 @@
+use super::WindowInfo;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+/// UIスレッドで待つ最大時間の既定値
+pub const DEFAULT_ENUMERATION_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// ワーカースレッドでウィンドウを列挙する
+///
+/// 応答しないウィンドウは列挙の中で1つずつ飛ばす（`windows_api::enumerate_windows`）。
+/// それでも列挙が `timeout` 以内に終わらない場合は、前回の結果を返して UI スレッドを止めない。
+/// 遅れて届いた結果は次の呼び出しで使われる。最初の列挙だけは返す一覧がないので終わるまで待つ。
+pub struct WindowEnumerator {
+    requests: Sender<Sender<Vec<WindowInfo>>>,
+    pending: Option<Receiver<Vec<WindowInfo>>>,
+    last: Option<Vec<WindowInfo>>,
+    timeout: Duration,
+}
+
+impl WindowEnumerator {
+    pub fn new(timeout: Duration) -> Self {
+        Self::with_source(crate::windows_api::enumerate_windows, timeout)
+    }
+
+    /// 列挙処理を差し替えて作成（テスト用）
+    pub fn with_source<F>(source: F, timeout: Duration) -> Self
+    where
+        F: Fn() -> Vec<WindowInfo> + Send + 'static,
+    {
+        let (requests, request_rx) = mpsc::channel::<Sender<Vec<WindowInfo>>>();
+
+        thread::Builder::new()
+            .name("window-enumerator".to_string())
+            .spawn(move || {
+                for reply in request_rx {
+                    let _ = reply.send(source());
+                }
+            })
+            .expect("failed to spawn window enumerator thread");
+
+        Self {
+            requests,
+            pending: None,
+            last: None,
+            timeout,
+        }
+    }
+
+    /// ウィンドウ一覧を取得（タイムアウト時は前回の結果）
+    pub fn enumerate(&mut self) -> Vec<WindowInfo> {
+        // 前回タイムアウトした列挙が終わっていればそれを使い、新しく列挙し直す
+        if let Some(pending) = &self.pending {
+            match pending.try_recv() {
+                Ok(windows) => {
+                    self.last = Some(windows);
+                    self.pending = None;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.pending = None,
+            }
+        }
+
+        let receiver = match self.pending.take() {
+            Some(receiver) => receiver,
+            None => {
+                let (reply_tx, reply_rx) = mpsc::channel();
+                if self.requests.send(reply_tx).is_err() {
+                    log::error!("Window enumerator thread has stopped");
+                    return self.last.clone().unwrap_or_default();
+                }
+                reply_rx
+            }
+        };
+
+        if self.last.is_none() {
+            match receiver.recv() {
+                Ok(windows) => self.last = Some(windows),
+                Err(_) => log::error!("Window enumerator thread has stopped"),
+            }
+            return self.last.clone().unwrap_or_default();
+        }
+
+        match receiver.recv_timeout(self.timeout) {
+            Ok(windows) => self.last = Some(windows),
+            Err(RecvTimeoutError::Timeout) => {
+                log::warn!("Window enumeration took longer than {:?}, using previous result", self.timeout);
+                self.pending = Some(receiver);
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                log::error!("Window enumerator thread has stopped");
+            }
+        }
+
+        self.last.clone().unwrap_or_default()
+    }
+}
+
+impl Default for WindowEnumerator {
+    fn default() -> Self {
+        Self::new(DEFAULT_ENUMERATION_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::helpers::create_test_window;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_enumerate_returns_result() {
+        let mut enumerator = WindowEnumerator::with_source(
+            || vec![create_test_window(1, "Notepad", "notepad.exe")],
+            Duration::from_secs(1),
+        );
+
+        let windows = enumerator.enumerate();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].title, "Notepad");
+    }
+
+    #[test]
+    fn test_slow_enumeration_returns_previous_result() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let mut enumerator = WindowEnumerator::with_source(
+            move || {
+                let call = counter.fetch_add(1, Ordering::SeqCst);
+                if call == 1 {
+                    // 2回目の列挙だけ応答しないプロセスがある想定
+                    thread::sleep(Duration::from_millis(300));
+                }
+                vec![create_test_window(call as isize + 1, "Window", "app.exe")]
+            },
+            Duration::from_millis(50),
+        );
+
+        assert_eq!(enumerator.enumerate()[0].hwnd, 1);
+        // タイムアウトしても前回の結果が返る
+        assert_eq!(enumerator.enumerate()[0].hwnd, 1);
+
+        // 遅れて届いた結果が次の呼び出しで使われる
+        thread::sleep(Duration::from_millis(400));
+        let windows = enumerator.enumerate();
+        assert!(windows[0].hwnd >= 2);
+    }
+
+    #[test]
+    fn test_first_enumeration_waits_past_timeout() {
+        let mut enumerator = WindowEnumerator::with_source(
+            || {
+                thread::sleep(Duration::from_millis(100));
+                vec![create_test_window(1, "Window", "app.exe")]
+            },
+            Duration::from_millis(10),
+        );
+
+        // 前回の結果がないので空の一覧は返さない
+        assert_eq!(enumerator.enumerate().len(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+//! 起動し直しても変わらないウィンドウの識別
+//!
+//! hwnd は起動のたびに変わるため、記録にはプロセス（実行ファイル）・ウィンドウクラス・
+//! 変わりやすい部分を除いたタイトルの形を残し、検索するときに今のウィンドウへ解決し直す。
+
+use super::window_manager::WindowInfo;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowFingerprint {
+    pub process_name: String,
+    /// 実行ファイルのパス（分からなければ `None`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_path: Option<String>,
+    /// 空ならどのクラスにも一致する（クラスを記録していなかった古い記録）
+    #[serde(default)]
+    pub class_name: String,
+    /// `normalize_title` したタイトル（空ならタイトルでは区別しない）
+    #[serde(default)]
+    pub title_pattern: String,
+}
+
+impl WindowFingerprint {
+    /// 今のウィンドウの識別を作る（実行ファイルのパスは Windows API で調べる）
+    pub fn of(window: &WindowInfo) -> Self {
+        Self::with_path(window, crate::windows_api::process_path_for_window(window.hwnd))
+    }
+
+    pub fn with_path(window: &WindowInfo, process_path: Option<String>) -> Self {
+        Self {
+            process_name: window.process_name.clone(),
+            process_path,
+            class_name: window.class_name.clone(),
+            title_pattern: normalize_title(&window.title),
+        }
+    }
+
+    /// 同じアプリのウィンドウか（プロセス名とクラス）
+    pub fn matches_app(&self, window: &WindowInfo) -> bool {
+        window.process_name.eq_ignore_ascii_case(&self.process_name)
+            && (self.class_name.is_empty() || window.class_name == self.class_name)
+    }
+
+    /// 今のウィンドウのうち、この識別に当たるもの
+    ///
+    /// タイトルの形まで一致するウィンドウを優先し、なければ同じアプリの最初のウィンドウにする。
+    pub fn resolve<'a>(&self, windows: &'a [WindowInfo]) -> Option<&'a WindowInfo> {
+        let mut same_app = windows.iter().filter(|window| self.matches_app(window)).peekable();
+        let first = *same_app.peek()?;
+        if self.title_pattern.is_empty() {
+            return Some(first);
+        }
+        Some(same_app.find(|window| normalize_title(&window.title) == self.title_pattern).unwrap_or(first))
+    }
+}
+
+/// タイトルから変わりやすい部分を除く
+///
+/// 英字は小文字にし、数字の並び（未読数・時刻・番号）は `#` にまとめ、先頭の未保存の印（`●` / `*`）と
+/// 未読数（`(3) `）を取り除く。
+pub fn normalize_title(title: &str) -> String {
+    let mut normalized = String::with_capacity(title.len());
+    let mut in_digits = false;
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_digit() {
+            if !in_digits {
+                normalized.push('#');
+            }
+            in_digits = true;
+        } else {
+            normalized.push(c);
+            in_digits = false;
+        }
+    }
+    let trimmed = normalized.trim_start_matches(|c: char| c == '●' || c == '*' || c.is_whitespace());
+    let trimmed = trimmed.strip_prefix("(#)").unwrap_or(trimmed);
+    trimmed.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::helpers::create_test_window;
+
+    fn window(hwnd: isize, process_name: &str, title: &str) -> WindowInfo {
+        create_test_window(hwnd, title, process_name)
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("(12) Inbox - Gmail"), "inbox - gmail");
+        assert_eq!(normalize_title("● main.rs - my-launcher - Visual Studio Code"), "main.rs - my-launcher - visual studio code");
+        assert_eq!(normalize_title("Meeting  10:30"), "meeting #:#");
+    }
+
+    #[test]
+    fn test_resolve_after_restart() {
+        let saved = WindowFingerprint::with_path(
+            &window(1, "Code.exe", "● lib.rs - my-launcher - Visual Studio Code"),
+            Some(r"C:\Apps\Code\Code.exe".to_string()),
+        );
+
+        // 起動し直して hwnd が変わっても、タイトルの形が同じウィンドウに解決する
+        let windows = vec![
+            window(200, "Code.exe", "notes - Visual Studio Code"),
+            window(300, "Code.exe", "lib.rs - my-launcher - Visual Studio Code"),
+            window(400, "chrome.exe", "lib.rs - my-launcher - Visual Studio Code"),
+        ];
+        assert_eq!(saved.resolve(&windows).map(|window| window.hwnd), Some(300));
+
+        // 同じタイトルがなければ同じアプリのウィンドウ
+        assert_eq!(saved.resolve(&windows[..1]).map(|window| window.hwnd), Some(200));
+        assert_eq!(saved.resolve(&windows[2..]), None);
+    }
+
+    #[test]
+    fn test_deserialize_process_name_only() {
+        let fingerprint: WindowFingerprint = serde_json::from_str(r#"{"process_name":"Code.exe"}"#).unwrap();
+        assert!(fingerprint.matches_app(&window(1, "code.exe", "anything")));
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WindowInfo {
     pub hwnd: isize,
     pub title: String,
@@ -7,6 +9,9 @@ pub struct WindowInfo {
     pub is_visible: bool,
     pub is_minimized: bool,
     pub rect: (i32, i32, i32, i32), // x, y, width, height
+    /// 昇格（管理者権限）プロセスのウィンドウで、非昇格のランチャーからは操作できない
+    #[serde(default)]
+    pub requires_elevation: bool,
 }
 
 impl WindowInfo {
@@ -23,20 +28,25 @@ pub trait WindowManager: Send + Sync {
     fn switch_to_window(&self, hwnd: isize);
 }
 
+/// Windows API でウィンドウを列挙する（列挙はワーカースレッドで行う）
 #[cfg(windows)]
-pub struct WindowsApiManager;
+pub struct WindowsApiManager {
+    enumerator: std::sync::Mutex<super::window_enumerator::WindowEnumerator>,
+}
 
 #[cfg(windows)]
 impl WindowsApiManager {
     pub fn new() -> Self {
-        Self
+        Self {
+            enumerator: std::sync::Mutex::new(Default::default()),
+        }
     }
 }
 
 #[cfg(windows)]
 impl WindowManager for WindowsApiManager {
     fn enumerate_windows(&self) -> Vec<WindowInfo> {
-        crate::windows_api::enumerate_windows()
+        self.enumerator.lock().unwrap().enumerate()
     }
 
     fn switch_to_window(&self, hwnd: isize) {
@@ -116,6 +126,7 @@ mod tests {
             is_visible: true,
             is_minimized: false,
             rect: (0, 0, 1920, 1080),
+            requires_elevation: false,
         };
 
         assert!(window.contains_text("visual"));
@@ -137,6 +148,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 100, 100),
+                requires_elevation: false,
             },
         ];
 
@@ -0,0 +1,77 @@
+//! 最近使ったウィンドウの順（MRU）
+//!
+//! ウィンドウの列挙順は Z オーダー（前面のものから）なので、最初に見たときはその順を使う。
+//! 以降に現れたウィンドウは開いたばかりとみなして先頭に置き、ランチャーから切り替えたウィンドウも
+//! 先頭に移す。閉じたウィンドウは次の列挙で外す。
+
+use super::window_manager::WindowInfo;
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+pub struct WindowMru {
+    /// 最近使った順の hwnd
+    order: Mutex<Vec<isize>>,
+}
+
+impl WindowMru {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 列挙したウィンドウに合わせる
+    pub fn observe(&self, windows: &[WindowInfo]) {
+        let mut order = self.order.lock().unwrap();
+        order.retain(|hwnd| windows.iter().any(|window| window.hwnd == *hwnd));
+        let mut new: Vec<isize> = windows.iter().map(|window| window.hwnd).filter(|hwnd| !order.contains(hwnd)).collect();
+        new.append(&mut *order);
+        *order = new;
+    }
+
+    /// 切り替えたウィンドウを先頭にする
+    pub fn touch(&self, hwnd: isize) {
+        let mut order = self.order.lock().unwrap();
+        order.retain(|known| *known != hwnd);
+        order.insert(0, hwnd);
+    }
+
+    /// `windows` のうち最近使った `count` 個（最近のものから）
+    pub fn recent<'a>(&self, windows: &'a [WindowInfo], count: usize) -> Vec<&'a WindowInfo> {
+        self.order
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|hwnd| windows.iter().find(|window| window.hwnd == *hwnd))
+            .take(count)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::helpers::create_test_window;
+
+    fn window(hwnd: isize) -> WindowInfo {
+        create_test_window(hwnd, &format!("Window {}", hwnd), "notepad.exe")
+    }
+
+    fn hwnds(windows: Vec<&WindowInfo>) -> Vec<isize> {
+        windows.into_iter().map(|window| window.hwnd).collect()
+    }
+
+    #[test]
+    fn test_order_follows_z_order_then_switches() {
+        let mru = WindowMru::new();
+        let windows = vec![window(3), window(1), window(2)];
+        mru.observe(&windows);
+        assert_eq!(hwnds(mru.recent(&windows, 5)), vec![3, 1, 2]);
+
+        mru.touch(2);
+        assert_eq!(hwnds(mru.recent(&windows, 2)), vec![2, 3]);
+
+        // 閉じたウィンドウは外し、新しいウィンドウは先頭に置く
+        let windows = vec![window(4), window(3), window(2)];
+        mru.observe(&windows);
+        assert_eq!(hwnds(mru.recent(&windows, 5)), vec![4, 2, 3]);
+    }
+}
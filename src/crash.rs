@@ -0,0 +1,172 @@
+//! パニック時のクラッシュレポート
+//!
+//! UIスレッド・バックグラウンドスレッドを問わずパニックを捕捉し、
+//! バックトレースと直近のログをログフォルダに書き出す。
+//! 次回起動時に `take_pending_report` でレポートの存在を通知できる。
+
+use chrono::Local;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+
+/// 次回起動時に通知するレポートのパスを記録するファイル
+const PENDING_MARKER: &str = "last_crash.txt";
+
+/// レポートに含めるログの行数
+const LOG_TAIL_LINES: usize = 50;
+
+/// クラッシュレポートの内容
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    pub version: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub log_tail: Vec<String>,
+}
+
+impl CrashReport {
+    /// パニック情報からレポートを作成
+    pub fn from_panic(info: &PanicHookInfo) -> Self {
+        let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = info.payload().downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "Unknown panic payload".to_string()
+        };
+
+        let log_tail = crate::logger::current_log_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| tail_lines(&content, LOG_TAIL_LINES))
+            .unwrap_or_default();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            thread: std::thread::current().name().unwrap_or("<unnamed>").to_string(),
+            message,
+            location: info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            backtrace: Backtrace::force_capture().to_string(),
+            log_tail,
+        }
+    }
+
+    /// テキスト形式に整形
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("My Launcher crash report\n");
+        out.push_str(&format!("Version: {}\n", self.version));
+        out.push_str(&format!("Time: {}\n", Local::now().format("%Y-%m-%d %H:%M:%S")));
+        out.push_str(&format!("Thread: {}\n", self.thread));
+        out.push_str(&format!("Message: {}\n", self.message));
+        if let Some(location) = &self.location {
+            out.push_str(&format!("Location: {}\n", location));
+        }
+        out.push_str("\n=== Backtrace ===\n");
+        out.push_str(&self.backtrace);
+        out.push_str("\n\n=== Recent log ===\n");
+        for line in &self.log_tail {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// パニックフックを登録（既定のフックも引き続き呼ばれる）
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = CrashReport::from_panic(info);
+        log::error!("Panic in thread '{}': {}", report.thread, report.message);
+
+        match crate::logger::get_log_directory() {
+            Ok(dir) => match write_report(&dir, &report) {
+                Ok(path) => log::error!("Crash report saved at {}", path.display()),
+                Err(e) => log::error!("Failed to write crash report: {}", e),
+            },
+            Err(e) => log::error!("Failed to resolve log directory for crash report: {}", e),
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// レポートを書き出し、次回起動時に通知されるよう記録する
+pub fn write_report(dir: &Path, report: &CrashReport) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let filename = format!("crash_{}.txt", Local::now().format("%Y%m%d_%H%M%S"));
+    let path = dir.join(filename);
+    fs::write(&path, report.render())?;
+    fs::write(dir.join(PENDING_MARKER), path.to_string_lossy().as_bytes())?;
+
+    Ok(path)
+}
+
+/// 前回のクラッシュレポートがあればパスを返し、通知済みにする
+pub fn take_pending_report(dir: &Path) -> Option<PathBuf> {
+    let marker = dir.join(PENDING_MARKER);
+    let content = fs::read_to_string(&marker).ok()?;
+    let _ = fs::remove_file(&marker);
+
+    let path = PathBuf::from(content.trim());
+    if path.exists() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// 末尾から指定行数を取り出す
+fn tail_lines(content: &str, max_lines: usize) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    lines[start..].iter().map(|s| s.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_report() -> CrashReport {
+        CrashReport {
+            version: "0.1.0".to_string(),
+            thread: "main".to_string(),
+            message: "something went wrong".to_string(),
+            location: Some("src/main.rs:10:5".to_string()),
+            backtrace: "<backtrace>".to_string(),
+            log_tail: vec!["line 1".to_string(), "line 2".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        let content = "a\nb\nc\nd";
+        assert_eq!(tail_lines(content, 2), vec!["c", "d"]);
+        assert_eq!(tail_lines(content, 10).len(), 4);
+        assert!(tail_lines("", 5).is_empty());
+    }
+
+    #[test]
+    fn test_render_contains_details() {
+        let rendered = create_test_report().render();
+        assert!(rendered.contains("Version: 0.1.0"));
+        assert!(rendered.contains("Thread: main"));
+        assert!(rendered.contains("Message: something went wrong"));
+        assert!(rendered.contains("Location: src/main.rs:10:5"));
+        assert!(rendered.contains("line 2"));
+    }
+
+    #[test]
+    fn test_pending_report_is_taken_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_report(dir.path(), &create_test_report()).unwrap();
+
+        assert_eq!(take_pending_report(dir.path()), Some(path));
+        assert_eq!(take_pending_report(dir.path()), None);
+    }
+}
@@ -0,0 +1,875 @@
+//! UIを持たないバックグラウンドサービス（`--daemon`）
+//!
+//! WebSocketサーバー・IPCサーバー・ウィンドウトラッカーだけを動かし続け、
+//! ランチャーのUIは `DaemonClient` 経由でタブとウィンドウの情報を取得する。
+
+use crate::config::LauncherConfig;
+use crate::core::action_rewriter::AppHandlerRewriter;
+use crate::core::window_manager::WindowsApiManager;
+use crate::core::{native_messaging::ChromeCommand, Action, BrowserSearchEngine, LauncherCore, SearchMode, SearchResult, TabManager, WindowInfo};
+use crate::data::tab_snapshot::{self, TabSnapshotStore};
+use crate::http_api::HttpApiServer;
+use crate::ipc::{IpcMessage, ResultInfo, TabInfo};
+use crate::notification::{self, NotificationAction};
+use crate::supervisor::{ServiceStatuses, Supervisor};
+use crate::tab_transport;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+
+#[cfg(windows)]
+use crate::core::{ChromeTab, WindowManager};
+#[cfg(windows)]
+use crate::core::native_messaging::{ClosedTab, ReadingListEntry};
+#[cfg(windows)]
+use crate::supervisor::{ServiceState, ServiceStatus};
+#[cfg(windows)]
+use crate::core::extension_sessions::{SessionControl, SessionInfo};
+#[cfg(windows)]
+use crate::ipc::{read_message, send_message, PIPE_NAME};
+#[cfg(windows)]
+use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeClient, ServerOptions};
+
+/// Chrome拡張が接続するWebSocketのポート
+pub const WEBSOCKET_PORT: u16 = 9999;
+
+/// ウィンドウ一覧を再取得する間隔
+const WINDOW_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Chrome拡張の接続を確認する間隔
+const EXTENSION_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Chrome が動いているのに拡張が切断されたままのとき、通知するまで待つ時間
+const EXTENSION_DISCONNECT_GRACE: Duration = Duration::from_secs(120);
+
+/// シンクライアントがデーモンとタブを同期する間隔
+#[cfg(windows)]
+const TAB_SYNC_INTERVAL: Duration = Duration::from_millis(300);
+
+/// シンクライアントがデーモンのサーバーの状態を取得する間隔（タブの同期の回数）
+#[cfg(windows)]
+const STATUS_SYNC_EVERY: u32 = 10;
+
+/// 外部のトリガーがキーワードを検索するときに使う `LauncherCore`
+type TriggerCore = LauncherCore<BrowserSearchEngine, dyn crate::core::WindowManager>;
+
+/// デーモンが保持する共有状態
+pub struct DaemonState {
+    tab_manager: Arc<TabManager>,
+    windows: Mutex<Vec<WindowInfo>>,
+    services: ServiceStatuses,
+    supervisor: Supervisor,
+    config: LauncherConfig,
+    /// 最初の検索で作る
+    launcher: Mutex<Option<TriggerCore>>,
+    /// 直前の検索で返した結果（`ExecuteAction` で ID から引く）
+    known_results: Mutex<HashMap<String, SearchResult>>,
+}
+
+impl DaemonState {
+    pub fn new(tab_manager: Arc<TabManager>) -> Self {
+        Self::with_services(tab_manager, ServiceStatuses::new())
+    }
+
+    pub fn with_services(tab_manager: Arc<TabManager>, services: ServiceStatuses) -> Self {
+        Self {
+            tab_manager,
+            windows: Mutex::new(Vec::new()),
+            supervisor: Supervisor::new(services.clone()),
+            services,
+            config: LauncherConfig::default(),
+            launcher: Mutex::new(None),
+            known_results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// キーワードの検索に使う設定（ノート・マクロ・プロバイダーなど）
+    pub fn with_config(mut self, config: LauncherConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// サーバーを起動し直す `Supervisor`（通知の「Retry」で使う）
+    pub fn supervisor(&self) -> &Supervisor {
+        &self.supervisor
+    }
+
+    /// 通知のボタンで頼まれた操作を実行
+    pub fn run_notification_action(&self, action: &NotificationAction) -> Result<(), String> {
+        match action {
+            NotificationAction::ReopenLauncher => {
+                notification::reopen_launcher();
+                Ok(())
+            }
+            NotificationAction::RetryService { name } => {
+                if self.supervisor.retry_now(name) {
+                    Ok(())
+                } else {
+                    Err(format!("Unknown service {}", name))
+                }
+            }
+        }
+    }
+
+    /// キーワードを Browser モードで検索して結果を返す（ID で実行できるように覚えておく）
+    pub fn run_keyword(&self, keyword: &str, args: &str) -> Vec<ResultInfo> {
+        self.search(format!("{} {}", keyword, args).trim(), SearchMode::Browser)
+    }
+
+    /// ランチャーと同じ検索を行って結果を返す（ID で実行できるように覚えておく）
+    pub fn search(&self, query: &str, mode: SearchMode) -> Vec<ResultInfo> {
+        let mut launcher = self.launcher.lock().unwrap();
+        let core = launcher.get_or_insert_with(|| {
+            let search_engine = BrowserSearchEngine::new_with_tab_manager(Arc::clone(&self.tab_manager)).with_config(&self.config);
+            LauncherCore::new(search_engine, Arc::new(WindowsApiManager::new()) as Arc<dyn crate::core::WindowManager>)
+                .with_rewriter(Box::new(AppHandlerRewriter::new(self.config.open_in_app.clone())))
+        });
+        core.refresh_windows();
+        let results = core.search(query, mode);
+
+        let mut known = self.known_results.lock().unwrap();
+        known.clear();
+        results
+            .into_iter()
+            .map(|result| {
+                let info = ResultInfo {
+                    id: result.action.id(),
+                    title: result.title.clone(),
+                    description: result.description.clone(),
+                };
+                known.insert(info.id.clone(), result);
+                info
+            })
+            .collect()
+    }
+
+    /// `search` で返した結果を ID で実行（実行した内容を返す）
+    ///
+    /// ランチャーの画面で操作する結果（ブックマークの追加・タブの一覧など）は実行しない。
+    pub fn execute_action_id(&self, id: &str) -> Result<String, String> {
+        let result = self
+            .known_results
+            .lock()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown result {} (search first)", id))?;
+        let launcher = self.launcher.lock().unwrap();
+        let core = launcher.as_ref().ok_or_else(|| "Nothing has been searched".to_string())?;
+
+        match &result.action {
+            Action::SwitchToTab { tab_id, window_id } => {
+                self.tab_manager.queue_command(ChromeCommand::SwitchToTab { tab_id: *tab_id, window_id: *window_id });
+                // タブがあるウィンドウは拡張機能が前面に出す
+            }
+            Action::RunMacro(name) => {
+                let config = core.search_engine().find_macro(name).ok_or_else(|| format!("No macro named {}", name))?;
+                crate::macros::spawn(config)?;
+            }
+            Action::ClearCaches => {
+                core.search_engine().clear_disk_caches();
+            }
+            Action::ProviderCommand { provider, command } => {
+                // 取り消せないコマンドはランチャーの画面で確認してから実行する
+                if core.search_engine().preview_provider_command(provider, command).is_some() {
+                    return Err(format!("{} needs confirmation in the launcher window", result.title));
+                }
+                return core.search_engine().execute_provider_command(provider, command).map_err(|e| e.to_string());
+            }
+            action if action.is_headless() => core.execute_action(action),
+            _ => return Err(format!("{} needs the launcher window", result.title)),
+        }
+        Ok(format!("Opened {}", result.title))
+    }
+
+    /// ウィンドウ一覧を再取得
+    pub fn refresh_windows(&self) {
+        let windows = crate::windows_api::enumerate_windows();
+        *self.windows.lock().unwrap() = windows;
+    }
+
+    pub fn windows(&self) -> Vec<WindowInfo> {
+        self.windows.lock().unwrap().clone()
+    }
+
+    /// IPCリクエストに対する応答を生成
+    pub fn handle_message(&self, message: IpcMessage) -> IpcMessage {
+        match message {
+            IpcMessage::GetTabs => IpcMessage::TabList {
+                tabs: self.tab_manager.get_tabs().into_iter().map(TabInfo::from).collect(),
+                extension_connected: self.tab_manager.is_extension_connected(),
+                age_ms: self.tab_manager.tabs_age().map(|age| age.as_millis() as u64),
+                restored: self.tab_manager.is_restored(),
+                recently_closed: self.tab_manager.recently_closed().as_ref().clone(),
+                reading_list: self.tab_manager.reading_list().as_ref().clone(),
+            },
+            IpcMessage::SwitchToTab { tab_id, window_id } => {
+                log::info!("Daemon: queueing tab switch tab_id={}, window_id={}", tab_id, window_id);
+                self.tab_manager.queue_command(ChromeCommand::SwitchToTab { tab_id, window_id });
+                IpcMessage::TabSwitchResult { success: true, error: None }
+            }
+            IpcMessage::CloseTab { tab_id } => {
+                log::info!("Daemon: queueing tab close tab_id={}", tab_id);
+                self.tab_manager.queue_command(ChromeCommand::CloseTab { tab_id });
+                IpcMessage::CommandQueued
+            }
+            IpcMessage::QueueCommand { command } => {
+                log::info!("Daemon: queueing command {:?}", command);
+                self.tab_manager.queue_command(command);
+                IpcMessage::CommandQueued
+            }
+            IpcMessage::GetWindows => IpcMessage::WindowList { windows: self.windows() },
+            IpcMessage::GetStatus => IpcMessage::Status {
+                services: self.services.all(),
+                transport: self.tab_manager.sessions().active_transport(),
+            },
+            IpcMessage::GetSessions => IpcMessage::Sessions { sessions: self.tab_manager.sessions().list() },
+            IpcMessage::ControlSession { id, control } => {
+                if self.tab_manager.sessions().request(id, control) {
+                    log::info!("Daemon: queueing {:?} for extension session {}", control, id);
+                    IpcMessage::CommandQueued
+                } else {
+                    IpcMessage::Error { message: format!("No extension session {}", id) }
+                }
+            }
+            IpcMessage::NotificationAction { action } => match self.run_notification_action(&action) {
+                Ok(()) => IpcMessage::CommandQueued,
+                Err(message) => IpcMessage::Error { message },
+            },
+            IpcMessage::ShowLauncher { mode, prefill } => match show_launcher(mode, prefill.as_deref()) {
+                Ok(()) => IpcMessage::ActionResult { message: format!("Opened the launcher in {} mode", mode.name()) },
+                Err(e) => IpcMessage::Error { message: format!("Failed to open the launcher: {}", e) },
+            },
+            IpcMessage::RunKeyword { keyword, args } => IpcMessage::Results { results: self.run_keyword(&keyword, &args) },
+            IpcMessage::Search { query, mode } => IpcMessage::Results { results: self.search(&query, mode) },
+            IpcMessage::ExecuteAction { id } => match self.execute_action_id(&id) {
+                Ok(message) => IpcMessage::ActionResult { message },
+                Err(message) => IpcMessage::Error { message },
+            },
+            other => {
+                log::warn!("Daemon: unexpected IPC message: {:?}", other);
+                IpcMessage::Error {
+                    message: "Unexpected message".to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// ランチャーを新しいプロセスで、モードと入力を指定して開く
+///
+/// `--background` を付け、フォーカスを失ったら閉じるようにする（設定の `auto_hide`）。
+fn show_launcher(mode: SearchMode, prefill: Option<&str>) -> io::Result<()> {
+    let mut command = crate::paths::launcher_command()?;
+    command.args(["--mode", mode.name(), "--background"]);
+    if let Some(prefill) = prefill {
+        command.args(["--query", prefill]);
+    }
+    command.spawn().map(|_| ())
+}
+
+/// `my-launcher show|run|exec ...` の引数を IPC のリクエストにする
+///
+/// - `show [--mode browser|windows|tabs] [text]`: ランチャーを開く
+/// - `run <keyword> [args]`: キーワードの結果を `id<TAB>title<TAB>description` で一覧する
+/// - `exec <id>`: `run` で返した結果を実行
+pub fn trigger_request(command: &str, args: &[String]) -> Result<IpcMessage, String> {
+    match command {
+        "show" => {
+            let mut mode = SearchMode::Windows;
+            let mut text = Vec::new();
+            let mut rest = args.iter();
+            while let Some(arg) = rest.next() {
+                if arg == "--mode" {
+                    let name = rest.next().ok_or("--mode needs browser, windows or tabs")?;
+                    mode = SearchMode::from_name(name).ok_or_else(|| format!("Unknown mode '{}'", name))?;
+                } else {
+                    text.push(arg.as_str());
+                }
+            }
+            let prefill = (!text.is_empty()).then(|| text.join(" "));
+            Ok(IpcMessage::ShowLauncher { mode, prefill })
+        }
+        "run" => {
+            let (keyword, rest) = args.split_first().ok_or("Usage: my-launcher run <keyword> [args]")?;
+            Ok(IpcMessage::RunKeyword { keyword: keyword.clone(), args: rest.join(" ") })
+        }
+        "exec" => match args {
+            [id] => Ok(IpcMessage::ExecuteAction { id: id.clone() }),
+            _ => Err("Usage: my-launcher exec <id>".to_string()),
+        },
+        _ => Err(format!("Unknown command {}", command)),
+    }
+}
+
+/// 保存しておいたタブ一覧を読み込み、以降は変わるたびに保存する（WebSocketサーバーを動かすプロセスで呼ぶ）
+pub fn restore_tab_snapshot(tab_manager: &Arc<TabManager>) -> std::io::Result<()> {
+    let store = TabSnapshotStore::default();
+    match store.restore_into(tab_manager) {
+        Ok(0) => {}
+        Ok(count) => log::info!("Restored {} tabs from {}", count, store.path().display()),
+        Err(e) => log::warn!("Failed to restore tab snapshot from {}: {}", store.path().display(), e),
+    }
+    tab_snapshot::spawn_saver(Arc::clone(tab_manager), store)?;
+    Ok(())
+}
+
+/// デーモンモードで起動（UIを表示せずにブロックし続ける）
+pub fn run() -> Result<(), Box<dyn Error>> {
+    start()?;
+    loop {
+        thread::park();
+    }
+}
+
+/// デーモンのサービスをバックグラウンドのスレッドで起動して戻る（トーストを表示する場合はこのあと UI を動かす）
+pub fn start() -> Result<(), Box<dyn Error>> {
+    log::info!("Starting daemon");
+
+    let config = crate::config::LauncherConfig::load();
+    let tab_manager = Arc::new(config.tabs.tab_manager());
+    restore_tab_snapshot(&tab_manager)?;
+    let services = ServiceStatuses::new();
+    let state = Arc::new(DaemonState::with_services(Arc::clone(&tab_manager), services.clone()).with_config(config.clone()));
+
+    // ウィンドウトラッカー
+    {
+        let state = Arc::clone(&state);
+        thread::Builder::new()
+            .name("window-tracker".to_string())
+            .spawn(move || loop {
+                state.refresh_windows();
+                thread::sleep(WINDOW_REFRESH_INTERVAL);
+            })?;
+    }
+
+    crate::timers::spawn_scheduler(crate::timers::TimerStore::default())?;
+    crate::hotkeys::spawn_listener(config.hotkeys, Arc::clone(&tab_manager))?;
+    spawn_extension_watch(Arc::clone(&state))?;
+
+    // このプロセスで出した通知のボタンはここで処理する
+    {
+        let state = Arc::clone(&state);
+        notification::set_action_handler(move |action| {
+            if let Err(e) = state.run_notification_action(&action) {
+                log::warn!("Notification action {:?} failed: {}", action, e);
+            }
+        });
+    }
+
+    // サーバーは止まっても起動し直す（それぞれ専用のスレッドとランタイムで動かす）
+    let supervisor = state.supervisor().clone().on_failure(|name, error| {
+        notification::show_notification_with_actions(
+            &format!("{} stopped", name),
+            error,
+            &[NotificationAction::RetryService { name: name.to_string() }],
+        );
+    });
+    tab_transport::spawn_transports(&supervisor, tab_transport::default_transports(&tab_manager, WEBSOCKET_PORT))?;
+
+    if config.api.enabled {
+        let token = crate::http_api::load_or_create_token(&crate::paths::api_token_path())?;
+        let state = Arc::clone(&state);
+        let port = config.api.port;
+        let origins = config.api.allowed_origins.clone();
+        supervisor.spawn("http-api", move || {
+            let rt = Runtime::new().map_err(|e| e.to_string())?;
+            let server = HttpApiServer::new(Arc::clone(&state), port, token.clone()).with_allowed_origins(origins.clone());
+            rt.block_on(server.start()).map_err(|e| e.to_string())
+        })?;
+    }
+
+    #[cfg(windows)]
+    supervisor.spawn("ipc", move || {
+        let rt = Runtime::new().map_err(|e| e.to_string())?;
+        rt.block_on(serve_ipc(Arc::clone(&state))).map_err(|e| e.to_string())
+    })?;
+    #[cfg(not(windows))]
+    let _ = state;
+
+    Ok(())
+}
+
+/// Chrome が動いているのに拡張が切断されたままになったら通知するスレッドを起動
+///
+/// 一度も接続していない間（拡張を入れていない環境）は通知しない。再接続するまで通知は1回だけ。
+fn spawn_extension_watch(state: Arc<DaemonState>) -> std::io::Result<()> {
+    thread::Builder::new().name("extension-watch".to_string()).spawn(move || {
+        let mut was_connected = false;
+        let mut disconnected_since: Option<Instant> = None;
+        let mut notified = false;
+        loop {
+            thread::sleep(EXTENSION_CHECK_INTERVAL);
+            if state.tab_manager.is_extension_connected() {
+                was_connected = true;
+                disconnected_since = None;
+                notified = false;
+                continue;
+            }
+            if !was_connected || notified {
+                continue;
+            }
+            let since = *disconnected_since.get_or_insert_with(Instant::now);
+            let chrome_running = state
+                .windows()
+                .iter()
+                .any(|window| window.process_name.eq_ignore_ascii_case("chrome.exe"));
+            if chrome_running && since.elapsed() >= EXTENSION_DISCONNECT_GRACE {
+                log::warn!("Chrome extension has been disconnected for {:?}", since.elapsed());
+                notification::show_notification_with_actions(
+                    "Chrome extension disconnected",
+                    "Tabs in the launcher may be out of date. Reload the extension in chrome://extensions.",
+                    &[NotificationAction::ReopenLauncher],
+                );
+                notified = true;
+            }
+        }
+    })?;
+    Ok(())
+}
+
+/// 名前付きパイプでIPCリクエストを受け付ける（クライアントごとにインスタンスを作り直す）
+#[cfg(windows)]
+async fn serve_ipc(state: Arc<DaemonState>) -> io::Result<()> {
+    let mut server = crate::ipc::create_ipc_server().await?;
+    log::info!("Daemon IPC server listening on {}", PIPE_NAME);
+
+    loop {
+        server.connect().await?;
+        let mut connected = server;
+        server = ServerOptions::new().create(PIPE_NAME)?;
+
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            loop {
+                let response = match read_message(&mut connected).await {
+                    Ok(message) => state.handle_message(message),
+                    // 大きすぎる・読めないメッセージにはエラーを返し、接続は切らずに次を待つ
+                    Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                        log::warn!("Rejected IPC message: {}", e);
+                        IpcMessage::Error { message: format!("Invalid request: {}", e) }
+                    }
+                    Err(e) => {
+                        log::debug!("Daemon IPC client disconnected: {}", e);
+                        break;
+                    }
+                };
+
+                if let Err(e) = send_message(&mut connected, &response).await {
+                    log::error!("Failed to send IPC response: {}", e);
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// デーモンから取得したタブ一覧の状態
+#[cfg(windows)]
+pub struct TabState {
+    pub tabs: Vec<ChromeTab>,
+    pub extension_connected: bool,
+    /// デーモンが最後にタブ一覧の更新か keepAlive を受け取ってからの時間
+    pub age: Option<Duration>,
+    /// 保存しておいたスナップショットのままか
+    pub restored: bool,
+    pub recently_closed: Vec<ClosedTab>,
+    pub reading_list: Vec<ReadingListEntry>,
+}
+
+/// 起動中のデーモンに接続するクライアント
+#[cfg(windows)]
+pub struct DaemonClient {
+    runtime: Runtime,
+    pipe: Mutex<NamedPipeClient>,
+}
+
+#[cfg(windows)]
+impl DaemonClient {
+    /// デーモンに接続（起動していなければ即座にエラーを返す）
+    pub fn connect() -> io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let pipe = runtime.block_on(async { ClientOptions::new().open(PIPE_NAME) })?;
+
+        Ok(Self {
+            runtime,
+            pipe: Mutex::new(pipe),
+        })
+    }
+
+    pub fn request(&self, message: &IpcMessage) -> io::Result<IpcMessage> {
+        let mut pipe = self.pipe.lock().unwrap();
+        self.runtime.block_on(async {
+            send_message(&mut *pipe, message).await?;
+            read_message(&mut *pipe).await
+        })
+    }
+
+    pub fn get_tabs(&self) -> io::Result<Vec<ChromeTab>> {
+        self.get_tab_state().map(|state| state.tabs)
+    }
+
+    /// デーモンが持っているタブ一覧の状態
+    pub fn get_tab_state(&self) -> io::Result<TabState> {
+        match self.request(&IpcMessage::GetTabs)? {
+            IpcMessage::TabList { tabs, extension_connected, age_ms, restored, recently_closed, reading_list } => Ok(TabState {
+                tabs: tabs.into_iter().map(ChromeTab::from).collect(),
+                extension_connected,
+                age: age_ms.map(Duration::from_millis),
+                restored,
+                recently_closed,
+                reading_list,
+            }),
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    pub fn get_status(&self) -> io::Result<Vec<ServiceStatus>> {
+        match self.request(&IpcMessage::GetStatus)? {
+            IpcMessage::Status { services, .. } => Ok(services),
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    pub fn get_sessions(&self) -> io::Result<Vec<SessionInfo>> {
+        match self.request(&IpcMessage::GetSessions)? {
+            IpcMessage::Sessions { sessions } => Ok(sessions),
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    pub fn control_session(&self, id: u64, control: SessionControl) -> io::Result<()> {
+        match self.request(&IpcMessage::ControlSession { id, control })? {
+            IpcMessage::CommandQueued => Ok(()),
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    pub fn get_windows(&self) -> io::Result<Vec<WindowInfo>> {
+        match self.request(&IpcMessage::GetWindows)? {
+            IpcMessage::WindowList { windows } => Ok(windows),
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    pub fn switch_to_tab(&self, tab_id: i32, window_id: i32) -> io::Result<()> {
+        match self.request(&IpcMessage::SwitchToTab { tab_id, window_id })? {
+            IpcMessage::TabSwitchResult { success: true, .. } => Ok(()),
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    pub fn queue_command(&self, command: ChromeCommand) -> io::Result<()> {
+        match self.request(&IpcMessage::QueueCommand { command })? {
+            IpcMessage::CommandQueued => Ok(()),
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    /// 通知のボタンで頼まれた操作をデーモンに実行させる
+    pub fn send_notification_action(&self, action: NotificationAction) -> io::Result<()> {
+        match self.request(&IpcMessage::NotificationAction { action })? {
+            IpcMessage::CommandQueued => Ok(()),
+            other => Err(unexpected_response(other)),
+        }
+    }
+
+    pub fn close_tab(&self, tab_id: i32) -> io::Result<()> {
+        match self.request(&IpcMessage::CloseTab { tab_id })? {
+            IpcMessage::CommandQueued => Ok(()),
+            other => Err(unexpected_response(other)),
+        }
+    }
+}
+
+#[cfg(windows)]
+fn unexpected_response(message: IpcMessage) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Unexpected daemon response: {:?}", message),
+    )
+}
+
+/// デーモンのウィンドウ一覧を使う `WindowManager`
+///
+/// フォアグラウンド切り替えの権限はUIプロセスにあるため、切り替え自体はローカルで行う。
+#[cfg(windows)]
+pub struct DaemonWindowManager {
+    client: Arc<DaemonClient>,
+}
+
+#[cfg(windows)]
+impl DaemonWindowManager {
+    pub fn new(client: Arc<DaemonClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(windows)]
+impl WindowManager for DaemonWindowManager {
+    fn enumerate_windows(&self) -> Vec<WindowInfo> {
+        match self.client.get_windows() {
+            Ok(windows) => windows,
+            Err(e) => {
+                log::warn!("Failed to get windows from daemon, enumerating locally: {}", e);
+                crate::windows_api::enumerate_windows()
+            }
+        }
+    }
+
+    fn switch_to_window(&self, hwnd: isize) {
+        crate::windows_api::switch_to_window(hwnd);
+    }
+}
+
+/// デーモンのタブ一覧をローカルの `TabManager` に同期し、キューされたコマンドを転送する
+///
+/// デーモンのサーバーの状態と接続中の拡張機能もときどき取得して写す（UI のヘッダーと接続の一覧の表示用）。
+#[cfg(windows)]
+pub fn spawn_tab_sync(client: Arc<DaemonClient>, tab_manager: Arc<TabManager>, services: ServiceStatuses) {
+    let mut round = 0u32;
+    thread::spawn(move || loop {
+        if round % STATUS_SYNC_EVERY == 0 {
+            match client.get_status() {
+                Ok(statuses) => services.replace_all(statuses),
+                Err(e) => {
+                    log::warn!("Failed to get daemon status: {}", e);
+                    let mut status = ServiceStatus::new("daemon", ServiceState::Stopped);
+                    status.last_error = Some(e.to_string());
+                    services.replace_all(vec![status]);
+                }
+            }
+            match client.get_sessions() {
+                Ok(sessions) => tab_manager.sessions().replace_all(sessions),
+                Err(e) => log::warn!("Failed to get extension sessions from daemon: {}", e),
+            }
+        }
+        round = round.wrapping_add(1);
+
+        match client.get_tab_state() {
+            Ok(state) => {
+                if state.restored {
+                    tab_manager.restore_tabs(state.tabs, state.age);
+                } else {
+                    tab_manager.update_tabs_with_age(state.tabs, state.age);
+                }
+                tab_manager.set_extension_connected(state.extension_connected);
+                tab_manager.update_recently_closed(state.recently_closed);
+                tab_manager.update_reading_list(state.reading_list);
+            }
+            Err(e) => log::warn!("Failed to sync tabs from daemon: {}", e),
+        }
+
+        for (id, control) in tab_manager.sessions().take_all_controls() {
+            if let Err(e) = client.control_session(id, control) {
+                log::error!("Failed to forward {:?} for extension session {} to daemon: {}", control, id, e);
+            }
+        }
+
+        while let Some(command) = tab_manager.pop_command() {
+            match command {
+                ChromeCommand::SwitchToTab { tab_id, window_id } => {
+                    if let Err(e) = client.switch_to_tab(tab_id, window_id) {
+                        log::error!("Failed to forward tab switch to daemon: {}", e);
+                    }
+                }
+                ChromeCommand::CloseTab { tab_id } => {
+                    if let Err(e) = client.close_tab(tab_id) {
+                        log::error!("Failed to forward tab close to daemon: {}", e);
+                    }
+                }
+                other => {
+                    if let Err(e) = client.queue_command(other) {
+                        log::error!("Failed to forward command to daemon: {}", e);
+                    }
+                }
+            }
+        }
+
+        thread::sleep(TAB_SYNC_INTERVAL);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ChromeTab;
+
+    fn create_test_tab(id: i32) -> ChromeTab {
+        ChromeTab {
+            id,
+            window_id: 1,
+            title: format!("Tab {}", id),
+            url: format!("https://example.com/{}", id),
+            fav_icon_url: String::new(),
+            active: false,
+            index: id,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_handle_get_tabs() {
+        let tab_manager = Arc::new(TabManager::new());
+        tab_manager.update_tabs(vec![create_test_tab(1), create_test_tab(2)]);
+        let state = DaemonState::new(tab_manager);
+
+        match state.handle_message(IpcMessage::GetTabs) {
+            IpcMessage::TabList { tabs, age_ms, .. } => {
+                assert_eq!(tabs.len(), 2);
+                assert_eq!(tabs[0].title, "Tab 1");
+                assert!(age_ms.is_some_and(|age| age < 1000));
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_handle_get_status() {
+        use crate::supervisor::{ServiceState, ServiceStatus};
+
+        let services = ServiceStatuses::new();
+        services.set(ServiceStatus::new("websocket", ServiceState::Restarting { retry_in_secs: 4 }));
+        let state = DaemonState::with_services(Arc::new(TabManager::new()), services);
+
+        match state.handle_message(IpcMessage::GetStatus) {
+            IpcMessage::Status { services, transport } => {
+                assert_eq!(services.len(), 1);
+                assert_eq!(transport, None);
+                assert_eq!(services[0].state, ServiceState::Restarting { retry_in_secs: 4 });
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_handle_switch_to_tab_queues_command() {
+        let tab_manager = Arc::new(TabManager::new());
+        let state = DaemonState::new(Arc::clone(&tab_manager));
+
+        let response = state.handle_message(IpcMessage::SwitchToTab { tab_id: 5, window_id: 2 });
+        assert!(matches!(response, IpcMessage::TabSwitchResult { success: true, .. }));
+        assert!(matches!(
+            tab_manager.pop_command(),
+            Some(ChromeCommand::SwitchToTab { tab_id: 5, window_id: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_handle_queue_command() {
+        let tab_manager = Arc::new(TabManager::new());
+        let state = DaemonState::new(Arc::clone(&tab_manager));
+
+        let command = ChromeCommand::CreateBookmark {
+            url: "https://example.com".to_string(),
+            title: "Example".to_string(),
+            folder: None,
+        };
+        let response = state.handle_message(IpcMessage::QueueCommand { command });
+        assert!(matches!(response, IpcMessage::CommandQueued));
+        assert!(matches!(tab_manager.pop_command(), Some(ChromeCommand::CreateBookmark { .. })));
+    }
+
+    #[test]
+    fn test_handle_close_tab_queues_command() {
+        let tab_manager = Arc::new(TabManager::new());
+        let state = DaemonState::new(Arc::clone(&tab_manager));
+
+        let response = state.handle_message(IpcMessage::CloseTab { tab_id: 7 });
+        assert!(matches!(response, IpcMessage::CommandQueued));
+        assert!(matches!(tab_manager.pop_command(), Some(ChromeCommand::CloseTab { tab_id: 7 })));
+    }
+
+    #[test]
+    fn test_handle_control_session() {
+        use crate::core::extension_sessions::{SessionControl, Transport};
+
+        let tab_manager = Arc::new(TabManager::new());
+        let id = tab_manager.sessions().open(Transport::WebSocket, None);
+        let state = DaemonState::new(Arc::clone(&tab_manager));
+
+        match state.handle_message(IpcMessage::GetSessions) {
+            IpcMessage::Sessions { sessions } => assert_eq!(sessions[0].id, id),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+        let response = state.handle_message(IpcMessage::ControlSession { id, control: SessionControl::RefreshTabs });
+        assert!(matches!(response, IpcMessage::CommandQueued));
+        assert_eq!(tab_manager.sessions().take_controls(id), vec![SessionControl::RefreshTabs]);
+
+        let response = state.handle_message(IpcMessage::ControlSession { id: id + 1, control: SessionControl::Disconnect });
+        assert!(matches!(response, IpcMessage::Error { .. }));
+    }
+
+    #[test]
+    fn test_handle_get_windows() {
+        let state = DaemonState::new(Arc::new(TabManager::new()));
+        *state.windows.lock().unwrap() = vec![crate::test_helpers::helpers::create_test_window(1, "Editor", "editor.exe")];
+
+        match state.handle_message(IpcMessage::GetWindows) {
+            IpcMessage::WindowList { windows } => {
+                assert_eq!(windows.len(), 1);
+                assert_eq!(windows[0].title, "Editor");
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_handle_notification_action() {
+        let state = DaemonState::new(Arc::new(TabManager::new()));
+        let response = state.handle_message(IpcMessage::NotificationAction {
+            action: NotificationAction::RetryService { name: "websocket".to_string() },
+        });
+        // まだ起動していないサービスは起動し直せない
+        assert!(matches!(response, IpcMessage::Error { .. }));
+    }
+
+    #[test]
+    fn test_handle_unexpected_message() {
+        let state = DaemonState::new(Arc::new(TabManager::new()));
+        let response = state.handle_message(IpcMessage::TabList { tabs: Vec::new(), extension_connected: false, age_ms: None, restored: false, recently_closed: Vec::new(), reading_list: Vec::new() });
+        assert!(matches!(response, IpcMessage::Error { .. }));
+    }
+
+    #[test]
+    fn test_handle_run_keyword_and_execute_action() {
+        let state = DaemonState::new(Arc::new(TabManager::new()));
+
+        match state.handle_message(IpcMessage::RunKeyword { keyword: "nt".to_string(), args: "rust lang".to_string() }) {
+            IpcMessage::Results { results } => {
+                assert_eq!(results.len(), 1);
+                assert_eq!(results[0].id, "url:https://www.google.com/search?q=rust%20lang");
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+
+        let response = state.handle_message(IpcMessage::ExecuteAction { id: "url:https://example.com".to_string() });
+        assert!(matches!(response, IpcMessage::Error { .. }));
+    }
+
+    #[test]
+    fn test_trigger_request() {
+        let args = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+
+        match trigger_request("show", &args(&["--mode", "tabs", "github", "pr"])) {
+            Ok(IpcMessage::ShowLauncher { mode, prefill }) => {
+                assert_eq!(mode, SearchMode::Tabs);
+                assert_eq!(prefill.as_deref(), Some("github pr"));
+            }
+            other => panic!("unexpected request: {:?}", other),
+        }
+        match trigger_request("run", &args(&["nt", "rust", "docs"])) {
+            Ok(IpcMessage::RunKeyword { keyword, args }) => assert_eq!((keyword.as_str(), args.as_str()), ("nt", "rust docs")),
+            other => panic!("unexpected request: {:?}", other),
+        }
+        assert!(matches!(trigger_request("exec", &args(&["macro:standup"])), Ok(IpcMessage::ExecuteAction { .. })));
+        assert!(trigger_request("show", &args(&["--mode", "grid"])).is_err());
+        assert!(trigger_request("exec", &[]).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use super::browser_item::{BookmarkItem, HistoryItem};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// 形式を変えたら上げる（古い形式の索引は読み込まずに作り直す）
+const INDEX_VERSION: u32 = 1;
+
+/// 読み込んだファイルとその更新日時
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub path: PathBuf,
+    /// UNIX時刻・ミリ秒
+    pub modified_ms: u64,
+}
+
+/// ファイルの更新日時（更新日時を読めないファイルは除く）
+pub fn stamp(files: &[PathBuf]) -> Vec<FileStamp> {
+    files
+        .iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+            let modified_ms = modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as u64;
+            Some(FileStamp { path: path.clone(), modified_ms })
+        })
+        .collect()
+}
+
+/// 読み込んだ項目と、読み込んだときのファイルの更新日時
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Indexed<T> {
+    pub stamps: Vec<FileStamp>,
+    pub items: Vec<T>,
+}
+
+impl<T> Indexed<T> {
+    pub fn new(files: &[PathBuf], items: Vec<T>) -> Self {
+        Self { stamps: stamp(files), items }
+    }
+
+    /// 読み込んだときからファイルが増減・更新されていないか
+    pub fn is_current(&self, files: &[PathBuf]) -> bool {
+        self.stamps == stamp(files)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserIndex {
+    version: u32,
+    pub bookmarks: Option<Indexed<BookmarkItem>>,
+    pub history: Option<Indexed<HistoryItem>>,
+}
+
+impl BrowserIndex {
+    pub fn new(bookmarks: Option<Indexed<BookmarkItem>>, history: Option<Indexed<HistoryItem>>) -> Self {
+        Self { version: INDEX_VERSION, bookmarks, history }
+    }
+}
+
+/// 終了時に保存するブックマーク・履歴の索引（cache/browser_index.json）
+///
+/// 起動時に読み込み、Bookmarks・History ファイルの更新日時が保存したときと同じなら
+/// すべてのプロファイルを読み直さずに使う（`CachedBrowserProvider::load_index`）。
+#[derive(Debug, Clone)]
+pub struct BrowserIndexStore {
+    path: PathBuf,
+}
+
+impl BrowserIndexStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 保存した索引（ないか形式が古ければ `None`）
+    pub fn load(&self) -> Result<Option<BrowserIndex>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        let index: BrowserIndex = serde_json::from_str(&content)?;
+        Ok((index.version == INDEX_VERSION).then_some(index))
+    }
+
+    pub fn save(&self, index: &BrowserIndex) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string(index)?)?;
+        Ok(())
+    }
+}
+
+impl Default for BrowserIndexStore {
+    fn default() -> Self {
+        Self::new(crate::paths::browser_index_path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_round_trip_and_staleness() {
+        let dir = tempfile::tempdir().unwrap();
+        let bookmarks_file = dir.path().join("Bookmarks");
+        fs::write(&bookmarks_file, "{}").unwrap();
+        let files = vec![bookmarks_file.clone()];
+
+        let store = BrowserIndexStore::new(dir.path().join("cache").join("browser_index.json"));
+        assert!(store.load().unwrap().is_none());
+
+        let bookmark = BookmarkItem {
+            id: Some("5".to_string()),
+            title: "Rust".to_string(),
+            url: "https://www.rust-lang.org".to_string(),
+            folder: None,
+            browser_name: Some("Chrome".to_string()),
+            profile_name: Some("Default".to_string()),
+        };
+        store.save(&BrowserIndex::new(Some(Indexed::new(&files, vec![bookmark])), None)).unwrap();
+
+        let loaded = store.load().unwrap().unwrap();
+        let bookmarks = loaded.bookmarks.unwrap();
+        assert_eq!(bookmarks.items[0].title, "Rust");
+        assert!(bookmarks.is_current(&files));
+        assert!(loaded.history.is_none());
+
+        // プロファイルが増えたら使わない
+        let other = dir.path().join("Profile 1 Bookmarks");
+        fs::write(&other, "{}").unwrap();
+        assert!(!bookmarks.is_current(&[bookmarks_file, other]));
+    }
+}
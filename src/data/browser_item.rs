@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 /// ブックマークアイテム
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookmarkItem {
+    /// Bookmarks ファイルのノードの ID（拡張機能の `chrome.bookmarks` と同じ値）
+    #[serde(default)]
+    pub id: Option<String>,
     pub title: String,
     pub url: String,
     pub folder: Option<String>,
@@ -31,7 +34,7 @@ impl Searchable for BookmarkItem {
 }
 
 /// 履歴アイテム
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryItem {
     pub title: String,
     pub url: String,
@@ -74,6 +77,8 @@ pub struct BookmarkRoots {
 
 #[derive(Debug, Deserialize)]
 pub struct BookmarkNode {
+    #[serde(default)]
+    pub id: Option<String>,
     #[serde(default)]
     pub children: Vec<BookmarkNode>,
     pub name: String,
@@ -89,6 +94,7 @@ impl BookmarkNode {
         if self.node_type == "url" {
             if let Some(url) = &self.url {
                 items.push(BookmarkItem {
+                    id: self.id.clone(),
                     title: self.name.clone(),
                     url: url.clone(),
                     folder: parent_folder.map(|s| s.to_string()),
@@ -1,13 +1,25 @@
 use super::browser_item::{BookmarkItem, HistoryItem, ChromeBookmarks};
+use super::browser_index::{BrowserIndex, BrowserIndexStore, Indexed};
 use std::path::PathBuf;
 use std::fs;
 use std::error::Error;
+use std::collections::HashMap;
 
 pub trait BrowserDataProvider: Send + Sync {
     fn get_bookmarks(&self) -> Result<Vec<BookmarkItem>, Box<dyn Error>>;
     fn get_history(&self) -> Result<Vec<HistoryItem>, Box<dyn Error>>;
     fn search_bookmarks(&self, query: &str) -> Result<Vec<BookmarkItem>, Box<dyn Error>>;
     fn search_history(&self, query: &str) -> Result<Vec<HistoryItem>, Box<dyn Error>>;
+
+    /// ブックマークを読み込むファイル（変わっていなければ `CachedBrowserProvider` が索引を使い続ける）
+    fn bookmark_files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// 履歴を読み込むファイル
+    fn history_files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +57,34 @@ impl Default for BrowserConfig {
     }
 }
 
+/// プロファイルを指定してブラウザで URL を開く（`profile` は `Chrome - Profile 1` の形式）
+pub fn open_in_profile(profile: &str, url: &str) -> Result<(), Box<dyn Error>> {
+    let (browser, profile_directory) = profile
+        .split_once(" - ")
+        .ok_or_else(|| format!("Invalid profile '{}'", profile))?;
+    let executable = browser_executable(browser).ok_or_else(|| format!("{} is not installed", browser))?;
+    log::info!("Opening {} in {}", url, profile);
+    std::process::Command::new(executable)
+        .arg(format!("--profile-directory={}", profile_directory))
+        .arg(url)
+        .spawn()?;
+    Ok(())
+}
+
+/// ブラウザの実行ファイル（ユーザー単位のインストールを優先）
+fn browser_executable(browser_name: &str) -> Option<PathBuf> {
+    let relative: &[&str] = match browser_name {
+        "Chrome" => &["Google", "Chrome", "Application", "chrome.exe"],
+        "Wavebox" => &["WaveboxApp", "Application", "wavebox.exe"],
+        _ => return None,
+    };
+    ["LOCALAPPDATA", "ProgramFiles", "ProgramFiles(x86)"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .map(|base| relative.iter().fold(PathBuf::from(base), |path, part| path.join(part)))
+        .find(|path| path.exists())
+}
+
 pub struct ChromeBrowserProvider {
     profiles: Vec<BrowserProfile>,
     config: BrowserConfig,
@@ -247,14 +287,7 @@ impl ChromeBrowserProvider {
         
         // クエリに基づいてSQL文とパラメータを準備
         // Chrome の last_visit_time は Webkit timestamp (1601年1月1日からのマイクロ秒)
-        // 2週間前の timestamp を計算
-        let two_weeks_ago = {
-            use std::time::{SystemTime, UNIX_EPOCH};
-            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            // Unix timestamp を Webkit timestamp に変換 (1601年1月1日との差は11644473600秒)
-            let webkit_now = (now + 11644473600) * 1_000_000; // マイクロ秒に変換
-            webkit_now - (14 * 24 * 60 * 60 * 1_000_000) // 2週間前
-        };
+        let two_weeks_ago = crate::time_format::to_webkit(chrono::Utc::now() - chrono::Duration::days(14));
         
         let (sql, params): (&str, Vec<String>) = if let Some(q) = query {
             // SQLインジェクション対策のため、パラメータバインディングを使用
@@ -343,15 +376,41 @@ impl BrowserDataProvider for ChromeBrowserProvider {
     fn search_history(&self, query: &str) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
         self.search_history_internal(Some(query))
     }
+
+    fn bookmark_files(&self) -> Vec<PathBuf> {
+        self.profiles.iter().map(|profile| profile.profile_path.join("Bookmarks")).filter(|path| path.exists()).collect()
+    }
+
+    fn history_files(&self) -> Vec<PathBuf> {
+        self.profiles.iter().map(|profile| profile.profile_path.join("History")).filter(|path| path.exists()).collect()
+    }
 }
 
 // キャッシュ付きプロバイダー
 use std::sync::Mutex;
 
+/// 拡張機能経由で行ったブックマークの変更
+///
+/// Chromeが Bookmarks ファイルを書き出すまでには時間がかかるため、
+/// それまでの間も検索結果に反映されるよう変更内容を保持しておく。
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookmarkEdit {
+    Deleted,
+    Renamed(String),
+}
+
+/// 取得元の結果をメモリに持っておくプロバイダー
+///
+/// 取得元のファイルが分かる場合（`bookmark_files`）は、ファイルの更新日時が変わるまで
+/// ブックマークの検索もメモリ上で行う。終了時に `save_index` で保存し、次の起動時に `load_index` で読み込む。
 pub struct CachedBrowserProvider {
     inner: Box<dyn BrowserDataProvider>,
-    bookmarks_cache: Mutex<Option<Vec<BookmarkItem>>>,
-    history_cache: Mutex<Option<Vec<HistoryItem>>>,
+    bookmarks_cache: Mutex<Option<Indexed<BookmarkItem>>>,
+    history_cache: Mutex<Option<Indexed<HistoryItem>>>,
+    /// URLごとの未反映の変更
+    pending_edits: Mutex<HashMap<String, BookmarkEdit>>,
+    /// 読み込まないプロファイル（`ブラウザ - プロファイル`、設定の `privacy.private_profiles`）
+    private_profiles: Vec<String>,
 }
 
 impl CachedBrowserProvider {
@@ -360,45 +419,346 @@ impl CachedBrowserProvider {
             inner: provider,
             bookmarks_cache: Mutex::new(None),
             history_cache: Mutex::new(None),
+            pending_edits: Mutex::new(HashMap::new()),
+            private_profiles: Vec::new(),
         }
     }
 
+    /// このプロファイルのブックマーク・履歴は結果にもキャッシュ・索引にも含めない
+    pub fn set_private_profiles(&mut self, profiles: Vec<String>) {
+        self.private_profiles = profiles;
+        self.refresh();
+    }
+
+    fn is_private(&self, browser: Option<&str>, profile: Option<&str>) -> bool {
+        match (browser, profile) {
+            (Some(browser), Some(profile)) => self.private_profiles.iter().any(|private| *private == format!("{} - {}", browser, profile)),
+            _ => false,
+        }
+    }
+
+    fn public_bookmarks(&self, mut bookmarks: Vec<BookmarkItem>) -> Vec<BookmarkItem> {
+        bookmarks.retain(|bookmark| !self.is_private(bookmark.browser_name.as_deref(), bookmark.profile_name.as_deref()));
+        bookmarks
+    }
+
+    fn public_history(&self, mut history: Vec<HistoryItem>) -> Vec<HistoryItem> {
+        history.retain(|item| !self.is_private(item.browser_name.as_deref(), item.profile_name.as_deref()));
+        history
+    }
+
     pub fn refresh(&mut self) {
         *self.bookmarks_cache.lock().unwrap() = None;
         *self.history_cache.lock().unwrap() = None;
+        self.pending_edits.lock().unwrap().clear();
+    }
+
+    /// ブックマークの変更を記録し、以降の結果に反映する
+    pub fn record_edit(&self, url: &str, edit: BookmarkEdit) {
+        self.pending_edits.lock().unwrap().insert(url.to_string(), edit);
+    }
+
+    /// 保存した索引のうち、ファイルが変わっていないものを読み込む
+    pub fn load_index(&self, store: &BrowserIndexStore) {
+        let index = match store.load() {
+            Ok(Some(index)) => index,
+            Ok(None) => return,
+            Err(e) => {
+                log::warn!("Failed to load browser index from {}: {}", store.path().display(), e);
+                return;
+            }
+        };
+
+        let bookmark_files = self.inner.bookmark_files();
+        if let Some(mut bookmarks) = index.bookmarks.filter(|bookmarks| !bookmark_files.is_empty() && bookmarks.is_current(&bookmark_files)) {
+            bookmarks.items = self.public_bookmarks(bookmarks.items);
+            log::info!("Loaded {} bookmarks from the browser index", bookmarks.items.len());
+            *self.bookmarks_cache.lock().unwrap() = Some(bookmarks);
+        }
+        let history_files = self.inner.history_files();
+        if let Some(mut history) = index.history.filter(|history| !history_files.is_empty() && history.is_current(&history_files)) {
+            history.items = self.public_history(history.items);
+            log::info!("Loaded {} history items from the browser index", history.items.len());
+            *self.history_cache.lock().unwrap() = Some(history);
+        }
+    }
+
+    /// メモリに持っている結果を索引として保存する
+    pub fn save_index(&self, store: &BrowserIndexStore) -> Result<(), Box<dyn Error>> {
+        let bookmarks = self.bookmarks_cache.lock().unwrap().clone();
+        let history = self.history_cache.lock().unwrap().clone();
+        if bookmarks.is_none() && history.is_none() {
+            return Ok(());
+        }
+        store.save(&BrowserIndex::new(bookmarks, history))
+    }
+
+    fn apply_edits(&self, bookmarks: Vec<BookmarkItem>) -> Vec<BookmarkItem> {
+        let edits = self.pending_edits.lock().unwrap();
+        if edits.is_empty() {
+            return bookmarks;
+        }
+
+        bookmarks
+            .into_iter()
+            .filter_map(|mut bookmark| match edits.get(&bookmark.url) {
+                Some(BookmarkEdit::Deleted) => None,
+                Some(BookmarkEdit::Renamed(title)) => {
+                    bookmark.title = title.clone();
+                    Some(bookmark)
+                }
+                None => Some(bookmark),
+            })
+            .collect()
     }
 }
 
 impl BrowserDataProvider for CachedBrowserProvider {
     fn get_bookmarks(&self) -> Result<Vec<BookmarkItem>, Box<dyn Error>> {
+        let files = self.inner.bookmark_files();
         let mut cache = self.bookmarks_cache.lock().unwrap();
-        if let Some(cached) = cache.as_ref() {
-            return Ok(cached.clone());
+        if let Some(cached) = cache.as_ref().filter(|cached| cached.is_current(&files)) {
+            return Ok(self.apply_edits(cached.items.clone()));
         }
         
-        let bookmarks = self.inner.get_bookmarks()?;
-        *cache = Some(bookmarks.clone());
-        Ok(bookmarks)
+        let bookmarks = self.public_bookmarks(self.inner.get_bookmarks()?);
+        *cache = Some(Indexed::new(&files, bookmarks.clone()));
+        Ok(self.apply_edits(bookmarks))
     }
 
     fn get_history(&self) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
+        let files = self.inner.history_files();
         let mut cache = self.history_cache.lock().unwrap();
-        if let Some(cached) = cache.as_ref() {
-            return Ok(cached.clone());
+        if let Some(cached) = cache.as_ref().filter(|cached| cached.is_current(&files)) {
+            return Ok(cached.items.clone());
         }
         
-        let history = self.inner.get_history()?;
-        *cache = Some(history.clone());
+        let history = self.public_history(self.inner.get_history()?);
+        *cache = Some(Indexed::new(&files, history.clone()));
         Ok(history)
     }
     
     fn search_bookmarks(&self, query: &str) -> Result<Vec<BookmarkItem>, Box<dyn Error>> {
-        // 検索はキャッシュせずに直接実行
-        self.inner.search_bookmarks(query)
+        // 取得元のファイルが分からなければ、キャッシュせずに直接実行
+        if self.inner.bookmark_files().is_empty() {
+            return Ok(self.apply_edits(self.public_bookmarks(self.inner.search_bookmarks(query)?)));
+        }
+        // ChromeBrowserProvider と同じく、タイトル・URL・ブラウザ名・プロファイル名で絞り込む
+        let query = query.to_lowercase();
+        let contains = |field: Option<&str>| field.is_some_and(|field| field.to_lowercase().contains(&query));
+        Ok(self
+            .get_bookmarks()?
+            .into_iter()
+            .filter(|bookmark| {
+                contains(Some(&bookmark.title))
+                    || contains(Some(&bookmark.url))
+                    || contains(bookmark.browser_name.as_deref())
+                    || contains(bookmark.profile_name.as_deref())
+            })
+            .collect())
     }
     
     fn search_history(&self, query: &str) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
         // 検索はキャッシュせずに直接実行
-        self.inner.search_history(query)
+        Ok(self.public_history(self.inner.search_history(query)?))
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod mock {
+    use super::*;
+
+    /// 決まったブックマークと履歴を返すプロバイダー（実際の Chrome のプロファイルを読まずに検索をテストする）
+    ///
+    /// 検索は `ChromeBrowserProvider` と同じく、タイトルか URL に大文字小文字を区別せず含まれるものを返し、
+    /// 履歴は訪問回数・最終訪問の新しい順に並べる。
+    #[derive(Debug, Clone, Default)]
+    pub struct InMemoryBrowserProvider {
+        bookmarks: Vec<BookmarkItem>,
+        history: Vec<HistoryItem>,
+    }
+
+    impl InMemoryBrowserProvider {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn with_bookmarks(mut self, bookmarks: Vec<BookmarkItem>) -> Self {
+            self.bookmarks = bookmarks;
+            self
+        }
+
+        pub fn with_history(mut self, history: Vec<HistoryItem>) -> Self {
+            self.history = history;
+            self
+        }
+
+        fn matches(title: &str, url: &str, query: &str) -> bool {
+            let query = query.to_lowercase();
+            title.to_lowercase().contains(&query) || url.to_lowercase().contains(&query)
+        }
     }
-}
\ No newline at end of file
+
+    impl BrowserDataProvider for InMemoryBrowserProvider {
+        fn get_bookmarks(&self) -> Result<Vec<BookmarkItem>, Box<dyn Error>> {
+            Ok(self.bookmarks.clone())
+        }
+
+        fn get_history(&self) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
+            let mut history = self.history.clone();
+            history.sort_by(|a, b| b.visit_count.cmp(&a.visit_count).then(b.last_visit_time.cmp(&a.last_visit_time)));
+            Ok(history)
+        }
+
+        fn search_bookmarks(&self, query: &str) -> Result<Vec<BookmarkItem>, Box<dyn Error>> {
+            Ok(self.bookmarks.iter().filter(|bookmark| Self::matches(&bookmark.title, &bookmark.url, query)).cloned().collect())
+        }
+
+        fn search_history(&self, query: &str) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
+            Ok(self.get_history()?.into_iter().filter(|history| Self::matches(&history.title, &history.url, query)).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StaticBookmarkProvider {
+        bookmarks: Vec<BookmarkItem>,
+    }
+
+    impl BrowserDataProvider for StaticBookmarkProvider {
+        fn get_bookmarks(&self) -> Result<Vec<BookmarkItem>, Box<dyn Error>> {
+            Ok(self.bookmarks.clone())
+        }
+
+        fn get_history(&self) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
+            Ok(Vec::new())
+        }
+
+        fn search_bookmarks(&self, _query: &str) -> Result<Vec<BookmarkItem>, Box<dyn Error>> {
+            Ok(self.bookmarks.clone())
+        }
+
+        fn search_history(&self, _query: &str) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn create_bookmark(title: &str, url: &str) -> BookmarkItem {
+        BookmarkItem {
+            id: None,
+            title: title.to_string(),
+            url: url.to_string(),
+            folder: None,
+            browser_name: None,
+            profile_name: None,
+        }
+    }
+
+    fn create_provider() -> CachedBrowserProvider {
+        CachedBrowserProvider::new(Box::new(StaticBookmarkProvider {
+            bookmarks: vec![
+                create_bookmark("Rust", "https://www.rust-lang.org"),
+                create_bookmark("GitHub", "https://github.com"),
+            ],
+        }))
+    }
+
+    #[test]
+    fn test_deleted_bookmark_is_hidden() {
+        let provider = create_provider();
+        provider.record_edit("https://github.com", BookmarkEdit::Deleted);
+
+        let results = provider.search_bookmarks("").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rust");
+        assert_eq!(provider.get_bookmarks().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_renamed_bookmark_uses_new_title() {
+        let provider = create_provider();
+        provider.record_edit("https://www.rust-lang.org", BookmarkEdit::Renamed("Rust Language".to_string()));
+
+        let results = provider.search_bookmarks("").unwrap();
+        assert_eq!(results[0].title, "Rust Language");
+    }
+
+    #[test]
+    fn test_refresh_clears_pending_edits() {
+        let mut provider = create_provider();
+        provider.record_edit("https://github.com", BookmarkEdit::Deleted);
+        provider.refresh();
+
+        assert_eq!(provider.search_bookmarks("").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_private_profiles_are_excluded() {
+        let mut private = create_bookmark("Payroll", "https://payroll.example.com");
+        private.browser_name = Some("Chrome".to_string());
+        private.profile_name = Some("Profile 2".to_string());
+        let mut provider = CachedBrowserProvider::new(Box::new(StaticBookmarkProvider {
+            bookmarks: vec![create_bookmark("Rust", "https://www.rust-lang.org"), private],
+        }));
+        provider.set_private_profiles(vec!["Chrome - Profile 2".to_string()]);
+
+        assert_eq!(provider.search_bookmarks("").unwrap().len(), 1);
+        assert_eq!(provider.get_bookmarks().unwrap()[0].title, "Rust");
+    }
+
+    /// Bookmarks ファイルを読んだ回数を数えるプロバイダー
+    struct FileBookmarkProvider {
+        file: PathBuf,
+        reads: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl BrowserDataProvider for FileBookmarkProvider {
+        fn get_bookmarks(&self) -> Result<Vec<BookmarkItem>, Box<dyn Error>> {
+            self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(vec![create_bookmark("Rust", "https://www.rust-lang.org"), create_bookmark("GitHub", "https://github.com")])
+        }
+
+        fn get_history(&self) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
+            Ok(Vec::new())
+        }
+
+        fn search_bookmarks(&self, _query: &str) -> Result<Vec<BookmarkItem>, Box<dyn Error>> {
+            unreachable!("searched through the index")
+        }
+
+        fn search_history(&self, _query: &str) -> Result<Vec<HistoryItem>, Box<dyn Error>> {
+            Ok(Vec::new())
+        }
+
+        fn bookmark_files(&self) -> Vec<PathBuf> {
+            vec![self.file.clone()]
+        }
+    }
+
+    #[test]
+    fn test_index_is_saved_and_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Bookmarks");
+        fs::write(&file, "{}").unwrap();
+        let store = BrowserIndexStore::new(dir.path().join("browser_index.json"));
+        let reads = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let provider = || {
+            CachedBrowserProvider::new(Box::new(FileBookmarkProvider { file: file.clone(), reads: reads.clone() }))
+        };
+
+        let first = provider();
+        assert_eq!(first.search_bookmarks("rust").unwrap().len(), 1);
+        assert_eq!(first.search_bookmarks("GIT").unwrap()[0].title, "GitHub");
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), 1);
+        first.save_index(&store).unwrap();
+
+        // 次の起動ではファイルを読まずに検索できる
+        let second = provider();
+        second.load_index(&store);
+        assert_eq!(second.search_bookmarks("rust").unwrap().len(), 1);
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
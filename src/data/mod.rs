@@ -3,4 +3,10 @@ pub mod window_item;
 pub mod browser_item;
 pub mod browser_provider;
 pub mod tab_item;
-pub mod tab_provider;
\ No newline at end of file
+pub mod tab_provider;
+pub mod tab_snapshot;
+pub mod notes;
+pub mod usage;
+pub mod workspaces;
+pub mod recent_queries;
+pub mod browser_index;
\ No newline at end of file
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Local};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 保存時刻の書式
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// `notes` で表示する最大件数
+const MAX_NOTE_RESULTS: usize = 20;
+
+/// クイックメモの1行
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteEntry {
+    /// ファイル内の行番号（0始まり）
+    pub line: usize,
+    pub timestamp: String,
+    pub text: String,
+}
+
+/// クイックメモを Markdown ファイルに追記・検索する
+///
+/// 1件のメモは `- [2024-01-01 12:00] テキスト` の形式の1行で保存する。
+/// ファイルを直接編集して書き加えた行も検索対象になる。
+#[derive(Debug, Clone)]
+pub struct NoteStore {
+    path: PathBuf,
+}
+
+impl NoteStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// メモを末尾に追記
+    pub fn append(&self, text: &str, now: DateTime<Local>) -> Result<(), Box<dyn Error>> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("note is empty".into());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        // 改行を含むメモも1行に収める
+        let text = text.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+        writeln!(file, "- [{}] {}", now.format(TIMESTAMP_FORMAT), text)?;
+        Ok(())
+    }
+
+    /// クエリを含むメモを新しい順に返す（空のクエリではすべて）
+    pub fn search(&self, query: &str) -> Result<Vec<NoteEntry>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        let query = query.trim().to_lowercase();
+
+        let matching: Vec<NoteEntry> = content
+            .lines()
+            .enumerate()
+            .filter_map(|(line, text)| parse_note_line(line, text))
+            .filter(|entry| query.is_empty() || entry.text.to_lowercase().contains(&query))
+            .collect();
+        Ok(matching.into_iter().rev().take(MAX_NOTE_RESULTS).collect())
+    }
+}
+
+/// 1行をメモとして解析（リスト項目以外の行は無視）
+fn parse_note_line(line: usize, text: &str) -> Option<NoteEntry> {
+    let item = text.trim().strip_prefix("- ")?.trim();
+    if item.is_empty() {
+        return None;
+    }
+
+    // 手で書いた行には時刻がないこともある
+    let (timestamp, text) = match item.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+        Some((timestamp, text)) => (timestamp.to_string(), text.trim()),
+        None => (String::new(), item),
+    };
+
+    Some(NoteEntry {
+        line,
+        timestamp,
+        text: text.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 1, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_append_writes_timestamped_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = NoteStore::new(dir.path().join("notes").join("inbox.md"));
+
+        store.append("  buy milk ", at(9, 5)).unwrap();
+        store.append("call\nbob", at(10, 30)).unwrap();
+        assert!(store.append("   ", at(11, 0)).is_err());
+
+        let content = fs::read_to_string(store.path()).unwrap();
+        assert_eq!(content, "- [2024-05-01 09:05] buy milk\n- [2024-05-01 10:30] call bob\n");
+    }
+
+    #[test]
+    fn test_search_returns_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        fs::write(&path, "# Inbox\n\n- [2024-05-01 09:05] Buy milk\n- hand written idea\n- [2024-05-02 08:00] milk again\n").unwrap();
+        let store = NoteStore::new(path);
+
+        let all = store.search("").unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].text, "milk again");
+        assert_eq!(all[1].timestamp, "");
+        assert_eq!(all[1].text, "hand written idea");
+
+        let milk = store.search("MILK").unwrap();
+        assert_eq!(milk.len(), 2);
+        assert_eq!(milk[1].line, 2);
+        assert_eq!(milk[1].timestamp, "2024-05-01 09:05");
+    }
+
+    #[test]
+    fn test_search_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = NoteStore::new(dir.path().join("missing.md"));
+        assert!(store.search("anything").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 残す検索語の数
+const MAX_QUERIES: usize = 20;
+
+/// 結果を開いたときの検索語（recent_queries.json、新しいものが先頭）
+///
+/// 検索語が空のときの表示（`core/empty_state.rs`）で、最近の検索語として出す。
+#[derive(Debug, Clone)]
+pub struct RecentQueryStore {
+    path: PathBuf,
+}
+
+impl RecentQueryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 検索語を先頭に記録する（同じ検索語は大文字小文字を区別せず 1 つにまとめる）
+    pub fn record(&self, query: &str) -> Result<(), Box<dyn Error>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(());
+        }
+        let mut queries = self.load()?;
+        queries.retain(|recent| !recent.eq_ignore_ascii_case(query));
+        queries.insert(0, query.to_string());
+        queries.truncate(MAX_QUERIES);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string(&queries)?)?;
+        Ok(())
+    }
+}
+
+impl RecentQueryStore {
+    /// 記録した検索語をすべて消す（プライバシーモードにしたとき）
+    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for RecentQueryStore {
+    fn default() -> Self {
+        Self::new(crate::paths::recent_queries_path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_moves_to_front() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RecentQueryStore::new(dir.path().join("recent_queries.json"));
+        assert!(store.load().unwrap().is_empty());
+
+        store.record("rust book").unwrap();
+        store.record("jira").unwrap();
+        store.record(" Rust Book ").unwrap();
+        store.record("  ").unwrap();
+        assert_eq!(store.load().unwrap(), vec!["Rust Book", "jira"]);
+
+        for i in 0..MAX_QUERIES {
+            store.record(&format!("query {}", i)).unwrap();
+        }
+        assert_eq!(store.load().unwrap().len(), MAX_QUERIES);
+
+        store.clear().unwrap();
+        assert!(store.load().unwrap().is_empty());
+    }
+}
@@ -40,8 +40,7 @@ impl TabProvider for ChromeTabProvider {
     }
     
     fn search_tabs(&self, query: &str) -> Vec<TabItem> {
-        let all_tabs = self.tab_manager.get_tabs();
-        log::info!("TabProvider: Total tabs in manager: {}", all_tabs.len());
+        log::info!("TabProvider: Total tabs in manager: {}", self.tab_manager.snapshot().len());
         let results = self.tab_manager
             .search_tabs(query)
             .into_iter()
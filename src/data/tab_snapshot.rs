@@ -0,0 +1,132 @@
+use crate::core::{ChromeTab, TabManager};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// 一覧が変わったかを確認して保存する間隔
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 保存したタブ一覧と保存した時刻
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabSnapshot {
+    pub saved_at: DateTime<Utc>,
+    pub tabs: Vec<ChromeTab>,
+}
+
+/// 最後に分かっていたタブ一覧の保存先
+///
+/// 起動直後は拡張機能が接続して一覧を送ってくるまでタブを検索できないため、保存しておいた一覧を
+/// 古いもの（グレー表示）として先に使い、拡張機能から全体の一覧が届いたら置き換える。
+#[derive(Debug, Clone)]
+pub struct TabSnapshotStore {
+    path: PathBuf,
+}
+
+impl TabSnapshotStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Option<TabSnapshot>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    pub fn save(&self, tabs: &[ChromeTab]) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let snapshot = TabSnapshot {
+            saved_at: Utc::now(),
+            tabs: tabs.to_vec(),
+        };
+        fs::write(&self.path, serde_json::to_string(&snapshot)?)?;
+        Ok(())
+    }
+
+    /// 保存しておいた一覧を `TabManager` に読み込む（読み込んだタブの数を返す）
+    pub fn restore_into(&self, tab_manager: &TabManager) -> Result<usize, Box<dyn Error>> {
+        let Some(snapshot) = self.load()? else {
+            return Ok(0);
+        };
+        let age = (Utc::now() - snapshot.saved_at).to_std().unwrap_or(Duration::ZERO);
+        let count = snapshot.tabs.len();
+        tab_manager.restore_tabs(snapshot.tabs, Some(age));
+        Ok(count)
+    }
+}
+
+impl Default for TabSnapshotStore {
+    fn default() -> Self {
+        Self::new(crate::paths::tab_snapshot_path())
+    }
+}
+
+/// 拡張機能から届いた一覧が変わったら保存するスレッドを起動
+pub fn spawn_saver(tab_manager: Arc<TabManager>, store: TabSnapshotStore) -> std::io::Result<thread::JoinHandle<()>> {
+    let mut saved_revision = tab_manager.revision();
+    thread::Builder::new().name("tab-snapshot".to_string()).spawn(move || loop {
+        thread::sleep(SAVE_INTERVAL);
+        let revision = tab_manager.revision();
+        // 読み込んだスナップショットのままなら保存し直さない
+        if revision == saved_revision || tab_manager.is_restored() {
+            continue;
+        }
+        match store.save(&tab_manager.get_tabs()) {
+            Ok(()) => saved_revision = revision,
+            Err(e) => log::error!("Failed to save tab snapshot to {}: {}", store.path().display(), e),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::TabFreshness;
+
+    fn tab(id: i32) -> ChromeTab {
+        ChromeTab {
+            id,
+            window_id: 1,
+            title: format!("Tab {}", id),
+            url: format!("https://example.com/{}", id),
+            fav_icon_url: String::new(),
+            active: false,
+            index: id,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_restore_snapshot_until_live_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TabSnapshotStore::new(dir.path().join("tabs.json"));
+        let tab_manager = TabManager::new();
+        assert_eq!(store.restore_into(&tab_manager).unwrap(), 0);
+
+        store.save(&[tab(1), tab(2)]).unwrap();
+        assert_eq!(store.restore_into(&tab_manager).unwrap(), 2);
+        assert!(tab_manager.is_restored());
+        assert_eq!(tab_manager.freshness(), TabFreshness::Stale);
+        assert_eq!(tab_manager.get_tabs().len(), 2);
+
+        // 拡張機能から全体の一覧が届いたら置き換わる
+        tab_manager.update_tabs(vec![tab(3)]);
+        assert!(!tab_manager.is_restored());
+        assert_eq!(tab_manager.freshness(), TabFreshness::Fresh);
+        assert_eq!(tab_manager.get_tabs()[0].id, 3);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::core::window_fingerprint::WindowFingerprint;
+use crate::core::{Action, SearchResult};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 1件あたりに残す開いた時刻の数
+const MAX_OPENS: usize = 200;
+
+/// この日数より前に開いた記録は捨てる
+const RETENTION_DAYS: i64 = 60;
+
+/// 開いたもの（ウィンドウは起動し直しても変わる hwnd ではなく `WindowFingerprint`、ページは URL で区別する）
+///
+/// プロセス名だけを記録していた古い usage.json も、そのプロセスのウィンドウとして読み込む。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UsageTarget {
+    Window(WindowFingerprint),
+    Url { url: String },
+}
+
+/// 開いたものと、開いた時刻（UNIX時刻・秒）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageEntry {
+    pub target: UsageTarget,
+    /// 最後に開いたときのタイトル
+    pub title: String,
+    pub opens: Vec<i64>,
+}
+
+/// 結果を開いた記録（usage.json）
+///
+/// 時間帯ごとによく開くものを提案するために使う（`result_composer::time_of_day_score`）。
+#[derive(Debug, Clone)]
+pub struct UsageStore {
+    path: PathBuf,
+}
+
+impl UsageStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Vec<UsageEntry>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 開いたことを記録（古い記録はここで捨てる）
+    pub fn record(&self, target: UsageTarget, title: &str, at: DateTime<Local>) -> Result<(), Box<dyn Error>> {
+        let mut entries = self.load()?;
+        let cutoff = (at - Duration::days(RETENTION_DAYS)).timestamp();
+        match entries.iter_mut().find(|entry| entry.target == target) {
+            Some(entry) => {
+                entry.title = title.to_string();
+                entry.opens.push(at.timestamp());
+            }
+            None => entries.push(UsageEntry {
+                target,
+                title: title.to_string(),
+                opens: vec![at.timestamp()],
+            }),
+        }
+        for entry in &mut entries {
+            entry.opens.retain(|opened| *opened >= cutoff);
+            let excess = entry.opens.len().saturating_sub(MAX_OPENS);
+            entry.opens.drain(..excess);
+        }
+        entries.retain(|entry| !entry.opens.is_empty());
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string(&entries)?)?;
+        Ok(())
+    }
+}
+
+impl Default for UsageStore {
+    fn default() -> Self {
+        Self::new(crate::paths::usage_db_path())
+    }
+}
+
+/// 記録の対象になる結果なら、その対象
+pub fn target_for(result: &SearchResult) -> Option<UsageTarget> {
+    match &result.action {
+        Action::SwitchWindow(_) => result.window_info.as_ref().map(|window| UsageTarget::Window(WindowFingerprint::of(window))),
+        Action::OpenBookmark(url) | Action::OpenHistory(url) | Action::OpenUrl(url) => Some(UsageTarget::Url { url: url.clone() }),
+        Action::SwitchToTab { .. } => result.metadata.as_ref().map(|metadata| UsageTarget::Url {
+            url: metadata.url().to_string(),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_record_merges_and_expires() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = UsageStore::new(dir.path().join("usage.json"));
+        let target = UsageTarget::Url { url: "https://example.com/standup".to_string() };
+        let old = Local.with_ymd_and_hms(2024, 1, 1, 9, 45, 0).unwrap();
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 9, 45, 0).unwrap();
+
+        store.record(target.clone(), "Standup", old).unwrap();
+        let code = WindowFingerprint {
+            process_name: "Code.exe".to_string(),
+            process_path: None,
+            class_name: String::new(),
+            title_pattern: String::new(),
+        };
+        store.record(UsageTarget::Window(code), "Code", now).unwrap();
+        store.record(target.clone(), "Standup notes", now).unwrap();
+
+        let entries = store.load().unwrap();
+        assert_eq!(entries.len(), 2);
+        let standup = entries.iter().find(|entry| entry.target == target).unwrap();
+        assert_eq!(standup.title, "Standup notes");
+        assert_eq!(standup.opens, vec![now.timestamp()]);
+    }
+
+    #[test]
+    fn test_load_process_name_only_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = UsageStore::new(dir.path().join("usage.json"));
+        fs::write(store.path(), r#"[{"target":{"kind":"window","process_name":"Code.exe"},"title":"Code","opens":[1]}]"#).unwrap();
+
+        let entries = store.load().unwrap();
+        assert!(matches!(&entries[0].target, UsageTarget::Window(fingerprint) if fingerprint.process_name == "Code.exe"));
+    }
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "ui")]
 use crate::ui::alt_tab_grid::GridItem;
 use crate::filter::Searchable;
 
@@ -11,6 +12,8 @@ pub struct WindowItem {
     pub is_visible: bool,
     pub is_minimized: bool,
     pub rect: (i32, i32, i32, i32), // (x, y, width, height)
+    /// 昇格プロセスのウィンドウのため操作できない
+    pub requires_elevation: bool,
 }
 
 impl WindowItem {
@@ -28,6 +31,7 @@ impl WindowItem {
             is_visible: true,
             is_minimized: false,
             rect: (0, 0, 0, 0),
+            requires_elevation: false,
         }
     }
 
@@ -37,6 +41,7 @@ impl WindowItem {
     }
 }
 
+#[cfg(feature = "ui")]
 impl GridItem for WindowItem {
     fn title(&self) -> &str {
         &self.title
@@ -53,6 +58,14 @@ impl GridItem for WindowItem {
     fn id(&self) -> String {
         format!("window_{}", self.hwnd)
     }
+
+    fn badge(&self) -> Option<&str> {
+        if self.requires_elevation {
+            Some("🛡 Admin")
+        } else {
+            None
+        }
+    }
 }
 
 impl Searchable for WindowItem {
@@ -78,6 +91,7 @@ mod tests {
             is_visible: true,
             is_minimized: false,
             rect: (100, 200, 800, 600),
+            requires_elevation: false,
         }
     }
     
@@ -120,6 +134,7 @@ mod tests {
         assert!(!window.is_valid());
     }
     
+    #[cfg(feature = "ui")]
     #[test]
     fn test_grid_item_implementation() {
         let window = create_test_window();
@@ -128,6 +143,16 @@ mod tests {
         assert_eq!(window.description(), "notepad.exe");
         assert_eq!(window.hwnd(), 12345);
         assert_eq!(window.id(), "window_12345");
+        assert_eq!(window.badge(), None);
+    }
+    
+    #[cfg(feature = "ui")]
+    #[test]
+    fn test_badge_for_elevated_window() {
+        let mut window = create_test_window();
+        window.requires_elevation = true;
+        
+        assert_eq!(window.badge(), Some("🛡 Admin"));
     }
     
     #[test]
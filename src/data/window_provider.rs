@@ -1,5 +1,7 @@
 use super::window_item::WindowItem;
 use crate::core::WindowInfo;
+#[cfg(windows)]
+use crate::core::window_enumerator::WindowEnumerator;
 
 /// ウィンドウ情報を提供するトレイト
 pub trait WindowProvider {
@@ -16,12 +18,17 @@ pub trait WindowProvider {
 /// Windows API を使用したウィンドウプロバイダー
 pub struct WindowsApiProvider {
     cached_windows: Vec<WindowItem>,
+    /// 応答しないプロセスがあっても UI を止めないためのワーカー
+    #[cfg(windows)]
+    enumerator: WindowEnumerator,
 }
 
 impl WindowsApiProvider {
     pub fn new() -> Self {
         Self {
             cached_windows: Vec::new(),
+            #[cfg(windows)]
+            enumerator: WindowEnumerator::default(),
         }
     }
 
@@ -35,6 +42,7 @@ impl WindowsApiProvider {
             is_visible: info.is_visible,
             is_minimized: info.is_minimized,
             rect: info.rect,
+            requires_elevation: info.requires_elevation,
         }
     }
 }
@@ -47,7 +55,7 @@ impl WindowProvider for WindowsApiProvider {
     fn refresh(&mut self) {
         #[cfg(windows)]
         {
-            let windows = crate::windows_api::enumerate_windows();
+            let windows = self.enumerator.enumerate();
             self.cached_windows = windows
                 .into_iter()
                 .map(|w| self.convert_window_info(&w))
@@ -164,6 +172,7 @@ mod tests {
             is_visible: true,
             is_minimized: false,
             rect: (10, 20, 300, 400),
+            requires_elevation: false,
         };
         
         let item = provider.convert_window_info(&info);
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// タグを付ける対象
+///
+/// ウィンドウの hwnd は起動し直すと変わるため、プロセス名とタイトルの一部（大文字・小文字を区別しない）で
+/// 対象を決める。ブラウザのプロファイルは `ブラウザ - プロファイル` の表示名で決める。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TagTarget {
+    /// `title` が `None` ならそのプロセスのすべてのウィンドウ
+    Window {
+        process_name: String,
+        #[serde(default)]
+        title: Option<String>,
+    },
+    Profile { profile: String },
+}
+
+impl TagTarget {
+    pub fn matches_window(&self, process_name: &str, title: &str) -> bool {
+        match self {
+            TagTarget::Window { process_name: pattern, title: title_pattern } => {
+                pattern.eq_ignore_ascii_case(process_name)
+                    && title_pattern
+                        .as_ref()
+                        .is_none_or(|pattern| title.to_lowercase().contains(&pattern.to_lowercase()))
+            }
+            TagTarget::Profile { .. } => false,
+        }
+    }
+
+    pub fn matches_profile(&self, profile: &str) -> bool {
+        matches!(self, TagTarget::Profile { profile: tagged } if tagged == profile)
+    }
+
+    /// 表示用の説明（例: `Code.exe "my-launcher"` / `Chrome - Work`）
+    pub fn label(&self) -> String {
+        match self {
+            TagTarget::Window { process_name, title: Some(title) } => format!("{} \"{}\"", process_name, title),
+            TagTarget::Window { process_name, title: None } => process_name.clone(),
+            TagTarget::Profile { profile } => profile.clone(),
+        }
+    }
+}
+
+/// ワークスペースのタグ（例: `project-A`）を対象に付けるルール
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagRule {
+    pub tag: String,
+    #[serde(flatten)]
+    pub target: TagTarget,
+}
+
+/// タグのルールと、選んでいるワークスペース
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Workspaces {
+    /// 絞り込みに使うワークスペース（`None` ならすべて表示）
+    #[serde(default)]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<TagRule>,
+}
+
+impl Workspaces {
+    /// ルールに使われているタグ（名前順、重複なし）
+    pub fn tags(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .map(|rule| rule.tag.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// ワークスペースのタグの保存先（workspaces.json）
+///
+/// ランチャーは結果を開くたびに閉じるため、選んでいるワークスペースも一緒に保存して次の起動に引き継ぐ。
+#[derive(Debug, Clone)]
+pub struct WorkspaceStore {
+    path: PathBuf,
+}
+
+impl WorkspaceStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Workspaces, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(Workspaces::default());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, workspaces: &Workspaces) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(workspaces)?)?;
+        Ok(())
+    }
+
+    /// ルールを追加（同じルールがあれば何もしない）
+    pub fn assign(&self, rule: TagRule) -> Result<(), Box<dyn Error>> {
+        let mut workspaces = self.load()?;
+        if !workspaces.rules.contains(&rule) {
+            workspaces.rules.push(rule);
+            self.save(&workspaces)?;
+        }
+        Ok(())
+    }
+
+    /// ルールを削除（そのタグのルールがなくなったら、選んでいるワークスペースも解除する）
+    pub fn remove(&self, rule: &TagRule) -> Result<(), Box<dyn Error>> {
+        let mut workspaces = self.load()?;
+        workspaces.rules.retain(|r| r != rule);
+        if workspaces.active.as_ref().is_some_and(|active| !workspaces.rules.iter().any(|r| &r.tag == active)) {
+            workspaces.active = None;
+        }
+        self.save(&workspaces)
+    }
+
+    pub fn set_active(&self, active: Option<String>) -> Result<(), Box<dyn Error>> {
+        let mut workspaces = self.load()?;
+        workspaces.active = active;
+        self.save(&workspaces)
+    }
+}
+
+impl Default for WorkspaceStore {
+    fn default() -> Self {
+        Self::new(crate::paths::workspaces_path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(tag: &str, target: TagTarget) -> TagRule {
+        TagRule { tag: tag.to_string(), target }
+    }
+
+    #[test]
+    fn test_assign_and_remove_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = WorkspaceStore::new(dir.path().join("workspaces.json"));
+        let code = rule("project-A", TagTarget::Window { process_name: "Code.exe".to_string(), title: Some("my-launcher".to_string()) });
+        let work = rule("project-A", TagTarget::Profile { profile: "Chrome - Work".to_string() });
+        let slack = rule("personal", TagTarget::Window { process_name: "slack.exe".to_string(), title: None });
+
+        store.assign(code.clone()).unwrap();
+        store.assign(code.clone()).unwrap();
+        store.assign(work.clone()).unwrap();
+        store.assign(slack).unwrap();
+        store.set_active(Some("project-A".to_string())).unwrap();
+
+        let workspaces = store.load().unwrap();
+        assert_eq!(workspaces.rules.len(), 3);
+        assert_eq!(workspaces.tags(), vec!["personal", "project-A"]);
+        assert!(code.target.matches_window("code.exe", "main.rs - My-Launcher - Visual Studio Code"));
+        assert!(!code.target.matches_window("Code.exe", "other - Visual Studio Code"));
+        assert!(work.target.matches_profile("Chrome - Work"));
+
+        // タグのルールがなくなったら、選んでいるワークスペースも解除される
+        store.remove(&code).unwrap();
+        assert_eq!(store.load().unwrap().active.as_deref(), Some("project-A"));
+        store.remove(&work).unwrap();
+        assert_eq!(store.load().unwrap().active, None);
+    }
+}
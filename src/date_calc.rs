@@ -0,0 +1,351 @@
+//! 日時とタイムゾーンの計算（`now in UTC` / `3pm JST in PST` / `epoch 1712345678` / `days until 2025-12-01`）
+//!
+//! クエリ全体がいずれかの形式なら計算結果を表示する。Enter でその表記をコピーする。
+//! タイムゾーンは略称（`JST` / `PST` など）か IANA 名（`Asia/Tokyo`）で指定し、夏時間は chrono-tz で解決する。
+
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::error::Error;
+
+/// 表示用の日時の書式
+const DISPLAY_FORMAT: &str = "%a %Y-%m-%d %H:%M %Z";
+
+/// ISO 8601 の書式
+const ISO_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%:z";
+
+/// よく使われる略称と代表的なタイムゾーンの対応（夏時間の略称も同じ地域に寄せる）
+const ZONE_ABBREVIATIONS: &[(&str, Tz)] = &[
+    ("utc", Tz::UTC),
+    ("gmt", Tz::UTC),
+    ("z", Tz::UTC),
+    ("jst", Tz::Asia__Tokyo),
+    ("kst", Tz::Asia__Seoul),
+    ("hkt", Tz::Asia__Hong_Kong),
+    ("sgt", Tz::Asia__Singapore),
+    ("ist", Tz::Asia__Kolkata),
+    ("aest", Tz::Australia__Sydney),
+    ("aedt", Tz::Australia__Sydney),
+    ("nzst", Tz::Pacific__Auckland),
+    ("nzdt", Tz::Pacific__Auckland),
+    ("cet", Tz::Europe__Paris),
+    ("cest", Tz::Europe__Paris),
+    ("bst", Tz::Europe__London),
+    ("uk", Tz::Europe__London),
+    ("et", Tz::America__New_York),
+    ("est", Tz::America__New_York),
+    ("edt", Tz::America__New_York),
+    ("ct", Tz::America__Chicago),
+    ("cst", Tz::America__Chicago),
+    ("cdt", Tz::America__Chicago),
+    ("mt", Tz::America__Denver),
+    ("mst", Tz::America__Denver),
+    ("mdt", Tz::America__Denver),
+    ("pt", Tz::America__Los_Angeles),
+    ("pst", Tz::America__Los_Angeles),
+    ("pdt", Tz::America__Los_Angeles),
+];
+
+/// 計算に使うタイムゾーン
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zone {
+    /// PC のローカルタイムゾーン
+    Local,
+    Named(Tz),
+}
+
+impl Zone {
+    fn format(&self, instant: DateTime<Utc>, format: &str) -> String {
+        match self {
+            Zone::Local => instant.with_timezone(&Local).format(format).to_string(),
+            Zone::Named(tz) => instant.with_timezone(tz).format(format).to_string(),
+        }
+    }
+
+    fn date_of(&self, instant: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Zone::Local => instant.with_timezone(&Local).date_naive(),
+            Zone::Named(tz) => instant.with_timezone(tz).date_naive(),
+        }
+    }
+
+    /// このタイムゾーンでの日時を UTC に変換（夏時間の切り替えで存在しない時刻なら None）
+    fn resolve(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Local => Local.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+            Zone::Named(tz) => tz.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+}
+
+/// `JST` / `pst` / `Asia/Tokyo` / `local` を解釈
+pub fn parse_zone(text: &str) -> Option<Zone> {
+    let lower = text.trim().to_lowercase();
+    if lower == "local" {
+        return Some(Zone::Local);
+    }
+    if let Some((_, tz)) = ZONE_ABBREVIATIONS.iter().find(|(abbr, _)| *abbr == lower) {
+        return Some(Zone::Named(*tz));
+    }
+    // IANA 名は大文字小文字を区別するため元の表記で解釈する
+    text.trim().parse::<Tz>().ok().map(Zone::Named)
+}
+
+/// `3pm` / `3:30pm` / `15:00` を解釈
+pub fn parse_time_of_day(text: &str) -> Option<NaiveTime> {
+    let lower = text.trim().to_lowercase();
+    let (clock, meridiem) = if let Some(clock) = lower.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = lower.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (lower.as_str(), None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        // 午前・午後の指定がない数字だけの表記は時刻とみなさない
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// タイムゾーン変換の解析結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub instant: DateTime<Utc>,
+    pub from: Zone,
+    pub to: Zone,
+}
+
+/// `now in UTC` / `3pm JST in PST` / `15:00 to Europe/London` を解析（元のタイムゾーンを省略するとローカル）
+pub fn parse_conversion(query: &str, now: DateTime<Utc>) -> Option<Conversion> {
+    let (source, target) = query.rsplit_once(" in ").or_else(|| query.rsplit_once(" to "))?;
+    let to = parse_zone(target)?;
+
+    let source = source.trim();
+    let (time, zone) = source.split_once(' ').unwrap_or((source, ""));
+    let from = if zone.trim().is_empty() { Zone::Local } else { parse_zone(zone)? };
+
+    let instant = if time.eq_ignore_ascii_case("now") {
+        now
+    } else {
+        // 元のタイムゾーンでの今日の時刻として扱う
+        let time = parse_time_of_day(time)?;
+        from.resolve(from.date_of(now).and_time(time))?
+    };
+
+    Some(Conversion { instant, from, to })
+}
+
+/// `epoch 1712345678` の引数（桁数からミリ秒も判別する）
+pub fn parse_epoch(text: &str) -> Option<DateTime<Utc>> {
+    let value: i64 = text.trim().parse().ok()?;
+    if value.abs() >= 100_000_000_000 {
+        Utc.timestamp_millis_opt(value).single()
+    } else {
+        Utc.timestamp_opt(value, 0).single()
+    }
+}
+
+/// `2025-12-01` / `2025/12/01` を解釈
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(text, "%Y/%m/%d"))
+        .ok()
+}
+
+/// `days until 2025-12-01` / `days since 2024-01-01` の日数
+pub fn days_between(args: &str, today: NaiveDate) -> Option<(i64, NaiveDate)> {
+    let args = args.trim();
+    let (direction, date) = args.split_once(' ')?;
+    let date = parse_date(date)?;
+    match direction {
+        "until" => Some(((date - today).num_days(), date)),
+        "since" => Some(((today - date).num_days(), date)),
+        _ => None,
+    }
+}
+
+fn copy_result(text: String, description: String) -> SearchResult {
+    SearchResult {
+        title: text.clone(),
+        description: format!("{} · Enter to copy", description),
+        action: Action::CopyToClipboard(text),
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+fn zone_label(zone: Zone) -> String {
+    match zone {
+        Zone::Local => "local time".to_string(),
+        Zone::Named(tz) => tz.name().to_string(),
+    }
+}
+
+pub fn conversion_results(conversion: &Conversion) -> Vec<SearchResult> {
+    let source = conversion.from.format(conversion.instant, DISPLAY_FORMAT);
+    let target = zone_label(conversion.to);
+    vec![
+        copy_result(conversion.to.format(conversion.instant, DISPLAY_FORMAT), format!("{} → {}", source, target)),
+        copy_result(conversion.to.format(conversion.instant, ISO_FORMAT), format!("ISO 8601 in {}", target)),
+    ]
+}
+
+pub fn epoch_results(args: &str, now: DateTime<Utc>) -> Vec<SearchResult> {
+    if args.trim().is_empty() {
+        return vec![
+            copy_result(now.timestamp().to_string(), "Current Unix time (seconds)".to_string()),
+            copy_result(now.timestamp_millis().to_string(), "Current Unix time (milliseconds)".to_string()),
+        ];
+    }
+
+    let Some(instant) = parse_epoch(args) else {
+        return Vec::new();
+    };
+    vec![
+        copy_result(Zone::Local.format(instant, DISPLAY_FORMAT), "Local time".to_string()),
+        copy_result(Zone::Named(Tz::UTC).format(instant, DISPLAY_FORMAT), "UTC".to_string()),
+        copy_result(instant.format(ISO_FORMAT).to_string(), "ISO 8601 (UTC)".to_string()),
+    ]
+}
+
+pub fn days_results(args: &str, today: NaiveDate) -> Option<Vec<SearchResult>> {
+    let (days, date) = days_between(args, today)?;
+    let date = date.format("%a %Y-%m-%d");
+    let description = if args.trim_start().starts_with("until") {
+        if days >= 0 {
+            format!("Days until {}", date)
+        } else {
+            format!("{} has already passed", date)
+        }
+    } else {
+        format!("Days since {}", date)
+    };
+    Some(vec![copy_result(days.to_string(), description)])
+}
+
+/// 日時とタイムゾーンの計算結果を返すプロバイダー
+pub struct DateTimeProvider;
+
+impl KeywordProvider for DateTimeProvider {
+    fn name(&self) -> &'static str {
+        "datetime"
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        // `days` などはブックマークの検索と重なるため、クエリ全体が一致したときだけ結果を出す
+        &[]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("now in <zone>", "Current time in a time zone, e.g. now in UTC"),
+            KeywordHelp::new("<time> <zone> in <zone>", "Convert a time, e.g. 3pm JST in PST"),
+            KeywordHelp::new("epoch <seconds>", "Convert a Unix timestamp"),
+            KeywordHelp::new("days until <date>", "Count days, e.g. days until 2025-12-01"),
+        ];
+        HELP
+    }
+
+    fn search(&self, _keyword: &str, _args: &str) -> Vec<SearchResult> {
+        Vec::new()
+    }
+
+    fn search_query(&self, query: &str) -> Option<Vec<SearchResult>> {
+        let query = query.trim();
+        let now = Utc::now();
+
+        if query == "epoch" {
+            return Some(epoch_results("", now));
+        }
+        if let Some(args) = query.strip_prefix("epoch ") {
+            return parse_epoch(args).map(|_| epoch_results(args, now));
+        }
+        if let Some(args) = query.strip_prefix("days ") {
+            return days_results(args, Local::now().date_naive());
+        }
+        parse_conversion(query, now).map(|conversion| conversion_results(&conversion))
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        Err(format!("unknown datetime command: {}", command).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 4, 5, 1, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_zone() {
+        assert_eq!(parse_zone("JST"), Some(Zone::Named(Tz::Asia__Tokyo)));
+        assert_eq!(parse_zone("pdt"), Some(Zone::Named(Tz::America__Los_Angeles)));
+        assert_eq!(parse_zone("Europe/London"), Some(Zone::Named(Tz::Europe__London)));
+        assert_eq!(parse_zone("local"), Some(Zone::Local));
+        assert_eq!(parse_zone("github"), None);
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(parse_time_of_day("3pm"), NaiveTime::from_hms_opt(15, 0, 0));
+        assert_eq!(parse_time_of_day("12am"), NaiveTime::from_hms_opt(0, 0, 0));
+        assert_eq!(parse_time_of_day("9:45AM"), NaiveTime::from_hms_opt(9, 45, 0));
+        assert_eq!(parse_time_of_day("15:00"), NaiveTime::from_hms_opt(15, 0, 0));
+        assert_eq!(parse_time_of_day("15"), None);
+        assert_eq!(parse_time_of_day("13pm"), None);
+    }
+
+    #[test]
+    fn test_convert_between_zones() {
+        let conversion = parse_conversion("3pm JST in PST", fixed_now()).unwrap();
+        assert_eq!(conversion.instant, Utc.with_ymd_and_hms(2024, 4, 5, 6, 0, 0).unwrap());
+        // 4月のロサンゼルスは夏時間
+        let results = conversion_results(&conversion);
+        assert_eq!(results[0].title, "Thu 2024-04-04 23:00 PDT");
+        assert_eq!(results[1].action, Action::CopyToClipboard("2024-04-04T23:00:00-07:00".to_string()));
+
+        let now = parse_conversion("now in UTC", fixed_now()).unwrap();
+        assert_eq!(now.instant, fixed_now());
+        assert!(parse_conversion("go to ET", fixed_now()).is_none());
+        assert!(parse_conversion("3pm JST in nowhere", fixed_now()).is_none());
+    }
+
+    #[test]
+    fn test_epoch() {
+        let expected = Utc.with_ymd_and_hms(2024, 4, 5, 19, 34, 38).unwrap();
+        assert_eq!(parse_epoch("1712345678"), Some(expected));
+        assert_eq!(parse_epoch("1712345678000"), Some(expected));
+        assert_eq!(parse_epoch("soon"), None);
+
+        let results = epoch_results("1712345678", fixed_now());
+        assert_eq!(results[1].title, "Fri 2024-04-05 19:34 UTC");
+        assert_eq!(epoch_results("", fixed_now())[0].title, "1712280600");
+    }
+
+    #[test]
+    fn test_days_between() {
+        let today = NaiveDate::from_ymd_opt(2025, 10, 16).unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+        assert_eq!(days_between("until 2025-12-01", today), Some((46, date)));
+        assert_eq!(days_between("since 2025/12/01", today), Some((-46, date)));
+        assert_eq!(days_between("of thunder", today), None);
+        assert_eq!(days_results("until 2025-12-01", today).unwrap()[0].title, "46");
+    }
+}
@@ -0,0 +1,125 @@
+//! `mylauncher://` のリンク（他のアプリやウェブページからランチャーを開く）
+//!
+//! - `mylauncher://search?q=foo&mode=browser`: 入力済みのランチャーを開く（`mode` は省略時 browser）。
+//!   ウェブページからシェルのコマンドを入力させないよう、general モードと `>` で始まる検索語は受け付けない
+//! - `mylauncher://open?url=https://...`: URL を既定のブラウザで開く（http / https のみ）
+//!
+//! `my-launcher register-protocol` で現在のユーザーに登録し、リンクは `--url <link>` で渡される。
+
+use crate::core::search_engine::COMMAND_PREFIX;
+use crate::core::SearchMode;
+use std::collections::HashMap;
+use std::error::Error;
+
+pub const SCHEME: &str = "mylauncher";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    Search { query: String, mode: SearchMode },
+    Open { url: String },
+}
+
+/// リンクを解析する
+pub fn parse(link: &str) -> Result<DeepLink, String> {
+    let url = url::Url::parse(link).map_err(|e| format!("invalid link '{}': {}", link, e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("not a {}:// link: {}", SCHEME, link));
+    }
+    // `mylauncher://search?...` ではホスト、`mylauncher:search?...` ではパスが操作の名前になる
+    let action = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| url.path())
+        .trim_matches('/')
+        .to_lowercase();
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+    match action.as_str() {
+        "search" => {
+            let mode = match params.get("mode") {
+                Some(name) => SearchMode::from_name(name).ok_or_else(|| format!("unknown mode '{}'", name))?,
+                None => SearchMode::Browser,
+            };
+            // Enter 1回でリンクに書かれたコマンドが実行されないようにする
+            if mode == SearchMode::General {
+                return Err("the general mode cannot be opened from a link".to_string());
+            }
+            let query = params.get("q").cloned().unwrap_or_default();
+            if query.trim_start().starts_with(COMMAND_PREFIX) {
+                return Err(format!("commands ('{}') cannot be typed from a link", COMMAND_PREFIX));
+            }
+            Ok(DeepLink::Search { query, mode })
+        }
+        "open" => {
+            let target = params.get("url").ok_or("open needs a url parameter")?;
+            let target = url::Url::parse(target).map_err(|e| format!("invalid url '{}': {}", target, e))?;
+            // ウェブページから任意のファイルやアプリを開かせないようにする
+            if !matches!(target.scheme(), "http" | "https") {
+                return Err(format!("only http and https URLs can be opened, not {}", target.scheme()));
+            }
+            Ok(DeepLink::Open { url: target.to_string() })
+        }
+        other => Err(format!("unknown action '{}'", other)),
+    }
+}
+
+/// 現在のユーザーに `mylauncher://` を登録する（HKCU\Software\Classes）
+#[cfg(windows)]
+pub fn register() -> Result<(), Box<dyn Error>> {
+    use crate::system::run_command;
+
+    let exe = std::env::current_exe()?;
+    let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+    let command = format!("\"{}\" --url \"%1\"", exe.display());
+    run_command("reg", &["add", &key, "/ve", "/d", "URL:My Launcher", "/f"])?;
+    run_command("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?;
+    run_command("reg", &["add", &format!(r"{}\shell\open\command", key), "/ve", "/d", &command, "/f"])?;
+    Ok(())
+}
+
+/// 登録した `mylauncher://` を削除する
+#[cfg(windows)]
+pub fn unregister() -> Result<(), Box<dyn Error>> {
+    crate::system::run_command("reg", &["delete", &format!(r"HKCU\Software\Classes\{}", SCHEME), "/f"])?;
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn register() -> Result<(), Box<dyn Error>> {
+    Err("URL scheme registration is only supported on Windows".into())
+}
+
+#[cfg(not(windows))]
+pub fn unregister() -> Result<(), Box<dyn Error>> {
+    Err("URL scheme registration is only supported on Windows".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("mylauncher://search?q=rust%20docs&mode=tabs"),
+            Ok(DeepLink::Search { query: "rust docs".to_string(), mode: SearchMode::Tabs })
+        );
+        // Windows は末尾に / を付けて渡すことがある
+        assert_eq!(
+            parse("mylauncher://search/?q=foo"),
+            Ok(DeepLink::Search { query: "foo".to_string(), mode: SearchMode::Browser })
+        );
+        assert_eq!(
+            parse("mylauncher://open?url=https%3A%2F%2Fexample.com%2Fa%3Fb%3D1"),
+            Ok(DeepLink::Open { url: "https://example.com/a?b=1".to_string() })
+        );
+
+        assert!(parse("mylauncher://open?url=file:///C:/Windows/System32/calc.exe").is_err());
+        assert!(parse("mylauncher://open").is_err());
+        assert!(parse("mylauncher://search?q=x&mode=grid").is_err());
+        assert!(parse("mylauncher://search?q=foo&mode=general").is_err());
+        assert!(parse("mylauncher://search?q=%20%3Edel%20%2Fq%20file&mode=browser").is_err());
+        assert!(parse("mylauncher://delete").is_err());
+        assert!(parse("https://example.com").is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! データディレクトリの `cache/` に保存する画像のキャッシュ
+//!
+//! 画像は内容のハッシュを名前にして保存し（`<hash>.img`、同じ画像は1つだけ）、キー（画像を取得したページ・
+//! ファビコンの URL）のハッシュの `<hash>.key` にどの画像かを書いておく。画像の合計サイズが上限を超えたら
+//! 更新日時の古い画像から消す。上限より大きい画像は保存しない。プレビュー画像とファビコンが同じ仕組みを
+//! 別のディレクトリで使い、「Clear caches」コマンドで使用量を表示して消せる。
+
+use crate::config::PreviewsConfig;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// ファビコンのキャッシュの合計サイズの上限
+const FAVICON_CACHE_BYTES: u64 = 2 * 1024 * 1024;
+
+const IMAGE_EXTENSION: &str = "img";
+const KEY_EXTENSION: &str = "key";
+
+/// FNV-1a（64 ビット）
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// キャッシュの使用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheUsage {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl DiskCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    /// よく開くブックマークのプレビュー画像（cache/previews、上限は設定の `max_cache_mb`）
+    pub fn previews(config: &PreviewsConfig) -> Self {
+        Self::new(crate::paths::preview_cache_dir(), config.max_cache_mb * 1024 * 1024)
+    }
+
+    /// グリッドに重ねるファビコン（cache/favicons）
+    pub fn favicons() -> Self {
+        Self::new(crate::paths::favicon_cache_dir(), FAVICON_CACHE_BYTES)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// キーが指す画像の名前を書いておくファイル
+    fn key_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.{}", fnv1a(key.as_bytes()), KEY_EXTENSION))
+    }
+
+    /// キーが指す画像のファイル（画像が消されていれば None）
+    pub fn path_for(&self, key: &str) -> Option<PathBuf> {
+        let name = fs::read_to_string(self.key_path(key)).ok()?;
+        let path = self.dir.join(name.trim());
+        path.exists().then_some(path)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.path_for(key).is_some()
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.path_for(key)?).ok()
+    }
+
+    /// 保存し、上限を超えていれば古いものから消す（上限より大きい画像は保存しない）
+    pub fn put(&self, key: &str, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        if bytes.len() as u64 > self.max_bytes {
+            log::debug!("Not caching {} ({}): larger than the cache", key, format_size(bytes.len() as u64));
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir)?;
+        let name = format!("{:016x}.{}", fnv1a(bytes), IMAGE_EXTENSION);
+        // 同じ内容でも書き直して更新日時を新しくする（古いものから消すので）
+        fs::write(self.dir.join(&name), bytes)?;
+        fs::write(self.key_path(key), name)?;
+        self.prune()
+    }
+
+    pub fn usage(&self) -> CacheUsage {
+        self.files().iter().fold(CacheUsage::default(), |usage, (_, len, _)| CacheUsage { files: usage.files + 1, bytes: usage.bytes + len })
+    }
+
+    /// すべて消して、消したバイト数を返す
+    pub fn clear(&self) -> Result<u64, Box<dyn Error>> {
+        let mut freed = 0;
+        for (_, len, path) in self.files() {
+            fs::remove_file(&path)?;
+            freed += len;
+        }
+        Ok(freed)
+    }
+
+    /// 画像の合計が上限以下になるまで更新日時の古いものから消し、消えた画像を指すキーも消す
+    fn prune(&self) -> Result<(), Box<dyn Error>> {
+        let (mut images, keys): (Vec<_>, Vec<_>) = self
+            .files()
+            .into_iter()
+            .partition(|(_, _, path)| path.extension().is_some_and(|extension| extension == IMAGE_EXTENSION));
+        images.sort();
+
+        let mut total: u64 = images.iter().map(|(_, len, _)| len).sum();
+        let mut removed = false;
+        for (_, len, path) in images {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= len;
+            removed = true;
+        }
+
+        if removed {
+            for (_, _, path) in keys {
+                let target = fs::read_to_string(&path).map(|name| self.dir.join(name.trim())).ok();
+                if !target.is_some_and(|target| target.exists()) {
+                    fs::remove_file(&path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// キャッシュのファイル（更新日時, サイズ, パス）。ディレクトリがなければ空
+    fn files(&self) -> Vec<(std::time::SystemTime, u64, PathBuf)> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let metadata = entry.metadata().ok()?;
+                if !metadata.is_file() {
+                    return None;
+                }
+                Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+            })
+            .collect()
+    }
+}
+
+/// ランチャーのキャッシュ（表示名, キャッシュ）
+pub fn launcher_caches(previews: &PreviewsConfig) -> Vec<(&'static str, DiskCache)> {
+    vec![("previews", DiskCache::previews(previews)), ("favicons", DiskCache::favicons())]
+}
+
+/// `1.5 MB` のような表示
+pub fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    match bytes {
+        b if b >= MB => format!("{:.1} MB", b as f64 / MB as f64),
+        b if b >= KB => format!("{} KB", b / KB),
+        b => format!("{} B", b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_put_prunes_and_clear_reports_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("favicons"), 10);
+        assert_eq!(cache.usage(), CacheUsage::default());
+
+        cache.put("https://example.com/a.ico", &[0u8; 6]).unwrap();
+        assert_eq!(cache.get("https://example.com/a.ico").map(|bytes| bytes.len()), Some(6));
+
+        // 上限を超えたら古いものから消す
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put("https://example.com/b.ico", &[1u8; 6]).unwrap();
+        assert!(!cache.contains("https://example.com/a.ico"));
+        // 残った画像と、それを指すキー（画像のファイル名）
+        assert_eq!(cache.usage(), CacheUsage { files: 2, bytes: 6 + 20 });
+
+        assert_eq!(cache.clear().unwrap(), 26);
+        assert_eq!(cache.usage().files, 0);
+    }
+
+    #[test]
+    fn test_same_image_is_stored_once_and_large_images_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("favicons"), 10);
+
+        cache.put("https://example.com/a", &[1u8; 4]).unwrap();
+        cache.put("https://example.com/b", &[1u8; 4]).unwrap();
+        assert_eq!(cache.path_for("https://example.com/a"), cache.path_for("https://example.com/b"));
+        let images = fs::read_dir(cache.dir()).unwrap().filter(|entry| entry.as_ref().unwrap().path().extension().unwrap() == "img").count();
+        assert_eq!(images, 1);
+
+        // 上限より大きい画像は保存せず、今ある画像も追い出さない
+        cache.put("https://example.com/large", &[2u8; 11]).unwrap();
+        assert!(!cache.contains("https://example.com/large"));
+        assert_eq!(cache.get("https://example.com/a"), Some(vec![1u8; 4]));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 / 2), "1.5 MB");
+    }
+}
@@ -0,0 +1,133 @@
+//! dmenu / rofi 互換モード（`--dmenu`）
+//!
+//! 標準入力の行を候補としてランチャーに表示し、選んだ行を標準出力に書いて終了する。
+//! 選んだ結果は `execute_action` を通さず、`LauncherApp` がそのまま返す。
+
+use crate::core::keyword_provider::KeywordProvider;
+use crate::core::{Action, BrowserSearchEngine, ResultType, SearchResult, TabManager};
+use std::error::Error;
+use std::io::BufRead;
+use std::sync::Arc;
+
+/// 標準入力の行を返すプロバイダーの名前（結果の `Action::ProviderCommand` の `provider`）
+pub const STDIN_PROVIDER: &str = "stdin";
+
+/// 候補の行を読み込む（空行は除く。UTF-8 でない部分は置き換える）
+pub fn read_lines(reader: impl BufRead) -> Vec<String> {
+    reader
+        .split(b'\n')
+        .map_while(Result::ok)
+        .map(|bytes| String::from_utf8_lossy(&bytes).trim_end_matches('\r').to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// 標準入力から読み込んだ行を、クエリの単語をすべて含むものに絞り込む（大文字小文字は区別しない）
+///
+/// 一致する行がなければ、入力した文字列そのものを候補にする（dmenu と同じく任意の入力を返せる）。
+pub struct StdinProvider {
+    lines: Vec<String>,
+}
+
+impl StdinProvider {
+    pub fn new(lines: Vec<String>) -> Self {
+        Self { lines }
+    }
+
+    fn result(line: &str, description: &str) -> SearchResult {
+        SearchResult {
+            title: line.to_string(),
+            description: description.to_string(),
+            action: Action::ProviderCommand { provider: STDIN_PROVIDER.to_string(), command: line.to_string() },
+            window_info: None,
+            result_type: ResultType::Command,
+            group: None,
+            metadata: None,
+        }
+    }
+}
+
+impl KeywordProvider for StdinProvider {
+    fn name(&self) -> &'static str {
+        STDIN_PROVIDER
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &[]
+    }
+
+    fn search(&self, _keyword: &str, _args: &str) -> Vec<SearchResult> {
+        Vec::new()
+    }
+
+    fn search_query(&self, query: &str) -> Option<Vec<SearchResult>> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let results: Vec<SearchResult> = self
+            .lines
+            .iter()
+            .filter(|line| {
+                let line = line.to_lowercase();
+                terms.iter().all(|term| line.contains(term))
+            })
+            .map(|line| Self::result(line, ""))
+            .collect();
+        if results.is_empty() && !query.trim().is_empty() {
+            return Some(vec![Self::result(query.trim(), "No match · Enter to return the typed text")]);
+        }
+        Some(results)
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        Ok(command.to_string())
+    }
+}
+
+/// 標準入力の行だけを検索する検索エンジン（組み込みのキーワードと他の提供元は無効にする）
+pub fn search_engine(lines: Vec<String>) -> BrowserSearchEngine {
+    let mut engine = BrowserSearchEngine::new_with_tab_manager(Arc::new(TabManager::new()));
+    engine.register_provider(Box::new(StdinProvider::new(lines)));
+    let others: Vec<String> = engine
+        .providers()
+        .providers()
+        .into_iter()
+        .map(|info| info.name.to_string())
+        .filter(|name| name != STDIN_PROVIDER)
+        .collect();
+    engine.providers().set_disabled(&others);
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{SearchEngine, SearchMode};
+
+    #[test]
+    fn test_read_lines() {
+        let input = "firefox\r\n\nchrome\nwezterm";
+        assert_eq!(read_lines(input.as_bytes()), vec!["firefox", "chrome", "wezterm"]);
+    }
+
+    #[test]
+    fn test_search_engine_lists_only_stdin_lines() {
+        let engine = search_engine(vec!["Open Terminal".to_string(), "open browser".to_string(), "Lock screen".to_string()]);
+        let titles = |query: &str| {
+            engine
+                .search(query, SearchMode::Browser, &[])
+                .into_iter()
+                .map(|result| result.title)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles("").len(), 3);
+        assert_eq!(titles("OPEN term"), vec!["Open Terminal"]);
+        // 組み込みのキーワードは使わない
+        assert_eq!(titles("nt rust"), vec!["nt rust"]);
+
+        let results = engine.search("lock", SearchMode::Browser, &[]);
+        assert_eq!(
+            results[0].action,
+            Action::ProviderCommand { provider: STDIN_PROVIDER.to_string(), command: "Lock screen".to_string() }
+        );
+    }
+}
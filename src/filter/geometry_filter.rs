@@ -0,0 +1,166 @@
+use crate::core::WindowInfo;
+use crate::data::window_item::WindowItem;
+use crate::monitor::MonitorInfo;
+use super::window_filter::WindowFilter;
+
+/// 位置で絞り込む条件（Windows モードのクエリの `on:2` / `min:` / `left:` など）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeometryCondition {
+    /// 列挙順で 1 から数えたモニターにある
+    Monitor(usize),
+    /// プライマリモニターにある
+    PrimaryMonitor,
+    /// 最小化されている
+    Minimized,
+    /// モニターの作業領域の左・右・上・下半分にある（ウィンドウの中心で判断）
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl GeometryCondition {
+    /// `on:2` のような 1 語を解析する（位置の条件でなければ `None`）
+    pub fn parse(term: &str) -> Option<Self> {
+        let (name, value) = term.split_once(':')?;
+        match (name.to_ascii_lowercase().as_str(), value) {
+            ("on", "primary") => Some(GeometryCondition::PrimaryMonitor),
+            ("on", value) => value.parse().ok().filter(|n| *n > 0).map(GeometryCondition::Monitor),
+            ("min", "") => Some(GeometryCondition::Minimized),
+            ("left", "") => Some(GeometryCondition::Left),
+            ("right", "") => Some(GeometryCondition::Right),
+            ("top", "") => Some(GeometryCondition::Top),
+            ("bottom", "") => Some(GeometryCondition::Bottom),
+            _ => None,
+        }
+    }
+}
+
+/// ウィンドウの位置とモニターの配置で絞り込むフィルタ（すべての条件に合うもの）
+///
+/// 最小化されたウィンドウは画面外の座標になるため、`min:` 以外の条件には合わない。
+#[derive(Debug, Clone, Default)]
+pub struct GeometryFilter {
+    conditions: Vec<GeometryCondition>,
+    monitors: Vec<MonitorInfo>,
+}
+
+impl GeometryFilter {
+    /// クエリから位置の条件を取り出し、フィルタと残りの検索語を返す
+    pub fn parse(query: &str) -> (Self, String) {
+        let mut conditions = Vec::new();
+        let mut rest = Vec::new();
+        for term in query.split_whitespace() {
+            match GeometryCondition::parse(term) {
+                Some(condition) => conditions.push(condition),
+                None => rest.push(term),
+            }
+        }
+        (Self { conditions, monitors: Vec::new() }, rest.join(" "))
+    }
+
+    /// 判断に使うモニター（`monitor::enumerate_monitors` の順）
+    pub fn with_monitors(mut self, monitors: Vec<MonitorInfo>) -> Self {
+        self.monitors = monitors;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// モニターの作業領域を使う条件があるか（なければモニターを列挙しなくてよい）
+    pub fn needs_monitors(&self) -> bool {
+        self.conditions.iter().any(|condition| *condition != GeometryCondition::Minimized)
+    }
+
+    pub fn matches_rect(&self, is_minimized: bool, rect: (i32, i32, i32, i32)) -> bool {
+        let (x, y, width, height) = rect;
+        let center = (x + width / 2, y + height / 2);
+        let monitor = self.monitors.iter().find(|monitor| contains(monitor.work_area, center));
+        self.conditions.iter().all(|condition| match condition {
+            GeometryCondition::Minimized => is_minimized,
+            GeometryCondition::Monitor(number) => !is_minimized && monitor.is_some_and(|monitor| monitor.index + 1 == *number),
+            GeometryCondition::PrimaryMonitor => !is_minimized && monitor.is_some_and(|monitor| monitor.is_primary),
+            half => !is_minimized && monitor.is_some_and(|monitor| in_half(monitor.work_area, center, *half)),
+        })
+    }
+
+    pub fn matches_window_info(&self, window: &WindowInfo) -> bool {
+        self.matches_rect(window.is_minimized, window.rect)
+    }
+}
+
+impl WindowFilter for GeometryFilter {
+    fn matches(&self, window: &WindowItem) -> bool {
+        self.matches_rect(window.is_minimized, window.rect)
+    }
+
+    fn name(&self) -> &str {
+        "GeometryFilter"
+    }
+}
+
+fn contains(area: (i32, i32, i32, i32), point: (i32, i32)) -> bool {
+    let (x, y, width, height) = area;
+    (x..x + width).contains(&point.0) && (y..y + height).contains(&point.1)
+}
+
+fn in_half(area: (i32, i32, i32, i32), point: (i32, i32), half: GeometryCondition) -> bool {
+    let (x, y, width, height) = area;
+    match half {
+        GeometryCondition::Left => point.0 < x + width / 2,
+        GeometryCondition::Right => point.0 >= x + width / 2,
+        GeometryCondition::Top => point.1 < y + height / 2,
+        GeometryCondition::Bottom => point.1 >= y + height / 2,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitors() -> Vec<MonitorInfo> {
+        vec![
+            MonitorInfo { index: 0, handle: 1, work_area: (0, 0, 1920, 1040), is_primary: true },
+            MonitorInfo { index: 1, handle: 2, work_area: (1920, 0, 2560, 1400), is_primary: false },
+        ]
+    }
+
+    fn filter(query: &str) -> GeometryFilter {
+        GeometryFilter::parse(query).0.with_monitors(monitors())
+    }
+
+    #[test]
+    fn test_parse_keeps_other_terms() {
+        let (geometry, rest) = GeometryFilter::parse("chrome on:2 left: notes");
+        assert_eq!(geometry.conditions, vec![GeometryCondition::Monitor(2), GeometryCondition::Left]);
+        assert_eq!(rest, "chrome notes");
+
+        // 位置の条件でなければ検索語のまま
+        let (geometry, rest) = GeometryFilter::parse("on:0 min:yes url:example");
+        assert!(geometry.is_empty());
+        assert_eq!(rest, "on:0 min:yes url:example");
+        assert!(!GeometryFilter::parse("min:").0.needs_monitors());
+    }
+
+    #[test]
+    fn test_matches_monitor_and_half() {
+        let left_of_second = (1920, 0, 1280, 1400);
+        let right_of_primary = (1000, 100, 800, 600);
+        assert!(filter("on:2").matches_rect(false, left_of_second));
+        assert!(filter("on:2 left:").matches_rect(false, left_of_second));
+        assert!(!filter("on:2 right:").matches_rect(false, left_of_second));
+        assert!(filter("on:primary right: top:").matches_rect(false, right_of_primary));
+        assert!(!filter("on:1").matches_rect(false, left_of_second));
+    }
+
+    #[test]
+    fn test_minimized_windows() {
+        let minimized = (-32000, -32000, 160, 28);
+        assert!(filter("min:").matches_rect(true, minimized));
+        assert!(!filter("min:").matches_rect(false, (0, 0, 800, 600)));
+        assert!(!filter("on:1").matches_rect(true, (0, 0, 800, 600)));
+    }
+}
@@ -1,5 +1,9 @@
 pub mod window_filter;
 pub mod search_filter;
+pub mod tag_filter;
+pub mod geometry_filter;
 
 pub use window_filter::{WindowFilter, TaskbarWindowFilter, CompositeFilter, FilterMode, filter_windows};
-pub use search_filter::{Searchable, SearchFilter, search_items};
\ No newline at end of file
+pub use search_filter::{Searchable, SearchFilter, search_items};
+pub use tag_filter::TagFilter;
+pub use geometry_filter::{GeometryCondition, GeometryFilter};
\ No newline at end of file
@@ -0,0 +1,100 @@
+use crate::core::WindowInfo;
+use crate::data::window_item::WindowItem;
+use crate::data::workspaces::{TagRule, TagTarget, Workspaces};
+use super::window_filter::WindowFilter;
+
+/// ワークスペースのタグで絞り込むフィルタ
+///
+/// ウィンドウとプロファイルはそれぞれ、そのタグのルールが1つもなければ絞り込まない
+/// （プロファイルだけにタグを付けたワークスペースでグリッドが空にならないように）。
+pub struct TagFilter {
+    tag: String,
+    rules: Vec<TagRule>,
+}
+
+impl TagFilter {
+    pub fn new(tag: &str, rules: &[TagRule]) -> Self {
+        Self {
+            tag: tag.to_string(),
+            rules: rules.iter().filter(|rule| rule.tag == tag).cloned().collect(),
+        }
+    }
+
+    /// 選んでいるワークスペースのフィルタ（選んでいなければ `None`）
+    pub fn active(workspaces: &Workspaces) -> Option<Self> {
+        workspaces.active.as_deref().map(|tag| Self::new(tag, &workspaces.rules))
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn matches_window_parts(&self, process_name: &str, title: &str) -> bool {
+        let mut window_rules = self.rules.iter().filter(|rule| matches!(rule.target, TagTarget::Window { .. })).peekable();
+        window_rules.peek().is_none() || window_rules.any(|rule| rule.target.matches_window(process_name, title))
+    }
+
+    pub fn matches_window_info(&self, window: &WindowInfo) -> bool {
+        self.matches_window_parts(&window.process_name, &window.title)
+    }
+
+    /// プロファイルの分からない結果は絞り込まない
+    pub fn matches_profile(&self, profile: Option<&str>) -> bool {
+        let Some(profile) = profile else {
+            return true;
+        };
+        let mut profile_rules = self.rules.iter().filter(|rule| matches!(rule.target, TagTarget::Profile { .. })).peekable();
+        profile_rules.peek().is_none() || profile_rules.any(|rule| rule.target.matches_profile(profile))
+    }
+}
+
+impl WindowFilter for TagFilter {
+    fn matches(&self, window: &WindowItem) -> bool {
+        self.matches_window_parts(&window.process_name, &window.title)
+    }
+
+    fn name(&self) -> &str {
+        "TagFilter"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::filter_windows;
+
+    #[test]
+    fn test_filter_by_workspace() {
+        let workspaces = Workspaces {
+            active: Some("project-A".to_string()),
+            rules: vec![
+                TagRule {
+                    tag: "project-A".to_string(),
+                    target: TagTarget::Window { process_name: "Code.exe".to_string(), title: Some("my-launcher".to_string()) },
+                },
+                TagRule {
+                    tag: "personal".to_string(),
+                    target: TagTarget::Profile { profile: "Chrome - Personal".to_string() },
+                },
+            ],
+        };
+        let windows = vec![
+            WindowItem::new(1, "main.rs - my-launcher - Visual Studio Code".to_string(), "Code.exe".to_string(), "Chrome_WidgetWin_1".to_string()),
+            WindowItem::new(2, "notes - Visual Studio Code".to_string(), "Code.exe".to_string(), "Chrome_WidgetWin_1".to_string()),
+            WindowItem::new(3, "Slack".to_string(), "slack.exe".to_string(), "Chrome_WidgetWin_1".to_string()),
+        ];
+
+        let filter = TagFilter::active(&workspaces).unwrap();
+        let filtered = filter_windows(windows.clone(), &filter);
+        assert_eq!(filtered.iter().map(|w| w.hwnd).collect::<Vec<_>>(), vec![1]);
+        // project-A はプロファイルにタグがないので絞り込まない
+        assert!(filter.matches_profile(Some("Chrome - Personal")));
+
+        // personal はウィンドウにタグがないのでグリッドは絞り込まず、プロファイルだけ絞り込む
+        let personal = TagFilter::new("personal", &workspaces.rules);
+        assert_eq!(filter_windows(windows, &personal).len(), 3);
+        assert!(personal.matches_profile(Some("Chrome - Personal")));
+        assert!(!personal.matches_profile(Some("Chrome - Work")));
+        assert!(personal.matches_profile(None));
+    }
+}
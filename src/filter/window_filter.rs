@@ -140,6 +140,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 100, 100),
+                requires_elevation: false,
             };
             assert!(filter.matches(&window1));
             
@@ -152,6 +153,7 @@ mod tests {
                 is_visible: false,
                 is_minimized: false,
                 rect: (0, 0, 100, 100),
+                requires_elevation: false,
             };
             assert!(!filter.matches(&window2));
             
@@ -164,6 +166,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 100, 100),
+                requires_elevation: false,
             };
             assert!(!filter.matches(&window3));
         }
@@ -186,6 +189,7 @@ mod tests {
             is_visible: true,
             is_minimized: false,
             rect: (0, 0, 100, 100),
+            requires_elevation: false,
         };
         
         #[cfg(not(windows))]
@@ -219,6 +223,7 @@ mod tests {
             is_visible: true,
             is_minimized: false,
             rect: (0, 0, 100, 100),
+            requires_elevation: false,
         };
         
         assert!(composite.matches(&window));
@@ -235,6 +240,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 100, 100),
+                requires_elevation: false,
             },
             WindowItem {
                 hwnd: 2,
@@ -244,6 +250,7 @@ mod tests {
                 is_visible: true,
                 is_minimized: false,
                 rect: (0, 0, 100, 100),
+                requires_elevation: false,
             },
         ];
         
@@ -0,0 +1,133 @@
+//! フレームごとの処理時間の計測（F12 のデバッグ表示用）
+//!
+//! 計測は `Instant` で区間の時間を足し合わせるだけなので、表示していないときも常に有効にしておく。
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// 平均を取るフレーム数
+const HISTORY_FRAMES: usize = 120;
+
+/// 計測する区間
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Span {
+    /// 検索（`update_search`）
+    Search,
+    /// ウィンドウのサムネイルの取得
+    Thumbnails,
+}
+
+/// 1フレームの処理時間
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameTimings {
+    pub search: Duration,
+    pub thumbnails: Duration,
+    /// UI の構築と描画（フレーム全体から検索とサムネイルを除いた時間）
+    pub paint: Duration,
+    pub total: Duration,
+}
+
+#[derive(Default)]
+pub struct FrameProfiler {
+    frame_start: Option<Instant>,
+    current: FrameTimings,
+    history: VecDeque<FrameTimings>,
+}
+
+impl FrameProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.frame_start = Some(Instant::now());
+        self.current = FrameTimings::default();
+    }
+
+    pub fn record(&mut self, span: Span, elapsed: Duration) {
+        match span {
+            Span::Search => self.current.search += elapsed,
+            Span::Thumbnails => self.current.thumbnails += elapsed,
+        }
+    }
+
+    pub fn end_frame(&mut self) {
+        let Some(start) = self.frame_start.take() else {
+            return;
+        };
+        self.finish(start.elapsed());
+    }
+
+    fn finish(&mut self, total: Duration) {
+        let mut timings = self.current;
+        timings.total = total;
+        timings.paint = total.saturating_sub(timings.search + timings.thumbnails);
+        if self.history.len() == HISTORY_FRAMES {
+            self.history.pop_front();
+        }
+        self.history.push_back(timings);
+    }
+
+    /// 直前のフレーム
+    pub fn last(&self) -> Option<FrameTimings> {
+        self.history.back().copied()
+    }
+
+    /// 直近のフレームの平均
+    pub fn average(&self) -> FrameTimings {
+        let count = self.history.len().max(1) as u32;
+        let sum = self.history.iter().fold(FrameTimings::default(), |sum, t| FrameTimings {
+            search: sum.search + t.search,
+            thumbnails: sum.thumbnails + t.thumbnails,
+            paint: sum.paint + t.paint,
+            total: sum.total + t.total,
+        });
+        FrameTimings {
+            search: sum.search / count,
+            thumbnails: sum.thumbnails / count,
+            paint: sum.paint / count,
+            total: sum.total / count,
+        }
+    }
+
+    /// 直近のフレームで最も時間がかかったもの
+    pub fn slowest(&self) -> Option<FrameTimings> {
+        self.history.iter().copied().max_by_key(|t| t.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_is_the_rest_of_the_frame() {
+        let mut profiler = FrameProfiler::new();
+        profiler.record(Span::Search, Duration::from_millis(5));
+        profiler.record(Span::Search, Duration::from_millis(3));
+        profiler.record(Span::Thumbnails, Duration::from_millis(4));
+        profiler.finish(Duration::from_millis(20));
+
+        let last = profiler.last().unwrap();
+        assert_eq!(last.search, Duration::from_millis(8));
+        assert_eq!(last.thumbnails, Duration::from_millis(4));
+        assert_eq!(last.paint, Duration::from_millis(8));
+    }
+
+    #[test]
+    fn test_average_and_slowest() {
+        let mut profiler = FrameProfiler::new();
+        assert_eq!(profiler.average(), FrameTimings::default());
+        for ms in [10, 30] {
+            profiler.begin_frame();
+            profiler.finish(Duration::from_millis(ms));
+        }
+        assert_eq!(profiler.average().total, Duration::from_millis(20));
+        assert_eq!(profiler.slowest().unwrap().total, Duration::from_millis(30));
+
+        for _ in 0..HISTORY_FRAMES {
+            profiler.finish(Duration::from_millis(1));
+        }
+        assert_eq!(profiler.slowest().unwrap().total, Duration::from_millis(1));
+    }
+}
@@ -0,0 +1,201 @@
+//! ランチャーを開かずに操作を実行するグローバルホットキー（設定の `hotkeys`）
+//!
+//! デーモンが RegisterHotKey で登録し、押されたら `LauncherCore::execute_hotkey` で実行する。
+//! ランチャーは結果を開くたびに終了するため、常駐しているデーモンだけが登録する。
+
+use crate::config::HotkeyBinding;
+use crate::core::TabManager;
+use std::sync::Arc;
+
+/// 修飾キー（RegisterHotKey の `fsModifiers` と同じ値）
+pub const MOD_ALT: u32 = 0x0001;
+pub const MOD_CONTROL: u32 = 0x0002;
+pub const MOD_SHIFT: u32 = 0x0004;
+pub const MOD_WIN: u32 = 0x0008;
+
+/// キーの組み合わせ（`key` は仮想キーコード）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub modifiers: u32,
+    pub key: u32,
+}
+
+/// `Ctrl+Alt+B` / `Win+1` / `Shift+Win+F5` の形式を解析する
+///
+/// 普段の入力を奪わないように、修飾キーを1つ以上必要とする。
+pub fn parse_hotkey(keys: &str) -> Result<Hotkey, String> {
+    let hotkey = parse_keys(keys)?;
+    if hotkey.modifiers == 0 {
+        return Err(format!("'{}' needs Ctrl, Alt, Shift or Win", keys));
+    }
+    Ok(hotkey)
+}
+
+/// `Enter` / `Ctrl+V` の形式を解析する（修飾キーは省略できる。マクロのキー入力に使う）
+pub fn parse_keys(keys: &str) -> Result<Hotkey, String> {
+    let mut modifiers = 0;
+    let mut key = None;
+    for part in keys.split('+').map(str::trim) {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "control" => modifiers |= MOD_CONTROL,
+            "alt" => modifiers |= MOD_ALT,
+            "shift" => modifiers |= MOD_SHIFT,
+            "win" | "super" => modifiers |= MOD_WIN,
+            name => {
+                if key.is_some() {
+                    return Err(format!("more than one key in '{}'", keys));
+                }
+                key = Some(key_code(name).ok_or_else(|| format!("unknown key '{}'", part))?);
+            }
+        }
+    }
+
+    let key = key.ok_or_else(|| format!("no key in '{}'", keys))?;
+    Ok(Hotkey { modifiers, key })
+}
+
+/// キーの名前（小文字）の仮想キーコード
+fn key_code(name: &str) -> Option<u32> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() {
+            return Some(c.to_ascii_uppercase() as u32);
+        }
+    }
+    if let Some(number) = name.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+        return (1..=24).contains(&number).then(|| 0x70 + number - 1);
+    }
+    let code = match name {
+        "space" => 0x20,
+        "enter" => 0x0D,
+        "tab" => 0x09,
+        "esc" | "escape" => 0x1B,
+        "pageup" => 0x21,
+        "pagedown" => 0x22,
+        "end" => 0x23,
+        "home" => 0x24,
+        "left" => 0x25,
+        "up" => 0x26,
+        "right" => 0x27,
+        "down" => 0x28,
+        "insert" => 0x2D,
+        "delete" => 0x2E,
+        "backspace" => 0x08,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// ホットキーを登録して、押されたら操作を実行するスレッドを起動
+///
+/// 登録できなかったホットキー（書式の誤り、他のアプリが使用中）は警告をログに出して飛ばす。
+#[cfg(windows)]
+pub fn spawn_listener(bindings: Vec<HotkeyBinding>, tab_manager: Arc<TabManager>) -> std::io::Result<()> {
+    use crate::config::HotkeyAction;
+    use crate::core::{window_manager::WindowsApiManager, BrowserSearchEngine, LauncherCore};
+    use crate::toast::{self, ToastLevel};
+    use std::ptr;
+    use winapi::um::winuser::{GetMessageW, RegisterHotKey, UnregisterHotKey, MSG, WM_HOTKEY};
+
+    /// 押し続けても繰り返し届かないようにする
+    const MOD_NOREPEAT: u32 = 0x4000;
+
+    if bindings.is_empty() {
+        return Ok(());
+    }
+
+    std::thread::Builder::new().name("hotkeys".to_string()).spawn(move || {
+        // RegisterHotKey はこのスレッドのメッセージキューに WM_HOTKEY を送る
+        let mut registered = Vec::new();
+        for (id, binding) in bindings.iter().enumerate() {
+            let hotkey = match parse_hotkey(&binding.keys) {
+                Ok(hotkey) => hotkey,
+                Err(e) => {
+                    log::warn!("Invalid hotkey {}: {}", binding.keys, e);
+                    continue;
+                }
+            };
+            if unsafe { RegisterHotKey(ptr::null_mut(), id as i32, hotkey.modifiers | MOD_NOREPEAT, hotkey.key) } == 0 {
+                log::warn!("Failed to register hotkey {}: {}", binding.keys, std::io::Error::last_os_error());
+                continue;
+            }
+            registered.push(id);
+        }
+        if registered.is_empty() {
+            return;
+        }
+        log::info!("Registered {} hotkeys", registered.len());
+
+        let mut core = LauncherCore::new(
+            BrowserSearchEngine::new_with_tab_manager(tab_manager),
+            Arc::new(WindowsApiManager::new()),
+        );
+        let mut msg: MSG = unsafe { std::mem::zeroed() };
+        while unsafe { GetMessageW(&mut msg, ptr::null_mut(), 0, 0) } > 0 {
+            if msg.message != WM_HOTKEY {
+                continue;
+            }
+            let Some(binding) = bindings.get(msg.wParam) else {
+                continue;
+            };
+            if binding.action == HotkeyAction::RecentWindows {
+                if let Err(e) = show_recent_windows() {
+                    log::warn!("Hotkey {}: failed to open recent windows: {}", binding.keys, e);
+                }
+                continue;
+            }
+            // ランチャーのウィンドウを開かないので、結果はトーストで知らせる
+            match core.execute_hotkey(&binding.action) {
+                Ok(done) => {
+                    log::info!("Hotkey {}: {}", binding.keys, done);
+                    toast::notify(ToastLevel::Info, &done, Some(&binding.keys));
+                }
+                Err(e) => {
+                    log::warn!("Hotkey {} failed: {}", binding.keys, e);
+                    toast::notify(ToastLevel::Error, &format!("{} failed", binding.keys), Some(&e));
+                }
+            }
+        }
+
+        for id in registered {
+            unsafe { UnregisterHotKey(ptr::null_mut(), id as i32) };
+        }
+    })?;
+    Ok(())
+}
+
+/// 最近使ったウィンドウの一覧を新しいプロセスで開く
+#[cfg(windows)]
+fn show_recent_windows() -> std::io::Result<()> {
+    crate::paths::launcher_command()?.arg("--recent").spawn().map(|_| ())
+}
+
+#[cfg(not(windows))]
+pub fn spawn_listener(bindings: Vec<HotkeyBinding>, _tab_manager: Arc<TabManager>) -> std::io::Result<()> {
+    if !bindings.is_empty() {
+        log::warn!("Global hotkeys are only supported on Windows; ignoring {} bindings", bindings.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hotkey() {
+        assert_eq!(parse_hotkey("Win+1"), Ok(Hotkey { modifiers: MOD_WIN, key: '1' as u32 }));
+        assert_eq!(
+            parse_hotkey("ctrl + alt + b"),
+            Ok(Hotkey { modifiers: MOD_CONTROL | MOD_ALT, key: 'B' as u32 })
+        );
+        assert_eq!(parse_hotkey("Shift+Win+F5"), Ok(Hotkey { modifiers: MOD_SHIFT | MOD_WIN, key: 0x74 }));
+        assert_eq!(parse_hotkey("Alt+Space"), Ok(Hotkey { modifiers: MOD_ALT, key: 0x20 }));
+
+        assert!(parse_hotkey("B").is_err());
+        assert!(parse_hotkey("Win+A+B").is_err());
+        assert!(parse_hotkey("Ctrl+F25").is_err());
+        assert!(parse_hotkey("Ctrl+").is_err());
+        assert_eq!(parse_keys("Enter"), Ok(Hotkey { modifiers: 0, key: 0x0D }));
+    }
+}
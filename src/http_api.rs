@@ -0,0 +1,403 @@
+//! デーモンのローカル HTTP API（設定の `api`）
+//!
+//! ブラウザのダッシュボードやスクリプトから、WebSocket より手軽に JSON で使えるようにする。
+//! 127.0.0.1 だけで待ち受け、すべてのリクエストにデータディレクトリの `api_token` のトークンを要求する
+//! （`Authorization: Bearer <token>` ヘッダーか `?token=`）。トークンは時間が一定になるように比べる。
+//! Chrome 拡張の WebSocket はトークンを持たないので、このトークンは HTTP API だけで使う。
+//! ブラウザのページから呼べるのは設定の `api.allowed_origins` に入れたオリジンだけ（それ以外の
+//! `Origin` 付きのリクエストは断り、CORS のヘッダーも返さない）。
+//!
+//! - `GET /search?q=...&mode=browser|windows|tabs`: 検索結果（`id` を `/execute` に渡す）
+//! - `POST /execute`（本文は `{"id": "..."}`）: 直前の検索の結果を実行
+//! - `GET /tabs`: 開いているタブ
+//! - `GET /status`: 監視しているサーバーの状態
+//!
+//! 処理はデーモンの IPC と同じ `DaemonState::handle_message` で行う。
+
+use crate::core::SearchMode;
+use crate::daemon::DaemonState;
+use crate::ipc::IpcMessage;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 受け付けるリクエストの大きさの上限（ヘッダーと本文）
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+pub struct HttpApiServer {
+    state: Arc<DaemonState>,
+    port: u16,
+    token: String,
+    allowed_origins: Arc<Vec<String>>,
+}
+
+impl HttpApiServer {
+    pub fn new(state: Arc<DaemonState>, port: u16, token: String) -> Self {
+        Self { state, port, token, allowed_origins: Arc::new(Vec::new()) }
+    }
+
+    /// ブラウザから呼べるページのオリジン（例: `http://localhost:8080`）
+    pub fn with_allowed_origins(mut self, origins: Vec<String>) -> Self {
+        self.allowed_origins = Arc::new(origins);
+        self
+    }
+
+    pub async fn start(&self) -> Result<(), Box<dyn Error>> {
+        let addr = format!("127.0.0.1:{}", self.port);
+        let listener = TcpListener::bind(&addr).await?;
+        log::info!("HTTP API listening on {}", addr);
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let state = Arc::clone(&self.state);
+                    let token = self.token.clone();
+                    let origins = Arc::clone(&self.allowed_origins);
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &state, &token, &origins).await {
+                            log::debug!("HTTP API connection closed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    log::error!("Failed to accept HTTP API connection: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// 1つの接続で1つのリクエストに応答して閉じる
+async fn handle_connection(mut stream: TcpStream, state: &DaemonState, token: &str, origins: &[String]) -> io::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let response = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        match parse_request(&buffer) {
+            Ok(Some(request)) => break route(state, token, origins, &request),
+            Ok(None) if buffer.len() < MAX_REQUEST_BYTES => continue,
+            Ok(None) => break HttpResponse::error(413, "Request too large"),
+            Err(e) => break HttpResponse::error(400, &e),
+        }
+    };
+    stream.write_all(&response.to_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// 解析した HTTP リクエスト（ヘッダー名は小文字）
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn is_authorized(&self, token: &str) -> bool {
+        let bearer = self
+            .headers
+            .get("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        bearer
+            .or(self.query.get("token").map(String::as_str))
+            .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+    }
+}
+
+/// 一致しない位置によって時間が変わらないように比べる（長さが違えばすぐ false）
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// 受け取った分を解析する（ヘッダーか本文がまだ届いていなければ `None`）
+pub fn parse_request(buffer: &[u8]) -> Result<Option<HttpRequest>, String> {
+    let Some(head_end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") else {
+        return Ok(None);
+    };
+    let head = std::str::from_utf8(&buffer[..head_end]).map_err(|_| "Headers are not UTF-8".to_string())?;
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(format!("Malformed request line '{}'", request_line));
+    };
+
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    let content_length = match headers.get("content-length") {
+        Some(value) => value.parse::<usize>().map_err(|_| format!("Invalid Content-Length '{}'", value))?,
+        None => 0,
+    };
+    let body_start = head_end + 4;
+    if buffer.len() < body_start + content_length {
+        return Ok(None);
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Some(HttpRequest {
+        method: method.to_uppercase(),
+        path: path.to_string(),
+        query: url::form_urlencoded::parse(query.as_bytes()).into_owned().collect(),
+        headers,
+        body: buffer[body_start..body_start + content_length].to_vec(),
+    }))
+}
+
+/// JSON の応答
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Option<Value>,
+    /// CORS で許可するオリジン（許可したページからのリクエストだけ）
+    pub allow_origin: Option<String>,
+}
+
+impl HttpResponse {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body: Some(body), allow_origin: None }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self { status, body: Some(json!({ "error": message })), allow_origin: None }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            204 => "No Content",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            _ => "Internal Server Error",
+        };
+        let body = self.body.as_ref().map(Value::to_string).unwrap_or_default();
+        let cors = match &self.allow_origin {
+            Some(origin) => format!(
+                "Access-Control-Allow-Origin: {}\r\nVary: Origin\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\nAccess-Control-Allow-Headers: Authorization, Content-Type\r\n",
+                origin
+            ),
+            None => String::new(),
+        };
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            body.len(),
+            cors,
+            body
+        )
+        .into_bytes()
+    }
+}
+
+#[derive(Deserialize)]
+struct ExecuteRequest {
+    id: String,
+}
+
+/// リクエストを IPC のメッセージにして処理し、応答を JSON にする
+///
+/// `Origin` の付いたリクエスト（ブラウザのページから）は `origins` にあるオリジンだけ受け付ける。
+pub fn route(state: &DaemonState, token: &str, origins: &[String], request: &HttpRequest) -> HttpResponse {
+    let origin = request.headers.get("origin");
+    if origin.is_some_and(|origin| !origins.contains(origin)) {
+        return HttpResponse::error(403, "Origin not allowed");
+    }
+    // CORS のプリフライトはトークンを付けずに届く
+    let response = if request.method == "OPTIONS" {
+        HttpResponse { status: 204, body: None, allow_origin: None }
+    } else {
+        respond(state, token, request)
+    };
+    HttpResponse { allow_origin: origin.cloned(), ..response }
+}
+
+fn respond(state: &DaemonState, token: &str, request: &HttpRequest) -> HttpResponse {
+    if !request.is_authorized(token) {
+        return HttpResponse::error(401, "Missing or invalid token");
+    }
+
+    let message = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => IpcMessage::GetStatus,
+        ("GET", "/tabs") => IpcMessage::GetTabs,
+        ("GET", "/search") => {
+            let mode = match request.query.get("mode") {
+                Some(name) => match SearchMode::from_name(name) {
+                    Some(mode) => mode,
+                    None => return HttpResponse::error(400, &format!("Unknown mode '{}'", name)),
+                },
+                None => SearchMode::Browser,
+            };
+            IpcMessage::Search { query: request.query.get("q").cloned().unwrap_or_default(), mode }
+        }
+        ("POST", "/execute") => match serde_json::from_slice::<ExecuteRequest>(&request.body) {
+            Ok(execute) => IpcMessage::ExecuteAction { id: execute.id },
+            Err(e) => return HttpResponse::error(400, &format!("Expected {{\"id\": ...}}: {}", e)),
+        },
+        (_, "/status" | "/tabs" | "/search" | "/execute") => return HttpResponse::error(405, "Method not allowed"),
+        _ => return HttpResponse::error(404, "Not found"),
+    };
+
+    match state.handle_message(message) {
+        IpcMessage::Status { services, transport } => HttpResponse::ok(json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "services": services,
+            "transport": transport,
+        })),
+        IpcMessage::TabList { tabs, extension_connected, age_ms, restored, recently_closed, reading_list } => HttpResponse::ok(json!({
+            "tabs": tabs,
+            "extension_connected": extension_connected,
+            "age_ms": age_ms,
+            "restored": restored,
+            "recently_closed": recently_closed,
+            "reading_list": reading_list,
+        })),
+        IpcMessage::Results { results } => HttpResponse::ok(json!({ "results": results })),
+        IpcMessage::ActionResult { message } => HttpResponse::ok(json!({ "message": message })),
+        IpcMessage::Error { message } => HttpResponse::error(400, &message),
+        other => HttpResponse::error(500, &format!("Unexpected response {:?}", other)),
+    }
+}
+
+/// API のトークンを読み込む（なければ作って保存する）
+pub fn load_or_create_token(path: &Path) -> io::Result<String> {
+    if let Ok(token) = std::fs::read_to_string(path) {
+        let token = token.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string());
+        }
+    }
+
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| io::Error::other(e.to_string()))?;
+    let token: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, &token)?;
+    log::info!("Created HTTP API token at {}", path.display());
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ChromeTab, TabManager};
+
+    const TOKEN: &str = "secret";
+
+    fn get(target: &str) -> HttpRequest {
+        let raw = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\n\r\n", target, TOKEN);
+        parse_request(raw.as_bytes()).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(parse_request(b"GET /tabs HTTP/1.1\r\nHost: localhost\r\n"), Ok(None));
+
+        let raw = b"POST /execute?token=abc HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: 14\r\n\r\n{\"id\":\"url:";
+        assert_eq!(parse_request(raw), Ok(None));
+        let raw = b"POST /execute?token=abc HTTP/1.1\r\nContent-Length: 14\r\n\r\n{\"id\":\"url:x\"}";
+        let request = parse_request(raw).unwrap().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/execute");
+        assert_eq!(request.query["token"], "abc");
+        assert_eq!(request.body, b"{\"id\":\"url:x\"}".to_vec());
+
+        let request = get("/search?q=rust%20docs&mode=tabs");
+        assert_eq!(request.query["q"], "rust docs");
+        assert_eq!(request.headers["host"], "localhost");
+    }
+
+    #[test]
+    fn test_route() {
+        let tab_manager = Arc::new(TabManager::new());
+        tab_manager.update_tabs(vec![ChromeTab {
+            id: 1,
+            window_id: 1,
+            title: "Docs".to_string(),
+            url: "https://docs.rs".to_string(),
+            fav_icon_url: String::new(),
+            active: true,
+            index: 0,
+            pinned: false,
+        }]);
+        let state = DaemonState::new(tab_manager);
+
+        let mut unauthorized = get("/tabs");
+        unauthorized.headers.remove("authorization");
+        assert_eq!(route(&state, TOKEN, &[], &unauthorized).status, 401);
+        unauthorized.query.insert("token".to_string(), TOKEN.to_string());
+        assert_eq!(route(&state, TOKEN, &[], &unauthorized).status, 200);
+
+        let tabs = route(&state, TOKEN, &[], &get("/tabs"));
+        assert_eq!(tabs.body.unwrap()["tabs"][0]["title"], "Docs");
+
+        let search = route(&state, TOKEN, &[], &get("/search?q=nt%20rust"));
+        assert_eq!(search.status, 200);
+        assert_eq!(search.body.unwrap()["results"][0]["id"], "url:https://www.google.com/search?q=rust");
+
+        assert_eq!(route(&state, TOKEN, &[], &get("/search?q=x&mode=grid")).status, 400);
+        assert_eq!(route(&state, TOKEN, &[], &get("/execute")).status, 405);
+        assert_eq!(route(&state, TOKEN, &[], &get("/nope")).status, 404);
+    }
+
+    #[test]
+    fn test_route_checks_origin() {
+        let state = DaemonState::new(Arc::new(TabManager::new()));
+        let origins = ["http://localhost:8080".to_string()];
+        let mut request = get("/tabs");
+        assert_eq!(route(&state, TOKEN, &origins, &request).allow_origin, None);
+
+        request.headers.insert("origin".to_string(), "https://evil.example".to_string());
+        let response = route(&state, TOKEN, &origins, &request);
+        assert_eq!(response.status, 403);
+        assert!(!String::from_utf8(response.to_bytes()).unwrap().contains("Access-Control-Allow-Origin"));
+
+        request.headers.insert("origin".to_string(), "http://localhost:8080".to_string());
+        let response = route(&state, TOKEN, &origins, &request);
+        assert_eq!(response.status, 200);
+        assert!(String::from_utf8(response.to_bytes()).unwrap().contains("Access-Control-Allow-Origin: http://localhost:8080\r\n"));
+
+        request.method = "OPTIONS".to_string();
+        request.headers.remove("authorization");
+        assert_eq!(route(&state, TOKEN, &origins, &request).status, 204);
+    }
+
+    #[test]
+    fn test_token_comparison() {
+        let mut request = get("/tabs");
+        assert!(request.is_authorized(TOKEN));
+        assert!(!request.is_authorized("secreT"));
+        assert!(!request.is_authorized("secret2"));
+        request.headers.insert("authorization".to_string(), "Bearer ".to_string());
+        assert!(!request.is_authorized(TOKEN));
+    }
+
+    #[test]
+    fn test_load_or_create_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api_token");
+        let token = load_or_create_token(&path).unwrap();
+        assert_eq!(token.len(), 32);
+        assert_eq!(load_or_create_token(&path).unwrap(), token);
+    }
+}
@@ -3,18 +3,92 @@ use std::io;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 #[cfg(windows)]
 use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeClient, ServerOptions, NamedPipeServer};
+#[cfg(windows)]
 use std::time::Duration;
+use crate::core::{ChromeTab, WindowInfo};
 
 pub const PIPE_NAME: &str = r"\\.\pipe\my_launcher_ipc";
 
+/// 1 件のメッセージの上限（長さが壊れていても大きなバッファを確保しない）
+pub const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum IpcMessage {
     GetTabs,
     SwitchToTab { tab_id: i32, window_id: i32 },
-    TabList { tabs: Vec<TabInfo> },
+    CloseTab { tab_id: i32 },
+    // Any other command for the Chrome extension queued through the daemon
+    QueueCommand { command: crate::core::native_messaging::ChromeCommand },
+    CommandQueued,
+    TabList {
+        tabs: Vec<TabInfo>,
+        #[serde(default)]
+        extension_connected: bool,
+        /// デーモンが最後にタブ一覧の更新か keepAlive を受け取ってからの時間（未受信なら `None`）
+        #[serde(default)]
+        age_ms: Option<u64>,
+        /// 一覧が保存しておいたスナップショットのままか
+        #[serde(default)]
+        restored: bool,
+        /// 最近閉じたタブ（新しいものから）
+        #[serde(default)]
+        recently_closed: Vec<crate::core::native_messaging::ClosedTab>,
+        /// リーディングリスト
+        #[serde(default)]
+        reading_list: Vec<crate::core::native_messaging::ReadingListEntry>,
+    },
     TabSwitchResult { success: bool, error: Option<String> },
     // Command to be sent to Chrome extension
     ChromeCommand { command: ChromeExtensionCommand },
+    // Window list tracked by the daemon
+    GetWindows,
+    WindowList { windows: Vec<WindowInfo> },
+    // State of the daemon's supervised servers
+    GetStatus,
+    Status {
+        services: Vec<crate::supervisor::ServiceStatus>,
+        /// いま拡張機能とつながっている経路（接続がなければ `None`）
+        #[serde(default)]
+        transport: Option<crate::core::extension_sessions::Transport>,
+    },
+    // Browser extensions connected to the daemon
+    GetSessions,
+    Sessions { sessions: Vec<crate::core::extension_sessions::SessionInfo> },
+    /// 接続中の拡張機能にタブ一覧の送り直しや切断を頼む
+    ControlSession { id: u64, control: crate::core::extension_sessions::SessionControl },
+    // Button pressed on a Windows notification, run by the daemon
+    NotificationAction { action: crate::notification::NotificationAction },
+    // External triggers (AutoHotkey, scripts, the CLI)
+    /// ランチャーを指定したモードで開く（`prefill` は入力欄に入れておく文字列）
+    ShowLauncher {
+        mode: crate::core::SearchMode,
+        #[serde(default)]
+        prefill: Option<String>,
+    },
+    /// キーワードの検索結果を返す（`ExecuteAction` で結果の ID を指定して実行する）
+    RunKeyword {
+        keyword: String,
+        #[serde(default)]
+        args: String,
+    },
+    /// ランチャーと同じ検索を行って結果を返す
+    Search {
+        query: String,
+        mode: crate::core::SearchMode,
+    },
+    Results { results: Vec<ResultInfo> },
+    /// `RunKeyword` で返した結果を ID で実行
+    ExecuteAction { id: String },
+    ActionResult { message: String },
+    Error { message: String },
+}
+
+/// 外部に返す検索結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultInfo {
+    pub id: String,
+    pub title: String,
+    pub description: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +105,38 @@ pub struct TabInfo {
     pub fav_icon_url: String,
     pub active: bool,
     pub index: i32,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl From<ChromeTab> for TabInfo {
+    fn from(tab: ChromeTab) -> Self {
+        Self {
+            id: tab.id,
+            window_id: tab.window_id,
+            title: tab.title,
+            url: tab.url,
+            fav_icon_url: tab.fav_icon_url,
+            active: tab.active,
+            index: tab.index,
+            pinned: tab.pinned,
+        }
+    }
+}
+
+impl From<TabInfo> for ChromeTab {
+    fn from(tab: TabInfo) -> Self {
+        Self {
+            id: tab.id,
+            window_id: tab.window_id,
+            title: tab.title,
+            url: tab.url,
+            fav_icon_url: tab.fav_icon_url,
+            active: tab.active,
+            index: tab.index,
+            pinned: tab.pinned,
+        }
+    }
 }
 
 pub async fn send_message<S>(stream: &mut S, message: &IpcMessage) -> io::Result<()>
@@ -39,6 +145,12 @@ where
 {
     let json = serde_json::to_string(message)?;
     let json_bytes = json.as_bytes();
+    if json_bytes.len() > MAX_MESSAGE_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("message of {} bytes exceeds the {} byte limit", json_bytes.len(), MAX_MESSAGE_BYTES),
+        ));
+    }
     let length = json_bytes.len() as u32;
     
     // Write message length (4 bytes)
@@ -59,6 +171,14 @@ where
     let mut length_bytes = [0u8; 4];
     stream.read_exact(&mut length_bytes).await?;
     let message_length = u32::from_le_bytes(length_bytes) as usize;
+    if message_length > MAX_MESSAGE_BYTES {
+        // 本文は読み捨てて、同じ接続で次のメッセージから続けられるようにする
+        tokio::io::copy(&mut (&mut *stream).take(message_length as u64), &mut tokio::io::sink()).await?;
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {} bytes exceeds the {} byte limit", message_length, MAX_MESSAGE_BYTES),
+        ));
+    }
     
     // Read message body
     let mut message_bytes = vec![0u8; message_length];
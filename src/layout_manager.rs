@@ -0,0 +1,409 @@
+//! ウィンドウ配置のスナップショット（`layout save 自宅` / `layout 自宅`）
+//!
+//! タスクバーに表示されるウィンドウの位置・サイズ・モニターを名前付きで layouts.json に保存し、
+//! 後から `SetWindowPos` で元の配置に戻す。ノートPCのドッキング・取り外しの後に使う想定。
+
+use crate::core::WindowInfo;
+use crate::monitor::MonitorInfo;
+use crate::windows_api::ShowState;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 保存時にウィンドウがあったモニター
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedMonitor {
+    pub index: usize,
+    pub work_area: (i32, i32, i32, i32),
+}
+
+/// 保存されたウィンドウの配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedWindow {
+    pub process_name: String,
+    pub class_name: String,
+    pub title: String,
+    /// 復元時に起動し直すための実行ファイル
+    #[serde(default)]
+    pub exe_path: Option<String>,
+    pub rect: (i32, i32, i32, i32),
+    #[serde(default)]
+    pub is_minimized: bool,
+    #[serde(default)]
+    pub is_maximized: bool,
+    #[serde(default)]
+    pub monitor: Option<SavedMonitor>,
+}
+
+impl SavedWindow {
+    fn show_state(&self) -> ShowState {
+        if self.is_minimized {
+            ShowState::Minimized
+        } else if self.is_maximized {
+            ShowState::Maximized
+        } else {
+            ShowState::Normal
+        }
+    }
+}
+
+/// 名前付きのウィンドウ配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    pub name: String,
+    /// 保存日時（UNIX時刻・秒）
+    pub saved_at: i64,
+    pub windows: Vec<SavedWindow>,
+}
+
+/// 移動するウィンドウ (HWND, 位置, 移動したあとの表示状態)
+pub type WindowMove = (isize, (i32, i32, i32, i32), ShowState);
+
+/// 復元時に行う操作
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestorePlan {
+    /// 移動するウィンドウ
+    pub moves: Vec<WindowMove>,
+    /// 対応するウィンドウが見つからなかった配置
+    pub missing: Vec<SavedWindow>,
+}
+
+/// 復元の結果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestoreSummary {
+    pub moved: usize,
+    pub launched: usize,
+    pub missing: usize,
+}
+
+/// ウィンドウ配置の保存と復元
+#[derive(Debug, Clone)]
+pub struct LayoutManager {
+    path: PathBuf,
+    relaunch_missing: bool,
+}
+
+impl LayoutManager {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            relaunch_missing: false,
+        }
+    }
+
+    /// 復元時に見つからないアプリを起動し直すか
+    pub fn with_relaunch_missing(mut self, relaunch_missing: bool) -> Self {
+        self.relaunch_missing = relaunch_missing;
+        self
+    }
+
+    pub fn relaunch_missing(&self) -> bool {
+        self.relaunch_missing
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 保存済みの配置を新しい順に返す
+    pub fn load_all(&self) -> Result<Vec<Layout>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        let mut layouts: Vec<Layout> = serde_json::from_str(&content)?;
+        layouts.sort_by_key(|layout| std::cmp::Reverse(layout.saved_at));
+        Ok(layouts)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<Layout>, Box<dyn Error>> {
+        Ok(self.load_all()?.into_iter().find(|layout| layout.name.eq_ignore_ascii_case(name)))
+    }
+
+    /// 現在のウィンドウ配置を保存（同じ名前の配置は上書き）
+    pub fn save(&self, name: &str, windows: &[WindowInfo], monitors: &[MonitorInfo]) -> Result<Layout, Box<dyn Error>> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("layout name is empty".into());
+        }
+
+        let layout = Layout {
+            name: name.to_string(),
+            saved_at: chrono::Local::now().timestamp(),
+            windows: windows
+                .iter()
+                .map(|window| SavedWindow {
+                    process_name: window.process_name.clone(),
+                    class_name: window.class_name.clone(),
+                    title: window.title.clone(),
+                    exe_path: crate::windows_api::process_path_for_window(window.hwnd),
+                    rect: window.rect,
+                    is_minimized: window.is_minimized,
+                    is_maximized: !window.is_minimized && crate::windows_api::is_window_maximized(window.hwnd),
+                    monitor: monitor_containing(window.rect, monitors).map(|monitor| SavedMonitor {
+                        index: monitor.index,
+                        work_area: monitor.work_area,
+                    }),
+                })
+                .collect(),
+        };
+
+        let mut layouts = self.load_all()?;
+        layouts.retain(|existing| !existing.name.eq_ignore_ascii_case(name));
+        layouts.push(layout.clone());
+        self.write(&layouts)?;
+        Ok(layout)
+    }
+
+    /// 配置を削除（見つからなければ `false`）
+    pub fn delete(&self, name: &str) -> Result<bool, Box<dyn Error>> {
+        let mut layouts = self.load_all()?;
+        let before = layouts.len();
+        layouts.retain(|layout| !layout.name.eq_ignore_ascii_case(name));
+        if layouts.len() == before {
+            return Ok(false);
+        }
+        self.write(&layouts)?;
+        Ok(true)
+    }
+
+    /// 配置を復元する
+    pub fn restore(&self, layout: &Layout, windows: &[WindowInfo], monitors: &[MonitorInfo]) -> RestoreSummary {
+        let plan = plan_restore(layout, windows, monitors);
+        let mut summary = RestoreSummary {
+            missing: plan.missing.len(),
+            ..Default::default()
+        };
+
+        for (hwnd, rect, show) in &plan.moves {
+            if crate::windows_api::move_window(*hwnd, *rect, *show) {
+                summary.moved += 1;
+            } else {
+                log::warn!("Failed to move window {} to {:?}", hwnd, rect);
+            }
+        }
+
+        if self.relaunch_missing {
+            for exe_path in relaunch_targets(&plan.missing) {
+                match std::process::Command::new(exe_path).spawn() {
+                    Ok(_) => {
+                        log::info!("Relaunched {} for layout {}", exe_path, layout.name);
+                        summary.launched += 1;
+                    }
+                    Err(e) => log::warn!("Failed to relaunch {}: {}", exe_path, e),
+                }
+            }
+        }
+
+        summary
+    }
+
+    fn write(&self, layouts: &[Layout]) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(layouts)?)?;
+        Ok(())
+    }
+}
+
+impl Default for LayoutManager {
+    fn default() -> Self {
+        Self::new(crate::paths::layouts_path())
+    }
+}
+
+/// ウィンドウの中心があるモニター
+fn monitor_containing(rect: (i32, i32, i32, i32), monitors: &[MonitorInfo]) -> Option<&MonitorInfo> {
+    let (cx, cy) = (rect.0 + rect.2 / 2, rect.1 + rect.3 / 2);
+    monitors.iter().find(|monitor| {
+        let (x, y, w, h) = monitor.work_area;
+        cx >= x && cx < x + w && cy >= y && cy < y + h
+    })
+}
+
+/// 保存時のモニターがなくなっていれば、プライマリモニターの同じ相対位置に移す
+fn relocate(rect: (i32, i32, i32, i32), saved: Option<&SavedMonitor>, monitors: &[MonitorInfo]) -> (i32, i32, i32, i32) {
+    let Some(saved) = saved else { return rect };
+    if monitors.is_empty() || monitors.iter().any(|monitor| monitor.work_area == saved.work_area) {
+        return rect;
+    }
+
+    let target = monitors.iter().find(|monitor| monitor.is_primary).unwrap_or(&monitors[0]).work_area;
+    let (x, y, width, height) = rect;
+    let width = width.min(target.2);
+    let height = height.min(target.3);
+    let x = (target.0 + (x - saved.work_area.0)).clamp(target.0, target.0 + target.2 - width);
+    let y = (target.1 + (y - saved.work_area.1)).clamp(target.1, target.1 + target.3 - height);
+    (x, y, width, height)
+}
+
+/// 保存された配置と現在のウィンドウを対応付ける
+///
+/// プロセス名とクラス名が同じウィンドウのうち、タイトルが一致するものを優先する。
+/// 1つのウィンドウは1つの配置にしか使わない。
+pub fn plan_restore(layout: &Layout, windows: &[WindowInfo], monitors: &[MonitorInfo]) -> RestorePlan {
+    let same_app = |saved: &SavedWindow, window: &WindowInfo| {
+        window.process_name.eq_ignore_ascii_case(&saved.process_name) && window.class_name == saved.class_name
+    };
+
+    let mut used = vec![false; windows.len()];
+    let mut assigned: Vec<Option<usize>> = vec![None; layout.windows.len()];
+
+    // 先にタイトルまで一致するウィンドウを割り当て、残りを同じアプリのウィンドウに割り当てる
+    for exact_title in [true, false] {
+        for (slot, saved) in assigned.iter_mut().zip(&layout.windows) {
+            if slot.is_some() {
+                continue;
+            }
+            *slot = (0..windows.len()).find(|&i| {
+                !used[i] && same_app(saved, &windows[i]) && (!exact_title || windows[i].title == saved.title)
+            });
+            if let Some(i) = *slot {
+                used[i] = true;
+            }
+        }
+    }
+
+    let mut plan = RestorePlan {
+        moves: Vec::new(),
+        missing: Vec::new(),
+    };
+    for (slot, saved) in assigned.into_iter().zip(&layout.windows) {
+        match slot {
+            Some(i) => {
+                let rect = relocate(saved.rect, saved.monitor.as_ref(), monitors);
+                plan.moves.push((windows[i].hwnd, rect, saved.show_state()));
+            }
+            None => plan.missing.push(saved.clone()),
+        }
+    }
+
+    plan
+}
+
+/// 見つからなかった配置のために起動し直す実行ファイル（同じアプリの複数のウィンドウでも起動は1回）
+pub fn relaunch_targets(missing: &[SavedWindow]) -> Vec<&str> {
+    let mut targets: Vec<&str> = Vec::new();
+    for exe_path in missing.iter().filter_map(|saved| saved.exe_path.as_deref()) {
+        if !targets.iter().any(|target| target.eq_ignore_ascii_case(exe_path)) {
+            targets.push(exe_path);
+        }
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::helpers::create_test_window;
+
+    fn monitor(index: usize, work_area: (i32, i32, i32, i32), is_primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            index,
+            handle: index as isize + 1,
+            work_area,
+            is_primary,
+        }
+    }
+
+    fn window(hwnd: isize, title: &str, process: &str, rect: (i32, i32, i32, i32)) -> WindowInfo {
+        let mut window = create_test_window(hwnd, title, process);
+        window.rect = rect;
+        window
+    }
+
+    #[test]
+    fn test_save_and_load_layouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = LayoutManager::new(dir.path().join("layouts.json"));
+        let monitors = vec![monitor(0, (0, 0, 1920, 1040), true), monitor(1, (1920, 0, 2560, 1400), false)];
+        let windows = vec![window(1, "Editor", "code.exe", (2000, 100, 800, 600))];
+
+        manager.save("Docked", &windows, &monitors).unwrap();
+        manager.save("docked", &windows, &[]).unwrap();
+
+        let layouts = manager.load_all().unwrap();
+        assert_eq!(layouts.len(), 1);
+        assert_eq!(layouts[0].name, "docked");
+        assert_eq!(layouts[0].windows[0].rect, (2000, 100, 800, 600));
+
+        assert!(manager.save("  ", &windows, &monitors).is_err());
+        assert!(manager.delete("DOCKED").unwrap());
+        assert!(manager.get("docked").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_plan_restore_matches_by_process_and_title() {
+        let monitors = vec![monitor(0, (0, 0, 1920, 1040), true)];
+        let saved = vec![
+            window(10, "Notes", "notepad.exe", (0, 0, 400, 300)),
+            window(11, "Todo", "notepad.exe", (400, 0, 400, 300)),
+            window(12, "Music", "spotify.exe", (800, 0, 400, 300)),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let manager = LayoutManager::new(dir.path().join("layouts.json"));
+        let layout = manager.save("work", &saved, &monitors).unwrap();
+
+        let current = vec![
+            window(21, "Todo", "Notepad.exe", (50, 50, 100, 100)),
+            window(22, "Untitled", "notepad.exe", (50, 50, 100, 100)),
+        ];
+        let plan = plan_restore(&layout, &current, &monitors);
+
+        assert_eq!(plan.moves, vec![(22, (0, 0, 400, 300), ShowState::Normal), (21, (400, 0, 400, 300), ShowState::Normal)]);
+        assert_eq!(plan.missing.len(), 1);
+        assert_eq!(plan.missing[0].process_name, "spotify.exe");
+    }
+
+    #[test]
+    fn test_plan_restore_keeps_maximized_and_relaunches_each_app_once() {
+        let saved = |title: &str, exe_path: Option<&str>, is_maximized: bool| SavedWindow {
+            process_name: "code.exe".to_string(),
+            class_name: "Chrome_WidgetWin_1".to_string(),
+            title: title.to_string(),
+            exe_path: exe_path.map(str::to_string),
+            rect: (0, 0, 1920, 1040),
+            is_minimized: false,
+            is_maximized,
+            monitor: None,
+        };
+        let layout = Layout {
+            name: "work".to_string(),
+            saved_at: 0,
+            windows: vec![
+                saved("Editor", Some(r"C:\Code\Code.exe"), true),
+                saved("Notes", Some(r"C:\code\code.exe"), false),
+                saved("Other", None, false),
+            ],
+        };
+
+        let mut current = window(30, "Editor", "code.exe", (10, 10, 100, 100));
+        current.class_name = "Chrome_WidgetWin_1".to_string();
+        let plan = plan_restore(&layout, &[current], &[]);
+        assert_eq!(plan.moves, vec![(30, (0, 0, 1920, 1040), ShowState::Maximized)]);
+        assert_eq!(plan.missing.len(), 2);
+
+        let missing = [layout.windows[0].clone(), layout.windows[1].clone(), layout.windows[2].clone()];
+        assert_eq!(relaunch_targets(&missing), vec![r"C:\Code\Code.exe"]);
+    }
+
+    #[test]
+    fn test_relocate_to_primary_when_monitor_is_gone() {
+        let external = SavedMonitor {
+            index: 1,
+            work_area: (1920, 0, 2560, 1400),
+        };
+        let laptop = vec![monitor(0, (0, 0, 1920, 1040), true)];
+
+        // 相対位置を保ち、はみ出す分は作業領域に収める
+        assert_eq!(relocate((2020, 100, 800, 600), Some(&external), &laptop), (100, 100, 800, 600));
+        assert_eq!(relocate((3000, 900, 2400, 600), Some(&external), &laptop), (0, 440, 1920, 600));
+
+        // モニターが残っていればそのまま
+        let docked = vec![monitor(0, (0, 0, 1920, 1040), true), monitor(1, (1920, 0, 2560, 1400), false)];
+        assert_eq!(relocate((2020, 100, 800, 600), Some(&external), &docked), (2020, 100, 800, 600));
+    }
+}
@@ -1,18 +1,57 @@
 pub mod windows_api;
+pub mod monitor;
+#[cfg(feature = "ui")]
 pub mod window_thumbnail;
 pub mod core;
+#[cfg(feature = "ui")]
 pub mod ui;
 pub mod logger;
+pub mod log_redaction;
+pub mod crash;
+pub mod config;
+pub mod paths;
+pub mod profile_archive;
+pub mod update;
+pub mod notification;
+pub mod toast;
+pub mod timers;
+pub mod layout_manager;
+pub mod session;
+pub mod hotkeys;
+pub mod macros;
+pub mod dmenu;
+pub mod deep_link;
+pub mod result_script;
+pub mod system;
+pub mod lookup;
+pub mod page_preview;
+pub mod disk_cache;
+pub mod color;
+pub mod utility;
+pub mod date_calc;
+pub mod time_format;
+pub mod frame_profiler;
+pub mod system_prefs;
+pub mod supervisor;
 pub mod data;
 pub mod filter;
-#[cfg(windows)]
+#[cfg(feature = "ipc")]
 pub mod ipc;
+#[cfg(all(feature = "ipc", feature = "websocket"))]
+pub mod daemon;
+#[cfg(all(feature = "ipc", feature = "websocket"))]
+pub mod http_api;
+#[cfg(feature = "websocket")]
 pub mod websocket_server;
+#[cfg(feature = "websocket")]
 pub mod websocket_types;
+#[cfg(feature = "websocket")]
+pub mod tab_transport;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-support"))]
 pub mod test_helpers;
 
 pub use windows_api::{enumerate_windows, switch_to_window};
+#[cfg(feature = "ui")]
 pub use window_thumbnail::ThumbnailCache;
 pub use core::{LauncherCore, SearchEngine, SearchResult, SearchMode, Action, WindowManager, WindowInfo};
\ No newline at end of file
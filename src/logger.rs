@@ -1,21 +1,22 @@
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Mutex, RwLock};
 use chrono::Local;
 use env_logger::Builder;
+use crate::config::LoggingConfig;
+use crate::log_redaction::Redactor;
 
 lazy_static::lazy_static! {
     static ref LOG_FILE: Mutex<Option<fs::File>> = Mutex::new(None);
+    static ref LOG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+    /// 設定を読み込むまでは既定の設定で伏せる
+    static ref REDACTOR: RwLock<Redactor> = RwLock::new(Redactor::default());
 }
 
 pub fn init_logger() -> Result<(), Box<dyn std::error::Error>> {
-    // Get executable directory
-    let exe_path = std::env::current_exe()?;
-    let exe_dir = exe_path.parent().ok_or("Failed to get executable directory")?;
-    
     // Create logs directory
-    let logs_dir = exe_dir.join("logs");
+    let logs_dir = crate::paths::logs_dir();
     fs::create_dir_all(&logs_dir)?;
     
     // Create log file with timestamp
@@ -32,6 +33,7 @@ pub fn init_logger() -> Result<(), Box<dyn std::error::Error>> {
     
     // Store file handle
     *LOG_FILE.lock().unwrap() = Some(log_file);
+    *LOG_PATH.lock().unwrap() = Some(log_path.clone());
     
     // Initialize env_logger with custom format
     let mut builder = Builder::new();
@@ -46,15 +48,19 @@ pub fn init_logger() -> Result<(), Box<dyn std::error::Error>> {
         let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
         let level = record.level();
         let target = record.target();
-        let args = record.args();
+        // URL のトークンやウィンドウタイトルを伏せてから書く
+        let message = match REDACTOR.read() {
+            Ok(redactor) => redactor.redact(&record.args().to_string()),
+            Err(_) => record.args().to_string(),
+        };
         
         // Write to stdout/stderr
-        let _ = writeln!(buf, "[{}] {} {} - {}", timestamp, level, target, args);
+        let _ = writeln!(buf, "[{}] {} {} - {}", timestamp, level, target, message);
         
         // Also write to file
         if let Ok(mut file_opt) = LOG_FILE.lock() {
             if let Some(ref mut file) = *file_opt {
-                let _ = writeln!(file, "[{}] {} {} - {}", timestamp, level, target, args);
+                let _ = writeln!(file, "[{}] {} {} - {}", timestamp, level, target, message);
                 let _ = file.flush();
             }
         }
@@ -66,7 +72,7 @@ pub fn init_logger() -> Result<(), Box<dyn std::error::Error>> {
     
     // Log initialization info
     log::info!("Logger initialized. Log file: {}", log_path.display());
-    log::info!("Executable directory: {}", exe_dir.display());
+    log::info!("Data directory: {} (portable: {})", crate::paths::data_dir().display(), crate::paths::is_portable());
     
     // Also print to stderr for debugging
     eprintln!("Log file created at: {}", log_path.display());
@@ -75,7 +81,22 @@ pub fn init_logger() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 pub fn get_log_directory() -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let exe_path = std::env::current_exe()?;
-    let exe_dir = exe_path.parent().ok_or("Failed to get executable directory")?;
-    Ok(exe_dir.join("logs"))
+    Ok(crate::paths::logs_dir())
+}
+
+/// 現在書き込み中のログファイルのパス
+pub fn current_log_path() -> Option<PathBuf> {
+    LOG_PATH.lock().ok().and_then(|path| path.clone())
+}
+
+/// 設定の `logging` で伏せる内容を置き換える（設定を読み込んだあとに呼ぶ）
+pub fn set_redaction(config: &LoggingConfig) {
+    let redactor = Redactor::new(config);
+    let enabled = redactor.is_enabled();
+    if let Ok(mut current) = REDACTOR.write() {
+        *current = redactor;
+    }
+    if !enabled {
+        log::warn!("Log redaction is disabled; URLs and window titles are written as-is");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,272 @@
+//! 辞書・翻訳（`def <word>` / `tr <text>`）
+//!
+//! 設定した HTTP API にバックグラウンドで問い合わせ、結果が届いたら検索し直して一覧に反映する。
+//! 結果はメモリにキャッシュし、失敗した場合はしばらくしてから再試行する。
+
+use crate::config::LookupConfig;
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 失敗した問い合わせを再試行するまでの時間
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LookupKind {
+    Definition,
+    Translation,
+}
+
+type LookupKey = (LookupKind, String);
+
+#[derive(Debug, Clone)]
+enum CachedLookup {
+    Found(String),
+    Failed { error: String, at: Instant },
+}
+
+#[derive(Default)]
+struct LookupState {
+    cache: HashMap<LookupKey, CachedLookup>,
+    in_flight: HashSet<LookupKey>,
+}
+
+/// `def` / `tr` キーワードのプロバイダー
+pub struct LookupProvider {
+    config: LookupConfig,
+    state: Arc<Mutex<LookupState>>,
+    updated: Arc<AtomicBool>,
+    fetch: fn(&str) -> Result<String, Box<dyn Error>>,
+}
+
+impl LookupProvider {
+    pub fn new(config: LookupConfig) -> Self {
+        Self::with_fetcher(config, fetch_text)
+    }
+
+    /// HTTP の取得処理を差し替えて作成（テスト用）
+    pub fn with_fetcher(config: LookupConfig, fetch: fn(&str) -> Result<String, Box<dyn Error>>) -> Self {
+        Self {
+            config,
+            state: Arc::new(Mutex::new(LookupState::default())),
+            updated: Arc::new(AtomicBool::new(false)),
+            fetch,
+        }
+    }
+
+    fn request_url(&self, kind: LookupKind, text: &str) -> String {
+        let template = match kind {
+            LookupKind::Definition => &self.config.dictionary_url,
+            LookupKind::Translation => &self.config.translate_url,
+        };
+        template
+            .replace("{query}", &urlencoding::encode(text))
+            .replace("{source}", &self.config.source_lang)
+            .replace("{target}", &self.config.target_lang)
+    }
+
+    /// バックグラウンドで問い合わせを開始（同じ問い合わせが実行中なら何もしない）
+    fn start_lookup(&self, key: LookupKey) {
+        if !self.state.lock().unwrap().in_flight.insert(key.clone()) {
+            return;
+        }
+
+        let url = self.request_url(key.0, &key.1);
+        let pointer = match key.0 {
+            LookupKind::Definition => self.config.dictionary_pointer.clone(),
+            LookupKind::Translation => self.config.translate_pointer.clone(),
+        };
+        let state = Arc::clone(&self.state);
+        let updated = Arc::clone(&self.updated);
+        let fetch = self.fetch;
+
+        let spawned = std::thread::Builder::new().name("lookup".to_string()).spawn(move || {
+            let result = fetch(&url).and_then(|body| extract_text(&body, &pointer));
+            let cached = match result {
+                Ok(text) => CachedLookup::Found(text),
+                Err(e) => {
+                    log::warn!("Lookup failed for {:?}: {}", key, e);
+                    CachedLookup::Failed {
+                        error: e.to_string(),
+                        at: Instant::now(),
+                    }
+                }
+            };
+
+            let mut state = state.lock().unwrap();
+            state.in_flight.remove(&key);
+            state.cache.insert(key, cached);
+            updated.store(true, Ordering::SeqCst);
+        });
+        if let Err(e) = spawned {
+            log::error!("Failed to spawn lookup thread: {}", e);
+            self.state.lock().unwrap().in_flight.clear();
+        }
+    }
+}
+
+/// JSON から JSON Pointer（例: `/0/meanings/0/definitions/0/definition`）の位置の文字列を取り出す
+pub fn extract_text(body: &str, pointer: &str) -> Result<String, Box<dyn Error>> {
+    let json: serde_json::Value = serde_json::from_str(body)?;
+    match json.pointer(pointer) {
+        Some(serde_json::Value::String(text)) if !text.trim().is_empty() => Ok(text.trim().to_string()),
+        Some(_) | None => Err(format!("no result at {}", pointer).into()),
+    }
+}
+
+impl KeywordProvider for LookupProvider {
+    fn name(&self) -> &'static str {
+        "lookup"
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &["def", "tr"]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("def <word>", "Look up a definition"),
+            KeywordHelp::new("tr <text>", "Translate text"),
+        ];
+        HELP
+    }
+
+    fn search(&self, keyword: &str, args: &str) -> Vec<SearchResult> {
+        if args.is_empty() {
+            return Vec::new();
+        }
+
+        let kind = if keyword == "def" { LookupKind::Definition } else { LookupKind::Translation };
+        let key = (kind, args.to_string());
+        let cached = self.state.lock().unwrap().cache.get(&key).cloned();
+
+        let result = match cached {
+            Some(CachedLookup::Found(text)) => SearchResult {
+                title: text.clone(),
+                description: format!("{} · Enter to copy", args),
+                action: Action::CopyToClipboard(text),
+                window_info: None,
+                result_type: ResultType::Command,
+                group: None,
+                metadata: None,
+            },
+            Some(CachedLookup::Failed { error, at }) if at.elapsed() < RETRY_AFTER => SearchResult {
+                title: format!("Could not look up \"{}\"", args),
+                description: format!("{} · Enter to search the web instead", error),
+                action: Action::GoogleSearch(match kind {
+                    LookupKind::Definition => format!("define {}", args),
+                    LookupKind::Translation => format!("translate {}", args),
+                }),
+                window_info: None,
+                result_type: ResultType::Command,
+                group: None,
+                metadata: None,
+            },
+            _ => {
+                self.start_lookup(key);
+                SearchResult {
+                    title: format!("Looking up \"{}\"...", args),
+                    description: "Waiting for the response".to_string(),
+                    action: Action::GoogleSearch(args.to_string()),
+                    window_info: None,
+                    result_type: ResultType::Command,
+                    group: None,
+                    metadata: None,
+                }
+            }
+        };
+
+        vec![result]
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        Err(format!("unknown lookup command: {}", command).into())
+    }
+
+    fn take_updated(&self) -> bool {
+        self.updated.swap(false, Ordering::SeqCst)
+    }
+
+    fn is_pending(&self) -> bool {
+        !self.state.lock().unwrap().in_flight.is_empty()
+    }
+}
+
+#[cfg(feature = "lookup")]
+fn fetch_text(url: &str) -> Result<String, Box<dyn Error>> {
+    let response = ureq::get(url)
+        .set("User-Agent", concat!("my-launcher/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(5))
+        .call()?;
+    Ok(response.into_string()?)
+}
+
+#[cfg(not(feature = "lookup"))]
+fn fetch_text(_url: &str) -> Result<String, Box<dyn Error>> {
+    Err("Lookup support is not enabled (build with --features lookup)".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_for_update(provider: &LookupProvider) {
+        for _ in 0..100 {
+            if provider.take_updated() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("lookup did not finish");
+    }
+
+    #[test]
+    fn test_extract_text() {
+        let body = r#"[{"word":"rust","meanings":[{"definitions":[{"definition":"A reddish-brown oxide."}]}]}]"#;
+        assert_eq!(
+            extract_text(body, "/0/meanings/0/definitions/0/definition").unwrap(),
+            "A reddish-brown oxide."
+        );
+        assert!(extract_text(body, "/0/word/missing").is_err());
+        assert!(extract_text("not json", "/0").is_err());
+    }
+
+    #[test]
+    fn test_request_url() {
+        let provider = LookupProvider::with_fetcher(LookupConfig::default(), |_| Err("offline".into()));
+        let url = provider.request_url(LookupKind::Translation, "good morning");
+        assert!(url.contains("q=good%20morning"));
+        assert!(url.contains("langpair=en|ja"));
+    }
+
+    #[test]
+    fn test_lookup_result_streams_in_after_fetch() {
+        let provider = LookupProvider::with_fetcher(LookupConfig::default(), |_| {
+            Ok(r#"{"responseData":{"translatedText":"おはよう"}}"#.to_string())
+        });
+
+        let pending = provider.search("tr", "good morning");
+        assert!(pending[0].title.starts_with("Looking up"));
+
+        wait_for_update(&provider);
+        assert!(!provider.is_pending());
+        let results = provider.search("tr", "good morning");
+        assert_eq!(results[0].title, "おはよう");
+        assert_eq!(results[0].action, Action::CopyToClipboard("おはよう".to_string()));
+    }
+
+    #[test]
+    fn test_failed_lookup_offers_web_search() {
+        let provider = LookupProvider::with_fetcher(LookupConfig::default(), |_| Err("offline".into()));
+
+        provider.search("def", "rust");
+        wait_for_update(&provider);
+        let results = provider.search("def", "rust");
+        assert_eq!(results[0].title, "Could not look up \"rust\"");
+        assert_eq!(results[0].action, Action::GoogleSearch("define rust".to_string()));
+    }
+}
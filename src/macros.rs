@@ -0,0 +1,424 @@
+//! 1つの結果から一連の操作を実行するマクロ（設定の `macros`）
+//!
+//! ステップは1行に1つの小さな書式で書く:
+//! - `switch <プロセス名> [タイトルに含む文字列]` - ウィンドウに切り替える
+//! - `open <URL>` - 既定のブラウザで開く
+//! - `paste <テキスト>` - クリップボードに入れて Ctrl+V で貼り付ける
+//! - `type <テキスト>` - 1文字ずつ入力する
+//! - `key <キー>` - `Enter` / `Ctrl+Shift+T` の形式のキーを押す
+//! - `wait <時間>` - `500` / `500ms` / `2s` だけ待つ
+//!
+//! ステップの間は `delay_ms` だけ待ち、失敗したステップで中断する。
+
+use crate::config::MacroConfig;
+use crate::hotkeys::{self, Hotkey};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// `wait` で待てる時間の上限
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// 最初のステップの前に、ランチャーのウィンドウが閉じるのを待つ時間
+const LAUNCHER_CLOSE_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroStep {
+    SwitchWindow { process: String, title: Option<String> },
+    OpenUrl(String),
+    Paste(String),
+    Type(String),
+    Keys(Hotkey),
+    Wait(Duration),
+}
+
+/// ステップを1つ解析する
+pub fn parse_step(line: &str) -> Result<MacroStep, String> {
+    let line = line.trim();
+    let (command, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let args = args.trim();
+    let required = |what: &str| {
+        if args.is_empty() {
+            Err(format!("'{}' needs {}", command, what))
+        } else {
+            Ok(args.to_string())
+        }
+    };
+
+    match command.to_lowercase().as_str() {
+        "switch" => {
+            let args = required("a process name")?;
+            let (process, title) = args.split_once(char::is_whitespace).unwrap_or((args.as_str(), ""));
+            let title = title.trim();
+            Ok(MacroStep::SwitchWindow {
+                process: process.to_string(),
+                title: (!title.is_empty()).then(|| title.to_string()),
+            })
+        }
+        "open" => Ok(MacroStep::OpenUrl(required("a URL")?)),
+        "paste" => Ok(MacroStep::Paste(required("text")?)),
+        "type" => Ok(MacroStep::Type(required("text")?)),
+        "key" => Ok(MacroStep::Keys(hotkeys::parse_keys(&required("a key")?)?)),
+        "wait" => {
+            let args = required("a duration")?;
+            let duration = parse_duration(&args).ok_or_else(|| format!("invalid duration '{}'", args))?;
+            if duration > MAX_WAIT {
+                return Err(format!("'{}' is longer than {:?}", args, MAX_WAIT));
+            }
+            Ok(MacroStep::Wait(duration))
+        }
+        "" => Err("empty step".to_string()),
+        other => Err(format!("unknown step '{}'", other)),
+    }
+}
+
+/// `500` / `500ms` / `2s`
+fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim().to_lowercase();
+    if let Some(millis) = text.strip_suffix("ms") {
+        return millis.trim().parse().ok().map(Duration::from_millis);
+    }
+    if let Some(secs) = text.strip_suffix('s') {
+        // 負の値・inf・NaN・大きすぎる値は Duration にできない
+        return secs.trim().parse::<f64>().ok().and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+    }
+    text.parse().ok().map(Duration::from_millis)
+}
+
+/// マクロの全ステップを解析する（誤りがあれば何行目かを含めて返す）
+pub fn parse_macro(config: &MacroConfig) -> Result<Vec<MacroStep>, String> {
+    if config.steps.is_empty() {
+        return Err(format!("macro '{}' has no steps", config.name));
+    }
+    config
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(index, line)| parse_step(line).map_err(|e| format!("step {}: {}", index + 1, e)))
+        .collect()
+}
+
+/// ステップを実行する OS の操作
+pub trait MacroHost {
+    fn switch_window(&self, process: &str, title: Option<&str>) -> Result<(), String>;
+    fn open_url(&self, url: &str) -> Result<(), String>;
+    fn paste(&self, text: &str) -> Result<(), String>;
+    fn type_text(&self, text: &str) -> Result<(), String>;
+    fn press_keys(&self, keys: Hotkey) -> Result<(), String>;
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// ステップを順に実行する（失敗したらそこで止め、何番目のステップかを含めて返す）
+pub fn run_steps(steps: &[MacroStep], delay: Duration, host: &dyn MacroHost) -> Result<(), String> {
+    for (index, step) in steps.iter().enumerate() {
+        if index > 0 {
+            host.sleep(delay);
+        }
+        let result = match step {
+            MacroStep::SwitchWindow { process, title } => host.switch_window(process, title.as_deref()),
+            MacroStep::OpenUrl(url) => host.open_url(url),
+            MacroStep::Paste(text) => host.paste(text),
+            MacroStep::Type(text) => host.type_text(text),
+            MacroStep::Keys(keys) => host.press_keys(*keys),
+            MacroStep::Wait(duration) => {
+                host.sleep(*duration);
+                Ok(())
+            }
+        };
+        result.map_err(|e| format!("step {} failed: {}", index + 1, e))?;
+    }
+    Ok(())
+}
+
+fn running() -> &'static Mutex<Vec<JoinHandle<()>>> {
+    static RUNNING: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+    &RUNNING
+}
+
+/// マクロを別スレッドで実行する（ランチャーのウィンドウが閉じるのを待ってから始める）
+///
+/// 失敗したらトーストで知らせる。プロセスを終える前に `wait_for_running` で終わるのを待つこと。
+pub fn spawn(config: &MacroConfig) -> Result<(), String> {
+    let steps = parse_macro(config)?;
+    let name = config.name.clone();
+    let delay = Duration::from_millis(config.delay_ms);
+    let handle = thread::Builder::new()
+        .name("macro".to_string())
+        .spawn(move || {
+            thread::sleep(LAUNCHER_CLOSE_DELAY);
+            log::info!("Running macro {} ({} steps)", name, steps.len());
+            if let Err(e) = run_steps(&steps, delay, &SystemMacroHost) {
+                log::warn!("Macro {} aborted: {}", name, e);
+                crate::toast::notify(crate::toast::ToastLevel::Error, &format!("Macro {} aborted", name), Some(&e));
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    running().lock().unwrap().push(handle);
+    Ok(())
+}
+
+/// ランチャーが閉じたあと、開く前に前面にあったウィンドウに戻ってテキストを貼り付ける
+///
+/// 失敗したらトーストで知らせる。マクロと同じく `wait_for_running` で終わるのを待つこと。
+pub fn spawn_paste_into(hwnd: isize, text: String) -> Result<(), String> {
+    let handle = thread::Builder::new()
+        .name("paste-into".to_string())
+        .spawn(move || {
+            thread::sleep(LAUNCHER_CLOSE_DELAY);
+            if let Err(e) = paste_into(hwnd, &text, &SystemMacroHost) {
+                log::warn!("Failed to paste into window {}: {}", hwnd, e);
+                crate::toast::notify(crate::toast::ToastLevel::Error, "Could not paste into the previous window", Some(&e));
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    running().lock().unwrap().push(handle);
+    Ok(())
+}
+
+fn paste_into(hwnd: isize, text: &str, host: &SystemMacroHost) -> Result<(), String> {
+    crate::windows_api::switch_to_window(hwnd);
+    // 前面に出られなかったまま入力すると別のウィンドウに送ってしまう
+    thread::sleep(Duration::from_millis(50));
+    if crate::windows_api::foreground_window() != Some(hwnd) {
+        return Err("the previous window could not be brought to the front".to_string());
+    }
+    host.paste(text)
+}
+
+/// 実行中のマクロが終わるまで待つ
+pub fn wait_for_running() {
+    let handles: Vec<_> = running().lock().unwrap().drain(..).collect();
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
+
+/// 実際のウィンドウ・クリップボード・キー入力を使う `MacroHost`
+pub struct SystemMacroHost;
+
+#[cfg(windows)]
+impl MacroHost for SystemMacroHost {
+    fn switch_window(&self, process: &str, title: Option<&str>) -> Result<(), String> {
+        let window = crate::windows_api::enumerate_windows()
+            .into_iter()
+            .find(|window| {
+                window.process_name.eq_ignore_ascii_case(process)
+                    && title.map_or(true, |title| window.title.to_lowercase().contains(&title.to_lowercase()))
+            })
+            .ok_or_else(|| format!("no window of {} is open", process))?;
+        crate::windows_api::switch_to_window(window.hwnd);
+        // 前面に出られなかったまま入力すると別のウィンドウに送ってしまう
+        thread::sleep(Duration::from_millis(50));
+        if unsafe { winapi::um::winuser::GetForegroundWindow() } as isize != window.hwnd {
+            return Err(format!("could not bring {} to the front", window.title));
+        }
+        Ok(())
+    }
+
+    fn open_url(&self, url: &str) -> Result<(), String> {
+        open::that(url).map_err(|e| e.to_string())
+    }
+
+    fn paste(&self, text: &str) -> Result<(), String> {
+        set_clipboard_text(text)?;
+        self.press_keys(Hotkey { modifiers: hotkeys::MOD_CONTROL, key: 'V' as u32 })
+    }
+
+    fn type_text(&self, text: &str) -> Result<(), String> {
+        use winapi::um::winuser::{KEYEVENTF_KEYUP, KEYEVENTF_UNICODE};
+
+        let inputs: Vec<_> = text
+            .encode_utf16()
+            .flat_map(|unit| [key_input(0, unit, KEYEVENTF_UNICODE), key_input(0, unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP)])
+            .collect();
+        send_inputs(inputs)
+    }
+
+    fn press_keys(&self, keys: Hotkey) -> Result<(), String> {
+        use winapi::um::winuser::{KEYEVENTF_KEYUP, VK_CONTROL, VK_LWIN, VK_MENU, VK_SHIFT};
+
+        let modifiers: Vec<u16> = [
+            (hotkeys::MOD_CONTROL, VK_CONTROL),
+            (hotkeys::MOD_ALT, VK_MENU),
+            (hotkeys::MOD_SHIFT, VK_SHIFT),
+            (hotkeys::MOD_WIN, VK_LWIN),
+        ]
+        .iter()
+        .filter(|(flag, _)| keys.modifiers & flag != 0)
+        .map(|(_, vk)| *vk as u16)
+        .collect();
+
+        let mut inputs: Vec<_> = modifiers.iter().map(|vk| key_input(*vk, 0, 0)).collect();
+        inputs.push(key_input(keys.key as u16, 0, 0));
+        inputs.push(key_input(keys.key as u16, 0, KEYEVENTF_KEYUP));
+        inputs.extend(modifiers.iter().rev().map(|vk| key_input(*vk, 0, KEYEVENTF_KEYUP)));
+        send_inputs(inputs)
+    }
+}
+
+#[cfg(not(windows))]
+impl MacroHost for SystemMacroHost {
+    fn switch_window(&self, _process: &str, _title: Option<&str>) -> Result<(), String> {
+        Err("macros are only supported on Windows".to_string())
+    }
+
+    fn open_url(&self, url: &str) -> Result<(), String> {
+        open::that(url).map_err(|e| e.to_string())
+    }
+
+    fn paste(&self, _text: &str) -> Result<(), String> {
+        Err("macros are only supported on Windows".to_string())
+    }
+
+    fn type_text(&self, _text: &str) -> Result<(), String> {
+        Err("macros are only supported on Windows".to_string())
+    }
+
+    fn press_keys(&self, _keys: Hotkey) -> Result<(), String> {
+        Err("macros are only supported on Windows".to_string())
+    }
+}
+
+#[cfg(windows)]
+fn key_input(vk: u16, scan: u16, flags: u32) -> winapi::um::winuser::INPUT {
+    use winapi::um::winuser::{INPUT, INPUT_KEYBOARD, KEYBDINPUT};
+
+    let mut input: INPUT = unsafe { std::mem::zeroed() };
+    input.type_ = INPUT_KEYBOARD;
+    unsafe {
+        *input.u.ki_mut() = KEYBDINPUT { wVk: vk, wScan: scan, dwFlags: flags, time: 0, dwExtraInfo: 0 };
+    }
+    input
+}
+
+#[cfg(windows)]
+fn send_inputs(mut inputs: Vec<winapi::um::winuser::INPUT>) -> Result<(), String> {
+    use winapi::um::winuser::{SendInput, INPUT};
+
+    let sent = unsafe { SendInput(inputs.len() as u32, inputs.as_mut_ptr(), std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        return Err(format!("SendInput failed: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+/// クリップボードにテキストを入れる
+#[cfg(windows)]
+fn set_clipboard_text(text: &str) -> Result<(), String> {
+    use std::ptr;
+    use winapi::um::winbase::{GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use winapi::um::winuser::{CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData, CF_UNICODETEXT};
+
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        if OpenClipboard(ptr::null_mut()) == 0 {
+            return Err(format!("OpenClipboard failed: {}", std::io::Error::last_os_error()));
+        }
+        let result = (|| {
+            EmptyClipboard();
+            let memory = GlobalAlloc(GMEM_MOVEABLE, wide.len() * std::mem::size_of::<u16>());
+            if memory.is_null() {
+                return Err("GlobalAlloc failed".to_string());
+            }
+            let target = GlobalLock(memory) as *mut u16;
+            ptr::copy_nonoverlapping(wide.as_ptr(), target, wide.len());
+            GlobalUnlock(memory);
+            // 成功したらメモリはクリップボードのものになる
+            if SetClipboardData(CF_UNICODETEXT, memory).is_null() {
+                GlobalFree(memory);
+                return Err(format!("SetClipboardData failed: {}", std::io::Error::last_os_error()));
+            }
+            Ok(())
+        })();
+        CloseClipboard();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct RecordingHost {
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl MacroHost for RecordingHost {
+        fn switch_window(&self, process: &str, title: Option<&str>) -> Result<(), String> {
+            self.calls.borrow_mut().push(format!("switch {} {:?}", process, title));
+            if process == "missing.exe" {
+                return Err("no window".to_string());
+            }
+            Ok(())
+        }
+        fn open_url(&self, url: &str) -> Result<(), String> {
+            self.calls.borrow_mut().push(format!("open {}", url));
+            Ok(())
+        }
+        fn paste(&self, text: &str) -> Result<(), String> {
+            self.calls.borrow_mut().push(format!("paste {}", text));
+            Ok(())
+        }
+        fn type_text(&self, text: &str) -> Result<(), String> {
+            self.calls.borrow_mut().push(format!("type {}", text));
+            Ok(())
+        }
+        fn press_keys(&self, keys: Hotkey) -> Result<(), String> {
+            self.calls.borrow_mut().push(format!("key {:x}+{:x}", keys.modifiers, keys.key));
+            Ok(())
+        }
+        fn sleep(&self, duration: Duration) {
+            self.calls.borrow_mut().push(format!("sleep {}", duration.as_millis()));
+        }
+    }
+
+    #[test]
+    fn test_parse_steps() {
+        assert_eq!(
+            parse_step("switch Code.exe my-launcher"),
+            Ok(MacroStep::SwitchWindow { process: "Code.exe".to_string(), title: Some("my-launcher".to_string()) })
+        );
+        assert_eq!(parse_step("paste  Good morning; see you "), Ok(MacroStep::Paste("Good morning; see you".to_string())));
+        assert_eq!(parse_step("key Enter"), Ok(MacroStep::Keys(Hotkey { modifiers: 0, key: 0x0D })));
+        assert_eq!(parse_step("wait 2s"), Ok(MacroStep::Wait(Duration::from_secs(2))));
+        assert_eq!(parse_step("WAIT 250"), Ok(MacroStep::Wait(Duration::from_millis(250))));
+        assert!(parse_step("wait 5m").is_err());
+        assert!(parse_step("wait 120s").is_err());
+        assert!(parse_step("wait infs").is_err());
+        assert!(parse_step("wait 1e30s").is_err());
+        assert!(parse_step("wait -1s").is_err());
+        assert!(parse_step("paste").is_err());
+        assert!(parse_step("click 10 20").is_err());
+
+        let config = MacroConfig {
+            name: "Broken".to_string(),
+            steps: vec!["switch slack.exe".to_string(), "press enter".to_string()],
+            delay_ms: 100,
+        };
+        assert_eq!(parse_macro(&config), Err("step 2: unknown step 'press'".to_string()));
+    }
+
+    #[test]
+    fn test_run_aborts_on_failure() {
+        let steps = vec![
+            parse_step("switch slack.exe").unwrap(),
+            parse_step("paste hello").unwrap(),
+            parse_step("key enter").unwrap(),
+        ];
+        let host = RecordingHost::default();
+        assert_eq!(run_steps(&steps, Duration::from_millis(100), &host), Ok(()));
+        assert_eq!(
+            *host.calls.borrow(),
+            vec!["switch slack.exe None", "sleep 100", "paste hello", "sleep 100", "key 0+d"]
+        );
+
+        let steps = vec![parse_step("switch missing.exe").unwrap(), parse_step("key enter").unwrap()];
+        let host = RecordingHost::default();
+        assert_eq!(run_steps(&steps, Duration::ZERO, &host), Err("step 1 failed: no window".to_string()));
+        assert_eq!(host.calls.borrow().len(), 1);
+    }
+}
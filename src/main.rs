@@ -2,19 +2,65 @@ use eframe::egui;
 use my_launcher::core::{
     launcher::LauncherCore,
     search_engine::{SearchMode, SearchResult},
-    window_manager::WindowsApiManager,
+    window_manager::{WindowInfo, WindowManager, WindowsApiManager},
     BrowserSearchEngine,
     native_messaging::TabManager,
 };
 use my_launcher::ui::alt_tab_grid::{AltTabGrid, GridItem};
 use my_launcher::ui::browser_list::BrowserList;
+use my_launcher::ui::{empty_state, filter_chips, sort_selector, privacy_toggle, workspace_chips, toast_window, tab_overview, frame_overlay, motion, settings_panel, help_popup, theme, hint_bar, result_details, monitor_map, export_dialog, confirm_overlay, diagnostics, extension_sessions, recent_windows};
+use my_launcher::ui::drop_down::DropDown;
+use my_launcher::core::result_export::{self, ExportFormat};
+use my_launcher::core::action_rewriter::AppHandlerRewriter;
+use my_launcher::core::extension_commands;
+use my_launcher::core::help;
+use my_launcher::core::auto_hide::AutoHide;
+use my_launcher::ui::export_dialog::ExportTarget;
+use my_launcher::ui::settings_panel::SettingsAction;
+use my_launcher::ui::tab_overview::OverviewAction;
+use my_launcher::core::tab_overview::TabGrouping;
+use my_launcher::core::empty_state::{EmptyState, EmptyTarget};
+use my_launcher::core::result_cache::{self, ResultCache};
+use my_launcher::config::{Presentation, Theme};
+use my_launcher::system_prefs::{self, MotionPrefs};
+use my_launcher::supervisor::{ServiceState, ServiceStatuses, Supervisor};
+use my_launcher::frame_profiler::{FrameProfiler, Span};
 use my_launcher::window_thumbnail::ThumbnailCache;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::Receiver;
+use std::collections::HashSet;
 use std::error::Error;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use std::thread;
-use tokio::runtime::Runtime;
-use my_launcher::websocket_server::WebSocketServer;
+use my_launcher::tab_transport;
+use my_launcher::daemon::{self, WEBSOCKET_PORT};
+use my_launcher::crash;
+use my_launcher::session::{self, Session, SessionStore};
+use my_launcher::toast::{self, ToastLevel, ToastQueue};
+use my_launcher::config::{LauncherConfig, ScriptsConfig};
+use my_launcher::result_script::ResultScripts;
+use my_launcher::update::{self, ReleaseInfo};
+use my_launcher::profile_archive;
+use my_launcher::core::search_engine::{Action, BookmarkSource, ResultMetadata, ResultType};
+use my_launcher::core::chrome_window_map::{BrowserWindowKind, ChromeWindowMap};
+use my_launcher::core::result_filter::{self, QuickFilter, ResultFilter};
+use my_launcher::core::result_sort::SortOrder;
+use my_launcher::core::native_messaging::ChromeCommand;
+use my_launcher::core::extension_sessions::SessionControl;
+use my_launcher::data::browser_provider::{BookmarkEdit, BrowserConfig};
+use my_launcher::data::usage;
+use my_launcher::page_preview::{self, PreviewStore};
+use my_launcher::disk_cache;
+use my_launcher::ui::page_preview::PreviewTextures;
+use my_launcher::windows_api::{self, ProcessLaunchInfo};
+use my_launcher::timers::{self, TimerStore};
+use my_launcher::macros;
+use my_launcher::dmenu;
+use my_launcher::deep_link::{self, DeepLink};
+use my_launcher::ipc::IpcMessage;
+#[cfg(windows)]
+use my_launcher::daemon::{DaemonClient, DaemonWindowManager};
 
 fn setup_custom_fonts(ctx: &egui::Context) -> Result<(), Box<dyn Error>> {
     let mut fonts = egui::FontDefinitions::default();
@@ -66,8 +112,8 @@ fn setup_custom_fonts(ctx: &egui::Context) -> Result<(), Box<dyn Error>> {
     }
 }
 
-// SearchResultをGridItemとして扱うためのラッパー
-struct SearchResultItem<'a>(&'a SearchResult);
+// SearchResultをGridItemとして扱うためのラッパー（ブラウザのウィンドウはアクティブなタブのファビコン付き）
+struct SearchResultItem<'a>(&'a SearchResult, Option<egui::TextureHandle>);
 
 impl<'a> GridItem for SearchResultItem<'a> {
     fn title(&self) -> &str {
@@ -75,7 +121,14 @@ impl<'a> GridItem for SearchResultItem<'a> {
     }
 
     fn description(&self) -> &str {
-        &self.0.description
+        match &self.0.window_info {
+            Some(window) => &window.process_name,
+            None => &self.0.description,
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.0.window_info.as_ref().map(|window| window.class_name.as_str())
     }
 
     fn hwnd(&self) -> isize {
@@ -83,20 +136,18 @@ impl<'a> GridItem for SearchResultItem<'a> {
     }
 
     fn id(&self) -> String {
-        match &self.0.action {
-            my_launcher::core::search_engine::Action::SwitchWindow(hwnd) => hwnd.to_string(),
-            my_launcher::core::search_engine::Action::GoogleSearch(query) => format!("google:{}", query),
-            my_launcher::core::search_engine::Action::OpenBookmark(url) => format!("bookmark:{}", url),
-            my_launcher::core::search_engine::Action::OpenHistory(url) => format!("history:{}", url),
-            my_launcher::core::search_engine::Action::SwitchToTab { tab_id, window_id } => format!("tab:{}:{}", tab_id, window_id),
-        }
+        self.0.action.id()
+    }
+
+    fn icon(&self) -> Option<&egui::TextureHandle> {
+        self.1.as_ref()
     }
 }
 
 struct LauncherApp {
     input_text: String,
     mode: SearchMode,
-    core: LauncherCore<BrowserSearchEngine, WindowsApiManager>,
+    core: LauncherCore<BrowserSearchEngine, dyn WindowManager>,
     search_results: Vec<SearchResult>,
     grid: AltTabGrid,
     browser_list: BrowserList,
@@ -105,40 +156,174 @@ struct LauncherApp {
     last_input_change: Option<Instant>,
     pending_search_text: Option<String>,
     debounce_duration: Duration,
+    /// 最近の検索結果（デバウンスを待たずに表示する）
+    result_cache: ResultCache,
+    /// 表示中の結果が `result_cache` から出したものか（検索し直したら選択を引き継ぐ）
+    warm_results: bool,
     tab_manager: Arc<TabManager>,
     status_message: Option<String>,
     status_timestamp: Option<Instant>,
+    crash_report: Option<PathBuf>,
+    available_update: Arc<Mutex<Option<ReleaseInfo>>>,
+    chrome_windows: ChromeWindowMap,
+    /// 名前を変更中のブックマーク (URL, 新しい名前)
+    bookmark_rename: Option<(String, String)>,
+    /// Browserモードのフィルターチップの状態
+    result_filter: ResultFilter,
+    /// Alt+1..5 で結果を 1 種類に絞る一時的な絞り込みと、絞り込む前の結果
+    quick_filter: Option<QuickFilter>,
+    unfiltered_results: Vec<SearchResult>,
+    /// Browserモードの結果の並べ替え（Ctrl+S で切り替え）
+    sort_order: SortOrder,
+    /// プライバシーモードか（検索のたびに更新する）
+    privacy_active: bool,
+    /// ワークスペースのタグ（Windowsモードの切り替えチップ）と選んでいるワークスペース
+    workspace_tags: Vec<String>,
+    active_workspace: Option<String>,
+    /// ランチャーを開く前に前面にあったウィンドウ（Shift+Enter で貼り付ける先）
+    previous_window: Option<isize>,
+    /// 設定パネルを開いているか
+    settings_open: bool,
+    /// 設定パネルでプロファイルを書き出す・読み込むファイル
+    profile_path: String,
+    /// クエリの書き方の早見表を開いているか（`?` / F1）
+    help_open: bool,
+    help_filter: String,
+    /// 次のフレームで早見表の絞り込みの入力欄にフォーカスする
+    help_focus: bool,
+    /// 検索元とサーバーの状態の一覧を開いているか（ヘッダーの点をクリック）
+    diagnostics_open: bool,
+    /// 接続中の拡張機能の一覧を開いているか（ヘッダーのサービスの点をクリック）
+    extensions_open: bool,
+    /// タブ一覧が変わったことの通知（開いている間に変わったら検索をやり直す）
+    tab_changes: Option<Receiver<u64>>,
+    /// 選択中の結果の詳細を表示しているか（Ctrl+I）
+    details_open: bool,
+    /// 詳細に出すウィンドウのプロセスのコマンドラインと作業フォルダ（選んだウィンドウが変わったら読み直す）
+    launch_info: Option<(isize, ProcessLaunchInfo)>,
+    /// 検索結果の書き出しダイアログを開いているか（Ctrl+Shift+E）
+    export_open: bool,
+    export_format: ExportFormat,
+    /// 開いているタブの一覧を表示しているか（「Open tabs overview」）
+    tabs_overview_open: bool,
+    tab_grouping: TabGrouping,
+    tabs_overview_filter: String,
+    profiler: FrameProfiler,
+    /// フレームごとの処理時間を表示するか（F12）
+    frame_overlay: bool,
+    /// アニメーション効果・省電力の状態に応じた動きの設定
+    motion: MotionPrefs,
+    theme: Theme,
+    /// 文字・グリッド・余白の倍率（設定の `appearance.ui_scale` か Windows のテキストのサイズ）
+    ui_scale: f32,
+    /// 画面下部のヒントバーを表示するか
+    hint_bar: bool,
+    /// 上端から降りてくる表示のとき、その動き
+    drop_down: Option<DropDown>,
+    /// フォーカスを失ったら閉じるか（デーモン・ホットキーから開いたときと上端から降りてくる表示のとき）
+    auto_hide: Option<AutoHide>,
+    /// WebSocket サーバーなど、監視しているサーバーの状態（ヘッダーに表示）
+    services: ServiceStatuses,
+    /// `--dmenu` のとき、選んだ行を入れて閉じる先
+    dmenu_selection: Option<Arc<Mutex<Option<String>>>>,
+    /// 表示する前に結果を変換するスクリプト（設定の `scripts`）
+    result_scripts: ResultScripts,
+    /// 検索語が空のときに表示する内容（設定の `empty_state`）
+    empty_state: EmptyState,
+    /// よく開くブックマークのプレビュー画像（設定の `previews` で有効なときだけ）
+    previews: Option<PreviewStore>,
+    preview_textures: PreviewTextures,
+    /// プレビューを取得する URL（起動時に開いた記録から選ぶ）
+    frequent_urls: HashSet<String>,
+    /// グリッドのブラウザのウィンドウに重ねるファビコン（設定の `grid.favicons`）
+    favicons: Option<PreviewStore>,
+    favicon_textures: PreviewTextures,
 }
 
 impl LauncherApp {
     fn new() -> Self {
-        let tab_manager = Arc::new(TabManager::new());
-        Self::new_with_tab_manager(tab_manager)
+        let config = LauncherConfig::default();
+        let tab_manager = Arc::new(config.tabs.tab_manager());
+        Self::new_with_tab_manager(tab_manager, Arc::new(WindowsApiManager::new()), &config)
     }
     
-    fn new_with_tab_manager(tab_manager: Arc<TabManager>) -> Self {
-        let window_manager = Arc::new(WindowsApiManager);
-        let search_engine = BrowserSearchEngine::new_with_tab_manager(Arc::clone(&tab_manager));
-        let mut core = LauncherCore::new(search_engine, window_manager);
+    fn new_with_tab_manager(tab_manager: Arc<TabManager>, window_manager: Arc<dyn WindowManager>, config: &LauncherConfig) -> Self {
+        let search_engine = BrowserSearchEngine::new_with_tab_manager(Arc::clone(&tab_manager))
+            .with_config(config)
+            .with_previous_session(load_previous_session());
+        let mut core = LauncherCore::new(search_engine, window_manager)
+            .with_rewriter(Box::new(AppHandlerRewriter::new(config.open_in_app.clone())));
         
         // 初期状態でウィンドウ情報を更新
         core.refresh_windows();
+        let workspaces = core.search_engine().workspaces();
+        let previews = PreviewStore::from_config(&config.previews);
+        let frequent_urls = match &previews {
+            Some(_) => page_preview::frequent_urls(&core.search_engine().usage_store().load().unwrap_or_default(), config.previews.min_opens),
+            None => HashSet::new(),
+        };
         
         let mut app = Self {
             input_text: String::new(),
             mode: SearchMode::Windows, // Windowsモードから開始
             core,
             search_results: Vec::new(),
-            grid: AltTabGrid::new(),
-            browser_list: BrowserList::new(),
+            grid: AltTabGrid::with_config(&config.grid),
+            browser_list: BrowserList::with_columns(config.appearance.list_columns.clone()),
             thumbnail_cache: ThumbnailCache::new(),
             first_frame: true,
             last_input_change: None,
             pending_search_text: None,
             debounce_duration: Duration::from_millis(500), // 500msのデバウンス（調整可能）
+            result_cache: ResultCache::new(config.sources.warm_results),
+            warm_results: false,
             tab_manager,
             status_message: None,
             status_timestamp: None,
+            crash_report: None,
+            available_update: Arc::new(Mutex::new(None)),
+            chrome_windows: ChromeWindowMap::with_browsers(BrowserWindowKind::enabled(&BrowserConfig::default())),
+            bookmark_rename: None,
+            result_filter: ResultFilter::default(),
+            quick_filter: None,
+            unfiltered_results: Vec::new(),
+            sort_order: SortOrder::default(),
+            privacy_active: false,
+            workspace_tags: workspaces.tags(),
+            active_workspace: workspaces.active,
+            previous_window: None,
+            settings_open: false,
+            profile_path: my_launcher::paths::exports_dir().join("profile.json").display().to_string(),
+            help_open: false,
+            help_filter: String::new(),
+            help_focus: false,
+            diagnostics_open: false,
+            extensions_open: false,
+            tab_changes: None,
+            details_open: false,
+            launch_info: None,
+            export_open: false,
+            export_format: ExportFormat::default(),
+            tabs_overview_open: false,
+            tab_grouping: TabGrouping::default(),
+            tabs_overview_filter: String::new(),
+            profiler: FrameProfiler::new(),
+            frame_overlay: config.debug.frame_overlay,
+            motion: MotionPrefs::new(config.motion.clone()),
+            theme: config.appearance.theme,
+            ui_scale: system_prefs::ui_scale(config.appearance.ui_scale),
+            hint_bar: config.appearance.hint_bar,
+            drop_down: None,
+            auto_hide: None,
+            services: ServiceStatuses::new(),
+            dmenu_selection: None,
+            result_scripts: ResultScripts::load(&config.scripts),
+            empty_state: EmptyState::default(),
+            previews,
+            preview_textures: PreviewTextures::default(),
+            frequent_urls,
+            favicons: config.grid.favicons.then(PreviewStore::favicons),
+            favicon_textures: PreviewTextures::with_size((32, 32)),
         };
         
         // 初期表示のために検索を実行
@@ -146,11 +331,40 @@ impl LauncherApp {
         app
     }
 
+    /// `--dmenu`: 標準入力の行だけを候補にし、選んだ行を `selection` に入れて閉じる
+    fn new_dmenu(lines: Vec<String>, selection: Arc<Mutex<Option<String>>>, config: &LauncherConfig) -> Self {
+        let window_manager: Arc<dyn WindowManager> = Arc::new(WindowsApiManager::new());
+        let mut app = Self::new_with_tab_manager(Arc::new(TabManager::new()), Arc::clone(&window_manager), config);
+        app.core = LauncherCore::new(dmenu::search_engine(lines), window_manager);
+        app.dmenu_selection = Some(selection);
+        app.result_scripts = ResultScripts::load(&ScriptsConfig::default());
+        app.start_with(Some(SearchMode::Browser), None);
+        app
+    }
+
+    /// 外部から指定されたモードと入力で始める（`--mode` / `--query`）
+    fn start_with(&mut self, mode: Option<SearchMode>, query: Option<String>) {
+        if let Some(mode) = mode {
+            self.mode = mode;
+        }
+        if let Some(query) = query {
+            self.input_text = query;
+        }
+        self.force_search();
+    }
+
     fn switch_mode(&mut self) {
+        // dmenu では候補の行だけを表示する
+        if self.dmenu_selection.is_some() {
+            return;
+        }
         self.mode = match self.mode {
-            SearchMode::Browser => SearchMode::Windows,
             SearchMode::Windows => SearchMode::Browser,
+            SearchMode::Browser => SearchMode::Tabs,
+            // General は --mode などで指定したときだけ使い、Tab では Windows に戻る
+            SearchMode::Tabs | SearchMode::General => SearchMode::Windows,
         };
+        self.quick_filter = None;
         self.grid.selected_index = 0;
         self.browser_list.selected_index = 0;
         // モード切り替え時は即座に検索
@@ -158,14 +372,72 @@ impl LauncherApp {
     }
 
     fn update_search(&mut self) {
+        let started = Instant::now();
         let old_query = self.search_results.first().map(|r| r.title.clone());
-        self.search_results = self.core.search(&self.input_text, self.mode);
+        let privacy_active = self.core.search_engine().update_privacy();
+        // プライバシーモードに入ったら、それまでに覚えた結果も忘れる
+        if privacy_active && !self.privacy_active {
+            self.result_cache.clear();
+        }
+        self.privacy_active = privacy_active;
+        let results = self.core.search(&self.input_text, self.mode);
+        let results = self.result_scripts.apply(self.mode, &self.input_text, results);
+        let results = self.sorted(results);
+        // 覚えておいた結果を表示していたら、選んでいた行を新しい結果でも選ぶ
+        let warm_selection = if std::mem::take(&mut self.warm_results) {
+            result_cache::reconcile_selection(&self.search_results, self.browser_list.selected_index, &results)
+        } else {
+            None
+        };
+        if matches!(self.mode, SearchMode::Browser | SearchMode::General) && self.dmenu_selection.is_none() && !self.privacy_active {
+            self.result_cache.insert(self.mode, &self.input_text, &results);
+        }
+        self.search_results = results;
+        // よく開くブックマークのプレビューは詳細を開く前から取得しておく
+        if let Some(previews) = &self.previews {
+            for url in self.search_results.iter().filter_map(|result| preview_url(result, &self.frequent_urls)) {
+                previews.request(url);
+            }
+        }
+        self.empty_state = if self.input_text.trim().is_empty() && self.dmenu_selection.is_none() {
+            self.core.search_engine().empty_state(self.mode)
+        } else {
+            EmptyState::default()
+        };
+        self.chrome_windows.update(&self.tab_manager.snapshot(), self.core.get_cached_windows());
+        
+        // 拡張機能が接続されていない場合、タブの選択まではできないことを示す
+        if !self.tab_manager.is_extension_connected() {
+            for result in self.search_results.iter_mut().filter(|r| r.result_type.is_tab()) {
+                result.description.push_str(" (extension offline: focuses the window only)");
+            }
+        }
+        // 更新のお知らせは Browser/General モードの一覧だけに出す（dmenu の選択肢には混ぜない）
+        let shows_update = matches!(self.mode, SearchMode::Browser | SearchMode::General) && self.dmenu_selection.is_none();
+        if let Some(release) = self.available_update.lock().unwrap().as_ref().filter(|_| shows_update) {
+            self.search_results.insert(0, SearchResult {
+                title: format!("Update available: v{}", release.version),
+                description: "Run `my-launcher self-update` to install, or press Enter to open the release page".to_string(),
+                action: Action::OpenUrl(release.page_url.clone()),
+                window_info: None,
+                result_type: ResultType::Update,
+                group: None,
+                metadata: None,
+            });
+        }
+        // Alt+1..5 の絞り込みは絞り込む前の結果を残しておき、キーを押すたびにそこから選び直す
+        self.unfiltered_results = std::mem::take(&mut self.search_results);
+        self.search_results = QuickFilter::apply(self.quick_filter, &self.unfiltered_results);
         let new_query = self.search_results.first().map(|r| r.title.clone());
         
         // 検索クエリが変わった場合、BrowserListをリセット
         if old_query != new_query {
             self.browser_list.reset_for_new_search();
         }
+        if let Some(index) = warm_selection {
+            self.browser_list.selected_index = index;
+            self.grid.selected_index = index;
+        }
         
         if self.grid.selected_index >= self.search_results.len() && !self.search_results.is_empty() {
             self.grid.selected_index = self.search_results.len() - 1;
@@ -173,8 +445,53 @@ impl LauncherApp {
         if self.browser_list.selected_index >= self.search_results.len() && !self.search_results.is_empty() {
             self.browser_list.selected_index = self.search_results.len() - 1;
         }
+        self.profiler.record(Span::Search, started.elapsed());
     }
     
+    /// Alt+1..5: 結果を 1 種類に絞る（同じキーで戻す）。検索し直さずに直前の結果から選ぶ
+    fn toggle_quick_filter(&mut self, filter: QuickFilter) {
+        self.quick_filter = QuickFilter::toggle(self.quick_filter, filter);
+        self.search_results = QuickFilter::apply(self.quick_filter, &self.unfiltered_results);
+        self.grid.selected_index = 0;
+        self.browser_list.selected_index = 0;
+        self.browser_list.reset_for_new_search();
+    }
+
+    /// Browserモードなら選んだ並べ替えを適用する
+    fn sorted(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        if self.mode == SearchMode::Browser {
+            self.sort_order.apply(results)
+        } else {
+            results
+        }
+    }
+
+    /// タブ一覧の変更を受け取る（変わったら再描画させて `refresh_on_tab_changes` で検索し直す）
+    fn watch_tabs(&mut self, ctx: &egui::Context) {
+        let ctx = ctx.clone();
+        self.tab_changes = Some(self.tab_manager.subscribe(move || ctx.request_repaint()));
+        // 最近閉じたタブは開いたときに取り直す（閉じてすぐ開き直すことが多いため）
+        self.tab_manager.queue_command(ChromeCommand::GetRecentlyClosed);
+    }
+
+    /// 前のフレームからタブ一覧が変わっていて、今の検索にタブが含まれるならやり直す
+    fn refresh_on_tab_changes(&mut self) {
+        let Some(changes) = &self.tab_changes else {
+            return;
+        };
+        // 続けて届いた変更はまとめて 1 回にする
+        if changes.try_iter().count() == 0 {
+            return;
+        }
+        if !matches!(self.mode, SearchMode::Browser | SearchMode::Tabs) || self.pending_search_text.is_some() {
+            return;
+        }
+        log::debug!("Tabs changed, refreshing results");
+        // 選んでいた行は新しい結果でも選んだままにする
+        self.warm_results = true;
+        self.update_search();
+    }
+
     fn force_search(&mut self) {
         // デバウンスをキャンセルして即座に検索
         self.last_input_change = None;
@@ -183,11 +500,58 @@ impl LauncherApp {
     }
 
     fn execute_selected(&mut self, ctx: &egui::Context) {
+        if let Some(selection) = &self.dmenu_selection {
+            // 選んだ行を返して閉じる（実行はしない）
+            if let Some(Action::ProviderCommand { command, .. }) = self.search_results.get(self.grid.selected_index).map(|result| &result.action) {
+                *selection.lock().unwrap() = Some(command.clone());
+            }
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            return;
+        }
+        // 取り消せない操作は、何が起きるかを確認してから実行する
+        if let Some(action) = self.search_results.get(self.grid.selected_index).map(|result| result.action.clone()) {
+            if self.core.request_confirmation(&action) {
+                return;
+            }
+        }
         if let Some(result) = self.search_results.get(self.grid.selected_index) {
+            // 時間帯の提案のために開いたものを記録する（プライバシーモードでは記録しない）
+            if let Some(target) = usage::target_for(result).filter(|_| !self.privacy_active) {
+                let usage = self.core.search_engine().usage_store();
+                if let Err(e) = usage.record(target, &result.title, chrono::Local::now()) {
+                    log::warn!("Failed to record usage to {}: {}", usage.path().display(), e);
+                }
+            }
+            // 検索語が空のときに最近の検索語として出す
+            let recent_queries = self.core.search_engine().recent_queries_store();
+            if self.privacy_active {
+                log::debug!("Privacy mode: not recording the query");
+            } else if let Err(e) = recent_queries.record(&self.input_text) {
+                log::warn!("Failed to record the query to {}: {}", recent_queries.path().display(), e);
+            }
+            
+            // アプリで開く URL のタブは、タブに切り替えずにアプリで開く
+            if let (Action::SwitchToTab { .. }, Some(metadata)) = (&result.action, &result.metadata) {
+                if let Some(action) = self.core.rewrite(&Action::OpenUrl(metadata.url().to_string())) {
+                    self.core.execute_action(&action);
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    return;
+                }
+            }
+            
             // Special handling for tab switching
             match &result.action {
+                my_launcher::core::search_engine::Action::SwitchToTab { window_id, .. }
+                    if !self.tab_manager.is_extension_connected() =>
+                {
+                    // 拡張機能が接続されていないので、タブがあるウィンドウを前面に出すだけにする
+                    log::warn!("Chrome extension is not connected; tab selection is unavailable");
+                    match self.chrome_windows.hwnd_for(*window_id) {
+                        Some(hwnd) => self.core.execute_action(&Action::SwitchWindow(hwnd)),
+                        None => self.core.execute_action(&result.action),
+                    }
+                }
                 my_launcher::core::search_engine::Action::SwitchToTab { tab_id, window_id } => {
-                    use my_launcher::core::native_messaging::ChromeCommand;
                     log::info!("=== TAB SWITCH INITIATED ===");
                     log::info!("Tab ID: {}, Window ID: {}", tab_id, window_id);
                     log::info!("Selected result: {} - {}", result.title, result.description);
@@ -196,16 +560,233 @@ impl LauncherApp {
                     self.status_message = Some(format!("Switching to tab: {}", result.title));
                     self.status_timestamp = Some(Instant::now());
                     
+                    // 先にタブがあるウィンドウを前面に出す（別のChromeウィンドウが一瞬前面に出るのを防ぐ）
+                    match self.chrome_windows.hwnd_for(*window_id) {
+                        Some(hwnd) => {
+                            log::info!("Bringing Chrome window {} (hwnd={}) to front", window_id, hwnd);
+                            self.core.execute_action(&Action::SwitchWindow(hwnd));
+                        }
+                        None => {
+                            log::info!("No known HWND for Chrome window {}, bringing any Chrome window to front", window_id);
+                            self.core.execute_action(&result.action);
+                        }
+                    }
+                    
                     // Queue the command
-                    self.tab_manager.queue_command(ChromeCommand::SwitchToTab {
-                        tab_id: *tab_id,
-                        window_id: *window_id,
-                    });
+                    self.queue_extension_commands(&result.action);
                     log::info!("Command queued successfully");
+                }
+                Action::RestoreClosedTab { url, .. } if self.tab_manager.is_extension_connected() => {
+                    log::info!("Restoring closed tab: {}", url);
+                    self.queue_extension_commands(&result.action);
+                }
+                Action::CreateBookmark { url, title, folder } => {
+                    log::info!("Creating bookmark for {} in {:?}: {}", url, folder, title);
+                    self.queue_extension_commands(&result.action);
+                }
+                Action::AppendNote(text) => {
+                    // 続けてメモを取れるようにランチャーは閉じない
+                    self.status_message = Some(match self.core.search_engine().note_store().append(text, chrono::Local::now()) {
+                        Ok(()) => format!("Note saved: {}", text),
+                        Err(e) => {
+                            log::error!("Failed to save note: {}", e);
+                            format!("Failed to save note: {}", e)
+                        }
+                    });
+                    self.status_timestamp = Some(Instant::now());
+                    self.input_text.clear();
+                    self.force_search();
+                    return;
+                }
+                Action::StartTimer(request) => {
+                    self.status_message = Some(match self.core.search_engine().timer_store().add(request) {
+                        Ok(timer) => format!("{} set for {}: {}", timer.kind.label(), request.due.format("%H:%M"), timer.label),
+                        Err(e) => {
+                            log::error!("Failed to save timer: {}", e);
+                            format!("Failed to set timer: {}", e)
+                        }
+                    });
+                    self.status_timestamp = Some(Instant::now());
+                    self.input_text.clear();
+                    self.force_search();
+                    return;
+                }
+                Action::CancelTimer(id) => {
+                    if let Err(e) = self.core.search_engine().timer_store().cancel(*id) {
+                        log::error!("Failed to cancel timer {}: {}", id, e);
+                    }
+                    self.status_message = Some(format!("Cancelled: {}", result.title.trim_start_matches("Cancel ")));
+                    self.status_timestamp = Some(Instant::now());
+                    self.force_search();
+                    return;
+                }
+                Action::SaveLayout(name) => {
+                    let monitors = my_launcher::monitor::enumerate_monitors();
+                    let layouts = self.core.search_engine().layout_manager();
+                    self.status_message = Some(match layouts.save(name, self.core.get_cached_windows(), &monitors) {
+                        Ok(layout) => format!("Saved layout {} ({} windows)", layout.name, layout.windows.len()),
+                        Err(e) => {
+                            log::error!("Failed to save layout {}: {}", name, e);
+                            format!("Failed to save layout: {}", e)
+                        }
+                    });
+                    self.status_timestamp = Some(Instant::now());
+                    self.input_text.clear();
+                    self.force_search();
+                    return;
+                }
+                Action::DeleteLayout(name) => {
+                    self.status_message = Some(match self.core.search_engine().layout_manager().delete(name) {
+                        Ok(true) => format!("Deleted layout {}", name),
+                        Ok(false) => format!("Layout {} not found", name),
+                        Err(e) => {
+                            log::error!("Failed to delete layout {}: {}", name, e);
+                            format!("Failed to delete layout: {}", e)
+                        }
+                    });
+                    self.status_timestamp = Some(Instant::now());
+                    self.force_search();
+                    return;
+                }
+                Action::RestoreLayout(name) => {
+                    // ランチャーを閉じた後の配置ではなく、最新のウィンドウ一覧で対応付ける
+                    self.core.refresh_windows();
+                    let layouts = self.core.search_engine().layout_manager();
+                    // ランチャーはこのあと閉じるので、結果はトーストで知らせる
+                    match layouts.get(name) {
+                        Ok(Some(layout)) => {
+                            let monitors = my_launcher::monitor::enumerate_monitors();
+                            let summary = layouts.restore(&layout, self.core.get_cached_windows(), &monitors);
+                            log::info!(
+                                "Restored layout {}: {} moved, {} relaunched, {} missing",
+                                name, summary.moved, summary.launched, summary.missing
+                            );
+                            let detail = format!("{} moved, {} relaunched, {} missing", summary.moved, summary.launched, summary.missing);
+                            if summary.moved == 0 && summary.launched == 0 && !layout.windows.is_empty() {
+                                toast::notify(ToastLevel::Error, &format!("Could not restore layout {}", name), Some(&detail));
+                            } else {
+                                toast::notify(ToastLevel::Info, &format!("Restored layout {}", name), Some(&detail));
+                            }
+                        }
+                        Ok(None) => {
+                            log::warn!("Layout {} not found", name);
+                            toast::notify(ToastLevel::Error, &format!("Layout {} not found", name), None);
+                        }
+                        Err(e) => {
+                            log::error!("Failed to load layout {}: {}", name, e);
+                            toast::notify(ToastLevel::Error, &format!("Failed to restore layout {}", name), Some(&e.to_string()));
+                        }
+                    }
+                }
+                Action::AssignTag(rule) | Action::RemoveTag(rule) => {
+                    let store = self.core.search_engine().workspace_store();
+                    let (saved, done) = match &result.action {
+                        Action::AssignTag(_) => (store.assign(rule.clone()), format!("Tagged {} as {}", rule.target.label(), rule.tag)),
+                        _ => (store.remove(rule), format!("Removed tag {} from {}", rule.tag, rule.target.label())),
+                    };
+                    self.status_message = Some(match saved {
+                        Ok(()) => done,
+                        Err(e) => {
+                            log::error!("Failed to save workspaces to {}: {}", store.path().display(), e);
+                            format!("Failed to save workspaces: {}", e)
+                        }
+                    });
+                    self.status_timestamp = Some(Instant::now());
+                    self.reload_workspaces();
+                    self.force_search();
+                    return;
+                }
+                Action::RestoreSession => {
+                    self.core.refresh_windows();
+                    let missing = match self.core.search_engine().previous_session() {
+                        Some(previous) => session::missing_apps(previous, self.core.get_cached_windows()),
+                        None => Vec::new(),
+                    };
+                    let launched = missing
+                        .iter()
+                        .filter(|app| match session::relaunch(app) {
+                            Ok(()) => true,
+                            Err(e) => {
+                                log::warn!("Failed to relaunch {}: {}", app.exe_path, e);
+                                false
+                            }
+                        })
+                        .count();
+                    log::info!("Restored previous session: {} of {} apps relaunched", launched, missing.len());
+                    if launched < missing.len() {
+                        toast::notify(
+                            ToastLevel::Error,
+                            "Failed to restore the previous session",
+                            Some(&format!("{} of {} apps could not be relaunched (see the log)", missing.len() - launched, missing.len())),
+                        );
+                    }
+                }
+                Action::ProviderCommand { provider, command } => {
+                    self.status_message = Some(match self.core.search_engine().execute_provider_command(provider, command) {
+                        Ok(message) => message,
+                        Err(e) => {
+                            log::error!("{} command {} failed: {}", provider, command, e);
+                            format!("Failed: {}", e)
+                        }
+                    });
+                    self.status_timestamp = Some(Instant::now());
+                    self.force_search();
+                    return;
+                }
+                Action::CopyToClipboard(text) => {
+                    // クリップボードへの書き込みはフレームの最後に行われるため、ランチャーは閉じない
+                    ctx.output_mut(|output| output.copied_text = text.clone());
+                    self.status_message = Some("Copied to clipboard".to_string());
+                    self.status_timestamp = Some(Instant::now());
+                    return;
+                }
+                Action::OpenNote { .. } => {
+                    let path = self.core.search_engine().note_store().path().to_path_buf();
+                    if let Err(e) = open::that(&path) {
+                        log::error!("Failed to open {}: {}", path.display(), e);
+                    }
+                }
+                Action::CloseDuplicateTabs => {
+                    let closed = self.queue_extension_commands(&Action::CloseDuplicateTabs);
+                    log::info!("Queued {} duplicate tabs to close", closed);
                     
-                    // Also execute the action to bring Chrome to front
-                    log::info!("Bringing Chrome window to front");
-                    self.core.execute_action(&result.action);
+                    // 結果を表示するためランチャーは閉じない
+                    self.status_message = Some(format!("Requested closing {} tabs", closed));
+                    self.status_timestamp = Some(Instant::now());
+                    self.force_search();
+                    return;
+                }
+                Action::ClearCaches => {
+                    let freed = self.core.search_engine().clear_disk_caches();
+                    // 読み込んだ画像も捨て、次に表示するときに取得し直す
+                    self.preview_textures = PreviewTextures::default();
+                    self.favicon_textures = PreviewTextures::with_size((32, 32));
+                    for store in self.previews.iter().chain(&self.favicons) {
+                        store.forget_missing();
+                    }
+                    self.status_message = Some(format!("Cleared {} of cached images", disk_cache::format_size(freed)));
+                    self.status_timestamp = Some(Instant::now());
+                    self.force_search();
+                    return;
+                }
+                Action::RunMacro(name) => {
+                    // ランチャーが閉じてから対象のウィンドウに切り替えて入力する
+                    let started = match self.core.search_engine().find_macro(name) {
+                        Some(config) => macros::spawn(config),
+                        None => Err(format!("No macro named {}", name)),
+                    };
+                    if let Err(e) = started {
+                        log::error!("Failed to run macro {}: {}", name, e);
+                        self.status_message = Some(format!("Failed: {}", e));
+                        self.status_timestamp = Some(Instant::now());
+                        return;
+                    }
+                }
+                Action::OpenTabsOverview => {
+                    // 一覧から操作するためランチャーは閉じない
+                    self.tabs_overview_open = true;
+                    self.tabs_overview_filter.clear();
+                    return;
                 }
                 _ => {
                     // For other actions, just execute normally
@@ -219,6 +800,18 @@ impl LauncherApp {
     }
 
     fn show_browser_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        if self.mode == SearchMode::Browser && (!self.input_text.is_empty() || !self.result_filter.is_empty()) {
+            self.show_filter_chips(ui);
+            if sort_selector::render(ui, &mut self.sort_order) {
+                self.force_search();
+            }
+        }
+        if let Some(filter) = self.quick_filter.filter(|_| self.mode == SearchMode::Browser) {
+            if filter_chips::render_quick_filter(ui, filter) {
+                self.toggle_quick_filter(filter);
+            }
+        }
+        
         if !self.search_results.is_empty() {
             // BrowserListコンポーネントを使用
             if let Some(clicked_index) = self.browser_list.render(ui, &self.search_results) {
@@ -232,6 +825,8 @@ impl LauncherApp {
             ui.centered_and_justified(|ui| {
                 ui.label("Press Enter to search on Google");
             });
+        } else if !self.empty_state.is_empty() {
+            self.show_empty_state(ui, ctx);
         } else {
             ui.centered_and_justified(|ui| {
                 ui.label("Type something to search");
@@ -239,7 +834,442 @@ impl LauncherApp {
         }
     }
 
+    /// 検索語が空のときのセクション。検索語はそのまま検索し、項目は開いて閉じる
+    fn show_empty_state(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        match empty_state::render(ui, &self.empty_state) {
+            Some(EmptyTarget::Query(query)) => {
+                self.input_text = query;
+                self.force_search();
+            }
+            Some(EmptyTarget::Run(action)) => {
+                self.core.execute_action(&action);
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            Some(EmptyTarget::None) | None => {}
+        }
+    }
+
+    /// 結果の種類とプロファイルで絞り込むチップ（Ctrl+1..9 でも切り替えられる）
+    fn show_filter_chips(&mut self, ui: &mut egui::Ui) {
+        let chips = result_filter::filter_chips(&self.core.search_engine().detected_profiles());
+        let clicked = filter_chips::render(ui, &chips, &mut self.result_filter);
+        let pressed = filter_chips::handle_shortcuts(ui, &chips, &mut self.result_filter);
+        ui.add_space(4.0);
+        
+        if clicked || pressed {
+            self.core.search_engine().set_filter(self.result_filter.clone());
+            self.force_search();
+        }
+    }
+
+    /// ワークスペースを切り替えるチップ（Ctrl+1..9、Ctrl+0 ですべて表示）
+    fn show_workspace_chips(&mut self, ui: &mut egui::Ui) {
+        let clicked = workspace_chips::render(ui, &self.workspace_tags, &mut self.active_workspace);
+        let pressed = workspace_chips::handle_shortcuts(ui, &self.workspace_tags, &mut self.active_workspace);
+        ui.add_space(4.0);
+        
+        if clicked || pressed {
+            let store = self.core.search_engine().workspace_store();
+            if let Err(e) = store.set_active(self.active_workspace.clone()) {
+                log::error!("Failed to save workspaces to {}: {}", store.path().display(), e);
+            }
+            self.grid.selected_index = 0;
+            self.force_search();
+        }
+    }
+    
+    /// タグを付け直したあとに切り替えチップを読み込み直す
+    fn reload_workspaces(&mut self) {
+        let workspaces = self.core.search_engine().workspaces();
+        self.workspace_tags = workspaces.tags();
+        self.active_workspace = workspaces.active;
+    }
+
+    /// 選択中の結果がブックマークならそのURL・名前・読み込んだプロファイル
+    fn selected_bookmark(&self) -> Option<(String, String, Option<BookmarkSource>)> {
+        let result = self.search_results.get(self.grid.selected_index)?;
+        match (&result.result_type, &result.metadata) {
+            (ResultType::Bookmark, Some(ResultMetadata::Bookmark { url, title, source, .. })) => {
+                Some((url.clone(), title.clone(), source.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Shift+Delete: 拡張機能が接続しているプロファイルの Chrome のブックマークだけ、確認してから削除する
+    fn request_bookmark_delete(&mut self, url: String, title: String, source: Option<BookmarkSource>) {
+        let source = match source {
+            Some(source) if source.browser != "Chrome" => Err(format!("Can't delete {} bookmarks from the launcher", source.browser)),
+            Some(source) if !self.tab_manager.sessions().serves(&source.browser, &source.profile) => {
+                Err(format!("Can't delete: the extension is not connected in {}", source.label()))
+            }
+            Some(source) => Ok(source),
+            None => Err("Can't delete this bookmark from the launcher".to_string()),
+        };
+        let source = match source {
+            Ok(source) => source,
+            Err(message) => {
+                self.status_message = Some(message);
+                self.status_timestamp = Some(Instant::now());
+                return;
+            }
+        };
+        
+        let action = Action::DeleteBookmark { url, title, source };
+        if !self.core.request_confirmation(&action) {
+            self.delete_bookmark(&action);
+        }
+    }
+
+    fn delete_bookmark(&mut self, action: &Action) {
+        let Action::DeleteBookmark { url, title, source } = action else {
+            return;
+        };
+        // 確認している間に拡張機能が切断していたら送らない
+        self.status_message = Some(if self.tab_manager.sessions().serves(&source.browser, &source.profile) {
+            log::info!("Deleting bookmark {} ({}) from {}", url, source.id, source.label());
+            self.queue_extension_commands(action);
+            self.core.search_engine().record_bookmark_edit(url, BookmarkEdit::Deleted);
+            format!("Deleted bookmark: {}", title)
+        } else {
+            log::warn!("Not deleting bookmark {}: no extension is connected in {}", url, source.label());
+            format!("Failed to delete bookmark: the extension is not connected in {}", source.label())
+        });
+        self.status_timestamp = Some(Instant::now());
+        self.force_search();
+    }
+
+    fn rename_bookmark(&mut self, url: String, title: String) {
+        log::info!("Renaming bookmark {} to {}", url, title);
+        self.tab_manager.queue_command(ChromeCommand::RenameBookmark {
+            url: url.clone(),
+            title: title.clone(),
+        });
+        self.core.search_engine().record_bookmark_edit(&url, BookmarkEdit::Renamed(title.clone()));
+        
+        self.status_message = Some(format!("Renamed bookmark to: {}", title));
+        self.status_timestamp = Some(Instant::now());
+        self.force_search();
+    }
+
+    /// ブックマーク名の変更ダイアログ
+    fn show_bookmark_rename(&mut self, ctx: &egui::Context) {
+        let Some((url, mut title)) = self.bookmark_rename.take() else {
+            return;
+        };
+        
+        let mut submit = false;
+        let mut cancel = false;
+        egui::Window::new("Rename bookmark")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(&url).color(egui::Color32::from_rgb(150, 150, 150)));
+                let response = ui.add(egui::TextEdit::singleline(&mut title).desired_width(400.0));
+                response.request_focus();
+                if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    submit = true;
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Rename").clicked() {
+                        submit = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        
+        if submit && !title.trim().is_empty() {
+            self.rename_bookmark(url, title.trim().to_string());
+        } else if !cancel {
+            self.bookmark_rename = Some((url, title));
+        }
+    }
+
+    /// 早見表を開き、`filter` で絞り込む
+    fn open_help(&mut self, filter: String) {
+        self.help_open = true;
+        self.help_filter = filter;
+        self.help_focus = true;
+    }
+
+    /// 設定パネル。提供元を切り替えたら検索し直して設定ファイルに保存し、プロファイルの書き出し・読み込みは結果を表示する
+    fn show_settings(&mut self, ctx: &egui::Context) {
+        if !self.settings_open {
+            return;
+        }
+        
+        let registry = self.core.search_engine().providers();
+        let message = match settings_panel::show(ctx, &mut self.settings_open, registry, &mut self.profile_path) {
+            None => return,
+            Some(SettingsAction::ProvidersChanged) => None,
+            Some(SettingsAction::ExportProfile) => {
+                let path = std::path::PathBuf::from(self.profile_path.trim());
+                Some(match profile_archive::export_profile(&path) {
+                    Ok(count) => format!("Exported {} files to {}", count, path.display()),
+                    Err(e) => format!("Failed to export profile: {}", e),
+                })
+            }
+            Some(SettingsAction::ImportProfile) => {
+                let path = std::path::PathBuf::from(self.profile_path.trim());
+                Some(match profile_archive::import_profile(&path) {
+                    Ok(files) => format!("Imported {} (restart to apply)", files.join(", ")),
+                    Err(e) => format!("Failed to import profile: {}", e),
+                })
+            }
+        };
+        if let Some(message) = message {
+            self.status_message = Some(message);
+            self.status_timestamp = Some(Instant::now());
+            return;
+        }
+        let disabled = registry.disabled();
+        
+        let path = my_launcher::paths::config_path();
+        let mut config = LauncherConfig::load();
+        config.providers.disabled = disabled;
+        if let Err(e) = config.save_to(&path) {
+            log::error!("Failed to save config to {}: {}", path.display(), e);
+        }
+        self.force_search();
+    }
+
+    /// サーバーの状態を示す小さな点（止まっているものがあれば橙、ホバーで詳細）。クリックしたら `true`
+    fn show_service_indicator(&self, ui: &mut egui::Ui) -> bool {
+        let statuses = self.services.all();
+        if statuses.is_empty() {
+            return false;
+        }
+        let color = if self.services.is_healthy() {
+            egui::Color32::from_rgb(80, 140, 80)
+        } else {
+            egui::Color32::from_rgb(230, 150, 40)
+        };
+        let dot = egui::Label::new(egui::RichText::new("●").small().color(color)).sense(egui::Sense::click());
+        ui.add(dot).on_hover_ui(|ui| {
+            for status in &statuses {
+                let state = match &status.state {
+                    ServiceState::Running => "running".to_string(),
+                    ServiceState::Restarting { retry_in_secs } => format!("restarting in {}s", retry_in_secs),
+                    ServiceState::Stopped => "stopped".to_string(),
+                };
+                ui.label(format!("{}: {} ({} restarts)", status.name, state, status.restarts));
+                if let Some(error) = &status.last_error {
+                    ui.label(egui::RichText::new(format!("last error: {}", error)).small().weak());
+                }
+            }
+            match self.tab_manager.sessions().active_transport() {
+                Some(transport) => ui.label(format!("extension: connected via {}", transport.label())),
+                None => ui.label("extension: not connected"),
+            };
+            ui.label(egui::RichText::new("Click to manage extension connections").small().weak());
+        }).clicked()
+    }
+
+    fn show_export(&mut self, ctx: &egui::Context) {
+        if !self.export_open {
+            return;
+        }
+        let Some(target) = export_dialog::show(ctx, &mut self.export_open, &mut self.export_format, self.search_results.len()) else {
+            return;
+        };
+        
+        let text = result_export::export(&self.search_results, self.export_format);
+        let message = match target {
+            ExportTarget::Clipboard => {
+                ctx.output_mut(|output| output.copied_text = text);
+                format!("Copied {} results as {}", self.search_results.len(), self.export_format.label())
+            }
+            ExportTarget::File => {
+                let dir = my_launcher::paths::exports_dir();
+                let path = dir.join(format!("results-{}.{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), self.export_format.extension()));
+                match std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, text)) {
+                    Ok(()) => format!("Saved results to {}", path.display()),
+                    Err(e) => {
+                        log::error!("Failed to export results to {}: {}", path.display(), e);
+                        format!("Failed to save results: {}", e)
+                    }
+                }
+            }
+        };
+        self.export_open = false;
+        self.status_message = Some(message);
+        self.status_timestamp = Some(Instant::now());
+    }
+
+    fn show_tabs_overview(&mut self, ctx: &egui::Context) {
+        if !self.tabs_overview_open {
+            return;
+        }
+        let tabs = self.tab_manager.snapshot();
+        let Some(action) = tab_overview::show(ctx, &mut self.tabs_overview_open, &mut self.tab_grouping, &mut self.tabs_overview_filter, &tabs) else {
+            return;
+        };
+
+        let message = match action {
+            OverviewAction::SwitchToTab { tab_id, window_id } => {
+                match self.chrome_windows.hwnd_for(window_id) {
+                    Some(hwnd) => self.core.execute_action(&Action::SwitchWindow(hwnd)),
+                    None => self.core.execute_action(&Action::SwitchToTab { tab_id, window_id }),
+                }
+                self.tab_manager.queue_command(ChromeCommand::SwitchToTab { tab_id, window_id });
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                return;
+            }
+            OverviewAction::CloseTabs(tab_ids) => {
+                let message = format!("Closed {} tabs", tab_ids.len());
+                self.tab_manager.queue_command(ChromeCommand::CloseTabs { tab_ids });
+                message
+            }
+            OverviewAction::MoveToNewWindow(tab_ids) => {
+                let message = format!("Moved {} tabs to a new window", tab_ids.len());
+                self.tab_manager.queue_command(ChromeCommand::MoveTabsToNewWindow { tab_ids });
+                message
+            }
+        };
+        log::info!("Tabs overview: {}", message);
+        self.status_message = Some(message);
+        self.status_timestamp = Some(Instant::now());
+    }
+
+    /// 拡張機能に任せる操作のコマンドをキューに積む（積んだ数を返す）
+    fn queue_extension_commands(&self, action: &Action) -> usize {
+        let commands = extension_commands::commands_for(action, &self.tab_manager.get_tabs());
+        let count = commands.len();
+        for command in commands {
+            self.tab_manager.queue_command(command);
+        }
+        count
+    }
+
+    /// 選択中の結果の URL（コピーする結果はその文字列）を前のウィンドウに貼り付けて閉じる
+    fn paste_into_previous_window(&mut self, ctx: &egui::Context) {
+        let text = self.search_results.get(self.grid.selected_index).and_then(|result| result.paste_text());
+        let started = match (text, self.previous_window) {
+            (None, _) => Err("This result has nothing to paste".to_string()),
+            (_, None) => Err("No window was focused before the launcher".to_string()),
+            (Some(text), Some(hwnd)) => macros::spawn_paste_into(hwnd, text),
+        };
+        match started {
+            Ok(()) => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            Err(e) => {
+                self.status_message = Some(e);
+                self.status_timestamp = Some(Instant::now());
+            }
+        }
+    }
+
+    /// 確認待ちの操作があればダイアログを表示し、選ばれたら実行するかキャンセルする
+    fn show_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(pending) = self.core.pending_confirmation() else {
+            return;
+        };
+        match confirm_overlay::show(ctx, pending) {
+            Some(confirm_overlay::ConfirmChoice::Confirm) => {
+                let action = pending.action.clone();
+                self.core.confirm();
+                // ブックマークの削除は選択中の結果の操作ではないので、確認した操作を実行し直す
+                match action {
+                    Action::DeleteBookmark { url, title, source } => self.request_bookmark_delete(url, title, Some(source)),
+                    _ => self.execute_selected(ctx),
+                }
+            }
+            Some(confirm_overlay::ConfirmChoice::Cancel) => self.core.cancel_confirmation(),
+            None => {}
+        }
+    }
+
     fn handle_keyboard_input(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        // 確認ダイアログの表示中は Enter / Esc をダイアログに任せる
+        if self.core.pending_confirmation().is_some() {
+            return;
+        }
+        // Ctrl+P: 設定パネルを開閉（開いている間は Esc で閉じる）
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::P)) {
+            self.settings_open = !self.settings_open;
+        }
+        if self.settings_open {
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.settings_open = false;
+            }
+            return;
+        }
+        
+        // F1: クエリの書き方の早見表を開閉（開いている間は Esc で閉じる）
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F1)) {
+            if self.help_open {
+                self.help_open = false;
+            } else {
+                self.open_help(String::new());
+            }
+        }
+        if self.help_open {
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.help_open = false;
+            }
+            return;
+        }
+        
+        // Ctrl+Shift+E: 検索結果の書き出しダイアログを開閉（開いている間は Esc で閉じる）
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL | egui::Modifiers::SHIFT, egui::Key::E)) {
+            self.export_open = !self.export_open && !self.search_results.is_empty();
+        }
+        if self.export_open {
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.export_open = false;
+            }
+            return;
+        }
+        if self.tabs_overview_open {
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.tabs_overview_open = false;
+            }
+            return;
+        }
+        
+        // Ctrl+S: Browserモードの結果の並べ替えを切り替え
+        if self.mode == SearchMode::Browser && ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::S)) {
+            self.sort_order = self.sort_order.next();
+            self.force_search();
+        }
+        
+        // Alt+1..5: タブ・ブックマーク・履歴・検索・ウィンドウだけに絞る（もう一度押すと戻す）
+        if self.mode == SearchMode::Browser {
+            if let Some(filter) = filter_chips::quick_filter_key(ui) {
+                self.toggle_quick_filter(filter);
+            }
+        }
+        
+        // Ctrl+I: 選択中の結果の詳細を開閉（開いている間は Esc で閉じる）
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::I)) {
+            self.details_open = !self.details_open;
+        }
+        if self.details_open && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            self.details_open = false;
+        }
+        
+        // 名前の変更中はダイアログが入力を受け取る
+        if self.bookmark_rename.is_some() {
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.bookmark_rename = None;
+            }
+            return;
+        }
+        
+        // Shift+Delete: ブックマークを削除 / F2: ブックマークの名前を変更
+        if self.mode == SearchMode::Browser {
+            if ui.input(|i| i.modifiers.shift && i.key_pressed(egui::Key::Delete)) {
+                if let Some((url, title, source)) = self.selected_bookmark() {
+                    self.request_bookmark_delete(url, title, source);
+                }
+            }
+            if ui.input(|i| i.key_pressed(egui::Key::F2)) {
+                self.bookmark_rename = self.selected_bookmark().map(|(url, title, _)| (url, title));
+            }
+        }
+
         // Tab: モード切り替え
         if ui.input(|i| i.key_pressed(egui::Key::Tab)) {
             self.switch_mode();
@@ -273,22 +1303,29 @@ impl LauncherApp {
                     }
                 }
             }
-            SearchMode::Browser => {
-                // Browserモード: BrowserListのキーボード処理を使用
-                self.browser_list.handle_keyboard(ui, self.search_results.len());
+            SearchMode::Browser | SearchMode::Tabs | SearchMode::General => {
+                // Browser/Tabs/Generalモード: BrowserListのキーボード処理を使用
+                self.browser_list.handle_keyboard(ui, &self.search_results);
                 // 選択インデックスを同期
                 self.grid.selected_index = self.browser_list.selected_index;
             }
         }
 
-        // 共通のキーボードショートカット
-        // Home/End: 最初/最後の項目へ
-        if ui.input(|i| i.key_pressed(egui::Key::Home)) {
-            self.grid.selected_index = 0;
+        // Home/End: 最初/最後の項目へ（Browser/TabsモードはBrowserListが折りたたみを考慮して処理する）
+        if self.mode == SearchMode::Windows {
+            if ui.input(|i| i.key_pressed(egui::Key::Home)) {
+                self.grid.selected_index = 0;
+            }
+
+            if ui.input(|i| i.key_pressed(egui::Key::End)) && !self.search_results.is_empty() {
+                self.grid.selected_index = self.search_results.len() - 1;
+            }
         }
 
-        if ui.input(|i| i.key_pressed(egui::Key::End)) && !self.search_results.is_empty() {
-            self.grid.selected_index = self.search_results.len() - 1;
+        // Shift+Enter: 選択項目の URL を、開く前に前面にあったウィンドウに貼り付ける
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::SHIFT, egui::Key::Enter)) {
+            self.paste_into_previous_window(ctx);
+            return;
         }
 
         // Enter: 選択項目を実行
@@ -306,10 +1343,22 @@ impl LauncherApp {
 }
 
 impl eframe::App for LauncherApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.profiler.begin_frame();
+        
+        // F12: フレームごとの処理時間の表示を切り替え
+        if ctx.input(|i| i.key_pressed(egui::Key::F12)) {
+            self.frame_overlay = !self.frame_overlay;
+        }
+        
+        self.show_confirmation(ctx);
+        self.refresh_on_tab_changes();
+        
         // デバウンス処理：一定時間経過後に検索を実行
         if let (Some(last_change), Some(pending_text)) = (self.last_input_change, &self.pending_search_text) {
-            if last_change.elapsed() >= self.debounce_duration {
+            // 遅い検索元を抑えている間は待ち時間を延ばす
+            let debounce = self.debounce_duration + self.core.search_engine().extra_debounce();
+            if last_change.elapsed() >= debounce {
                 // デバウンス時間が経過したら検索を実行
                 if &self.input_text == pending_text {
                     self.update_search();
@@ -328,6 +1377,21 @@ impl eframe::App for LauncherApp {
             }
         }
         
+        // プレビュー画像・ファビコンが届いたら表示する
+        let previews_updated = self.previews.as_ref().is_some_and(|previews| previews.take_updated());
+        let favicons_updated = self.favicons.as_ref().is_some_and(|favicons| favicons.take_updated());
+        if previews_updated || favicons_updated {
+            ctx.request_repaint();
+        }
+        
+        // バックグラウンドで取得していた検索結果が届いたら検索し直す
+        if self.core.search_engine().take_provider_updates() {
+            self.update_search();
+        }
+        if self.core.search_engine().has_pending_providers() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        
         // 初回実行時に日本語フォントを設定
         static INIT: std::sync::Once = std::sync::Once::new();
         INIT.call_once(|| {
@@ -336,20 +1400,54 @@ impl eframe::App for LauncherApp {
             }
         });
 
-        // ダークテーマを適用
-        ctx.set_visuals(egui::Visuals::dark());
+        // テーマと UI の倍率を適用（既定はダーク）
+        ctx.set_visuals(theme::visuals(self.theme));
+        theme::apply_scale(ctx, ctx.native_pixels_per_point(), self.ui_scale);
+        
+        // アニメーション効果がオフ・省電力中は動きを控える
+        let motion_settings = self.motion.current();
+        motion::apply(ctx, motion_settings);
+        self.thumbnail_cache.set_enabled(motion_settings.thumbnails);
+        if let Some(drop_down) = &mut self.drop_down {
+            drop_down.update(ctx, motion_settings.animations);
+        }
+        let dialog_open = self.settings_open || self.core.pending_confirmation().is_some();
+        hide_on_focus_loss(ctx, &mut self.auto_hide, dialog_open);
+        
+        // 画面下部: 使えるショートカットと結果の種類の凡例
+        if self.hint_bar {
+            let grouped = self.search_results.iter().any(|r| r.group.is_some());
+            let hints = hint_bar::hints(self.mode, self.search_results.get(self.grid.selected_index), grouped);
+            let legend = hint_bar::legend(&self.search_results);
+            egui::TopBottomPanel::bottom("hint_bar").show(ctx, |ui| {
+                hint_bar::render(ui, &hints, &legend);
+            });
+        }
         
         egui::CentralPanel::default().show(ctx, |ui| {
             // 検索バー
             ui.horizontal(|ui| {
                 ui.label(format!("Mode: {:?}", self.mode));
+                if self.show_service_indicator(ui) {
+                    self.extensions_open = !self.extensions_open;
+                }
+                if diagnostics::indicators(ui, &self.core.search_engine().health().all()) {
+                    self.diagnostics_open = !self.diagnostics_open;
+                }
+                let privacy = self.core.search_engine().privacy().clone();
+                if privacy_toggle::render(ui, self.privacy_active, privacy.is_manual()) {
+                    privacy.set(!privacy.is_manual());
+                    self.force_search();
+                }
                 
                 let response = ui.add(
                     egui::TextEdit::singleline(&mut self.input_text)
                         .desired_width(400.0)
                         .hint_text(match self.mode {
-                            SearchMode::Browser => "Search web, bookmarks, history...",
+                            SearchMode::Browser => "Search web, bookmarks, history... (t <query> for tabs)",
                             SearchMode::Windows => "Search windows...",
+                            SearchMode::Tabs => "Search open tabs...",
+                            SearchMode::General => "Search the web, w <window>, >command, or a URL...",
                         })
                         .id(egui::Id::new("search_input"))
                 );
@@ -363,15 +1461,29 @@ impl eframe::App for LauncherApp {
                 if response.changed() {
                     self.grid.selected_index = 0;
                     
+                    // `?` / `? <語>`: 早見表を開き、入力欄は空に戻す
+                    if let Some(filter) = help::parse_help(&self.input_text) {
+                        self.open_help(filter.to_string());
+                        self.input_text.clear();
+                    }
+                    
                     match self.mode {
-                        SearchMode::Windows => {
-                            // Windowsモードでは即座に検索
+                        SearchMode::Windows | SearchMode::Tabs => {
+                            // Windows/Tabsモードではメモリ上の検索なので即座に検索
                             self.update_search();
                         }
-                        SearchMode::Browser => {
-                            // Browserモードではデバウンス処理
+                        SearchMode::Browser | SearchMode::General => {
+                            // Browser/Generalモードではデバウンス処理
                             self.last_input_change = Some(Instant::now());
                             self.pending_search_text = Some(self.input_text.clone());
+                            // 最近の同じ検索の結果があれば、検索が終わるまでそれを表示しておく
+                            let cached = if self.privacy_active { None } else { self.result_cache.lookup(self.mode, &self.input_text) };
+                            if let Some(cached) = cached {
+                                self.unfiltered_results = self.sorted(cached);
+                                self.search_results = QuickFilter::apply(self.quick_filter, &self.unfiltered_results);
+                                self.browser_list.reset_for_new_search();
+                                self.warm_results = true;
+                            }
                         }
                     }
                 }
@@ -392,7 +1504,12 @@ impl eframe::App for LauncherApp {
                     if elapsed < Duration::from_secs(3) {
                         ui.horizontal(|ui| {
                             ui.spacing_mut().item_spacing.x = 10.0;
-                            ui.add(egui::Spinner::new());
+                            // スピナーは毎フレーム再描画するので、控える場合は消える時刻に一度だけ再描画する
+                            if motion_settings.continuous_repaint {
+                                ui.add(egui::Spinner::new());
+                            } else {
+                                ctx.request_repaint_after(Duration::from_secs(3) - elapsed);
+                            }
                             ui.label(egui::RichText::new(status).color(egui::Color32::from_rgb(100, 200, 255)));
                         });
                     } else {
@@ -409,23 +1526,55 @@ impl eframe::App for LauncherApp {
             match self.mode {
                 SearchMode::Windows => {
                     // Windowsモード: Alt+Tabスタイルのグリッド表示
+                    if !self.workspace_tags.is_empty() {
+                        self.show_workspace_chips(ui);
+                    }
                     if !self.search_results.is_empty() {
-                        let items: Vec<SearchResultItem> = self.search_results.iter()
-                            .map(|r| SearchResultItem(r))
-                            .collect();
+                        let tabs = self.tab_manager.snapshot();
+                        let mut items: Vec<SearchResultItem> = Vec::with_capacity(self.search_results.len());
+                        for result in &self.search_results {
+                            let favicon_url = result.window_info.as_ref()
+                                .and_then(|window| self.chrome_windows.active_tab(window.hwnd, &tabs))
+                                .map(|tab| tab.fav_icon_url.as_str())
+                                .filter(|url| !url.is_empty());
+                            let icon = match (&self.favicons, favicon_url) {
+                                (Some(favicons), Some(url)) => self.favicon_textures.get(ctx, favicons, url).cloned(),
+                                _ => None,
+                            };
+                            items.push(SearchResultItem(result, icon));
+                        }
 
-                        if let Some(clicked_index) = self.grid.show(ui, ctx, &items, &mut self.thumbnail_cache) {
+                        let clicked = self.grid.show(ui, ctx, &items, &mut self.thumbnail_cache);
+                        // タイルを画面下端のモニターのミニマップに落とすとウィンドウを移動
+                        if let Some(item) = self.grid.dragged().and_then(|index| items.get(index)) {
+                            if let Some(message) = monitor_map::drop_window(ctx, item.hwnd(), self.grid.drag_released()) {
+                                self.status_message = Some(message);
+                                self.status_timestamp = Some(Instant::now());
+                            }
+                        }
+                        self.profiler.record(Span::Thumbnails, self.thumbnail_cache.take_capture_time());
+                        // キャプチャできなかったサムネイルは少し待って取り直す
+                        if self.thumbnail_cache.take_missing() && motion_settings.continuous_repaint {
+                            ctx.request_repaint_after(Duration::from_secs(1));
+                        }
+                        if let Some(clicked_index) = clicked {
                             self.grid.selected_index = clicked_index;
                             self.execute_selected(ctx);
                         }
+                    } else if self.active_workspace.is_none() && !self.empty_state.is_empty() {
+                        self.show_empty_state(ui, ctx);
                     } else {
+                        let message = match &self.active_workspace {
+                            Some(workspace) => format!("No windows in workspace {}", workspace),
+                            None => "No windows found".to_string(),
+                        };
                         ui.centered_and_justified(|ui| {
-                            ui.label("No windows found");
+                            ui.label(message);
                         });
                     }
                 }
-                SearchMode::Browser => {
-                    // Browserモード: シンプルなリスト表示
+                SearchMode::Browser | SearchMode::Tabs | SearchMode::General => {
+                    // Browser/Tabs/Generalモード: シンプルなリスト表示
                     self.show_browser_ui(ui, ctx);
                 }
             }
@@ -433,45 +1582,572 @@ impl eframe::App for LauncherApp {
             // キーボードショートカット処理
             self.handle_keyboard_input(ui, ctx);
         });
+
+        self.show_bookmark_rename(ctx);
+        self.show_settings(ctx);
+        if self.help_open {
+            let registry = self.core.search_engine().providers();
+            help_popup::show(ctx, &mut self.help_open, &mut self.help_filter, &mut self.help_focus, registry);
+        }
+        self.show_export(ctx);
+        if self.diagnostics_open {
+            let sources = self.core.search_engine().health().all();
+            diagnostics::show(ctx, &mut self.diagnostics_open, &sources, &self.services.all());
+        }
+        if self.extensions_open {
+            let sessions = self.tab_manager.sessions().list();
+            if let Some((id, control)) = extension_sessions::show(ctx, &mut self.extensions_open, &sessions) {
+                self.tab_manager.sessions().request(id, control);
+                self.status_message = Some(match control {
+                    SessionControl::RefreshTabs => "Asked the extension to resend its tabs".to_string(),
+                    SessionControl::Disconnect => "Disconnected the extension".to_string(),
+                });
+                self.status_timestamp = Some(Instant::now());
+            }
+        }
+        self.show_tabs_overview(ctx);
+        if self.details_open {
+            match self.search_results.get(self.grid.selected_index) {
+                Some(result) => {
+                    let preview = match (&self.previews, preview_url(result, &self.frequent_urls)) {
+                        (Some(previews), Some(url)) => self.preview_textures.get(ctx, previews, url),
+                        _ => None,
+                    };
+                    let hwnd = result.window_info.as_ref().map(|window| window.hwnd);
+                    if let Some(hwnd) = hwnd {
+                        if self.launch_info.as_ref().map(|(known, _)| *known) != Some(hwnd) {
+                            self.launch_info = Some((hwnd, windows_api::process_launch_info_for_window(hwnd)));
+                        }
+                    }
+                    let launch = self.launch_info.as_ref().filter(|(known, _)| Some(*known) == hwnd).map(|(_, info)| info);
+                    result_details::show(ctx, &mut self.details_open, result, preview, launch);
+                }
+                None => self.details_open = false,
+            }
+        }
+
+        // 前回クラッシュした場合はレポートの場所を通知
+        if let Some(path) = self.crash_report.clone() {
+            egui::Window::new("Launcher crashed")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label("The launcher crashed last time. A crash report was saved at:");
+                    ui.monospace(path.display().to_string());
+                    if ui.button("OK").clicked() {
+                        self.crash_report = None;
+                    }
+                });
+        }
+        
+        self.profiler.end_frame();
+        if self.frame_overlay {
+            frame_overlay::show(ctx, &self.profiler, frame.info().cpu_usage);
+        }
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // 次の起動で最初の検索からすぐに結果を出せるよう、ブックマーク・履歴の索引を残しておく
+        self.core.search_engine().save_browser_index();
     }
 }
 
 // IPCサーバーは削除（WebSocketに移行済み）
 
-fn main() -> Result<(), eframe::Error> {
-    let _ = my_launcher::logger::init_logger();
+/// 拡張機能との経路（WebSocket と Native Messaging）を起動する（止まったら `Supervisor` が起動し直す）
+fn start_tab_transports(tab_manager: Arc<TabManager>, services: ServiceStatuses) {
+    let transports = tab_transport::default_transports(&tab_manager, WEBSOCKET_PORT);
+    if let Err(e) = tab_transport::spawn_transports(&Supervisor::new(services), transports) {
+        log::error!("Failed to spawn extension transport thread: {}", e);
+    }
+}
 
-    // Create a shared TabManager instance
-    let tab_manager = Arc::new(TabManager::new());
-    
-    // Start WebSocket server in a background thread
+/// プレビューを表示する結果ならそのページの URL（よく開くブックマークだけ）
+fn preview_url<'a>(result: &'a SearchResult, frequent_urls: &HashSet<String>) -> Option<&'a str> {
+    let url = result.metadata.as_ref()?.url();
+    (result.result_type == ResultType::Bookmark && frequent_urls.contains(url)).then_some(url)
+}
+
+/// 前回記録したセッション（なければ `None`）
+fn load_previous_session() -> Option<Session> {
+    let store = SessionStore::default();
+    match store.load() {
+        Ok(session) => session,
+        Err(e) => {
+            log::warn!("Failed to load previous session from {}: {}", store.path().display(), e);
+            None
+        }
+    }
+}
+
+/// デーモンが起動していればシンクライアントとして接続し、なければ自前でWebSocketサーバーを起動する
+fn connect_window_manager(tab_manager: &Arc<TabManager>, services: &ServiceStatuses) -> Arc<dyn WindowManager> {
+    #[cfg(windows)]
     {
-        let tab_manager_clone = Arc::clone(&tab_manager);
-        thread::spawn(move || {
-            log::info!("Starting WebSocket server thread");
-            let rt = Runtime::new().expect("Failed to create Tokio runtime for WebSocket");
-            rt.block_on(async {
-                let server = WebSocketServer::new(tab_manager_clone, 9999);
-                if let Err(e) = server.start().await {
-                    log::error!("WebSocket server error: {}", e);
+        match DaemonClient::connect() {
+            Ok(client) => {
+                log::info!("Connected to daemon, running as thin client");
+                let client = Arc::new(client);
+                daemon::spawn_tab_sync(Arc::clone(&client), Arc::clone(tab_manager), services.clone());
+                // 通知のボタンはデーモンに実行させる（ランチャーはすぐに閉じるため）
+                let notification_client = Arc::clone(&client);
+                my_launcher::notification::set_action_handler(move |action| {
+                    if let Err(e) = notification_client.send_notification_action(action) {
+                        log::error!("Failed to forward notification action to daemon: {}", e);
+                    }
+                });
+                return Arc::new(DaemonWindowManager::new(client));
+            }
+            Err(e) => {
+                log::info!("Daemon not running ({}), starting standalone", e);
+            }
+        }
+    }
+
+    if let Err(e) = daemon::restore_tab_snapshot(tab_manager) {
+        log::error!("Failed to start tab snapshot saver: {}", e);
+    }
+    start_tab_transports(Arc::clone(tab_manager), services.clone());
+    // デーモンがない場合はランチャーを開いている間だけタイマーを通知する
+    if let Err(e) = timers::spawn_scheduler(TimerStore::default()) {
+        log::error!("Failed to start timer scheduler: {}", e);
+    }
+    Arc::new(WindowsApiManager::new())
+}
+
+/// UIを起動せずに終了するサブコマンドを処理（該当すれば終了コードを返す）
+fn run_subcommand(args: &[String], config: &LauncherConfig) -> Option<i32> {
+    let command = args.get(1)?;
+    let exit_code = match command.as_str() {
+        // 最新リリースをダウンロードして実行ファイルを置き換える
+        "self-update" => match update::self_update(&config.update) {
+            Ok(Some(version)) => {
+                println!("Updated to version {}", version);
+                0
+            }
+            Ok(None) => {
+                println!("Already up to date ({})", env!("CARGO_PKG_VERSION"));
+                0
+            }
+            Err(e) => {
+                eprintln!("Self-update failed: {}", e);
+                1
+            }
+        },
+        "export-profile" | "import-profile" => {
+            let Some(file) = args.get(2) else {
+                eprintln!("Usage: my-launcher {} <file>", command);
+                return Some(2);
+            };
+            let path = PathBuf::from(file);
+            let result = if command == "export-profile" {
+                profile_archive::export_profile(&path).map(|count| format!("Exported {} files to {}", count, path.display()))
+            } else {
+                profile_archive::import_profile(&path).map(|files| format!("Imported: {}", files.join(", ")))
+            };
+            match result {
+                Ok(message) => {
+                    println!("{}", message);
+                    0
                 }
-            });
-        });
+                Err(e) => {
+                    eprintln!("{} failed: {}", command, e);
+                    1
+                }
+            }
+        }
+        // mylauncher:// のリンクで開かれるようにする
+        "register-protocol" | "unregister-protocol" => {
+            let result = if command == "register-protocol" { deep_link::register() } else { deep_link::unregister() };
+            match result {
+                Ok(()) => {
+                    println!("{} {}://", if command == "register-protocol" { "Registered" } else { "Unregistered" }, deep_link::SCHEME);
+                    0
+                }
+                Err(e) => {
+                    eprintln!("{} failed: {}", command, e);
+                    1
+                }
+            }
+        }
+        // AutoHotkey などの外部のトリガーから、デーモンに IPC で頼む
+        "show" | "run" | "exec" => run_trigger(command, &args[2..]),
+        // WebSocket が使えない環境向けに、Chrome に Native Messaging ホストとして登録する
+        "register-native-host" => {
+            let Some(extension_id) = args.get(2) else {
+                eprintln!("Usage: my-launcher register-native-host <extension-id>");
+                return Some(2);
+            };
+            match tab_transport::register_native_host(extension_id) {
+                Ok(manifest) => {
+                    println!("Registered {} ({})", tab_transport::NATIVE_HOST_NAME, manifest.display());
+                    0
+                }
+                Err(e) => {
+                    eprintln!("register-native-host failed: {}", e);
+                    1
+                }
+            }
+        }
+        "unregister-native-host" => match tab_transport::unregister_native_host() {
+            Ok(()) => {
+                println!("Unregistered {}", tab_transport::NATIVE_HOST_NAME);
+                0
+            }
+            Err(e) => {
+                eprintln!("unregister-native-host failed: {}", e);
+                1
+            }
+        },
+        // Chrome が Native Messaging ホストとして起動した（最初の引数は拡張機能のオリジン）
+        origin if origin.starts_with("chrome-extension://") => match tab_transport::run_native_host() {
+            Ok(()) => 0,
+            Err(e) => {
+                log::error!("Native messaging host failed: {}", e);
+                1
+            }
+        },
+        _ => return None,
+    };
+    Some(exit_code)
+}
+
+/// 外部のトリガー API の参照クライアント（`run` は結果を `id<TAB>title<TAB>description` で出力する）
+fn run_trigger(command: &str, args: &[String]) -> i32 {
+    let request = match daemon::trigger_request(command, args) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 2;
+        }
+    };
+    #[cfg(windows)]
+    let response = DaemonClient::connect().and_then(|client| client.request(&request));
+    #[cfg(not(windows))]
+    let response: std::io::Result<IpcMessage> = {
+        let _ = request;
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "the daemon only runs on Windows"))
+    };
+    match response {
+        Ok(IpcMessage::Results { results }) => {
+            for result in results {
+                println!("{}\t{}\t{}", result.id, result.title, result.description);
+            }
+            0
+        }
+        Ok(IpcMessage::ActionResult { message }) => {
+            println!("{}", message);
+            0
+        }
+        Ok(IpcMessage::Error { message }) => {
+            eprintln!("{}", message);
+            1
+        }
+        Ok(other) => {
+            eprintln!("Unexpected response from the daemon: {:?}", other);
+            1
+        }
+        Err(e) => {
+            eprintln!("Failed to reach the daemon (is `my-launcher --daemon` running?): {}", e);
+            1
+        }
     }
+}
+
+/// dmenu 互換モード（何も選ばずに閉じたら終了コード 1）
+fn run_dmenu(config: &LauncherConfig) -> Result<(), eframe::Error> {
+    let lines = dmenu::read_lines(std::io::stdin().lock());
+    let selection = Arc::new(Mutex::new(None));
+    let scale = system_prefs::ui_scale(config.appearance.ui_scale);
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1200.0, 800.0])
+            .with_inner_size([900.0 * scale, 600.0 * scale])
+            .with_always_on_top()
+            .with_title("My Launcher - dmenu"),
+        ..Default::default()
+    };
+    let config = config.clone();
+    let app_selection = Arc::clone(&selection);
+    eframe::run_native(
+        "My Launcher",
+        options,
+        Box::new(move |_cc| Box::new(LauncherApp::new_dmenu(lines, app_selection, &config))),
+    )?;
+
+    let selected = selection.lock().unwrap().take();
+    match selected {
+        Some(line) => {
+            println!("{}", line);
+            Ok(())
+        }
+        None => std::process::exit(1),
+    }
+}
+
+/// フォーカスを失って猶予が過ぎたらウィンドウを閉じる（猶予の間は終わるころに再描画する）
+fn hide_on_focus_loss(ctx: &egui::Context, auto_hide: &mut Option<AutoHide>, dialog_open: bool) {
+    let Some(auto_hide) = auto_hide else {
+        return;
+    };
+    let now = Instant::now();
+    if auto_hide.observe(ctx.input(|i| i.focused), dialog_open, now) {
+        log::info!("Launcher lost focus, closing");
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    } else if let Some(remaining) = auto_hide.remaining(now) {
+        ctx.request_repaint_after(remaining);
+    }
+}
+
+/// 最近使ったウィンドウの一覧に出す数
+const RECENT_WINDOWS: usize = 5;
+
+/// 最近使ったウィンドウだけを並べる小さなポップアップ（検索はしない）
+struct RecentWindowsApp {
+    core: LauncherCore<BrowserSearchEngine, dyn WindowManager>,
+    windows: Vec<WindowInfo>,
+    selected: usize,
+    theme: Theme,
+    ui_scale: f32,
+    auto_hide: Option<AutoHide>,
+}
+
+impl eframe::App for RecentWindowsApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        ctx.set_visuals(theme::visuals(self.theme));
+        theme::apply_scale(ctx, ctx.native_pixels_per_point(), self.ui_scale);
+        hide_on_focus_loss(ctx, &mut self.auto_hide, false);
+
+        match recent_windows::show(ctx, &self.windows, &mut self.selected) {
+            Some(recent_windows::RecentChoice::Switch(index)) => {
+                if let Some(window) = self.windows.get(index) {
+                    log::info!("Switching to recent window {}", window.hwnd);
+                    self.core.execute_action(&Action::SwitchWindow(window.hwnd));
+                }
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            Some(recent_windows::RecentChoice::Close) => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            None => {}
+        }
+    }
+}
+
+/// `--recent`: 最近使ったウィンドウの一覧を、ランチャーとは別の小さなウィンドウで開く
+fn run_recent_windows(config: &LauncherConfig) -> Result<(), eframe::Error> {
+    let window_manager: Arc<dyn WindowManager> = Arc::new(WindowsApiManager::new());
+    let core = LauncherCore::new(BrowserSearchEngine::new_with_tab_manager(Arc::new(TabManager::new())), window_manager);
+    // 一覧は開いた時点のもの（前面の順）で、選んでいる間は並べ替えない
+    let windows = core.recent_windows(RECENT_WINDOWS);
+    // 2 番目（直前に使っていたウィンドウ）を選んでおき、Enter だけで戻れるようにする
+    let selected = usize::from(windows.len() > 1);
+
+    let scale = system_prefs::ui_scale(config.appearance.ui_scale);
+    let size = [480.0 * scale, (60.0 + 32.0 * RECENT_WINDOWS as f32) * scale];
+    let position = my_launcher::monitor::resolve_overlay_position(&config.alt_tab.placement, size);
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(size)
+        .with_always_on_top()
+        .with_decorations(false)
+        .with_resizable(false)
+        .with_title("My Launcher - Recent windows");
+    if let Some(position) = position {
+        viewport = viewport.with_position(position);
+    }
+    let options = eframe::NativeOptions {
+        viewport,
+        centered: position.is_none(),
+        ..Default::default()
+    };
+
+    // ホットキーから開くので、ほかのウィンドウをクリックしたら閉じる
+    let auto_hide = config.auto_hide.enabled.then(|| AutoHide::new(&config.auto_hide));
+    let app = RecentWindowsApp { core, windows, selected, theme: config.appearance.theme, ui_scale: scale, auto_hide };
+    eframe::run_native(
+        "My Launcher Recent Windows",
+        options,
+        Box::new(move |cc| {
+            if let Err(e) = setup_custom_fonts(&cc.egui_ctx) {
+                log::warn!("Failed to load Japanese fonts: {}", e);
+            }
+            Box::new(app)
+        }),
+    )
+}
+
+/// `--name value` の形式の引数の値
+fn flag_value(args: &[String], name: &str) -> Option<String> {
+    args.iter().position(|arg| arg == name).and_then(|index| args.get(index + 1)).cloned()
+}
+
+/// デーモンで、トーストだけを表示する透明なウィンドウ
+struct ToastHost {
+    queue: &'static ToastQueue,
+}
+
+impl eframe::App for ToastHost {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        toast_window::render(ctx, self.queue);
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        [0.0; 4]
+    }
+}
+
+fn run_toast_host() -> Result<(), eframe::Error> {
+    let options = eframe::NativeOptions {
+        viewport: toast_window::viewport_builder(),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My Launcher Notifications",
+        options,
+        Box::new(|cc| {
+            let queue = toast::global();
+            let ctx = cc.egui_ctx.clone();
+            queue.attach_host(move || ctx.request_repaint());
+            Box::new(ToastHost { queue })
+        }),
+    )
+}
+
+fn main() -> Result<(), eframe::Error> {
+    let _ = my_launcher::logger::init_logger();
+    crash::install_panic_hook();
+    let config = LauncherConfig::load();
+    my_launcher::logger::set_redaction(&config.logging);
+
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(exit_code) = run_subcommand(&args, &config) {
+        std::process::exit(exit_code);
+    }
+
+    // mylauncher:// のリンクから開かれた（登録したコマンドは `--url <link>`）
+    let deep_link = match flag_value(&args, "--url").map(|link| deep_link::parse(&link)) {
+        Some(Ok(DeepLink::Open { url })) => {
+            if let Err(e) = open::that(&url) {
+                log::error!("Failed to open {}: {}", url, e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Ok(link)) => Some(link),
+        Some(Err(e)) => {
+            log::warn!("Ignoring deep link: {}", e);
+            None
+        }
+        None => None,
+    };
+
+    // --dmenu: 標準入力の行から選び、選んだ行を標準出力に書く
+    if args.iter().any(|arg| arg == "--dmenu") {
+        return run_dmenu(&config);
+    }
+
+    // --recent: 最近使ったウィンドウだけの一覧（ホットキーの `recent_windows` から開く）
+    if args.iter().any(|arg| arg == "--recent") {
+        return run_recent_windows(&config);
+    }
+
+    // --daemon: UIなしでバックグラウンドサービスとして動作
+    if std::env::args().any(|arg| arg == "--daemon") {
+        if let Err(e) = daemon::start() {
+            log::error!("Daemon error: {}", e);
+            std::process::exit(1);
+        }
+        // メインスレッドではトースト（ホットキーの結果・タイマーの完了）を表示するウィンドウを動かす
+        return run_toast_host();
+    }
+
+    // ランチャーのウィンドウが出る前に、貼り付け先になるウィンドウを覚えておく
+    let previous_window = my_launcher::windows_api::foreground_window();
+    
+    // Create a shared TabManager instance
+    let tab_manager = Arc::new(config.tabs.tab_manager());
+    let services = ServiceStatuses::new();
+    let window_manager = connect_window_manager(&tab_manager, &services);
+    // 前回のセッションは LauncherApp を作るときに読み込み、記録は最初の間隔が過ぎてから上書きする
+    if let Err(e) = session::spawn_recorder(SessionStore::default()) {
+        log::error!("Failed to start session recorder: {}", e);
+    }
+
+    // UI の倍率に合わせてウィンドウも大きくする
+    let scale = system_prefs::ui_scale(config.appearance.ui_scale);
+    let mut drop_down = None;
+    let viewport = match config.appearance.presentation {
+        Presentation::Centered => egui::ViewportBuilder::default()
+            .with_inner_size([1200.0 * scale, 800.0 * scale])
             .with_always_on_top()
             .with_decorations(true)
             .with_title("My Launcher - Alt+Tab Style"),
-        ..Default::default()
+        Presentation::DropDown => {
+            // アクティブなモニターの上端に枠なしで出し、上から降ろす
+            let settings = &config.appearance.drop_down;
+            let size = [settings.width * scale, settings.height * scale];
+            let (top_left, size) = match my_launcher::monitor::active_monitor() {
+                // 作業領域は物理ピクセルなので、大きさと同じ論理座標に直す
+                Some(monitor) => my_launcher::monitor::drop_down_rect(monitor.logical_work_area(), size),
+                None => ([0.0, 0.0], size),
+            };
+            let animate = MotionPrefs::new(config.motion.clone()).current().animations;
+            let slide = DropDown::new(top_left, size[1], Duration::from_millis(settings.slide_ms));
+            let viewport = egui::ViewportBuilder::default()
+                .with_inner_size(size)
+                .with_position(slide.start_position(animate))
+                .with_always_on_top()
+                .with_decorations(false)
+                .with_title("My Launcher");
+            drop_down = Some(slide);
+            viewport
+        }
+    };
+    let options = eframe::NativeOptions { viewport, ..Default::default() };
+    // デーモン・ホットキーから開いた（`--background`）なら、ほかのウィンドウをクリックしたら閉じる
+    let background = args.iter().any(|arg| arg == "--background") || drop_down.is_some();
+    let auto_hide = (config.auto_hide.enabled && background).then(|| AutoHide::new(&config.auto_hide));
+
+    let crash_report = my_launcher::logger::get_log_directory()
+        .ok()
+        .and_then(|dir| crash::take_pending_report(&dir));
+
+    // 起動時の更新チェック（設定で有効な場合のみ）
+    let available_update = Arc::new(Mutex::new(None));
+    if config.update.check_on_startup {
+        let available_update = Arc::clone(&available_update);
+        let update_config = config.update.clone();
+        thread::spawn(move || match update::check_for_update(&update_config) {
+            Ok(release) => *available_update.lock().unwrap() = release,
+            Err(e) => log::warn!("Update check failed: {}", e),
+        });
+    }
+
+    // 外部のトリガー（`my-launcher show`、デーモンの ShowLauncher、mylauncher://search）から開いたとき
+    let (start_mode, start_query) = match deep_link {
+        Some(DeepLink::Search { query, mode }) => (Some(mode), Some(query)),
+        _ => (flag_value(&args, "--mode").and_then(|name| SearchMode::from_name(&name)), flag_value(&args, "--query")),
     };
 
     let tab_manager_for_app = Arc::clone(&tab_manager);
-    eframe::run_native(
+    let result = eframe::run_native(
         "My Launcher",
         options,
-        Box::new(move |_cc| Box::new(LauncherApp::new_with_tab_manager(tab_manager_for_app))),
-    )
+        Box::new(move |cc| {
+            let mut app = LauncherApp::new_with_tab_manager(tab_manager_for_app, window_manager, &config);
+            app.watch_tabs(&cc.egui_ctx);
+            app.crash_report = crash_report;
+            app.available_update = available_update;
+            app.services = services;
+            app.previous_window = previous_window;
+            app.drop_down = drop_down;
+            app.auto_hide = auto_hide;
+            if start_mode.is_some() || start_query.is_some() {
+                app.start_with(start_mode, start_query);
+            }
+            Box::new(app)
+        }),
+    );
+    // ランチャーを閉じたあとに動くマクロが終わるまで待つ
+    macros::wait_for_running();
+    result
 }
\ No newline at end of file
@@ -1,14 +1,18 @@
 use eframe::egui;
 use my_launcher::{
-    ui::alt_tab_grid::AltTabGrid,
+    ui::{alt_tab_grid::AltTabGrid, monitor_map, motion, theme},
     data::{
         window_provider::{WindowProvider, WindowsApiProvider},
         window_item::WindowItem,
     },
     filter::{WindowFilter, TaskbarWindowFilter, filter_windows, SearchFilter, search_items},
     ThumbnailCache,
+    config::{GridConfig, LauncherConfig, MotionConfig, Theme},
+    monitor::resolve_overlay_position,
+    system_prefs::{self, MotionPrefs},
 };
 use std::error::Error;
+use std::time::Duration;
 
 fn setup_custom_fonts(ctx: &egui::Context) -> Result<(), Box<dyn Error>> {
     let mut fonts = egui::FontDefinitions::default();
@@ -60,6 +64,9 @@ fn setup_custom_fonts(ctx: &egui::Context) -> Result<(), Box<dyn Error>> {
     }
 }
 
+/// キャプチャできなかったサムネイルを取り直す間隔
+const THUMBNAIL_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
 struct AltTabApp {
     /// グリッドUIコンポーネント
     grid: AltTabGrid,
@@ -75,10 +82,17 @@ struct AltTabApp {
     thumbnail_cache: ThumbnailCache,
     /// ウィンドウフィルタ
     window_filter: Box<dyn WindowFilter>,
+    /// 管理者権限の警告を閉じたか
+    elevation_notice_dismissed: bool,
+    /// アニメーション効果・省電力の状態に応じた動きの設定
+    motion: MotionPrefs,
+    theme: Theme,
+    /// 文字・グリッド・余白の倍率
+    ui_scale: f32,
 }
 
 impl AltTabApp {
-    fn new() -> Self {
+    fn new(initial_query: String, motion: MotionConfig, theme: Theme, grid: GridConfig, ui_scale: f32) -> Self {
         let mut window_provider = Box::new(WindowsApiProvider::new());
         window_provider.refresh();
         
@@ -90,15 +104,64 @@ impl AltTabApp {
                   window_provider.get_windows().len(), 
                   filtered.len());
         
-        Self {
-            grid: AltTabGrid::new(),
+        let mut app = Self {
+            grid: AltTabGrid::with_config(&grid),
             window_provider,
-            search_text: String::new(),
+            search_text: initial_query,
             show_search_bar: true,  // デフォルトで検索バーを表示
             filtered_windows: filtered,
             thumbnail_cache: ThumbnailCache::new(),
             window_filter,
+            elevation_notice_dismissed: false,
+            motion: MotionPrefs::new(motion),
+            theme,
+            ui_scale,
+        };
+        if !app.search_text.is_empty() {
+            app.filter_windows();
+        }
+        app
+    }
+
+    /// 管理者権限が必要なウィンドウがある場合の警告を表示
+    fn show_elevation_notice(&mut self, ui: &mut egui::Ui) {
+        if self.elevation_notice_dismissed {
+            return;
+        }
+        
+        let elevated_count = self.filtered_windows.iter().filter(|w| w.requires_elevation).count();
+        if elevated_count == 0 {
+            return;
         }
+        
+        egui::Frame::none()
+            .fill(egui::Color32::from_rgb(80, 60, 20))
+            .rounding(egui::Rounding::same(6.0))
+            .inner_margin(egui::Margin::symmetric(12.0, 6.0))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} window(s) belong to elevated processes and can't be previewed or switched to.",
+                            elevated_count
+                        ))
+                        .color(egui::Color32::from_rgb(240, 220, 180))
+                    );
+                    
+                    if ui.button("Relaunch as administrator").clicked() {
+                        // 検索テキストを引き継いで再起動
+                        let args = vec!["--query".to_string(), self.search_text.clone()];
+                        match my_launcher::windows_api::relaunch_elevated(&args) {
+                            Ok(()) => std::process::exit(0),
+                            Err(e) => log::error!("Failed to relaunch elevated: {}", e),
+                        }
+                    }
+                    
+                    if ui.small_button("✕").clicked() {
+                        self.elevation_notice_dismissed = true;
+                    }
+                });
+            });
     }
 
     fn filter_windows(&mut self) {
@@ -131,16 +194,22 @@ impl AltTabApp {
 
 impl eframe::App for AltTabApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // ダークテーマを設定
-        let mut visuals = egui::Visuals::dark();
-        visuals.window_fill = egui::Color32::from_rgba_premultiplied(30, 30, 30, 240);
+        // テーマを設定（背景は半透明、ハイコントラストでは黒）
+        let mut visuals = theme::visuals(self.theme);
+        visuals.window_fill = theme::overlay_fill(self.theme);
         ctx.set_visuals(visuals);
+        theme::apply_scale(ctx, ctx.native_pixels_per_point(), self.ui_scale);
         
-        // 継続的な再描画をリクエスト
-        ctx.request_repaint();
+        // アニメーション効果がオフ・省電力中は動きを控える
+        let motion_settings = self.motion.current();
+        motion::apply(ctx, motion_settings);
+        self.thumbnail_cache.set_enabled(motion_settings.thumbnails);
+        
+        // 入力があれば egui が再描画するので、ここでは継続的な再描画を要求しない
+        // （バックグラウンドで待機中に CPU を使い続けないように）
 
         egui::CentralPanel::default()
-            .frame(egui::Frame::none().fill(egui::Color32::from_rgba_premultiplied(30, 30, 30, 240)))
+            .frame(egui::Frame::none().fill(theme::overlay_fill(self.theme)))
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     // キーボードショートカットの処理
@@ -196,13 +265,24 @@ impl eframe::App for AltTabApp {
                         ui.add_space(20.0);
                     }
                     
+                    self.show_elevation_notice(ui);
+                    
                     // ウィンドウグリッド
                     if !self.filtered_windows.is_empty() {
                         // キーボードナビゲーション
                         self.grid.handle_keyboard_navigation(ui, self.filtered_windows.len());
                         
                         // グリッド表示
-                        if let Some(clicked_index) = self.grid.show(ui, ctx, &self.filtered_windows, &mut self.thumbnail_cache) {
+                        let clicked = self.grid.show(ui, ctx, &self.filtered_windows, &mut self.thumbnail_cache);
+                        // タイルを画面下端のモニターのミニマップに落とすとウィンドウを移動
+                        if let Some(window) = self.grid.dragged().and_then(|index| self.filtered_windows.get(index)) {
+                            monitor_map::drop_window(ctx, window.hwnd, self.grid.drag_released());
+                        }
+                        // キャプチャできなかったサムネイルは少し待って取り直す
+                        if self.thumbnail_cache.take_missing() && motion_settings.continuous_repaint {
+                            ctx.request_repaint_after(THUMBNAIL_RETRY_INTERVAL);
+                        }
+                        if let Some(clicked_index) = clicked {
                             if clicked_index < self.filtered_windows.len() {
                                 let window = &self.filtered_windows[clicked_index];
                                 log::info!("Window clicked: {}", window.title);
@@ -247,24 +327,50 @@ fn main() -> Result<(), eframe::Error> {
         eprintln!("Failed to initialize logger: {}", e);
         env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug")).init();
     }
+    my_launcher::crash::install_panic_hook();
     
     log::info!("Starting Alt+Tab style launcher...");
     
+    // 管理者権限での再起動時に引き継がれる検索テキスト
+    let args: Vec<String> = std::env::args().collect();
+    let initial_query = args
+        .iter()
+        .position(|arg| arg == "--query")
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+        .unwrap_or_default();
+    
+    // 設定に従って表示するモニターを決定
+    let config = LauncherConfig::load();
+    my_launcher::logger::set_redaction(&config.logging);
+    // UI の倍率に合わせてオーバーレイも大きくする
+    let ui_scale = system_prefs::ui_scale(config.appearance.ui_scale);
+    let window_size = [1200.0 * ui_scale, 800.0 * ui_scale];
+    let position = resolve_overlay_position(&config.alt_tab.placement, window_size);
+    let motion_config = config.motion.clone();
+    let theme = config.appearance.theme;
+    let grid_config = config.grid.clone();
+    
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(window_size)
+        .with_decorations(false)
+        .with_transparent(true)
+        .with_resizable(false);
+    if let Some(position) = position {
+        viewport = viewport.with_position(position);
+    }
+    
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1200.0, 800.0])
-            .with_decorations(false)
-            .with_transparent(true)
-            .with_resizable(false),
+        viewport,
         vsync: true,
-        centered: true,
+        centered: position.is_none(),
         ..Default::default()
     };
     
     eframe::run_native(
         "Alt+Tab Launcher",
         options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             log::info!("Creating AltTabApp instance");
             
             // Try to load Japanese font at runtime
@@ -272,7 +378,7 @@ fn main() -> Result<(), eframe::Error> {
                 log::warn!("Failed to setup custom fonts: {}", e);
             }
             
-            Box::new(AltTabApp::new())
+            Box::new(AltTabApp::new(initial_query, motion_config, theme, grid_config, ui_scale))
         }),
     )
 }
\ No newline at end of file
@@ -0,0 +1,309 @@
+//! モニター情報の取得とオーバーレイの配置先の決定
+
+#[cfg(windows)]
+use winapi::{
+    shared::{
+        minwindef::{BOOL, LPARAM, TRUE},
+        windef::{HDC, HMONITOR, LPRECT, POINT},
+    },
+    um::{
+        shellscalingapi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI},
+        winuser::{
+            EnumDisplayMonitors, GetCursorPos, GetForegroundWindow, GetMonitorInfoW, MonitorFromPoint,
+            MonitorFromWindow, MONITORINFO, MONITORINFOF_PRIMARY, MONITOR_DEFAULTTONEAREST,
+        },
+    },
+};
+#[cfg(windows)]
+use std::ptr;
+
+use crate::config::OverlayPlacement;
+
+/// モニターの情報
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    /// 列挙順のインデックス
+    pub index: usize,
+    /// HMONITOR
+    pub handle: isize,
+    /// タスクバーを除いた作業領域 (x, y, width, height)。物理ピクセル
+    pub work_area: (i32, i32, i32, i32),
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    /// 論理座標の作業領域（`ViewportBuilder::with_position` などの egui の座標に使う）
+    pub fn logical_work_area(&self) -> (i32, i32, i32, i32) {
+        logical_work_area(self.work_area, monitor_scale(self.handle))
+    }
+}
+
+/// 物理ピクセルの作業領域をモニターの倍率で割って論理座標にする
+pub fn logical_work_area(work_area: (i32, i32, i32, i32), scale: f32) -> (i32, i32, i32, i32) {
+    let to_logical = |value: i32| (value as f32 / scale).round() as i32;
+    let (x, y, width, height) = work_area;
+    (to_logical(x), to_logical(y), to_logical(width), to_logical(height))
+}
+
+#[cfg(windows)]
+pub fn enumerate_monitors() -> Vec<MonitorInfo> {
+    unsafe {
+        let mut monitors: Vec<MonitorInfo> = Vec::new();
+        let monitors_ptr = &mut monitors as *mut Vec<MonitorInfo>;
+        EnumDisplayMonitors(
+            ptr::null_mut(),
+            ptr::null(),
+            Some(enum_monitor_callback),
+            monitors_ptr as LPARAM,
+        );
+        monitors
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn enum_monitor_callback(
+    hmonitor: HMONITOR,
+    _hdc: HDC,
+    _rect: LPRECT,
+    lparam: LPARAM,
+) -> BOOL {
+    let monitors = &mut *(lparam as *mut Vec<MonitorInfo>);
+
+    let mut info: MONITORINFO = std::mem::zeroed();
+    info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+    if GetMonitorInfoW(hmonitor, &mut info) != 0 {
+        let work = info.rcWork;
+        monitors.push(MonitorInfo {
+            index: monitors.len(),
+            handle: hmonitor as isize,
+            work_area: (work.left, work.top, work.right - work.left, work.bottom - work.top),
+            is_primary: (info.dwFlags & MONITORINFOF_PRIMARY) != 0,
+        });
+    }
+
+    TRUE
+}
+
+/// マウスカーソルがあるモニター
+#[cfg(windows)]
+pub fn cursor_monitor() -> Option<isize> {
+    unsafe {
+        let mut point = POINT { x: 0, y: 0 };
+        if GetCursorPos(&mut point) == 0 {
+            return None;
+        }
+        Some(MonitorFromPoint(point, MONITOR_DEFAULTTONEAREST) as isize)
+    }
+}
+
+/// フォアグラウンドウィンドウがあるモニター
+#[cfg(windows)]
+pub fn foreground_monitor() -> Option<isize> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return None;
+        }
+        Some(MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) as isize)
+    }
+}
+
+/// モニターの表示倍率（96 DPI が 1.0。取れなければ 1.0）
+#[cfg(windows)]
+pub fn monitor_scale(handle: isize) -> f32 {
+    let (mut dpi_x, mut dpi_y) = (0u32, 0u32);
+    let result = unsafe { GetDpiForMonitor(handle as HMONITOR, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) };
+    if result < 0 || dpi_x == 0 {
+        return 1.0;
+    }
+    dpi_x as f32 / 96.0
+}
+
+#[cfg(not(windows))]
+pub fn monitor_scale(_handle: isize) -> f32 {
+    1.0
+}
+
+#[cfg(not(windows))]
+pub fn enumerate_monitors() -> Vec<MonitorInfo> {
+    vec![]
+}
+
+#[cfg(not(windows))]
+pub fn cursor_monitor() -> Option<isize> {
+    None
+}
+
+#[cfg(not(windows))]
+pub fn foreground_monitor() -> Option<isize> {
+    None
+}
+
+/// 配置設定に従ってモニターを選ぶ（見つからなければプライマリモニター）
+pub fn select_monitor<'a>(
+    placement: &OverlayPlacement,
+    monitors: &'a [MonitorInfo],
+    cursor: Option<isize>,
+    focused: Option<isize>,
+) -> Option<&'a MonitorInfo> {
+    let by_handle = |handle: Option<isize>| {
+        handle.and_then(|h| monitors.iter().find(|m| m.handle == h))
+    };
+
+    let selected = match placement {
+        OverlayPlacement::Primary => None,
+        OverlayPlacement::CursorMonitor => by_handle(cursor),
+        OverlayPlacement::FocusedWindowMonitor => by_handle(focused),
+        OverlayPlacement::MonitorIndex { index } => monitors.get(*index),
+    };
+
+    selected.or_else(|| monitors.iter().find(|m| m.is_primary))
+}
+
+/// モニターの作業領域の中央に置いたときの左上座標
+pub fn center_in(work_area: (i32, i32, i32, i32), size: [f32; 2]) -> [f32; 2] {
+    let (x, y, width, height) = work_area;
+    [
+        x as f32 + (width as f32 - size[0]) / 2.0,
+        y as f32 + (height as f32 - size[1]) / 2.0,
+    ]
+}
+
+/// オーバーレイの表示位置を決定（プライマリ中央で良い場合は `None`）
+pub fn resolve_overlay_position(placement: &OverlayPlacement, size: [f32; 2]) -> Option<[f32; 2]> {
+    if *placement == OverlayPlacement::Primary {
+        return None;
+    }
+
+    let monitors = enumerate_monitors();
+    let monitor = select_monitor(placement, &monitors, cursor_monitor(), foreground_monitor())?;
+    log::info!("Placing overlay on monitor {} ({:?})", monitor.index, monitor.work_area);
+    Some(center_in(monitor.logical_work_area(), size))
+}
+
+/// アクティブなモニター（フォアグラウンドウィンドウがあるモニター。分からなければプライマリ）
+pub fn active_monitor() -> Option<MonitorInfo> {
+    let monitors = enumerate_monitors();
+    select_monitor(&OverlayPlacement::FocusedWindowMonitor, &monitors, cursor_monitor(), foreground_monitor()).cloned()
+}
+
+/// 作業領域の上端に中央揃えで置いたときの左上座標と大きさ（作業領域より大きければ縮める）
+pub fn drop_down_rect(work_area: (i32, i32, i32, i32), size: [f32; 2]) -> ([f32; 2], [f32; 2]) {
+    let (x, y, width, height) = work_area;
+    let size = [size[0].min(width as f32), size[1].min(height as f32)];
+    ([x as f32 + (width as f32 - size[0]) / 2.0, y as f32], size)
+}
+
+/// ウィンドウを寄せるモニター上の位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapRegion {
+    Left,
+    Full,
+    Right,
+}
+
+impl SnapRegion {
+    pub const ALL: [SnapRegion; 3] = [SnapRegion::Left, SnapRegion::Full, SnapRegion::Right];
+}
+
+/// 作業領域のうち、その位置に寄せたときのウィンドウの矩形 (x, y, width, height)
+pub fn snap_rect(work_area: (i32, i32, i32, i32), region: SnapRegion) -> (i32, i32, i32, i32) {
+    let (x, y, width, height) = work_area;
+    let half = width / 2;
+    match region {
+        SnapRegion::Left => (x, y, half, height),
+        SnapRegion::Full => work_area,
+        SnapRegion::Right => (x + half, y, width - half, height),
+    }
+}
+
+/// モニターの配置を `width` x `height` の枠に収まるよう縮小した矩形（縦横比を保ち、中央に寄せる）
+pub fn fit_monitors(monitors: &[MonitorInfo], width: f32, height: f32) -> Vec<(f32, f32, f32, f32)> {
+    let Some(min_x) = monitors.iter().map(|m| m.work_area.0).min() else { return Vec::new() };
+    let min_y = monitors.iter().map(|m| m.work_area.1).min().unwrap_or(0);
+    let max_x = monitors.iter().map(|m| m.work_area.0 + m.work_area.2).max().unwrap_or(0);
+    let max_y = monitors.iter().map(|m| m.work_area.1 + m.work_area.3).max().unwrap_or(0);
+    let (total_width, total_height) = ((max_x - min_x).max(1) as f32, (max_y - min_y).max(1) as f32);
+
+    let scale = (width / total_width).min(height / total_height);
+    let offset_x = (width - total_width * scale) / 2.0;
+    let offset_y = (height - total_height * scale) / 2.0;
+    monitors
+        .iter()
+        .map(|m| {
+            let (x, y, w, h) = m.work_area;
+            (
+                offset_x + (x - min_x) as f32 * scale,
+                offset_y + (y - min_y) as f32 * scale,
+                w as f32 * scale,
+                h as f32 * scale,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_monitors() -> Vec<MonitorInfo> {
+        vec![
+            MonitorInfo { index: 0, handle: 100, work_area: (0, 0, 1920, 1040), is_primary: true },
+            MonitorInfo { index: 1, handle: 200, work_area: (1920, 0, 2560, 1400), is_primary: false },
+        ]
+    }
+
+    #[test]
+    fn test_select_monitor_by_cursor() {
+        let monitors = create_test_monitors();
+        let selected = select_monitor(&OverlayPlacement::CursorMonitor, &monitors, Some(200), Some(100));
+        assert_eq!(selected.unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_select_monitor_by_focused_window() {
+        let monitors = create_test_monitors();
+        let selected = select_monitor(&OverlayPlacement::FocusedWindowMonitor, &monitors, Some(200), Some(100));
+        assert_eq!(selected.unwrap().index, 0);
+    }
+
+    #[test]
+    fn test_select_monitor_by_index_falls_back_to_primary() {
+        let monitors = create_test_monitors();
+        let selected = select_monitor(&OverlayPlacement::MonitorIndex { index: 1 }, &monitors, None, None);
+        assert_eq!(selected.unwrap().index, 1);
+
+        let selected = select_monitor(&OverlayPlacement::MonitorIndex { index: 5 }, &monitors, None, None);
+        assert!(selected.unwrap().is_primary);
+    }
+
+    #[test]
+    fn test_center_in() {
+        assert_eq!(center_in((1920, 0, 2560, 1400), [1200.0, 800.0]), [2600.0, 300.0]);
+        // 150% のモニターでは論理座標に直してから中央に置く
+        let logical = logical_work_area((3840, 0, 3840, 2100), 1.5);
+        assert_eq!(logical, (2560, 0, 2560, 1400));
+        assert_eq!(center_in(logical, [1200.0, 800.0]), [3240.0, 300.0]);
+    }
+
+    #[test]
+    fn test_drop_down_rect() {
+        assert_eq!(drop_down_rect((1920, 0, 2560, 1400), [1200.0, 560.0]), ([2600.0, 0.0], [1200.0, 560.0]));
+        // 作業領域より広ければ作業領域に合わせる
+        assert_eq!(drop_down_rect((0, 40, 1280, 680), [1600.0, 800.0]), ([0.0, 40.0], [1280.0, 680.0]));
+    }
+
+    #[test]
+    fn test_snap_rect_and_fit_monitors() {
+        let monitors = create_test_monitors();
+        assert_eq!(snap_rect(monitors[1].work_area, SnapRegion::Left), (1920, 0, 1280, 1400));
+        assert_eq!(snap_rect(monitors[1].work_area, SnapRegion::Right), (3200, 0, 1280, 1400));
+        assert_eq!(snap_rect(monitors[0].work_area, SnapRegion::Full), (0, 0, 1920, 1040));
+
+        // 4480 x 1400 を 448 x 200 に収めると 1/10 で、縦は中央に寄せる
+        let fitted = fit_monitors(&monitors, 448.0, 200.0);
+        assert_eq!(fitted[0], (0.0, 30.0, 192.0, 104.0));
+        assert_eq!(fitted[1], (192.0, 30.0, 256.0, 140.0));
+        assert!(fit_monitors(&[], 100.0, 100.0).is_empty());
+    }
+}
@@ -0,0 +1,316 @@
+//! デスクトップ通知
+//!
+//! Windows 10 以降では WinRT のトースト通知として表示し、アクションセンターにも残す。
+//! トーストを出せなかったときは通知領域のアイコンのバルーンで代わりに表示する。
+//! ランチャーのウィンドウが閉じていても表示できるので、タイマーの完了やバックグラウンドのエラーに使う。
+//!
+//! 通知のボタン（「Reopen launcher」「Retry」）を押すと `set_action_handler` で登録した関数が呼ばれる。
+//! ランチャーはそれを IPC でデーモンに送り、デーモンは自分で処理する。
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock};
+#[cfg(windows)]
+use std::time::Duration;
+
+/// 通知を表示してからアイコンを取り除くまでの時間（バルーンで表示したとき）
+#[cfg(windows)]
+const NOTIFICATION_LIFETIME: Duration = Duration::from_secs(10);
+
+/// トーストの送り主の AUMID（`register_app_id` で現在のユーザーに登録する）
+#[cfg(windows)]
+const APP_ID: &str = "MyLauncher.Launcher";
+
+/// トーストとアクションセンターに表示する送り主の名前
+#[cfg(windows)]
+const APP_DISPLAY_NAME: &str = "My Launcher";
+
+/// 通知のボタンで実行する操作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationAction {
+    /// ランチャーを開き直す
+    ReopenLauncher,
+    /// 止まっているデーモンのサービスを待たずに起動し直す
+    RetryService { name: String },
+}
+
+impl NotificationAction {
+    /// ボタンの表示名
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationAction::ReopenLauncher => "Reopen launcher",
+            NotificationAction::RetryService { .. } => "Retry",
+        }
+    }
+
+    /// トーストの `arguments` に入れる文字列
+    pub fn to_argument(&self) -> String {
+        match self {
+            NotificationAction::ReopenLauncher => "reopen".to_string(),
+            NotificationAction::RetryService { name } => format!("retry:{}", name),
+        }
+    }
+
+    pub fn from_argument(argument: &str) -> Option<Self> {
+        match argument.split_once(':') {
+            None if argument == "reopen" => Some(NotificationAction::ReopenLauncher),
+            Some(("retry", name)) if !name.is_empty() => {
+                Some(NotificationAction::RetryService { name: name.to_string() })
+            }
+            _ => None,
+        }
+    }
+}
+
+type ActionHandler = Arc<dyn Fn(NotificationAction) + Send + Sync>;
+
+fn action_handler() -> &'static Mutex<Option<ActionHandler>> {
+    static HANDLER: OnceLock<Mutex<Option<ActionHandler>>> = OnceLock::new();
+    HANDLER.get_or_init(|| Mutex::new(None))
+}
+
+/// 通知のボタンが押されたときに呼ぶ関数を登録（登録し直すと置き換える）
+pub fn set_action_handler(handler: impl Fn(NotificationAction) + Send + Sync + 'static) {
+    *action_handler().lock().unwrap() = Some(Arc::new(handler));
+}
+
+/// 押されたボタンの操作を実行する（関数が登録されていなければランチャーを開き直すだけ）
+pub fn dispatch_action(action: NotificationAction) {
+    log::info!("Notification action: {:?}", action);
+    let handler = action_handler().lock().unwrap().clone();
+    match (handler, action) {
+        (Some(handler), action) => handler(action),
+        (None, NotificationAction::ReopenLauncher) => reopen_launcher(),
+        (None, action) => log::warn!("No handler for notification action {:?}", action),
+    }
+}
+
+/// ランチャーを新しいプロセスで開く
+pub fn reopen_launcher() {
+    let spawned = crate::paths::launcher_command().and_then(|mut command| command.spawn());
+    if let Err(e) = spawned {
+        log::error!("Failed to reopen launcher: {}", e);
+    }
+}
+
+/// 通知を表示する（表示できなかった場合はログに残すだけ）
+pub fn show_notification(title: &str, message: &str) {
+    show_notification_with_actions(title, message, &[]);
+}
+
+/// ボタン付きの通知を表示する（バルーンで表示したときはボタンは出ない）
+#[cfg(windows)]
+pub fn show_notification_with_actions(title: &str, message: &str, actions: &[NotificationAction]) {
+    log::info!("Showing notification: {} - {}", title, message);
+    if let Err(e) = show_toast(title, message, actions) {
+        log::warn!("Failed to show toast notification ({}), falling back to a balloon", e);
+        show_balloon(title, message);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn show_notification_with_actions(title: &str, message: &str, _actions: &[NotificationAction]) {
+    log::info!("Notification (not supported on this platform): {} - {}", title, message);
+}
+
+/// トースト通知の XML（本文のクリックは最初のボタンと同じ操作）
+#[cfg(any(windows, test))]
+fn toast_xml(title: &str, message: &str, actions: &[NotificationAction]) -> String {
+    let launch = actions
+        .first()
+        .map(|action| format!(r#" launch="{}""#, escape_xml(&action.to_argument())))
+        .unwrap_or_default();
+    let buttons: String = actions
+        .iter()
+        .map(|action| {
+            format!(
+                r#"<action content="{}" arguments="{}" activationType="foreground"/>"#,
+                escape_xml(action.label()),
+                escape_xml(&action.to_argument())
+            )
+        })
+        .collect();
+    let actions_xml = if buttons.is_empty() { String::new() } else { format!("<actions>{}</actions>", buttons) };
+    format!(
+        r#"<toast activationType="foreground"{}><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual>{}</toast>"#,
+        launch,
+        escape_xml(title),
+        escape_xml(message),
+        actions_xml
+    )
+}
+
+#[cfg(any(windows, test))]
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// WinRT のトースト通知を表示する
+#[cfg(windows)]
+fn show_toast(title: &str, message: &str, actions: &[NotificationAction]) -> windows::core::Result<()> {
+    use std::collections::VecDeque;
+    use windows::core::{ComInterface, IInspectable, HSTRING};
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::TypedEventHandler;
+    use windows::UI::Notifications::{ToastActivatedEventArgs, ToastNotification, ToastNotificationManager};
+
+    /// ボタンが押されたときに Activated が届くよう、最近のトーストを手放さずに持っておく数
+    const KEEP_ALIVE: usize = 16;
+    static RECENT: OnceLock<Mutex<VecDeque<ToastNotification>>> = OnceLock::new();
+
+    if let Err(e) = register_app_id() {
+        log::warn!("Failed to register the notification app id: {}", e);
+    }
+    let document = XmlDocument::new()?;
+    document.LoadXml(&HSTRING::from(toast_xml(title, message, actions)))?;
+    let toast = ToastNotification::CreateToastNotification(&document)?;
+    toast.Activated(&TypedEventHandler::<ToastNotification, IInspectable>::new(|_, args| {
+        let argument = args
+            .as_ref()
+            .and_then(|args| args.cast::<ToastActivatedEventArgs>().ok())
+            .and_then(|args| args.Arguments().ok())
+            .map(|argument| argument.to_string_lossy())
+            .unwrap_or_default();
+        match NotificationAction::from_argument(&argument) {
+            Some(action) => dispatch_action(action),
+            None if argument.is_empty() => {}
+            None => log::warn!("Unknown notification argument: {}", argument),
+        }
+        Ok(())
+    }))?;
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?.Show(&toast)?;
+
+    let mut recent = RECENT.get_or_init(|| Mutex::new(VecDeque::new())).lock().unwrap();
+    recent.push_back(toast);
+    while recent.len() > KEEP_ALIVE {
+        recent.pop_front();
+    }
+    Ok(())
+}
+
+/// `HKCU\Software\Classes\AppUserModelId\<APP_ID>` に表示名を登録する（プロセスごとに一度だけ）
+///
+/// インストーラーがないので、登録しておかないとトーストを表示できない。
+#[cfg(windows)]
+fn register_app_id() -> Result<(), String> {
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::winnt::REG_SZ;
+    use winapi::um::winreg::{RegSetKeyValueW, HKEY_CURRENT_USER};
+
+    static REGISTERED: OnceLock<Result<(), String>> = OnceLock::new();
+    REGISTERED
+        .get_or_init(|| {
+            let to_wide = |text: &str| text.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+            let key = to_wide(&format!(r"Software\Classes\AppUserModelId\{}", APP_ID));
+            let name = to_wide("DisplayName");
+            let value = to_wide(APP_DISPLAY_NAME);
+            let status = unsafe {
+                RegSetKeyValueW(
+                    HKEY_CURRENT_USER,
+                    key.as_ptr(),
+                    name.as_ptr(),
+                    REG_SZ,
+                    value.as_ptr() as *const _,
+                    (value.len() * std::mem::size_of::<u16>()) as u32,
+                )
+            };
+            if status as u32 == ERROR_SUCCESS {
+                Ok(())
+            } else {
+                Err(format!("RegSetKeyValueW returned {}", status))
+            }
+        })
+        .clone()
+}
+
+/// 通知領域のアイコンのバルーンとして表示する
+#[cfg(windows)]
+fn show_balloon(title: &str, message: &str) {
+    use std::ptr;
+    use winapi::um::shellapi::{
+        Shell_NotifyIconW, NIF_ICON, NIF_INFO, NIF_TIP, NIIF_INFO, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW,
+    };
+    use winapi::um::winuser::{LoadIconW, IDI_INFORMATION};
+
+    // 同時に複数の通知を出せるよう、呼び出しごとに別のアイコンIDを使う
+    static NEXT_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(1);
+    let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+    let mut data: NOTIFYICONDATAW = unsafe { std::mem::zeroed() };
+    data.cbSize = std::mem::size_of::<NOTIFYICONDATAW>() as u32;
+    data.hWnd = ptr::null_mut();
+    data.uID = id;
+    data.uFlags = NIF_ICON | NIF_TIP | NIF_INFO;
+    data.dwInfoFlags = NIIF_INFO;
+    data.hIcon = unsafe { LoadIconW(ptr::null_mut(), IDI_INFORMATION) };
+    copy_wide(&mut data.szTip, "My Launcher");
+    copy_wide(&mut data.szInfoTitle, title);
+    copy_wide(&mut data.szInfo, message);
+
+    if unsafe { Shell_NotifyIconW(NIM_ADD, &mut data) } == 0 {
+        log::error!("Failed to show notification: {}", std::io::Error::last_os_error());
+        return;
+    }
+
+    // 表示中にアイコンを消すと通知も消えるため、しばらく待ってから取り除く
+    let spawned = std::thread::Builder::new()
+        .name("notification".to_string())
+        .spawn(move || {
+            std::thread::sleep(NOTIFICATION_LIFETIME);
+            let mut data: NOTIFYICONDATAW = unsafe { std::mem::zeroed() };
+            data.cbSize = std::mem::size_of::<NOTIFYICONDATAW>() as u32;
+            data.uID = id;
+            unsafe { Shell_NotifyIconW(NIM_DELETE, &mut data) };
+        });
+    if let Err(e) = spawned {
+        log::error!("Failed to spawn notification cleanup thread: {}", e);
+    }
+}
+
+/// 固定長の UTF-16 バッファに文字列をコピー（収まらない分は切り詰め、必ず NUL で終える）
+#[cfg(windows)]
+fn copy_wide(buffer: &mut [u16], text: &str) {
+    let max = buffer.len() - 1;
+    let mut len = 0;
+    for (slot, unit) in buffer.iter_mut().zip(text.encode_utf16().take(max)) {
+        *slot = unit;
+        len += 1;
+    }
+    buffer[len] = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_arguments() {
+        let actions = vec![
+            NotificationAction::ReopenLauncher,
+            NotificationAction::RetryService { name: "websocket".to_string() },
+        ];
+        for action in &actions {
+            assert_eq!(NotificationAction::from_argument(&action.to_argument()).as_ref(), Some(action));
+        }
+        assert_eq!(NotificationAction::from_argument("retry:"), None);
+        assert_eq!(NotificationAction::from_argument("open"), None);
+        assert_eq!(NotificationAction::from_argument(""), None);
+
+        let xml = toast_xml("Timer <done>", "Tea & \"biscuits\"", &actions);
+        assert!(xml.contains(r#"launch="reopen""#));
+        assert!(xml.contains("<text>Timer &lt;done&gt;</text><text>Tea &amp; &quot;biscuits&quot;</text>"));
+        assert!(xml.contains(r#"<action content="Retry" arguments="retry:websocket" activationType="foreground"/>"#));
+        assert!(!toast_xml("Title", "Message", &[]).contains("<actions>"));
+    }
+}
@@ -0,0 +1,306 @@
+//! よく開くブックマークのプレビュー画像（Open Graph の `og:image`）
+//!
+//! ページの HTML から画像の URL を探し、画像をバックグラウンドで取得してデータディレクトリの
+//! キャッシュ（`disk_cache.rs`）に保存する。
+//! グリッドのブラウザのウィンドウに重ねるファビコンも、同じ仕組みで別のディレクトリにキャッシュする。
+//! 取得と画像の読み込みは `previews` フィーチャーでビルドしたときだけ行う。
+
+use crate::config::PreviewsConfig;
+use crate::data::usage::{UsageEntry, UsageTarget};
+use crate::disk_cache::DiskCache;
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 画像 1 枚の上限
+#[cfg_attr(not(feature = "previews"), allow(dead_code))]
+const MAX_IMAGE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// 画像の URL を探すために読む HTML の上限（`<head>` にあるため先頭だけでよい）
+#[cfg_attr(not(feature = "previews"), allow(dead_code))]
+const MAX_HTML_BYTES: u64 = 256 * 1024;
+
+/// URL から画像のバイト列を取得する処理
+type Fetcher = fn(&str) -> Result<Vec<u8>, Box<dyn Error>>;
+
+#[derive(Default)]
+struct PreviewState {
+    in_flight: HashSet<String>,
+    /// 画像がなかったページ（起動している間は取得し直さない）
+    missing: HashSet<String>,
+}
+
+pub struct PreviewStore {
+    cache: DiskCache,
+    state: Arc<Mutex<PreviewState>>,
+    updated: Arc<AtomicBool>,
+    fetch: Fetcher,
+}
+
+impl PreviewStore {
+    pub fn new(cache: DiskCache) -> Self {
+        Self::with_fetcher(cache, fetch_preview)
+    }
+
+    /// 設定で有効なときだけ作る
+    pub fn from_config(config: &PreviewsConfig) -> Option<Self> {
+        config
+            .enabled
+            .then(|| Self::new(DiskCache::previews(config)))
+    }
+
+    /// ファビコンの URL から画像を取得してキャッシュする
+    pub fn favicons() -> Self {
+        Self::with_fetcher(DiskCache::favicons(), fetch_image)
+    }
+
+    /// ページから画像を取得する処理を差し替えて作成（テスト用）
+    pub fn with_fetcher(cache: DiskCache, fetch: Fetcher) -> Self {
+        Self {
+            cache,
+            state: Arc::new(Mutex::new(PreviewState::default())),
+            updated: Arc::new(AtomicBool::new(false)),
+            fetch,
+        }
+    }
+
+    /// キャッシュ済みの画像（ファイルのまま）
+    pub fn cached(&self, url: &str) -> Option<Vec<u8>> {
+        self.cache.get(url)
+    }
+
+    pub fn cache(&self) -> &DiskCache {
+        &self.cache
+    }
+
+    /// 画像がなかったページを忘れる（キャッシュを消したあと取得し直す）
+    pub fn forget_missing(&self) {
+        self.state.lock().unwrap().missing.clear();
+    }
+
+    /// キャッシュになければバックグラウンドで取得を始める（取得中・画像がなかったページは何もしない）
+    pub fn request(&self, url: &str) {
+        if self.cache.contains(url) {
+            return;
+        }
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.missing.contains(url) || !state.in_flight.insert(url.to_string()) {
+                return;
+            }
+        }
+
+        let url = url.to_string();
+        let cache = self.cache.clone();
+        let state = Arc::clone(&self.state);
+        let updated = Arc::clone(&self.updated);
+        let fetch = self.fetch;
+
+        let spawned = std::thread::Builder::new().name("page-preview".to_string()).spawn(move || {
+            let saved = fetch(&url).and_then(|image| cache.put(&url, &image));
+
+            let mut state = state.lock().unwrap();
+            state.in_flight.remove(&url);
+            match saved {
+                Ok(()) => updated.store(true, Ordering::SeqCst),
+                Err(e) => {
+                    log::debug!("No preview for {}: {}", url, e);
+                    state.missing.insert(url);
+                }
+            }
+        });
+        if let Err(e) = spawned {
+            log::error!("Failed to spawn preview thread: {}", e);
+            self.state.lock().unwrap().in_flight.clear();
+        }
+    }
+
+    /// 前回呼び出してから画像が届いたか（届いていれば再描画する）
+    pub fn take_updated(&self) -> bool {
+        self.updated.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// `min_opens` 回以上開いたページの URL（プレビューを取得する対象）
+pub fn frequent_urls(entries: &[UsageEntry], min_opens: usize) -> HashSet<String> {
+    entries
+        .iter()
+        .filter(|entry| entry.opens.len() >= min_opens)
+        .filter_map(|entry| match &entry.target {
+            UsageTarget::Url { url } => Some(url.clone()),
+            UsageTarget::Window(_) => None,
+        })
+        .collect()
+}
+
+/// HTML の `<meta property="og:image">`（なければ `twitter:image`）の画像の URL
+///
+/// 相対 URL はページの URL を基準に解決する。
+pub fn extract_image_url(html: &str, page_url: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut found: Option<(usize, String)> = None;
+    for (start, _) in lower.match_indices("<meta") {
+        let end = lower[start..].find('>').map(|end| start + end)?;
+        let tag = &html[start..end];
+        let name = attribute(tag, "property").or_else(|| attribute(tag, "name")).unwrap_or_default().to_ascii_lowercase();
+        let priority = match name.as_str() {
+            "og:image" | "og:image:url" | "og:image:secure_url" => 0,
+            "twitter:image" | "twitter:image:src" => 1,
+            _ => continue,
+        };
+        let Some(content) = attribute(tag, "content").filter(|content| !content.is_empty()) else {
+            continue;
+        };
+        if found.as_ref().is_none_or(|(best, _)| priority < *best) {
+            found = Some((priority, content));
+        }
+    }
+
+    let content = found?.1.replace("&amp;", "&");
+    let base = url::Url::parse(page_url).ok()?;
+    base.join(&content).ok().map(String::from)
+}
+
+/// タグの属性の値（`name="value"` / `name='value'`）
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(offset) = lower[from..].find(name) {
+        let start = from + offset;
+        from = start + name.len();
+        // `og:image:width` の `content` などの部分一致を除く
+        let preceded = lower[..start].chars().next_back().is_some_and(|c| c.is_ascii_alphanumeric() || c == '-' || c == ':');
+        let rest = lower[from..].trim_start();
+        if preceded || !rest.starts_with('=') {
+            continue;
+        }
+        let value_start = tag.len() - rest.len() + 1;
+        let value = tag[value_start..].trim_start();
+        let quote = value.chars().next()?;
+        return if quote == '"' || quote == '\'' {
+            value[1..].find(quote).map(|end| value[1..1 + end].trim().to_string())
+        } else {
+            Some(value.split_whitespace().next().unwrap_or_default().trim_end_matches('/').to_string())
+        };
+    }
+    None
+}
+
+#[cfg(feature = "previews")]
+fn fetch_preview(page_url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    use std::io::Read;
+
+    let agent = ureq::AgentBuilder::new().timeout(std::time::Duration::from_secs(5)).build();
+    let user_agent = concat!("my-launcher/", env!("CARGO_PKG_VERSION"));
+
+    let mut html = String::new();
+    agent.get(page_url).set("User-Agent", user_agent).call()?.into_reader().take(MAX_HTML_BYTES).read_to_string(&mut html)?;
+    let image_url = extract_image_url(&html, page_url).ok_or("no og:image")?;
+    fetch_image(&image_url)
+}
+
+/// 画像を取得する（`MAX_IMAGE_BYTES` を超えるものは取得しない）
+#[cfg(feature = "previews")]
+fn fetch_image(image_url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    use std::io::Read;
+
+    if !image_url.starts_with("http://") && !image_url.starts_with("https://") {
+        return Err(format!("unsupported image URL: {}", image_url).into());
+    }
+    let response = ureq::get(image_url)
+        .set("User-Agent", concat!("my-launcher/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(5))
+        .call()?;
+    let mut image = Vec::new();
+    response.into_reader().take(MAX_IMAGE_BYTES + 1).read_to_end(&mut image)?;
+    if image.len() as u64 > MAX_IMAGE_BYTES {
+        return Err(format!("image is larger than {} bytes", MAX_IMAGE_BYTES).into());
+    }
+    Ok(image)
+}
+
+#[cfg(not(feature = "previews"))]
+fn fetch_preview(_page_url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    Err("Previews are not enabled (build with --features previews)".into())
+}
+
+#[cfg(not(feature = "previews"))]
+fn fetch_image(_image_url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    Err("Previews are not enabled (build with --features previews)".into())
+}
+
+/// 画像を `max_width` × `max_height` に収まるよう縮小し、RGBA の画素にする（幅, 高さ, 画素）
+#[cfg(feature = "previews")]
+pub fn decode_thumbnail(bytes: &[u8], max_width: u32, max_height: u32) -> Option<(usize, usize, Vec<u8>)> {
+    let image = image::load_from_memory(bytes).ok()?.thumbnail(max_width, max_height).to_rgba8();
+    Some((image.width() as usize, image.height() as usize, image.into_raw()))
+}
+
+#[cfg(not(feature = "previews"))]
+pub fn decode_thumbnail(_bytes: &[u8], _max_width: u32, _max_height: u32) -> Option<(usize, usize, Vec<u8>)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_extract_image_url() {
+        let html = r#"<html><head>
+            <meta name="twitter:image" content="https://cdn.example.com/card.png">
+            <META property="og:image:width" content="1200" />
+            <meta content='/images/og.png?a=1&amp;b=2' property='og:image'>
+        </head></html>"#;
+        assert_eq!(
+            extract_image_url(html, "https://example.com/docs/page").as_deref(),
+            Some("https://example.com/images/og.png?a=1&b=2")
+        );
+
+        let twitter_only = r#"<meta name="twitter:image" content="https://cdn.example.com/card.png">"#;
+        assert_eq!(extract_image_url(twitter_only, "https://example.com/").as_deref(), Some("https://cdn.example.com/card.png"));
+        assert_eq!(extract_image_url("<meta name=\"description\" content=\"x\">", "https://example.com/"), None);
+    }
+
+    #[test]
+    fn test_request_caches_and_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PreviewStore::with_fetcher(DiskCache::new(dir.path().to_path_buf(), 10), |url| {
+            if url.contains("missing") {
+                Err("no og:image".into())
+            } else {
+                // ページごとに違う画像
+                Ok(vec![*url.as_bytes().last().unwrap(); 6])
+            }
+        });
+
+        let wait = |url: &str| {
+            for _ in 0..100 {
+                if !store.state.lock().unwrap().in_flight.contains(url) {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        };
+
+        store.request("https://example.com/a");
+        wait("https://example.com/a");
+        assert!(store.take_updated());
+        assert_eq!(store.cached("https://example.com/a").map(|image| image.len()), Some(6));
+
+        // 上限を超えたら古い画像から消す
+        std::thread::sleep(Duration::from_millis(20));
+        store.request("https://example.com/b");
+        wait("https://example.com/b");
+        assert!(store.take_updated());
+        assert!(store.cached("https://example.com/b").is_some());
+        assert!(store.cached("https://example.com/a").is_none());
+
+        store.request("https://example.com/missing");
+        wait("https://example.com/missing");
+        assert!(!store.take_updated());
+        assert!(store.state.lock().unwrap().missing.contains("https://example.com/missing"));
+    }
+}
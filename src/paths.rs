@@ -0,0 +1,195 @@
+//! ファイルの保存先の解決
+//!
+//! 設定・使用履歴・キャッシュ・ログのパスはすべてここで決める。
+//! ポータブルモード（実行ファイルと同じ場所に `portable.flag` があるか `--portable` 付きで起動）
+//! では実行ファイルのディレクトリに、通常は `%APPDATA%\my-launcher` に保存する。
+//! 自分自身を起動し直すときは `launcher_command` を使い、ポータブルモードを子プロセスへ引き継ぐ。
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// ポータブルモードを有効にするファイル名
+pub const PORTABLE_FLAG_FILE: &str = "portable.flag";
+
+/// 子プロセスへポータブルモードを引き継ぐ環境変数
+pub const PORTABLE_ENV: &str = "MY_LAUNCHER_PORTABLE";
+
+const APP_DIR_NAME: &str = "my-launcher";
+
+lazy_static::lazy_static! {
+    static ref PORTABLE: bool = portable_requested(
+        std::env::args(),
+        std::env::var(PORTABLE_ENV).ok().as_deref(),
+        exe_dir().join(PORTABLE_FLAG_FILE).exists(),
+    );
+
+    static ref DATA_DIR: PathBuf = {
+        let app_data = std::env::var("APPDATA").ok().map(PathBuf::from)
+            .or_else(|| std::env::var("HOME").ok().map(|home| PathBuf::from(home).join(".config")));
+        resolve_data_dir(*PORTABLE, &exe_dir(), app_data.as_deref())
+    };
+}
+
+/// 実行ファイルのあるディレクトリ
+pub fn exe_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// ポータブルモードで動作しているか
+pub fn is_portable() -> bool {
+    *PORTABLE
+}
+
+/// `--portable` 付きで起動されたか、親プロセスから引き継いだか、`portable.flag` があるか
+pub fn portable_requested(mut args: impl Iterator<Item = String>, env: Option<&str>, flag_exists: bool) -> bool {
+    args.any(|arg| arg == "--portable") || env == Some("1") || flag_exists
+}
+
+/// 自分自身を起動するコマンド（ポータブルモードなら環境変数で子プロセスへ引き継ぐ）
+pub fn launcher_command() -> std::io::Result<Command> {
+    let mut command = Command::new(std::env::current_exe()?);
+    if is_portable() {
+        command.env(PORTABLE_ENV, "1");
+    }
+    Ok(command)
+}
+
+/// データの保存先を決定
+pub fn resolve_data_dir(portable: bool, exe_dir: &Path, app_data: Option<&Path>) -> PathBuf {
+    match (portable, app_data) {
+        (false, Some(app_data)) => app_data.join(APP_DIR_NAME),
+        _ => exe_dir.to_path_buf(),
+    }
+}
+
+/// すべてのデータのルートディレクトリ
+pub fn data_dir() -> PathBuf {
+    DATA_DIR.clone()
+}
+
+pub fn config_path() -> PathBuf {
+    data_dir().join("config.json")
+}
+
+pub fn favorites_path() -> PathBuf {
+    data_dir().join("favorites.json")
+}
+
+pub fn aliases_path() -> PathBuf {
+    data_dir().join("aliases.json")
+}
+
+pub fn notes_path() -> PathBuf {
+    data_dir().join("notes.md")
+}
+
+pub fn timers_path() -> PathBuf {
+    data_dir().join("timers.json")
+}
+
+pub fn layouts_path() -> PathBuf {
+    data_dir().join("layouts.json")
+}
+
+/// 最後に分かっていたタブ一覧（起動直後に拡張機能の接続を待たずに使う）
+pub fn tab_snapshot_path() -> PathBuf {
+    data_dir().join("tabs.json")
+}
+
+/// 前回のセッションで開いていたアプリ
+pub fn session_path() -> PathBuf {
+    data_dir().join("session.json")
+}
+
+/// ウィンドウとプロファイルに付けたワークスペースのタグ
+pub fn workspaces_path() -> PathBuf {
+    data_dir().join("workspaces.json")
+}
+
+/// ローカル HTTP API のトークン（初回起動時に作る）
+pub fn api_token_path() -> PathBuf {
+    data_dir().join("api_token")
+}
+
+/// 結果を開いたときの検索語（検索語が空のときに表示する）
+pub fn recent_queries_path() -> PathBuf {
+    data_dir().join("recent_queries.json")
+}
+
+pub fn usage_db_path() -> PathBuf {
+    data_dir().join("usage.json")
+}
+
+/// プロファイルのエクスポートに含めるユーザーデータ（キャッシュ・ログ・トークン・タブやセッションの記録は含めない）
+///
+/// 新しく保存するユーザーデータを増やしたらここにも追加する。
+pub fn profile_files() -> Vec<PathBuf> {
+    vec![
+        config_path(),
+        favorites_path(),
+        aliases_path(),
+        usage_db_path(),
+        notes_path(),
+        timers_path(),
+        layouts_path(),
+        workspaces_path(),
+        recent_queries_path(),
+    ]
+}
+
+pub fn favicon_cache_dir() -> PathBuf {
+    data_dir().join("cache").join("favicons")
+}
+
+/// ブックマーク・履歴の索引（`data/browser_index.rs`）
+pub fn browser_index_path() -> PathBuf {
+    data_dir().join("cache").join("browser_index.json")
+}
+
+/// よく開くブックマークのプレビュー画像（`page_preview.rs`）
+pub fn preview_cache_dir() -> PathBuf {
+    data_dir().join("cache").join("previews")
+}
+
+pub fn logs_dir() -> PathBuf {
+    data_dir().join("logs")
+}
+
+pub fn exports_dir() -> PathBuf {
+    data_dir().join("exports")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_data_dir_uses_app_data() {
+        let dir = resolve_data_dir(false, Path::new("/opt/launcher"), Some(Path::new("/home/user/AppData")));
+        assert_eq!(dir, PathBuf::from("/home/user/AppData").join("my-launcher"));
+    }
+
+    #[test]
+    fn test_resolve_data_dir_portable() {
+        let dir = resolve_data_dir(true, Path::new("/media/usb/launcher"), Some(Path::new("/home/user/AppData")));
+        assert_eq!(dir, PathBuf::from("/media/usb/launcher"));
+    }
+
+    #[test]
+    fn test_portable_requested() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>().into_iter();
+        assert!(portable_requested(args(&["my-launcher", "--portable"]), None, false));
+        assert!(portable_requested(args(&["my-launcher", "--recent"]), Some("1"), false));
+        assert!(portable_requested(args(&["my-launcher"]), None, true));
+        assert!(!portable_requested(args(&["my-launcher"]), Some("0"), false));
+    }
+
+    #[test]
+    fn test_resolve_data_dir_without_app_data_falls_back_to_exe_dir() {
+        let dir = resolve_data_dir(false, Path::new("/opt/launcher"), None);
+        assert_eq!(dir, PathBuf::from("/opt/launcher"));
+    }
+}
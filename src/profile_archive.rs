@@ -0,0 +1,215 @@
+//! 設定と使用履歴のエクスポート／インポート
+//!
+//! 設定・お気に入り・エイリアス・使用履歴・メモなど（`paths::profile_files`）を
+//! 1つのJSONファイルにまとめ、別のマシンへ移行できるようにする。
+//! 設定でメモのファイル（`notes.path`）を変えていれば、そのファイルを書き出して同じ場所へ戻す。
+
+use crate::config::LauncherConfig;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// アーカイブ形式のバージョン
+pub const ARCHIVE_VERSION: u32 = 1;
+
+const CONFIG_FILE: &str = "config.json";
+const NOTES_FILE: &str = "notes.md";
+
+/// アーカイブに含めるファイル名（データディレクトリ直下）
+pub fn profile_file_names() -> Vec<String> {
+    crate::paths::profile_files()
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileArchive {
+    pub version: u32,
+    pub created_at: String,
+    pub files: BTreeMap<String, String>,
+}
+
+impl ProfileArchive {
+    /// データディレクトリから存在するファイルを集める（メモは設定で指定したファイルから）
+    pub fn collect(data_dir: &Path) -> io::Result<Self> {
+        let config = fs::read_to_string(data_dir.join(CONFIG_FILE)).ok();
+        let mut files = BTreeMap::new();
+        for name in profile_file_names() {
+            let path = profile_file_path(data_dir, &name, config.as_deref());
+            if path.exists() {
+                let content = fs::read_to_string(&path)?;
+                files.insert(name, content);
+            }
+        }
+
+        Ok(Self {
+            version: ARCHIVE_VERSION,
+            created_at: Local::now().to_rfc3339(),
+            files,
+        })
+    }
+
+    /// データディレクトリへ書き戻す。既存のファイルは `.bak` として残す。
+    pub fn restore(&self, data_dir: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+        if self.version > ARCHIVE_VERSION {
+            return Err(format!(
+                "Unsupported profile archive version {} (supported: {})",
+                self.version, ARCHIVE_VERSION
+            )
+            .into());
+        }
+
+        // 既知のファイル以外は書き込まない（パストラバーサル対策）
+        let known = profile_file_names();
+        if let Some(unknown) = self.files.keys().find(|name| !known.contains(name)) {
+            return Err(format!("Unexpected file in profile archive: {}", unknown).into());
+        }
+
+        fs::create_dir_all(data_dir)?;
+        let config = self.files.get(CONFIG_FILE).map(String::as_str);
+        let mut restored = Vec::new();
+        for (name, content) in &self.files {
+            let path = profile_file_path(data_dir, name, config);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if path.exists() {
+                fs::copy(&path, backup_path(&path))?;
+            }
+            fs::write(&path, content)?;
+            restored.push(name.clone());
+        }
+
+        Ok(restored)
+    }
+}
+
+/// アーカイブのファイルの場所（メモは `config` の `notes.path` があればそこ）
+fn profile_file_path(data_dir: &Path, name: &str, config: Option<&str>) -> PathBuf {
+    if name == NOTES_FILE {
+        let notes = config.and_then(|config| serde_json::from_str::<LauncherConfig>(config).ok()).and_then(|config| config.notes.path);
+        if let Some(notes) = notes {
+            return notes;
+        }
+    }
+    data_dir.join(name)
+}
+
+/// 上書きする前のファイルの退避先（`notes.md` → `notes.md.bak`）
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// 現在のプロファイルをファイルに書き出す
+pub fn export_profile(target: &Path) -> Result<usize, Box<dyn Error>> {
+    let archive = ProfileArchive::collect(&crate::paths::data_dir())?;
+    if let Some(parent) = target.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(target, serde_json::to_string_pretty(&archive)?)?;
+    log::info!("Exported {} profile files to {}", archive.files.len(), target.display());
+    Ok(archive.files.len())
+}
+
+/// ファイルからプロファイルを読み込んで復元する
+pub fn import_profile(source: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let content = fs::read_to_string(source)?;
+    let archive: ProfileArchive = serde_json::from_str(&content)?;
+    let restored = archive.restore(&crate::paths::data_dir())?;
+    log::info!("Imported {} profile files from {}", restored.len(), source.display());
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_and_restore_roundtrip() {
+        let source = tempfile::tempdir().unwrap();
+        fs::write(source.path().join("config.json"), r#"{"update":{}}"#).unwrap();
+        fs::write(source.path().join("usage.json"), "[]").unwrap();
+        fs::write(source.path().join("notes.md"), "- [ ] buy milk").unwrap();
+        fs::write(source.path().join("unrelated.txt"), "ignored").unwrap();
+
+        let archive = ProfileArchive::collect(source.path()).unwrap();
+        assert_eq!(archive.files.len(), 3);
+
+        let target = tempfile::tempdir().unwrap();
+        let restored = archive.restore(target.path()).unwrap();
+        assert_eq!(restored, vec!["config.json", "notes.md", "usage.json"]);
+        assert_eq!(fs::read_to_string(target.path().join("usage.json")).unwrap(), "[]");
+        assert_eq!(fs::read_to_string(target.path().join("notes.md")).unwrap(), "- [ ] buy milk");
+    }
+
+    #[test]
+    fn test_custom_notes_path_is_archived_and_restored_there() {
+        let source = tempfile::tempdir().unwrap();
+        let notes_dir = tempfile::tempdir().unwrap();
+        let notes = notes_dir.path().join("journal").join("my-notes.md");
+        fs::create_dir_all(notes.parent().unwrap()).unwrap();
+        fs::write(&notes, "- [ ] call back").unwrap();
+        let config = serde_json::json!({ "notes": { "path": notes } });
+        fs::write(source.path().join("config.json"), config.to_string()).unwrap();
+
+        let archive = ProfileArchive::collect(source.path()).unwrap();
+        assert_eq!(archive.files.get("notes.md").map(String::as_str), Some("- [ ] call back"));
+
+        fs::remove_dir_all(notes.parent().unwrap()).unwrap();
+        let target = tempfile::tempdir().unwrap();
+        archive.restore(target.path()).unwrap();
+        assert_eq!(fs::read_to_string(&notes).unwrap(), "- [ ] call back");
+        assert!(!target.path().join("notes.md").exists());
+    }
+
+    #[test]
+    fn test_profile_files_cover_persisted_user_data() {
+        let names = profile_file_names();
+        for name in ["config.json", "favorites.json", "aliases.json", "usage.json", "notes.md", "layouts.json", "workspaces.json", "timers.json", "recent_queries.json"] {
+            assert!(names.iter().any(|known| known == name), "{} is missing", name);
+        }
+        assert!(!names.iter().any(|name| name == "api_token"));
+    }
+
+    #[test]
+    fn test_restore_backs_up_existing_files() {
+        let target = tempfile::tempdir().unwrap();
+        fs::write(target.path().join("config.json"), "old").unwrap();
+        fs::write(target.path().join("notes.md"), "old notes").unwrap();
+
+        let mut files = BTreeMap::new();
+        files.insert("config.json".to_string(), "new".to_string());
+        files.insert("notes.md".to_string(), "new notes".to_string());
+        let archive = ProfileArchive { version: ARCHIVE_VERSION, created_at: String::new(), files };
+
+        archive.restore(target.path()).unwrap();
+        assert_eq!(fs::read_to_string(target.path().join("config.json")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(target.path().join("config.json.bak")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(target.path().join("notes.md.bak")).unwrap(), "old notes");
+    }
+
+    #[test]
+    fn test_restore_rejects_unknown_files() {
+        let mut files = BTreeMap::new();
+        files.insert("../evil.exe".to_string(), "x".to_string());
+        let archive = ProfileArchive { version: ARCHIVE_VERSION, created_at: String::new(), files };
+
+        let target = tempfile::tempdir().unwrap();
+        assert!(archive.restore(target.path()).is_err());
+    }
+
+    #[test]
+    fn test_restore_rejects_newer_version() {
+        let archive = ProfileArchive { version: ARCHIVE_VERSION + 1, created_at: String::new(), files: BTreeMap::new() };
+        let target = tempfile::tempdir().unwrap();
+        assert!(archive.restore(target.path()).is_err());
+    }
+}
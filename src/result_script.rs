@@ -0,0 +1,221 @@
+//! 検索結果を表示する前に変換するユーザースクリプト（設定の `scripts`、Rhai）
+//!
+//! スクリプトは `fn process(query, results)` を定義し、表示する結果の配列を返す。
+//! 並べ替え・絞り込み・タイトルの書き換え・`url_result(title, url)` で作った結果の追加ができる。
+//!
+//! ```rhai
+//! fn process(query, results) {
+//!     let github = results.filter(|r| r.url.contains("github.com"));
+//!     let rest = results.filter(|r| !r.url.contains("github.com"));
+//!     github + rest
+//! }
+//! ```
+//!
+//! 結果は `title` / `description`（書き換え可）と `id` / `kind` / `url` / `process`（読み取りのみ）を持つ。
+//! Rhai はファイルやネットワークに触れず、処理の回数と深さにも上限を設ける。
+//! 失敗したときは元の結果をそのまま表示する。
+
+use crate::config::ScriptsConfig;
+use crate::core::{SearchMode, SearchResult};
+
+#[cfg(feature = "scripting")]
+pub use self::rhai_scripts::ResultScripts;
+
+#[cfg(feature = "scripting")]
+mod rhai_scripts {
+    use super::*;
+    use crate::core::{Action, ResultType};
+    use rhai::{Array, Dynamic, Engine, Scope, AST};
+
+    /// スクリプトが1回の変換で実行できる処理の数
+    const MAX_OPERATIONS: u64 = 200_000;
+
+    /// スクリプトに渡す結果
+    #[derive(Clone)]
+    struct ScriptResult(SearchResult);
+
+    /// モードごとのコンパイル済みスクリプト
+    pub struct ResultScripts {
+        engine: Engine,
+        scripts: Vec<(SearchMode, AST)>,
+    }
+
+    impl ResultScripts {
+        /// 設定したスクリプトを読み込む（読み込めないものはログに出して使わない）
+        pub fn load(config: &ScriptsConfig) -> Self {
+            let engine = build_engine();
+            let mut scripts = Vec::new();
+            for mode in [SearchMode::Browser, SearchMode::Windows, SearchMode::Tabs] {
+                let Some(path) = config.path(mode) else {
+                    continue;
+                };
+                match engine.compile_file(path.clone()) {
+                    Ok(ast) => {
+                        log::info!("Loaded {} result script from {}", mode.name(), path.display());
+                        scripts.push((mode, ast));
+                    }
+                    Err(e) => log::warn!("Failed to load result script {}: {}", path.display(), e),
+                }
+            }
+            Self { engine, scripts }
+        }
+
+        /// スクリプトの文字列から作る（テスト用）
+        pub fn from_source(mode: SearchMode, source: &str) -> Result<Self, String> {
+            let engine = build_engine();
+            let ast = engine.compile(source).map_err(|e| e.to_string())?;
+            Ok(Self { engine, scripts: vec![(mode, ast)] })
+        }
+
+        /// モードのスクリプトで結果を変換する（スクリプトがなければそのまま）
+        pub fn apply(&self, mode: SearchMode, query: &str, results: Vec<SearchResult>) -> Vec<SearchResult> {
+            let Some((_, ast)) = self.scripts.iter().find(|(script_mode, _)| *script_mode == mode) else {
+                return results;
+            };
+            let array: Array = results.iter().cloned().map(|result| Dynamic::from(ScriptResult(result))).collect();
+            let mut scope = Scope::new();
+            match self.engine.call_fn::<Array>(&mut scope, ast, "process", (query.to_string(), array)) {
+                Ok(array) => array
+                    .into_iter()
+                    .filter_map(|item| item.try_cast::<ScriptResult>())
+                    .map(|item| item.0)
+                    .collect(),
+                Err(e) => {
+                    log::warn!("{} result script failed: {}", mode.name(), e);
+                    results
+                }
+            }
+        }
+    }
+
+    fn build_engine() -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(32);
+        engine.set_max_expr_depths(64, 32);
+        engine.set_max_string_size(64 * 1024);
+        engine.set_max_array_size(10_000);
+        engine.set_max_map_size(10_000);
+
+        engine
+            .register_type_with_name::<ScriptResult>("Result")
+            .register_get_set(
+                "title",
+                |result: &mut ScriptResult| result.0.title.clone(),
+                |result: &mut ScriptResult, title: String| result.0.title = title,
+            )
+            .register_get_set(
+                "description",
+                |result: &mut ScriptResult| result.0.description.clone(),
+                |result: &mut ScriptResult, description: String| result.0.description = description,
+            )
+            .register_get("id", |result: &mut ScriptResult| result.0.action.id())
+            .register_get("kind", |result: &mut ScriptResult| kind(&result.0.result_type).to_string())
+            .register_get("url", |result: &mut ScriptResult| {
+                result.0.metadata.as_ref().map(|metadata| metadata.url().to_string()).unwrap_or_default()
+            })
+            .register_get("process", |result: &mut ScriptResult| {
+                result.0.window_info.as_ref().map(|window| window.process_name.clone()).unwrap_or_default()
+            })
+            .register_fn("url_result", |title: &str, url: &str| {
+                ScriptResult(SearchResult {
+                    title: title.to_string(),
+                    description: url.to_string(),
+                    action: Action::OpenUrl(url.to_string()),
+                    window_info: None,
+                    result_type: ResultType::Command,
+                    group: None,
+                    metadata: None,
+                })
+            });
+        engine
+    }
+
+    /// スクリプトで見分けるための結果の種類
+    fn kind(result_type: &ResultType) -> &'static str {
+        match result_type {
+            ResultType::GoogleSearch => "google",
+            ResultType::Bookmark => "bookmark",
+            ResultType::History => "history",
+            ResultType::Window => "window",
+            ResultType::Tab => "tab",
+            ResultType::PinnedTab => "pinned_tab",
+            ResultType::ReadingList => "reading_list",
+            ResultType::Update => "update",
+            ResultType::Command => "command",
+            ResultType::Note => "note",
+            ResultType::Timer => "timer",
+            ResultType::Color { .. } => "color",
+        }
+    }
+}
+
+/// `scripting` なしでビルドしたときは結果をそのまま使う
+#[cfg(not(feature = "scripting"))]
+pub struct ResultScripts;
+
+#[cfg(not(feature = "scripting"))]
+impl ResultScripts {
+    pub fn load(config: &ScriptsConfig) -> Self {
+        let configured = [SearchMode::Browser, SearchMode::Windows, SearchMode::Tabs]
+            .into_iter()
+            .any(|mode| config.path(mode).is_some());
+        if configured {
+            log::warn!("Result scripts are not enabled (build with --features scripting)");
+        }
+        Self
+    }
+
+    pub fn apply(&self, _mode: SearchMode, _query: &str, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        results
+    }
+}
+
+#[cfg(all(test, feature = "scripting"))]
+mod tests {
+    use super::*;
+    use crate::core::{Action, ResultType};
+
+    fn result(title: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            description: String::new(),
+            action: Action::OpenUrl(format!("https://example.com/{}", title)),
+            window_info: None,
+            result_type: ResultType::Bookmark,
+            group: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let scripts = ResultScripts::from_source(
+            SearchMode::Browser,
+            r#"
+                fn process(query, results) {
+                    results.reverse();
+                    for i in 0..results.len() { results[i].title = results[i].title + " (" + results[i].kind + ")"; }
+                    results.push(url_result("Search " + query, "https://example.com/?q=" + query));
+                    results
+                }
+            "#,
+        )
+        .unwrap();
+
+        let results = scripts.apply(SearchMode::Browser, "rust", vec![result("a"), result("b")]);
+        let titles: Vec<_> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["b (bookmark)", "a (bookmark)", "Search rust"]);
+        assert_eq!(results[2].action, Action::OpenUrl("https://example.com/?q=rust".to_string()));
+
+        // 他のモードには適用しない
+        assert_eq!(scripts.apply(SearchMode::Tabs, "rust", vec![result("a")]).len(), 1);
+    }
+
+    #[test]
+    fn test_apply_keeps_results_on_error() {
+        let scripts = ResultScripts::from_source(SearchMode::Browser, "fn process(query, results) { loop {} }").unwrap();
+        let results = scripts.apply(SearchMode::Browser, "x", vec![result("a")]);
+        assert_eq!(results[0].title, "a");
+    }
+}
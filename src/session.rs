@@ -0,0 +1,213 @@
+//! 前回のセッションで開いていたアプリ（`restore previous session`）
+//!
+//! タスクバーに表示されるウィンドウを持つアプリの実行ファイルと引数を session.json に定期的に記録し、
+//! 次に起動したときに、記録にあって今は動いていないアプリを起動し直せるようにする。
+//! シャットダウンの直前には記録できないことがあるため、終了時ではなく一定間隔で上書きする。
+
+use crate::core::WindowInfo;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// セッションを記録する間隔
+const RECORD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 記録したアプリ
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionApp {
+    pub process_name: String,
+    pub exe_path: String,
+    /// 実行ファイルのあとの引数（取得できなければ `None`）
+    #[serde(default)]
+    pub arguments: Option<String>,
+    /// 記録したときのウィンドウのタイトル（表示用）
+    pub title: String,
+}
+
+/// 記録したセッション
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// 記録日時（UNIX時刻・秒）
+    pub saved_at: i64,
+    pub apps: Vec<SessionApp>,
+}
+
+/// セッションの保存先
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    path: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Option<Session>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    pub fn save(&self, session: &Session) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(session)?)?;
+        Ok(())
+    }
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new(crate::paths::session_path())
+    }
+}
+
+/// 現在のウィンドウからセッションを作る
+///
+/// 同じコマンドラインのアプリは1つにまとめる（1つのプロセスが複数のウィンドウを持つ場合など）。
+/// 実行ファイルが分からないウィンドウとランチャー自身は記録しない。
+pub fn capture(windows: &[WindowInfo]) -> Session {
+    let own_exe = std::env::current_exe().ok().map(|path| path.to_string_lossy().to_lowercase());
+    let mut apps: Vec<SessionApp> = Vec::new();
+    for window in windows {
+        let command_line = crate::windows_api::process_command_line_for_window(window.hwnd);
+        let (exe_path, arguments) = match command_line.as_deref().map(split_command_line) {
+            Some((exe_path, arguments)) if !exe_path.is_empty() => (exe_path, arguments),
+            // コマンドラインを読めないプロセスは引数なしで起動し直す
+            _ => match crate::windows_api::process_path_for_window(window.hwnd) {
+                Some(exe_path) => (exe_path, None),
+                None => continue,
+            },
+        };
+        if own_exe.as_deref() == Some(exe_path.to_lowercase().as_str()) {
+            continue;
+        }
+        if apps.iter().any(|app| app.exe_path.eq_ignore_ascii_case(&exe_path) && app.arguments == arguments) {
+            continue;
+        }
+        apps.push(SessionApp {
+            process_name: window.process_name.clone(),
+            exe_path,
+            arguments,
+            title: window.title.clone(),
+        });
+    }
+    Session {
+        saved_at: chrono::Local::now().timestamp(),
+        apps,
+    }
+}
+
+/// コマンドラインを実行ファイルと引数に分ける（`"C:\a b\x.exe" --flag` → (`C:\a b\x.exe`, `--flag`)）
+pub fn split_command_line(command_line: &str) -> (String, Option<String>) {
+    let command_line = command_line.trim();
+    let (exe, rest) = match command_line.strip_prefix('"') {
+        Some(quoted) => match quoted.split_once('"') {
+            Some((exe, rest)) => (exe, rest),
+            None => (quoted, ""),
+        },
+        None => command_line.split_once(char::is_whitespace).unwrap_or((command_line, "")),
+    };
+    let rest = rest.trim();
+    (exe.to_string(), (!rest.is_empty()).then(|| rest.to_string()))
+}
+
+/// 記録にあって、今は動いていないアプリ
+///
+/// 同じ実行ファイルのアプリが記録より少なければ、足りない分を動いていないとみなす。
+pub fn missing_apps(session: &Session, windows: &[WindowInfo]) -> Vec<SessionApp> {
+    let mut running: Vec<String> = windows.iter().map(|window| window.process_name.to_lowercase()).collect();
+    let mut missing = Vec::new();
+    for app in &session.apps {
+        match running.iter().position(|name| *name == app.process_name.to_lowercase()) {
+            Some(index) => {
+                running.swap_remove(index);
+            }
+            None => missing.push(app.clone()),
+        }
+    }
+    missing
+}
+
+/// アプリを記録したときの引数で起動し直す
+pub fn relaunch(app: &SessionApp) -> std::io::Result<()> {
+    let mut command = std::process::Command::new(&app.exe_path);
+    if let Some(arguments) = &app.arguments {
+        // Windows ではコマンドラインをそのまま渡す（引用符を付け直さない）
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.raw_arg(arguments);
+        }
+        #[cfg(not(windows))]
+        command.args(arguments.split_whitespace());
+    }
+    command.spawn().map(|_| ())
+}
+
+/// 開いているアプリを一定間隔で記録するスレッドを起動
+pub fn spawn_recorder(store: SessionStore) -> std::io::Result<thread::JoinHandle<()>> {
+    thread::Builder::new().name("session-recorder".to_string()).spawn(move || loop {
+        thread::sleep(RECORD_INTERVAL);
+        let session = capture(&crate::windows_api::enumerate_windows());
+        if session.apps.is_empty() {
+            continue;
+        }
+        if let Err(e) = store.save(&session) {
+            log::error!("Failed to save session to {}: {}", store.path().display(), e);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::helpers::create_test_window;
+
+    fn app(process_name: &str) -> SessionApp {
+        SessionApp {
+            process_name: process_name.to_string(),
+            exe_path: format!("C:\\Apps\\{}", process_name),
+            arguments: None,
+            title: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line(r#""C:\Program Files\App\app.exe" --profile "Work" "#),
+            (r"C:\Program Files\App\app.exe".to_string(), Some(r#"--profile "Work""#.to_string()))
+        );
+        assert_eq!(split_command_line(r"C:\Windows\notepad.exe"), (r"C:\Windows\notepad.exe".to_string(), None));
+        assert_eq!(
+            split_command_line(r"notepad.exe C:\notes.txt"),
+            ("notepad.exe".to_string(), Some(r"C:\notes.txt".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_missing_apps() {
+        let session = Session {
+            saved_at: 0,
+            apps: vec![app("Code.exe"), app("notepad.exe"), app("notepad.exe")],
+        };
+        let notepad = create_test_window(1, "memo.txt - Notepad", "Notepad.exe");
+
+        let missing = missing_apps(&session, &[notepad]);
+        let names: Vec<&str> = missing.iter().map(|app| app.process_name.as_str()).collect();
+        assert_eq!(names, vec!["Code.exe", "notepad.exe"]);
+    }
+}
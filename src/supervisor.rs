@@ -0,0 +1,262 @@
+//! IPC サーバーや WebSocket サーバーなど、動き続けるべきタスクの監視
+//!
+//! タスクが予期しないエラーやパニックで止まったら、間隔を空けながら（1秒, 2秒, 4秒…最大60秒）
+//! 起動し直す。状態は `ServiceStatuses` に書き込み、UI のヘッダーとデーモンのステータス応答で使う。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 再起動の間隔の上限
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// この時間より長く動いていたら、次に止まったときの再起動の間隔を最初に戻す
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ServiceState {
+    Running,
+    /// 止まったので再起動を待っている
+    Restarting { retry_in_secs: u64 },
+    /// 監視していない（デーモンに接続できないなど）
+    Stopped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub name: String,
+    pub state: ServiceState,
+    /// 起動し直した回数
+    pub restarts: u32,
+    pub last_error: Option<String>,
+}
+
+impl ServiceStatus {
+    pub fn new(name: &str, state: ServiceState) -> Self {
+        Self {
+            name: name.to_string(),
+            state,
+            restarts: 0,
+            last_error: None,
+        }
+    }
+}
+
+/// 監視しているタスクの状態の一覧（スレッド間で共有する）
+#[derive(Debug, Clone, Default)]
+pub struct ServiceStatuses(Arc<Mutex<Vec<ServiceStatus>>>);
+
+impl ServiceStatuses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 同じ名前の状態を置き換える（なければ追加）
+    pub fn set(&self, status: ServiceStatus) {
+        let mut statuses = self.0.lock().unwrap();
+        match statuses.iter_mut().find(|s| s.name == status.name) {
+            Some(existing) => *existing = status,
+            None => statuses.push(status),
+        }
+    }
+
+    /// 一覧をまとめて置き換える（シンクライアントがデーモンの状態を写すとき）
+    pub fn replace_all(&self, statuses: Vec<ServiceStatus>) {
+        *self.0.lock().unwrap() = statuses;
+    }
+
+    pub fn all(&self) -> Vec<ServiceStatus> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// すべて動いているか
+    pub fn is_healthy(&self) -> bool {
+        self.0.lock().unwrap().iter().all(|s| s.state == ServiceState::Running)
+    }
+}
+
+/// 止まったサービスの名前とエラーを受け取る関数
+type FailureHook = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// タスクを専用のスレッドで動かし、止まったら起動し直す
+#[derive(Clone)]
+pub struct Supervisor {
+    statuses: ServiceStatuses,
+    base_backoff: Duration,
+    /// 再起動を待っているサービスを、待たずに起動し直すための送信側
+    retries: Arc<Mutex<HashMap<&'static str, Sender<()>>>>,
+    on_failure: Option<FailureHook>,
+}
+
+impl Supervisor {
+    pub fn new(statuses: ServiceStatuses) -> Self {
+        Self {
+            statuses,
+            base_backoff: Duration::from_secs(1),
+            retries: Arc::new(Mutex::new(HashMap::new())),
+            on_failure: None,
+        }
+    }
+
+    /// 最初の再起動までの間隔（以降は倍になっていく）
+    pub fn with_backoff(mut self, base: Duration) -> Self {
+        self.base_backoff = base;
+        self
+    }
+
+    /// サービスが止まったときに呼ぶ関数（続けて止まっている間は最初の1回だけ呼ぶ）
+    pub fn on_failure(mut self, hook: impl Fn(&str, &str) + Send + Sync + 'static) -> Self {
+        self.on_failure = Some(Arc::new(hook));
+        self
+    }
+
+    /// 再起動を待っているサービスを今すぐ起動し直す（そのサービスがなければ false）
+    pub fn retry_now(&self, name: &str) -> bool {
+        match self.retries.lock().unwrap().get(name) {
+            Some(retry) => retry.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// `run` は止まるまで戻らない関数。戻ったら（`Ok` でも）エラーとして起動し直す
+    pub fn spawn<F>(&self, name: &'static str, run: F) -> std::io::Result<()>
+    where
+        F: Fn() -> Result<(), String> + Send + 'static,
+    {
+        let statuses = self.statuses.clone();
+        let base_backoff = self.base_backoff;
+        let on_failure = self.on_failure.clone();
+        let (retry, retry_requested) = mpsc::channel();
+        self.retries.lock().unwrap().insert(name, retry);
+        statuses.set(ServiceStatus::new(name, ServiceState::Running));
+
+        thread::Builder::new().name(format!("supervised-{}", name)).spawn(move || {
+            let mut status = ServiceStatus::new(name, ServiceState::Running);
+            let mut attempt = 0;
+            loop {
+                status.state = ServiceState::Running;
+                statuses.set(status.clone());
+
+                let started = Instant::now();
+                let error = match panic::catch_unwind(AssertUnwindSafe(&run)) {
+                    Ok(Ok(())) => "stopped unexpectedly".to_string(),
+                    Ok(Err(e)) => e,
+                    Err(_) => "panicked".to_string(),
+                };
+                if started.elapsed() >= STABLE_AFTER {
+                    attempt = 0;
+                }
+
+                let delay = backoff(base_backoff, attempt);
+                log::error!("{} stopped ({}), restarting in {:?}", name, error, delay);
+                if attempt == 0 {
+                    if let Some(on_failure) = &on_failure {
+                        on_failure(name, &error);
+                    }
+                }
+                status.state = ServiceState::Restarting { retry_in_secs: delay.as_secs() };
+                status.last_error = Some(error);
+                statuses.set(status.clone());
+
+                // 動いている間に頼まれた再試行は捨て、待っている間に頼まれたらすぐに起動し直す
+                while retry_requested.try_recv().is_ok() {}
+                if retry_requested.recv_timeout(delay).is_ok() {
+                    log::info!("Retrying {} without waiting for the backoff", name);
+                }
+                attempt += 1;
+                status.restarts += 1;
+                log::info!("Restarting {} (restart #{})", name, status.restarts);
+            }
+        })?;
+        Ok(())
+    }
+}
+
+/// `attempt` 回目の再起動までの間隔（`base` から倍々、上限あり）
+pub fn backoff(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1 << attempt.min(16)).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_doubles_up_to_limit() {
+        let base = Duration::from_secs(1);
+        assert_eq!(backoff(base, 0), Duration::from_secs(1));
+        assert_eq!(backoff(base, 3), Duration::from_secs(8));
+        assert_eq!(backoff(base, 10), MAX_BACKOFF);
+        assert_eq!(backoff(base, 100), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_restarts_failed_task() {
+        let statuses = ServiceStatuses::new();
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&runs);
+        Supervisor::new(statuses.clone())
+            .with_backoff(Duration::from_millis(1))
+            .spawn("flaky", move || {
+                // 2回失敗（1回はパニック）したあとは動き続ける
+                match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err("bind failed".to_string()),
+                    1 => panic!("accept loop died"),
+                    _ => loop {
+                        thread::park();
+                    },
+                }
+            })
+            .unwrap();
+
+        let started = Instant::now();
+        while runs.load(Ordering::SeqCst) < 3 {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(5));
+        }
+        thread::sleep(Duration::from_millis(20));
+        let status = &statuses.all()[0];
+        assert_eq!(status.state, ServiceState::Running);
+        assert_eq!(status.restarts, 2);
+        assert_eq!(status.last_error.as_deref(), Some("panicked"));
+        assert!(statuses.is_healthy());
+    }
+
+    #[test]
+    fn test_retry_now_skips_backoff() {
+        let statuses = ServiceStatuses::new();
+        let runs = Arc::new(AtomicU32::new(0));
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let counter = Arc::clone(&runs);
+        let reported = Arc::clone(&failures);
+        let supervisor = Supervisor::new(statuses.clone())
+            .with_backoff(Duration::from_secs(60))
+            .on_failure(move |name, error| reported.lock().unwrap().push(format!("{}: {}", name, error)));
+        supervisor
+            .spawn("websocket", move || match counter.fetch_add(1, Ordering::SeqCst) {
+                0 => Err("port in use".to_string()),
+                _ => loop {
+                    thread::park();
+                },
+            })
+            .unwrap();
+
+        let started = Instant::now();
+        while !matches!(statuses.all()[0].state, ServiceState::Restarting { .. }) {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(supervisor.retry_now("websocket"));
+        assert!(!supervisor.retry_now("ipc"));
+        while runs.load(Ordering::SeqCst) < 2 {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(*failures.lock().unwrap(), vec!["websocket: port in use"]);
+    }
+}
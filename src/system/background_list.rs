@@ -0,0 +1,126 @@
+//! 時間のかかる一覧の取得（`netsh`、`rasdial`、Docker など）をバックグラウンドで行い、取れた一覧を覚えておく
+//!
+//! 検索のたびにコマンドの終了を待たないよう、古くなったら取り直しを始めて今ある一覧をすぐ返す。
+//! 取り直しが終わったら `take_updated` で知らせ、検索し直して一覧に反映する。
+
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct ListState<T> {
+    /// 最後に取った一覧（失敗したらそのエラー）
+    listed: Option<Result<T, String>>,
+    listed_at: Option<Instant>,
+    listing: bool,
+}
+
+pub struct BackgroundList<T> {
+    name: &'static str,
+    refresh_after: Duration,
+    state: Arc<Mutex<ListState<T>>>,
+    updated: Arc<AtomicBool>,
+}
+
+impl<T> Clone for BackgroundList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            refresh_after: self.refresh_after,
+            state: Arc::clone(&self.state),
+            updated: Arc::clone(&self.updated),
+        }
+    }
+}
+
+impl<T: Clone + Send + 'static> BackgroundList<T> {
+    /// `name` はスレッド名とログに使う
+    pub fn new(name: &'static str, refresh_after: Duration) -> Self {
+        Self {
+            name,
+            refresh_after,
+            state: Arc::new(Mutex::new(ListState { listed: None, listed_at: None, listing: false })),
+            updated: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 一覧が古ければ `list` でバックグラウンドに取り直しを始め、今ある一覧を返す（まだ取れていなければ None）
+    pub fn get<F>(&self, list: F) -> Option<Result<T, String>>
+    where
+        F: FnOnce() -> Result<T, Box<dyn Error>> + Send + 'static,
+    {
+        let mut state = self.state.lock().unwrap();
+        let fresh = state.listed_at.is_some_and(|at| at.elapsed() < self.refresh_after);
+        if !state.listing && !fresh {
+            state.listing = true;
+            let background = self.clone();
+            let spawned = std::thread::Builder::new().name(format!("{}-list", self.name)).spawn(move || {
+                let listed = list().map_err(|e| {
+                    log::warn!("Failed to list {}: {}", background.name, e);
+                    e.to_string()
+                });
+                let mut state = background.state.lock().unwrap();
+                state.listed = Some(listed);
+                state.listed_at = Some(Instant::now());
+                state.listing = false;
+                background.updated.store(true, Ordering::SeqCst);
+            });
+            if let Err(e) = spawned {
+                log::error!("Failed to spawn {} list thread: {}", self.name, e);
+                state.listing = false;
+            }
+        }
+        state.listed.clone()
+    }
+
+    /// 取り直さずに、今ある一覧を返す
+    pub fn latest(&self) -> Option<Result<T, String>> {
+        self.state.lock().unwrap().listed.clone()
+    }
+
+    /// 次の `get` で取り直す（接続や停止のあと）
+    pub fn invalidate(&self) {
+        self.state.lock().unwrap().listed_at = None;
+    }
+
+    /// 前回の確認から取り直しが終わっていれば true
+    pub fn take_updated(&self) -> bool {
+        self.updated.swap(false, Ordering::SeqCst)
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.state.lock().unwrap().listing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_for_update<T: Clone + Send + 'static>(list: &BackgroundList<T>) {
+        for _ in 0..100 {
+            if list.take_updated() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("the list was not updated");
+    }
+
+    #[test]
+    fn test_lists_in_background_and_keeps_the_last_list() {
+        let list = BackgroundList::new("test", Duration::from_secs(60));
+        assert_eq!(list.get(|| Ok(vec!["a"])), None);
+        wait_for_update(&list);
+        assert!(!list.is_pending());
+
+        // 新しいうちは取り直さない
+        assert_eq!(list.get(|| panic!("listed again")), Some(Ok(vec!["a"])));
+
+        // 取り直している間は前の一覧を返す
+        list.invalidate();
+        assert_eq!(list.get(|| Err("not reachable".into())), Some(Ok(vec!["a"])));
+        wait_for_update(&list);
+        assert_eq!(list.get(|| Ok(vec!["b"])), Some(Err("not reachable".to_string())));
+    }
+}
@@ -0,0 +1,407 @@
+//! ディスプレイ設定（`display` キーワード）
+//!
+//! 投影モードの切り替え（`SetDisplayConfig`）、夜間モードの設定画面、
+//! モニターごとのリフレッシュレートの変更を提供する。
+//! 夜間モードには切り替える公開 API がないため、オン・オフはせずに設定画面を開く。
+
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use std::error::Error;
+
+#[cfg(windows)]
+use std::ffi::OsString;
+#[cfg(windows)]
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+#[cfg(windows)]
+use std::ptr;
+#[cfg(windows)]
+use winapi::um::{
+    wingdi::{DEVMODEW, DISPLAY_DEVICEW, DISPLAY_DEVICE_ATTACHED_TO_DESKTOP, DM_DISPLAYFREQUENCY},
+    winuser::{
+        ChangeDisplaySettingsExW, EnumDisplayDevicesW, EnumDisplaySettingsW, CDS_UPDATEREGISTRY,
+        DISP_CHANGE_SUCCESSFUL, ENUM_CURRENT_SETTINGS,
+    },
+};
+
+/// 投影モード（Win+P と同じ4種類）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectionMode {
+    /// PC画面のみ
+    Internal,
+    /// 複製
+    Clone,
+    /// 拡張
+    Extend,
+    /// セカンドスクリーンのみ
+    External,
+}
+
+impl ProjectionMode {
+    pub const ALL: [ProjectionMode; 4] = [
+        ProjectionMode::Internal,
+        ProjectionMode::Clone,
+        ProjectionMode::Extend,
+        ProjectionMode::External,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectionMode::Internal => "PC screen only",
+            ProjectionMode::Clone => "Duplicate",
+            ProjectionMode::Extend => "Extend",
+            ProjectionMode::External => "Second screen only",
+        }
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            ProjectionMode::Internal => "internal",
+            ProjectionMode::Clone => "clone",
+            ProjectionMode::Extend => "extend",
+            ProjectionMode::External => "external",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.id() == id)
+    }
+}
+
+/// デスクトップに接続されているディスプレイ
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayDevice {
+    /// デバイス名（例: `\\.\DISPLAY1`）
+    pub name: String,
+    /// アダプター名（例: `Intel(R) UHD Graphics`）
+    pub description: String,
+    pub current_hz: u32,
+    /// 現在の解像度で選べるリフレッシュレート（昇順）
+    pub refresh_rates: Vec<u32>,
+}
+
+/// 実行するディスプレイ操作
+#[derive(Debug, Clone, PartialEq)]
+enum DisplayCommand {
+    Projection(ProjectionMode),
+    NightLight,
+    RefreshRate { device: String, hz: u32 },
+}
+
+impl DisplayCommand {
+    fn encode(&self) -> String {
+        match self {
+            DisplayCommand::Projection(mode) => format!("projection:{}", mode.id()),
+            DisplayCommand::NightLight => "night_light".to_string(),
+            DisplayCommand::RefreshRate { device, hz } => format!("refresh:{}:{}", hz, device),
+        }
+    }
+
+    fn decode(command: &str) -> Option<Self> {
+        if command == "night_light" {
+            return Some(DisplayCommand::NightLight);
+        }
+        if let Some(mode) = command.strip_prefix("projection:") {
+            return ProjectionMode::from_id(mode).map(DisplayCommand::Projection);
+        }
+        let (hz, device) = command.strip_prefix("refresh:")?.split_once(':')?;
+        Some(DisplayCommand::RefreshRate {
+            device: device.to_string(),
+            hz: hz.parse().ok()?,
+        })
+    }
+}
+
+/// `display` キーワードのプロバイダー
+pub struct DisplayProvider {
+    devices: fn() -> Vec<DisplayDevice>,
+}
+
+impl DisplayProvider {
+    pub fn new() -> Self {
+        Self::with_source(display_devices)
+    }
+
+    /// ディスプレイの列挙を差し替えて作成（テスト用）
+    pub fn with_source(devices: fn() -> Vec<DisplayDevice>) -> Self {
+        Self { devices }
+    }
+}
+
+impl Default for DisplayProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn command_result(title: String, description: String, command: DisplayCommand) -> SearchResult {
+    SearchResult {
+        title,
+        description,
+        action: Action::ProviderCommand {
+            provider: "display".to_string(),
+            command: command.encode(),
+        },
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+impl KeywordProvider for DisplayProvider {
+    fn name(&self) -> &'static str {
+        "display"
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &["display"]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("display [setting]", "Switch projection mode, open night light settings or change refresh rate"),
+        ];
+        HELP
+    }
+
+    fn search(&self, _keyword: &str, args: &str) -> Vec<SearchResult> {
+        let mut results: Vec<SearchResult> = ProjectionMode::ALL
+            .iter()
+            .map(|mode| {
+                command_result(
+                    format!("Projection: {}", mode.label()),
+                    "Switch the projection mode (same as Win+P)".to_string(),
+                    DisplayCommand::Projection(*mode),
+                )
+            })
+            .collect();
+
+        results.push(command_result(
+            "Open night light settings".to_string(),
+            "Turn night light on or off in Settings (Windows has no API to toggle it)".to_string(),
+            DisplayCommand::NightLight,
+        ));
+
+        for device in (self.devices)() {
+            for hz in &device.refresh_rates {
+                let current = if *hz == device.current_hz { " (current)" } else { "" };
+                results.push(command_result(
+                    format!("Refresh rate: {} Hz on {}{}", hz, short_device_name(&device.name), current),
+                    device.description.clone(),
+                    DisplayCommand::RefreshRate {
+                        device: device.name.clone(),
+                        hz: *hz,
+                    },
+                ));
+            }
+        }
+
+        results.retain(|result| args.is_empty() || crate::core::browser_search_engine::matches_command(args, &result.title));
+        results
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        match DisplayCommand::decode(command).ok_or_else(|| format!("unknown display command: {}", command))? {
+            DisplayCommand::Projection(mode) => {
+                set_projection_mode(mode)?;
+                Ok(format!("Projection: {}", mode.label()))
+            }
+            DisplayCommand::NightLight => {
+                open::that("ms-settings:nightlight")?;
+                Ok("Opened night light settings".to_string())
+            }
+            DisplayCommand::RefreshRate { device, hz } => {
+                set_refresh_rate(&device, hz)?;
+                Ok(format!("{} set to {} Hz", short_device_name(&device), hz))
+            }
+        }
+    }
+}
+
+/// `\\.\DISPLAY1` → `DISPLAY1`
+fn short_device_name(name: &str) -> &str {
+    name.trim_start_matches(r"\\.\")
+}
+
+#[cfg(windows)]
+const SDC_TOPOLOGY_INTERNAL: u32 = 0x0000_0001;
+#[cfg(windows)]
+const SDC_TOPOLOGY_CLONE: u32 = 0x0000_0002;
+#[cfg(windows)]
+const SDC_TOPOLOGY_EXTEND: u32 = 0x0000_0004;
+#[cfg(windows)]
+const SDC_TOPOLOGY_EXTERNAL: u32 = 0x0000_0008;
+#[cfg(windows)]
+const SDC_APPLY: u32 = 0x0000_0080;
+
+#[cfg(windows)]
+#[link(name = "user32")]
+extern "system" {
+    fn SetDisplayConfig(
+        num_path_array_elements: u32,
+        path_array: *mut std::ffi::c_void,
+        num_mode_info_array_elements: u32,
+        mode_info_array: *mut std::ffi::c_void,
+        flags: u32,
+    ) -> i32;
+}
+
+/// 投影モードを切り替える
+#[cfg(windows)]
+pub fn set_projection_mode(mode: ProjectionMode) -> Result<(), Box<dyn Error>> {
+    let topology = match mode {
+        ProjectionMode::Internal => SDC_TOPOLOGY_INTERNAL,
+        ProjectionMode::Clone => SDC_TOPOLOGY_CLONE,
+        ProjectionMode::Extend => SDC_TOPOLOGY_EXTEND,
+        ProjectionMode::External => SDC_TOPOLOGY_EXTERNAL,
+    };
+
+    let result = unsafe { SetDisplayConfig(0, ptr::null_mut(), 0, ptr::null_mut(), SDC_APPLY | topology) };
+    if result != 0 {
+        return Err(format!("SetDisplayConfig failed with code {}", result).into());
+    }
+    Ok(())
+}
+
+/// デスクトップに接続されているディスプレイを列挙
+#[cfg(windows)]
+pub fn display_devices() -> Vec<DisplayDevice> {
+    let mut devices = Vec::new();
+
+    for index in 0.. {
+        let mut device: DISPLAY_DEVICEW = unsafe { std::mem::zeroed() };
+        device.cb = std::mem::size_of::<DISPLAY_DEVICEW>() as u32;
+        if unsafe { EnumDisplayDevicesW(ptr::null(), index, &mut device, 0) } == 0 {
+            break;
+        }
+        if device.StateFlags & DISPLAY_DEVICE_ATTACHED_TO_DESKTOP == 0 {
+            continue;
+        }
+
+        let name_wide = device.DeviceName;
+        let Some(current) = display_mode(&name_wide, ENUM_CURRENT_SETTINGS) else { continue };
+
+        let mut refresh_rates = Vec::new();
+        for mode_index in 0.. {
+            let Some(mode) = display_mode(&name_wide, mode_index) else { break };
+            let same_resolution = mode.dmPelsWidth == current.dmPelsWidth
+                && mode.dmPelsHeight == current.dmPelsHeight
+                && mode.dmBitsPerPel == current.dmBitsPerPel;
+            if same_resolution && mode.dmDisplayFrequency > 1 && !refresh_rates.contains(&mode.dmDisplayFrequency) {
+                refresh_rates.push(mode.dmDisplayFrequency);
+            }
+        }
+        refresh_rates.sort_unstable();
+
+        devices.push(DisplayDevice {
+            name: from_wide(&device.DeviceName),
+            description: from_wide(&device.DeviceString),
+            current_hz: current.dmDisplayFrequency,
+            refresh_rates,
+        });
+    }
+
+    devices
+}
+
+#[cfg(windows)]
+fn display_mode(device_name: &[u16], mode_index: u32) -> Option<DEVMODEW> {
+    let mut mode: DEVMODEW = unsafe { std::mem::zeroed() };
+    mode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+    if unsafe { EnumDisplaySettingsW(device_name.as_ptr(), mode_index, &mut mode) } == 0 {
+        None
+    } else {
+        Some(mode)
+    }
+}
+
+#[cfg(windows)]
+fn from_wide(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    OsString::from_wide(&buffer[..len]).to_string_lossy().to_string()
+}
+
+/// 現在の解像度のまま、リフレッシュレートを変更する
+#[cfg(windows)]
+pub fn set_refresh_rate(device: &str, hz: u32) -> Result<(), Box<dyn Error>> {
+    let device_wide: Vec<u16> = std::ffi::OsStr::new(device).encode_wide().chain(std::iter::once(0)).collect();
+    let mut mode = display_mode(&device_wide, ENUM_CURRENT_SETTINGS).ok_or("failed to read the current display mode")?;
+    mode.dmDisplayFrequency = hz;
+    mode.dmFields = DM_DISPLAYFREQUENCY;
+
+    let result = unsafe {
+        ChangeDisplaySettingsExW(device_wide.as_ptr(), &mut mode, ptr::null_mut(), CDS_UPDATEREGISTRY, ptr::null_mut())
+    };
+    if result != DISP_CHANGE_SUCCESSFUL {
+        return Err(format!("ChangeDisplaySettingsExW failed with code {}", result).into());
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn set_projection_mode(_mode: ProjectionMode) -> Result<(), Box<dyn Error>> {
+    Err("Display configuration is only supported on Windows".into())
+}
+
+#[cfg(not(windows))]
+pub fn display_devices() -> Vec<DisplayDevice> {
+    Vec::new()
+}
+
+#[cfg(not(windows))]
+pub fn set_refresh_rate(_device: &str, _hz: u32) -> Result<(), Box<dyn Error>> {
+    Err("Display configuration is only supported on Windows".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_devices() -> Vec<DisplayDevice> {
+        vec![DisplayDevice {
+            name: r"\\.\DISPLAY1".to_string(),
+            description: "Test Adapter".to_string(),
+            current_hz: 60,
+            refresh_rates: vec![60, 144],
+        }]
+    }
+
+    #[test]
+    fn test_command_roundtrip() {
+        let commands = vec![
+            DisplayCommand::Projection(ProjectionMode::Extend),
+            DisplayCommand::NightLight,
+            DisplayCommand::RefreshRate {
+                device: r"\\.\DISPLAY2".to_string(),
+                hz: 120,
+            },
+        ];
+        for command in commands {
+            assert_eq!(DisplayCommand::decode(&command.encode()), Some(command));
+        }
+        assert_eq!(DisplayCommand::decode("projection:sideways"), None);
+    }
+
+    #[test]
+    fn test_search_lists_actions() {
+        let provider = DisplayProvider::with_source(test_devices);
+
+        let all = provider.search("display", "");
+        assert_eq!(all.len(), 7);
+        assert_eq!(all[5].title, "Refresh rate: 60 Hz on DISPLAY1 (current)");
+
+        let filtered = provider.search("display", "144");
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(
+            filtered[0].action,
+            Action::ProviderCommand {
+                provider: "display".to_string(),
+                command: r"refresh:144:\\.\DISPLAY1".to_string(),
+            }
+        );
+
+        let extend = provider.search("display", "ext");
+        assert_eq!(extend.len(), 1);
+        assert_eq!(extend[0].title, "Projection: Extend");
+    }
+}
@@ -0,0 +1,315 @@
+//! Docker のコンテナ（`docker` キーワード、設定の `docker.enabled` で有効にする）
+//!
+//! 名前付きパイプで Docker Engine API に問い合わせ、動いているコンテナを一覧にする。
+//! 一覧はバックグラウンドで取得して覚えておき、Docker が応答しなくても検索は待たない。
+//! シェルとログは `docker` CLI を新しいコンソールで開き、停止と再起動は API で行う。
+
+use super::background_list::BackgroundList;
+use crate::core::browser_search_engine::matches_command;
+use crate::core::confirmation::ConfirmableAction;
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use serde::Deserialize;
+use std::error::Error;
+use std::sync::mpsc;
+use std::time::Duration;
+
+const PROVIDER: &str = "docker";
+
+/// 一覧を取り直すまでの時間
+const REFRESH_AFTER: Duration = Duration::from_secs(5);
+
+/// 一覧の応答を待つ時間（パイプの読み込みには期限を付けられないので、別スレッドで読んで待つ）
+const LIST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// コンテナ一覧の取得（テストで差し替える）
+type Lister = fn(&str) -> Result<Vec<Container>, Box<dyn Error>>;
+
+/// `GET /containers/json` の要素
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Container {
+    pub id: String,
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub image: String,
+    /// 例: `Up 3 hours`
+    #[serde(default)]
+    pub status: String,
+}
+
+impl Container {
+    /// 表示名（先頭の `/` を除いた最初の名前、なければ短い ID）
+    pub fn name(&self) -> &str {
+        self.names
+            .first()
+            .map(|name| name.trim_start_matches('/'))
+            .unwrap_or_else(|| &self.id[..self.id.len().min(12)])
+    }
+}
+
+/// HTTP の応答をステータスコードと本文に分ける（`Transfer-Encoding: chunked` も読む）
+pub fn parse_response(response: &[u8]) -> Result<(u16, String), String> {
+    let text = String::from_utf8_lossy(response);
+    let (head, body) = text.split_once("\r\n\r\n").ok_or("incomplete HTTP response")?;
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or("invalid status line")?;
+    let chunked = head
+        .lines()
+        .any(|line| line.to_ascii_lowercase().starts_with("transfer-encoding:") && line.to_ascii_lowercase().contains("chunked"));
+    if !chunked {
+        return Ok((status, body.to_string()));
+    }
+
+    let mut decoded = String::new();
+    let mut rest = body;
+    loop {
+        let (size, after) = rest.split_once("\r\n").ok_or("incomplete chunk")?;
+        let size = usize::from_str_radix(size.trim(), 16).map_err(|_| format!("invalid chunk size '{}'", size))?;
+        if size == 0 {
+            break;
+        }
+        let chunk = after.get(..size).ok_or("incomplete chunk")?;
+        decoded.push_str(chunk);
+        rest = after[size..].trim_start_matches("\r\n");
+    }
+    Ok((status, decoded))
+}
+
+/// 名前付きパイプで Docker Engine API にリクエストを送る
+#[cfg(windows)]
+fn request(pipe: &str, method: &str, path: &str) -> Result<(u16, String), Box<dyn Error>> {
+    use std::io::{Read, Write};
+
+    let mut stream = std::fs::OpenOptions::new().read(true).write(true).open(pipe)?;
+    // HTTP/1.0 なら応答を送ったあとに接続を閉じるので、最後まで読めばよい
+    write!(stream, "{} {} HTTP/1.0\r\nHost: docker\r\nContent-Length: 0\r\n\r\n", method, path)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    Ok(parse_response(&response)?)
+}
+
+#[cfg(not(windows))]
+fn request(_pipe: &str, _method: &str, _path: &str) -> Result<(u16, String), Box<dyn Error>> {
+    Err("The Docker named pipe is only available on Windows".into())
+}
+
+/// 動いているコンテナを取得する（`LIST_TIMEOUT` までに応答がなければエラー）
+fn list_containers(pipe: &str) -> Result<Vec<Container>, Box<dyn Error>> {
+    let (sender, receiver) = mpsc::channel();
+    let pipe = pipe.to_string();
+    std::thread::Builder::new().name("docker-request".to_string()).spawn(move || {
+        let _ = sender.send(request(&pipe, "GET", "/containers/json").map_err(|e| e.to_string()));
+    })?;
+    let (status, body) = receiver
+        .recv_timeout(LIST_TIMEOUT)
+        .map_err(|_| format!("Docker did not answer within {} seconds", LIST_TIMEOUT.as_secs()))??;
+    if status != 200 {
+        return Err(format!("Docker returned {}: {}", status, body.trim()).into());
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+fn docker_result(title: String, description: String, command: String) -> SearchResult {
+    SearchResult {
+        title,
+        description,
+        action: Action::ProviderCommand { provider: PROVIDER.to_string(), command },
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// コンテナごとにシェル・ログ・停止・再起動の結果を作る（名前かイメージで絞り込む）
+pub fn container_results(containers: &[Container], args: &str) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    for container in containers
+        .iter()
+        .filter(|container| args.is_empty() || matches_command(args, container.name()) || matches_command(args, &container.image))
+    {
+        let name = container.name();
+        let description = format!("{} · {}", container.image, container.status);
+        for (label, command) in [("Shell", "shell"), ("Logs", "logs"), ("Stop", "stop"), ("Restart", "restart")] {
+            results.push(docker_result(format!("{}: {}", label, name), description.clone(), format!("{}:{}", command, container.id)));
+        }
+    }
+    results
+}
+
+/// `docker` キーワードのプロバイダー
+pub struct DockerProvider {
+    pipe: String,
+    list: BackgroundList<Vec<Container>>,
+    lister: Lister,
+}
+
+impl DockerProvider {
+    pub fn new(pipe: String) -> Self {
+        Self::with_lister(pipe, list_containers)
+    }
+
+    /// コンテナ一覧の取得を差し替えて作成（テスト用）
+    pub fn with_lister(pipe: String, lister: Lister) -> Self {
+        Self { pipe, list: BackgroundList::new(PROVIDER, REFRESH_AFTER), lister }
+    }
+
+    /// コンソールを開いて `docker` CLI を実行する
+    fn open_console(args: &[&str]) -> Result<(), Box<dyn Error>> {
+        std::process::Command::new("docker").args(args).spawn()?;
+        Ok(())
+    }
+}
+
+impl KeywordProvider for DockerProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &["docker"]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("docker [name]", "Open a shell or logs, stop or restart a running container"),
+        ];
+        HELP
+    }
+
+    fn search(&self, _keyword: &str, args: &str) -> Vec<SearchResult> {
+        let (pipe, lister) = (self.pipe.clone(), self.lister);
+        match self.list.get(move || lister(&pipe)) {
+            None => vec![docker_result("Listing Docker containers...".to_string(), self.pipe.clone(), String::new())],
+            Some(Ok(containers)) if containers.is_empty() => {
+                vec![docker_result("No running containers".to_string(), self.pipe.clone(), String::new())]
+            }
+            Some(Ok(containers)) => container_results(&containers, args),
+            Some(Err(error)) => vec![docker_result("Docker is not reachable".to_string(), error, String::new())],
+        }
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        let (kind, id) = command.split_once(':').ok_or("Nothing to do")?;
+        let short_id = &id[..id.len().min(12)];
+        match kind {
+            "shell" => {
+                Self::open_console(&["exec", "-it", id, "sh"])?;
+                Ok(format!("Opened a shell in {}", short_id))
+            }
+            "logs" => {
+                Self::open_console(&["logs", "--follow", "--tail", "200", id])?;
+                Ok(format!("Showing logs of {}", short_id))
+            }
+            "stop" | "restart" => {
+                // 停止には数秒かかるのでバックグラウンドで待つ
+                let pipe = self.pipe.clone();
+                let path = format!("/containers/{}/{}", id, kind);
+                let label = format!("{} {}", kind, short_id);
+                let message = format!("Requested {}", label);
+                let list = self.list.clone();
+                std::thread::Builder::new().name("docker-command".to_string()).spawn(move || {
+                    match request(&pipe, "POST", &path) {
+                        Ok((status, _)) if (200..300).contains(&status) || status == 304 => log::info!("Docker {} succeeded", label),
+                        Ok((status, body)) => log::error!("Docker {} failed ({}): {}", label, status, body.trim()),
+                        Err(e) => log::error!("Docker {} failed: {}", label, e),
+                    }
+                    list.invalidate();
+                })?;
+                Ok(message)
+            }
+            _ => Err(format!("unknown docker command: {}", command).into()),
+        }
+    }
+
+    fn preview(&self, command: &str) -> Option<ConfirmableAction> {
+        let (kind, id) = command.split_once(':')?;
+        let verb = match kind {
+            "stop" => "Stop",
+            "restart" => "Restart",
+            _ => return None,
+        };
+        let container = match self.list.latest() {
+            Some(Ok(containers)) => containers.into_iter().find(|container| container.id == id),
+            _ => None,
+        };
+        let (name, effect) = match &container {
+            Some(container) => (container.name(), format!("{}: {} ({})", verb, container.name(), container.image)),
+            None => (&id[..id.len().min(12)], format!("{}: {}", verb, id)),
+        };
+        let action = Action::ProviderCommand { provider: PROVIDER.to_string(), command: command.to_string() };
+        Some(ConfirmableAction::new(action, format!("{} container {}", verb, name), vec![effect]))
+    }
+
+    fn take_updated(&self) -> bool {
+        self.list.take_updated()
+    }
+
+    fn is_pending(&self) -> bool {
+        self.list.is_pending()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let plain = b"HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n[]";
+        assert_eq!(parse_response(plain), Ok((200, "[]".to_string())));
+
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\n[{}\r\n1\r\n]\r\n0\r\n\r\n";
+        assert_eq!(parse_response(chunked), Ok((200, "[{}]".to_string())));
+
+        assert!(parse_response(b"HTTP/1.0 204").is_err());
+    }
+
+    #[test]
+    fn test_container_results() {
+        let body = r#"[{"Id":"4f66ad9a0b2e","Names":["/web"],"Image":"nginx:latest","State":"running","Status":"Up 2 hours"},
+                       {"Id":"9c1d3e5f7a9b","Names":["/db"],"Image":"postgres:16","Status":"Up 5 minutes"}]"#;
+        let containers: Vec<Container> = serde_json::from_str(body).unwrap();
+        assert_eq!(containers[0].name(), "web");
+
+        let results = container_results(&containers, "postgres");
+        let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["Shell: db", "Logs: db", "Stop: db", "Restart: db"]);
+        assert_eq!(results[0].description, "postgres:16 · Up 5 minutes");
+        assert_eq!(
+            results[2].action,
+            Action::ProviderCommand { provider: PROVIDER.to_string(), command: "stop:9c1d3e5f7a9b".to_string() }
+        );
+        assert_eq!(container_results(&containers, "").len(), 8);
+    }
+
+    #[test]
+    fn test_list_streams_in() {
+        let provider = DockerProvider::with_lister("pipe".to_string(), |_| {
+            Ok(serde_json::from_str(r#"[{"Id":"4f66ad9a0b2e","Names":["/web"],"Image":"nginx:latest"}]"#)?)
+        });
+        assert_eq!(provider.search("docker", "")[0].title, "Listing Docker containers...");
+
+        for _ in 0..100 {
+            if provider.take_updated() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!provider.is_pending());
+        assert_eq!(provider.search("docker", "web")[0].title, "Shell: web");
+
+        // 停止と再起動は確認してから実行する
+        assert!(provider.preview("shell:4f66ad9a0b2e").is_none());
+        let stop = provider.preview("stop:4f66ad9a0b2e").unwrap();
+        assert_eq!(stop.summary, "Stop container web");
+        assert_eq!(stop.effects, vec!["Stop: web (nginx:latest)"]);
+        assert_eq!(provider.preview("restart:0123456789abcdef").unwrap().summary, "Restart container 0123456789ab");
+    }
+}
@@ -0,0 +1,50 @@
+//! OSの設定を操作するキーワードプロバイダー群
+
+pub mod background_list;
+pub mod display;
+pub mod docker;
+pub mod network;
+pub mod terminal;
+pub mod wsl;
+
+use crate::core::keyword_provider::KeywordProvider;
+use std::error::Error;
+use std::process::Command;
+
+/// 既定で登録するシステムプロバイダー
+pub fn default_providers() -> Vec<Box<dyn KeywordProvider>> {
+    vec![
+        Box::new(display::DisplayProvider::new()),
+        Box::new(network::WifiProvider::new()),
+        Box::new(network::VpnProvider::new()),
+        Box::new(terminal::TerminalProvider::new()),
+        Box::new(wsl::WslProvider::new()),
+    ]
+}
+
+/// コンソールウィンドウを表示せずにコマンドを実行し、標準出力を返す
+pub fn run_command(program: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+    let mut command = Command::new(program);
+    command.args(args);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command.output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stdout
+            .lines()
+            .chain(stderr.lines())
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("no output");
+        return Err(format!("{} failed: {}", program, message).into());
+    }
+    Ok(stdout)
+}
@@ -0,0 +1,385 @@
+//! Wi-Fi と VPN の接続（`wifi` / `vpn` キーワード）
+//!
+//! Wi-Fi は `netsh wlan`、VPN は `rasdial` と電話帳ファイル（rasphone.pbk）を使う。
+//! 一覧の取得と接続には数秒かかることがあるため、バックグラウンドで実行する。
+//! 一覧は覚えておいて検索のたびにすぐ返し、取り直しが終わったら検索し直して反映する。接続の結果はログに残す。
+
+use super::background_list::BackgroundList;
+use super::run_command;
+use crate::core::browser_search_engine::matches_command;
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 一覧を取り直すまでの時間
+const REFRESH_AFTER: Duration = Duration::from_secs(5);
+
+/// コマンドの実行（テストで差し替える）
+type Runner = fn(&str, &[&str]) -> Result<String, Box<dyn Error>>;
+
+/// 保存済みの Wi-Fi プロファイル名を取り出す（`netsh wlan show profiles` の出力）
+pub fn parse_wifi_profiles(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(" : "))
+        // 英語版は "All User Profile"、日本語版は "すべてのユーザー プロファイル"
+        .filter(|(key, _)| key.contains("Profile") || key.contains("プロファイル"))
+        .map(|(_, name)| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// 接続中の SSID を取り出す（`netsh wlan show interfaces` の出力）
+pub fn parse_current_ssid(output: &str) -> Option<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(" : "))
+        .find(|(key, _)| key.trim() == "SSID")
+        .map(|(_, ssid)| ssid.trim().to_string())
+        .filter(|ssid| !ssid.is_empty())
+}
+
+/// 電話帳ファイルから VPN 接続名を取り出す（`[接続名]` のセクション）
+pub fn parse_phonebook_entries(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('[')?.strip_suffix(']').map(str::to_string))
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// 接続中の VPN 名を取り出す（引数なしの `rasdial` の出力）
+///
+/// 接続中なら "Connected to" の後に1行ずつ接続名が並び、"Command completed successfully." で終わる。
+pub fn parse_rasdial_connections(output: &str) -> Vec<String> {
+    let lines: Vec<&str> = output.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    if lines.len() < 3 {
+        // "No connections" + "Command completed successfully."
+        return Vec::new();
+    }
+    lines[1..lines.len() - 1].iter().map(|line| line.to_string()).collect()
+}
+
+fn provider_result(provider: &str, title: String, description: String, command: String) -> SearchResult {
+    SearchResult {
+        title,
+        description,
+        action: Action::ProviderCommand {
+            provider: provider.to_string(),
+            command,
+        },
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// 保存済みのネットワークごとに接続・切断の検索結果を作る
+pub fn wifi_results(profiles: &[String], current: Option<&str>, args: &str) -> Vec<SearchResult> {
+    profiles
+        .iter()
+        .filter(|name| args.is_empty() || matches_command(args, name))
+        .map(|name| {
+            if Some(name.as_str()) == current {
+                provider_result("wifi", format!("Disconnect Wi-Fi: {}", name), "Connected".to_string(), "disconnect".to_string())
+            } else {
+                let description = match current {
+                    Some(current) => format!("Saved network (currently connected to {})", current),
+                    None => "Saved network (not connected)".to_string(),
+                };
+                provider_result("wifi", format!("Connect Wi-Fi: {}", name), description, format!("connect:{}", name))
+            }
+        })
+        .collect()
+}
+
+/// 設定済みの VPN ごとに接続・切断の検索結果を作る
+pub fn vpn_results(entries: &[String], connected: &[String], args: &str) -> Vec<SearchResult> {
+    entries
+        .iter()
+        .filter(|name| args.is_empty() || matches_command(args, name))
+        .map(|name| {
+            if connected.contains(name) {
+                provider_result("vpn", format!("Disconnect VPN: {}", name), "Connected".to_string(), format!("disconnect:{}", name))
+            } else {
+                provider_result("vpn", format!("Connect VPN: {}", name), "Not connected".to_string(), format!("connect:{}", name))
+            }
+        })
+        .collect()
+}
+
+/// 時間のかかる接続処理をバックグラウンドで実行し、終わったら一覧を取り直す
+fn run_in_background<T: Clone + Send + 'static>(
+    label: String,
+    run: Runner,
+    program: &'static str,
+    args: Vec<String>,
+    list: BackgroundList<T>,
+) -> Result<(), Box<dyn Error>> {
+    std::thread::Builder::new().name("network-connect".to_string()).spawn(move || {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match run(program, &args) {
+            Ok(_) => log::info!("{} succeeded", label),
+            Err(e) => log::error!("{} failed: {}", label, e),
+        }
+        list.invalidate();
+    })?;
+    Ok(())
+}
+
+/// 保存済みの Wi-Fi プロファイルと接続中の SSID
+#[derive(Debug, Clone, PartialEq)]
+struct WifiState {
+    profiles: Vec<String>,
+    current: Option<String>,
+}
+
+/// `wifi` キーワードのプロバイダー
+pub struct WifiProvider {
+    list: BackgroundList<WifiState>,
+    run: Runner,
+}
+
+impl WifiProvider {
+    pub fn new() -> Self {
+        Self::with_runner(run_command)
+    }
+
+    /// コマンドの実行を差し替えて作成（テスト用）
+    pub fn with_runner(run: Runner) -> Self {
+        Self { list: BackgroundList::new("wifi", REFRESH_AFTER), run }
+    }
+}
+
+impl Default for WifiProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeywordProvider for WifiProvider {
+    fn name(&self) -> &'static str {
+        "wifi"
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &["wifi"]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("wifi [network]", "Connect to a saved Wi-Fi network"),
+        ];
+        HELP
+    }
+
+    fn search(&self, _keyword: &str, args: &str) -> Vec<SearchResult> {
+        let run = self.run;
+        let listed = self.list.get(move || {
+            let profiles = parse_wifi_profiles(&run("netsh", &["wlan", "show", "profiles"])?);
+            let current = run("netsh", &["wlan", "show", "interfaces"]).ok().and_then(|output| parse_current_ssid(&output));
+            Ok(WifiState { profiles, current })
+        });
+        match listed {
+            None => vec![provider_result("wifi", "Listing Wi-Fi networks...".to_string(), "Waiting for netsh".to_string(), String::new())],
+            Some(Err(error)) => vec![provider_result("wifi", "Could not list Wi-Fi networks".to_string(), error, String::new())],
+            Some(Ok(state)) => wifi_results(&state.profiles, state.current.as_deref(), args),
+        }
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        if command.is_empty() {
+            return Err("Wi-Fi networks are still being listed".into());
+        }
+        if command == "disconnect" {
+            (self.run)("netsh", &["wlan", "disconnect"])?;
+            self.list.invalidate();
+            return Ok("Disconnected from Wi-Fi".to_string());
+        }
+
+        let name = command.strip_prefix("connect:").ok_or_else(|| format!("unknown wifi command: {}", command))?;
+        run_in_background(
+            format!("Connecting to Wi-Fi {}", name),
+            self.run,
+            "netsh",
+            vec!["wlan".to_string(), "connect".to_string(), format!("name={}", name)],
+            self.list.clone(),
+        )?;
+        Ok(format!("Connecting to {}...", name))
+    }
+
+    fn take_updated(&self) -> bool {
+        self.list.take_updated()
+    }
+
+    fn is_pending(&self) -> bool {
+        self.list.is_pending()
+    }
+}
+
+/// 電話帳の VPN 接続名と接続中のもの
+#[derive(Debug, Clone, PartialEq)]
+struct VpnState {
+    entries: Vec<String>,
+    connected: Vec<String>,
+}
+
+/// `vpn` キーワードのプロバイダー
+pub struct VpnProvider {
+    phonebook: PathBuf,
+    list: BackgroundList<VpnState>,
+    run: Runner,
+}
+
+impl VpnProvider {
+    pub fn new() -> Self {
+        let app_data = std::env::var("APPDATA").map(PathBuf::from).unwrap_or_default();
+        Self::with_phonebook(app_data.join(r"Microsoft\Network\Connections\Pbk\rasphone.pbk"), run_command)
+    }
+
+    /// 電話帳ファイルとコマンドの実行を差し替えて作成（テスト用）
+    pub fn with_phonebook(phonebook: PathBuf, run: Runner) -> Self {
+        Self { phonebook, list: BackgroundList::new("vpn", REFRESH_AFTER), run }
+    }
+}
+
+impl Default for VpnProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeywordProvider for VpnProvider {
+    fn name(&self) -> &'static str {
+        "vpn"
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &["vpn"]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("vpn [name]", "Connect or disconnect a VPN"),
+        ];
+        HELP
+    }
+
+    fn search(&self, _keyword: &str, args: &str) -> Vec<SearchResult> {
+        let entries = match std::fs::read_to_string(&self.phonebook) {
+            Ok(content) => parse_phonebook_entries(&content),
+            Err(e) => {
+                log::debug!("No VPN phonebook at {}: {}", self.phonebook.display(), e);
+                return Vec::new();
+            }
+        };
+        let run = self.run;
+        let listed = self.list.get(move || {
+            let connected = run("rasdial", &[]).map(|output| parse_rasdial_connections(&output)).unwrap_or_default();
+            Ok(VpnState { entries, connected })
+        });
+        match listed {
+            None => vec![provider_result("vpn", "Listing VPN connections...".to_string(), "Waiting for rasdial".to_string(), String::new())],
+            Some(Err(error)) => vec![provider_result("vpn", "Could not list VPN connections".to_string(), error, String::new())],
+            Some(Ok(state)) => vpn_results(&state.entries, &state.connected, args),
+        }
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        if command.is_empty() {
+            return Err("VPN connections are still being listed".into());
+        }
+        if let Some(name) = command.strip_prefix("disconnect:") {
+            (self.run)("rasdial", &[name, "/disconnect"])?;
+            self.list.invalidate();
+            return Ok(format!("Disconnected from {}", name));
+        }
+
+        let name = command.strip_prefix("connect:").ok_or_else(|| format!("unknown vpn command: {}", command))?;
+        // 保存済みの資格情報で接続する
+        run_in_background(format!("Connecting to VPN {}", name), self.run, "rasdial", vec![name.to_string()], self.list.clone())?;
+        Ok(format!("Connecting to {}...", name))
+    }
+
+    fn take_updated(&self) -> bool {
+        self.list.take_updated()
+    }
+
+    fn is_pending(&self) -> bool {
+        self.list.is_pending()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wifi_profiles() {
+        let output = "Profiles on interface Wi-Fi:\n\nGroup policy profiles (read only)\n---------------------------------\n    <None>\n\nUser profiles\n-------------\n    All User Profile     : Home Network\n    All User Profile     : Cafe\n";
+        assert_eq!(parse_wifi_profiles(output), vec!["Home Network", "Cafe"]);
+    }
+
+    #[test]
+    fn test_parse_current_ssid() {
+        let output = "    Name                   : Wi-Fi\n    State                  : connected\n    SSID                   : Cafe\n    BSSID                  : 00:11:22:33:44:55\n";
+        assert_eq!(parse_current_ssid(output), Some("Cafe".to_string()));
+        assert_eq!(parse_current_ssid("    State                  : disconnected\n"), None);
+    }
+
+    #[test]
+    fn test_parse_vpn_state() {
+        let phonebook = "[Office VPN]\nMEDIA=rastapi\n\n[Lab]\nMEDIA=rastapi\n";
+        assert_eq!(parse_phonebook_entries(phonebook), vec!["Office VPN", "Lab"]);
+
+        let connected = "Connected to\nOffice VPN\nCommand completed successfully.\n";
+        assert_eq!(parse_rasdial_connections(connected), vec!["Office VPN"]);
+        assert!(parse_rasdial_connections("No connections\nCommand completed successfully.\n").is_empty());
+    }
+
+    #[test]
+    fn test_results_reflect_connection_state() {
+        let profiles = vec!["Home".to_string(), "Cafe".to_string()];
+        let results = wifi_results(&profiles, Some("Cafe"), "");
+        assert_eq!(results[0].title, "Connect Wi-Fi: Home");
+        assert_eq!(results[0].description, "Saved network (currently connected to Cafe)");
+        assert_eq!(results[1].title, "Disconnect Wi-Fi: Cafe");
+        assert_eq!(wifi_results(&profiles, None, "ho").len(), 1);
+
+        let entries = vec!["Office VPN".to_string()];
+        let results = vpn_results(&entries, &entries, "");
+        assert_eq!(
+            results[0].action,
+            Action::ProviderCommand {
+                provider: "vpn".to_string(),
+                command: "disconnect:Office VPN".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_lists_stream_in() {
+        let wifi = WifiProvider::with_runner(|_, args| match args {
+            ["wlan", "show", "profiles"] => Ok("    All User Profile     : Home\n    All User Profile     : Cafe\n".to_string()),
+            _ => Ok("    SSID                   : Cafe\n".to_string()),
+        });
+        assert_eq!(wifi.search("wifi", "")[0].title, "Listing Wi-Fi networks...");
+        for _ in 0..100 {
+            if wifi.take_updated() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!wifi.is_pending());
+        let titles: Vec<String> = wifi.search("wifi", "").into_iter().map(|r| r.title).collect();
+        assert_eq!(titles, vec!["Connect Wi-Fi: Home", "Disconnect Wi-Fi: Cafe"]);
+
+        // 電話帳がなければ rasdial を待たずに何も出さない
+        let vpn = VpnProvider::with_phonebook(PathBuf::from("missing/rasphone.pbk"), |_, _| panic!("rasdial ran"));
+        assert!(vpn.search("vpn", "").is_empty());
+        assert!(!vpn.is_pending());
+    }
+}
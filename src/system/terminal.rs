@@ -0,0 +1,293 @@
+//! Windows Terminal のプロファイル（`term` / `wt` キーワード）
+//!
+//! settings.json（JSONC）のプロファイルを一覧にし、`wt.exe -p <profile>` で開く。
+//! ランチャーを開く前に前面にあったウィンドウのプロセスがフォルダーを引数に取っていれば、
+//! そのフォルダーで開く「here」の結果も出す。
+
+use crate::core::browser_search_engine::matches_command;
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+const PROVIDER: &str = "terminal";
+
+/// settings.json の場所（ストア版・プレビュー版・非パッケージ版の順に探す）
+fn settings_paths() -> Vec<PathBuf> {
+    let Ok(local_app_data) = std::env::var("LOCALAPPDATA").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    vec![
+        local_app_data.join(r"Packages\Microsoft.WindowsTerminal_8wekyb3d8bbwe\LocalState\settings.json"),
+        local_app_data.join(r"Packages\Microsoft.WindowsTerminalPreview_8wekyb3d8bbwe\LocalState\settings.json"),
+        local_app_data.join(r"Microsoft\Windows Terminal\settings.json"),
+    ]
+}
+
+/// JSONC のコメントと末尾のカンマを取り除く（文字列の中はそのまま）
+pub fn strip_jsonc(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|next| *next != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+    strip_trailing_commas(&output)
+}
+
+/// 閉じ括弧の直前のカンマを取り除く（コメントを取り除いたあとの文字列）
+fn strip_trailing_commas(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut output = String::with_capacity(content.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|next| !next.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        output.push(c);
+    }
+    output
+}
+
+/// 表示するプロファイル名（`hidden` のものは除く）
+pub fn parse_profiles(content: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let settings: serde_json::Value = serde_json::from_str(&strip_jsonc(content))?;
+    // `profiles` は `{ "defaults": ..., "list": [...] }` か、古い形式では配列そのもの
+    let list = settings["profiles"]["list"].as_array().or_else(|| settings["profiles"].as_array());
+    Ok(list
+        .into_iter()
+        .flatten()
+        .filter(|profile| !profile["hidden"].as_bool().unwrap_or(false))
+        .filter_map(|profile| profile["name"].as_str().map(str::to_string))
+        .collect())
+}
+
+/// コマンドラインの引数を分ける（`"` で囲んだ部分は1つの引数）
+fn split_arguments(command_line: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in command_line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    arguments.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        arguments.push(current);
+    }
+    arguments
+}
+
+/// プロセスのコマンドラインから作業フォルダーを推測する（最後に現れる、存在するフォルダーの引数）
+pub fn working_dir_from_command_line(command_line: &str) -> Option<PathBuf> {
+    split_arguments(command_line)
+        .into_iter()
+        .skip(1)
+        .rev()
+        .map(PathBuf::from)
+        .find(|path| path.is_absolute() && path.is_dir())
+}
+
+fn terminal_result(title: String, description: String, command: String) -> SearchResult {
+    SearchResult {
+        title,
+        description,
+        action: Action::ProviderCommand { provider: PROVIDER.to_string(), command },
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// プロファイルごとに「開く」「管理者として開く」と、作業フォルダーが分かれば「ここで開く」の結果を作る
+pub fn terminal_results(profiles: &[String], here: Option<&Path>, args: &str) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    for name in profiles.iter().filter(|name| args.is_empty() || matches_command(args, name)) {
+        if let Some(here) = here {
+            results.push(terminal_result(
+                format!("Open Terminal here: {}", name),
+                here.display().to_string(),
+                format!("here:{}", name),
+            ));
+        }
+        results.push(terminal_result(format!("Open Terminal: {}", name), "Windows Terminal".to_string(), format!("open:{}", name)));
+        results.push(terminal_result(
+            format!("Open Terminal: {} (Admin)", name),
+            "Windows Terminal as administrator".to_string(),
+            format!("admin:{}", name),
+        ));
+    }
+    results
+}
+
+/// `term` / `wt` キーワードのプロバイダー
+pub struct TerminalProvider {
+    /// ランチャーを開く前に前面にあったウィンドウの作業フォルダー
+    here: Option<PathBuf>,
+}
+
+impl TerminalProvider {
+    pub fn new() -> Self {
+        let here = crate::windows_api::foreground_window()
+            .and_then(crate::windows_api::process_command_line_for_window)
+            .and_then(|command_line| working_dir_from_command_line(&command_line));
+        Self { here }
+    }
+
+    fn load_profiles() -> Vec<String> {
+        let Some(path) = settings_paths().into_iter().find(|path| path.exists()) else {
+            log::debug!("Windows Terminal settings.json not found");
+            return Vec::new();
+        };
+        match std::fs::read_to_string(&path).map_err(Box::<dyn Error>::from).and_then(|content| parse_profiles(&content)) {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                log::warn!("Failed to read Windows Terminal profiles from {}: {}", path.display(), e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+impl Default for TerminalProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeywordProvider for TerminalProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &["term", "wt"]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("term [profile] / wt [profile]", "Open a Windows Terminal profile"),
+        ];
+        HELP
+    }
+
+    fn search(&self, _keyword: &str, args: &str) -> Vec<SearchResult> {
+        terminal_results(&Self::load_profiles(), self.here.as_deref(), args)
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        let (kind, name) = command.split_once(':').ok_or_else(|| format!("unknown terminal command: {}", command))?;
+        match kind {
+            "open" => {
+                std::process::Command::new("wt.exe").args(["-p", name]).spawn()?;
+                Ok(format!("Opened {}", name))
+            }
+            "here" => {
+                let here = self.here.as_ref().ok_or("The working folder is not known")?;
+                std::process::Command::new("wt.exe").args(["-p", name, "-d"]).arg(here).spawn()?;
+                Ok(format!("Opened {} in {}", name, here.display()))
+            }
+            "admin" => {
+                crate::windows_api::run_elevated("wt.exe", &format!("-p \"{}\"", name))?;
+                Ok(format!("Opened {} as administrator", name))
+            }
+            _ => Err(format!("unknown terminal command: {}", command).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles() {
+        let settings = r#"{
+            // 既定のプロファイル
+            "defaultProfile": "{61c54bbd}",
+            "profiles": {
+                "defaults": {},
+                "list": [
+                    { "name": "PowerShell", "commandline": "pwsh.exe", },
+                    /* WSL から自動生成 */
+                    { "name": "Ubuntu", "source": "Windows.Terminal.Wsl" },
+                    { "name": "Azure Cloud Shell", "hidden": true },
+                    { "name": "Path // not a comment" }
+                ]
+            },
+        }"#;
+        assert_eq!(parse_profiles(settings).unwrap(), vec!["PowerShell", "Ubuntu", "Path // not a comment"]);
+        assert_eq!(parse_profiles(r#"{ "profiles": [{ "name": "cmd" }] }"#).unwrap(), vec!["cmd"]);
+    }
+
+    #[test]
+    fn test_working_dir_from_command_line() {
+        let dir = std::env::temp_dir();
+        let command_line = format!("\"C:\\Program Files\\Editor\\editor.exe\" --reuse \"{}\"", dir.display());
+        assert_eq!(working_dir_from_command_line(&command_line), Some(dir));
+        assert_eq!(working_dir_from_command_line("notepad.exe"), None);
+    }
+
+    #[test]
+    fn test_terminal_results() {
+        let profiles = vec!["PowerShell".to_string(), "Ubuntu".to_string()];
+        let titles = |here: Option<&Path>, args: &str| {
+            terminal_results(&profiles, here, args).into_iter().map(|r| r.title).collect::<Vec<_>>()
+        };
+        assert_eq!(titles(None, "ubu"), vec!["Open Terminal: Ubuntu", "Open Terminal: Ubuntu (Admin)"]);
+        assert_eq!(titles(Some(Path::new("C:\\src")), "power")[0], "Open Terminal here: PowerShell");
+        assert_eq!(titles(None, "").len(), 4);
+    }
+}
@@ -0,0 +1,334 @@
+//! WSL のディストリビューション（`wsl` キーワード）
+//!
+//! `wsl -l -v` の一覧をバックグラウンドで取得し、届いたら検索し直して一覧に反映する。
+//! シェルを開く・ホームをエクスプローラーで開く（`\\wsl$\...`）・終了・再起動ができる。
+
+use super::run_command;
+use crate::core::browser_search_engine::matches_command;
+use crate::core::confirmation::ConfirmableAction;
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const PROVIDER: &str = "wsl";
+
+/// 一覧を取り直すまでの時間
+const REFRESH_AFTER: Duration = Duration::from_secs(5);
+
+/// コマンドの実行（テストで差し替える）
+type Runner = fn(&str, &[&str]) -> Result<String, Box<dyn Error>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WslDistro {
+    pub name: String,
+    /// `Running` / `Stopped` など
+    pub state: String,
+    pub version: String,
+    pub default: bool,
+}
+
+impl WslDistro {
+    pub fn is_running(&self) -> bool {
+        self.state.eq_ignore_ascii_case("Running")
+    }
+}
+
+/// `wsl.exe` は UTF-16 で出力するため、UTF-8 として読んだときに残る NUL と BOM を取り除く
+pub fn clean_output(output: &str) -> String {
+    output.chars().filter(|c| !matches!(c, '\0' | '\u{feff}' | '\u{fffd}')).collect()
+}
+
+/// `wsl -l -v` の出力からディストリビューションを取り出す
+pub fn parse_distros(output: &str) -> Vec<WslDistro> {
+    clean_output(output)
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let line = line.trim();
+            let (default, line) = match line.strip_prefix('*') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            // 名前に空白が入ることがあるので、後ろの2つ（状態と版）以外を名前にする
+            if words.len() < 3 {
+                return None;
+            }
+            let (name, rest) = words.split_at(words.len() - 2);
+            Some(WslDistro {
+                name: name.join(" "),
+                state: rest[0].to_string(),
+                version: rest[1].to_string(),
+                default,
+            })
+        })
+        .collect()
+}
+
+fn wsl_result(title: String, description: String, command: String) -> SearchResult {
+    SearchResult {
+        title,
+        description,
+        action: Action::ProviderCommand { provider: PROVIDER.to_string(), command },
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// ディストリビューションごとの操作の結果を作る（終了は動いているものだけ）
+pub fn wsl_results(distros: &[WslDistro], args: &str) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    for distro in distros.iter().filter(|distro| args.is_empty() || matches_command(args, &distro.name)) {
+        let name = &distro.name;
+        let status = format!("{} · WSL {}{}", distro.state, distro.version, if distro.default { " · default" } else { "" });
+        results.push(wsl_result(format!("Open shell: {}", name), status.clone(), format!("shell:{}", name)));
+        results.push(wsl_result(format!("Open home in Explorer: {}", name), format!(r"\\wsl$\{}", name), format!("home:{}", name)));
+        if distro.is_running() {
+            results.push(wsl_result(format!("Terminate: {}", name), status.clone(), format!("terminate:{}", name)));
+        }
+        results.push(wsl_result(format!("Restart: {}", name), status, format!("restart:{}", name)));
+    }
+    results
+}
+
+#[derive(Default)]
+struct WslState {
+    distros: Vec<WslDistro>,
+    error: Option<String>,
+    listed_at: Option<Instant>,
+    listing: bool,
+}
+
+/// `wsl` キーワードのプロバイダー
+pub struct WslProvider {
+    state: Arc<Mutex<WslState>>,
+    updated: Arc<AtomicBool>,
+    run: Runner,
+}
+
+impl WslProvider {
+    pub fn new() -> Self {
+        Self::with_runner(run_command)
+    }
+
+    /// コマンドの実行を差し替えて作成（テスト用）
+    pub fn with_runner(run: Runner) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(WslState::default())),
+            updated: Arc::new(AtomicBool::new(false)),
+            run,
+        }
+    }
+
+    /// 一覧が古ければバックグラウンドで取り直す（取得中なら何もしない）
+    fn refresh(&self) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.listing || state.listed_at.is_some_and(|at| at.elapsed() < REFRESH_AFTER) {
+                return;
+            }
+            state.listing = true;
+        }
+
+        let state = Arc::clone(&self.state);
+        let updated = Arc::clone(&self.updated);
+        let run = self.run;
+        let spawned = std::thread::Builder::new().name("wsl-list".to_string()).spawn(move || {
+            let listed = run("wsl.exe", &["--list", "--verbose"]).map(|output| parse_distros(&output));
+            let mut state = state.lock().unwrap();
+            match listed {
+                Ok(distros) => {
+                    state.distros = distros;
+                    state.error = None;
+                }
+                Err(e) => {
+                    log::warn!("Failed to list WSL distributions: {}", e);
+                    state.error = Some(e.to_string());
+                }
+            }
+            state.listed_at = Some(Instant::now());
+            state.listing = false;
+            updated.store(true, Ordering::SeqCst);
+        });
+        if let Err(e) = spawned {
+            log::error!("Failed to spawn WSL list thread: {}", e);
+            self.state.lock().unwrap().listing = false;
+        }
+    }
+
+    /// 時間のかかる操作をバックグラウンドで実行し、終わったら一覧を取り直す
+    fn run_in_background(&self, label: String, steps: Vec<Vec<String>>) -> Result<(), Box<dyn Error>> {
+        let state = Arc::clone(&self.state);
+        let run = self.run;
+        std::thread::Builder::new().name("wsl-command".to_string()).spawn(move || {
+            for step in &steps {
+                let args: Vec<&str> = step.iter().map(String::as_str).collect();
+                if let Err(e) = run("wsl.exe", &args) {
+                    log::error!("{} failed: {}", label, e);
+                    break;
+                }
+            }
+            log::info!("{} finished", label);
+            state.lock().unwrap().listed_at = None;
+        })?;
+        Ok(())
+    }
+}
+
+impl Default for WslProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeywordProvider for WslProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &["wsl"]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("wsl [distribution]", "Open a shell or the home folder, stop or restart a distribution"),
+        ];
+        HELP
+    }
+
+    fn search(&self, _keyword: &str, args: &str) -> Vec<SearchResult> {
+        self.refresh();
+        let state = self.state.lock().unwrap();
+        if state.listed_at.is_none() {
+            return vec![wsl_result("Listing WSL distributions...".to_string(), "Waiting for wsl.exe".to_string(), String::new())];
+        }
+        if let Some(error) = &state.error {
+            return vec![wsl_result("Could not list WSL distributions".to_string(), error.clone(), String::new())];
+        }
+        wsl_results(&state.distros, args)
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        let (kind, name) = command.split_once(':').ok_or("WSL is still being listed")?;
+        match kind {
+            "shell" => {
+                std::process::Command::new("wsl.exe").args(["--distribution", name, "--cd", "~"]).spawn()?;
+                Ok(format!("Opened a shell in {}", name))
+            }
+            "home" => {
+                // ユーザーのホームは起動しないと分からないので、取得と表示をバックグラウンドで行う
+                let message = format!("Opening the home of {}...", name);
+                let name = name.to_string();
+                let run = self.run;
+                std::thread::Builder::new().name("wsl-home".to_string()).spawn(move || {
+                    let home = run("wsl.exe", &["--distribution", &name, "--exec", "sh", "-c", "echo $HOME"])
+                        .map(|output| clean_output(&output).trim().to_string())
+                        .unwrap_or_else(|e| {
+                            log::warn!("Failed to get the home of {}: {}", name, e);
+                            "/home".to_string()
+                        });
+                    let path = format!(r"\\wsl$\{}{}", name, home.replace('/', r"\"));
+                    if let Err(e) = open::that(&path) {
+                        log::error!("Failed to open {}: {}", path, e);
+                    }
+                })?;
+                Ok(message)
+            }
+            "terminate" => {
+                self.run_in_background(format!("Terminating {}", name), vec![vec!["--terminate".to_string(), name.to_string()]])?;
+                Ok(format!("Terminating {}...", name))
+            }
+            "restart" => {
+                self.run_in_background(
+                    format!("Restarting {}", name),
+                    vec![
+                        vec!["--terminate".to_string(), name.to_string()],
+                        vec!["--distribution".to_string(), name.to_string(), "--exec".to_string(), "true".to_string()],
+                    ],
+                )?;
+                Ok(format!("Restarting {}...", name))
+            }
+            _ => Err(format!("unknown wsl command: {}", command).into()),
+        }
+    }
+
+    fn preview(&self, command: &str) -> Option<ConfirmableAction> {
+        let (kind, name) = command.split_once(':')?;
+        let (summary, effects) = match kind {
+            "terminate" => (format!("Terminate {}", name), vec![format!("Stop: every process in {}", name)]),
+            "restart" => (format!("Restart {}", name), vec![format!("Stop: every process in {}", name), format!("Start: {}", name)]),
+            _ => return None,
+        };
+        let action = Action::ProviderCommand { provider: PROVIDER.to_string(), command: command.to_string() };
+        Some(ConfirmableAction::new(action, summary, effects))
+    }
+
+    fn take_updated(&self) -> bool {
+        self.updated.swap(false, Ordering::SeqCst)
+    }
+
+    fn is_pending(&self) -> bool {
+        self.state.lock().unwrap().listing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST: &str = "  NAME            STATE           VERSION\r\n* Ubuntu          Running         2\r\n  Debian Testing  Stopped         2\r\n";
+
+    #[test]
+    fn test_parse_distros() {
+        // UTF-16 の出力を UTF-8 として読んだ場合
+        let utf16: String = "\u{feff}".chars().chain(LIST.chars().flat_map(|c| [c, '\0'])).collect();
+        let distros = parse_distros(&utf16);
+        assert_eq!(distros.len(), 2);
+        assert_eq!(
+            distros[0],
+            WslDistro { name: "Ubuntu".to_string(), state: "Running".to_string(), version: "2".to_string(), default: true }
+        );
+        assert_eq!(distros[1].name, "Debian Testing");
+        assert!(!distros[1].is_running());
+    }
+
+    #[test]
+    fn test_wsl_results() {
+        let distros = parse_distros(LIST);
+        let titles: Vec<String> = wsl_results(&distros, "deb").into_iter().map(|r| r.title).collect();
+        assert_eq!(titles, vec!["Open shell: Debian Testing", "Open home in Explorer: Debian Testing", "Restart: Debian Testing"]);
+        assert_eq!(wsl_results(&distros, "").len(), 7);
+    }
+
+    #[test]
+    fn test_terminate_and_restart_need_confirmation() {
+        let provider = WslProvider::with_runner(|_, _| Ok(LIST.to_string()));
+        assert!(provider.preview("shell:Ubuntu").is_none());
+        assert!(provider.preview("home:Ubuntu").is_none());
+        let terminate = provider.preview("terminate:Ubuntu").unwrap();
+        assert_eq!(terminate.summary, "Terminate Ubuntu");
+        assert_eq!(terminate.action, Action::ProviderCommand { provider: "wsl".to_string(), command: "terminate:Ubuntu".to_string() });
+        assert_eq!(provider.preview("restart:Ubuntu").unwrap().effects.len(), 2);
+    }
+
+    #[test]
+    fn test_list_streams_in() {
+        let provider = WslProvider::with_runner(|_, _| Ok(LIST.to_string()));
+        assert!(provider.search("wsl", "")[0].title.starts_with("Listing"));
+
+        for _ in 0..100 {
+            if provider.take_updated() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!provider.is_pending());
+        assert_eq!(provider.search("wsl", "ubuntu")[0].title, "Open shell: Ubuntu");
+    }
+}
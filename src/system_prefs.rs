@@ -0,0 +1,190 @@
+//! Windows の「アニメーション効果」と省電力（バッテリー節約機能）の状態、「テキストのサイズ」
+//!
+//! どちらかが有効ならアニメーション・サムネイル・継続的な再描画を控える。
+//! 機能ごとに設定（`motion`）で上書きできる。テキストのサイズは UI 全体の倍率に使う。
+
+use crate::config::MotionConfig;
+use std::time::{Duration, Instant};
+
+/// 省電力の状態などを確認し直す間隔
+const RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// UI の倍率の範囲
+const MIN_UI_SCALE: f32 = 0.75;
+const MAX_UI_SCALE: f32 = 3.0;
+
+/// OSの設定
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemPrefs {
+    /// 「アニメーション効果」がオフ
+    pub reduced_motion: bool,
+    /// バッテリー節約機能がオン
+    pub power_saver: bool,
+}
+
+/// UI で使う動きの設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MotionSettings {
+    /// egui のアニメーション（ホバーや折りたたみ）
+    pub animations: bool,
+    /// ウィンドウのサムネイルのキャプチャ
+    pub thumbnails: bool,
+    /// スピナーやサムネイルの取り直しのための継続的な再描画
+    pub continuous_repaint: bool,
+}
+
+impl MotionSettings {
+    /// OSの設定から決め、設定ファイルで指定した項目はそちらを優先する
+    pub fn resolve(config: &MotionConfig, prefs: SystemPrefs) -> Self {
+        let reduce = prefs.reduced_motion || prefs.power_saver;
+        Self {
+            animations: config.animations.unwrap_or(!reduce),
+            thumbnails: config.thumbnails.unwrap_or(!prefs.power_saver),
+            continuous_repaint: config.continuous_repaint.unwrap_or(!reduce),
+        }
+    }
+}
+
+/// OSの設定を定期的に確認し直して `MotionSettings` を返す
+pub struct MotionPrefs {
+    config: MotionConfig,
+    settings: MotionSettings,
+    checked: Instant,
+}
+
+impl MotionPrefs {
+    pub fn new(config: MotionConfig) -> Self {
+        let prefs = detect();
+        log::info!("System preferences: {:?}", prefs);
+        Self {
+            settings: MotionSettings::resolve(&config, prefs),
+            config,
+            checked: Instant::now(),
+        }
+    }
+
+    pub fn current(&mut self) -> MotionSettings {
+        if self.checked.elapsed() >= RECHECK_INTERVAL {
+            self.settings = MotionSettings::resolve(&self.config, detect());
+            self.checked = Instant::now();
+        }
+        self.settings
+    }
+}
+
+#[cfg(windows)]
+pub fn detect() -> SystemPrefs {
+    use winapi::shared::minwindef::{BOOL, FALSE};
+    use winapi::um::winbase::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    use winapi::um::winuser::SystemParametersInfoW;
+
+    // 設定 > アクセシビリティ > 視覚効果 > アニメーション効果
+    const SPI_GETCLIENTAREAANIMATION: u32 = 0x1042;
+
+    let mut animation: BOOL = 1;
+    let reduced_motion = unsafe {
+        SystemParametersInfoW(SPI_GETCLIENTAREAANIMATION, 0, &mut animation as *mut BOOL as *mut _, 0) != 0
+    } && animation == FALSE;
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    // winapi では SystemStatusFlag が Reserved1 という名前になっている（1 ならバッテリー節約機能がオン）
+    let power_saver = unsafe { GetSystemPowerStatus(&mut status) } != 0 && status.Reserved1 == 1;
+
+    SystemPrefs { reduced_motion, power_saver }
+}
+
+#[cfg(not(windows))]
+pub fn detect() -> SystemPrefs {
+    SystemPrefs::default()
+}
+
+/// UI 全体の倍率（設定の `appearance.ui_scale`、なければ Windows のテキストのサイズ）
+pub fn ui_scale(configured: Option<f32>) -> f32 {
+    resolve_ui_scale(configured, text_scale())
+}
+
+fn resolve_ui_scale(configured: Option<f32>, system: f32) -> f32 {
+    let scale = configured.unwrap_or(system);
+    if !scale.is_finite() {
+        return 1.0;
+    }
+    let clamped = scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+    if clamped != scale {
+        log::warn!("appearance.ui_scale = {} is out of range ({}..={}), using {}", scale, MIN_UI_SCALE, MAX_UI_SCALE, clamped);
+    }
+    clamped
+}
+
+/// 設定 > アクセシビリティ > テキストのサイズ（100%〜225%、読めなければ 1.0）
+#[cfg(windows)]
+pub fn text_scale() -> f32 {
+    use std::ptr::null_mut;
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::winreg::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+
+    let key: Vec<u16> = "Software\\Microsoft\\Accessibility".encode_utf16().chain(std::iter::once(0)).collect();
+    let value: Vec<u16> = "TextScaleFactor".encode_utf16().chain(std::iter::once(0)).collect();
+    let mut percent: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            null_mut(),
+            &mut percent as *mut u32 as *mut _,
+            &mut size,
+        )
+    };
+    if status as u32 == ERROR_SUCCESS && (100..=225).contains(&percent) {
+        percent as f32 / 100.0
+    } else {
+        1.0
+    }
+}
+
+#[cfg(not(windows))]
+pub fn text_scale() -> f32 {
+    1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_from_system_prefs() {
+        let config = MotionConfig::default();
+        let all = MotionSettings::resolve(&config, SystemPrefs::default());
+        assert!(all.animations && all.thumbnails && all.continuous_repaint);
+
+        let reduced = MotionSettings::resolve(&config, SystemPrefs { reduced_motion: true, power_saver: false });
+        assert!(!reduced.animations && !reduced.continuous_repaint);
+        assert!(reduced.thumbnails);
+
+        let saver = MotionSettings::resolve(&config, SystemPrefs { reduced_motion: false, power_saver: true });
+        assert!(!saver.animations && !saver.thumbnails && !saver.continuous_repaint);
+    }
+
+    #[test]
+    fn test_config_overrides() {
+        let config = MotionConfig {
+            thumbnails: Some(true),
+            animations: Some(false),
+            continuous_repaint: None,
+        };
+        let settings = MotionSettings::resolve(&config, SystemPrefs { reduced_motion: false, power_saver: true });
+        assert!(settings.thumbnails);
+        assert!(!settings.animations);
+        assert!(!settings.continuous_repaint);
+    }
+
+    #[test]
+    fn test_ui_scale() {
+        assert_eq!(resolve_ui_scale(None, 1.5), 1.5);
+        assert_eq!(resolve_ui_scale(Some(1.25), 1.5), 1.25);
+        assert_eq!(resolve_ui_scale(Some(10.0), 1.0), MAX_UI_SCALE);
+        assert_eq!(resolve_ui_scale(Some(f32::NAN), 1.0), 1.0);
+    }
+}
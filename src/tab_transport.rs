@@ -0,0 +1,385 @@
+//! 拡張機能とタブ一覧をやり取りする経路
+//!
+//! 既定は WebSocket（`ws://localhost:9999`）。社内の端末などで拡張機能から localhost への WebSocket が
+//! 止められていると、拡張機能は何度か失敗したあと Native Messaging に切り替える。Chrome が起動した
+//! `my-launcher`（引数が `chrome-extension://...`）が標準入出力のメッセージを名前付きパイプ
+//! （`NATIVE_PIPE_NAME`）でランチャーかデーモンに中継し、こちらは WebSocket と同じ処理
+//! （`handle_session_text`）で扱う。どちらの経路も `Supervisor` で動かしておくので、拡張機能が
+//! どちらで接続してもタブが届く。いま使われている経路は `ExtensionSessions::active_transport`。
+
+use std::error::Error;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use log::{debug, error, info, warn};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::runtime::Runtime;
+
+use crate::core::extension_sessions::{SessionControl, Transport};
+use crate::core::native_messaging::TabManager;
+use crate::supervisor::Supervisor;
+use crate::websocket_server::{command_event, handle_session_text, WebSocketServer, MAX_MESSAGE_BYTES};
+use crate::websocket_types::{EventData, EventType, WebSocketMessage};
+
+/// Chrome に登録する Native Messaging ホストの名前（拡張機能の `connectNative` と同じ）
+pub const NATIVE_HOST_NAME: &str = "com.my_launcher.tabs";
+
+/// Native Messaging ホストとランチャーの間の名前付きパイプ
+pub const NATIVE_PIPE_NAME: &str = r"\\.\pipe\my_launcher_native";
+
+/// キューされたコマンドと、一覧から頼まれた操作を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 拡張機能からの接続を受け付ける経路
+pub trait TabTransport: Send + Sync {
+    fn kind(&self) -> Transport;
+
+    /// `Supervisor` で監視するときの名前（ヘッダーの点とステータスに表示される）
+    fn service_name(&self) -> &'static str;
+
+    /// 接続を受け付ける（止まるまで戻らない）
+    fn serve(&self) -> Result<(), String>;
+}
+
+pub struct WebSocketTransport {
+    tab_manager: Arc<TabManager>,
+    port: u16,
+}
+
+impl WebSocketTransport {
+    pub fn new(tab_manager: Arc<TabManager>, port: u16) -> Self {
+        Self { tab_manager, port }
+    }
+}
+
+impl TabTransport for WebSocketTransport {
+    fn kind(&self) -> Transport {
+        Transport::WebSocket
+    }
+
+    fn service_name(&self) -> &'static str {
+        "websocket"
+    }
+
+    fn serve(&self) -> Result<(), String> {
+        let rt = Runtime::new().map_err(|e| e.to_string())?;
+        let server = WebSocketServer::new(Arc::clone(&self.tab_manager), self.port);
+        rt.block_on(server.start()).map_err(|e| e.to_string())
+    }
+}
+
+pub struct NativeMessagingTransport {
+    tab_manager: Arc<TabManager>,
+}
+
+impl NativeMessagingTransport {
+    pub fn new(tab_manager: Arc<TabManager>) -> Self {
+        Self { tab_manager }
+    }
+}
+
+impl TabTransport for NativeMessagingTransport {
+    fn kind(&self) -> Transport {
+        Transport::NativeMessaging
+    }
+
+    fn service_name(&self) -> &'static str {
+        "native-messaging"
+    }
+
+    #[cfg(windows)]
+    fn serve(&self) -> Result<(), String> {
+        let rt = Runtime::new().map_err(|e| e.to_string())?;
+        rt.block_on(serve_native_pipe(Arc::clone(&self.tab_manager))).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(windows))]
+    fn serve(&self) -> Result<(), String> {
+        let _ = &self.tab_manager;
+        Err("Native messaging is only supported on Windows".to_string())
+    }
+}
+
+/// 既定の経路（Windows では WebSocket と Native Messaging、それ以外は WebSocket だけ）
+pub fn default_transports(tab_manager: &Arc<TabManager>, port: u16) -> Vec<Arc<dyn TabTransport>> {
+    let mut transports: Vec<Arc<dyn TabTransport>> = vec![Arc::new(WebSocketTransport::new(Arc::clone(tab_manager), port))];
+    if cfg!(windows) {
+        transports.push(Arc::new(NativeMessagingTransport::new(Arc::clone(tab_manager))));
+    }
+    transports
+}
+
+/// それぞれの経路を `supervisor` で動かす（片方が止まっても、拡張機能はもう片方でつながる）
+pub fn spawn_transports(supervisor: &Supervisor, transports: Vec<Arc<dyn TabTransport>>) -> io::Result<()> {
+    for transport in transports {
+        supervisor.spawn(transport.service_name(), move || {
+            info!("Starting {} transport", transport.kind().label());
+            transport.serve()
+        })?;
+    }
+    Ok(())
+}
+
+/// Native Messaging と同じ形（4 バイトの長さ（リトルエンディアン）と JSON）で 1 件読む
+///
+/// 上限を超えるものは本文を読み捨てて `InvalidData` を返す（次のメッセージはそのまま読める）。
+pub async fn read_frame<R>(reader: &mut R) -> io::Result<String>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut length_bytes = [0u8; 4];
+    reader.read_exact(&mut length_bytes).await?;
+    let length = u32::from_le_bytes(length_bytes) as usize;
+    if length > MAX_MESSAGE_BYTES {
+        tokio::io::copy(&mut (&mut *reader).take(length as u64), &mut tokio::io::sink()).await?;
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {} bytes exceeds the {} byte limit", length, MAX_MESSAGE_BYTES),
+        ));
+    }
+
+    let mut bytes = vec![0u8; length];
+    reader.read_exact(&mut bytes).await?;
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub async fn write_frame<W>(writer: &mut W, text: &str) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(&(text.len() as u32).to_le_bytes()).await?;
+    writer.write_all(text.as_bytes()).await?;
+    writer.flush().await
+}
+
+async fn write_message<W>(writer: &mut W, message: &WebSocketMessage) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    write_frame(writer, &serde_json::to_string(message)?).await
+}
+
+/// Native Messaging ホストからの接続を 1 つ処理する（WebSocket の接続と同じメッセージをやり取りする）
+pub async fn handle_native_connection<S>(stream: S, tab_manager: Arc<TabManager>)
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    info!("Native messaging host connected");
+    tab_manager.extension_connected();
+    let session = tab_manager.sessions().open(Transport::NativeMessaging, None);
+    let (mut reader, mut writer) = tokio::io::split(stream);
+
+    // read_exact は select! で中断すると読みかけを失うので、読むのは別のタスクにする。
+    // チャンネルがいっぱいの間は読まないので、処理が追いつくまでホストを待たせる
+    let (frame_tx, mut frame_rx) = tokio::sync::mpsc::channel::<Result<String, String>>(32);
+    let read_task = tokio::spawn(async move {
+        loop {
+            let frame = match read_frame(&mut reader).await {
+                Ok(text) => Ok(text),
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => Err(e.to_string()),
+                Err(e) => {
+                    debug!("Native messaging host stopped sending: {}", e);
+                    break;
+                }
+            };
+            if frame_tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let initial = WebSocketMessage::event(EventType::TabsUpdated, EventData::TabsUpdate { tabs: tab_manager.get_tabs() });
+    if let Err(e) = write_message(&mut writer, &initial).await {
+        error!("Failed to send initial tabs to native messaging host: {}", e);
+    }
+
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+    'connection: loop {
+        let (messages, disconnect): (Vec<WebSocketMessage>, bool) = tokio::select! {
+            frame = frame_rx.recv() => match frame {
+                Some(Ok(text)) => {
+                    debug!("Received native message: {}", text);
+                    (handle_session_text(&text, &tab_manager, Some(session)).into_iter().collect(), false)
+                }
+                // 読めなかったメッセージにはエラーを返し、接続は続ける
+                Some(Err(message)) => {
+                    warn!("Rejected native message: {}", message);
+                    (vec![WebSocketMessage::response_error(String::new(), 400, format!("Invalid message: {}", message))], false)
+                }
+                None => break,
+            },
+            _ = poll.tick() => pending_events(&tab_manager, session),
+        };
+
+        for message in &messages {
+            if let Err(e) = write_message(&mut writer, message).await {
+                error!("Failed to write to native messaging host: {}", e);
+                break 'connection;
+            }
+        }
+        if disconnect {
+            info!("Disconnecting extension session {}", session);
+            break;
+        }
+    }
+
+    read_task.abort();
+    tab_manager.sessions().close(session);
+    tab_manager.extension_disconnected();
+    info!("Native messaging host disconnected");
+}
+
+/// キューされたコマンドと一覧から頼まれた操作を、拡張機能へ送るイベントにする（切断を頼まれたら true）
+fn pending_events(tab_manager: &TabManager, session: u64) -> (Vec<WebSocketMessage>, bool) {
+    let mut events: Vec<WebSocketMessage> = std::iter::from_fn(|| tab_manager.pop_command()).map(command_event).collect();
+    let mut disconnect = false;
+    for control in tab_manager.sessions().take_controls(session) {
+        match control {
+            SessionControl::RefreshTabs => {
+                events.push(WebSocketMessage::event(EventType::TabsRefreshRequested, EventData::Empty {}));
+            }
+            SessionControl::Disconnect => {
+                events.push(WebSocketMessage::event(EventType::DisconnectRequested, EventData::Empty {}));
+                disconnect = true;
+            }
+        }
+    }
+    (events, disconnect)
+}
+
+/// Native Messaging ホストからの接続を受け付ける（接続ごとにパイプのインスタンスを作り直す）
+#[cfg(windows)]
+async fn serve_native_pipe(tab_manager: Arc<TabManager>) -> io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new().first_pipe_instance(true).create(NATIVE_PIPE_NAME)?;
+    info!("Native messaging pipe listening on {}", NATIVE_PIPE_NAME);
+
+    loop {
+        server.connect().await?;
+        let connected = server;
+        server = ServerOptions::new().create(NATIVE_PIPE_NAME)?;
+        tokio::spawn(handle_native_connection(connected, Arc::clone(&tab_manager)));
+    }
+}
+
+/// Chrome から起動された Native Messaging ホストとして、標準入出力とランチャーのパイプを中継する
+///
+/// パイプも同じ形でメッセージを送るので、バイト列のまま流す。どちらかが閉じたら終わる
+/// （Chrome が閉じれば標準入力が、ランチャーが終了するか切断すればパイプが閉じる）。
+#[cfg(windows)]
+pub fn run_native_host() -> Result<(), Box<dyn Error>> {
+    use tokio::net::windows::named_pipe::ClientOptions;
+
+    let rt = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    rt.block_on(async {
+        let mut attempts = 0;
+        let pipe = loop {
+            match ClientOptions::new().open(NATIVE_PIPE_NAME) {
+                Ok(pipe) => break pipe,
+                Err(e) if attempts < 10 => {
+                    debug!("Native messaging pipe not ready ({}), retrying", e);
+                    attempts += 1;
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        info!("Native messaging host relaying to {}", NATIVE_PIPE_NAME);
+
+        let (mut pipe_reader, mut pipe_writer) = tokio::io::split(pipe);
+        let mut stdin = tokio::io::stdin();
+        let mut stdout = tokio::io::stdout();
+        tokio::select! {
+            result = tokio::io::copy(&mut stdin, &mut pipe_writer) => result?,
+            result = tokio::io::copy(&mut pipe_reader, &mut stdout) => result?,
+        };
+        Ok(())
+    })?;
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn run_native_host() -> Result<(), Box<dyn Error>> {
+    Err("Native messaging is only supported on Windows".into())
+}
+
+#[cfg(windows)]
+fn native_host_key() -> String {
+    format!(r"HKCU\Software\Google\Chrome\NativeMessagingHosts\{}", NATIVE_HOST_NAME)
+}
+
+/// 現在のユーザーの Chrome に Native Messaging ホストを登録する（マニフェストのパスを返す）
+#[cfg(windows)]
+pub fn register_native_host(extension_id: &str) -> Result<std::path::PathBuf, Box<dyn Error>> {
+    let manifest_path = crate::paths::data_dir().join("native-host.json");
+    let manifest = serde_json::json!({
+        "name": NATIVE_HOST_NAME,
+        "description": "My Launcher tab bridge",
+        "path": std::env::current_exe()?,
+        "type": "stdio",
+        "allowed_origins": [format!("chrome-extension://{}/", extension_id)],
+    });
+    std::fs::create_dir_all(crate::paths::data_dir())?;
+    std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    crate::system::run_command("reg", &["add", &native_host_key(), "/ve", "/d", &manifest_path.to_string_lossy(), "/f"])?;
+    Ok(manifest_path)
+}
+
+/// 登録した Native Messaging ホストを削除する
+#[cfg(windows)]
+pub fn unregister_native_host() -> Result<(), Box<dyn Error>> {
+    crate::system::run_command("reg", &["delete", &native_host_key(), "/f"])?;
+    let _ = std::fs::remove_file(crate::paths::data_dir().join("native-host.json"));
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn register_native_host(_extension_id: &str) -> Result<std::path::PathBuf, Box<dyn Error>> {
+    Err("Native messaging host registration is only supported on Windows".into())
+}
+
+#[cfg(not(windows))]
+pub fn unregister_native_host() -> Result<(), Box<dyn Error>> {
+    Err("Native messaging host registration is only supported on Windows".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::native_messaging::ChromeCommand;
+
+    #[test]
+    fn test_frames_round_trip() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let (mut client, mut server) = tokio::io::duplex(1024);
+            write_frame(&mut client, r#"{"type":"request","id":"1","method":"keepAlive"}"#).await.unwrap();
+            write_frame(&mut client, "日本語").await.unwrap();
+            assert_eq!(read_frame(&mut server).await.unwrap(), r#"{"type":"request","id":"1","method":"keepAlive"}"#);
+            assert_eq!(read_frame(&mut server).await.unwrap(), "日本語");
+
+            // 上限を超える長さは本文を読み捨ててエラーにする（ここでは本文の途中で閉じる）
+            client.write_all(&u32::MAX.to_le_bytes()).await.unwrap();
+            client.write_all(b"{}").await.unwrap();
+            drop(client);
+            assert_eq!(read_frame(&mut server).await.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        });
+    }
+
+    #[test]
+    fn test_pending_events_include_commands_and_controls() {
+        let tab_manager = TabManager::new();
+        let session = tab_manager.sessions().open(Transport::NativeMessaging, None);
+        tab_manager.queue_command(ChromeCommand::CloseTab { tab_id: 3 });
+        tab_manager.sessions().request(session, SessionControl::Disconnect);
+
+        let (events, disconnect) = pending_events(&tab_manager, session);
+        let events: Vec<String> = events.iter().map(|event| serde_json::to_string(event).unwrap()).collect();
+        assert!(disconnect);
+        assert_eq!(events.len(), 2);
+        assert!(events[0].contains("tabCloseRequested"));
+        assert!(events[1].contains("disconnectRequested"));
+        assert_eq!(pending_events(&tab_manager, session).0.len(), 0);
+    }
+}
@@ -11,6 +11,7 @@ pub mod helpers {
             is_visible: true,
             is_minimized: false,
             rect: (0, 0, 1920, 1080),
+            requires_elevation: false,
         }
     }
     
@@ -21,4 +22,185 @@ pub mod helpers {
     pub fn assert_search_result_contains_title(results: &[SearchResult], title: &str) -> bool {
         results.iter().any(|r| r.title.contains(title))
     }
-}
\ No newline at end of file
+}
+
+/// 検索のテスト用のデータ（`test-support` 機能で結合テストからも使える）
+pub mod fixtures {
+    use crate::core::{BrowserSearchEngine, ChromeTab, TabManager};
+    use crate::data::browser_item::{BookmarkItem, HistoryItem};
+    use crate::data::browser_provider::mock::InMemoryBrowserProvider;
+    use std::sync::Arc;
+
+    pub fn bookmark(title: &str, url: &str) -> BookmarkItem {
+        BookmarkItem {
+            id: None,
+            title: title.to_string(),
+            url: url.to_string(),
+            folder: None,
+            browser_name: None,
+            profile_name: None,
+        }
+    }
+
+    /// 最終訪問は `last_visit_time`（Webkit 時刻）の代わりに今からの分数で指定する
+    pub fn history(title: &str, url: &str, visit_count: i32, minutes_ago: i64) -> HistoryItem {
+        HistoryItem {
+            title: title.to_string(),
+            url: url.to_string(),
+            visit_count,
+            last_visit_time: crate::time_format::to_webkit(chrono::Utc::now() - chrono::Duration::minutes(minutes_ago)),
+            browser_name: None,
+            profile_name: None,
+        }
+    }
+
+    pub fn tab(id: i32, window_id: i32, title: &str, url: &str) -> ChromeTab {
+        ChromeTab {
+            id,
+            window_id,
+            title: title.to_string(),
+            url: url.to_string(),
+            fav_icon_url: String::new(),
+            active: false,
+            index: id,
+            pinned: false,
+        }
+    }
+
+    /// ブックマーク・履歴・タブを読み込んだ検索エンジン（拡張機能は接続済みとして扱う）
+    pub fn browser_engine(
+        bookmarks: Vec<BookmarkItem>,
+        history: Vec<HistoryItem>,
+        tabs: Vec<ChromeTab>,
+    ) -> (BrowserSearchEngine, Arc<TabManager>) {
+        let tab_manager = Arc::new(TabManager::new());
+        tab_manager.extension_connected();
+        tab_manager.update_tabs(tabs);
+        let provider = InMemoryBrowserProvider::new().with_bookmarks(bookmarks).with_history(history);
+        (BrowserSearchEngine::with_providers(Box::new(provider), tab_manager.clone()), tab_manager)
+    }
+}
+
+/// ランチャー全体を画面なしで動かすシミュレーション（結合テスト用）
+///
+/// ウィンドウは `MockWindowManager`、ブックマークと履歴は `InMemoryBrowserProvider`、タブは拡張機能の
+/// メッセージ（WebSocket の JSON）を流して `TabManager` に入れる。結果を選ぶと `LauncherApp` と同じく
+/// 拡張機能へのコマンドをキューに積み、ウィンドウの切り替えはモックに記録し、URL などは開かずに記録する。
+pub mod simulation {
+    use crate::core::confirmation::ConfirmableAction;
+    use crate::core::extension_commands::commands_for;
+    use crate::core::native_messaging::ChromeCommand;
+    use crate::core::window_manager::mock::MockWindowManager;
+    use crate::core::{Action, BrowserSearchEngine, LauncherCore, SearchMode, SearchResult, TabManager, WindowInfo};
+    use crate::data::browser_provider::mock::InMemoryBrowserProvider;
+    use std::sync::Arc;
+
+    pub struct Simulation {
+        launcher: LauncherCore<BrowserSearchEngine, MockWindowManager>,
+        window_manager: Arc<MockWindowManager>,
+        tab_manager: Arc<TabManager>,
+        /// 選んだが、開かずに記録した操作
+        opened: Vec<Action>,
+    }
+
+    impl Simulation {
+        pub fn new(windows: Vec<WindowInfo>, browser: InMemoryBrowserProvider) -> Self {
+            let tab_manager = Arc::new(TabManager::new());
+            let window_manager = Arc::new(MockWindowManager::new(windows));
+            let search_engine = BrowserSearchEngine::with_providers(Box::new(browser), tab_manager.clone());
+            Self {
+                launcher: LauncherCore::new(search_engine, window_manager.clone()),
+                window_manager,
+                tab_manager,
+                opened: Vec::new(),
+            }
+        }
+
+        pub fn tab_manager(&self) -> &TabManager {
+            &self.tab_manager
+        }
+
+        pub fn window_manager(&self) -> &MockWindowManager {
+            &self.window_manager
+        }
+
+        pub fn launcher(&self) -> &LauncherCore<BrowserSearchEngine, MockWindowManager> {
+            &self.launcher
+        }
+
+        /// 拡張機能が接続したことにする（WebSocket のハンドシェイクが終わったとき）
+        pub fn connect_extension(&self) {
+            self.tab_manager.extension_connected();
+        }
+
+        /// 拡張機能から届いたメッセージとして処理し、拡張機能に返す応答を返す
+        #[cfg(feature = "websocket")]
+        pub fn receive(&self, message: &str) -> Option<crate::websocket_types::WebSocketMessage> {
+            crate::websocket_server::handle_text(message, &self.tab_manager)
+        }
+
+        /// 拡張機能からのリクエスト（`{"type":"request",...}`）を作って処理する
+        #[cfg(feature = "websocket")]
+        pub fn request(&self, method: &str, params: serde_json::Value) -> Option<crate::websocket_types::WebSocketMessage> {
+            let message = crate::websocket_types::WebSocketMessage::request(format!("sim-{}", method), method.to_string(), Some(params));
+            self.receive(&serde_json::to_string(&message).unwrap())
+        }
+
+        pub fn set_windows(&mut self, windows: Vec<WindowInfo>) {
+            self.window_manager.set_windows(windows);
+        }
+
+        /// ランチャーを開いたときと同じく、ウィンドウ一覧を取り直してから検索する
+        pub fn search(&mut self, query: &str, mode: SearchMode) -> Vec<SearchResult> {
+            self.launcher.refresh_windows();
+            self.launcher.search(query, mode)
+        }
+
+        /// 結果を Enter で選んだときと同じように実行する
+        ///
+        /// 確認が必要な操作は実行せずに確認の内容を返す（`confirm` で実行する）。
+        pub fn select(&mut self, result: &SearchResult) -> Option<ConfirmableAction> {
+            if self.launcher.request_confirmation(&result.action) {
+                return self.launcher.pending_confirmation().cloned();
+            }
+            self.run(&result.action);
+            None
+        }
+
+        /// 確認待ちの操作を確認して実行する
+        pub fn confirm(&mut self) {
+            let Some(action) = self.launcher.pending_confirmation().map(|pending| pending.action.clone()) else {
+                return;
+            };
+            self.launcher.confirm();
+            if !self.launcher.request_confirmation(&action) {
+                self.run(&action);
+            }
+        }
+
+        fn run(&mut self, action: &Action) {
+            let rewritten = self.launcher.rewrite(action);
+            let action = rewritten.as_ref().unwrap_or(action);
+            let commands = commands_for(action, &self.tab_manager.get_tabs());
+            if !commands.is_empty() {
+                for command in commands {
+                    self.tab_manager.queue_command(command);
+                }
+            } else if let Action::SwitchWindow(_) = action {
+                self.launcher.execute_action(action);
+            } else {
+                self.opened.push(action.clone());
+            }
+        }
+
+        /// 開かずに記録した操作
+        pub fn opened(&self) -> &[Action] {
+            &self.opened
+        }
+
+        /// 拡張機能へ送るコマンドを取り出す（取り出したものはキューから消える）
+        pub fn take_commands(&self) -> Vec<ChromeCommand> {
+            std::iter::from_fn(|| self.tab_manager.pop_command()).collect()
+        }
+    }
+}
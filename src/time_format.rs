@@ -0,0 +1,170 @@
+//! 日時の表示（「3 days ago」などの相対表記）と Webkit timestamp の変換
+//!
+//! 表示言語は OS の UI 言語で決める（日本語以外は英語）。
+
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::OnceLock;
+
+/// 1601-01-01 と 1970-01-01 の差（秒）
+const WEBKIT_EPOCH_OFFSET_SECS: i64 = 11_644_473_600;
+
+/// 相対表記の言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// OS の UI 言語（初回に確認したものを使い続ける）
+    pub fn current() -> Self {
+        static LOCALE: OnceLock<Locale> = OnceLock::new();
+        *LOCALE.get_or_init(detect)
+    }
+}
+
+#[cfg(windows)]
+fn detect() -> Locale {
+    use winapi::um::winnls::GetUserDefaultUILanguage;
+
+    const LANG_JAPANESE: u16 = 0x11;
+    // 下位 10 ビットが主言語
+    let language = unsafe { GetUserDefaultUILanguage() };
+    if language & 0x3ff == LANG_JAPANESE {
+        Locale::Ja
+    } else {
+        Locale::En
+    }
+}
+
+#[cfg(not(windows))]
+fn detect() -> Locale {
+    let lang = std::env::var("LC_ALL").or_else(|_| std::env::var("LANG")).unwrap_or_default();
+    if lang.starts_with("ja") {
+        Locale::Ja
+    } else {
+        Locale::En
+    }
+}
+
+/// Webkit timestamp（1601-01-01 からのマイクロ秒、Chrome の履歴など）を日時に変換
+///
+/// 0 以下は Chrome が「不明」に使う値なので `None`。
+pub fn from_webkit(timestamp: i64) -> Option<DateTime<Utc>> {
+    if timestamp <= 0 {
+        return None;
+    }
+    Utc.timestamp_opt(timestamp / 1_000_000 - WEBKIT_EPOCH_OFFSET_SECS, 0).single()
+}
+
+/// 日時を Webkit timestamp に変換
+pub fn to_webkit(time: DateTime<Utc>) -> i64 {
+    (time.timestamp() + WEBKIT_EPOCH_OFFSET_SECS) * 1_000_000
+}
+
+/// 今からの相対表記（例: `3 days ago` / `3日前`、未来は `in 2 hours` / `2時間後`）
+pub fn relative(time: DateTime<Utc>) -> String {
+    relative_to(time, Utc::now(), Locale::current())
+}
+
+/// `now` からの相対表記
+pub fn relative_to(time: DateTime<Utc>, now: DateTime<Utc>, locale: Locale) -> String {
+    let secs = (now - time).num_seconds();
+    let future = secs < 0;
+    let (count, unit) = largest_unit(secs.unsigned_abs());
+    let Some(unit) = unit else {
+        return match locale {
+            Locale::En => "just now".to_string(),
+            Locale::Ja => "たった今".to_string(),
+        };
+    };
+
+    match locale {
+        Locale::En => {
+            let plural = if count == 1 { "" } else { "s" };
+            if future {
+                format!("in {} {}{}", count, unit.en, plural)
+            } else {
+                format!("{} {}{} ago", count, unit.en, plural)
+            }
+        }
+        Locale::Ja => format!("{}{}{}", count, unit.ja, if future { "後" } else { "前" }),
+    }
+}
+
+/// 列などの狭い場所向けの短い表記（例: `3d`、未来は `+3d`）
+pub fn short(time: DateTime<Utc>) -> String {
+    short_to(time, Utc::now())
+}
+
+pub fn short_to(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - time).num_seconds();
+    let sign = if secs < 0 { "+" } else { "" };
+    match largest_unit(secs.unsigned_abs()) {
+        (_, None) => "now".to_string(),
+        (count, Some(unit)) => format!("{}{}{}", sign, count, unit.short),
+    }
+}
+
+struct Unit {
+    en: &'static str,
+    ja: &'static str,
+    short: &'static str,
+}
+
+const UNITS: [(u64, Unit); 4] = [
+    (604_800, Unit { en: "week", ja: "週間", short: "w" }),
+    (86_400, Unit { en: "day", ja: "日", short: "d" }),
+    (3_600, Unit { en: "hour", ja: "時間", short: "h" }),
+    (60, Unit { en: "minute", ja: "分", short: "m" }),
+];
+
+/// 1 以上になる最も大きい単位での数（1 分未満は単位なし）
+fn largest_unit(secs: u64) -> (u64, Option<&'static Unit>) {
+    if secs < 60 {
+        return (0, None);
+    }
+    UNITS
+        .iter()
+        .find(|(size, _)| secs >= *size)
+        .map(|(size, unit)| (secs / size, Some(unit)))
+        .unwrap_or((0, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_webkit_round_trip() {
+        // 2024-05-01 12:00:00 UTC
+        let timestamp = 13_359_038_400_000_000;
+        assert_eq!(from_webkit(timestamp), Some(now()));
+        assert_eq!(to_webkit(now()), timestamp);
+        assert_eq!(from_webkit(0), None);
+    }
+
+    #[test]
+    fn test_relative_past_and_future() {
+        let ago = |secs: i64| relative_to(now() - Duration::seconds(secs), now(), Locale::En);
+        assert_eq!(ago(30), "just now");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(3 * 3600 + 59), "3 hours ago");
+        assert_eq!(ago(2 * 86_400), "2 days ago");
+        assert_eq!(ago(15 * 86_400), "2 weeks ago");
+        assert_eq!(ago(-3600), "in 1 hour");
+
+        let ja = |secs: i64| relative_to(now() - Duration::seconds(secs), now(), Locale::Ja);
+        assert_eq!(ja(5), "たった今");
+        assert_eq!(ja(3 * 86_400), "3日前");
+        assert_eq!(ja(-120), "2分後");
+
+        assert_eq!(short_to(now() - Duration::days(3), now()), "3d");
+        assert_eq!(short_to(now() + Duration::hours(2), now()), "+2h");
+    }
+}
@@ -0,0 +1,375 @@
+//! タイマーとリマインダー（`timer 25m 休憩` / `remind 15:30 会議`）
+//!
+//! 設定したタイマーは timers.json に保存し、スケジューラースレッドが期限を過ぎたものを通知する。
+//! ランチャーを閉じている間に期限を過ぎたタイマーは、次にスケジューラーが動いたときに通知する。
+
+use crate::notification::NotificationAction;
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// 期限を確認する間隔
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// timers.json の読み書きをまとめて行うためのロック（UI とスケジューラーのスレッドで共有する）
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// タイマーの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerKind {
+    /// 相対時間（`timer 25m`）
+    Timer,
+    /// 時刻指定（`remind 15:30`）
+    Reminder,
+}
+
+impl TimerKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimerKind::Timer => "Timer",
+            TimerKind::Reminder => "Reminder",
+        }
+    }
+}
+
+/// 設定中のタイマー
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timer {
+    pub id: u64,
+    pub kind: TimerKind,
+    pub label: String,
+    /// 期限（UNIX時刻・秒）
+    pub due: i64,
+}
+
+impl Timer {
+    pub fn due_local(&self) -> DateTime<Local> {
+        Local.timestamp_opt(self.due, 0).single().unwrap_or_else(Local::now)
+    }
+}
+
+/// `timer` / `remind` の入力を解析した結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerRequest {
+    pub kind: TimerKind,
+    pub due: DateTime<Local>,
+    pub label: String,
+}
+
+/// `25m`, `1h30m`, `90s`, `2h` のような時間を解析（単位を省略した場合は分）
+pub fn parse_duration(text: &str) -> Option<chrono::Duration> {
+    let text = text.trim().to_lowercase();
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Ok(minutes) = text.parse::<i64>() {
+        return chrono::Duration::try_minutes(minutes).filter(|duration| *duration > chrono::Duration::zero());
+    }
+
+    let mut total = chrono::Duration::zero();
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let value: i64 = number.parse().ok()?;
+        number.clear();
+        // 大きすぎる値は chrono の範囲を超えるので不正として扱う
+        let part = match c {
+            'h' => chrono::Duration::try_hours(value)?,
+            'm' => chrono::Duration::try_minutes(value)?,
+            's' => chrono::Duration::try_seconds(value)?,
+            _ => return None,
+        };
+        total = total.checked_add(&part)?;
+    }
+
+    // 末尾に単位のない数字が残っていたら不正
+    if !number.is_empty() || total <= chrono::Duration::zero() {
+        return None;
+    }
+    Some(total)
+}
+
+/// `15:30` を `now` 以降で最も近いその時刻に変換（過ぎていれば翌日）
+pub fn parse_clock_time(text: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let time = NaiveTime::parse_from_str(text.trim(), "%H:%M").ok()?;
+    let today = now.date_naive().and_time(time).and_local_timezone(Local).earliest()?;
+    if today > now {
+        Some(today)
+    } else {
+        (now.date_naive() + chrono::Duration::days(1))
+            .and_time(time)
+            .and_local_timezone(Local)
+            .earliest()
+    }
+}
+
+/// キーワードの引数（`25m stand up` / `15:30 meeting`）を解析
+pub fn parse_timer_request(kind: TimerKind, args: &str, now: DateTime<Local>) -> Option<TimerRequest> {
+    let args = args.trim();
+    let (when, label) = args.split_once(' ').unwrap_or((args, ""));
+
+    let due = match kind {
+        TimerKind::Timer => now.checked_add_signed(parse_duration(when)?)?,
+        TimerKind::Reminder => parse_clock_time(when, now)?,
+    };
+
+    let label = label.trim();
+    Some(TimerRequest {
+        kind,
+        due,
+        label: if label.is_empty() { kind.label().to_string() } else { label.to_string() },
+    })
+}
+
+/// 残り時間を表示用に整形（例: `1h 05m left`）
+pub fn format_remaining(timer: &Timer, now: DateTime<Local>) -> String {
+    let seconds = timer.due - now.timestamp();
+    if seconds <= 0 {
+        return "due now".to_string();
+    }
+
+    let (hours, minutes, seconds) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {:02}m left", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s left", minutes, seconds)
+    } else {
+        format!("{}s left", seconds)
+    }
+}
+
+/// タイマーの保存先（ランチャーとスケジューラーはこのファイルを介してタイマーを共有する）
+#[derive(Debug, Clone)]
+pub struct TimerStore {
+    path: PathBuf,
+}
+
+impl TimerStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 設定中のタイマーを期限の早い順に返す
+    pub fn load(&self) -> Result<Vec<Timer>, Box<dyn Error>> {
+        let _lock = lock();
+        self.read()
+    }
+
+    fn read(&self) -> Result<Vec<Timer>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        let mut timers: Vec<Timer> = serde_json::from_str(&content)?;
+        timers.sort_by_key(|timer| timer.due);
+        Ok(timers)
+    }
+
+    fn save(&self, timers: &[Timer]) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // 書き込み途中のファイルを読まないよう、一時ファイルに書いてから置き換える
+        let temp = self.path.with_extension("json.tmp");
+        fs::write(&temp, serde_json::to_string_pretty(timers)?)?;
+        fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+
+    /// タイマーを追加
+    pub fn add(&self, request: &TimerRequest) -> Result<Timer, Box<dyn Error>> {
+        let _lock = lock();
+        let mut timers = self.read()?;
+        let timer = Timer {
+            id: timers.iter().map(|t| t.id).max().unwrap_or(0) + 1,
+            kind: request.kind,
+            label: request.label.clone(),
+            due: request.due.timestamp(),
+        };
+        timers.push(timer.clone());
+        self.save(&timers)?;
+        Ok(timer)
+    }
+
+    /// タイマーを取り消す（見つからなければ `false`）
+    pub fn cancel(&self, id: u64) -> Result<bool, Box<dyn Error>> {
+        let _lock = lock();
+        let mut timers = self.read()?;
+        let before = timers.len();
+        timers.retain(|timer| timer.id != id);
+        if timers.len() == before {
+            return Ok(false);
+        }
+        self.save(&timers)?;
+        Ok(true)
+    }
+
+    /// 期限を過ぎたタイマーを取り出して削除
+    pub fn take_due(&self, now: DateTime<Local>) -> Result<Vec<Timer>, Box<dyn Error>> {
+        let _lock = lock();
+        let timers = self.read()?;
+        let (due, pending): (Vec<Timer>, Vec<Timer>) =
+            timers.into_iter().partition(|timer| timer.due <= now.timestamp());
+        if !due.is_empty() {
+            self.save(&pending)?;
+        }
+        Ok(due)
+    }
+}
+
+fn lock() -> MutexGuard<'static, ()> {
+    STORE_LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Default for TimerStore {
+    fn default() -> Self {
+        Self::new(crate::paths::timers_path())
+    }
+}
+
+/// 期限を過ぎたタイマーを通知するスレッドを起動
+pub fn spawn_scheduler(store: TimerStore) -> std::io::Result<thread::JoinHandle<()>> {
+    thread::Builder::new().name("timer-scheduler".to_string()).spawn(move || loop {
+        match store.take_due(Local::now()) {
+            Ok(due) => {
+                for timer in due {
+                    log::info!("{} fired: {}", timer.kind.label(), timer.label);
+                    // 見逃してもアクションセンターに残るよう、OSD ではなく Windows の通知で知らせる
+                    crate::notification::show_notification_with_actions(
+                        timer.kind.label(),
+                        &format!("{} ({})", timer.label, timer.due_local().format("%H:%M")),
+                        &[NotificationAction::ReopenLauncher],
+                    );
+                }
+            }
+            Err(e) => log::error!("Failed to check timers in {}: {}", store.path().display(), e),
+        }
+        thread::sleep(SCHEDULER_INTERVAL);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 1, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("25m"), Some(chrono::Duration::minutes(25)));
+        assert_eq!(parse_duration("1h30m"), Some(chrono::Duration::minutes(90)));
+        assert_eq!(parse_duration("90s"), Some(chrono::Duration::seconds(90)));
+        assert_eq!(parse_duration("10"), Some(chrono::Duration::minutes(10)));
+        assert_eq!(parse_duration("1h30"), None);
+        assert_eq!(parse_duration("soon"), None);
+        assert_eq!(parse_duration("0m"), None);
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_negative_and_huge_values() {
+        assert_eq!(parse_duration("-5"), None);
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("999999999999999999"), None);
+        assert_eq!(parse_duration("99999999999999999h"), None);
+        assert_eq!(parse_duration("9000000000000000s9000000000000000s"), None);
+
+        // 期間としては表せても、期限の日時が範囲を超えるものは作らない
+        assert_eq!(parse_timer_request(TimerKind::Timer, "999999999999", at(9, 0)), None);
+        assert_eq!(parse_timer_request(TimerKind::Timer, "-5 tea", at(9, 0)), None);
+    }
+
+    #[test]
+    fn test_parse_clock_time_rolls_over_to_tomorrow() {
+        assert_eq!(parse_clock_time("15:30", at(9, 0)), Some(at(15, 30)));
+        let tomorrow = parse_clock_time("08:00", at(9, 0)).unwrap();
+        assert_eq!(tomorrow, at(8, 0) + chrono::Duration::days(1));
+        assert_eq!(parse_clock_time("25:00", at(9, 0)), None);
+    }
+
+    #[test]
+    fn test_parse_timer_request() {
+        let request = parse_timer_request(TimerKind::Timer, "25m stand up", at(9, 0)).unwrap();
+        assert_eq!(request.due, at(9, 25));
+        assert_eq!(request.label, "stand up");
+
+        let request = parse_timer_request(TimerKind::Reminder, "15:30", at(9, 0)).unwrap();
+        assert_eq!(request.due, at(15, 30));
+        assert_eq!(request.label, "Reminder");
+
+        assert_eq!(parse_timer_request(TimerKind::Timer, "", at(9, 0)), None);
+    }
+
+    #[test]
+    fn test_store_add_cancel_and_take_due() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TimerStore::new(dir.path().join("timers.json"));
+
+        let first = store.add(&parse_timer_request(TimerKind::Timer, "10m tea", at(9, 0)).unwrap()).unwrap();
+        let second = store.add(&parse_timer_request(TimerKind::Reminder, "09:05 call", at(9, 0)).unwrap()).unwrap();
+        let third = store.add(&parse_timer_request(TimerKind::Timer, "1h lunch", at(9, 0)).unwrap()).unwrap();
+        assert_eq!((first.id, second.id, third.id), (1, 2, 3));
+
+        // 期限の早い順に並ぶ
+        let labels: Vec<String> = store.load().unwrap().into_iter().map(|t| t.label).collect();
+        assert_eq!(labels, vec!["call", "tea", "lunch"]);
+
+        assert!(store.cancel(third.id).unwrap());
+        assert!(!store.cancel(third.id).unwrap());
+
+        let due = store.take_due(at(9, 10)).unwrap();
+        assert_eq!(due.len(), 2);
+        assert!(store.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_store_keeps_timers_added_from_other_threads() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TimerStore::new(dir.path().join("timers.json"));
+        let request = parse_timer_request(TimerKind::Timer, "10m tea", at(9, 0)).unwrap();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let (store, request) = (store.clone(), request.clone());
+                thread::spawn(move || {
+                    for _ in 0..10 {
+                        store.add(&request).unwrap();
+                        store.take_due(at(9, 0)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let ids: std::collections::HashSet<u64> = store.load().unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(ids.len(), 40);
+        assert!(!dir.path().join("timers.json.tmp").exists());
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        let timer = Timer { id: 1, kind: TimerKind::Timer, label: "tea".to_string(), due: at(10, 5).timestamp() };
+        assert_eq!(format_remaining(&timer, at(9, 0)), "1h 05m left");
+        assert_eq!(format_remaining(&timer, at(10, 0)), "5m 00s left");
+        assert_eq!(format_remaining(&timer, at(10, 5)), "due now");
+    }
+}
@@ -0,0 +1,184 @@
+//! 画面の隅に出す短い通知（OSD）
+//!
+//! ホットキーで実行した操作の結果、操作の失敗、タイマーの完了を、ランチャーのウィンドウを開かずに知らせる。
+//! 描画はデーモンのトースト用ウィンドウ（`ui::toast_window`）が担当し、それが動いていないプロセスでは
+//! デスクトップ通知（`notification.rs`）で代わりに表示する。
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 同時に表示するトーストの数（残りは順番を待つ）
+pub const MAX_VISIBLE: usize = 3;
+
+/// 表示してから消えるまでの時間
+const INFO_DURATION: Duration = Duration::from_secs(4);
+const ERROR_DURATION: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Error,
+}
+
+impl ToastLevel {
+    fn duration(&self) -> Duration {
+        match self {
+            ToastLevel::Info => INFO_DURATION,
+            ToastLevel::Error => ERROR_DURATION,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub id: u64,
+    pub level: ToastLevel,
+    pub title: String,
+    /// クリックで開く詳細
+    pub detail: Option<String>,
+    /// 詳細を開いている間は消さない
+    pub expanded: bool,
+    /// 表示を始めた時刻（順番待ちの間は `None`）
+    shown_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    next_id: u64,
+    toasts: VecDeque<Toast>,
+    /// トーストを描画するウィンドウに再描画を頼む（登録されていなければデスクトップ通知で表示する）
+    waker: Option<Box<dyn Fn() + Send + Sync>>,
+}
+
+/// 表示待ち・表示中のトースト
+#[derive(Clone, Default)]
+pub struct ToastQueue {
+    state: Arc<Mutex<QueueState>>,
+}
+
+impl ToastQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// トーストを追加して、描画するウィンドウを起こす
+    pub fn push(&self, level: ToastLevel, title: &str, detail: Option<&str>) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        let id = state.next_id;
+        state.toasts.push_back(Toast {
+            id,
+            level,
+            title: title.to_string(),
+            detail: detail.map(str::to_string),
+            expanded: false,
+            shown_at: None,
+        });
+        if let Some(waker) = &state.waker {
+            waker();
+        }
+        id
+    }
+
+    /// 表示するトースト（古い順に `MAX_VISIBLE` 件）。表示時間は初めてここで返したときから数える
+    pub fn visible(&self, now: Instant) -> Vec<Toast> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .toasts
+            .iter_mut()
+            .take(MAX_VISIBLE)
+            .map(|toast| {
+                toast.shown_at.get_or_insert(now);
+                toast.clone()
+            })
+            .collect()
+    }
+
+    /// 表示時間を過ぎたトーストを取り除き、次に取り除くまでの時間を返す（消えるものがなければ `None`）
+    pub fn expire(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let remaining = |toast: &Toast| {
+            let shown_at = toast.shown_at.filter(|_| !toast.expanded)?;
+            Some(toast.level.duration().saturating_sub(now.saturating_duration_since(shown_at)))
+        };
+        state.toasts.retain(|toast| remaining(toast) != Some(Duration::ZERO));
+        state.toasts.iter().filter_map(remaining).min()
+    }
+
+    pub fn dismiss(&self, id: u64) {
+        self.state.lock().unwrap().toasts.retain(|toast| toast.id != id);
+    }
+
+    /// 詳細の表示を切り替える（閉じたら改めて表示時間を数える）
+    pub fn toggle_expanded(&self, id: u64, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(toast) = state.toasts.iter_mut().find(|toast| toast.id == id) {
+            toast.expanded = !toast.expanded;
+            toast.shown_at = Some(now);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.lock().unwrap().toasts.is_empty()
+    }
+
+    /// トーストを描画するウィンドウを登録（`waker` はトーストが追加されたときに呼ばれる）
+    pub fn attach_host(&self, waker: impl Fn() + Send + Sync + 'static) {
+        self.state.lock().unwrap().waker = Some(Box::new(waker));
+    }
+
+    pub fn has_host(&self) -> bool {
+        self.state.lock().unwrap().waker.is_some()
+    }
+}
+
+/// プロセス全体で共有するキュー
+pub fn global() -> &'static ToastQueue {
+    static QUEUE: OnceLock<ToastQueue> = OnceLock::new();
+    QUEUE.get_or_init(ToastQueue::new)
+}
+
+/// トーストを表示する（描画するウィンドウがないプロセスではデスクトップ通知）
+pub fn notify(level: ToastLevel, title: &str, detail: Option<&str>) {
+    let queue = global();
+    if queue.has_host() {
+        queue.push(level, title, detail);
+    } else {
+        crate::notification::show_notification(title, detail.unwrap_or_default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_and_auto_dismiss() {
+        let queue = ToastQueue::new();
+        let start = Instant::now();
+        for i in 0..4 {
+            queue.push(ToastLevel::Info, &format!("Toast {}", i), None);
+        }
+        let error = queue.push(ToastLevel::Error, "Failed", Some("details"));
+
+        // 表示は3件まで、残りは順番待ち
+        let visible = queue.visible(start);
+        assert_eq!(visible.len(), MAX_VISIBLE);
+        assert_eq!(visible[0].title, "Toast 0");
+        assert_eq!(queue.expire(start), Some(INFO_DURATION));
+
+        // 詳細を開いたトーストは時間が過ぎても残る
+        queue.toggle_expanded(visible[0].id, start);
+        let later = start + INFO_DURATION;
+        assert_eq!(queue.expire(later), None);
+        let visible = queue.visible(later);
+        assert_eq!(visible.iter().map(|t| t.title.as_str()).collect::<Vec<_>>(), vec!["Toast 0", "Toast 3", "Failed"]);
+
+        queue.dismiss(visible[0].id);
+        assert_eq!(queue.expire(later + Duration::from_secs(1)), Some(Duration::from_secs(3)));
+        assert_eq!(queue.expire(later + ERROR_DURATION), None);
+        assert!(queue.visible(later + ERROR_DURATION).iter().all(|t| t.id != error));
+        assert!(queue.is_empty());
+    }
+}
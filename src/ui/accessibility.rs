@@ -0,0 +1,30 @@
+use egui;
+use crate::core::ResultType;
+
+/// 結果の種類の読み上げ名
+pub fn type_name(result_type: &ResultType) -> &'static str {
+    match result_type {
+        ResultType::GoogleSearch => "web search",
+        ResultType::Bookmark => "bookmark",
+        ResultType::History => "history",
+        ResultType::Window => "window",
+        ResultType::Tab => "tab",
+        ResultType::PinnedTab => "pinned tab",
+        ResultType::ReadingList => "reading list",
+        ResultType::Update => "update",
+        ResultType::Command => "command",
+        ResultType::Note => "note",
+        ResultType::Timer => "timer",
+        ResultType::Color { .. } => "color",
+    }
+}
+
+/// スクリーンリーダーに渡す結果の名前（タイトル、種類、何件中の何件目か）
+pub fn item_label(title: &str, kind: &str, index: usize, count: usize) -> String {
+    format!("{}, {}, {} of {}", title, kind, index + 1, count)
+}
+
+/// 描画だけで作った行やタイルに、読み上げ用の名前と選択状態を付ける（AccessKit）
+pub fn describe(response: &egui::Response, label: String, selected: bool) {
+    response.widget_info(|| egui::WidgetInfo::selected(egui::WidgetType::SelectableLabel, selected, label.clone()));
+}
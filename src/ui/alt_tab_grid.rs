@@ -1,5 +1,7 @@
 use egui::{Vec2, Pos2, Rect, Color32, Stroke, Rounding, Sense};
 use crate::ThumbnailCache;
+use crate::config::GridConfig;
+use super::accessibility;
 
 /// Alt+Tab風のグリッド表示のためのUIコンポーネント
 pub struct AltTabGrid {
@@ -11,6 +13,14 @@ pub struct AltTabGrid {
     pub item_size: Vec2,
     /// アイテム間のスペース
     pub spacing: f32,
+    /// 表示サイズに対するサムネイルのキャプチャ解像度の倍率
+    pub thumbnail_scale: f32,
+    /// タイトルを省略するまでの文字数
+    pub title_length: usize,
+    /// ドラッグ中のアイテムのインデックス
+    dragging: Option<usize>,
+    /// このフレームでドラッグを離したか
+    drag_released: bool,
 }
 
 /// グリッドに表示するアイテムのインターフェース
@@ -19,22 +29,54 @@ pub trait GridItem {
     fn title(&self) -> &str;
     /// アイテムの説明（プロセス名など）
     fn description(&self) -> &str;
+    /// 説明に続けて表示する補足（ウィンドウクラスなど）
+    fn detail(&self) -> Option<&str> {
+        None
+    }
     /// ウィンドウハンドル（サムネイル取得用）
     fn hwnd(&self) -> isize;
     /// アイテムの一意な識別子
     fn id(&self) -> String;
+    /// タイル右上に表示するバッジ（管理者権限が必要なウィンドウなど）
+    fn badge(&self) -> Option<&str> {
+        None
+    }
+    /// サムネイルの左下に重ねるアイコン（ブラウザのウィンドウのアクティブなタブのファビコンなど）
+    fn icon(&self) -> Option<&egui::TextureHandle> {
+        None
+    }
 }
 
 impl AltTabGrid {
     pub fn new() -> Self {
+        Self::with_config(&GridConfig::default())
+    }
+
+    /// 設定の値（範囲内に丸めたもの）でグリッドを作る
+    pub fn with_config(config: &GridConfig) -> Self {
+        let config = config.validated();
         Self {
             selected_index: 0,
-            columns: 5,
-            item_size: Vec2::new(200.0, 150.0),
-            spacing: 10.0,
+            columns: config.columns,
+            item_size: Vec2::new(config.item_width, config.item_height),
+            spacing: config.spacing,
+            thumbnail_scale: config.thumbnail_scale,
+            title_length: config.title_length,
+            dragging: None,
+            drag_released: false,
         }
     }
 
+    /// ドラッグ中のアイテム（離したフレームまで返す）
+    pub fn dragged(&self) -> Option<usize> {
+        self.dragging
+    }
+
+    /// このフレームでドラッグを離したか
+    pub fn drag_released(&self) -> bool {
+        self.drag_released
+    }
+
     /// グリッドを表示し、クリックされたアイテムのインデックスを返す
     pub fn show<T: GridItem>(
         &mut self,
@@ -43,6 +85,11 @@ impl AltTabGrid {
         items: &[T],
         thumbnail_cache: &mut ThumbnailCache,
     ) -> Option<usize> {
+        // 前のフレームで離したドラッグを終える
+        if self.drag_released {
+            self.dragging = None;
+            self.drag_released = false;
+        }
         if items.is_empty() {
             return None;
         }
@@ -116,8 +163,8 @@ impl AltTabGrid {
             // サムネイルまたはプレースホルダーを描画
             // 高解像度でキャプチャして、表示時にスケールダウン
             let capture_size = (
-                (thumbnail_rect.width() * 2.0) as u32,
-                (thumbnail_rect.height() * 2.0) as u32,
+                (thumbnail_rect.width() * self.thumbnail_scale) as u32,
+                (thumbnail_rect.height() * self.thumbnail_scale) as u32,
             );
             if let Some(texture) = thumbnail_cache.get_or_create_thumbnail(
                 ctx,
@@ -134,13 +181,39 @@ impl AltTabGrid {
                 self.draw_placeholder(ui, thumbnail_rect);
             }
 
+            // アイコンを描画
+            if let Some(icon) = item.icon() {
+                let icon_rect = Rect::from_min_size(
+                    Pos2::new(thumbnail_rect.min.x + 4.0, thumbnail_rect.max.y - 24.0),
+                    Vec2::splat(20.0),
+                );
+                ui.painter().rect_filled(icon_rect.expand(2.0), Rounding::same(4.0), Color32::from_rgb(240, 240, 240));
+                ui.put(icon_rect, egui::Image::from_texture(icon).fit_to_exact_size(icon_rect.size()));
+            }
+
+            // バッジを描画
+            if let Some(badge) = item.badge() {
+                let badge_pos = Pos2::new(thumbnail_rect.max.x - 4.0, thumbnail_rect.min.y + 4.0);
+                let galley = ui.painter().layout_no_wrap(
+                    badge.to_string(),
+                    egui::FontId::proportional(11.0),
+                    Color32::WHITE,
+                );
+                let badge_rect = Rect::from_min_size(
+                    Pos2::new(badge_pos.x - galley.size().x - 8.0, badge_pos.y),
+                    galley.size() + Vec2::new(8.0, 4.0),
+                );
+                ui.painter().rect_filled(badge_rect, Rounding::same(4.0), Color32::from_rgb(180, 120, 30));
+                ui.painter().galley(badge_rect.min + Vec2::new(4.0, 2.0), galley);
+            }
+
             // タイトルを描画
             let title_rect = Rect::from_min_size(
                 Pos2::new(inner_rect.min.x, inner_rect.max.y - 35.0),
                 Vec2::new(inner_rect.width(), 20.0),
             );
 
-            let title_text = self.truncate_text(item.title(), 25);
+            let title_text = self.truncate_text(item.title(), self.title_length);
             ui.painter().text(
                 title_rect.center(),
                 egui::Align2::CENTER_CENTER,
@@ -150,6 +223,10 @@ impl AltTabGrid {
             );
 
             // 説明を描画
+            let description = match item.detail() {
+                Some(detail) => format!("{} - {}", item.description(), detail),
+                None => item.description().to_string(),
+            };
             let desc_rect = Rect::from_min_size(
                 Pos2::new(inner_rect.min.x, inner_rect.max.y - 15.0),
                 Vec2::new(inner_rect.width(), 15.0),
@@ -158,27 +235,61 @@ impl AltTabGrid {
             ui.painter().text(
                 desc_rect.center(),
                 egui::Align2::CENTER_CENTER,
-                item.description(),
+                &description,
                 egui::FontId::proportional(11.0),
                 Color32::from_rgb(150, 150, 150),
             );
 
             // インタラクション処理
-            let response = ui.interact(item_rect, ui.id().with(index), Sense::click());
+            let response = ui.interact(item_rect, ui.id().with(index), Sense::click_and_drag());
+            let kind = match item.badge() {
+                Some(badge) => format!("window, {}, {}", description, badge),
+                None => format!("window, {}", description),
+            };
+            accessibility::describe(
+                &response,
+                accessibility::item_label(item.title(), &kind, index, items.len()),
+                is_selected,
+            );
+            // 省略したタイトルや説明はホバーで全文を表示
+            let response = response.on_hover_ui(|ui| {
+                ui.set_max_width(400.0);
+                ui.label(egui::RichText::new(item.title()).strong());
+                ui.label(&description);
+            });
 
             if response.clicked() {
                 clicked_index = Some(index);
             }
+            if response.drag_started() {
+                self.dragging = Some(index);
+            }
 
-            if response.hovered() {
+            // 選択が変わったときだけ再描画する（ホバー中に毎フレーム再描画し続けないように）
+            if response.hovered() && self.selected_index != index {
                 self.selected_index = index;
                 ui.ctx().request_repaint();
             }
         }
 
+        if let Some(index) = self.dragging.filter(|index| *index < items.len()) {
+            self.draw_drag_ghost(ui, items[index].title());
+            self.drag_released = ui.input(|i| i.pointer.any_released());
+        }
+
         clicked_index
     }
 
+    /// ドラッグ中のアイテムのタイトルをカーソルの横に描画
+    fn draw_drag_ghost(&self, ui: &egui::Ui, title: &str) {
+        let Some(pointer) = ui.ctx().pointer_interact_pos() else { return };
+        let painter = ui.ctx().layer_painter(egui::LayerId::new(egui::Order::Tooltip, egui::Id::new("grid_drag_ghost")));
+        let galley = painter.layout_no_wrap(self.truncate_text(title, 40), egui::FontId::proportional(12.0), Color32::WHITE);
+        let rect = Rect::from_min_size(pointer + Vec2::new(12.0, 12.0), galley.size() + Vec2::new(12.0, 8.0));
+        painter.rect_filled(rect, Rounding::same(4.0), Color32::from_rgba_premultiplied(60, 90, 160, 220));
+        painter.galley(rect.min + Vec2::new(6.0, 4.0), galley);
+    }
+
     /// キーボードナビゲーションを処理
     pub fn handle_keyboard_navigation(&mut self, ui: &egui::Ui, item_count: usize) {
         if item_count == 0 {
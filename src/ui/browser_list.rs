@@ -1,18 +1,76 @@
 use egui;
-use crate::core::search_engine::{SearchResult, ResultType};
+use crate::core::search_engine::{SearchResult, ResultType, ResultMetadata};
+use crate::config::{ColumnConfig, ListColumn};
+use crate::time_format;
+use super::{accessibility, result_details};
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// 見出しでまとめた結果の範囲（見出しのない結果は `title` が `None`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultSection {
+    pub title: Option<String>,
+    pub range: Range<usize>,
+}
+
+/// 結果の種類のアイコン（一覧とヒントバーの凡例で使う）
+pub fn type_icon(result_type: &ResultType) -> &'static str {
+    match result_type {
+        ResultType::GoogleSearch => "🔍",
+        ResultType::Bookmark => "⭐",
+        ResultType::History => "🕒",
+        ResultType::Window => "🪟",
+        ResultType::Tab => "📑",
+        ResultType::PinnedTab => "📌",
+        ResultType::ReadingList => "📖",
+        ResultType::Update => "⬆",
+        ResultType::Command => "⚙",
+        ResultType::Note => "📝",
+        ResultType::Timer => "⏱",
+        ResultType::Color { .. } => "🎨",
+    }
+}
+
+/// 連続する同じグループの結果をセクションに分ける
+pub fn result_sections(results: &[SearchResult]) -> Vec<ResultSection> {
+    let mut sections: Vec<ResultSection> = Vec::new();
+    for (index, result) in results.iter().enumerate() {
+        match sections.last_mut() {
+            Some(section) if section.title == result.group => section.range.end = index + 1,
+            _ => sections.push(ResultSection {
+                title: result.group.clone(),
+                range: index..index + 1,
+            }),
+        }
+    }
+    sections
+}
 
 pub struct BrowserList {
     pub selected_index: usize,
     visible_items: usize,
     items_per_batch: usize,
+    /// 折りたたんだ見出し（検索し直しても保つ）
+    collapsed_sections: HashSet<String>,
+    /// タイトルの右に並べる列
+    columns: Vec<ColumnConfig>,
 }
 
+/// 行の高さ（列の位置をそろえるため固定）
+const ROW_HEIGHT: f32 = 30.0;
+
 impl BrowserList {
     pub fn new() -> Self {
+        Self::with_columns(Vec::new())
+    }
+
+    pub fn with_columns(columns: Vec<ColumnConfig>) -> Self {
         Self {
             selected_index: 0,
             visible_items: 20,  // 初期表示数
             items_per_batch: 10, // スクロール時の追加表示数
+            collapsed_sections: HashSet::new(),
+            columns,
         }
     }
 
@@ -22,6 +80,7 @@ impl BrowserList {
         results: &[SearchResult],
     ) -> Option<usize> {
         let mut clicked_index = None;
+        self.ensure_visible_selection(results);
         
         // 選択されたアイテムが表示範囲に近い場合、表示数を増やす
         if self.selected_index + 5 >= self.visible_items && self.visible_items < results.len() {
@@ -31,113 +90,322 @@ impl BrowserList {
         // 表示する結果を制限
         let display_results = &results[..self.visible_items.min(results.len())];
 
+        let sections = result_sections(results);
+        let mut toggled_section = None;
+
+        if results.iter().any(|result| result.metadata.is_some()) {
+            self.render_column_header(ui);
+        }
+
         egui::ScrollArea::vertical().show(ui, |ui| {
-            for (index, result) in display_results.iter().enumerate() {
-                let is_selected = index == self.selected_index;
+            for section in &sections {
+                if section.range.start >= display_results.len() {
+                    break;
+                }
                 
-                ui.horizontal(|ui| {
-                    // 選択状態の表示
-                    if is_selected {
-                        ui.label("▶");
-                    } else {
-                        ui.label(" ");
+                if let Some(title) = &section.title {
+                    let collapsed = self.collapsed_sections.contains(title);
+                    if Self::render_section_header(ui, title, section.range.len(), collapsed) {
+                        toggled_section = Some(title.clone());
                     }
-                    
-                    // 結果タイプに応じたアイコン
-                    let icon = match &result.result_type {
-                        ResultType::GoogleSearch => "🔍",
-                        ResultType::Bookmark => "⭐",
-                        ResultType::History => "🕒",
-                        ResultType::Window => "🪟",
-                        ResultType::Tab => "📑",
-                    };
-                    ui.label(icon);
-                    
-                    // タイトルと説明を縦に並べて表示
-                    ui.vertical(|ui| {
-                        // 結果タイプに応じた背景色
-                        let bg_color = match &result.result_type {
-                            ResultType::GoogleSearch => egui::Color32::from_rgb(40, 50, 60),  // 青っぽい
-                            ResultType::Bookmark => egui::Color32::from_rgb(60, 50, 40),      // 黄色っぽい
-                            ResultType::History => egui::Color32::from_rgb(50, 40, 50),       // 紫っぽい
-                            ResultType::Window => egui::Color32::from_rgb(40, 40, 40),        // グレー
-                            ResultType::Tab => egui::Color32::from_rgb(40, 60, 40),          // 緑っぽい
-                        };
-                        
-                        let selected_bg_color = match &result.result_type {
-                            ResultType::GoogleSearch => egui::Color32::from_rgb(50, 70, 90),
-                            ResultType::Bookmark => egui::Color32::from_rgb(90, 70, 50),
-                            ResultType::History => egui::Color32::from_rgb(70, 50, 70),
-                            ResultType::Window => egui::Color32::from_rgb(60, 60, 60),
-                            ResultType::Tab => egui::Color32::from_rgb(50, 80, 50),
-                        };
-                        
-                        let response = ui.add(
-                            egui::Button::new(&result.title)
-                                .fill(if is_selected { selected_bg_color } else { bg_color })
-                                .min_size(egui::Vec2::new(ui.available_width() - 20.0, 30.0))
-                        );
-                        
-                        if response.clicked() {
-                            clicked_index = Some(index);
-                        }
-                        
-                        if is_selected {
-                            response.scroll_to_me(Some(egui::Align::Center));
-                        }
-                        
-                        // 説明文を表示（履歴の場合はURLをトリミング）
-                        let description = if matches!(result.result_type, ResultType::History) {
-                            Self::trim_url_for_display(&result.description, 50)
-                        } else {
-                            result.description.clone()
-                        };
-                        
-                        ui.label(
-                            egui::RichText::new(&description)
-                                .small()
-                                .color(egui::Color32::from_gray(180))
-                        );
-                    });
-                });
+                    if collapsed {
+                        continue;
+                    }
+                }
                 
-                ui.add_space(5.0);
+                let end = section.range.end.min(display_results.len());
+                for (index, result) in display_results.iter().enumerate().take(end).skip(section.range.start) {
+                    if self.render_item(ui, index, results.len(), result) {
+                        clicked_index = Some(index);
+                    }
+                }
             }
         });
+        
+        if let Some(title) = toggled_section {
+            if !self.collapsed_sections.remove(&title) {
+                self.collapsed_sections.insert(title);
+            }
+            self.ensure_visible_selection(results);
+        }
 
         clicked_index
     }
 
-    fn trim_url_for_display(url: &str, max_query_length: usize) -> String {
-        // URLとvisit count情報を分離
-        let (url_part, visit_info) = if let Some(pos) = url.rfind(" (visited") {
-            (&url[..pos], &url[pos..])
-        } else {
-            (url, "")
+    /// 列の見出し（行と同じ幅で右端にそろえる）
+    fn render_column_header(&self, ui: &mut egui::Ui) {
+        if self.columns.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() - self.columns_width(ui) - 20.0);
+            for column in &self.columns {
+                let name = match column.column {
+                    ListColumn::Domain => "Domain",
+                    ListColumn::Url => "URL",
+                    ListColumn::VisitCount => "Visits",
+                    ListColumn::LastVisit => "Last visit",
+                };
+                Self::render_cell(ui, column, egui::RichText::new(name).small().strong());
+            }
+        });
+    }
+
+    /// 列の合計の幅（間隔を含む）
+    fn columns_width(&self, ui: &egui::Ui) -> f32 {
+        let spacing = ui.spacing().item_spacing.x;
+        self.columns.iter().map(|column| column.width + spacing).sum()
+    }
+
+    /// 1つの列の中身（幅を固定し、はみ出す文字は省略）
+    fn render_cell(ui: &mut egui::Ui, column: &ColumnConfig, text: egui::RichText) {
+        let layout = match column.column {
+            ListColumn::VisitCount => egui::Layout::right_to_left(egui::Align::Center),
+            _ => egui::Layout::left_to_right(egui::Align::Center),
         };
+        ui.allocate_ui_with_layout(egui::vec2(column.width, ROW_HEIGHT), layout, |ui| {
+            ui.set_width(column.width);
+            ui.add(egui::Label::new(text).truncate(true));
+        });
+    }
 
+    /// 結果の情報を列の形式の文字列にする
+    fn cell_text(column: ListColumn, metadata: &ResultMetadata) -> String {
+        match column {
+            ListColumn::Domain => metadata.domain().to_string(),
+            ListColumn::Url => Self::trim_url_for_display(metadata.url(), 50),
+            // 最近閉じたタブは訪問回数を持たない（0）ので空にする
+            ListColumn::VisitCount => metadata.visit_count().filter(|count| *count > 0).map(|count| count.to_string()).unwrap_or_default(),
+            ListColumn::LastVisit => metadata.last_visit().map(time_format::short).unwrap_or_default(),
+        }
+    }
+
+    /// プロファイルなどの見出し（クリックで折りたたむ）。クリックされたら true を返す
+    fn render_section_header(ui: &mut egui::Ui, title: &str, count: usize, collapsed: bool) -> bool {
+        let arrow = if collapsed { "▶" } else { "▼" };
+        let response = ui.add(
+            egui::Button::new(
+                egui::RichText::new(format!("{} {} ({})", arrow, title, count))
+                    .strong()
+                    .color(egui::Color32::from_gray(200)),
+            )
+            .frame(false),
+        );
+        let state = if collapsed { "collapsed" } else { "expanded" };
+        response.widget_info(|| {
+            egui::WidgetInfo::labeled(egui::WidgetType::CollapsingHeader, format!("{}, {} results, {}", title, count, state))
+        });
+        ui.add_space(2.0);
+        response.clicked()
+    }
+
+    /// 1件分の描画。クリックされたら true を返す
+    fn render_item(&self, ui: &mut egui::Ui, index: usize, count: usize, result: &SearchResult) -> bool {
+        let mut clicked = false;
+        let is_selected = index == self.selected_index;
+        
+        ui.horizontal(|ui| {
+            // 選択状態の表示
+            if is_selected {
+                ui.label("▶");
+            } else {
+                ui.label(" ");
+            }
+            
+            // 結果タイプに応じたアイコン
+            ui.label(type_icon(&result.result_type));
+            Self::render_inline_visual(ui, result);
+            
+            // タイトルと説明を縦に並べて表示
+            ui.vertical(|ui| {
+                // 結果タイプに応じた背景色
+                let bg_color = match &result.result_type {
+                    ResultType::GoogleSearch => egui::Color32::from_rgb(40, 50, 60),  // 青っぽい
+                    ResultType::Bookmark => egui::Color32::from_rgb(60, 50, 40),      // 黄色っぽい
+                    ResultType::History => egui::Color32::from_rgb(50, 40, 50),       // 紫っぽい
+                    ResultType::Window => egui::Color32::from_rgb(40, 40, 40),        // グレー
+                    ResultType::Tab => egui::Color32::from_rgb(40, 60, 40),          // 緑っぽい
+                    ResultType::PinnedTab => egui::Color32::from_rgb(35, 65, 55),    // 濃い緑
+                    ResultType::ReadingList => egui::Color32::from_rgb(45, 45, 65),  // 藍っぽい
+                    ResultType::Update => egui::Color32::from_rgb(60, 40, 40),       // 赤っぽい
+                    ResultType::Command => egui::Color32::from_rgb(40, 50, 50),      // 青緑っぽい
+                    ResultType::Note => egui::Color32::from_rgb(55, 55, 40),         // 黄緑っぽい
+                    ResultType::Timer => egui::Color32::from_rgb(60, 50, 30),        // 橙っぽい
+                    ResultType::Color { .. } => egui::Color32::from_rgb(45, 45, 45),  // グレー
+                };
+                
+                let selected_bg_color = match &result.result_type {
+                    ResultType::GoogleSearch => egui::Color32::from_rgb(50, 70, 90),
+                    ResultType::Bookmark => egui::Color32::from_rgb(90, 70, 50),
+                    ResultType::History => egui::Color32::from_rgb(70, 50, 70),
+                    ResultType::Window => egui::Color32::from_rgb(60, 60, 60),
+                    ResultType::Tab => egui::Color32::from_rgb(50, 80, 50),
+                    ResultType::PinnedTab => egui::Color32::from_rgb(45, 90, 75),
+                    ResultType::ReadingList => egui::Color32::from_rgb(60, 60, 95),
+                    ResultType::Update => egui::Color32::from_rgb(90, 50, 50),
+                    ResultType::Command => egui::Color32::from_rgb(50, 80, 80),
+                    ResultType::Note => egui::Color32::from_rgb(85, 85, 50),
+                    ResultType::Timer => egui::Color32::from_rgb(95, 75, 40),
+                    ResultType::Color { .. } => egui::Color32::from_rgb(70, 70, 70),
+                };
+                
+                // 更新の止まったタブはグレーで表示する（切り替えに失敗するかもしれない）
+                let stale = result.metadata.as_ref().is_some_and(ResultMetadata::is_stale);
+                let (bg_color, selected_bg_color) = if stale {
+                    (egui::Color32::from_gray(35), egui::Color32::from_gray(55))
+                } else {
+                    (bg_color, selected_bg_color)
+                };
+                let title = if stale {
+                    egui::RichText::new(&result.title).color(egui::Color32::from_gray(130))
+                } else {
+                    egui::RichText::new(&result.title)
+                };
+                
+                // タイトルのあとに列を並べる（列の幅を除いた残りがタイトル）
+                let columns = if result.metadata.is_some() { self.columns.as_slice() } else { &[] };
+                let title_width = ui.available_width() - 20.0 - if columns.is_empty() { 0.0 } else { self.columns_width(ui) };
+                let response = ui.horizontal(|ui| {
+                    let response = ui.add_sized(
+                        [title_width, ROW_HEIGHT],
+                        egui::Button::new(title)
+                            .wrap(false)
+                            .fill(if is_selected { selected_bg_color } else { bg_color }),
+                    );
+                    // 省略した情報はホバーで全文を表示
+                    let response = response.on_hover_ui(|ui| result_details::render(ui, result));
+                    if let Some(metadata) = &result.metadata {
+                        for column in columns {
+                            let text = Self::cell_text(column.column, metadata);
+                            Self::render_cell(ui, column, egui::RichText::new(text).small().color(egui::Color32::from_gray(180)));
+                        }
+                    }
+                    response
+                }).inner;
+                accessibility::describe(
+                    &response,
+                    accessibility::item_label(&result.title, accessibility::type_name(&result.result_type), index, count),
+                    is_selected,
+                );
+                
+                if response.clicked() {
+                    clicked = true;
+                }
+                
+                if is_selected {
+                    response.scroll_to_me(Some(egui::Align::Center));
+                }
+                
+                // 列に出していない情報を説明の行に表示（metadata のない結果は description のまま）
+                let description = match &result.metadata {
+                    Some(metadata) => Self::details(metadata, columns),
+                    None => result.description.clone(),
+                };
+                if description.is_empty() {
+                    return;
+                }
+                
+                ui.label(
+                    egui::RichText::new(&description)
+                        .small()
+                        .color(egui::Color32::from_gray(180))
+                );
+            });
+        });
+        
+        ui.add_space(5.0);
+        
+        clicked
+    }
+
+    /// 結果の種類に応じた追加の描画（色の見本など）
+    fn render_inline_visual(ui: &mut egui::Ui, result: &SearchResult) {
+        if let ResultType::Color { rgb: [r, g, b] } = result.result_type {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(30.0, 30.0), egui::Sense::hover());
+            ui.painter().rect_filled(rect, 4.0, egui::Color32::from_rgb(r, g, b));
+            ui.painter().rect_stroke(rect, 4.0, egui::Stroke::new(1.0, egui::Color32::from_gray(120)));
+        }
+    }
+
+    /// 列に出していない情報の説明（例: `https://... · visited 12 times · 3 days ago`）
+    fn details(metadata: &ResultMetadata, columns: &[ColumnConfig]) -> String {
+        let shown = |column: ListColumn| columns.iter().any(|c| c.column == column);
+        let mut parts = Vec::new();
+        if !shown(ListColumn::Url) && !shown(ListColumn::Domain) {
+            parts.push(Self::trim_url_for_display(metadata.url(), 50));
+        }
+        match metadata {
+            ResultMetadata::Bookmark { folder: Some(folder), .. } => parts.push(folder.clone()),
+            ResultMetadata::Bookmark { folder: None, .. } => {}
+            ResultMetadata::History { visit_count, last_visit, .. } => {
+                if !shown(ListColumn::VisitCount) {
+                    parts.push(format!("visited {} times", visit_count));
+                }
+                if let Some(last_visit) = last_visit.filter(|_| !shown(ListColumn::LastVisit)) {
+                    parts.push(time_format::relative(last_visit));
+                }
+            }
+            ResultMetadata::Tab { active, window_number, stale, .. } => {
+                if let Some(number) = window_number {
+                    parts.push(format!("Window {}", number));
+                }
+                if *active {
+                    parts.push("active".to_string());
+                }
+                if *stale {
+                    parts.push("⚠ not updated by the extension recently".to_string());
+                }
+            }
+        }
+        parts.join(" · ")
+    }
+
+    fn trim_url_for_display(url: &str, max_query_length: usize) -> String {
         // クエリパラメータをトリミング
-        let trimmed_url = if let Some(query_start) = url_part.find('?') {
-            let base = &url_part[..query_start];
-            let query = &url_part[query_start..];
+        if let Some(query_start) = url.find('?') {
+            let base = &url[..query_start];
+            let query = &url[query_start..];
             
             if query.len() > max_query_length {
-                format!("{}{}...", base, &query[..max_query_length])
-            } else {
-                url_part.to_string()
+                return format!("{}{}...", base, &query[..max_query_length]);
             }
+        }
+        url.to_string()
+    }
+
+    /// 折りたたんだ見出しの下にあるか
+    fn is_hidden(&self, result: &SearchResult) -> bool {
+        result.group.as_ref().is_some_and(|group| self.collapsed_sections.contains(group))
+    }
+
+    /// 次（`forward`）または前の、折りたたまれていない結果
+    fn next_visible(&self, results: &[SearchResult], from: usize, forward: bool) -> Option<usize> {
+        if forward {
+            (from + 1..results.len()).find(|&i| !self.is_hidden(&results[i]))
         } else {
-            url_part.to_string()
-        };
+            (0..from.min(results.len())).rev().find(|&i| !self.is_hidden(&results[i]))
+        }
+    }
 
-        // visit count情報を再度追加
-        format!("{}{}", trimmed_url, visit_info)
+    /// 選択中の結果が折りたたまれたら、前後の表示されている結果を選択し直す
+    fn ensure_visible_selection(&mut self, results: &[SearchResult]) {
+        let hidden = results.get(self.selected_index).is_some_and(|result| self.is_hidden(result));
+        if hidden {
+            if let Some(index) = self
+                .next_visible(results, self.selected_index, true)
+                .or_else(|| self.next_visible(results, self.selected_index, false))
+            {
+                self.selected_index = index;
+            }
+        }
     }
 
-    pub fn handle_keyboard(&mut self, ui: &mut egui::Ui, results_count: usize) {
+    pub fn handle_keyboard(&mut self, ui: &mut egui::Ui, results: &[SearchResult]) {
+        let results_count = results.len();
+        
         if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
-            if self.selected_index < results_count.saturating_sub(1) {
-                self.selected_index += 1;
+            if let Some(index) = self.next_visible(results, self.selected_index, true) {
+                self.selected_index = index;
                 
                 // 下にスクロールするときに表示数を増やす
                 if self.selected_index + 5 >= self.visible_items && self.visible_items < results_count {
@@ -147,17 +415,30 @@ impl BrowserList {
         }
 
         if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
-            if self.selected_index > 0 {
-                self.selected_index -= 1;
+            if let Some(index) = self.next_visible(results, self.selected_index, false) {
+                self.selected_index = index;
             }
         }
 
+        // Alt+←: 選択中の結果の見出しを折りたたむ / Alt+→: 折りたたんだ見出しをすべて展開
+        if ui.input(|i| i.modifiers.alt && i.key_pressed(egui::Key::ArrowLeft)) {
+            if let Some(group) = results.get(self.selected_index).and_then(|result| result.group.clone()) {
+                self.collapsed_sections.insert(group);
+                self.ensure_visible_selection(results);
+            }
+        }
+        if ui.input(|i| i.modifiers.alt && i.key_pressed(egui::Key::ArrowRight)) {
+            self.collapsed_sections.clear();
+        }
+
         if ui.input(|i| i.key_pressed(egui::Key::Home)) {
             self.selected_index = 0;
+            self.ensure_visible_selection(results);
         }
 
         if ui.input(|i| i.key_pressed(egui::Key::End)) && results_count > 0 {
             self.selected_index = results_count - 1;
+            self.ensure_visible_selection(results);
             // 最後まで表示
             self.visible_items = results_count;
         }
@@ -0,0 +1,45 @@
+use egui;
+use crate::core::confirmation::ConfirmableAction;
+
+/// 確認ダイアログで選んだ操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfirmChoice {
+    Confirm,
+    Cancel,
+}
+
+/// 取り消せない操作の前に、何が起きるかを表示して確認する（Enter で実行、Esc でキャンセル）
+pub fn show(ctx: &egui::Context, pending: &ConfirmableAction) -> Option<ConfirmChoice> {
+    let mut choice = None;
+
+    egui::Window::new("Confirm")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(egui::RichText::new(&pending.summary).strong());
+            ui.add_space(4.0);
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for effect in &pending.effects {
+                    ui.label(egui::RichText::new(effect).color(egui::Color32::from_rgb(150, 150, 150)));
+                }
+            });
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                if ui.button("Confirm (Enter)").clicked() {
+                    choice = Some(ConfirmChoice::Confirm);
+                }
+                if ui.button("Cancel (Esc)").clicked() {
+                    choice = Some(ConfirmChoice::Cancel);
+                }
+            });
+        });
+
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)) {
+        choice = Some(ConfirmChoice::Confirm);
+    }
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+        choice = Some(ConfirmChoice::Cancel);
+    }
+    choice
+}
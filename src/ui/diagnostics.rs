@@ -0,0 +1,84 @@
+use egui;
+use crate::core::health::{HealthState, SourceHealth};
+use crate::supervisor::{ServiceState, ServiceStatus};
+
+const HEALTHY: egui::Color32 = egui::Color32::from_rgb(80, 140, 80);
+const DEGRADED: egui::Color32 = egui::Color32::from_rgb(230, 150, 40);
+const DISABLED: egui::Color32 = egui::Color32::from_rgb(110, 110, 110);
+const THROTTLED: egui::Color32 = egui::Color32::from_rgb(200, 90, 60);
+
+/// 検索元の状態の色
+pub fn color(state: &HealthState) -> egui::Color32 {
+    match state {
+        HealthState::Healthy => HEALTHY,
+        HealthState::Degraded(_) => DEGRADED,
+        HealthState::Throttled(_) => THROTTLED,
+        HealthState::Disabled => DISABLED,
+    }
+}
+
+fn describe(state: &HealthState) -> String {
+    match state {
+        HealthState::Healthy => "healthy".to_string(),
+        HealthState::Degraded(reason) => format!("degraded: {}", reason),
+        HealthState::Throttled(reason) => format!("throttled: {}", reason),
+        HealthState::Disabled => "disabled".to_string(),
+    }
+}
+
+/// ヘッダーに並べる検索元ごとの小さな点。どれかをクリックしたら `true`
+pub fn indicators(ui: &mut egui::Ui, sources: &[SourceHealth]) -> bool {
+    let mut clicked = false;
+    for source in sources {
+        let response = ui
+            .add(egui::Label::new(egui::RichText::new("●").small().color(color(&source.state))).sense(egui::Sense::click()))
+            .on_hover_text(format!("{}: {}", source.name, describe(&source.state)));
+        clicked |= response.clicked();
+    }
+    clicked
+}
+
+/// 検索元とサーバーの状態の一覧（ヘッダーの点をクリックで開く）
+pub fn show(ctx: &egui::Context, open: &mut bool, sources: &[SourceHealth], services: &[ServiceStatus]) {
+    egui::Window::new("Diagnostics")
+        .open(open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(egui::RichText::new("Sources").strong());
+            egui::Grid::new("source_health").striped(true).show(ui, |ui| {
+                for source in sources {
+                    ui.label(egui::RichText::new("●").color(color(&source.state)));
+                    ui.label(&source.name);
+                    ui.label(describe(&source.state));
+                    ui.end_row();
+                }
+            });
+
+            if services.is_empty() {
+                return;
+            }
+            ui.add_space(8.0);
+            ui.label(egui::RichText::new("Services").strong());
+            egui::Grid::new("service_health").striped(true).show(ui, |ui| {
+                for status in services {
+                    let (dot, state) = match &status.state {
+                        ServiceState::Running => (HEALTHY, "running".to_string()),
+                        ServiceState::Restarting { retry_in_secs } => (DEGRADED, format!("restarting in {}s", retry_in_secs)),
+                        ServiceState::Stopped => (DISABLED, "stopped".to_string()),
+                    };
+                    ui.label(egui::RichText::new("●").color(dot));
+                    ui.label(&status.name);
+                    ui.label(format!("{} ({} restarts)", state, status.restarts));
+                    ui.end_row();
+                    if let Some(error) = &status.last_error {
+                        ui.label("");
+                        ui.label("");
+                        ui.label(egui::RichText::new(format!("last error: {}", error)).small().weak());
+                        ui.end_row();
+                    }
+                }
+            });
+        });
+}
@@ -0,0 +1,52 @@
+use egui;
+use std::time::{Duration, Instant};
+
+/// 上端から降りてくる表示（`appearance.presentation: drop_down`）の動き
+///
+/// ウィンドウは上端の外で作り、最初のフレームから `slide` の間に作業領域の上端まで降ろす。
+/// フォーカスを失ったら閉じるのは `core/auto_hide.rs` に任せる。
+pub struct DropDown {
+    /// 降りきったときの左上座標
+    top_left: [f32; 2],
+    height: f32,
+    slide: Duration,
+    started: Option<Instant>,
+    settled: bool,
+}
+
+impl DropDown {
+    pub fn new(top_left: [f32; 2], height: f32, slide: Duration) -> Self {
+        Self { top_left, height, slide, started: None, settled: false }
+    }
+
+    /// ウィンドウを作るときの左上座標（動かさないなら降りきった位置）
+    pub fn start_position(&self, animate: bool) -> [f32; 2] {
+        if animate && !self.slide.is_zero() {
+            [self.top_left[0], self.top_left[1] - self.height]
+        } else {
+            self.top_left
+        }
+    }
+
+    /// 毎フレーム呼ぶ。降りている途中なら位置を進める
+    pub fn update(&mut self, ctx: &egui::Context, animate: bool) {
+        if self.settled {
+            return;
+        }
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let progress = if animate && !self.slide.is_zero() {
+            (started.elapsed().as_secs_f32() / self.slide.as_secs_f32()).min(1.0)
+        } else {
+            1.0
+        };
+        // 最後はゆっくり止まる（ease-out）
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        let y = self.top_left[1] - self.height * (1.0 - eased);
+        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(self.top_left[0], y)));
+        if progress < 1.0 {
+            ctx.request_repaint();
+        } else {
+            self.settled = true;
+        }
+    }
+}
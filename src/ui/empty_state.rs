@@ -0,0 +1,35 @@
+use egui;
+use crate::core::empty_state::{EmptyState, EmptyTarget};
+
+/// 検索語が空のときのセクションを見出し付きで並べる。選んだ行の `EmptyTarget` を返す
+pub fn render(ui: &mut egui::Ui, state: &EmptyState) -> Option<EmptyTarget> {
+    let mut clicked = None;
+    let weak = ui.visuals().weak_text_color();
+
+    egui::ScrollArea::vertical().id_source("empty_state").show(ui, |ui| {
+        for (section, entries) in &state.sections {
+            ui.add_space(6.0);
+            ui.label(egui::RichText::new(section.title()).size(12.0).strong().color(weak));
+            for entry in entries {
+                ui.horizontal(|ui| {
+                    let label = egui::RichText::new(&entry.label).size(13.0);
+                    match &entry.target {
+                        EmptyTarget::None => {
+                            ui.label(label.strong());
+                        }
+                        target => {
+                            if ui.selectable_label(false, label).clicked() {
+                                clicked = Some(target.clone());
+                            }
+                        }
+                    }
+                    if !entry.detail.is_empty() {
+                        ui.label(egui::RichText::new(&entry.detail).size(11.0).color(weak));
+                    }
+                });
+            }
+        }
+    });
+
+    clicked
+}
@@ -0,0 +1,40 @@
+use egui;
+use crate::core::result_export::ExportFormat;
+
+/// 書き出し先
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget {
+    Clipboard,
+    File,
+}
+
+/// 検索結果の書き出しダイアログ（Ctrl+Shift+E）。書き出し先が選ばれたら返す
+pub fn show(ctx: &egui::Context, open: &mut bool, format: &mut ExportFormat, count: usize) -> Option<ExportTarget> {
+    let mut target = None;
+
+    egui::Window::new("Export results")
+        .open(open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!("{} results (titles, URLs / window info)", count));
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                for option in ExportFormat::ALL {
+                    ui.radio_value(format, option, option.label());
+                }
+            });
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                if ui.button("Copy to clipboard").clicked() {
+                    target = Some(ExportTarget::Clipboard);
+                }
+                if ui.button("Save to file").clicked() {
+                    target = Some(ExportTarget::File);
+                }
+            });
+        });
+
+    target
+}
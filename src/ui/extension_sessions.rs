@@ -0,0 +1,59 @@
+use egui;
+use crate::core::extension_sessions::{SessionControl, SessionInfo};
+use std::time::Duration;
+
+/// 接続中の拡張機能の一覧（ヘッダーのサービスの点をクリックで開く）
+///
+/// ボタンを押したら、その接続の ID と頼む操作を返す。
+pub fn show(ctx: &egui::Context, open: &mut bool, sessions: &[SessionInfo]) -> Option<(u64, SessionControl)> {
+    let mut requested = None;
+    egui::Window::new("Extension connections")
+        .open(open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            if sessions.is_empty() {
+                ui.label("No browser extension is connected.");
+                ui.label(egui::RichText::new("Tabs can't be searched or switched until the extension connects.").small().weak());
+                return;
+            }
+            egui::Grid::new("extension_sessions").striped(true).show(ui, |ui| {
+                ui.label(egui::RichText::new("Browser").strong());
+                ui.label(egui::RichText::new("Connection").strong());
+                ui.label(egui::RichText::new("Last activity").strong());
+                ui.label("");
+                ui.end_row();
+                for session in sessions {
+                    let label = ui.label(session.label());
+                    if let Some(peer) = &session.peer {
+                        label.on_hover_text(peer);
+                    }
+                    ui.label(format!("{} for {}", session.transport.label(), duration(session.connected_ms)));
+                    ui.label(format!("{} ago", duration(session.idle_ms)));
+                    ui.horizontal(|ui| {
+                        if ui.button("Refresh tabs").on_hover_text("Ask the extension to send its full tab list again").clicked() {
+                            requested = Some((session.id, SessionControl::RefreshTabs));
+                        }
+                        if ui.button("Disconnect").on_hover_text("The extension reconnects after 5 minutes or when the browser restarts").clicked() {
+                            requested = Some((session.id, SessionControl::Disconnect));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        });
+    // 最後の通信からの時間を更新し続ける
+    ctx.request_repaint_after(Duration::from_secs(1));
+    requested
+}
+
+/// `12s` / `3m` / `2h`
+fn duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3600),
+    }
+}
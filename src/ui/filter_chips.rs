@@ -0,0 +1,74 @@
+use egui;
+use crate::core::result_filter::{FilterChip, QuickFilter, ResultFilter};
+
+/// 先頭から順にチップに割り当てるショートカット（Ctrl+1..9）
+pub(crate) const CHIP_KEYS: [egui::Key; 9] = [
+    egui::Key::Num1,
+    egui::Key::Num2,
+    egui::Key::Num3,
+    egui::Key::Num4,
+    egui::Key::Num5,
+    egui::Key::Num6,
+    egui::Key::Num7,
+    egui::Key::Num8,
+    egui::Key::Num9,
+];
+
+/// 結果一覧の上に絞り込みのチップを並べる。絞り込みを変更したら true を返す
+pub fn render(ui: &mut egui::Ui, chips: &[FilterChip], filter: &mut ResultFilter) -> bool {
+    let mut changed = false;
+    
+    ui.horizontal_wrapped(|ui| {
+        for (index, chip) in chips.iter().enumerate() {
+            let mut response = ui.selectable_label(chip.is_active(filter), chip.label());
+            if index < CHIP_KEYS.len() {
+                response = response.on_hover_text(format!("Ctrl+{}", index + 1));
+            }
+            if response.clicked() {
+                chip.toggle(filter);
+                changed = true;
+            }
+        }
+        
+        if !filter.is_empty() && ui.small_button("✖ Clear").clicked() {
+            *filter = ResultFilter::default();
+            changed = true;
+        }
+    });
+    
+    changed
+}
+
+/// Ctrl+1..9 で対応するチップを切り替える。絞り込みを変更したら true を返す
+pub fn handle_shortcuts(ui: &mut egui::Ui, chips: &[FilterChip], filter: &mut ResultFilter) -> bool {
+    let mut changed = false;
+    for (chip, key) in chips.iter().zip(CHIP_KEYS) {
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, key)) {
+            chip.toggle(filter);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Alt+1..5 で押した一時的な絞り込み（`QuickFilter::ALL` の順）
+pub fn quick_filter_key(ui: &mut egui::Ui) -> Option<QuickFilter> {
+    QuickFilter::ALL
+        .into_iter()
+        .zip(CHIP_KEYS)
+        .find(|(_, key)| ui.input_mut(|i| i.consume_key(egui::Modifiers::ALT, *key)))
+        .map(|(filter, _)| filter)
+}
+
+/// 一時的な絞り込みの表示。外すボタンを押したら true を返す
+pub fn render_quick_filter(ui: &mut egui::Ui, filter: QuickFilter) -> bool {
+    let mut cleared = false;
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new(format!("Only {}", filter.label())).strong());
+        let position = QuickFilter::ALL.iter().position(|f| *f == filter).unwrap_or(0) + 1;
+        if ui.small_button("✖").on_hover_text(format!("Alt+{} to show all", position)).clicked() {
+            cleared = true;
+        }
+    });
+    cleared
+}
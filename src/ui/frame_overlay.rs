@@ -0,0 +1,39 @@
+use egui;
+use crate::frame_profiler::{FrameProfiler, FrameTimings};
+use std::time::Duration;
+
+/// 右上にフレームごとの処理時間を表示する（スクリーンショットで報告してもらえるように）
+pub fn show(ctx: &egui::Context, profiler: &FrameProfiler, cpu_usage: Option<f32>) {
+    egui::Area::new("frame_overlay")
+        .anchor(egui::Align2::RIGHT_TOP, [-8.0, 8.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                egui::Grid::new("frame_timings").num_columns(5).show(ui, |ui| {
+                    for header in ["", "search", "thumbs", "paint", "total"] {
+                        ui.label(egui::RichText::new(header).small().strong());
+                    }
+                    ui.end_row();
+                    
+                    timings_row(ui, "last", profiler.last().unwrap_or_default());
+                    timings_row(ui, "avg", profiler.average());
+                    timings_row(ui, "max", profiler.slowest().unwrap_or_default());
+                });
+                if let Some(cpu) = cpu_usage {
+                    ui.label(egui::RichText::new(format!("eframe cpu: {:.2} ms", cpu * 1000.0)).small().monospace());
+                }
+            });
+        });
+}
+
+fn timings_row(ui: &mut egui::Ui, label: &str, timings: FrameTimings) {
+    ui.label(egui::RichText::new(label).small().strong());
+    for duration in [timings.search, timings.thumbnails, timings.paint, timings.total] {
+        ui.label(egui::RichText::new(format_ms(duration)).small().monospace());
+    }
+    ui.end_row();
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.2}", duration.as_secs_f64() * 1000.0)
+}
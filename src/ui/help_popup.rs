@@ -0,0 +1,53 @@
+use egui;
+use crate::core::help::{self, HelpEntry};
+use crate::core::provider_registry::ProviderRegistry;
+
+/// クエリの書き方の早見表（`?` / F1）。`focus` が true なら絞り込みの入力欄にフォーカスする
+pub fn show(ctx: &egui::Context, open: &mut bool, filter: &mut String, focus: &mut bool, registry: &ProviderRegistry) {
+    let weak = egui::Color32::from_rgb(150, 150, 150);
+
+    egui::Window::new("Help")
+        .open(open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([520.0, 420.0])
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            let response = ui.add(egui::TextEdit::singleline(filter).hint_text("Filter keywords, operators and shortcuts").desired_width(f32::INFINITY));
+            if std::mem::take(focus) {
+                response.request_focus();
+            }
+            ui.add_space(4.0);
+
+            let entries = help::search(registry, filter);
+            if entries.is_empty() {
+                ui.label(egui::RichText::new("Nothing matches").color(weak));
+                return;
+            }
+            egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+                let mut section = None;
+                for entry in &entries {
+                    if section != Some(entry.section) {
+                        section = Some(entry.section);
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new(entry.section.label()).strong());
+                    }
+                    show_entry(ui, entry, weak);
+                }
+            });
+        });
+}
+
+fn show_entry(ui: &mut egui::Ui, entry: &HelpEntry, weak: egui::Color32) {
+    ui.horizontal_wrapped(|ui| {
+        let syntax = egui::RichText::new(&entry.syntax).monospace();
+        ui.label(if entry.enabled { syntax } else { syntax.strikethrough().color(weak) });
+        if !entry.description.is_empty() {
+            ui.label(&entry.description);
+        }
+        if let Some(provider) = entry.provider {
+            let suffix = if entry.enabled { String::new() } else { ", disabled".to_string() };
+            ui.label(egui::RichText::new(format!("({}{})", provider, suffix)).small().color(weak));
+        }
+    });
+}
@@ -0,0 +1,80 @@
+use egui;
+use crate::core::search_engine::{SearchResult, ResultType};
+use crate::core::SearchMode;
+use super::{accessibility, browser_list};
+
+/// 画面下部のヒント（キー, 説明）
+pub type Hint = (&'static str, &'static str);
+
+/// 今のモードと選択中の結果で使えるショートカット
+pub fn hints(mode: SearchMode, selected: Option<&SearchResult>, grouped: bool) -> Vec<Hint> {
+    let mut hints = vec![("Tab", "Mode")];
+    if let Some(selected) = selected {
+        hints.push(("Enter", open_label(&selected.result_type)));
+        if selected.paste_text().is_some() {
+            hints.push(("Shift+Enter", "Paste into previous window"));
+        }
+        hints.push(("Ctrl+I", "Details"));
+        hints.push(("Ctrl+Shift+E", "Export"));
+        if mode == SearchMode::Browser && selected.result_type == ResultType::Bookmark {
+            hints.push(("Shift+Del", "Delete bookmark"));
+            hints.push(("F2", "Rename"));
+        }
+    }
+    if mode == SearchMode::Browser {
+        hints.push(("Ctrl+1..9", "Filter"));
+    }
+    if grouped && mode != SearchMode::Windows {
+        hints.push(("Alt+←/→", "Collapse/Expand"));
+    }
+    hints.push(("F1", "Help"));
+    hints.push(("Ctrl+P", "Settings"));
+    hints.push(("Esc", "Exit"));
+    hints
+}
+
+fn open_label(result_type: &ResultType) -> &'static str {
+    match result_type {
+        ResultType::Window | ResultType::Tab | ResultType::PinnedTab => "Switch",
+        ResultType::GoogleSearch => "Search",
+        ResultType::Command | ResultType::Timer | ResultType::Update => "Run",
+        ResultType::Color { .. } => "Copy",
+        ResultType::Bookmark | ResultType::History | ResultType::ReadingList | ResultType::Note => "Open",
+    }
+}
+
+/// 表示中の結果に含まれる種類（出てきた順、重複なし）
+pub fn legend(results: &[SearchResult]) -> Vec<&ResultType> {
+    let mut types: Vec<&ResultType> = Vec::new();
+    for result in results {
+        if !types.iter().any(|t| std::mem::discriminant(*t) == std::mem::discriminant(&result.result_type)) {
+            types.push(&result.result_type);
+        }
+    }
+    types
+}
+
+/// ショートカットと結果の種類のアイコンの凡例を 1〜2 行で表示
+pub fn render(ui: &mut egui::Ui, hints: &[Hint], legend: &[&ResultType]) {
+    let weak = ui.visuals().weak_text_color();
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 4.0;
+        for (i, (key, label)) in hints.iter().enumerate() {
+            if i > 0 {
+                ui.label(egui::RichText::new("•").size(11.0).color(weak));
+            }
+            ui.label(egui::RichText::new(*key).size(11.0).strong());
+            ui.label(egui::RichText::new(*label).size(11.0).color(weak));
+        }
+    });
+    if !legend.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 4.0;
+            for result_type in legend {
+                ui.label(egui::RichText::new(browser_list::type_icon(result_type)).size(11.0));
+                ui.label(egui::RichText::new(accessibility::type_name(result_type)).size(11.0).color(weak));
+                ui.add_space(6.0);
+            }
+        });
+    }
+}
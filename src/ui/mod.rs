@@ -1,3 +1,25 @@
-pub mod window_grid;
 pub mod alt_tab_grid;
-pub mod browser_list;
\ No newline at end of file
+pub mod browser_list;
+pub mod filter_chips;
+pub mod settings_panel;
+pub mod frame_overlay;
+pub mod motion;
+pub mod accessibility;
+pub mod theme;
+pub mod hint_bar;
+pub mod result_details;
+pub mod monitor_map;
+pub mod export_dialog;
+pub mod tab_overview;
+pub mod workspace_chips;
+pub mod toast_window;
+pub mod confirm_overlay;
+pub mod diagnostics;
+pub mod empty_state;
+pub mod page_preview;
+pub mod sort_selector;
+pub mod privacy_toggle;
+pub mod extension_sessions;
+pub mod help_popup;
+pub mod recent_windows;
+pub mod drop_down;
\ No newline at end of file
@@ -0,0 +1,76 @@
+use egui::{Color32, Pos2, Rect, Rounding, Stroke, Vec2};
+use crate::monitor::{self, MonitorInfo, SnapRegion};
+
+/// ミニマップ全体の大きさ
+const MAP_SIZE: Vec2 = Vec2::new(360.0, 110.0);
+
+/// モニターの配置を縮小したドロップ先（グリッドのタイルをドラッグしている間だけ画面下端に出す）
+///
+/// 各モニターを左半分・全体・右半分の 3 つに分けて表示し、ドラッグを離したモニターと位置を返す。
+pub fn show(ctx: &egui::Context, monitors: &[MonitorInfo], released: bool) -> Option<(usize, SnapRegion)> {
+    if monitors.is_empty() {
+        return None;
+    }
+    let pointer = ctx.pointer_hover_pos();
+    let mut dropped = None;
+
+    egui::Area::new("monitor_map")
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -16.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(egui::RichText::new("Drop on a monitor to move the window").small());
+                let (map_rect, _) = ui.allocate_exact_size(MAP_SIZE, egui::Sense::hover());
+                let painter = ui.painter();
+
+                for (index, (x, y, w, h)) in monitor::fit_monitors(monitors, MAP_SIZE.x, MAP_SIZE.y).into_iter().enumerate() {
+                    let screen = Rect::from_min_size(map_rect.min + Vec2::new(x, y), Vec2::new(w, h)).shrink(2.0);
+                    painter.rect_filled(screen, Rounding::same(3.0), Color32::from_gray(35));
+
+                    for (zone, region) in zones(screen).into_iter().zip(SnapRegion::ALL) {
+                        let hovered = pointer.is_some_and(|pos| zone.contains(pos));
+                        let fill = if hovered {
+                            Color32::from_rgb(60, 100, 180)
+                        } else {
+                            Color32::from_gray(50)
+                        };
+                        painter.rect_filled(zone.shrink(2.0), Rounding::same(2.0), fill);
+                        if hovered && released {
+                            dropped = Some((index, region));
+                        }
+                    }
+                    painter.rect_stroke(screen, Rounding::same(3.0), Stroke::new(1.0, Color32::from_gray(140)));
+                    painter.text(
+                        screen.center(),
+                        egui::Align2::CENTER_CENTER,
+                        (index + 1).to_string(),
+                        egui::FontId::proportional(14.0),
+                        Color32::WHITE,
+                    );
+                }
+            });
+        });
+
+    dropped
+}
+
+/// モニターの左端・中央・右端の 3 つの領域（中央に落とすと作業領域全体に広げる）
+fn zones(screen: Rect) -> [Rect; 3] {
+    let third = screen.width() / 3.0;
+    let at = |i: f32| Rect::from_min_size(Pos2::new(screen.min.x + third * i, screen.min.y), Vec2::new(third, screen.height()));
+    [at(0.0), at(1.0), at(2.0)]
+}
+
+/// ドラッグ中のウィンドウをミニマップに落としたら移動し、移動先の説明を返す
+pub fn drop_window(ctx: &egui::Context, hwnd: isize, released: bool) -> Option<String> {
+    let monitors = monitor::enumerate_monitors();
+    let (index, region) = show(ctx, &monitors, released)?;
+    let rect = monitor::snap_rect(monitors[index].work_area, region);
+    if !crate::windows_api::move_window(hwnd, rect, crate::windows_api::ShowState::Normal) {
+        log::warn!("Failed to move window {} to {:?}", hwnd, rect);
+        return None;
+    }
+    log::info!("Moved window {} to monitor {} ({:?})", hwnd, index, region);
+    Some(format!("Moved to monitor {} ({:?})", index + 1, region))
+}
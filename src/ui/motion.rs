@@ -0,0 +1,12 @@
+use egui;
+use crate::system_prefs::MotionSettings;
+
+/// egui のアニメーション時間を設定に合わせる（変わったときだけスタイルを更新する）
+pub fn apply(ctx: &egui::Context, settings: MotionSettings) {
+    let animation_time = if settings.animations { egui::Style::default().animation_time } else { 0.0 };
+    if ctx.style().animation_time != animation_time {
+        let mut style = (*ctx.style()).clone();
+        style.animation_time = animation_time;
+        ctx.set_style(style);
+    }
+}
@@ -0,0 +1,47 @@
+use egui::TextureHandle;
+use std::collections::HashMap;
+use crate::page_preview::{self, PreviewStore};
+
+/// プレビュー画像の最大の大きさ
+const PREVIEW_SIZE: (u32, u32) = (320, 180);
+
+/// キャッシュした画像を読み込んだテクスチャ（読み込めなかった画像は `None` のまま覚える）
+pub struct PreviewTextures {
+    /// この大きさに収まるよう縮小して読み込む
+    size: (u32, u32),
+    textures: HashMap<String, Option<TextureHandle>>,
+}
+
+impl Default for PreviewTextures {
+    fn default() -> Self {
+        Self::with_size(PREVIEW_SIZE)
+    }
+}
+
+impl PreviewTextures {
+    pub fn with_size(size: (u32, u32)) -> Self {
+        Self { size, textures: HashMap::new() }
+    }
+
+    /// URL の画像。キャッシュになければ取得を始め、届くまでは `None`
+    pub fn get(&mut self, ctx: &egui::Context, store: &PreviewStore, url: &str) -> Option<&TextureHandle> {
+        if !self.textures.contains_key(url) {
+            let Some(bytes) = store.cached(url) else {
+                store.request(url);
+                return None;
+            };
+            let texture = page_preview::decode_thumbnail(&bytes, self.size.0, self.size.1).map(|(width, height, pixels)| {
+                let image = egui::ColorImage::from_rgba_unmultiplied([width, height], &pixels);
+                ctx.load_texture(format!("preview_{}", url), image, egui::TextureOptions::default())
+            });
+            self.textures.insert(url.to_string(), texture);
+        }
+        self.textures.get(url).and_then(Option::as_ref)
+    }
+}
+
+/// 詳細の上に表示するプレビュー画像
+pub fn render(ui: &mut egui::Ui, texture: &TextureHandle) {
+    ui.add(egui::Image::from_texture(texture).max_width(PREVIEW_SIZE.0 as f32).rounding(4.0));
+    ui.add_space(4.0);
+}
@@ -0,0 +1,18 @@
+use egui;
+
+/// ヘッダーのプライバシーモードの切り替え。クリックしたら true を返す
+///
+/// `active` は時間帯で有効になっている場合も含む。`manual` はヘッダーで有効にしたか。
+pub fn render(ui: &mut egui::Ui, active: bool, manual: bool) -> bool {
+    let text = if active {
+        egui::RichText::new("Private").small().strong().color(egui::Color32::from_rgb(150, 110, 200))
+    } else {
+        egui::RichText::new("Private").small().weak()
+    };
+    let hover = match (active, manual) {
+        (true, true) => "Privacy mode: history is not searched and nothing is recorded. Click to turn off",
+        (true, false) => "Privacy mode is on for the scheduled hours",
+        (false, _) => "Click to stop searching history and recording what you open",
+    };
+    ui.selectable_label(active, text).on_hover_text(hover).clicked()
+}
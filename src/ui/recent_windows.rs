@@ -0,0 +1,55 @@
+use egui;
+use crate::core::WindowInfo;
+use super::filter_chips::CHIP_KEYS;
+
+/// 最近使ったウィンドウの一覧での操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecentChoice {
+    /// その番号のウィンドウに切り替える
+    Switch(usize),
+    Close,
+}
+
+/// 最近使ったウィンドウを縦に並べる（`--recent` のポップアップ）
+///
+/// ↑/↓・Tab で選び Enter、または数字キー・クリックでそのウィンドウに切り替える。Esc で閉じる。
+pub fn show(ctx: &egui::Context, windows: &[WindowInfo], selected: &mut usize) -> Option<RecentChoice> {
+    let mut choice = ctx.input(|i| {
+        if i.key_pressed(egui::Key::Escape) {
+            return Some(RecentChoice::Close);
+        }
+        if i.key_pressed(egui::Key::Enter) && *selected < windows.len() {
+            return Some(RecentChoice::Switch(*selected));
+        }
+        if let Some(index) = CHIP_KEYS.iter().take(windows.len()).position(|key| i.key_pressed(*key)) {
+            return Some(RecentChoice::Switch(index));
+        }
+        let count = windows.len().max(1);
+        if i.key_pressed(egui::Key::ArrowDown) || (i.key_pressed(egui::Key::Tab) && !i.modifiers.shift) {
+            *selected = (*selected + 1) % count;
+        }
+        if i.key_pressed(egui::Key::ArrowUp) || (i.key_pressed(egui::Key::Tab) && i.modifiers.shift) {
+            *selected = (*selected + count - 1) % count;
+        }
+        None
+    });
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.label(egui::RichText::new("Recent windows").strong());
+        ui.add_space(4.0);
+        if windows.is_empty() {
+            ui.label(egui::RichText::new("No windows").weak());
+            return;
+        }
+        for (index, window) in windows.iter().enumerate() {
+            let text = format!("{}  {}", index + 1, window.title);
+            let response = ui
+                .add_sized([ui.available_width(), 28.0], egui::SelectableLabel::new(index == *selected, text))
+                .on_hover_text(&window.process_name);
+            if response.clicked() {
+                choice = Some(RecentChoice::Switch(index));
+            }
+        }
+    });
+    choice
+}
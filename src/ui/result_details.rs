@@ -0,0 +1,109 @@
+use egui;
+use crate::core::search_engine::{SearchResult, ResultMetadata};
+use crate::time_format;
+use crate::windows_api::ProcessLaunchInfo;
+
+/// 省略せずに表示する結果の情報（項目名, 値）
+pub fn fields(result: &SearchResult) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("Title", result.title.clone())];
+    match &result.metadata {
+        Some(metadata) => {
+            fields.push(("URL", metadata.url().to_string()));
+            match metadata {
+                ResultMetadata::Bookmark { folder, .. } => {
+                    if let Some(folder) = folder {
+                        fields.push(("Folder", folder.clone()));
+                    }
+                }
+                ResultMetadata::History { visit_count, last_visit, .. } => {
+                    fields.push(("Visits", visit_count.to_string()));
+                    if let Some(last_visit) = last_visit {
+                        fields.push(("Last visit", time_format::relative(*last_visit)));
+                    }
+                }
+                ResultMetadata::Tab { window_number, active, stale, .. } => {
+                    if let Some(number) = window_number {
+                        fields.push(("Window", number.to_string()));
+                    }
+                    if *active {
+                        fields.push(("State", "active".to_string()));
+                    }
+                    if *stale {
+                        fields.push(("Warning", "The extension has not sent updates recently".to_string()));
+                    }
+                }
+            }
+        }
+        None if !result.description.is_empty() && result.window_info.is_none() => {
+            fields.push(("Description", result.description.clone()));
+        }
+        None => {}
+    }
+    if let Some(window) = &result.window_info {
+        fields.push(("Process", window.process_name.clone()));
+        fields.push(("Window class", window.class_name.clone()));
+    }
+    fields
+}
+
+/// 項目名と値を 2 列で表示（ツールチップと詳細のポップアップで使う）
+pub fn render(ui: &mut egui::Ui, result: &SearchResult) {
+    ui.set_max_width(600.0);
+    egui::Grid::new("result_details").num_columns(2).show(ui, |ui| {
+        for (name, value) in fields(result) {
+            ui.label(egui::RichText::new(name).color(ui.visuals().weak_text_color()));
+            ui.add(egui::Label::new(value).wrap(true));
+            ui.end_row();
+        }
+    });
+}
+
+/// ウィンドウのプロセスのコマンドラインと作業フォルダ（値ごとにコピーのボタンを付ける）
+fn render_launch_info(ui: &mut egui::Ui, launch: &ProcessLaunchInfo) {
+    let rows = [("Command line", &launch.command_line), ("Working directory", &launch.current_dir)];
+    egui::Grid::new("launch_info").num_columns(3).show(ui, |ui| {
+        for (name, value) in rows {
+            ui.label(egui::RichText::new(name).color(ui.visuals().weak_text_color()));
+            match value {
+                Some(value) => {
+                    ui.add(egui::Label::new(egui::RichText::new(value).monospace()).wrap(true));
+                    if ui.small_button("Copy").on_hover_text(format!("Copy the {}", name.to_lowercase())).clicked() {
+                        ui.output_mut(|output| output.copied_text = value.clone());
+                    }
+                }
+                // 昇格したプロセスなどは読めない
+                None => {
+                    ui.label(egui::RichText::new("unavailable").weak());
+                }
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/// 選択中の結果の詳細のポップアップ（Ctrl+I で開閉）。プレビュー画像があれば上に表示する
+///
+/// ウィンドウの結果では、同じアプリの複数のウィンドウを見分けられるようプロセスの起動時の情報も表示する。
+pub fn show(
+    ctx: &egui::Context,
+    open: &mut bool,
+    result: &SearchResult,
+    preview: Option<&egui::TextureHandle>,
+    launch: Option<&ProcessLaunchInfo>,
+) {
+    egui::Window::new("Details")
+        .open(open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            if let Some(texture) = preview {
+                super::page_preview::render(ui, texture);
+            }
+            render(ui, result);
+            if let Some(launch) = launch {
+                ui.separator();
+                render_launch_info(ui, launch);
+            }
+        });
+}
@@ -0,0 +1,67 @@
+use egui;
+use crate::core::provider_registry::ProviderRegistry;
+use crate::core::SearchMode;
+
+/// 設定パネルで行われた操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsAction {
+    /// 提供元の有効・無効を切り替えた
+    ProvidersChanged,
+    /// `profile_path` へプロファイルを書き出す
+    ExportProfile,
+    /// `profile_path` からプロファイルを読み込む
+    ImportProfile,
+}
+
+/// 検索結果の提供元の有効・無効と、プロファイルのエクスポート／インポートの設定パネル
+pub fn show(ctx: &egui::Context, open: &mut bool, registry: &ProviderRegistry, profile_path: &mut String) -> Option<SettingsAction> {
+    let mut action = None;
+    
+    egui::Window::new("Settings")
+        .open(open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(egui::RichText::new("Providers").strong());
+            ui.label(egui::RichText::new("Disabled providers are skipped from the next search").color(egui::Color32::from_rgb(150, 150, 150)));
+            ui.add_space(4.0);
+            
+            egui::Grid::new("provider_settings").striped(true).show(ui, |ui| {
+                for info in registry.providers() {
+                    let mut enabled = registry.is_enabled(info.name);
+                    if ui.checkbox(&mut enabled, info.name).changed() {
+                        registry.set_enabled(info.name, enabled);
+                        action = Some(SettingsAction::ProvidersChanged);
+                    }
+                    ui.label(modes_label(&info.modes));
+                    ui.label(egui::RichText::new(info.keywords.join(", ")).monospace());
+                    ui.end_row();
+                }
+            });
+            
+            ui.separator();
+            ui.label(egui::RichText::new("Profile").strong());
+            ui.label(egui::RichText::new("Config, favorites, aliases, usage history, notes, layouts and timers in one file").color(egui::Color32::from_rgb(150, 150, 150)));
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(profile_path).desired_width(280.0));
+                let has_path = !profile_path.trim().is_empty();
+                if ui.add_enabled(has_path, egui::Button::new("Export")).clicked() {
+                    action = Some(SettingsAction::ExportProfile);
+                }
+                if ui.add_enabled(has_path, egui::Button::new("Import"))
+                    .on_hover_text("Existing files are kept as *.bak; restart to apply")
+                    .clicked()
+                {
+                    action = Some(SettingsAction::ImportProfile);
+                }
+            });
+        });
+    
+    action
+}
+
+fn modes_label(modes: &[SearchMode]) -> String {
+    modes.iter().map(|mode| format!("{:?}", mode)).collect::<Vec<_>>().join(" / ")
+}
@@ -0,0 +1,22 @@
+use egui;
+use crate::core::result_sort::SortOrder;
+
+/// 結果の並べ替えのドロップダウン。並べ替えを変更したら true を返す
+pub fn render(ui: &mut egui::Ui, order: &mut SortOrder) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Sort").small().weak());
+        egui::ComboBox::from_id_source("result_sort")
+            .selected_text(order.label())
+            .show_ui(ui, |ui| {
+                for option in SortOrder::ALL {
+                    changed |= ui.selectable_value(order, option, option.label()).changed();
+                }
+            })
+            .response
+            .on_hover_text("Ctrl+S");
+    });
+
+    changed
+}
@@ -0,0 +1,75 @@
+use egui;
+use crate::core::tab_overview::{group_tabs, TabGrouping};
+use crate::core::ChromeTab;
+
+/// 一覧で選ばれた操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverviewAction {
+    SwitchToTab { tab_id: i32, window_id: i32 },
+    /// まとめたタブを全て閉じる
+    CloseTabs(Vec<i32>),
+    /// まとめたタブを新しいウィンドウに移す
+    MoveToNewWindow(Vec<i32>),
+}
+
+/// 開いているタブをウィンドウ・ドメインごとのツリーで表示する（「Open tabs overview」）
+///
+/// `filter` はタイトル・URL の絞り込み。操作が選ばれたら返す。
+pub fn show(
+    ctx: &egui::Context,
+    open: &mut bool,
+    grouping: &mut TabGrouping,
+    filter: &mut String,
+    tabs: &[ChromeTab],
+) -> Option<OverviewAction> {
+    let mut action = None;
+
+    egui::Window::new("Open tabs")
+        .open(open)
+        .collapsible(false)
+        .default_size([560.0, 420.0])
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            let groups = group_tabs(tabs, *grouping, filter);
+            let shown: usize = groups.iter().map(|group| group.tabs.len()).sum();
+
+            ui.horizontal(|ui| {
+                ui.label("Group by");
+                for option in TabGrouping::ALL {
+                    ui.radio_value(grouping, option, option.label());
+                }
+                ui.separator();
+                ui.add(egui::TextEdit::singleline(filter).hint_text("Filter titles").desired_width(160.0))
+                    .request_focus();
+            });
+            ui.label(egui::RichText::new(format!("{} of {} tabs in {} groups", shown, tabs.len(), groups.len())).small().weak());
+            ui.separator();
+
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                for group in &groups {
+                    let header = format!("{} ({})", group.label, group.tabs.len());
+                    egui::CollapsingHeader::new(header)
+                        .id_source(("tab_overview", &group.label))
+                        .default_open(groups.len() <= 3 || !filter.is_empty())
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("Close group").clicked() {
+                                    action = Some(OverviewAction::CloseTabs(group.tab_ids()));
+                                }
+                                if ui.small_button("Move to new window").clicked() {
+                                    action = Some(OverviewAction::MoveToNewWindow(group.tab_ids()));
+                                }
+                            });
+                            for tab in &group.tabs {
+                                let title = if tab.active { format!("● {}", tab.title) } else { tab.title.clone() };
+                                if ui.selectable_label(false, title).on_hover_text(&tab.url).clicked() {
+                                    action = Some(OverviewAction::SwitchToTab { tab_id: tab.id, window_id: tab.window_id });
+                                }
+                            }
+                        });
+                }
+            });
+        });
+
+    action
+}
@@ -0,0 +1,66 @@
+use egui::{Color32, Stroke, Visuals};
+use crate::config::Theme;
+
+/// 既定のダークテーマの、ウィンドウ全体の半透明の背景（Alt+Tab）
+pub const OVERLAY_FILL: Color32 = Color32::from_rgba_premultiplied(30, 30, 30, 240);
+
+/// 画面の DPI に `scale` を掛けた倍率で描画する（文字・グリッドのタイル・余白がまとめて大きくなる）
+///
+/// `native_pixels_per_point` は egui が返す画面の倍率（`ctx.native_pixels_per_point()`）。
+pub fn apply_scale(ctx: &egui::Context, native_pixels_per_point: Option<f32>, scale: f32) {
+    let pixels_per_point = native_pixels_per_point.unwrap_or(1.0) * scale;
+    if (ctx.pixels_per_point() - pixels_per_point).abs() > 0.001 {
+        ctx.set_pixels_per_point(pixels_per_point);
+    }
+}
+
+/// 設定したテーマの配色
+pub fn visuals(theme: Theme) -> Visuals {
+    match theme {
+        Theme::Dark => Visuals::dark(),
+        Theme::HighContrast => high_contrast(),
+    }
+}
+
+/// ウィンドウ全体の背景
+pub fn overlay_fill(theme: Theme) -> Color32 {
+    match theme {
+        Theme::Dark => OVERLAY_FILL,
+        Theme::HighContrast => Color32::BLACK,
+    }
+}
+
+/// 黒地に白の文字、フォーカスと選択は黄色の太い枠
+///
+/// 文字と背景のコントラスト比は 21:1、選択範囲（白地に青）は 7:1 以上で、WCAG の AAA を満たす。
+fn high_contrast() -> Visuals {
+    let focus = Color32::from_rgb(255, 255, 0);
+    let mut visuals = Visuals::dark();
+    visuals.override_text_color = Some(Color32::WHITE);
+    visuals.panel_fill = Color32::BLACK;
+    visuals.window_fill = Color32::BLACK;
+    visuals.extreme_bg_color = Color32::BLACK;
+    visuals.faint_bg_color = Color32::from_gray(24);
+    visuals.window_stroke = Stroke::new(2.0, Color32::WHITE);
+    visuals.hyperlink_color = Color32::from_rgb(128, 200, 255);
+    visuals.selection.bg_fill = Color32::from_rgb(0, 60, 160);
+    visuals.selection.stroke = Stroke::new(2.0, focus);
+
+    visuals.widgets.noninteractive.bg_fill = Color32::BLACK;
+    visuals.widgets.noninteractive.weak_bg_fill = Color32::BLACK;
+    visuals.widgets.noninteractive.bg_stroke = Stroke::new(1.0, Color32::WHITE);
+    visuals.widgets.noninteractive.fg_stroke = Stroke::new(1.0, Color32::WHITE);
+    for widget in [&mut visuals.widgets.inactive, &mut visuals.widgets.open] {
+        widget.bg_fill = Color32::BLACK;
+        widget.weak_bg_fill = Color32::BLACK;
+        widget.bg_stroke = Stroke::new(1.5, Color32::WHITE);
+        widget.fg_stroke = Stroke::new(1.5, Color32::WHITE);
+    }
+    for widget in [&mut visuals.widgets.hovered, &mut visuals.widgets.active] {
+        widget.bg_fill = Color32::BLACK;
+        widget.weak_bg_fill = Color32::BLACK;
+        widget.bg_stroke = Stroke::new(2.5, focus);
+        widget.fg_stroke = Stroke::new(2.0, focus);
+    }
+    visuals
+}
@@ -0,0 +1,84 @@
+use egui;
+use crate::toast::{ToastLevel, ToastQueue, MAX_VISIBLE};
+use std::time::Instant;
+
+/// トースト1件の幅と、ウィンドウの高さの目安にする1件の高さ
+const TOAST_WIDTH: f32 = 320.0;
+const TOAST_HEIGHT: f32 = 90.0;
+/// 作業領域の右下からの余白
+const MARGIN: f32 = 16.0;
+
+/// プライマリモニターの右下に置く、枠のない常に手前のウィンドウ
+///
+/// トーストがない間も閉じずに透明なままにし、クリックは下のウィンドウに通す（`render` で切り替える）。
+pub fn viewport_builder() -> egui::ViewportBuilder {
+    let size = [TOAST_WIDTH + MARGIN, TOAST_HEIGHT * MAX_VISIBLE as f32];
+    let mut builder = egui::ViewportBuilder::default()
+        .with_title("My Launcher Notifications")
+        .with_inner_size(size)
+        .with_decorations(false)
+        .with_transparent(true)
+        .with_resizable(false)
+        .with_always_on_top()
+        .with_mouse_passthrough(true);
+    let monitors = crate::monitor::enumerate_monitors();
+    if let Some(monitor) = monitors.iter().find(|monitor| monitor.is_primary).or(monitors.first()) {
+        let (x, y, width, height) = monitor.work_area;
+        builder = builder.with_position([
+            (x + width) as f32 - size[0] - MARGIN,
+            (y + height) as f32 - size[1] - MARGIN,
+        ]);
+    }
+    builder
+}
+
+/// キューのトーストを下から積み上げて描画する（クリックで詳細を開閉、✖ で閉じる）
+pub fn render(ctx: &egui::Context, queue: &ToastQueue) {
+    let now = Instant::now();
+    if let Some(next) = queue.expire(now) {
+        ctx.request_repaint_after(next);
+    }
+    let toasts = queue.visible(now);
+    ctx.send_viewport_cmd(egui::ViewportCommand::MousePassthrough(toasts.is_empty()));
+
+    egui::CentralPanel::default()
+        .frame(egui::Frame::none())
+        .show(ctx, |ui| {
+            ui.with_layout(egui::Layout::bottom_up(egui::Align::Max), |ui| {
+                for toast in toasts.iter().rev() {
+                    let accent = match toast.level {
+                        ToastLevel::Info => egui::Color32::from_rgb(100, 200, 255),
+                        ToastLevel::Error => egui::Color32::from_rgb(255, 110, 110),
+                    };
+                    let response = egui::Frame::popup(ui.style())
+                        .stroke(egui::Stroke::new(1.0, accent))
+                        .show(ui, |ui| {
+                            ui.set_width(TOAST_WIDTH);
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(&toast.title).strong().color(accent));
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    if ui.small_button("✖").clicked() {
+                                        queue.dismiss(toast.id);
+                                    }
+                                });
+                            });
+                            match (&toast.detail, toast.expanded) {
+                                (Some(detail), true) => {
+                                    ui.label(detail);
+                                }
+                                (Some(_), false) => {
+                                    ui.label(egui::RichText::new("Click for details").small().weak());
+                                }
+                                (None, _) => {}
+                            }
+                        })
+                        .response
+                        .interact(egui::Sense::click());
+                    if response.clicked() && toast.detail.is_some() {
+                        queue.toggle_expanded(toast.id, now);
+                    }
+                    ui.add_space(6.0);
+                }
+            });
+        });
+}
@@ -0,0 +1,44 @@
+use egui;
+use super::filter_chips::CHIP_KEYS;
+
+/// グリッドの上にワークスペースを切り替えるチップ（「All」と各タグ）を並べる。選び直したら true を返す
+pub fn render(ui: &mut egui::Ui, tags: &[String], active: &mut Option<String>) -> bool {
+    let mut changed = false;
+
+    ui.horizontal_wrapped(|ui| {
+        if ui.selectable_label(active.is_none(), "All").on_hover_text("Ctrl+0").clicked() && active.is_some() {
+            *active = None;
+            changed = true;
+        }
+
+        for (index, tag) in tags.iter().enumerate() {
+            let selected = active.as_deref() == Some(tag.as_str());
+            let mut response = ui.selectable_label(selected, tag);
+            if index < CHIP_KEYS.len() {
+                response = response.on_hover_text(format!("Ctrl+{}", index + 1));
+            }
+            if response.clicked() {
+                *active = if selected { None } else { Some(tag.clone()) };
+                changed = true;
+            }
+        }
+    });
+
+    changed
+}
+
+/// Ctrl+1..9 で対応するワークスペースを選ぶ（選んでいるものなら解除）、Ctrl+0 ですべて表示。選び直したら true を返す
+pub fn handle_shortcuts(ui: &mut egui::Ui, tags: &[String], active: &mut Option<String>) -> bool {
+    if ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::Num0)) && active.is_some() {
+        *active = None;
+        return true;
+    }
+
+    for (tag, key) in tags.iter().zip(CHIP_KEYS) {
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, key)) {
+            *active = if active.as_deref() == Some(tag.as_str()) { None } else { Some(tag.clone()) };
+            return true;
+        }
+    }
+    false
+}
@@ -0,0 +1,296 @@
+//! 自動更新チェックと `self-update` コマンド
+//!
+//! ネットワークアクセスは `update` フィーチャーが有効な場合のみ行う。
+//! ダウンロードした実行ファイルは、リリースに添付された `<アセット名>.sha256` と
+//! SHA-256 が一致した場合だけ差し替える。
+
+use crate::config::UpdateConfig;
+use serde::Deserialize;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 公開されている最新リリースの情報
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub page_url: String,
+    pub download_url: Option<String>,
+    /// `<アセット名>.sha256`（`sha256sum` の出力形式）のURL
+    pub checksum_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// GitHub Releases API のレスポンスを解析
+pub fn parse_release(json: &str, asset_name: &str) -> Result<ReleaseInfo, Box<dyn Error>> {
+    let release: GithubRelease = serde_json::from_str(json)?;
+    let asset_url = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name.eq_ignore_ascii_case(name))
+            .map(|asset| asset.browser_download_url.clone())
+    };
+    let download_url = asset_url(asset_name);
+    let checksum_url = asset_url(&format!("{}.sha256", asset_name));
+
+    Ok(ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        page_url: release.html_url,
+        download_url,
+        checksum_url,
+    })
+}
+
+/// `latest` が `current` より新しいバージョンか（数値部分を比較）
+pub fn is_newer(current: &str, latest: &str) -> bool {
+    fn parse(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['.', '-'])
+            .map_while(|part| part.parse::<u64>().ok())
+            .collect()
+    }
+
+    let current = parse(current);
+    let latest = parse(latest);
+    let len = current.len().max(latest.len());
+    for i in 0..len {
+        let c = current.get(i).copied().unwrap_or(0);
+        let l = latest.get(i).copied().unwrap_or(0);
+        if l != c {
+            return l > c;
+        }
+    }
+    false
+}
+
+/// チェックサムファイルの先頭にある16進のダイジェストを取り出す（`<hash>  <file>` 形式にも対応）
+pub fn parse_checksum(text: &str) -> Option<String> {
+    let digest = text.split_whitespace().next()?;
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit())).then(|| digest.to_ascii_lowercase())
+}
+
+/// ファイルの SHA-256 が `expected` と一致するか確認する
+pub fn verify_checksum(path: &Path, expected: &str) -> Result<(), Box<dyn Error>> {
+    let actual = sha256_file(path)?;
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(format!("Checksum mismatch for {}: expected {}, got {}", path.display(), expected, actual).into())
+    }
+}
+
+/// 現在のバージョンより新しいリリースがあれば返す
+pub fn check_for_update(config: &UpdateConfig) -> Result<Option<ReleaseInfo>, Box<dyn Error>> {
+    let json = fetch_text(&config.releases_url)?;
+    let release = parse_release(&json, &config.asset_name)?;
+
+    let current = env!("CARGO_PKG_VERSION");
+    if is_newer(current, &release.version) {
+        log::info!("Update available: {} -> {}", current, release.version);
+        Ok(Some(release))
+    } else {
+        log::debug!("Launcher is up to date ({})", current);
+        Ok(None)
+    }
+}
+
+/// 最新リリースをダウンロードして実行ファイルを置き換える
+pub fn self_update(config: &UpdateConfig) -> Result<Option<String>, Box<dyn Error>> {
+    let release = match check_for_update(config)? {
+        Some(release) => release,
+        None => return Ok(None),
+    };
+    let download_url = release
+        .download_url
+        .as_deref()
+        .ok_or_else(|| format!("Release {} has no asset named {}", release.version, config.asset_name))?;
+    // チェックサムを公開していないリリースには更新しない
+    let checksum_url = release
+        .checksum_url
+        .as_deref()
+        .ok_or_else(|| format!("Release {} has no checksum asset {}.sha256", release.version, config.asset_name))?;
+    let checksum_text = fetch_text(checksum_url)?;
+    let expected = parse_checksum(&checksum_text).ok_or_else(|| format!("Invalid checksum file at {}", checksum_url))?;
+
+    let current_exe = std::env::current_exe()?;
+    let new_exe = current_exe.with_extension("new");
+    download_to(download_url, &new_exe)?;
+    if let Err(e) = verify_checksum(&new_exe, &expected) {
+        let _ = fs::remove_file(&new_exe);
+        return Err(e);
+    }
+
+    if let Err(e) = swap_binary(&current_exe, &new_exe) {
+        let _ = fs::remove_file(&new_exe);
+        return Err(e.into());
+    }
+
+    log::info!("Updated to version {}", release.version);
+    Ok(Some(release.version))
+}
+
+/// 実行ファイルを差し替える。途中で失敗した場合は元のファイルに戻す。
+///
+/// 実行中のファイルは削除できないが名前の変更はできるため、
+/// 旧ファイルを `.old` に退避してから新ファイルを配置する。
+pub fn swap_binary(current: &Path, new: &Path) -> io::Result<PathBuf> {
+    let backup = current.with_extension("old");
+    if backup.exists() {
+        fs::remove_file(&backup)?;
+    }
+
+    fs::rename(current, &backup)?;
+    if let Err(e) = fs::rename(new, current) {
+        log::error!("Failed to install new binary, rolling back: {}", e);
+        fs::rename(&backup, current)?;
+        return Err(e);
+    }
+
+    Ok(backup)
+}
+
+#[cfg(feature = "update")]
+fn fetch_text(url: &str) -> Result<String, Box<dyn Error>> {
+    let response = ureq::get(url)
+        .set("User-Agent", concat!("my-launcher/", env!("CARGO_PKG_VERSION")))
+        .call()?;
+    Ok(response.into_string()?)
+}
+
+#[cfg(feature = "update")]
+fn download_to(url: &str, path: &Path) -> Result<(), Box<dyn Error>> {
+    let response = ureq::get(url)
+        .set("User-Agent", concat!("my-launcher/", env!("CARGO_PKG_VERSION")))
+        .call()?;
+    let mut reader = response.into_reader();
+    let mut file = fs::File::create(path)?;
+    io::copy(&mut reader, &mut file)?;
+    Ok(())
+}
+
+#[cfg(feature = "update")]
+fn sha256_file(path: &Path) -> Result<String, Box<dyn Error>> {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+#[cfg(not(feature = "update"))]
+fn fetch_text(_url: &str) -> Result<String, Box<dyn Error>> {
+    Err("Update support is not enabled (build with --features update)".into())
+}
+
+#[cfg(not(feature = "update"))]
+fn download_to(_url: &str, _path: &Path) -> Result<(), Box<dyn Error>> {
+    Err("Update support is not enabled (build with --features update)".into())
+}
+
+#[cfg(not(feature = "update"))]
+fn sha256_file(_path: &Path) -> Result<String, Box<dyn Error>> {
+    Err("Update support is not enabled (build with --features update)".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.1.0", "0.2.0"));
+        assert!(is_newer("0.1.0", "v0.1.1"));
+        assert!(is_newer("0.9.9", "1.0"));
+        assert!(!is_newer("0.2.0", "0.1.9"));
+        assert!(!is_newer("1.0.0", "1.0.0"));
+        assert!(!is_newer("1.0.0", "1.0"));
+    }
+
+    #[test]
+    fn test_parse_release() {
+        let json = r#"{
+            "tag_name": "v0.3.0",
+            "html_url": "https://github.com/takeru911/my-launcher/releases/tag/v0.3.0",
+            "assets": [
+                { "name": "my-launcher.exe", "browser_download_url": "https://example.com/my-launcher.exe" },
+                { "name": "my-launcher.exe.sha256", "browser_download_url": "https://example.com/my-launcher.exe.sha256" },
+                { "name": "my-launcher-alt-tab.exe", "browser_download_url": "https://example.com/alt.exe" }
+            ]
+        }"#;
+
+        let release = parse_release(json, "my-launcher.exe").unwrap();
+        assert_eq!(release.version, "0.3.0");
+        assert_eq!(release.download_url.as_deref(), Some("https://example.com/my-launcher.exe"));
+        assert_eq!(release.checksum_url.as_deref(), Some("https://example.com/my-launcher.exe.sha256"));
+    }
+
+    #[test]
+    fn test_parse_release_without_asset() {
+        let json = r#"{ "tag_name": "0.3.0", "html_url": "https://example.com" }"#;
+        let release = parse_release(json, "my-launcher.exe").unwrap();
+        assert!(release.download_url.is_none());
+        assert!(release.checksum_url.is_none());
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let digest = "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+        assert_eq!(parse_checksum(&format!("{}  my-launcher.exe\n", digest)), Some(digest.to_ascii_lowercase()));
+        assert_eq!(parse_checksum(digest), Some(digest.to_ascii_lowercase()));
+        assert_eq!(parse_checksum("not-a-checksum my-launcher.exe"), None);
+        assert_eq!(parse_checksum(""), None);
+    }
+
+    #[cfg(feature = "update")]
+    #[test]
+    fn test_verify_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("launcher.new");
+        fs::write(&file, "abc").unwrap();
+
+        // "abc" の SHA-256
+        assert!(verify_checksum(&file, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad").is_ok());
+        assert!(verify_checksum(&file, "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855").is_err());
+    }
+
+    #[test]
+    fn test_swap_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join("launcher.exe");
+        let new = dir.path().join("launcher.new");
+        fs::write(&current, "old").unwrap();
+        fs::write(&new, "new").unwrap();
+
+        let backup = swap_binary(&current, &new).unwrap();
+        assert_eq!(fs::read_to_string(&current).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "old");
+        assert!(!new.exists());
+    }
+
+    #[test]
+    fn test_swap_binary_rolls_back_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join("launcher.exe");
+        let missing = dir.path().join("missing.new");
+        fs::write(&current, "old").unwrap();
+
+        assert!(swap_binary(&current, &missing).is_err());
+        assert_eq!(fs::read_to_string(&current).unwrap(), "old");
+    }
+}
@@ -0,0 +1,270 @@
+//! 生成系のユーティリティ（`uuid` / `password 24` / `lorem 2p` / `base64 encode <text>`）
+//!
+//! いずれも入力中に結果を表示し、Enter でクリップボードにコピーする。
+
+use crate::core::keyword_provider::{KeywordHelp, KeywordProvider};
+use crate::core::{Action, ResultType, SearchResult};
+use std::error::Error;
+
+/// パスワードの既定の長さ
+const DEFAULT_PASSWORD_LENGTH: usize = 20;
+
+/// パスワードの最大長
+const MAX_PASSWORD_LENGTH: usize = 256;
+
+const PASSWORD_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789!@#$%^&*-_=+?";
+
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const LOREM_WORDS: &[&str] = &[
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit", "sed", "do", "eiusmod",
+    "tempor", "incididunt", "ut", "labore", "et", "dolore", "magna", "aliqua", "enim", "ad", "minim", "veniam",
+    "quis", "nostrud", "exercitation", "ullamco", "laboris", "nisi", "aliquip", "ex", "ea", "commodo",
+    "consequat", "duis", "aute", "irure", "in", "reprehenderit", "voluptate", "velit", "esse", "cillum",
+    "fugiat", "nulla", "pariatur", "excepteur", "sint", "occaecat", "cupidatat", "non", "proident", "sunt",
+    "culpa", "qui", "officia", "deserunt", "mollit", "anim", "id", "est", "laborum",
+];
+
+/// 1文あたりの単語数
+const LOREM_SENTENCE_WORDS: usize = 12;
+
+/// 1段落あたりの文の数
+const LOREM_PARAGRAPH_SENTENCES: usize = 5;
+
+/// OSの乱数生成器から乱数を取得
+fn random_bytes(len: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut bytes = vec![0u8; len];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("failed to get random bytes: {}", e))?;
+    Ok(bytes)
+}
+
+/// ランダムな UUID（バージョン4）
+pub fn generate_uuid() -> Result<String, Box<dyn Error>> {
+    let mut bytes = random_bytes(16)?;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    Ok(format_uuid(&bytes))
+}
+
+fn format_uuid(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+/// ランダムなパスワード（偏りが出ないよう、文字数で割り切れない乱数は捨てる）
+pub fn generate_password(length: usize) -> Result<String, Box<dyn Error>> {
+    let limit = 256 - (256 % PASSWORD_CHARS.len());
+    let mut password = String::with_capacity(length);
+    while password.len() < length {
+        for byte in random_bytes(length * 2)? {
+            if password.len() == length {
+                break;
+            }
+            if (byte as usize) < limit {
+                password.push(PASSWORD_CHARS[byte as usize % PASSWORD_CHARS.len()] as char);
+            }
+        }
+    }
+    Ok(password)
+}
+
+/// `2p`（段落）/ `3s`（文）/ `10w`（単語）のダミーテキスト。単位を省略した場合は段落。
+pub fn lorem(spec: &str) -> Option<String> {
+    let spec = spec.trim();
+    let spec = if spec.is_empty() { "1p" } else { spec };
+    let (count, unit) = match spec.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((pos, _)) => (&spec[..pos], &spec[pos..]),
+        None => (spec, "p"),
+    };
+    let count: usize = count.parse().ok().filter(|n| (1..=50).contains(n))?;
+
+    let words = |n: usize, offset: usize| -> Vec<&str> { (0..n).map(|i| LOREM_WORDS[(offset + i) % LOREM_WORDS.len()]).collect() };
+    let sentence = |index: usize| {
+        let mut text = words(LOREM_SENTENCE_WORDS, index * LOREM_SENTENCE_WORDS).join(" ");
+        text[..1].make_ascii_uppercase();
+        text.push('.');
+        text
+    };
+    let sentences = |n: usize, offset: usize| (0..n).map(|i| sentence(offset + i)).collect::<Vec<_>>().join(" ");
+
+    match unit {
+        "w" => Some(words(count, 0).join(" ")),
+        "s" => Some(sentences(count, 0)),
+        "p" => Some(
+            (0..count)
+                .map(|i| sentences(LOREM_PARAGRAPH_SENTENCES, i * LOREM_PARAGRAPH_SENTENCES))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        ),
+        _ => None,
+    }
+}
+
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+pub fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim().trim_end_matches('=');
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes() {
+        // URL-safe な表記も受け付ける
+        let c = match c {
+            b'-' => b'+',
+            b'_' => b'/',
+            c => c,
+        };
+        let value = BASE64_CHARS.iter().position(|&b| b == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+fn copy_result(title: String, description: &str) -> SearchResult {
+    // 複数行のテキストは1行目だけを表示
+    let display = match title.lines().next() {
+        Some(first) if first.len() < title.len() => format!("{}…", first),
+        _ => title.clone(),
+    };
+    SearchResult {
+        title: display,
+        description: format!("{} · Enter to copy", description),
+        action: Action::CopyToClipboard(title),
+        window_info: None,
+        result_type: ResultType::Command,
+        group: None,
+        metadata: None,
+    }
+}
+
+/// 生成系ユーティリティのプロバイダー
+pub struct UtilityProvider;
+
+impl KeywordProvider for UtilityProvider {
+    fn name(&self) -> &'static str {
+        "utility"
+    }
+
+    fn keywords(&self) -> &[&'static str] {
+        &["uuid", "password", "lorem", "base64"]
+    }
+
+    fn help(&self) -> &[KeywordHelp] {
+        const HELP: &[KeywordHelp] = &[
+            KeywordHelp::new("uuid", "Copy a random UUID"),
+            KeywordHelp::new("password [length]", "Copy a random password"),
+            KeywordHelp::new("lorem [2p|3s|10w]", "Copy placeholder paragraphs, sentences or words"),
+            KeywordHelp::new("base64 encode|decode <text>", "Encode or decode Base64"),
+        ];
+        HELP
+    }
+
+    fn search(&self, keyword: &str, args: &str) -> Vec<SearchResult> {
+        let result = match keyword {
+            "uuid" => generate_uuid().map(|uuid| vec![copy_result(uuid, "Random UUID (v4)")]),
+            "password" => {
+                let length = args.parse().unwrap_or(DEFAULT_PASSWORD_LENGTH).clamp(1, MAX_PASSWORD_LENGTH);
+                generate_password(length)
+                    .map(|password| vec![copy_result(password, &format!("Random password ({} characters)", length))])
+            }
+            "lorem" => Ok(lorem(args)
+                .map(|text| vec![copy_result(text, "Lorem ipsum (e.g. lorem 2p / 3s / 10w)")])
+                .unwrap_or_default()),
+            "base64" => Ok(base64_results(args)),
+            _ => Ok(Vec::new()),
+        };
+
+        result.unwrap_or_else(|e| {
+            log::error!("{} failed: {}", keyword, e);
+            Vec::new()
+        })
+    }
+
+    fn execute(&self, command: &str) -> Result<String, Box<dyn Error>> {
+        Err(format!("unknown utility command: {}", command).into())
+    }
+}
+
+fn base64_results(args: &str) -> Vec<SearchResult> {
+    if let Some(text) = args.strip_prefix("encode ") {
+        return vec![copy_result(base64_encode(text.as_bytes()), "Base64 encoded")];
+    }
+    if let Some(text) = args.strip_prefix("decode ") {
+        // テキストとして表示できないデータは対象外
+        return match base64_decode(text).map(String::from_utf8) {
+            Some(Ok(decoded)) => vec![copy_result(decoded, "Base64 decoded")],
+            _ => Vec::new(),
+        };
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_uuid_format() {
+        let uuid = generate_uuid().unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(uuid, generate_uuid().unwrap());
+    }
+
+    #[test]
+    fn test_generate_password() {
+        let password = generate_password(24).unwrap();
+        assert_eq!(password.len(), 24);
+        assert!(password.bytes().all(|b| PASSWORD_CHARS.contains(&b)));
+    }
+
+    #[test]
+    fn test_lorem() {
+        assert_eq!(lorem("3w").unwrap(), "lorem ipsum dolor");
+        assert!(lorem("1s").unwrap().starts_with("Lorem ipsum"));
+        assert_eq!(lorem("2p").unwrap().split("\n\n").count(), 2);
+        assert_eq!(lorem("").unwrap().split("\n\n").count(), 1);
+        assert_eq!(lorem("0p"), None);
+        assert_eq!(lorem("2x"), None);
+    }
+
+    #[test]
+    fn test_base64_roundtrip() {
+        assert_eq!(base64_encode(b"hello"), "aGVsbG8=");
+        assert_eq!(base64_encode(b"hi!"), "aGkh");
+        assert_eq!(base64_encode("日本".as_bytes()), "5pel5pys");
+        assert_eq!(base64_decode("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(base64_decode("5pel5pys").unwrap(), "日本".as_bytes());
+        assert_eq!(base64_decode("not base64!"), None);
+    }
+
+    #[test]
+    fn test_search_base64() {
+        let results = UtilityProvider.search("base64", "encode hello");
+        assert_eq!(results[0].action, Action::CopyToClipboard("aGVsbG8=".to_string()));
+        let results = UtilityProvider.search("base64", "decode aGVsbG8=");
+        assert_eq!(results[0].title, "hello");
+        assert!(UtilityProvider.search("base64", "").is_empty());
+    }
+}
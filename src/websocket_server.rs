@@ -1,16 +1,35 @@
 use std::sync::Arc;
 use std::error::Error;
 use tokio::net::{TcpListener, TcpStream};
-use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio_tungstenite::{accept_async_with_config, tungstenite::Message};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Error as WsError;
+use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
 use futures_util::{StreamExt, SinkExt};
 use log::{info, error, debug, warn};
 use serde::Deserialize;
 
-use crate::core::native_messaging::{TabManager, ChromeCommand, ChromeTab};
+use crate::core::native_messaging::{TabManager, ChromeCommand, ChromeTab, ClosedTab, ReadingListEntry};
+use crate::core::extension_sessions::{SessionControl, Transport};
 use crate::websocket_types::{
     WebSocketMessage, ResponseResult, EventType, EventData
 };
 
+/// 一覧から切断したときの Close のコード（拡張機能はしばらく接続し直さない）
+pub const CLOSE_DISCONNECTED_BY_USER: u16 = 4001;
+
+/// 一覧から頼まれた操作を確認する間隔
+const CONTROL_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// 拡張機能から受け取る 1 件のメッセージの上限（Native Messaging の経路も同じ）
+pub const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// `updateTabs` で受け取るタブの数の上限
+pub const MAX_TABS: usize = 10_000;
+
+/// 接続へ送るのを待つコマンドの数（いっぱいの間はコマンドを `TabManager` のキューに残す）
+const COMMAND_CHANNEL_CAPACITY: usize = 32;
+
 pub struct WebSocketServer {
     tab_manager: Arc<TabManager>,
     port: u16,
@@ -47,7 +66,12 @@ async fn handle_connection(stream: TcpStream, tab_manager: Arc<TabManager>) {
     let addr = stream.peer_addr().ok();
     info!("Handling WebSocket connection from {:?}", addr);
     
-    let ws_stream = match accept_async(stream).await {
+    let config = WebSocketConfig {
+        max_message_size: Some(MAX_MESSAGE_BYTES),
+        max_frame_size: Some(MAX_MESSAGE_BYTES),
+        ..Default::default()
+    };
+    let ws_stream = match accept_async_with_config(stream, Some(config)).await {
         Ok(ws) => ws,
         Err(e) => {
             error!("WebSocket handshake failed: {}", e);
@@ -56,6 +80,8 @@ async fn handle_connection(stream: TcpStream, tab_manager: Arc<TabManager>) {
     };
     
     info!("WebSocket handshake successful");
+    tab_manager.extension_connected();
+    let session = tab_manager.sessions().open(Transport::WebSocket, addr.map(|addr| addr.to_string()));
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
     
     // Send initial tab list
@@ -68,29 +94,34 @@ async fn handle_connection(stream: TcpStream, tab_manager: Arc<TabManager>) {
         let _ = ws_sender.send(Message::Text(initial_msg)).await;
     }
     
-    // Create a channel for sending tab switch commands
-    let (command_tx, mut command_rx) = tokio::sync::mpsc::channel::<(i32, i32)>(32);
+    // Create a channel for sending commands to Chrome
+    let (command_tx, mut command_rx) = tokio::sync::mpsc::channel::<ChromeCommand>(COMMAND_CHANNEL_CAPACITY);
     
-    // Spawn a task to check for pending commands
+    // 送り先に空きがあるときだけキューから取り出す（送れずに捨てることはない）。接続が閉じたら終わる
     let tab_manager_clone = Arc::clone(&tab_manager);
-    let command_tx_clone = command_tx.clone();
     tokio::spawn(async move {
-        loop {
-            // Check for pending commands every 50ms
-            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-            
-            if let Some(command) = tab_manager_clone.pop_command() {
-                match command {
-                    ChromeCommand::SwitchToTab { tab_id, window_id } => {
-                        info!("WebSocket: Found pending command - SwitchToTab({}, {})", tab_id, window_id);
-                        let _ = command_tx_clone.send((tab_id, window_id)).await;
-                    }
+        while let Ok(permit) = command_tx.reserve().await {
+            let command = loop {
+                if let Some(command) = tab_manager_clone.pop_command() {
+                    break Some(command);
                 }
-            }
+                if command_tx.is_closed() {
+                    break None;
+                }
+                // Check for pending commands every 50ms
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            };
+            let Some(command) = command else {
+                break;
+            };
+            info!("WebSocket: Found pending command - {:?}", command);
+            permit.send(command);
         }
     });
     
-    loop {
+    let mut control_check = tokio::time::interval(CONTROL_CHECK_INTERVAL);
+    
+    'connection: loop {
         tokio::select! {
             // Handle incoming messages from Chrome
             msg = ws_receiver.next() => {
@@ -98,27 +129,24 @@ async fn handle_connection(stream: TcpStream, tab_manager: Arc<TabManager>) {
                     Some(Ok(Message::Text(text))) => {
                         debug!("Received WebSocket message: {}", text);
                         
-                        match serde_json::from_str::<WebSocketMessage>(&text) {
-                            Ok(WebSocketMessage::Request { id, method, params }) => {
-                                let response = handle_request(id, &method, params, &tab_manager).await;
-                                
-                                if let Ok(response_text) = serde_json::to_string(&response) {
-                                    if let Err(e) = ws_sender.send(Message::Text(response_text)).await {
-                                        error!("Failed to send response: {}", e);
-                                        break;
-                                    }
+                        if let Some(response) = handle_session_text(&text, &tab_manager, Some(session)) {
+                            if let Ok(response_text) = serde_json::to_string(&response) {
+                                if let Err(e) = ws_sender.send(Message::Text(response_text)).await {
+                                    error!("Failed to send response: {}", e);
+                                    break;
                                 }
                             }
-                            Ok(msg) => {
-                                debug!("Received non-request message: {:?}", msg);
-                            }
-                            Err(e) => {
-                                error!("Failed to parse WebSocket message: {}", e);
-                            }
                         }
                     }
                     Some(Ok(Message::Binary(_))) => {
                         warn!("Received binary message, ignoring");
+                        let response = WebSocketMessage::response_error(String::new(), 415, "Binary messages are not supported".to_string());
+                        if let Ok(response_text) = serde_json::to_string(&response) {
+                            if let Err(e) = ws_sender.send(Message::Text(response_text)).await {
+                                error!("Failed to send response: {}", e);
+                                break;
+                            }
+                        }
                     }
                     Some(Ok(Message::Close(_))) => {
                         info!("WebSocket connection closed by client");
@@ -133,6 +161,16 @@ async fn handle_connection(stream: TcpStream, tab_manager: Arc<TabManager>) {
                     Some(Ok(Message::Pong(_))) => {
                         debug!("Received pong");
                     }
+                    // 上限を超えるメッセージは読めないので、理由を付けて閉じる
+                    Some(Err(WsError::Capacity(e))) => {
+                        warn!("Closing WebSocket connection: {}", e);
+                        let frame = CloseFrame {
+                            code: CloseCode::Size,
+                            reason: format!("Messages are limited to {} bytes", MAX_MESSAGE_BYTES).into(),
+                        };
+                        let _ = ws_sender.send(Message::Close(Some(frame))).await;
+                        break;
+                    }
                     Some(Err(e)) => {
                         error!("WebSocket error: {}", e);
                         break;
@@ -145,35 +183,170 @@ async fn handle_connection(stream: TcpStream, tab_manager: Arc<TabManager>) {
                 }
             }
             
-            // Handle pending tab switch commands
-            Some((tab_id, window_id)) = command_rx.recv() => {
-                info!("Sending tab switch event to Chrome: tab_id={}, window_id={}", tab_id, window_id);
-                
-                let event = WebSocketMessage::event(
-                    EventType::TabSwitchRequested,
-                    EventData::TabSwitch { tab_id, window_id },
-                );
+            // Handle pending commands
+            Some(command) = command_rx.recv() => {
+                let event = command_event(command);
                 
                 if let Ok(event_text) = serde_json::to_string(&event) {
                     if let Err(e) = ws_sender.send(Message::Text(event_text)).await {
-                        error!("Failed to send tab switch event: {}", e);
+                        error!("Failed to send command event: {}", e);
                         break;
                     }
                 }
             }
+            
+            // 接続の一覧から頼まれた操作
+            _ = control_check.tick() => {
+                for control in tab_manager.sessions().take_controls(session) {
+                    match control {
+                        SessionControl::RefreshTabs => {
+                            info!("Asking extension session {} to resend its tabs", session);
+                            let event = WebSocketMessage::event(EventType::TabsRefreshRequested, EventData::Empty {});
+                            if let Ok(event_text) = serde_json::to_string(&event) {
+                                if let Err(e) = ws_sender.send(Message::Text(event_text)).await {
+                                    error!("Failed to send refresh request: {}", e);
+                                    break 'connection;
+                                }
+                            }
+                        }
+                        SessionControl::Disconnect => {
+                            info!("Disconnecting extension session {}", session);
+                            let frame = CloseFrame {
+                                code: CloseCode::from(CLOSE_DISCONNECTED_BY_USER),
+                                reason: "Disconnected from the launcher".into(),
+                            };
+                            let _ = ws_sender.send(Message::Close(Some(frame))).await;
+                            break 'connection;
+                        }
+                    }
+                }
+            }
         }
     }
     
+    tab_manager.sessions().close(session);
+    tab_manager.extension_disconnected();
     info!("WebSocket connection closed");
 }
 
-async fn handle_request(
+/// キューされたコマンドを拡張機能へ送るイベントに変換
+pub(crate) fn command_event(command: ChromeCommand) -> WebSocketMessage {
+    match command {
+        ChromeCommand::SwitchToTab { tab_id, window_id } => {
+            info!("Sending tab switch event to Chrome: tab_id={}, window_id={}", tab_id, window_id);
+            WebSocketMessage::event(
+                EventType::TabSwitchRequested,
+                EventData::TabSwitch { tab_id, window_id },
+            )
+        }
+        ChromeCommand::CloseTab { tab_id } => {
+            info!("Sending tab close event to Chrome: tab_id={}", tab_id);
+            WebSocketMessage::event(
+                EventType::TabCloseRequested,
+                EventData::TabClose { tab_id },
+            )
+        }
+        ChromeCommand::CreateBookmark { url, title, folder } => {
+            info!("Sending bookmark create event to Chrome: url={}, folder={:?}", url, folder);
+            WebSocketMessage::event(
+                EventType::BookmarkCreateRequested,
+                EventData::BookmarkCreate { url, title, folder },
+            )
+        }
+        ChromeCommand::DeleteBookmark { id } => {
+            info!("Sending bookmark delete event to Chrome: id={}", id);
+            WebSocketMessage::event(
+                EventType::BookmarkDeleteRequested,
+                EventData::BookmarkDelete { id },
+            )
+        }
+        ChromeCommand::RenameBookmark { url, title } => {
+            info!("Sending bookmark rename event to Chrome: url={}", url);
+            WebSocketMessage::event(
+                EventType::BookmarkRenameRequested,
+                EventData::BookmarkRename { url, title },
+            )
+        }
+        ChromeCommand::CloseTabs { tab_ids } => {
+            info!("Sending tabs close event to Chrome: {} tabs", tab_ids.len());
+            WebSocketMessage::event(
+                EventType::TabsCloseRequested,
+                EventData::TabIds { tab_ids },
+            )
+        }
+        ChromeCommand::MoveTabsToNewWindow { tab_ids } => {
+            info!("Sending tabs move event to Chrome: {} tabs", tab_ids.len());
+            WebSocketMessage::event(
+                EventType::TabsMoveRequested,
+                EventData::TabIds { tab_ids },
+            )
+        }
+        ChromeCommand::GetRecentlyClosed => {
+            info!("Asking Chrome for recently closed tabs");
+            WebSocketMessage::event(EventType::RecentlyClosedRequested, EventData::Empty {})
+        }
+        ChromeCommand::RestoreClosedTab { session_id } => {
+            info!("Sending closed tab restore event to Chrome: session_id={}", session_id);
+            WebSocketMessage::event(
+                EventType::ClosedTabRestoreRequested,
+                EventData::ClosedTabRestore { session_id },
+            )
+        }
+    }
+}
+
+/// 拡張機能から届いたテキストのメッセージを処理し、返す応答を作る（リクエスト以外には応答しない）
+///
+/// 接続を持たずに呼べるので、テストでは拡張機能のメッセージをここに直接流す。
+pub fn handle_text(text: &str, tab_manager: &TabManager) -> Option<WebSocketMessage> {
+    handle_session_text(text, tab_manager, None)
+}
+
+/// 接続 `session` から届いたメッセージを処理する（接続の最後の通信の時刻も更新する）
+pub fn handle_session_text(text: &str, tab_manager: &TabManager, session: Option<u64>) -> Option<WebSocketMessage> {
+    if let Some(session) = session {
+        tab_manager.sessions().touch(session);
+    }
+    match serde_json::from_str::<WebSocketMessage>(text) {
+        Ok(WebSocketMessage::Request { id, method, params }) => Some(handle_request(id, &method, params, tab_manager, session)),
+        Ok(msg) => {
+            debug!("Received non-request message: {:?}", msg);
+            None
+        }
+        Err(e) => {
+            error!("Failed to parse WebSocket message: {}", e);
+            Some(invalid_message(text, &e))
+        }
+    }
+}
+
+/// 読めなかったメッセージへのエラー応答（`id` だけでも読めればその ID で返す）
+fn invalid_message(text: &str, error: &serde_json::Error) -> WebSocketMessage {
+    let id = serde_json::from_str::<serde_json::Value>(text)
+        .ok()
+        .and_then(|value| value.get("id")?.as_str().map(str::to_string))
+        .unwrap_or_default();
+    WebSocketMessage::response_error(id, 400, format!("Invalid message: {}", error))
+}
+
+fn handle_request(
     id: String,
     method: &str,
     params: Option<serde_json::Value>,
-    tab_manager: &Arc<TabManager>,
+    tab_manager: &TabManager,
+    session: Option<u64>,
 ) -> WebSocketMessage {
     match method {
+        // 接続した拡張機能が名乗る（接続の一覧に表示する）
+        "hello" => {
+            let hello: HelloParams = params.and_then(|params| serde_json::from_value(params).ok()).unwrap_or_default();
+            info!("WebSocket: Hello from {:?} ({:?})", hello.browser, hello.profile);
+            if let Some(session) = session {
+                tab_manager.sessions().identify(session, hello.browser, hello.profile);
+            }
+            WebSocketMessage::response_ok(id, ResponseResult::Success { success: true })
+        }
+        
         "getTabs" => {
             let tabs = tab_manager.get_tabs();
             info!("WebSocket: GetTabs request, returning {} tabs", tabs.len());
@@ -183,6 +356,10 @@ async fn handle_request(
         "updateTabs" => {
             if let Some(params) = params {
                 if let Ok(tabs_data) = serde_json::from_value::<UpdateTabsParams>(params) {
+                    if tabs_data.tabs.len() > MAX_TABS {
+                        warn!("WebSocket: Rejecting updateTabs with {} tabs", tabs_data.tabs.len());
+                        return WebSocketMessage::response_error(id, 413, format!("Too many tabs ({}, at most {})", tabs_data.tabs.len(), MAX_TABS));
+                    }
                     info!("WebSocket: UpdateTabs request, updating {} tabs", tabs_data.tabs.len());
                     tab_manager.update_tabs(tabs_data.tabs);
                     WebSocketMessage::response_ok(id, ResponseResult::Success { success: true })
@@ -194,6 +371,77 @@ async fn handle_request(
             }
         }
         
+        // 最近閉じたタブ（接続時、閉じたタブが変わったとき、`GetRecentlyClosed` の応答として届く）
+        "updateRecentlyClosed" => {
+            match params.map(serde_json::from_value::<RecentlyClosedParams>) {
+                Some(Ok(closed)) if closed.tabs.len() > MAX_TABS => {
+                    warn!("WebSocket: Rejecting updateRecentlyClosed with {} tabs", closed.tabs.len());
+                    WebSocketMessage::response_error(id, 413, format!("Too many tabs ({}, at most {})", closed.tabs.len(), MAX_TABS))
+                }
+                Some(Ok(closed)) => {
+                    info!("WebSocket: UpdateRecentlyClosed request, {} tabs", closed.tabs.len());
+                    tab_manager.update_recently_closed(closed.tabs);
+                    WebSocketMessage::response_ok(id, ResponseResult::Success { success: true })
+                }
+                Some(Err(_)) => WebSocketMessage::response_error(id, 400, "Invalid updateRecentlyClosed params".to_string()),
+                None => WebSocketMessage::response_error(id, 400, "Missing params for updateRecentlyClosed".to_string()),
+            }
+        }
+        
+        // リーディングリスト（接続時と、項目が変わったときに届く）
+        "updateReadingList" => {
+            match params.map(serde_json::from_value::<ReadingListParams>) {
+                Some(Ok(reading_list)) if reading_list.entries.len() > MAX_TABS => {
+                    warn!("WebSocket: Rejecting updateReadingList with {} entries", reading_list.entries.len());
+                    WebSocketMessage::response_error(id, 413, format!("Too many entries ({}, at most {})", reading_list.entries.len(), MAX_TABS))
+                }
+                Some(Ok(reading_list)) => {
+                    info!("WebSocket: UpdateReadingList request, {} entries", reading_list.entries.len());
+                    tab_manager.update_reading_list(reading_list.entries);
+                    WebSocketMessage::response_ok(id, ResponseResult::Success { success: true })
+                }
+                Some(Err(_)) => WebSocketMessage::response_error(id, 400, "Invalid updateReadingList params".to_string()),
+                None => WebSocketMessage::response_error(id, 400, "Missing params for updateReadingList".to_string()),
+            }
+        }
+        
+        // 差分の更新（全体の一覧は updateTabs で定期的に送られ、ずれを直す）
+        "tabCreated" | "tabUpdated" => {
+            match params.map(serde_json::from_value::<TabParams>) {
+                Some(Ok(tab_params)) => {
+                    debug!("WebSocket: {} request, tab_id={}", method, tab_params.tab.id);
+                    tab_manager.upsert_tab(tab_params.tab);
+                    WebSocketMessage::response_ok(id, ResponseResult::Success { success: true })
+                }
+                Some(Err(_)) => WebSocketMessage::response_error(id, 400, format!("Invalid {} params", method)),
+                None => WebSocketMessage::response_error(id, 400, format!("Missing params for {}", method)),
+            }
+        }
+        
+        "tabRemoved" => {
+            match params.map(serde_json::from_value::<TabRemovedParams>) {
+                Some(Ok(removed)) => {
+                    debug!("WebSocket: TabRemoved request, tab_id={}", removed.tab_id);
+                    tab_manager.remove_tab(removed.tab_id);
+                    WebSocketMessage::response_ok(id, ResponseResult::Success { success: true })
+                }
+                Some(Err(_)) => WebSocketMessage::response_error(id, 400, "Invalid tabRemoved params".to_string()),
+                None => WebSocketMessage::response_error(id, 400, "Missing params for tabRemoved".to_string()),
+            }
+        }
+        
+        "tabMoved" => {
+            match params.map(serde_json::from_value::<TabMovedParams>) {
+                Some(Ok(moved)) => {
+                    debug!("WebSocket: TabMoved request, tab_id={}, window_id={}, index={}", moved.tab_id, moved.window_id, moved.index);
+                    tab_manager.move_tab(moved.tab_id, moved.window_id, moved.index);
+                    WebSocketMessage::response_ok(id, ResponseResult::Success { success: true })
+                }
+                Some(Err(_)) => WebSocketMessage::response_error(id, 400, "Invalid tabMoved params".to_string()),
+                None => WebSocketMessage::response_error(id, 400, "Missing params for tabMoved".to_string()),
+            }
+        }
+        
         "switchToTab" => {
             if let Some(params) = params {
                 if let Ok(switch_params) = serde_json::from_value::<SwitchTabParams>(params) {
@@ -216,6 +464,7 @@ async fn handle_request(
         
         "keepAlive" => {
             debug!("WebSocket: KeepAlive request");
+            tab_manager.heartbeat();
             let timestamp = chrono::Utc::now().timestamp_millis();
             WebSocketMessage::response_ok(id, ResponseResult::Pong { timestamp })
         }
@@ -227,11 +476,46 @@ async fn handle_request(
     }
 }
 
+#[derive(Debug, Default, Deserialize)]
+struct HelloParams {
+    #[serde(default)]
+    browser: Option<String>,
+    #[serde(default)]
+    profile: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct UpdateTabsParams {
     tabs: Vec<ChromeTab>,
 }
 
+#[derive(Debug, Deserialize)]
+struct RecentlyClosedParams {
+    tabs: Vec<ClosedTab>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadingListParams {
+    entries: Vec<ReadingListEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TabParams {
+    tab: ChromeTab,
+}
+
+#[derive(Debug, Deserialize)]
+struct TabRemovedParams {
+    tab_id: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct TabMovedParams {
+    tab_id: i32,
+    window_id: i32,
+    index: i32,
+}
+
 #[derive(Debug, Deserialize)]
 struct SwitchTabParams {
     tab_id: i32,
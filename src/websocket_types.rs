@@ -46,14 +46,36 @@ pub struct ErrorInfo {
 #[serde(rename_all = "camelCase")]
 pub enum EventType {
     TabSwitchRequested,
+    TabCloseRequested,
+    BookmarkCreateRequested,
+    BookmarkDeleteRequested,
+    BookmarkRenameRequested,
+    TabsCloseRequested,
+    TabsMoveRequested,
     TabsUpdated,
+    /// 全部のタブ一覧を送り直してもらう
+    TabsRefreshRequested,
+    /// 最近閉じたタブの一覧を送ってもらう
+    RecentlyClosedRequested,
+    /// 閉じたタブを開き直す
+    ClosedTabRestoreRequested,
+    /// ランチャーから切断した（Close のコードを送れない Native Messaging で、しばらく接続し直さないよう伝える）
+    DisconnectRequested,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum EventData {
     TabSwitch { tab_id: i32, window_id: i32 },
+    TabClose { tab_id: i32 },
+    BookmarkCreate { url: String, title: String, folder: Option<String> },
+    BookmarkDelete { id: String },
+    BookmarkRename { url: String, title: String },
+    TabIds { tab_ids: Vec<i32> },
     TabsUpdate { tabs: Vec<ChromeTab> },
+    ClosedTabRestore { session_id: String },
+    /// データのないイベント（`{}`）
+    Empty {},
 }
 
 impl WebSocketMessage {
@@ -2,6 +2,9 @@ use egui::TextureHandle;
 #[cfg(windows)]
 use egui::ColorImage;
 use std::collections::HashMap;
+use std::time::Duration;
+#[cfg(windows)]
+use std::time::Instant;
 
 #[cfg(windows)]
 use winapi::{
@@ -18,15 +21,40 @@ use winapi::{
 
 pub struct ThumbnailCache {
     textures: HashMap<isize, TextureHandle>,
+    /// 前回の `take_capture_time` 以降にキャプチャにかかった時間
+    capture_time: Duration,
+    /// 前回の `take_missing` 以降にキャプチャできなかったサムネイルがあるか
+    missing: bool,
+    /// 省電力時などにキャプチャを止める
+    #[cfg_attr(not(windows), allow(dead_code))]
+    enabled: bool,
 }
 
 impl ThumbnailCache {
     pub fn new() -> Self {
         Self {
             textures: HashMap::new(),
+            capture_time: Duration::ZERO,
+            missing: false,
+            enabled: true,
         }
     }
     
+    /// キャプチャの有無を切り替える（無効の間はプレースホルダーを表示する）
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+    
+    /// キャプチャできなかったサムネイルがあったか（呼び出すとリセットされる。あれば少し待って再描画する）
+    pub fn take_missing(&mut self) -> bool {
+        std::mem::take(&mut self.missing)
+    }
+    
+    /// キャプチャにかかった時間を取り出してリセットする（フレームごとの計測用）
+    pub fn take_capture_time(&mut self) -> Duration {
+        std::mem::take(&mut self.capture_time)
+    }
+    
     #[cfg(windows)]
     pub fn get_or_create_thumbnail(
         &mut self,
@@ -34,11 +62,20 @@ impl ThumbnailCache {
         hwnd: isize,
         size: (u32, u32),
     ) -> Option<&TextureHandle> {
+        if !self.enabled {
+            return None;
+        }
         if self.textures.contains_key(&hwnd) {
             return self.textures.get(&hwnd);
         }
         
-        let image = capture_window_thumbnail(hwnd, size)?;
+        let started = Instant::now();
+        let image = capture_window_thumbnail(hwnd, size);
+        self.capture_time += started.elapsed();
+        let Some(image) = image else {
+            self.missing = true;
+            return None;
+        };
         let texture = ctx.load_texture(
             format!("window_{}", hwnd),
             image,
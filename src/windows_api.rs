@@ -1,54 +1,155 @@
 #[cfg(windows)]
 use winapi::{
     shared::{
-        minwindef::{BOOL, LPARAM, TRUE},
+        minwindef::{BOOL, FILETIME, LPARAM, TRUE},
+        ntdef::{NTSTATUS, UNICODE_STRING},
+        winerror::ERROR_ACCESS_DENIED,
         windef::{HWND, RECT},
     },
     um::{
         dwmapi::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS},
-        processthreadsapi::OpenProcess,
+        errhandlingapi::GetLastError,
+        handleapi::CloseHandle,
+        memoryapi::ReadProcessMemory,
+        processthreadsapi::{GetCurrentProcess, GetProcessTimes, OpenProcess, OpenProcessToken},
         psapi::GetModuleFileNameExW,
-        winnt::{PROCESS_QUERY_INFORMATION, PROCESS_VM_READ},
+        securitybaseapi::GetTokenInformation,
+        shellapi::ShellExecuteW,
+        winnt::{
+            TokenElevation, HANDLE, PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION,
+            PROCESS_VM_READ, TOKEN_ELEVATION, TOKEN_QUERY,
+        },
         winuser::{
-            EnumWindows, GetClassNameW, GetWindowLongPtrW, GetWindowTextW, GetWindowThreadProcessId,
+            EnumChildWindows, EnumWindows, GetClassNameW, GetWindowLongPtrW, GetWindowTextW, GetWindowThreadProcessId,
             IsIconic, IsWindowVisible, SetForegroundWindow, ShowWindow, GWL_STYLE, GWL_EXSTYLE, SW_RESTORE,
-            WS_EX_TOOLWINDOW, WS_EX_APPWINDOW, GetWindow, GW_OWNER, WS_VISIBLE,
+            WS_EX_TOOLWINDOW, WS_EX_APPWINDOW, GetWindow, GW_OWNER, WS_VISIBLE, SW_SHOWNORMAL,
+            GetWindowRect, IsHungAppWindow, IsZoomed, SetWindowPos, SW_MAXIMIZE, SW_MINIMIZE, SWP_NOACTIVATE, SWP_NOZORDER,
         },
     },
 };
+use std::collections::HashMap;
+#[cfg(windows)]
+use std::sync::Mutex;
 #[cfg(windows)]
 use std::ffi::OsString;
 #[cfg(windows)]
 use std::os::windows::ffi::OsStringExt;
 #[cfg(windows)]
+use std::os::windows::ffi::OsStrExt;
+#[cfg(windows)]
 use std::ptr;
 
+#[cfg(windows)]
+lazy_static::lazy_static! {
+    static ref CURRENT_PROCESS_ELEVATED: bool = unsafe {
+        token_is_elevated(GetCurrentProcess()).unwrap_or(false)
+    };
+    static ref PROCESS_CACHE: Mutex<ProcessCache<ProcessDetails>> = Mutex::new(ProcessCache::new());
+}
+
+/// PIDをキーにしたプロセス情報のキャッシュ
+///
+/// PIDは再利用されるため、プロセスの作成時刻が変わっていたら情報を取り直す。
+pub struct ProcessCache<T> {
+    entries: HashMap<u32, (u64, T)>,
+}
+
+impl<T: Clone> ProcessCache<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// キャッシュ済みで作成時刻が一致すればそれを返し、そうでなければ `resolve` で取得する
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, pid: u32, creation_time: u64, resolve: F) -> T {
+        match self.entries.get(&pid) {
+            Some((cached_time, value)) if *cached_time == creation_time => value.clone(),
+            _ => {
+                let value = resolve();
+                self.entries.insert(pid, (creation_time, value.clone()));
+                value
+            }
+        }
+    }
+
+    /// 条件を満たすPIDだけを残す
+    pub fn retain<F: Fn(u32) -> bool>(&mut self, keep: F) {
+        self.entries.retain(|pid, _| keep(*pid));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<T: Clone> Default for ProcessCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 use crate::core::WindowInfo;
 
+/// 1回の列挙の間の状態
+#[cfg(windows)]
+struct EnumState {
+    windows: Vec<WindowInfo>,
+    /// PIDごとのプロセス情報（同じプロセスの複数ウィンドウで再取得しない）
+    processes: HashMap<u32, ProcessDetails>,
+}
+
+#[cfg(windows)]
+#[derive(Clone)]
+struct ProcessDetails {
+    name: String,
+    requires_elevation: bool,
+}
+
 #[cfg(windows)]
 pub fn enumerate_windows() -> Vec<WindowInfo> {
     unsafe {
-        let mut windows = Vec::new();
-        let windows_ptr = &mut windows as *mut Vec<WindowInfo>;
+        let mut state = EnumState {
+            windows: Vec::new(),
+            processes: HashMap::new(),
+        };
+        let state_ptr = &mut state as *mut EnumState;
         
         log::debug!("Starting window enumeration...");
-        EnumWindows(Some(enum_window_callback), windows_ptr as LPARAM);
-        log::debug!("Found {} windows after enumeration", windows.len());
+        EnumWindows(Some(enum_window_callback), state_ptr as LPARAM);
+        log::debug!(
+            "Found {} windows from {} processes after enumeration",
+            state.windows.len(),
+            state.processes.len()
+        );
+        
+        // 終了したプロセスのエントリを捨てる
+        PROCESS_CACHE.lock().unwrap().retain(|pid| state.processes.contains_key(&pid));
         
         // No additional filtering needed - is_taskbar_window already filtered
-        windows
+        state.windows
     }
 }
 
 #[cfg(windows)]
 unsafe extern "system" fn enum_window_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
-    let windows = &mut *(lparam as *mut Vec<WindowInfo>);
+    let state = &mut *(lparam as *mut EnumState);
     
     // Check if window is visible
     if IsWindowVisible(hwnd) == 0 {
         return TRUE;
     }
     
+    // 応答しないウィンドウは飛ばす（問い合わせると列挙全体が止まる）
+    if IsHungAppWindow(hwnd) != 0 {
+        log::debug!("Skipping hung window {:?}", hwnd);
+        return TRUE;
+    }
+    
     // Check if this is a main window that should appear in taskbar
     if !is_taskbar_window(hwnd) {
         return TRUE;
@@ -73,7 +174,19 @@ unsafe extern "system" fn enum_window_callback(hwnd: HWND, lparam: LPARAM) -> BO
     let mut process_id = 0;
     GetWindowThreadProcessId(hwnd, &mut process_id);
     
-    let process_name = get_process_name(process_id).unwrap_or_default();
+    // UWPアプリはApplicationFrameHost.exeがホストしているので、実際のアプリのプロセスを使う
+    let is_uwp_frame = class_name == UWP_FRAME_CLASS;
+    if is_uwp_frame {
+        if let Some(hosted_id) = find_uwp_hosted_process(hwnd, process_id) {
+            process_id = hosted_id;
+        }
+    }
+    
+    let process = state
+        .processes
+        .entry(process_id)
+        .or_insert_with(|| lookup_process(process_id, is_uwp_frame))
+        .clone();
     
     let mut rect = RECT {
         left: 0,
@@ -93,7 +206,7 @@ unsafe extern "system" fn enum_window_callback(hwnd: HWND, lparam: LPARAM) -> BO
         hwnd: hwnd as isize,
         title,
         class_name,
-        process_name,
+        process_name: process.name,
         is_visible: true,
         is_minimized: IsIconic(hwnd) == TRUE,
         rect: (
@@ -102,14 +215,145 @@ unsafe extern "system" fn enum_window_callback(hwnd: HWND, lparam: LPARAM) -> BO
             rect.right - rect.left,
             rect.bottom - rect.top,
         ),
+        requires_elevation: process.requires_elevation,
     };
     
-    windows.push(window_info);
+    state.windows.push(window_info);
     TRUE
 }
 
+/// プロセス情報を取得（作成時刻が変わっていなければキャッシュを使う）
+#[cfg(windows)]
+unsafe fn lookup_process(process_id: u32, is_uwp: bool) -> ProcessDetails {
+    let resolve = || ProcessDetails {
+        name: is_uwp
+            .then(|| get_app_user_model_id(process_id))
+            .flatten()
+            .and_then(|aumid| app_name_from_aumid(&aumid))
+            .or_else(|| get_process_name(process_id))
+            .unwrap_or_default(),
+        // ランチャーが非昇格で相手が昇格プロセスの場合はサムネイル取得や切り替えができない
+        requires_elevation: !*CURRENT_PROCESS_ELEVATED && is_process_elevated(process_id),
+    };
+    
+    match process_creation_time(process_id) {
+        Some(creation_time) => PROCESS_CACHE
+            .lock()
+            .unwrap()
+            .get_or_insert_with(process_id, creation_time, resolve),
+        None => resolve(),
+    }
+}
+
+/// UWPアプリのウィンドウを包むフレームのクラス名
+#[cfg(windows)]
+const UWP_FRAME_CLASS: &str = "ApplicationFrameWindow";
+
+/// フレームの子ウィンドウから、ホストされているUWPアプリのプロセスIDを探す
+///
+/// 最小化中は子ウィンドウ（CoreWindow）がフレームから外れるため見つからないことがある。
+#[cfg(windows)]
+unsafe fn find_uwp_hosted_process(frame_hwnd: HWND, frame_process_id: u32) -> Option<u32> {
+    // (フレームのPID, 見つかったPID)
+    let mut search = (frame_process_id, 0u32);
+    EnumChildWindows(frame_hwnd, Some(enum_uwp_child_callback), &mut search as *mut (u32, u32) as LPARAM);
+    
+    if search.1 == 0 {
+        None
+    } else {
+        Some(search.1)
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn enum_uwp_child_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let search = &mut *(lparam as *mut (u32, u32));
+    
+    let mut child_process_id = 0;
+    GetWindowThreadProcessId(hwnd, &mut child_process_id);
+    if child_process_id != 0 && child_process_id != search.0 {
+        search.1 = child_process_id;
+        return 0; // 見つかったので列挙を終了
+    }
+    
+    TRUE
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetApplicationUserModelId(process: HANDLE, length: *mut u32, application_user_model_id: *mut u16) -> i32;
+}
+
+/// パッケージアプリの AppUserModelID（例: `Microsoft.WindowsCalculator_8wekyb3d8bbwe!App`）
+#[cfg(windows)]
+unsafe fn get_app_user_model_id(process_id: u32) -> Option<String> {
+    let process_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+    if process_handle.is_null() {
+        return None;
+    }
+    
+    let mut buffer = [0u16; 256];
+    let mut length = buffer.len() as u32;
+    let result = GetApplicationUserModelId(process_handle, &mut length, buffer.as_mut_ptr());
+    CloseHandle(process_handle);
+    
+    // ERROR_SUCCESS 以外（パッケージアプリでない場合など）
+    if result != 0 || length == 0 {
+        return None;
+    }
+    
+    // length は終端のNULを含む
+    let id = OsString::from_wide(&buffer[..(length as usize - 1).min(buffer.len())])
+        .to_string_lossy()
+        .to_string();
+    Some(id)
+}
+
+/// AppUserModelID から表示用のアプリ名を取り出す
+///
+/// `Microsoft.WindowsCalculator_8wekyb3d8bbwe!App` → `WindowsCalculator`
+pub fn app_name_from_aumid(aumid: &str) -> Option<String> {
+    let package_family = aumid.split('!').next()?;
+    let package_name = package_family.split('_').next()?;
+    let name = package_name.rsplit('.').next()?;
+    
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// プロセスの作成時刻（FILETIME を u64 にしたもの）
+#[cfg(windows)]
+unsafe fn process_creation_time(process_id: u32) -> Option<u64> {
+    let process_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+    if process_handle.is_null() {
+        return None;
+    }
+    
+    let mut creation: FILETIME = std::mem::zeroed();
+    let mut exit: FILETIME = std::mem::zeroed();
+    let mut kernel: FILETIME = std::mem::zeroed();
+    let mut user: FILETIME = std::mem::zeroed();
+    let result = GetProcessTimes(process_handle, &mut creation, &mut exit, &mut kernel, &mut user);
+    CloseHandle(process_handle);
+    
+    if result == 0 {
+        None
+    } else {
+        Some(((creation.dwHighDateTime as u64) << 32) | creation.dwLowDateTime as u64)
+    }
+}
+
 #[cfg(windows)]
 unsafe fn get_process_name(process_id: u32) -> Option<String> {
+    get_process_path(process_id).and_then(|path| path.split('\\').last().map(|s| s.to_string()))
+}
+
+#[cfg(windows)]
+unsafe fn get_process_path(process_id: u32) -> Option<String> {
     let process_handle = OpenProcess(
         PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
         0,
@@ -128,17 +372,391 @@ unsafe fn get_process_name(process_id: u32) -> Option<String> {
         1024,
     );
     
-    winapi::um::handleapi::CloseHandle(process_handle);
+    CloseHandle(process_handle);
     
     if len == 0 {
         return None;
     }
     
-    let path = OsString::from_wide(&filename[..len as usize])
+    Some(OsString::from_wide(&filename[..len as usize])
         .to_string_lossy()
-        .to_string();
+        .to_string())
+}
+
+/// ウィンドウを所有するプロセスの実行ファイルのフルパス
+#[cfg(windows)]
+pub fn process_path_for_window(hwnd: isize) -> Option<String> {
+    unsafe {
+        let mut process_id = 0;
+        GetWindowThreadProcessId(hwnd as HWND, &mut process_id);
+        if process_id == 0 {
+            return None;
+        }
+        get_process_path(process_id)
+    }
+}
+
+// winapi 0.3 には `winternl` がないので ntdll から直接宣言する
+#[cfg(windows)]
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtQueryInformationProcess(
+        process_handle: HANDLE,
+        process_information_class: u32,
+        process_information: *mut std::ffi::c_void,
+        process_information_length: u32,
+        return_length: *mut u32,
+    ) -> NTSTATUS;
+}
+
+/// `NtQueryInformationProcess` でコマンドラインを取得する情報クラス（Windows 8.1 以降）
+#[cfg(windows)]
+const PROCESS_COMMAND_LINE_INFORMATION: u32 = 60;
+
+#[cfg(windows)]
+unsafe fn get_process_command_line(process_id: u32) -> Option<String> {
+    let process_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+    if process_handle.is_null() {
+        return None;
+    }
+
+    // 1回目で必要なサイズを調べる（UNICODE_STRING のあとに文字列が続く）
+    let mut length = 0u32;
+    NtQueryInformationProcess(process_handle, PROCESS_COMMAND_LINE_INFORMATION, ptr::null_mut(), 0, &mut length);
+    if length == 0 {
+        CloseHandle(process_handle);
+        return None;
+    }
+    let mut buffer = vec![0u64; (length as usize + 7) / 8];
+    let status = NtQueryInformationProcess(
+        process_handle,
+        PROCESS_COMMAND_LINE_INFORMATION,
+        buffer.as_mut_ptr() as *mut _,
+        length,
+        &mut length,
+    );
+    CloseHandle(process_handle);
+    if status < 0 {
+        return None;
+    }
+
+    let command_line = &*(buffer.as_ptr() as *const UNICODE_STRING);
+    if command_line.Buffer.is_null() || command_line.Length == 0 {
+        return None;
+    }
+    let chars = std::slice::from_raw_parts(command_line.Buffer, command_line.Length as usize / 2);
+    Some(OsString::from_wide(chars).to_string_lossy().to_string())
+}
+
+/// ウィンドウを所有するプロセスのコマンドライン（実行ファイルと引数）
+#[cfg(windows)]
+pub fn process_command_line_for_window(hwnd: isize) -> Option<String> {
+    unsafe {
+        let mut process_id = 0;
+        GetWindowThreadProcessId(hwnd as HWND, &mut process_id);
+        if process_id == 0 {
+            return None;
+        }
+        get_process_command_line(process_id)
+    }
+}
+
+/// 移動したあとのウィンドウの表示状態
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShowState {
+    #[default]
+    Normal,
+    Minimized,
+    Maximized,
+}
+
+/// プロセスの起動時の情報（詳細のポップアップで、同じエディタやターミナルの複数のウィンドウを見分ける）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessLaunchInfo {
+    pub command_line: Option<String>,
+    /// 今の作業フォルダ
+    pub current_dir: Option<String>,
+}
+
+/// `NtQueryInformationProcess` で PEB の位置を取得する情報クラス
+#[cfg(windows)]
+const PROCESS_BASIC_INFORMATION_CLASS: u32 = 0;
+
+/// `PROCESS_BASIC_INFORMATION`（winapi には宣言がない）
+#[cfg(windows)]
+#[repr(C)]
+#[allow(dead_code)] // 使わないフィールドも並びを合わせるために置く
+struct ProcessBasicInfo {
+    exit_status: i32,
+    peb_base_address: *mut std::ffi::c_void,
+    affinity_mask: usize,
+    base_priority: i32,
+    unique_process_id: usize,
+    inherited_from_unique_process_id: usize,
+}
+
+/// PEB の先頭から `ProcessParameters` まで（それより後ろは読まない）
+#[cfg(windows)]
+#[repr(C)]
+#[allow(dead_code)] // 使わないフィールドも並びを合わせるために置く
+struct PebPrefix {
+    reserved: [u8; 4],
+    reserved_pointers: [*mut std::ffi::c_void; 2],
+    ldr: *mut std::ffi::c_void,
+    process_parameters: *mut std::ffi::c_void,
+}
+
+/// `RTL_USER_PROCESS_PARAMETERS` の `CurrentDirectory.DosPath` の位置（公開されていない部分なので固定値）
+#[cfg(all(windows, target_pointer_width = "64"))]
+const CURRENT_DIRECTORY_OFFSET: usize = 0x38;
+#[cfg(all(windows, target_pointer_width = "32"))]
+const CURRENT_DIRECTORY_OFFSET: usize = 0x24;
+
+/// 別のプロセスのメモリから値を読む
+#[cfg(windows)]
+unsafe fn read_remote<T>(process_handle: HANDLE, address: usize) -> Option<T> {
+    let mut value: T = std::mem::zeroed();
+    let mut read = 0;
+    let ok = ReadProcessMemory(
+        process_handle,
+        address as *const _,
+        &mut value as *mut T as *mut _,
+        std::mem::size_of::<T>(),
+        &mut read,
+    );
+    (ok != 0 && read == std::mem::size_of::<T>()).then_some(value)
+}
+
+/// PEB のプロセスパラメータから作業フォルダを読む
+///
+/// 昇格したプロセスなど、`PROCESS_VM_READ` で開けないプロセスは `None`。
+#[cfg(windows)]
+unsafe fn get_process_current_dir(process_id: u32) -> Option<String> {
+    let process_handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, process_id);
+    if process_handle.is_null() {
+        return None;
+    }
+    let dir = read_current_dir(process_handle);
+    CloseHandle(process_handle);
+    dir
+}
+
+#[cfg(windows)]
+unsafe fn read_current_dir(process_handle: HANDLE) -> Option<String> {
+    let mut info: ProcessBasicInfo = std::mem::zeroed();
+    let status = NtQueryInformationProcess(
+        process_handle,
+        PROCESS_BASIC_INFORMATION_CLASS,
+        &mut info as *mut _ as *mut _,
+        std::mem::size_of::<ProcessBasicInfo>() as u32,
+        ptr::null_mut(),
+    );
+    if status < 0 || info.peb_base_address.is_null() {
+        return None;
+    }
+    let peb: PebPrefix = read_remote(process_handle, info.peb_base_address as usize)?;
+    if peb.process_parameters.is_null() {
+        return None;
+    }
+    let dir: UNICODE_STRING = read_remote(process_handle, peb.process_parameters as usize + CURRENT_DIRECTORY_OFFSET)?;
+    if dir.Buffer.is_null() || dir.Length == 0 {
+        return None;
+    }
+    let mut chars = vec![0u16; dir.Length as usize / 2];
+    let ok = ReadProcessMemory(
+        process_handle,
+        dir.Buffer as *const _,
+        chars.as_mut_ptr() as *mut _,
+        chars.len() * 2,
+        ptr::null_mut(),
+    );
+    if ok == 0 {
+        return None;
+    }
+    Some(trim_current_dir(&OsString::from_wide(&chars).to_string_lossy()).to_string())
+}
+
+/// 作業フォルダの末尾の `\` を取る（ドライブのルートはそのまま）
+pub fn trim_current_dir(dir: &str) -> &str {
+    let trimmed = dir.trim_end_matches('\\');
+    if trimmed.ends_with(':') && dir.len() > trimmed.len() {
+        &dir[..trimmed.len() + 1]
+    } else {
+        trimmed
+    }
+}
+
+/// ウィンドウを所有するプロセスのコマンドラインと作業フォルダ
+#[cfg(windows)]
+pub fn process_launch_info_for_window(hwnd: isize) -> ProcessLaunchInfo {
+    unsafe {
+        let mut process_id = 0;
+        GetWindowThreadProcessId(hwnd as HWND, &mut process_id);
+        if process_id == 0 {
+            return ProcessLaunchInfo::default();
+        }
+        ProcessLaunchInfo {
+            command_line: get_process_command_line(process_id),
+            current_dir: get_process_current_dir(process_id),
+        }
+    }
+}
+
+/// ウィンドウを指定した位置とサイズに移動する
+///
+/// `rect` は `enumerate_windows` と同じく見た目の枠（DWMの拡張フレーム）の座標で指定する。
+/// `SetWindowPos` は見えない枠を含む座標を使うため、その差を補正する。
+#[cfg(windows)]
+pub fn move_window(hwnd: isize, rect: (i32, i32, i32, i32), show: ShowState) -> bool {
+    unsafe {
+        let hwnd = hwnd as HWND;
+        
+        // 最小化・最大化されていると位置を変えられないので元に戻す
+        if IsIconic(hwnd) == TRUE || IsZoomed(hwnd) == TRUE {
+            ShowWindow(hwnd, SW_RESTORE);
+        }
+        
+        let mut window_rect: RECT = std::mem::zeroed();
+        let mut frame_rect: RECT = std::mem::zeroed();
+        GetWindowRect(hwnd, &mut window_rect);
+        let has_frame = DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut frame_rect as *mut _ as *mut _,
+            std::mem::size_of::<RECT>() as u32,
+        ) == 0;
+        
+        let (left, top, right, bottom) = if has_frame {
+            (
+                frame_rect.left - window_rect.left,
+                frame_rect.top - window_rect.top,
+                window_rect.right - frame_rect.right,
+                window_rect.bottom - frame_rect.bottom,
+            )
+        } else {
+            (0, 0, 0, 0)
+        };
+        
+        let (x, y, width, height) = rect;
+        let moved = SetWindowPos(
+            hwnd,
+            ptr::null_mut(),
+            x - left,
+            y - top,
+            width + left + right,
+            height + top + bottom,
+            SWP_NOZORDER | SWP_NOACTIVATE,
+        ) != 0;
+        
+        // 最大化は移動先のモニターで行う
+        match show {
+            ShowState::Normal => {}
+            ShowState::Minimized => {
+                ShowWindow(hwnd, SW_MINIMIZE);
+            }
+            ShowState::Maximized => {
+                ShowWindow(hwnd, SW_MAXIMIZE);
+            }
+        }
+        moved
+    }
+}
+
+/// ウィンドウが最大化されているか
+#[cfg(windows)]
+pub fn is_window_maximized(hwnd: isize) -> bool {
+    unsafe { IsZoomed(hwnd as HWND) == TRUE }
+}
+
+/// プロセスのトークンが昇格されているか
+#[cfg(windows)]
+unsafe fn token_is_elevated(process_handle: HANDLE) -> Option<bool> {
+    let mut token: HANDLE = ptr::null_mut();
+    if OpenProcessToken(process_handle, TOKEN_QUERY, &mut token) == 0 {
+        return None;
+    }
+    
+    let mut elevation: TOKEN_ELEVATION = std::mem::zeroed();
+    let mut size = 0u32;
+    let result = GetTokenInformation(
+        token,
+        TokenElevation,
+        &mut elevation as *mut _ as *mut _,
+        std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+        &mut size,
+    );
+    CloseHandle(token);
     
-    path.split('\\').last().map(|s| s.to_string())
+    if result == 0 {
+        None
+    } else {
+        Some(elevation.TokenIsElevated != 0)
+    }
+}
+
+/// プロセスが昇格されているか（アクセスが拒否された場合も昇格とみなす）
+#[cfg(windows)]
+unsafe fn is_process_elevated(process_id: u32) -> bool {
+    let process_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+    if process_handle.is_null() {
+        return GetLastError() == ERROR_ACCESS_DENIED;
+    }
+    
+    let elevated = token_is_elevated(process_handle).unwrap_or(true);
+    CloseHandle(process_handle);
+    elevated
+}
+
+/// ランチャー自身が管理者権限で動作しているか
+#[cfg(windows)]
+pub fn is_current_process_elevated() -> bool {
+    *CURRENT_PROCESS_ELEVATED
+}
+
+/// 管理者権限で自分自身を再起動する（UACプロンプトが表示される）
+#[cfg(windows)]
+pub fn relaunch_elevated(args: &[String]) -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let params = args
+        .iter()
+        .map(|arg| format!("\"{}\"", arg.replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    run_elevated(exe.as_os_str(), &params)
+}
+
+/// 管理者権限でプログラムを起動する（UACプロンプトが表示される）
+#[cfg(windows)]
+pub fn run_elevated(program: impl AsRef<std::ffi::OsStr>, params: &str) -> Result<(), String> {
+    let to_wide = |s: &std::ffi::OsStr| s.encode_wide().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let verb = to_wide("runas".as_ref());
+    let file = to_wide(program.as_ref());
+    let params = to_wide(params.as_ref());
+    
+    let result = unsafe {
+        ShellExecuteW(
+            ptr::null_mut(),
+            verb.as_ptr(),
+            file.as_ptr(),
+            params.as_ptr(),
+            ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    
+    // 32以下はエラー（UACでキャンセルされた場合を含む）
+    if result as isize > 32 {
+        Ok(())
+    } else {
+        Err(format!("ShellExecuteW failed with code {}", result as isize))
+    }
+}
+
+/// 前面にあるウィンドウ
+#[cfg(windows)]
+pub fn foreground_window() -> Option<isize> {
+    let hwnd = unsafe { winapi::um::winuser::GetForegroundWindow() };
+    (!hwnd.is_null()).then_some(hwnd as isize)
 }
 
 #[cfg(windows)]
@@ -162,6 +780,51 @@ pub fn enumerate_windows() -> Vec<WindowInfo> {
 #[cfg(not(windows))]
 pub fn switch_to_window(_hwnd: isize) {}
 
+#[cfg(not(windows))]
+pub fn process_path_for_window(_hwnd: isize) -> Option<String> {
+    None
+}
+
+#[cfg(not(windows))]
+pub fn process_command_line_for_window(_hwnd: isize) -> Option<String> {
+    None
+}
+
+#[cfg(not(windows))]
+pub fn process_launch_info_for_window(_hwnd: isize) -> ProcessLaunchInfo {
+    ProcessLaunchInfo::default()
+}
+
+#[cfg(not(windows))]
+pub fn move_window(_hwnd: isize, _rect: (i32, i32, i32, i32), _show: ShowState) -> bool {
+    false
+}
+
+#[cfg(not(windows))]
+pub fn is_window_maximized(_hwnd: isize) -> bool {
+    false
+}
+
+#[cfg(not(windows))]
+pub fn is_current_process_elevated() -> bool {
+    false
+}
+
+#[cfg(not(windows))]
+pub fn relaunch_elevated(_args: &[String]) -> Result<(), String> {
+    Err("Elevation is only supported on Windows".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn run_elevated(_program: impl AsRef<std::ffi::OsStr>, _params: &str) -> Result<(), String> {
+    Err("Elevation is only supported on Windows".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn foreground_window() -> Option<isize> {
+    None
+}
+
 #[cfg(windows)]
 unsafe fn is_taskbar_window(hwnd: HWND) -> bool {
     // Get window styles
@@ -197,4 +860,63 @@ unsafe fn is_taskbar_window(hwnd: HWND) -> bool {
     // - It's visible AND
     // - It either has WS_EX_APPWINDOW OR has no owner
     is_visible && (has_appwindow || has_no_owner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_cache_reuses_entry() {
+        let mut cache = ProcessCache::new();
+        let mut resolved = 0;
+        
+        cache.get_or_insert_with(100, 1, || { resolved += 1; "chrome.exe".to_string() });
+        let name = cache.get_or_insert_with(100, 1, || { resolved += 1; "other.exe".to_string() });
+        
+        assert_eq!(name, "chrome.exe");
+        assert_eq!(resolved, 1);
+    }
+
+    #[test]
+    fn test_process_cache_invalidates_reused_pid() {
+        let mut cache = ProcessCache::new();
+        cache.get_or_insert_with(100, 1, || "chrome.exe".to_string());
+        
+        // 同じPIDでも作成時刻が違えば別のプロセス
+        let name = cache.get_or_insert_with(100, 2, || "notepad.exe".to_string());
+        assert_eq!(name, "notepad.exe");
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_app_name_from_aumid() {
+        assert_eq!(
+            app_name_from_aumid("Microsoft.WindowsCalculator_8wekyb3d8bbwe!App").as_deref(),
+            Some("WindowsCalculator")
+        );
+        assert_eq!(
+            app_name_from_aumid("Microsoft.WindowsStore_8wekyb3d8bbwe!App").as_deref(),
+            Some("WindowsStore")
+        );
+        assert_eq!(app_name_from_aumid("SomeApp_abc123!App").as_deref(), Some("SomeApp"));
+        assert_eq!(app_name_from_aumid(""), None);
+    }
+
+    #[test]
+    fn test_trim_current_dir() {
+        assert_eq!(trim_current_dir(r"C:\Users\me\src\my-launcher\"), r"C:\Users\me\src\my-launcher");
+        assert_eq!(trim_current_dir(r"C:\"), r"C:\");
+        assert_eq!(trim_current_dir(r"\\server\share\dir"), r"\\server\share\dir");
+    }
+
+    #[test]
+    fn test_process_cache_retain() {
+        let mut cache = ProcessCache::new();
+        cache.get_or_insert_with(1, 1, || "a.exe".to_string());
+        cache.get_or_insert_with(2, 1, || "b.exe".to_string());
+        
+        cache.retain(|pid| pid == 2);
+        assert_eq!(cache.len(), 1);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+#![cfg(feature = "test-support")]
+
+use my_launcher::config::RankingConfig;
+use my_launcher::core::result_composer::ResultCategory;
+use my_launcher::core::{Action, ResultType, SearchEngine, SearchMode, SearchResult};
+use my_launcher::test_helpers::fixtures::{bookmark, browser_engine, history, tab};
+
+/// Google の行とコマンドを除いた、ブックマーク・履歴・タブの結果
+fn local_results(results: &[SearchResult]) -> Vec<(ResultType, String)> {
+    results
+        .iter()
+        .filter(|r| matches!(r.result_type, ResultType::Bookmark | ResultType::History | ResultType::Tab))
+        .map(|r| (r.result_type.clone(), r.title.clone()))
+        .collect()
+}
+
+#[test]
+fn test_browser_search_uses_fixtures() {
+    let (engine, _) = browser_engine(
+        vec![bookmark("Rust Book", "https://doc.rust-lang.org/book/"), bookmark("Python docs", "https://docs.python.org/")],
+        vec![
+            history("Rust Playground", "https://play.rust-lang.org/", 5, 10),
+            history("Rust Programming Language", "https://www.rust-lang.org/", 20, 60),
+        ],
+        vec![tab(1, 100, "Issues · rust-lang/rust", "https://github.com/rust-lang/rust/issues")],
+    );
+
+    let results = engine.search("rust", SearchMode::Browser, &[]);
+    assert_eq!(
+        local_results(&results),
+        vec![
+            (ResultType::Bookmark, "Rust Book".to_string()),
+            (ResultType::History, "Rust Programming Language".to_string()),
+            (ResultType::History, "Rust Playground".to_string()),
+            (ResultType::Tab, "Issues · rust-lang/rust".to_string()),
+        ]
+    );
+    assert!(results.iter().any(|r| r.action == Action::OpenBookmark("https://doc.rust-lang.org/book/".to_string())));
+
+    let tabs = engine.search("rust", SearchMode::Tabs, &[]);
+    let actions: Vec<&Action> = tabs.iter().filter(|r| r.result_type == ResultType::Tab).map(|r| &r.action).collect();
+    assert_eq!(actions, vec![&Action::SwitchToTab { tab_id: 1, window_id: 100 }]);
+}
+
+#[test]
+fn test_ranking_order() {
+    let (engine, _) = browser_engine(
+        vec![bookmark("Rust Book", "https://doc.rust-lang.org/book/")],
+        vec![history("Rust Playground", "https://play.rust-lang.org/", 5, 10)],
+        vec![tab(1, 100, "Rust Blog", "https://blog.rust-lang.org/")],
+    );
+    let engine = engine.with_ranking(RankingConfig {
+        order: vec![ResultCategory::Tab, ResultCategory::History, ResultCategory::Bookmark],
+        ..RankingConfig::default()
+    });
+
+    let titles: Vec<String> = local_results(&engine.search("rust", SearchMode::Browser, &[])).into_iter().map(|(_, title)| title).collect();
+    assert_eq!(titles, vec!["Rust Blog", "Rust Playground", "Rust Book"]);
+}
+
+#[test]
+fn test_close_duplicate_tabs_preview() {
+    let (engine, tab_manager) = browser_engine(
+        Vec::new(),
+        Vec::new(),
+        vec![
+            tab(1, 100, "Rust", "https://www.rust-lang.org/"),
+            tab(2, 100, "Rust", "https://www.rust-lang.org/"),
+            tab(3, 200, "Docs", "https://docs.rs/"),
+        ],
+    );
+
+    let preview = engine.preview(&Action::CloseDuplicateTabs, &[]).unwrap();
+    assert_eq!(preview.summary, "Close 1 duplicate tab(s)");
+    assert_eq!(preview.effects, vec!["Close: Rust"]);
+
+    tab_manager.update_tabs(vec![tab(3, 200, "Docs", "https://docs.rs/")]);
+    assert!(engine.preview(&Action::CloseDuplicateTabs, &[]).is_none());
+}
@@ -20,6 +20,7 @@ fn setup_test_launcher() -> (
             is_visible: true,
             is_minimized: false,
             rect: (0, 0, 1920, 1080),
+            requires_elevation: false,
         },
         WindowInfo {
             hwnd: 200,
@@ -29,6 +30,7 @@ fn setup_test_launcher() -> (
             is_visible: true,
             is_minimized: false,
             rect: (100, 100, 1600, 900),
+            requires_elevation: false,
         },
         WindowInfo {
             hwnd: 300,
@@ -38,6 +40,7 @@ fn setup_test_launcher() -> (
             is_visible: true,
             is_minimized: true,
             rect: (200, 200, 800, 600),
+            requires_elevation: false,
         },
     ];
 
@@ -147,6 +150,7 @@ fn test_window_refresh() {
             is_visible: true,
             is_minimized: false,
             rect: (0, 0, 800, 600),
+            requires_elevation: false,
         },
     ]);
     
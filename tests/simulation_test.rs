@@ -0,0 +1,178 @@
+#![cfg(all(feature = "test-support", feature = "websocket"))]
+
+use my_launcher::core::native_messaging::ChromeCommand;
+use my_launcher::core::{Action, ChromeTab, ResultType, SearchMode, WindowInfo};
+use my_launcher::data::browser_provider::mock::InMemoryBrowserProvider;
+use my_launcher::test_helpers::fixtures::{bookmark, history, tab};
+use my_launcher::test_helpers::simulation::Simulation;
+use my_launcher::websocket_types::WebSocketMessage;
+use serde_json::json;
+
+fn window(hwnd: isize, title: &str, process_name: &str) -> WindowInfo {
+    WindowInfo {
+        hwnd,
+        title: title.to_string(),
+        class_name: "Chrome_WidgetWin_1".to_string(),
+        process_name: process_name.to_string(),
+        is_visible: true,
+        is_minimized: false,
+        rect: (0, 0, 1920, 1080),
+        requires_elevation: false,
+    }
+}
+
+fn simulation() -> Simulation {
+    let browser = InMemoryBrowserProvider::new()
+        .with_bookmarks(vec![bookmark("Rust Book", "https://doc.rust-lang.org/book/")])
+        .with_history(vec![history("Rust Playground", "https://play.rust-lang.org/", 3, 5)]);
+    let sim = Simulation::new(
+        vec![window(100, "main.rs - my-launcher - Visual Studio Code", "Code.exe"), window(200, "Rust - Google Chrome", "chrome.exe")],
+        browser,
+    );
+    sim.connect_extension();
+    sim
+}
+
+fn assert_ok(response: Option<WebSocketMessage>) {
+    assert!(matches!(response, Some(WebSocketMessage::Response { error: None, .. })), "unexpected response: {:?}", response);
+}
+
+#[test]
+fn test_switch_to_tab_from_extension_update() {
+    let mut sim = simulation();
+    assert_ok(sim.request(
+        "updateTabs",
+        json!({ "tabs": [tab(1, 10, "Inbox", "https://mail.example.com/"), tab(2, 10, "Rust issues", "https://github.com/rust-lang/rust/issues")] }),
+    ));
+
+    let results = sim.search("rust", SearchMode::Tabs);
+    let selected = results.iter().find(|r| r.result_type == ResultType::Tab).unwrap();
+    assert_eq!(selected.title, "Rust issues");
+    assert!(sim.select(selected).is_none());
+
+    let commands = sim.take_commands();
+    assert!(matches!(commands.as_slice(), [ChromeCommand::SwitchToTab { tab_id: 2, window_id: 10 }]), "{:?}", commands);
+    assert!(sim.take_commands().is_empty());
+}
+
+#[test]
+fn test_tab_deltas_reach_search() {
+    let mut sim = simulation();
+    assert_ok(sim.request("updateTabs", json!({ "tabs": [tab(1, 10, "Inbox", "https://mail.example.com/")] })));
+    assert_ok(sim.request("tabCreated", json!({ "tab": tab(2, 10, "Rust blog", "https://blog.rust-lang.org/") })));
+
+    let titles = |sim: &mut Simulation| {
+        sim.search("", SearchMode::Tabs)
+            .into_iter()
+            .filter(|r| r.result_type == ResultType::Tab)
+            .map(|r| r.title)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(titles(&mut sim), vec!["Inbox", "Rust blog"]);
+
+    assert_ok(sim.request("tabRemoved", json!({ "tab_id": 1 })));
+    assert_eq!(titles(&mut sim), vec!["Rust blog"]);
+}
+
+#[test]
+fn test_restore_recently_closed_tab() {
+    let mut sim = simulation();
+    assert_ok(sim.request(
+        "updateRecentlyClosed",
+        json!({ "tabs": [
+            { "session_id": "42", "title": "Rust release notes", "url": "https://blog.rust-lang.org/releases/", "closed_at": 1700000000 },
+            { "session_id": "41", "title": "Inbox", "url": "https://mail.example.com/" },
+        ] }),
+    ));
+
+    let results = sim.search("rust release", SearchMode::Browser);
+    let closed = results.iter().find(|r| matches!(r.action, Action::RestoreClosedTab { .. })).unwrap();
+    assert_eq!(closed.title, "Rust release notes");
+    assert_eq!(closed.result_type, ResultType::History);
+    assert!(sim.select(closed).is_none());
+
+    let commands = sim.take_commands();
+    assert!(matches!(commands.as_slice(), [ChromeCommand::RestoreClosedTab { session_id }] if session_id == "42"), "{:?}", commands);
+}
+
+#[test]
+fn test_pinned_tabs_and_reading_list_rank_above_history() {
+    let mut sim = simulation();
+    assert_ok(sim.request(
+        "updateTabs",
+        json!({ "tabs": [
+            tab(1, 10, "Rust issues", "https://github.com/rust-lang/rust/issues"),
+            ChromeTab { pinned: true, ..tab(2, 10, "Rust Zulip", "https://rust-lang.zulipchat.com/") },
+        ] }),
+    ));
+    assert_ok(sim.request(
+        "updateReadingList",
+        json!({ "entries": [{ "title": "Rust 2024 edition guide", "url": "https://doc.rust-lang.org/edition-guide/", "has_been_read": false }] }),
+    ));
+
+    let types: Vec<ResultType> = sim
+        .search("rust", SearchMode::Browser)
+        .into_iter()
+        .map(|r| r.result_type)
+        .filter(|t| *t != ResultType::GoogleSearch)
+        .collect();
+    assert_eq!(
+        types,
+        vec![ResultType::PinnedTab, ResultType::ReadingList, ResultType::Bookmark, ResultType::History, ResultType::Tab]
+    );
+}
+
+#[test]
+fn test_close_duplicate_tabs_after_confirmation() {
+    let mut sim = simulation();
+    assert_ok(sim.request(
+        "updateTabs",
+        json!({ "tabs": [
+            tab(1, 10, "Rust", "https://www.rust-lang.org/"),
+            tab(2, 10, "Rust", "https://www.rust-lang.org/"),
+            tab(3, 20, "Docs", "https://docs.rs/"),
+        ] }),
+    ));
+
+    let results = sim.search("close dup", SearchMode::Browser);
+    let close = results.iter().find(|r| r.action == Action::CloseDuplicateTabs).unwrap();
+    let preview = sim.select(close).unwrap();
+    assert_eq!(preview.summary, "Close 1 duplicate tab(s)");
+    assert!(sim.take_commands().is_empty());
+
+    sim.confirm();
+    let commands = sim.take_commands();
+    assert!(matches!(commands.as_slice(), [ChromeCommand::CloseTab { tab_id: 2 }]), "{:?}", commands);
+}
+
+#[test]
+fn test_browser_and_window_results() {
+    let mut sim = simulation();
+
+    let results = sim.search("rust", SearchMode::Browser);
+    let book = results.iter().find(|r| r.result_type == ResultType::Bookmark).unwrap();
+    sim.select(book);
+    assert_eq!(sim.opened(), &[Action::OpenBookmark("https://doc.rust-lang.org/book/".to_string())]);
+
+    sim.set_windows(vec![window(300, "notes.md - my-launcher - Visual Studio Code", "Code.exe")]);
+    let results = sim.search("code", SearchMode::Windows);
+    assert_eq!(results.len(), 1);
+    sim.select(&results[0]);
+    assert_eq!(sim.window_manager().get_switched_window(), Some(300));
+}
+
+#[test]
+fn test_invalid_messages_get_error_responses() {
+    let sim = simulation();
+    let error_code = |response: Option<WebSocketMessage>| match response {
+        Some(WebSocketMessage::Response { id, error: Some(error), .. }) => (id, error.code),
+        other => panic!("unexpected response: {:?}", other),
+    };
+
+    assert_eq!(error_code(sim.receive("not json")), (String::new(), 400));
+    assert_eq!(error_code(sim.receive(r#"{"type":"request","id":"7","method":3}"#)), ("7".to_string(), 400));
+
+    let tabs: Vec<_> = (0..=my_launcher::websocket_server::MAX_TABS as i32).map(|id| tab(id, 1, "Tab", "https://example.com/")).collect();
+    assert_eq!(error_code(sim.request("updateTabs", json!({ "tabs": tabs }))).1, 413);
+    assert_ok(sim.request("keepAlive", json!({})));
+}